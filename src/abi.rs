@@ -0,0 +1,790 @@
+use std::{collections::HashMap, future::Future, pin::Pin};
+
+use anyhow::Result;
+use colored::Colorize;
+use num_bigint::BigUint;
+use serde_json::Value;
+use starknet::core::{
+    types::{
+        contract::{AbiEntry, AbiNamedMember, AbiOutput},
+        FieldElement,
+    },
+    utils::get_selector_from_name,
+};
+
+use crate::{chain_id::ChainIdSource, decode::FeltDecoder, utils::eth_address_from_felt};
+
+/// Data of enum variants given by name only.
+static UNIT_VALUE: Value = Value::Null;
+
+/// Variants of `core::option::Option` and `core::result::Result`, in declaration order.
+const OPTION_VARIANTS: [&str; 2] = ["Some", "None"];
+const RESULT_VARIANTS: [&str; 2] = ["Ok", "Err"];
+
+type EncodeFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<FieldElement>>> + Send + 'a>>;
+
+/// Encodes typed JSON values into calldata according to the types in a Sierra class ABI. String
+/// values for felt-like types go through `FeltDecoder`, so prefixes like `addr:` and `str:` work
+/// the same way as on the command line.
+pub struct AbiEncoder<'a, S> {
+    structs: HashMap<String, Vec<AbiNamedMember>>,
+    enums: HashMap<String, Vec<AbiNamedMember>>,
+    felt_decoder: &'a FeltDecoder<S>,
+}
+
+impl<'a, S> AbiEncoder<'a, S>
+where
+    S: ChainIdSource + Send + Sync,
+{
+    pub fn new(abi: &[AbiEntry], felt_decoder: &'a FeltDecoder<S>) -> Self {
+        let mut encoder = Self {
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            felt_decoder,
+        };
+        collect_types(abi, &mut encoder.structs, &mut encoder.enums);
+        encoder
+    }
+
+    /// Encodes a list of named inputs (e.g. constructor or function inputs). The value can either
+    /// be an object keyed by input name, or an array with inputs in order.
+    pub async fn encode_inputs(
+        &self,
+        inputs: &[AbiNamedMember],
+        value: &Value,
+    ) -> Result<Vec<FieldElement>> {
+        let mut calldata = vec![];
+
+        match value {
+            Value::Object(object) => {
+                for key in object.keys() {
+                    if !inputs.iter().any(|input| &input.name == key) {
+                        anyhow::bail!("unknown input `{}`", key);
+                    }
+                }
+                for input in inputs.iter() {
+                    let value = object
+                        .get(&input.name)
+                        .ok_or_else(|| anyhow::anyhow!("missing input `{}`", input.name))?;
+                    calldata.extend(
+                        self.encode(&input.r#type, value)
+                            .await
+                            .map_err(|err| anyhow::anyhow!("input `{}`: {}", input.name, err))?,
+                    );
+                }
+            }
+            Value::Array(items) => {
+                if items.len() != inputs.len() {
+                    anyhow::bail!("expected {} inputs but found {}", inputs.len(), items.len());
+                }
+                for (input, value) in inputs.iter().zip(items.iter()) {
+                    calldata.extend(
+                        self.encode(&input.r#type, value)
+                            .await
+                            .map_err(|err| anyhow::anyhow!("input `{}`: {}", input.name, err))?,
+                    );
+                }
+            }
+            _ => anyhow::bail!("inputs must be given as a JSON object or array"),
+        }
+
+        Ok(calldata)
+    }
+
+    /// Prompts on the terminal for each input by name and type. Values that fail to encode are
+    /// rejected and prompted for again.
+    pub async fn prompt_inputs(&self, inputs: &[AbiNamedMember]) -> Result<Vec<FieldElement>> {
+        let mut calldata = vec![];
+
+        for input in inputs.iter() {
+            loop {
+                eprint!("{} ({}): ", input.name.bright_yellow(), input.r#type);
+
+                let mut line = String::new();
+                if std::io::stdin().read_line(&mut line)? == 0 {
+                    anyhow::bail!("unexpected end of input");
+                }
+
+                match self
+                    .encode(&input.r#type, &parse_prompt_value(line.trim()))
+                    .await
+                {
+                    Ok(encoded) => {
+                        calldata.extend(encoded);
+                        break;
+                    }
+                    Err(err) => {
+                        eprintln!("{}", format!("Invalid value: {}", err).bright_magenta())
+                    }
+                }
+            }
+        }
+
+        Ok(calldata)
+    }
+
+    pub fn encode<'b>(&'b self, type_name: &'b str, value: &'b Value) -> EncodeFuture<'b> {
+        Box::pin(async move {
+            match type_name {
+                "core::felt252"
+                | "core::starknet::contract_address::ContractAddress"
+                | "core::starknet::class_hash::ClassHash"
+                | "core::starknet::eth_address::EthAddress" => {
+                    Ok(vec![self.encode_felt(value).await?])
+                }
+                "core::bool" => match value {
+                    Value::Bool(value) => Ok(vec![if *value {
+                        FieldElement::ONE
+                    } else {
+                        FieldElement::ZERO
+                    }]),
+                    _ => anyhow::bail!("expected a boolean for {}", type_name),
+                },
+                "core::integer::u8" => Ok(vec![self.encode_unsigned(value, 8).await?]),
+                "core::integer::u16" => Ok(vec![self.encode_unsigned(value, 16).await?]),
+                "core::integer::u32" => Ok(vec![self.encode_unsigned(value, 32).await?]),
+                "core::integer::u64" => Ok(vec![self.encode_unsigned(value, 64).await?]),
+                "core::integer::u128" => Ok(vec![self.encode_unsigned(value, 128).await?]),
+                "core::integer::i8"
+                | "core::integer::i16"
+                | "core::integer::i32"
+                | "core::integer::i64"
+                | "core::integer::i128" => Ok(vec![self.encode_signed(value).await?]),
+                "core::integer::u256" => self.encode_u256(value).await,
+                "core::byte_array::ByteArray" => match value {
+                    Value::String(value) => Ok(encode_byte_array(value)),
+                    _ => anyhow::bail!("expected a string for {}", type_name),
+                },
+                "()" => match value {
+                    Value::Null => Ok(vec![]),
+                    Value::Array(items) if items.is_empty() => Ok(vec![]),
+                    _ => anyhow::bail!("expected null for the unit type"),
+                },
+                _ => {
+                    if let Some(item_type) = generic_argument(type_name, "core::array::Array::<")
+                        .or_else(|| generic_argument(type_name, "core::array::Span::<"))
+                    {
+                        let items = value.as_array().ok_or_else(|| {
+                            anyhow::anyhow!("expected an array for {}", type_name)
+                        })?;
+
+                        let mut encoded = vec![FieldElement::from(items.len())];
+                        for item in items.iter() {
+                            encoded.extend(self.encode(item_type, item).await?);
+                        }
+                        Ok(encoded)
+                    } else if let Some(item_type) =
+                        generic_argument(type_name, "core::option::Option::<")
+                    {
+                        self.encode_option(item_type, value).await
+                    } else if let Some(arg_types) =
+                        generic_arguments(type_name, "core::result::Result::<")
+                    {
+                        match arg_types.as_slice() {
+                            [ok_type, err_type] => {
+                                self.encode_result(type_name, ok_type, err_type, value)
+                                    .await
+                            }
+                            _ => Err(anyhow::anyhow!("unsupported type: {}", type_name)),
+                        }
+                    } else if let Some(member_types) = tuple_members(type_name) {
+                        let items = match value.as_array() {
+                            Some(items) if items.len() == member_types.len() => items,
+                            _ => anyhow::bail!(
+                                "expected an array of {} items for {}",
+                                member_types.len(),
+                                type_name
+                            ),
+                        };
+
+                        let mut encoded = vec![];
+                        for (member_type, item) in member_types.iter().zip(items.iter()) {
+                            encoded.extend(self.encode(member_type, item).await?);
+                        }
+                        Ok(encoded)
+                    } else if let Some(members) = self.structs.get(type_name) {
+                        self.encode_inputs(members, value)
+                            .await
+                            .map_err(|err| anyhow::anyhow!("{}: {}", type_name, err))
+                    } else if let Some(variants) = self.enums.get(type_name) {
+                        self.encode_enum(type_name, variants, value).await
+                    } else {
+                        Err(anyhow::anyhow!("unsupported type: {}", type_name))
+                    }
+                }
+            }
+        })
+    }
+
+    /// Enum values are either the variant name as a string for variants without data, or an
+    /// object with a single key mapping the variant name to its data.
+    async fn encode_enum(
+        &self,
+        type_name: &str,
+        variants: &[AbiNamedMember],
+        value: &Value,
+    ) -> Result<Vec<FieldElement>> {
+        let (variant_name, data) = match value {
+            Value::String(name) => (name.as_str(), &UNIT_VALUE),
+            Value::Object(object) if object.len() == 1 => {
+                // Safe to unwrap as length is checked
+                let (name, data) = object.iter().next().unwrap();
+                (name.as_str(), data)
+            }
+            _ => anyhow::bail!(
+                "expected a variant name or a single-key object for {}",
+                type_name
+            ),
+        };
+
+        let (index, variant) = variants
+            .iter()
+            .enumerate()
+            .find(|(_, variant)| variant.name == variant_name)
+            .ok_or_else(|| {
+                anyhow::anyhow!("unknown variant `{}` of {}", variant_name, type_name)
+            })?;
+
+        let mut encoded = vec![FieldElement::from(index)];
+        encoded.extend(self.encode(&variant.r#type, data).await?);
+        Ok(encoded)
+    }
+
+    /// Options are handled before ABI enums so that `null` can be used for `None` and any other
+    /// value for `Some`. The enum forms (`"None"` and `{"Some": ...}`) are accepted too.
+    async fn encode_option(&self, item_type: &str, value: &Value) -> Result<Vec<FieldElement>> {
+        let data = match value {
+            Value::Null => None,
+            Value::String(name) if name == OPTION_VARIANTS[1] => None,
+            Value::Object(object) if object.len() == 1 => match object.iter().next() {
+                Some((name, data)) if name == OPTION_VARIANTS[0] => Some(data),
+                Some((name, _)) if name == OPTION_VARIANTS[1] => None,
+                _ => Some(value),
+            },
+            _ => Some(value),
+        };
+
+        match data {
+            Some(data) => {
+                let mut encoded = vec![FieldElement::ZERO];
+                encoded.extend(self.encode(item_type, data).await?);
+                Ok(encoded)
+            }
+            None => Ok(vec![FieldElement::ONE]),
+        }
+    }
+
+    async fn encode_result(
+        &self,
+        type_name: &str,
+        ok_type: &str,
+        err_type: &str,
+        value: &Value,
+    ) -> Result<Vec<FieldElement>> {
+        let (index, data_type, data) = match value {
+            Value::Object(object) if object.len() == 1 => match object.iter().next() {
+                Some((name, data)) if name == RESULT_VARIANTS[0] => (0u32, ok_type, data),
+                Some((name, data)) if name == RESULT_VARIANTS[1] => (1u32, err_type, data),
+                _ => anyhow::bail!(
+                    "expected an object with an `Ok` or `Err` key for {}",
+                    type_name
+                ),
+            },
+            _ => anyhow::bail!(
+                "expected an object with an `Ok` or `Err` key for {}",
+                type_name
+            ),
+        };
+
+        let mut encoded = vec![FieldElement::from(index)];
+        encoded.extend(self.encode(data_type, data).await?);
+        Ok(encoded)
+    }
+
+    async fn encode_felt(&self, value: &Value) -> Result<FieldElement> {
+        match value {
+            Value::Number(number) => number
+                .as_u64()
+                .map(FieldElement::from)
+                .ok_or_else(|| anyhow::anyhow!("invalid number: {}", number)),
+            Value::String(value) => {
+                self.felt_decoder
+                    .decode_single_with_addr_fallback(value)
+                    .await
+            }
+            _ => Err(anyhow::anyhow!("expected a number or string")),
+        }
+    }
+
+    async fn encode_unsigned(&self, value: &Value, bits: u64) -> Result<FieldElement> {
+        let felt = self.encode_felt(value).await?;
+        if BigUint::from_bytes_be(&felt.to_bytes_be()).bits() > bits {
+            anyhow::bail!("value {} does not fit in u{}", felt, bits);
+        }
+        Ok(felt)
+    }
+
+    async fn encode_signed(&self, value: &Value) -> Result<FieldElement> {
+        match value {
+            Value::Number(number) => match number.as_i64() {
+                Some(value) if value < 0 => {
+                    Ok(FieldElement::ZERO - FieldElement::from(value.unsigned_abs()))
+                }
+                Some(value) => Ok(FieldElement::from(value as u64)),
+                None => Err(anyhow::anyhow!("invalid number: {}", number)),
+            },
+            _ => self.encode_felt(value).await,
+        }
+    }
+
+    async fn encode_u256(&self, value: &Value) -> Result<Vec<FieldElement>> {
+        match value {
+            Value::Number(number) => {
+                let value = number
+                    .as_u64()
+                    .ok_or_else(|| anyhow::anyhow!("invalid number: {}", number))?;
+                Ok(vec![FieldElement::from(value), FieldElement::ZERO])
+            }
+            // Strings with a prefix (e.g. `const:u256_max`) are passed through as is
+            Value::String(value) if value.contains(':') => self.felt_decoder.decode(value).await,
+            Value::String(value) => self.felt_decoder.decode(&format!("u256:{}", value)).await,
+            _ => Err(anyhow::anyhow!("expected a number or string for u256")),
+        }
+    }
+}
+
+/// Decodes felts, like call results, into typed JSON values according to the types in a Sierra
+/// class ABI. Values use the same representation as the `AbiEncoder` input, so that decoded values
+/// can be passed back as arguments.
+pub struct AbiDecoder {
+    structs: HashMap<String, Vec<AbiNamedMember>>,
+    enums: HashMap<String, Vec<AbiNamedMember>>,
+}
+
+impl AbiDecoder {
+    pub fn new(abi: &[AbiEntry]) -> Self {
+        let mut decoder = Self {
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+        };
+        collect_types(abi, &mut decoder.structs, &mut decoder.enums);
+        decoder
+    }
+
+    /// Decodes function outputs. A single output is returned as is, and multiple outputs as an
+    /// array. All felts must be consumed.
+    pub fn decode_outputs(&self, outputs: &[AbiOutput], data: &[FieldElement]) -> Result<Value> {
+        let mut offset = 0;
+
+        let mut values = vec![];
+        for output in outputs.iter() {
+            values.push(self.decode(&output.r#type, data, &mut offset)?);
+        }
+
+        if offset != data.len() {
+            anyhow::bail!(
+                "{} unexpected felts after the decoded outputs",
+                data.len() - offset
+            );
+        }
+
+        Ok(if values.len() == 1 {
+            values.remove(0)
+        } else {
+            Value::Array(values)
+        })
+    }
+
+    pub fn decode(
+        &self,
+        type_name: &str,
+        data: &[FieldElement],
+        offset: &mut usize,
+    ) -> Result<Value> {
+        Ok(match type_name {
+            "core::felt252" | "core::starknet::class_hash::ClassHash" => {
+                Value::String(format!("{:#x}", next_felt(data, offset)?))
+            }
+            "core::starknet::contract_address::ContractAddress" => {
+                Value::String(format!("{:#064x}", next_felt(data, offset)?))
+            }
+            "core::starknet::eth_address::EthAddress" => {
+                Value::String(eth_address_from_felt(next_felt(data, offset)?)?)
+            }
+            "core::bool" => Value::Bool(next_felt(data, offset)? != FieldElement::ZERO),
+            "core::integer::u8" | "core::integer::u16" | "core::integer::u32"
+            | "core::integer::u64" => {
+                let felt = next_felt(data, offset)?;
+                Value::from(u64::try_from(felt).map_err(|_| {
+                    anyhow::anyhow!("value {:#x} out of range for {}", felt, type_name)
+                })?)
+            }
+            // Numbers beyond 64 bits are printed as decimal strings so that no precision is lost
+            "core::integer::u128" => {
+                Value::String(felt_to_biguint(next_felt(data, offset)?).to_string())
+            }
+            "core::integer::i8"
+            | "core::integer::i16"
+            | "core::integer::i32"
+            | "core::integer::i64"
+            | "core::integer::i128" => decode_signed(next_felt(data, offset)?),
+            "core::integer::u256" => {
+                let low = felt_to_biguint(next_felt(data, offset)?);
+                let high = felt_to_biguint(next_felt(data, offset)?);
+                Value::String(((high << 128u32) + low).to_string())
+            }
+            "core::byte_array::ByteArray" => Value::String(decode_byte_array(data, offset)?),
+            "()" => Value::Null,
+            _ => {
+                if let Some(item_type) = generic_argument(type_name, "core::array::Array::<")
+                    .or_else(|| generic_argument(type_name, "core::array::Span::<"))
+                {
+                    let len = felt_to_usize(next_felt(data, offset)?)?;
+                    let mut items = vec![];
+                    for _ in 0..len {
+                        items.push(self.decode(item_type, data, offset)?);
+                    }
+                    Value::Array(items)
+                } else if let Some(item_type) =
+                    generic_argument(type_name, "core::option::Option::<")
+                {
+                    match self.decode_variant_index(type_name, data, offset, 2)? {
+                        0 => self.decode(item_type, data, offset)?,
+                        _ => Value::Null,
+                    }
+                } else if let Some(arg_types) =
+                    generic_arguments(type_name, "core::result::Result::<")
+                {
+                    let (ok_type, err_type) = match arg_types.as_slice() {
+                        [ok_type, err_type] => (*ok_type, *err_type),
+                        _ => anyhow::bail!("unsupported type: {}", type_name),
+                    };
+
+                    let index = self.decode_variant_index(type_name, data, offset, 2)?;
+                    let data =
+                        self.decode(if index == 0 { ok_type } else { err_type }, data, offset)?;
+
+                    let mut object = serde_json::Map::new();
+                    object.insert(RESULT_VARIANTS[index].to_owned(), data);
+                    Value::Object(object)
+                } else if let Some(member_types) = tuple_members(type_name) {
+                    let mut items = vec![];
+                    for member_type in member_types.iter() {
+                        items.push(self.decode(member_type, data, offset)?);
+                    }
+                    Value::Array(items)
+                } else if let Some(members) = self.structs.get(type_name) {
+                    let mut object = serde_json::Map::new();
+                    for member in members.iter() {
+                        object.insert(
+                            member.name.clone(),
+                            self.decode(&member.r#type, data, offset)?,
+                        );
+                    }
+                    Value::Object(object)
+                } else if let Some(variants) = self.enums.get(type_name) {
+                    let index =
+                        self.decode_variant_index(type_name, data, offset, variants.len())?;
+                    let variant = &variants[index];
+
+                    // Variants without data are printed by name only, as accepted by the encoder
+                    if variant.r#type == "()" {
+                        Value::String(variant.name.clone())
+                    } else {
+                        let mut object = serde_json::Map::new();
+                        object.insert(
+                            variant.name.clone(),
+                            self.decode(&variant.r#type, data, offset)?,
+                        );
+                        Value::Object(object)
+                    }
+                } else {
+                    anyhow::bail!("unsupported type: {}", type_name)
+                }
+            }
+        })
+    }
+
+    /// Expands short type names like `u256` or `Array<MyStruct>` into the fully qualified names
+    /// used in ABIs. Structs and enums can be named without their module path when unambiguous.
+    pub fn resolve_type_name(&self, type_name: &str) -> Result<String> {
+        let type_name = type_name
+            .replace("::<", "<")
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+
+        let mut resolved = String::new();
+        let mut identifier = String::new();
+        for c in type_name.chars() {
+            if !matches!(c, '<' | '>' | ',' | '(' | ')') {
+                identifier.push(c);
+                continue;
+            }
+
+            if !identifier.is_empty() {
+                resolved.push_str(&self.resolve_identifier(&identifier)?);
+                identifier.clear();
+            }
+            match c {
+                '<' => resolved.push_str("::<"),
+                ',' => resolved.push_str(", "),
+                _ => resolved.push(c),
+            }
+        }
+        if !identifier.is_empty() {
+            resolved.push_str(&self.resolve_identifier(&identifier)?);
+        }
+
+        Ok(resolved)
+    }
+
+    fn resolve_identifier(&self, identifier: &str) -> Result<String> {
+        if identifier.contains("::") {
+            return Ok(identifier.to_owned());
+        }
+
+        let core_type = match identifier {
+            "felt252" => Some("core::felt252"),
+            "bool" => Some("core::bool"),
+            "u8" | "u16" | "u32" | "u64" | "u128" | "u256" | "i8" | "i16" | "i32" | "i64"
+            | "i128" => None,
+            "ByteArray" => Some("core::byte_array::ByteArray"),
+            "ContractAddress" => Some("core::starknet::contract_address::ContractAddress"),
+            "ClassHash" => Some("core::starknet::class_hash::ClassHash"),
+            "EthAddress" => Some("core::starknet::eth_address::EthAddress"),
+            "Array" => Some("core::array::Array"),
+            "Span" => Some("core::array::Span"),
+            "Option" => Some("core::option::Option"),
+            "Result" => Some("core::result::Result"),
+            _ => {
+                // Generic instantiations of the same type share the name before `::<`
+                let mut candidates = self
+                    .structs
+                    .keys()
+                    .chain(self.enums.keys())
+                    .map(|name| name.split("::<").next().unwrap_or(name))
+                    .filter(|name| name.rsplit("::").next() == Some(identifier))
+                    .collect::<Vec<_>>();
+                candidates.sort_unstable();
+                candidates.dedup();
+
+                return match candidates.as_slice() {
+                    [name] => Ok((*name).to_owned()),
+                    [] => Err(anyhow::anyhow!("unknown type: {}", identifier)),
+                    _ => Err(anyhow::anyhow!(
+                        "ambiguous type {}: could be any of {}",
+                        identifier,
+                        candidates.join(", ")
+                    )),
+                };
+            }
+        };
+
+        Ok(match core_type {
+            Some(core_type) => core_type.to_owned(),
+            None => format!("core::integer::{}", identifier),
+        })
+    }
+
+    fn decode_variant_index(
+        &self,
+        type_name: &str,
+        data: &[FieldElement],
+        offset: &mut usize,
+        variant_count: usize,
+    ) -> Result<usize> {
+        let index = next_felt(data, offset)?;
+        match felt_to_usize(index) {
+            Ok(index) if index < variant_count => Ok(index),
+            _ => Err(anyhow::anyhow!(
+                "invalid variant index {:#x} for {}",
+                index,
+                type_name
+            )),
+        }
+    }
+}
+
+fn collect_types(
+    entries: &[AbiEntry],
+    structs: &mut HashMap<String, Vec<AbiNamedMember>>,
+    enums: &mut HashMap<String, Vec<AbiNamedMember>>,
+) {
+    for entry in entries.iter() {
+        match entry {
+            AbiEntry::Struct(abi_struct) => {
+                structs.insert(abi_struct.name.clone(), abi_struct.members.clone());
+            }
+            AbiEntry::Enum(abi_enum) => {
+                enums.insert(abi_enum.name.clone(), abi_enum.variants.clone());
+            }
+            AbiEntry::Interface(interface) => collect_types(&interface.items, structs, enums),
+            _ => {}
+        }
+    }
+}
+
+/// Finds the inputs of the function with the given selector in a Sierra class ABI, including
+/// functions in interfaces.
+pub fn function_inputs(abi: &[AbiEntry], selector: FieldElement) -> Option<Vec<AbiNamedMember>> {
+    abi.iter().find_map(|entry| match entry {
+        AbiEntry::Function(function)
+            if get_selector_from_name(&function.name).ok() == Some(selector) =>
+        {
+            Some(function.inputs.clone())
+        }
+        AbiEntry::Interface(interface) => function_inputs(&interface.items, selector),
+        _ => None,
+    })
+}
+
+/// Finds the outputs of the function with the given selector in a Sierra class ABI, including
+/// functions in interfaces.
+pub fn function_outputs(abi: &[AbiEntry], selector: FieldElement) -> Option<Vec<AbiOutput>> {
+    abi.iter().find_map(|entry| match entry {
+        AbiEntry::Function(function)
+            if get_selector_from_name(&function.name).ok() == Some(selector) =>
+        {
+            Some(function.outputs.clone())
+        }
+        AbiEntry::Interface(interface) => function_outputs(&interface.items, selector),
+        _ => None,
+    })
+}
+
+/// Finds the constructor inputs in a Sierra class ABI. Classes without a constructor take no
+/// inputs.
+pub fn constructor_inputs(abi: &[AbiEntry]) -> Vec<AbiNamedMember> {
+    abi.iter()
+        .find_map(|entry| match entry {
+            AbiEntry::Constructor(constructor) => Some(constructor.inputs.clone()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Serializes a string as a Cairo `ByteArray`: the number of full 31-byte words, the words, then
+/// the pending word and its length.
+pub fn encode_byte_array(value: &str) -> Vec<FieldElement> {
+    let bytes = value.as_bytes();
+    let full_words = bytes.chunks_exact(31);
+    let pending = full_words.remainder();
+
+    let mut encoded = vec![FieldElement::from(bytes.len() / 31)];
+    for word in full_words {
+        // Safe to unwrap as 31 bytes always fit in a felt
+        encoded.push(FieldElement::from_byte_slice_be(word).unwrap());
+    }
+    encoded.push(FieldElement::from_byte_slice_be(pending).unwrap());
+    encoded.push(FieldElement::from(pending.len()));
+
+    encoded
+}
+
+/// Reverses `encode_byte_array`.
+fn decode_byte_array(data: &[FieldElement], offset: &mut usize) -> Result<String> {
+    let full_words = felt_to_usize(next_felt(data, offset)?)?;
+
+    let mut bytes = vec![];
+    for _ in 0..full_words {
+        bytes.extend_from_slice(&next_felt(data, offset)?.to_bytes_be()[1..]);
+    }
+
+    let pending_word = next_felt(data, offset)?;
+    let pending_len = felt_to_usize(next_felt(data, offset)?)?;
+    if pending_len >= 31 {
+        anyhow::bail!("invalid ByteArray pending word length: {}", pending_len);
+    }
+    bytes.extend_from_slice(&pending_word.to_bytes_be()[32 - pending_len..]);
+
+    String::from_utf8(bytes).map_err(|_| anyhow::anyhow!("ByteArray is not valid UTF-8"))
+}
+
+/// Signed integers are stored as field elements, with negative values wrapping around the prime.
+fn decode_signed(felt: FieldElement) -> Value {
+    let negative = felt_to_biguint(felt) > felt_to_biguint(FieldElement::MAX) / 2u32;
+    let magnitude = felt_to_biguint(if negative {
+        FieldElement::ZERO - felt
+    } else {
+        felt
+    });
+
+    let text = format!("{}{}", if negative { "-" } else { "" }, magnitude);
+    match text.parse::<i64>() {
+        Ok(value) => Value::from(value),
+        Err(_) => Value::String(text),
+    }
+}
+
+fn next_felt(data: &[FieldElement], offset: &mut usize) -> Result<FieldElement> {
+    let felt = data
+        .get(*offset)
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("unexpected end of data at offset {}", offset))?;
+    *offset += 1;
+    Ok(felt)
+}
+
+fn felt_to_usize(value: FieldElement) -> Result<usize> {
+    Ok(
+        u64::try_from(value).map_err(|_| anyhow::anyhow!("value out of range: {:#x}", value))?
+            as usize,
+    )
+}
+
+fn felt_to_biguint(value: FieldElement) -> BigUint {
+    BigUint::from_bytes_be(&value.to_bytes_be())
+}
+
+/// Values entered at prompts are taken as JSON when they look like it, and as plain strings
+/// otherwise, so that felts and prefixed values like `str:` can be entered as-is.
+fn parse_prompt_value(input: &str) -> Value {
+    if input.starts_with(['[', '{', '"']) || matches!(input, "true" | "false" | "null") {
+        if let Ok(value) = serde_json::from_str(input) {
+            return value;
+        }
+    }
+
+    Value::String(input.to_owned())
+}
+
+pub fn generic_argument<'a>(type_name: &'a str, prefix: &str) -> Option<&'a str> {
+    type_name.strip_prefix(prefix)?.strip_suffix('>')
+}
+
+/// Splits the arguments of a generic type like `core::result::Result::<core::felt252, (u8, u8)>`.
+pub fn generic_arguments<'a>(type_name: &'a str, prefix: &str) -> Option<Vec<&'a str>> {
+    generic_argument(type_name, prefix).map(split_top_level)
+}
+
+/// Splits a tuple type like `(core::felt252, (core::bool, core::integer::u8))` into its members,
+/// respecting nesting.
+pub fn tuple_members(type_name: &str) -> Option<Vec<&str>> {
+    let inner = type_name.strip_prefix('(')?.strip_suffix(')')?;
+    if inner.is_empty() {
+        return None;
+    }
+
+    Some(split_top_level(inner))
+}
+
+fn split_top_level(inner: &str) -> Vec<&str> {
+    let mut members = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (ind, c) in inner.char_indices() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' | '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                members.push(inner[start..ind].trim());
+                start = ind + 1;
+            }
+            _ => {}
+        }
+    }
+    members.push(inner[start..].trim());
+
+    members
+}
@@ -0,0 +1,224 @@
+use std::{collections::BTreeSet, path::PathBuf, time::SystemTime};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use starknet::{
+    core::types::{contract::AbiEntry, BlockId, BlockTag, ContractClass, FieldElement},
+    providers::Provider,
+};
+
+use crate::{artifact_store::ArtifactStore, profile::Profiles, provider::ExtendedProvider};
+
+/// The ABI of a class as cached under `abis` in the cache folder, one file per class hash. Classes
+/// are immutable, so entries never go stale and are fetched from the network only once. Setting
+/// `STARKLI_NO_ABI_CACHE` bypasses the cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedAbi {
+    pub class_hash: FieldElement,
+    /// Whether the class is a Sierra (Cairo 1) class. Legacy ABIs have a different format.
+    pub sierra: bool,
+    /// `null` for legacy classes declared without an ABI.
+    pub abi: Value,
+}
+
+/// An entry in the ABI cache, as listed by `starkli abi-cache`.
+pub struct CacheEntry {
+    pub class_hash: FieldElement,
+    pub path: PathBuf,
+    pub size: u64,
+    pub cached_at: SystemTime,
+}
+
+impl CachedAbi {
+    pub fn from_class(class_hash: FieldElement, class: &ContractClass) -> Result<Self> {
+        Ok(match class {
+            ContractClass::Sierra(class) => Self {
+                class_hash,
+                sierra: true,
+                abi: serde_json::from_str(&class.abi)?,
+            },
+            ContractClass::Legacy(class) => Self {
+                class_hash,
+                sierra: false,
+                abi: serde_json::to_value(&class.abi)?,
+            },
+        })
+    }
+
+    /// The typed ABI for Sierra classes, or `None` for legacy classes.
+    pub fn sierra_abi(&self) -> Result<Option<Vec<AbiEntry>>> {
+        if self.sierra {
+            Ok(Some(serde_json::from_value(self.abi.clone())?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Names of all external and view functions, including those nested in interfaces.
+    pub fn function_names(&self) -> Result<BTreeSet<String>> {
+        let mut names = BTreeSet::new();
+
+        match self.sierra_abi()? {
+            Some(entries) => collect_function_names(&entries, &mut names),
+            None => {
+                if let Some(entries) = self.abi.as_array() {
+                    for entry in entries.iter() {
+                        if entry.get("type").and_then(|ty| ty.as_str()) == Some("function") {
+                            if let Some(name) = entry.get("name").and_then(|name| name.as_str()) {
+                                names.insert(name.to_owned());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Loads the ABI of a class from the cache, fetching and caching it on first use.
+    pub async fn of_class(provider: &ExtendedProvider, class_hash: FieldElement) -> Result<Self> {
+        if let Some(cached) = Self::load(class_hash) {
+            return Ok(cached);
+        }
+
+        // Artifacts in the local store are verified against their class hash when added
+        if let Some(stored) = ArtifactStore::open()
+            .ok()
+            .and_then(|store| store.abi(class_hash))
+        {
+            return Ok(stored);
+        }
+
+        let class = provider
+            .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
+            .await?;
+        let abi = Self::from_class(class_hash, &class)?;
+
+        // Failing to write the cache is not worth failing the command for
+        if is_enabled() {
+            if let Err(err) = abi.save() {
+                log::warn!("unable to write ABI cache: {}", err);
+            }
+        }
+
+        Ok(abi)
+    }
+
+    /// Loads the ABI of the class currently deployed at an address. The class hash is always
+    /// looked up, as contracts can be upgraded to another class.
+    pub async fn of_contract(provider: &ExtendedProvider, address: FieldElement) -> Result<Self> {
+        let class_hash = provider
+            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+            .await?;
+        Self::of_class(provider, class_hash).await
+    }
+
+    /// Fetches and caches the ABI of a class unless it's already cached, regardless of
+    /// `STARKLI_NO_ABI_CACHE`. Returns whether the ABI was fetched.
+    pub async fn warm(provider: &ExtendedProvider, class_hash: FieldElement) -> Result<bool> {
+        if Self::is_cached(class_hash)? {
+            return Ok(false);
+        }
+
+        let class = provider
+            .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
+            .await?;
+        Self::from_class(class_hash, &class)?.save()?;
+
+        Ok(true)
+    }
+
+    pub fn get_folder() -> Result<PathBuf> {
+        let mut path = Profiles::get_cache_folder()?;
+        path.push("abis");
+        Ok(path)
+    }
+
+    pub fn get_path(class_hash: FieldElement) -> Result<PathBuf> {
+        let mut path = Self::get_folder()?;
+        path.push(format!("{:#064x}.json", class_hash));
+        Ok(path)
+    }
+
+    pub fn is_cached(class_hash: FieldElement) -> Result<bool> {
+        Ok(Self::get_path(class_hash)?.exists())
+    }
+
+    /// All cached entries, sorted by class hash. Files not named after a class hash are ignored.
+    pub fn list() -> Result<Vec<CacheEntry>> {
+        let folder = Self::get_folder()?;
+        if !folder.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut entries = vec![];
+        for entry in std::fs::read_dir(folder)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            let class_hash = match path
+                .file_stem()
+                .and_then(|stem| FieldElement::from_hex_be(&stem.to_string_lossy()).ok())
+            {
+                Some(class_hash) => class_hash,
+                None => continue,
+            };
+
+            let metadata = std::fs::metadata(&path)?;
+            entries.push(CacheEntry {
+                class_hash,
+                path,
+                size: metadata.len(),
+                cached_at: metadata.modified()?,
+            });
+        }
+        entries.sort_by_key(|entry| entry.class_hash);
+
+        Ok(entries)
+    }
+
+    fn load(class_hash: FieldElement) -> Option<Self> {
+        if !is_enabled() {
+            return None;
+        }
+
+        // Unreadable entries are treated as missing and overwritten
+        let content = std::fs::read_to_string(Self::get_path(class_hash).ok()?).ok()?;
+        serde_json::from_str::<Self>(&content)
+            .ok()
+            .filter(|cached| cached.class_hash == class_hash)
+    }
+
+    fn save(&self) -> Result<()> {
+        let folder = Self::get_folder()?;
+        if !folder.exists() {
+            std::fs::create_dir_all(folder)?;
+        }
+
+        std::fs::write(
+            Self::get_path(self.class_hash)?,
+            serde_json::to_string(self)?,
+        )?;
+
+        Ok(())
+    }
+}
+
+fn collect_function_names(entries: &[AbiEntry], names: &mut BTreeSet<String>) {
+    for entry in entries.iter() {
+        match entry {
+            AbiEntry::Function(function) => {
+                names.insert(function.name.clone());
+            }
+            AbiEntry::Interface(interface) => collect_function_names(&interface.items, names),
+            _ => {}
+        }
+    }
+}
+
+fn is_enabled() -> bool {
+    std::env::var_os("STARKLI_NO_ABI_CACHE").is_none()
+}
@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use starknet::core::types::FieldElement;
+
+/// Where class ABIs are published after declaring: either an HTTP endpoint that accepts a `PUT`
+/// per class hash, or a local directory with one JSON file per class hash.
+#[derive(Debug, Clone)]
+pub enum AbiRegistry {
+    Http(String),
+    Directory(PathBuf),
+}
+
+/// A class ABI along with the metadata of its declaration, as stored in registries.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PublishedAbi {
+    pub class_hash: FieldElement,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiled_class_hash: Option<FieldElement>,
+    pub chain_id: FieldElement,
+    pub transaction_hash: FieldElement,
+    pub sender_address: FieldElement,
+    /// Artifact file name without the extension, which is usually the contract name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_name: Option<String>,
+    pub declared_at: String,
+    pub starkli_version: String,
+    pub abi: Value,
+}
+
+impl AbiRegistry {
+    /// Anything other than an `http(s)://` URL is taken as a directory path.
+    pub fn parse(target: &str) -> Self {
+        if target.starts_with("https://") || target.starts_with("http://") {
+            Self::Http(target.trim_end_matches('/').to_owned())
+        } else {
+            Self::Directory(PathBuf::from(target))
+        }
+    }
+
+    /// Publishes the ABI, returning where it can be found. Existing entries for the same class hash
+    /// are overwritten, which is harmless as class hashes commit to the ABI.
+    pub async fn publish(&self, abi: &PublishedAbi) -> Result<String> {
+        let key = format!("{:#064x}", abi.class_hash);
+
+        match self {
+            Self::Http(endpoint) => {
+                let url = format!("{}/{}", endpoint, key);
+
+                let client = reqwest::Client::builder()
+                    .user_agent(concat!("starkli/", env!("CARGO_PKG_VERSION")))
+                    .build()?;
+                let mut request = client.put(&url).json(abi);
+                if let Ok(token) = std::env::var("STARKLI_ABI_REGISTRY_TOKEN") {
+                    request = request.bearer_auth(token);
+                }
+
+                let response = request.send().await?;
+                if !response.status().is_success() {
+                    anyhow::bail!("request to {} failed: {}", url, response.status());
+                }
+
+                Ok(url)
+            }
+            Self::Directory(dir) => {
+                if !dir.exists() {
+                    std::fs::create_dir_all(dir)?;
+                }
+
+                let mut path = dir.clone();
+                path.push(format!("{}.json", key));
+                std::fs::write(&path, serde_json::to_string_pretty(abi)?)?;
+
+                Ok(path.display().to_string())
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use clap::Parser;
+use colored::Colorize;
+
+use crate::profile::{Profiles, DEFAULT_PROFILE_NAME};
+
+/// Width of the longest label, so that whatever follows a label always starts on the same column.
+const LABEL_WIDTH: usize = 7;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Default, Parser)]
+pub struct AccessibilityOptions {
+    #[clap(
+        long,
+        global = true,
+        env = "STARKLI_ACCESSIBLE",
+        help = "Output for screen readers and log processors: no colors, explicit \
+                SUCCESS/FAILED/WARNING labels, and fixed-width status columns"
+    )]
+    accessible: bool,
+}
+
+/// The outcome conveyed by a status word. Normally the word is colored accordingly, which gets lost
+/// with screen readers and plain-text logs, so accessible output uses an explicit label instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Failure,
+    Warning,
+}
+
+impl AccessibilityOptions {
+    /// Enables accessible output for the rest of the process if requested with the flag or with
+    /// `accessible = true` in the `default` profile.
+    pub fn apply(self) {
+        // A broken profiles file is reported by the commands actually using it
+        let from_profile = || {
+            Profiles::load()
+                .ok()
+                .and_then(|profiles| {
+                    profiles
+                        .profiles
+                        .get(DEFAULT_PROFILE_NAME)
+                        .map(|profile| profile.accessible)
+                })
+                .unwrap_or(false)
+        };
+
+        if self.accessible || from_profile() {
+            ENABLED.store(true, Ordering::Relaxed);
+            colored::control::set_override(false);
+        }
+    }
+}
+
+impl Status {
+    /// Renders a status word such as `ok` or `mismatch`. In accessible mode, the word is replaced
+    /// by `SUCCESS`, `FAILED`, or `WARNING`, padded to a fixed width.
+    pub fn label(self, word: &str) -> String {
+        if is_enabled() {
+            let label = match self {
+                Self::Success => "SUCCESS",
+                Self::Failure => "FAILED",
+                Self::Warning => "WARNING",
+            };
+            return format!("{:<width$}", label, width = LABEL_WIDTH);
+        }
+
+        match self {
+            Self::Success => word.bright_green().to_string(),
+            Self::Failure => word.red().to_string(),
+            Self::Warning => word.bright_magenta().to_string(),
+        }
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
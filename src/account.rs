@@ -1,4 +1,7 @@
-use std::{fmt::Display, path::PathBuf};
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
 use clap::Parser;
@@ -8,7 +11,7 @@ use starknet::{
     accounts::{ExecutionEncoding, SingleOwnerAccount},
     core::{
         serde::unsigned_field_element::UfeHex,
-        types::{BlockId, BlockTag, FieldElement},
+        types::{BlockId, BlockTag, ContractClass, FieldElement},
         utils::get_contract_address,
     },
     macros::{felt, selector},
@@ -16,10 +19,25 @@ use starknet::{
     signers::{LocalWallet, SigningKey},
 };
 
-use crate::signer::{AnySigner, SignerArgs, SignerResolutionTask};
+use crate::{
+    network::NetworkSource,
+    profile::{Profiles, DEFAULT_PROFILE_NAME},
+    signer::{AnySigner, SignerArgs, SignerResolutionTask},
+};
 
 const BRAAVOS_SIGNER_TYPE_STARK: FieldElement = FieldElement::ONE;
 
+/// The latest account config file format version. Files of older versions are upgraded in memory
+/// when loaded, and can be persisted with `starkli account migrate`.
+///
+/// - 1: the original format.
+/// - 2: adds watch-only accounts and the network of deployed accounts.
+pub const CURRENT_ACCOUNT_CONFIG_VERSION: u64 = 2;
+
+/// OpenZeppelin account contract v0.7.0 compiled with cairo v2.2.0
+pub const OZ_ACCOUNT_CLASS_HASH: FieldElement =
+    felt!("0x04c6d6cf894f8bc96bb9c525e6853e5483177841f7388f74a46cfda6f028c755");
+
 pub const KNOWN_ACCOUNT_CLASSES: [KnownAccountClass; 7] = [
     KnownAccountClass {
         class_hash: felt!("0x048dd59fabc729a5db3afdf649ecaf388e931647ab2f53ca3c6183fa480aa292"),
@@ -126,9 +144,10 @@ pub struct AccountArgs {
     #[clap(
         long,
         env = "STARKNET_ACCOUNT",
-        help = "Path to account config JSON file"
+        help = "Path to account config JSON file, or name of an account in the accounts directory. \
+            Falls back to the default account if not set"
     )]
-    account: String,
+    account: Option<String>,
     #[clap(flatten)]
     signer: SignerArgs,
 }
@@ -146,6 +165,7 @@ pub enum AccountVariant {
     OpenZeppelin(OzAccountConfig),
     Argent(ArgentAccountConfig),
     Braavos(BraavosAccountConfig),
+    WatchOnly(WatchOnlyAccountConfig),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -175,6 +195,7 @@ pub enum AccountVariantType {
     Braavos,
     Argent,
     OpenZeppelin,
+    WatchOnly,
 }
 
 #[serde_as]
@@ -212,6 +233,15 @@ pub struct BraavosAccountConfig {
     pub signers: Vec<BraavosSigner>,
 }
 
+/// Accounts known only by address, without any signer. They can be used for reading and for
+/// building unsigned transactions, but never for signing.
+#[derive(Serialize, Deserialize)]
+pub struct WatchOnlyAccountConfig {
+    pub version: u64,
+    /// Whether the account uses the Cairo 0 `__execute__` calldata encoding.
+    pub legacy: bool,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "status", rename_all = "snake_case")]
 pub enum BraavosMultisigConfig {
@@ -251,6 +281,11 @@ pub struct DeployedStatus {
     pub class_hash: FieldElement,
     #[serde_as(as = "UfeHex")]
     pub address: FieldElement,
+    /// The `--network` value of the network the account is deployed on: a built-in network
+    /// detected from the chain ID, or the profile network created by `starkli devnet fork`. Not
+    /// set when the network couldn't be identified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -267,13 +302,37 @@ pub struct BraavosDeploymentContext {
 }
 
 impl AccountArgs {
+    /// Whether transactions are signed on a Ledger device, which only blind-signs hashes.
+    pub fn uses_ledger(&self) -> bool {
+        self.signer.is_ledger()
+    }
+
+    /// Replaces the account from the command line, keeping the signer options.
+    pub fn with_account(&self, account: String) -> Self {
+        Self {
+            account: Some(account),
+            signer: self.signer.clone(),
+        }
+    }
+
     pub async fn into_account<P>(self, provider: P) -> Result<SingleOwnerAccount<P, AnySigner>>
     where
         P: Provider + Send + Sync,
     {
         let signer = self.signer.into_task()?;
 
-        let mut account = if let Some(builtin_account) = find_builtin_account(&self.account) {
+        let account_arg = match self.account {
+            Some(account) => account,
+            None => match load_default_account()? {
+                Some(account) => account,
+                None => anyhow::bail!(
+                    "no account specified. Use --account (STARKNET_ACCOUNT), or set a default \
+                    account with `starkli account set-default`"
+                ),
+            },
+        };
+
+        let mut account = if let Some(builtin_account) = find_builtin_account(&account_arg) {
             if matches!(signer, SignerResolutionTask::Strong(_)) {
                 // The user is supplying a signer explicitly when using a built-in account. This
                 // might be legitimate if the built-in account key has been modified, but it's more
@@ -297,15 +356,25 @@ impl AccountArgs {
                 ExecutionEncoding::Legacy,
             )
         } else {
-            let signer = signer.resolve()?;
-            let account = PathBuf::from(shellexpand::tilde(&self.account).into_owned());
+            let account = resolve_account_path(&account_arg)?;
 
             if !account.exists() {
                 anyhow::bail!("account config file not found");
             }
 
-            let account_config: AccountConfig =
-                serde_json::from_reader(&mut std::fs::File::open(&self.account)?)?;
+            let account_config = AccountConfig::load(&account)?;
+
+            let signer = match account_config.variant {
+                AccountVariant::WatchOnly(_) => {
+                    if matches!(signer, SignerResolutionTask::Strong(_)) {
+                        anyhow::bail!(
+                            "do not supply signer options when using a watch-only account"
+                        );
+                    }
+                    AnySigner::WatchOnly
+                }
+                _ => signer.resolve()?,
+            };
 
             let account_address = match account_config.deployment {
                 DeploymentStatus::Undeployed(_) => anyhow::bail!("account not deployed"),
@@ -330,6 +399,38 @@ impl AccountArgs {
 }
 
 impl AccountConfig {
+    /// Config of a new, not yet deployed OpenZeppelin account controlled by `public_key`.
+    pub fn new_oz(public_key: FieldElement) -> Self {
+        // Too lazy to write random salt generation
+        let salt = SigningKey::from_random().secret_scalar();
+
+        Self {
+            version: CURRENT_ACCOUNT_CONFIG_VERSION,
+            variant: AccountVariant::OpenZeppelin(OzAccountConfig {
+                version: 1,
+                public_key,
+                legacy: false,
+            }),
+            deployment: DeploymentStatus::Undeployed(UndeployedStatus {
+                class_hash: OZ_ACCOUNT_CLASS_HASH,
+                salt,
+                context: None,
+            }),
+        }
+    }
+
+    /// Loads an account config file, transparently upgrading older formats.
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let raw_config: serde_json::Value =
+            serde_json::from_reader(&mut std::fs::File::open(path)?)?;
+        let (migrated, _) = migrate_account_config(raw_config)?;
+
+        Ok(serde_json::from_value(migrated)?)
+    }
+
     pub fn deploy_account_address(&self) -> Result<FieldElement> {
         let undeployed_status = match &self.deployment {
             DeploymentStatus::Undeployed(value) => value,
@@ -372,6 +473,9 @@ impl AccountConfig {
                 }
             },
 
+            AccountVariant::WatchOnly(_) => {
+                anyhow::bail!("watch-only accounts cannot be deployed")
+            }
             AccountVariant::Braavos(braavos) => {
                 if !matches!(braavos.multisig, BraavosMultisigConfig::Off) {
                     anyhow::bail!("Braavos accounts cannot be deployed with multisig on");
@@ -407,6 +511,40 @@ impl AccountConfig {
 }
 
 impl AccountVariant {
+    pub fn variant_type(&self) -> AccountVariantType {
+        match self {
+            AccountVariant::OpenZeppelin(oz) => {
+                if oz.legacy {
+                    AccountVariantType::OpenZeppelinLegacy
+                } else {
+                    AccountVariantType::OpenZeppelin
+                }
+            }
+            AccountVariant::Argent(argent) => {
+                if argent.implementation.is_some() {
+                    AccountVariantType::ArgentLegacy
+                } else {
+                    AccountVariantType::Argent
+                }
+            }
+            AccountVariant::Braavos(_) => AccountVariantType::Braavos,
+            AccountVariant::WatchOnly(_) => AccountVariantType::WatchOnly,
+        }
+    }
+
+    /// The public key of the account owner, if the account is controlled by a single Stark key.
+    pub fn owner_public_key(&self) -> Option<FieldElement> {
+        match self {
+            AccountVariant::OpenZeppelin(oz) => Some(oz.public_key),
+            AccountVariant::Argent(argent) => Some(argent.owner),
+            AccountVariant::Braavos(braavos) => match braavos.signers.as_slice() {
+                [BraavosSigner::Stark(signer)] => Some(signer.public_key),
+                _ => None,
+            },
+            AccountVariant::WatchOnly(_) => None,
+        }
+    }
+
     pub fn execution_encoding(&self) -> ExecutionEncoding {
         match self {
             AccountVariant::OpenZeppelin(oz) => {
@@ -424,6 +562,13 @@ impl AccountVariant {
                 }
             }
             AccountVariant::Braavos(_) => ExecutionEncoding::Legacy,
+            AccountVariant::WatchOnly(watch_only) => {
+                if watch_only.legacy {
+                    ExecutionEncoding::Legacy
+                } else {
+                    ExecutionEncoding::New
+                }
+            }
         }
     }
 }
@@ -453,16 +598,248 @@ impl Display for AccountVariantType {
             AccountVariantType::Braavos => write!(f, "Braavos"),
             AccountVariantType::Argent => write!(f, "Argent X"),
             AccountVariantType::OpenZeppelin => write!(f, "OpenZeppelin"),
+            AccountVariantType::WatchOnly => write!(f, "Watch-only"),
+        }
+    }
+}
+
+/// Upgrades a raw account config to the latest format version. Returns the upgraded config and
+/// whether any change has been made.
+pub fn migrate_account_config(raw: serde_json::Value) -> Result<(serde_json::Value, bool)> {
+    let mut config = match raw {
+        serde_json::Value::Object(config) => config,
+        _ => anyhow::bail!("invalid account config: not a JSON object"),
+    };
+    let original = config.clone();
+
+    let version = config
+        .get("version")
+        .ok_or_else(|| anyhow::anyhow!("invalid account config: missing `version`"))?;
+    let version = version
+        .as_u64()
+        .filter(|version| *version >= 1)
+        .ok_or_else(|| anyhow::anyhow!("invalid account config version: {}", version))?;
+
+    if version > CURRENT_ACCOUNT_CONFIG_VERSION {
+        anyhow::bail!(
+            "account config version {} is not supported by this version of Starkli (latest \
+            supported: {}). Upgrade Starkli to use this account.",
+            version,
+            CURRENT_ACCOUNT_CONFIG_VERSION
+        );
+    }
+
+    if version < 2 {
+        migrate_account_config_v1_to_v2(&mut config)?;
+    }
+
+    let changed = config != original;
+    Ok((serde_json::Value::Object(config), changed))
+}
+
+/// Version 2 only adds optional content, so version 1 files are valid as is once relabeled. The
+/// bump keeps older Starkli versions from misreading files that use the additions.
+fn migrate_account_config_v1_to_v2(
+    config: &mut serde_json::Map<String, serde_json::Value>,
+) -> Result<()> {
+    config.insert("version".into(), 2.into());
+
+    Ok(())
+}
+
+/// Detects the built-in network for the `network` field of deployed accounts. This is only
+/// informational, so failures are logged instead of failing a deployment that already happened.
+pub async fn detect_account_network<N>(provider: &N) -> Option<String>
+where
+    N: NetworkSource,
+{
+    match provider.get_network().await {
+        Ok(network) => network.map(|network| network.to_string()),
+        Err(err) => {
+            log::warn!("unable to detect the network of the account: {}", err);
+            None
         }
     }
 }
 
-fn find_builtin_account(id: &str) -> Option<&'static BuiltinAccount> {
+/// Resolves an `--account` value into a file path. Values that don't point to an existing file are
+/// looked up by name in the accounts directory.
+pub fn resolve_account_path(value: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(shellexpand::tilde(value).into_owned());
+    if path.exists() {
+        return Ok(path);
+    }
+
+    if is_plain_name(value) {
+        let mut named_path = Profiles::get_accounts_folder()?;
+        named_path.push(format!("{}.json", value));
+        if named_path.exists() {
+            return Ok(named_path);
+        }
+    }
+
+    Ok(path)
+}
+
+/// Loads the default account set with `starkli account set-default`, if any.
+pub fn load_default_account() -> Result<Option<String>> {
+    let profiles = Profiles::load()?;
+
+    Ok(profiles
+        .profiles
+        .get(DEFAULT_PROFILE_NAME)
+        .and_then(|profile| profile.default_account.clone()))
+}
+
+/// Loads the account configured for a network in the profile, if any.
+pub fn load_network_account(network: &str) -> Result<Option<String>> {
+    let profiles = Profiles::load()?;
+
+    Ok(profiles
+        .profiles
+        .get(DEFAULT_PROFILE_NAME)
+        .and_then(|profile| profile.networks.get(network))
+        .and_then(|network| network.account.clone()))
+}
+
+/// Loads a deployed account from its config file (path or name in the accounts directory), with a
+/// signer resolved by the caller. Used for operating on many accounts at once.
+pub async fn load_account<P>(
+    provider: P,
+    account: &str,
+    signer: AnySigner,
+) -> Result<SingleOwnerAccount<P, AnySigner>>
+where
+    P: Provider + Send + Sync,
+{
+    let path = resolve_account_path(account)?;
+    if !path.exists() {
+        anyhow::bail!("account config file not found: {}", account);
+    }
+
+    let account_config = AccountConfig::load(path)?;
+
+    if let AccountVariant::WatchOnly(_) = account_config.variant {
+        anyhow::bail!("watch-only account cannot send transactions: {}", account);
+    }
+
+    let account_address = match account_config.deployment {
+        DeploymentStatus::Undeployed(_) => anyhow::bail!("account not deployed: {}", account),
+        DeploymentStatus::Deployed(inner) => inner.address,
+    };
+
+    let chain_id = provider.chain_id().await?;
+
+    let mut account = SingleOwnerAccount::new(
+        provider,
+        signer,
+        account_address,
+        chain_id,
+        account_config.variant.execution_encoding(),
+    );
+    account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+    Ok(account)
+}
+
+/// Loads the accounts listed in a file, one per line as an account file path (or name) followed by
+/// its signer, which is either a `0x`-prefixed private key or a keystore path. Keystores are
+/// unlocked with `keystore_password` when given, or prompted for one by one otherwise. Empty lines
+/// and lines starting with `#` are ignored.
+pub async fn load_accounts_file<P>(
+    provider: P,
+    path: &Path,
+    keystore_password: Option<&str>,
+) -> Result<Vec<SingleOwnerAccount<P, AnySigner>>>
+where
+    P: Provider + Clone + Send + Sync,
+{
+    let mut accounts = vec![];
+    for (index, line) in std::fs::read_to_string(path)?.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (account, signer) = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [account, signer] => (account.to_string(), signer.to_string()),
+            _ => anyhow::bail!(
+                "invalid line {} in {}: expected an account and a signer",
+                index + 1,
+                path.display()
+            ),
+        };
+
+        let key = if signer.starts_with("0x") {
+            SigningKey::from_secret_scalar(FieldElement::from_hex_be(&signer)?)
+        } else {
+            let keystore = PathBuf::from(shellexpand::tilde(&signer).into_owned());
+            if !keystore.exists() {
+                anyhow::bail!("keystore file not found: {}", signer);
+            }
+
+            let password = match keystore_password {
+                Some(password) => password.to_owned(),
+                None => {
+                    rpassword::prompt_password(format!("Enter password for keystore {}: ", signer))?
+                }
+            };
+            SigningKey::from_keystore(keystore, &password)?
+        };
+        let signer = AnySigner::LocalWallet(LocalWallet::from_signing_key(key));
+
+        accounts.push(load_account(provider.clone(), &account, signer).await?);
+    }
+
+    if accounts.is_empty() {
+        anyhow::bail!("no account found in {}", path.display());
+    }
+
+    Ok(accounts)
+}
+
+/// Builds an account for an address without access to its private key, such as one impersonated on
+/// a devnet. A throwaway key is used for signing, so it only works where signatures are not
+/// validated.
+pub async fn impersonated_account<P>(
+    provider: P,
+    address: FieldElement,
+) -> Result<SingleOwnerAccount<P, AnySigner>>
+where
+    P: Provider + Send + Sync,
+{
+    let encoding = match provider
+        .get_class_at(BlockId::Tag(BlockTag::Pending), address)
+        .await?
+    {
+        ContractClass::Sierra(_) => ExecutionEncoding::New,
+        ContractClass::Legacy(_) => ExecutionEncoding::Legacy,
+    };
+    let chain_id = provider.chain_id().await?;
+
+    let mut account = SingleOwnerAccount::new(
+        provider,
+        AnySigner::LocalWallet(LocalWallet::from_signing_key(SigningKey::from_random())),
+        address,
+        chain_id,
+        encoding,
+    );
+    account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+    Ok(account)
+}
+
+pub fn find_builtin_account(id: &str) -> Option<&'static BuiltinAccount> {
     BUILTIN_ACCOUNTS
         .iter()
         .find(|&account| account.id == id || account.aliases.iter().any(|alias| *alias == id))
 }
 
+fn is_plain_name(value: &str) -> bool {
+    let path = Path::new(value);
+    path.components().count() == 1 && path.extension().is_none()
+}
+
 fn true_as_default() -> bool {
     true
 }
@@ -1,42 +1,58 @@
-// Very temporary implementation of a hard-coded address book
+// Hard-coded well-known addresses, plus namespaced address books synced from shared sources
 
-use std::cell::OnceCell;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Mutex,
+};
 
 use anyhow::Result;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
 use starknet::{
-    core::{chain_id, types::FieldElement},
+    core::{
+        chain_id,
+        types::FieldElement,
+        utils::{cairo_short_string_to_felt, parse_cairo_short_string},
+    },
     macros::{felt, short_string},
 };
+use tokio::sync::OnceCell;
 
-use crate::chain_id::ChainIdSource;
-
-const CHAIN_ID_KATANA: FieldElement = felt!("0x4b4154414e41");
+use crate::{
+    chain_id::{ChainIdSource, CHAIN_ID_KATANA},
+    profile::Profiles,
+    resolve_cache::ResolveCache,
+    token::ETH_ADDRESS,
+    warning::Warning,
+};
 
 pub const HARDCODED_ADDRESS_BOOK: [AddressBookEntry; 6] = [
     AddressBookEntry {
         chain_id: chain_id::MAINNET,
         name: "eth",
-        address: felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"),
+        address: ETH_ADDRESS,
     },
     AddressBookEntry {
         chain_id: chain_id::TESTNET,
         name: "eth",
-        address: felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"),
+        address: ETH_ADDRESS,
     },
     AddressBookEntry {
         chain_id: short_string!("SN_SEPOLIA"),
         name: "eth",
-        address: felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"),
+        address: ETH_ADDRESS,
     },
     AddressBookEntry {
         chain_id: short_string!("SN_INTEGRATION_SEPOLIA"),
         name: "eth",
-        address: felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"),
+        address: ETH_ADDRESS,
     },
     AddressBookEntry {
         chain_id: CHAIN_ID_KATANA,
         name: "eth",
-        address: felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"),
+        address: ETH_ADDRESS,
     },
     AddressBookEntry {
         chain_id: chain_id::MAINNET,
@@ -45,16 +61,226 @@ pub const HARDCODED_ADDRESS_BOOK: [AddressBookEntry; 6] = [
     },
 ];
 
+/// Group holding the names of shared address books, which apply to all networks.
+const SHARED_GROUP: &str = "*";
+
+/// Namespaces that would be shadowed by argument resolution schemes.
+const RESERVED_NAMESPACES: [&str; 7] =
+    ["addr", "u256", "const", "str", "eth", "selector", "storage"];
+
 pub struct AddressBookEntry {
     pub chain_id: FieldElement,
     pub name: &'static str,
     pub address: FieldElement,
 }
 
-/// A resolver that lazily fetches chain id to avoid unnecessary network calls.
+/// Names synced from a shared source, like a URL or a file tracked in a git repository, so that
+/// members of an organization use the same names for their deployments. Each address book is
+/// stored under a namespace, and its names are referenced as `namespace:name`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncedAddressBook {
+    /// URL or absolute file path the names are synced from.
+    pub source: String,
+    /// Git commit of the source file at the time of syncing, if it's tracked in a repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    pub synced_at: String,
+    /// Whether the names apply to all networks instead of being grouped by chain ID.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub shared: bool,
+    /// Addresses by name, grouped by chain ID in short string form (e.g. `SN_MAIN`). Shared
+    /// address books have a single group named `*`.
+    pub chains: BTreeMap<String, BTreeMap<String, FieldElement>>,
+}
+
+impl SyncedAddressBook {
+    pub fn get_folder() -> Result<PathBuf> {
+        let mut path = Profiles::get_config_folder()?;
+        path.push("address-books");
+        Ok(path)
+    }
+
+    pub fn get_path(namespace: &str) -> Result<PathBuf> {
+        if namespace.is_empty()
+            || !namespace
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            anyhow::bail!(
+                "invalid namespace \"{}\": only letters, digits, `_` and `-` are allowed",
+                namespace
+            );
+        }
+        if RESERVED_NAMESPACES.contains(&namespace) {
+            anyhow::bail!(
+                "invalid namespace \"{}\": reserved for argument resolution",
+                namespace
+            );
+        }
+
+        let mut path = Self::get_folder()?;
+        path.push(format!("{}.json", namespace));
+        Ok(path)
+    }
+
+    /// Namespaces of all synced address books, sorted.
+    pub fn list() -> Result<Vec<String>> {
+        let folder = Self::get_folder()?;
+        if !folder.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut namespaces = std::fs::read_dir(folder)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            })
+            .collect::<Vec<_>>();
+        namespaces.sort();
+
+        Ok(namespaces)
+    }
+
+    pub fn load(namespace: &str) -> Result<Option<Self>> {
+        let path = Self::get_path(namespace)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_str(&std::fs::read_to_string(path)?)?))
+    }
+
+    pub fn save(&self, namespace: &str) -> Result<()> {
+        let path = Self::get_path(namespace)?;
+
+        let folder = Self::get_folder()?;
+        if !folder.exists() {
+            std::fs::create_dir_all(folder)?;
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        ResolveCache::clear_namespace(namespace);
+
+        Ok(())
+    }
+
+    pub fn remove(namespace: &str) -> Result<()> {
+        let path = Self::get_path(namespace)?;
+        if !path.exists() {
+            anyhow::bail!("address book namespace not found: {}", namespace);
+        }
+
+        std::fs::remove_file(path)?;
+        ResolveCache::clear_namespace(namespace);
+
+        Ok(())
+    }
+
+    /// Downloads or reads the source, which maps chain IDs to tables of names and addresses, in
+    /// either JSON or TOML format:
+    ///
+    /// ```toml
+    /// [SN_MAIN]
+    /// treasury = "0x1234"
+    /// ```
+    ///
+    /// Sources of shared address books are a single table of names instead.
+    pub async fn fetch(source: &str, shared: bool) -> Result<Self> {
+        let is_remote = source.starts_with("https://") || source.starts_with("http://");
+
+        let (content, revision) = if is_remote {
+            let client = reqwest::Client::builder()
+                .user_agent(concat!("starkli/", env!("CARGO_PKG_VERSION")))
+                .build()?;
+            let response = client.get(source).send().await?;
+            if !response.status().is_success() {
+                anyhow::bail!("request to {} failed: {}", source, response.status());
+            }
+            (response.text().await?, None)
+        } else {
+            let path = Path::new(source);
+            let content = std::fs::read_to_string(path)
+                .map_err(|err| anyhow::anyhow!("unable to read {}: {}", source, err))?;
+            (content, git_revision(path))
+        };
+
+        // Query strings and fragments are ignored when guessing the format from URLs
+        let file_name = source.split(['?', '#']).next().unwrap_or_default();
+        let raw_chains: BTreeMap<String, BTreeMap<String, String>> = if shared {
+            BTreeMap::from([(
+                SHARED_GROUP.to_owned(),
+                parse_source(&content, file_name, source)?,
+            )])
+        } else {
+            parse_source(&content, file_name, source)?
+        };
+
+        let mut chains = BTreeMap::new();
+        for (chain, raw_names) in raw_chains.into_iter() {
+            if !shared && cairo_short_string_to_felt(&chain).is_err() {
+                anyhow::bail!("invalid chain ID in address book source: {}", chain);
+            }
+
+            let mut names = BTreeMap::new();
+            for (name, address) in raw_names.into_iter() {
+                if name.is_empty() || name.contains(':') || name.contains(char::is_whitespace) {
+                    anyhow::bail!("invalid name in address book source: \"{}\"", name);
+                }
+
+                let address = FieldElement::from_hex_be(&address).map_err(|_| {
+                    anyhow::anyhow!("invalid address for \"{}\" on {}: {}", name, chain, address)
+                })?;
+                names.insert(name, address);
+            }
+
+            chains.insert(chain, names);
+        }
+
+        Ok(Self {
+            source: source.to_owned(),
+            revision,
+            synced_at: chrono::Utc::now().to_rfc3339(),
+            shared,
+            chains,
+        })
+    }
+
+    pub fn resolve(&self, chain_id: FieldElement, name: &str) -> Option<FieldElement> {
+        self.chains
+            .iter()
+            .find(|(chain, _)| self.applies_to(chain, chain_id))
+            .and_then(|(_, names)| names.get(name).copied())
+    }
+
+    /// Chains other than `chain_id` where the name is defined, for telling a name missing on the
+    /// current network apart from one that doesn't exist at all.
+    pub fn other_chains_with(&self, chain_id: FieldElement, name: &str) -> Vec<String> {
+        self.chains
+            .iter()
+            .filter(|(chain, names)| !self.applies_to(chain, chain_id) && names.contains_key(name))
+            .map(|(chain, _)| chain.to_owned())
+            .collect()
+    }
+
+    fn applies_to(&self, chain: &str, chain_id: FieldElement) -> bool {
+        self.shared || cairo_short_string_to_felt(chain).ok() == Some(chain_id)
+    }
+
+    pub fn name_count(&self) -> usize {
+        self.chains.values().map(|names| names.len()).sum()
+    }
+}
+
+/// A resolver that lazily fetches chain id to avoid unnecessary network calls. The chain ID and
+/// resolved names are cached, so that concurrent and repeated lookups within a command only fetch
+/// the chain ID once.
 pub struct AddressBookResolver<S> {
     chain_id_source: S,
     chain_id: OnceCell<FieldElement>,
+    resolved: Mutex<HashMap<String, Option<FieldElement>>>,
 }
 
 impl<S> AddressBookResolver<S> {
@@ -62,6 +288,7 @@ impl<S> AddressBookResolver<S> {
         Self {
             chain_id_source,
             chain_id: OnceCell::new(),
+            resolved: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -71,26 +298,175 @@ where
     S: ChainIdSource,
 {
     pub async fn resolve_name(&self, name: &str) -> Result<Option<FieldElement>> {
-        let chain_id_cell = &self.chain_id;
+        if let Some(resolved) = self.resolved.lock().unwrap().get(name) {
+            return Ok(*resolved);
+        }
 
-        let chain_id = match chain_id_cell.get() {
-            Some(chain_id) => *chain_id,
-            None => {
-                let chain_id = self.chain_id_source.get_chain_id().await?;
+        let chain_id = self.chain_id().await?;
 
-                // It's OK if another thread set it first
-                let _ = chain_id_cell.set(chain_id);
+        let resolved = match ResolveCache::name(chain_id, name) {
+            Some(resolved) => resolved,
+            None => {
+                let resolved = if let Some((namespace, entry_name)) = name.split_once(':') {
+                    match SyncedAddressBook::load(namespace)? {
+                        Some(address_book) => {
+                            let resolved = address_book.resolve(chain_id, entry_name);
+                            let other_chains = address_book.other_chains_with(chain_id, entry_name);
+                            if resolved.is_none() && !other_chains.is_empty() {
+                                anyhow::bail!(
+                                    "\"{}\" is not defined on {}, only on {}. Check that you're \
+                                    connected to the intended network",
+                                    name,
+                                    format_chain_id(chain_id),
+                                    other_chains.join(", ")
+                                );
+                            }
+                            resolved
+                        }
+                        None => None,
+                    }
+                } else {
+                    HARDCODED_ADDRESS_BOOK.iter().find_map(|entry| {
+                        if entry.chain_id == chain_id && entry.name == name {
+                            Some(entry.address)
+                        } else {
+                            None
+                        }
+                    })
+                };
 
-                chain_id
+                ResolveCache::set_name(chain_id, name, resolved);
+                resolved
             }
         };
 
-        Ok(HARDCODED_ADDRESS_BOOK.iter().find_map(|entry| {
-            if entry.chain_id == chain_id && entry.name == name {
-                Some(entry.address)
-            } else {
-                None
+        // Names from synced address books are shown with the network they were resolved on, so
+        // that using a name on the wrong network doesn't go unnoticed
+        if let (Some(address), true) = (resolved, name.contains(':')) {
+            eprintln!(
+                "Resolved {} to {} on {}",
+                name,
+                format!("{:#064x}", address).bright_yellow(),
+                format_chain_id(chain_id).bright_yellow()
+            );
+        }
+
+        self.resolved
+            .lock()
+            .unwrap()
+            .insert(name.to_owned(), resolved);
+
+        Ok(resolved)
+    }
+
+    /// Warns when a raw address is only listed in address books for other networks, which
+    /// usually means it was copied from the wrong network. Address books are local, so this only
+    /// costs the chain ID lookup, and nothing when no address book is synced.
+    pub async fn check_address_network(&self, address: FieldElement) {
+        let namespaces = match SyncedAddressBook::list() {
+            Ok(namespaces) if !namespaces.is_empty() => namespaces,
+            _ => return,
+        };
+        let chain_id = match self.chain_id().await {
+            Ok(chain_id) => chain_id,
+            Err(_) => return,
+        };
+
+        let mut foreign_entry = None;
+        for namespace in namespaces.iter() {
+            let address_book = match SyncedAddressBook::load(namespace) {
+                Ok(Some(address_book)) => address_book,
+                _ => continue,
+            };
+
+            for (chain, names) in address_book.chains.iter() {
+                for (name, entry_address) in names.iter() {
+                    if *entry_address != address {
+                        continue;
+                    }
+                    if address_book.applies_to(chain, chain_id) {
+                        return;
+                    }
+                    foreign_entry.get_or_insert((format!("{}:{}", namespace, name), chain.clone()));
+                }
             }
-        }))
+        }
+
+        if let Some((name, chain)) = foreign_entry {
+            Warning::WrongNetwork.print(format!(
+                "{:#064x} is {} on {}, but the provider is on {}. Check that you're connected to \
+                the intended network.",
+                address,
+                name,
+                chain,
+                format_chain_id(chain_id)
+            ));
+        }
+    }
+
+    async fn chain_id(&self) -> Result<FieldElement> {
+        // Concurrent lookups wait for the same request instead of sending their own
+        Ok(*self
+            .chain_id
+            .get_or_try_init(|| self.chain_id_source.get_chain_id())
+            .await?)
     }
 }
+
+fn format_chain_id(chain_id: FieldElement) -> String {
+    parse_cairo_short_string(&chain_id).unwrap_or_else(|_| format!("{:#x}", chain_id))
+}
+
+fn parse_source<T>(content: &str, file_name: &str, source: &str) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    Ok(if file_name.ends_with(".toml") {
+        toml::from_str(content)?
+    } else if file_name.ends_with(".json") {
+        serde_json::from_str(content)?
+    } else {
+        match serde_json::from_str(content) {
+            Ok(parsed) => parsed,
+            Err(_) => toml::from_str(content).map_err(|_| {
+                anyhow::anyhow!("address book source {} is neither JSON nor TOML", source)
+            })?,
+        }
+    })
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Commit hash of the repository containing the file, with a `-dirty` suffix if the file has
+/// uncommitted changes. Returns `None` if the file is not tracked by git.
+fn git_revision(path: &Path) -> Option<String> {
+    let folder = path
+        .parent()
+        .filter(|folder| !folder.as_os_str().is_empty())?;
+    let git = |args: &[&std::ffi::OsStr]| {
+        Command::new("git")
+            .arg("-C")
+            .arg(folder)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    };
+
+    let revision = git(&["rev-parse".as_ref(), "--short".as_ref(), "HEAD".as_ref()])?;
+    let status = git(&[
+        "status".as_ref(),
+        "--porcelain".as_ref(),
+        "--".as_ref(),
+        path.as_os_str(),
+    ])?;
+
+    Some(if status.is_empty() {
+        revision
+    } else {
+        format!("{}-dirty", revision)
+    })
+}
@@ -0,0 +1,312 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::Result;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use starknet::core::{crypto::Signature, types::FieldElement};
+
+use crate::profile::Profiles;
+
+/// Connection details of the running agent, written to `agent.json` under the config folder so
+/// that other invocations can find it. The token keeps other local users from using the agent, and
+/// the agent proves it knows the token before anything is sent to it, so that a process taking
+/// over the port of a dead agent can't collect keys.
+#[derive(Serialize, Deserialize)]
+pub struct AgentSession {
+    pub port: u16,
+    pub token: String,
+}
+
+/// First message of a connection: a random challenge the agent must answer with `AgentProof`.
+#[derive(Serialize, Deserialize)]
+struct AgentChallenge {
+    challenge: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AgentProof {
+    proof: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AgentEnvelope {
+    token: String,
+    request: AgentRequest,
+}
+
+/// Requests to the agent. Keys are identified by the canonical path of their keystore files.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentRequest {
+    Add {
+        keystore: String,
+        private_key: FieldElement,
+        /// Overrides the agent's default TTL for this key, in seconds.
+        ttl: Option<u64>,
+    },
+    PublicKey {
+        keystore: String,
+    },
+    Sign {
+        keystore: String,
+        hash: FieldElement,
+    },
+    List,
+    /// Forgets all keys without stopping the agent.
+    Lock,
+    Stop,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentResponse {
+    Ok,
+    PublicKey(FieldElement),
+    Signature { r: FieldElement, s: FieldElement },
+    Keys(Vec<AgentKeyInfo>),
+    Error(String),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AgentKeyInfo {
+    pub keystore: String,
+    pub public_key: FieldElement,
+    /// Seconds until the key is forgotten.
+    pub expires_in: u64,
+}
+
+impl AgentSession {
+    pub fn get_path() -> Result<PathBuf> {
+        let mut path = Profiles::get_config_folder()?;
+        path.push("agent.json");
+        Ok(path)
+    }
+
+    pub fn load() -> Result<Option<Self>> {
+        let path = Self::get_path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let mut buffer = String::new();
+        std::fs::File::open(path)?.read_to_string(&mut buffer)?;
+
+        Ok(Some(serde_json::from_str(&buffer)?))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let config_folder = Profiles::get_config_folder()?;
+        if !config_folder.exists() {
+            std::fs::create_dir_all(config_folder)?;
+        }
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(Self::get_path()?)?;
+
+        // The mode above only applies to new files, while the file may be left from a previous
+        // agent with different permissions
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+
+        file.write_all(serde_json::to_string(self)?.as_bytes())?;
+
+        Ok(())
+    }
+
+    pub fn remove() -> Result<()> {
+        let path = Self::get_path()?;
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends a request to the agent and waits for the response. The agent must first prove it
+    /// knows the session token, as the request may carry a private key.
+    pub fn request(&self, request: AgentRequest) -> Result<AgentResponse> {
+        let stream = TcpStream::connect(("127.0.0.1", self.port)).map_err(|_| {
+            anyhow::anyhow!("agent not reachable. Start one with `starkli agent start`")
+        })?;
+        stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+        let mut reader = BufReader::new(&stream);
+
+        let mut challenge = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut challenge);
+        let challenge = hex::encode(challenge);
+        write_line(
+            &stream,
+            &AgentChallenge {
+                challenge: challenge.clone(),
+            },
+        )?;
+
+        let proof: AgentProof = read_line(&mut reader)?;
+        if !constant_time_eq(proof.proof.as_bytes(), self.proof(&challenge).as_bytes()) {
+            anyhow::bail!(
+                "the process on port {} is not the agent that wrote {}. Remove the file if no \
+                agent is running",
+                self.port,
+                Self::get_path()?.display()
+            );
+        }
+
+        write_line(
+            &stream,
+            &AgentEnvelope {
+                token: self.token.clone(),
+                request,
+            },
+        )?;
+
+        match read_line(&mut reader)? {
+            AgentResponse::Error(err) => Err(anyhow::anyhow!("agent error: {}", err)),
+            response => Ok(response),
+        }
+    }
+
+    /// Reads a request from a client connection, proving knowledge of the token to the client
+    /// first and then checking the client's token.
+    pub fn read_request(&self, stream: &TcpStream) -> Result<AgentRequest> {
+        let mut reader = BufReader::new(stream);
+
+        let challenge: AgentChallenge = read_line(&mut reader)?;
+        write_line(
+            stream,
+            &AgentProof {
+                proof: self.proof(&challenge.challenge),
+            },
+        )?;
+
+        let envelope: AgentEnvelope = read_line(&mut reader)?;
+        if !constant_time_eq(envelope.token.as_bytes(), self.token.as_bytes()) {
+            anyhow::bail!("invalid token");
+        }
+
+        Ok(envelope.request)
+    }
+
+    /// Answer to a client challenge, which can only be computed with the token.
+    fn proof(&self, challenge: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(b"starkli-agent-proof");
+        hasher.update(self.token.as_bytes());
+        hasher.update(challenge.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    pub fn write_response(stream: &TcpStream, response: &AgentResponse) -> Result<()> {
+        write_line(stream, response)
+    }
+}
+
+fn write_line<T>(mut stream: &TcpStream, message: &T) -> Result<()>
+where
+    T: Serialize,
+{
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    Ok(())
+}
+
+fn read_line<T>(reader: &mut BufReader<&TcpStream>) -> Result<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Compares secrets without stopping at the first differing byte, so that response times don't
+/// reveal how much of a guess is right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b.iter())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+/// Connects to the running agent, if any.
+pub fn connect() -> Result<AgentSession> {
+    AgentSession::load()?
+        .ok_or_else(|| anyhow::anyhow!("no agent running. Start one with `starkli agent start`"))
+}
+
+/// Signer backed by a key held in the agent.
+#[derive(Debug)]
+pub struct AgentSigner {
+    keystore: String,
+    public_key: FieldElement,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct AgentSignError(String);
+
+impl AgentSigner {
+    /// Finds the key of a keystore in the agent. Returns `None` when no agent is running or the
+    /// keystore hasn't been added, in which case the keystore is used as usual.
+    pub fn find(keystore: &str) -> Option<Self> {
+        let session = AgentSession::load().ok()??;
+
+        match session.request(AgentRequest::PublicKey {
+            keystore: keystore.to_owned(),
+        }) {
+            Ok(AgentResponse::PublicKey(public_key)) => Some(Self {
+                keystore: keystore.to_owned(),
+                public_key,
+            }),
+            Ok(_) => None,
+            Err(err) => {
+                log::debug!("keystore not available from agent: {}", err);
+                None
+            }
+        }
+    }
+
+    pub fn public_key(&self) -> FieldElement {
+        self.public_key
+    }
+
+    pub async fn sign(&self, hash: &FieldElement) -> Result<Signature, AgentSignError> {
+        let request = AgentRequest::Sign {
+            keystore: self.keystore.clone(),
+            hash: *hash,
+        };
+
+        // The agent is reached with blocking I/O, which must not stall the async runtime
+        let response = tokio::task::spawn_blocking(move || connect()?.request(request))
+            .await
+            .map_err(|err| AgentSignError(err.to_string()))?
+            .map_err(|err| AgentSignError(err.to_string()))?;
+
+        match response {
+            AgentResponse::Signature { r, s } => Ok(Signature { r, s }),
+            _ => Err(AgentSignError("unexpected agent response".into())),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use clap::Parser;
+use num_bigint::BigUint;
+use serde_json::Value;
+use starknet::core::types::FieldElement;
+
+/// Controls how token amounts are printed, shared by commands that output balances and fees.
+#[derive(Debug, Clone, Parser)]
+pub struct AmountFormatArgs {
+    #[clap(
+        long,
+        visible_alias = "wei",
+        conflicts_with = "human_amounts",
+        help = "Print amounts as exact integers in the smallest unit (e.g. Wei)"
+    )]
+    raw_amounts: bool,
+    #[clap(long, help = "Print amounts as decimals in whole tokens (e.g. ETH)")]
+    human_amounts: bool,
+    #[clap(
+        long,
+        conflicts_with = "raw_amounts",
+        help = "Number of decimal places in human-readable amounts, rounding half up. All the \
+                token's decimals are shown by default"
+    )]
+    precision: Option<u32>,
+    #[clap(
+        long,
+        conflicts_with = "raw_amounts",
+        help = "Separate thousands with commas in human-readable amounts"
+    )]
+    group_digits: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountFormat {
+    Raw,
+    Human(DecimalStyle),
+}
+
+/// How decimal amounts are rendered. The output never depends on the system locale: the decimal
+/// separator is always `.` and the optional thousands separator always `,`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalStyle {
+    /// Fixed number of decimal places, or all the token's decimals if not set.
+    pub precision: Option<u32>,
+    pub group_digits: bool,
+}
+
+impl AmountFormatArgs {
+    /// The format explicitly requested, if any. Styling options imply human-readable amounts.
+    pub fn format(&self) -> Option<AmountFormat> {
+        let style = DecimalStyle {
+            precision: self.precision,
+            group_digits: self.group_digits,
+        };
+
+        if self.raw_amounts {
+            Some(AmountFormat::Raw)
+        } else if self.human_amounts || style != DecimalStyle::default() {
+            Some(AmountFormat::Human(style))
+        } else {
+            None
+        }
+    }
+
+    pub fn format_or(&self, default: AmountFormat) -> AmountFormat {
+        self.format().unwrap_or(default)
+    }
+}
+
+impl AmountFormat {
+    pub const HUMAN: Self = Self::Human(DecimalStyle {
+        precision: None,
+        group_digits: false,
+    });
+
+    /// Formats an amount of a token with 18 decimals, like ETH and STRK. Raw amounts are printed
+    /// without the unit so that scripts can use them as is.
+    pub fn format(self, amount: FieldElement, symbol: &str) -> String {
+        self.format_units(&felt_to_biguint(amount), 18, Some(symbol))
+    }
+
+    pub fn format_units(self, amount: &BigUint, decimals: u32, symbol: Option<&str>) -> String {
+        match self {
+            Self::Raw => amount.to_string(),
+            Self::Human(style) => {
+                let amount = style.format(amount, decimals);
+                match symbol {
+                    Some(symbol) => format!("{} {}", amount, symbol),
+                    None => amount,
+                }
+            }
+        }
+    }
+
+    /// Rewrites a JSON fee amount in place, either a bare hex string or a fee payment object with
+    /// `amount` and `unit` fields. Values that cannot be parsed are left untouched.
+    pub fn apply_to_json_fee(self, fee: &mut Value) {
+        match fee {
+            Value::String(amount) => {
+                if let Ok(parsed) = FieldElement::from_hex_be(amount) {
+                    *amount = self.format(parsed, "ETH");
+                }
+            }
+            Value::Object(payment) => {
+                let symbol = match payment.get("unit").and_then(|unit| unit.as_str()) {
+                    Some("FRI") => "STRK",
+                    _ => "ETH",
+                };
+                if let Some(Value::String(amount)) = payment.get_mut("amount") {
+                    if let Ok(parsed) = FieldElement::from_hex_be(amount) {
+                        *amount = self.format(parsed, symbol);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl DecimalStyle {
+    /// Formats an integer amount in the smallest unit as a decimal with `decimals` places.
+    pub fn format(&self, amount: &BigUint, decimals: u32) -> String {
+        let ten = BigUint::from(10u32);
+
+        let (scaled, places) = match self.precision {
+            Some(precision) if precision < decimals => {
+                let divisor = ten.pow(decimals - precision);
+                ((amount + &divisor / 2u32) / &divisor, precision)
+            }
+            Some(precision) => (amount * ten.pow(precision - decimals), precision),
+            None => (amount.clone(), decimals),
+        };
+
+        let unit = ten.pow(places);
+        let integer = (&scaled / &unit).to_string();
+        let integer = if self.group_digits {
+            group_thousands(&integer)
+        } else {
+            integer
+        };
+
+        if places == 0 {
+            integer
+        } else {
+            format!(
+                "{}.{:0>width$}",
+                integer,
+                (&scaled % &unit).to_string(),
+                width = places as usize
+            )
+        }
+    }
+
+    pub fn format_ether(&self, amount: FieldElement) -> String {
+        self.format(&felt_to_biguint(amount), 18)
+    }
+}
+
+/// Formats an amount in Wei as Ether with all 18 decimal places, so that the same amount always
+/// prints the same way across commands.
+pub fn format_ether(amount: FieldElement) -> String {
+    DecimalStyle::default().format_ether(amount)
+}
+
+fn group_thousands(integer: &str) -> String {
+    let mut grouped = String::with_capacity(integer.len() + integer.len() / 3);
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+fn felt_to_biguint(value: FieldElement) -> BigUint {
+    BigUint::from_bytes_be(&value.to_bytes_be())
+}
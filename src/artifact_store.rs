@@ -0,0 +1,187 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::Result;
+use starknet::core::types::{
+    contract::{legacy::LegacyContractClass, CompiledClass, SierraClass},
+    FieldElement,
+};
+
+use crate::{abi_cache::CachedAbi, profile::Profiles};
+
+/// Contract artifacts stored under `store` in the config folder, one file per class hash. Commands
+/// can then refer to an artifact by its class hash, regardless of where it was built.
+pub struct ArtifactStore {
+    folder: PathBuf,
+}
+
+/// An artifact in the store, as listed by `starkli store list`.
+pub struct StoredArtifact {
+    pub class_hash: FieldElement,
+    pub kind: ArtifactKind,
+    pub path: PathBuf,
+    pub size: u64,
+    pub added_at: SystemTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactKind {
+    Sierra,
+    Legacy,
+}
+
+impl ArtifactStore {
+    pub fn open() -> Result<Self> {
+        let mut folder = Profiles::get_config_folder()?;
+        folder.push("store");
+        Ok(Self { folder })
+    }
+
+    /// Copies an artifact into the store. Returns the class hash and whether the artifact was new.
+    pub fn add(&self, path: &Path) -> Result<(FieldElement, ArtifactKind, bool)> {
+        let contents = std::fs::read_to_string(path)?;
+        let (class_hash, kind) = parse_artifact(&contents)?;
+
+        let stored_path = self.path_of(class_hash);
+        if stored_path.exists() {
+            return Ok((class_hash, kind, false));
+        }
+
+        std::fs::create_dir_all(&self.folder)?;
+
+        // Written under a temporary name first so that an interrupted copy never shows up as a
+        // stored artifact
+        let temp_path = stored_path.with_extension("json.tmp");
+        std::fs::write(&temp_path, contents)?;
+        std::fs::rename(&temp_path, &stored_path)?;
+
+        Ok((class_hash, kind, true))
+    }
+
+    /// Path of the stored artifact for a class hash. The artifact is hashed again so that an
+    /// edited file is never used in place of the class it's named after.
+    pub fn get(&self, class_hash: FieldElement) -> Result<PathBuf> {
+        let path = self.path_of(class_hash);
+        if !path.exists() {
+            anyhow::bail!(
+                "class {:#064x} is not in the artifact store. Add it with `starkli store add`",
+                class_hash
+            );
+        }
+
+        let (actual_hash, _) = parse_artifact(&std::fs::read_to_string(&path)?)?;
+        if actual_hash != class_hash {
+            anyhow::bail!(
+                "stored artifact {} hashes to {:#064x} instead. Remove it and add it again",
+                path.display(),
+                actual_hash
+            );
+        }
+
+        Ok(path)
+    }
+
+    /// The ABI of a stored class, so that ABI lookups don't need the network for known classes.
+    pub fn abi(&self, class_hash: FieldElement) -> Option<CachedAbi> {
+        let contents = std::fs::read_to_string(self.path_of(class_hash)).ok()?;
+
+        if let Ok(class) = serde_json::from_str::<SierraClass>(&contents) {
+            Some(CachedAbi {
+                class_hash,
+                sierra: true,
+                abi: serde_json::to_value(class.abi).ok()?,
+            })
+        } else if let Ok(class) = serde_json::from_str::<LegacyContractClass>(&contents) {
+            Some(CachedAbi {
+                class_hash,
+                sierra: false,
+                abi: serde_json::to_value(class.abi).ok()?,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn remove(&self, class_hash: FieldElement) -> Result<bool> {
+        let path = self.path_of(class_hash);
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        std::fs::remove_file(path)?;
+        Ok(true)
+    }
+
+    /// All stored artifacts, sorted by class hash.
+    pub fn list(&self) -> Result<Vec<StoredArtifact>> {
+        let mut artifacts = vec![];
+        if !self.folder.exists() {
+            return Ok(artifacts);
+        }
+
+        for entry in std::fs::read_dir(&self.folder)? {
+            let path = entry?.path();
+            let class_hash = match path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".json"))
+                .and_then(|hash| FieldElement::from_hex_be(hash).ok())
+            {
+                Some(class_hash) => class_hash,
+                None => continue,
+            };
+
+            let kind = match parse_artifact(&std::fs::read_to_string(&path)?) {
+                Ok((_, kind)) => kind,
+                Err(err) => {
+                    log::warn!("skipping unreadable artifact {}: {}", path.display(), err);
+                    continue;
+                }
+            };
+
+            let metadata = std::fs::metadata(&path)?;
+            artifacts.push(StoredArtifact {
+                class_hash,
+                kind,
+                size: metadata.len(),
+                added_at: metadata.modified()?,
+                path,
+            });
+        }
+
+        artifacts.sort_by_key(|artifact| artifact.class_hash);
+        Ok(artifacts)
+    }
+
+    fn path_of(&self, class_hash: FieldElement) -> PathBuf {
+        self.folder.join(format!("{:#064x}.json", class_hash))
+    }
+}
+
+impl std::fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sierra => write!(f, "Cairo 1"),
+            Self::Legacy => write!(f, "Cairo 0"),
+        }
+    }
+}
+
+/// Computes the class hash of a Sierra or legacy class artifact.
+fn parse_artifact(contents: &str) -> Result<(FieldElement, ArtifactKind)> {
+    // Working around a deserialization bug in `starknet-rs`:
+    //   https://github.com/xJonathanLEI/starknet-rs/issues/392
+
+    #[allow(clippy::redundant_pattern_matching)]
+    if let Ok(class) = serde_json::from_str::<SierraClass>(contents) {
+        Ok((class.class_hash()?, ArtifactKind::Sierra))
+    } else if let Ok(_) = serde_json::from_str::<CompiledClass>(contents) {
+        anyhow::bail!("CASM classes can't be stored. Add the Sierra class instead")
+    } else if let Ok(class) = serde_json::from_str::<LegacyContractClass>(contents) {
+        Ok((class.class_hash()?, ArtifactKind::Legacy))
+    } else {
+        anyhow::bail!("failed to parse contract artifact")
+    }
+}
@@ -3,15 +3,23 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use starknet::core::types::{
-    contract::{CompiledClass, SierraClass},
-    FieldElement,
+use serde::Deserialize;
+use starknet::core::{
+    types::{
+        contract::{
+            CompiledClass, CompiledClassEntrypoint, CompiledClassEntrypointList, SierraClass,
+        },
+        FieldElement,
+    },
+    utils::cairo_short_string_to_felt,
 };
+use starknet_crypto::poseidon_hash_many;
 
 use crate::{
-    compiler::{BuiltInCompiler, CompilerBinary, CompilerVersion},
+    compiler::{AllowedLibfuncs, BuiltInCompiler, CasmCacheMode, CompilerBinary, CompilerVersion},
     network::{Network, NetworkSource},
     path::ExpandedPathbufParser,
+    warning::Warning,
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -32,6 +40,26 @@ pub struct CasmArgs {
     casm_file: Option<PathBuf>,
     #[clap(long, help = "Override Sierra compilation and use CASM hash directly")]
     casm_hash: Option<String>,
+    #[clap(
+        long,
+        conflicts_with_all = ["casm_file", "casm_hash"],
+        help = "Validate the libfuncs used against an allowed list before compiling: \
+                `audited`, `experimental`, `all`, or a path to a list file"
+    )]
+    allowed_libfuncs: Option<AllowedLibfuncs>,
+    #[clap(
+        long,
+        env = "STARKLI_NO_CASM_CACHE",
+        help = "Always compile the Sierra class instead of using a CASM hash cached from a \
+                previous compilation with the same compiler version"
+    )]
+    no_casm_cache: bool,
+    #[clap(
+        long,
+        conflicts_with = "no_casm_cache",
+        help = "Also keep the full compiled CASM class in the cache, not only its hash"
+    )]
+    cache_full_casm: bool,
 }
 
 #[derive(Debug)]
@@ -47,18 +75,27 @@ impl CasmArgs {
     where
         N: NetworkSource,
     {
+        let allowed_libfuncs = self.allowed_libfuncs;
+        let cache = match (self.no_casm_cache, self.cache_full_casm) {
+            (true, _) => CasmCacheMode::Disabled,
+            (false, true) => CasmCacheMode::Full,
+            (false, false) => CasmCacheMode::HashOnly,
+        };
+
         match (
             self.compiler_version,
             self.compiler_path,
             self.casm_file,
             self.casm_hash,
         ) {
-            (Some(compiler_version), None, None, None) => {
-                Ok(CasmHashSource::BuiltInCompiler(compiler_version.into()))
-            }
-            (None, Some(compiler_path), None, None) => {
-                Ok(CasmHashSource::CompilerBinary(compiler_path.into()))
-            }
+            (Some(compiler_version), None, None, None) => Ok(CasmHashSource::BuiltInCompiler(
+                BuiltInCompiler::from(compiler_version)
+                    .with_allowed_libfuncs(allowed_libfuncs)
+                    .with_cache(cache),
+            )),
+            (None, Some(compiler_path), None, None) => Ok(CasmHashSource::CompilerBinary(
+                CompilerBinary::from(compiler_path).with_allowed_libfuncs(allowed_libfuncs),
+            )),
             (None, None, Some(casm_file), None) => Ok(CasmHashSource::CasmFile(casm_file)),
             (None, None, None, Some(casm_hash)) => Ok(CasmHashSource::Hash(casm_hash.parse()?)),
             // Tries to detect compiler version if nothing provided
@@ -71,13 +108,7 @@ impl CasmArgs {
                 let network = network_source.get_network().await?;
                 match network {
                     Some(network) => {
-                        let auto_version = match network {
-                            Network::Mainnet => CompilerVersion::V2_1_0,
-                            Network::Goerli
-                            | Network::Sepolia
-                            | Network::GoerliIntegration
-                            | Network::SepoliaIntegration => CompilerVersion::V2_4_0,
-                        };
+                        let auto_version = network_compiler_version(network);
 
                         eprintln!(
                             "Network detected: {}. \
@@ -87,7 +118,11 @@ impl CasmArgs {
                             format!("{}", auto_version).bright_yellow()
                         );
 
-                        Ok(CasmHashSource::BuiltInCompiler(auto_version.into()))
+                        Ok(CasmHashSource::BuiltInCompiler(
+                            BuiltInCompiler::from(auto_version)
+                                .with_allowed_libfuncs(allowed_libfuncs)
+                                .with_cache(cache),
+                        ))
                     }
                     None => {
                         let default_version: CompilerVersion = Default::default();
@@ -98,7 +133,11 @@ impl CasmArgs {
                             format!("{}", default_version).bright_yellow()
                         );
 
-                        Ok(CasmHashSource::BuiltInCompiler(default_version.into()))
+                        Ok(CasmHashSource::BuiltInCompiler(
+                            BuiltInCompiler::from(default_version)
+                                .with_allowed_libfuncs(allowed_libfuncs)
+                                .with_cache(cache),
+                        ))
                     }
                 }
             }
@@ -115,13 +154,157 @@ impl CasmHashSource {
         match self {
             Self::BuiltInCompiler(compiler) => compiler.compile(sierra_class),
             Self::CompilerBinary(compiler) => compiler.compile(sierra_class),
-            Self::CasmFile(path) => {
-                let mut casm_file = std::fs::File::open(path)?;
-                let casm_class = serde_json::from_reader::<_, CompiledClass>(&mut casm_file)?;
+            Self::CasmFile(path) => compiled_class_hash(&std::fs::read_to_string(path)?),
+            Self::Hash(hash) => Ok(*hash),
+        }
+    }
+}
+
+/// The parts of a CASM class that make up its hash, including the bytecode segmentation that
+/// compilers emit since Sierra 1.5.
+#[derive(Deserialize)]
+struct HashedCasmClass {
+    bytecode: Vec<FieldElement>,
+    #[serde(default)]
+    bytecode_segment_lengths: Option<NestedIntList>,
+    entry_points_by_type: CompiledClassEntrypointList,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NestedIntList {
+    Leaf(u64),
+    Node(Vec<NestedIntList>),
+}
 
-                Ok(casm_class.class_hash()?)
+/// Computes the compiled class hash of a CASM class from its JSON. Classes with
+/// `bytecode_segment_lengths` have their bytecode hashed segment by segment, like Starknet does
+/// since v0.13.1. Classes without it are hashed over the flat bytecode as before.
+pub fn compiled_class_hash(casm_json: &str) -> Result<FieldElement> {
+    let casm_class = serde_json::from_str::<HashedCasmClass>(casm_json)?;
+
+    let segment_lengths = match &casm_class.bytecode_segment_lengths {
+        Some(segment_lengths) => segment_lengths,
+        None => return Ok(serde_json::from_str::<CompiledClass>(casm_json)?.class_hash()?),
+    };
+
+    let (bytecode_hash, hashed_len) = hash_bytecode_segment(&casm_class.bytecode, segment_lengths)?;
+    if hashed_len != casm_class.bytecode.len() {
+        anyhow::bail!(
+            "invalid CASM class: bytecode segment lengths add up to {} but the bytecode has {} \
+            felts",
+            hashed_len,
+            casm_class.bytecode.len()
+        );
+    }
+
+    let entry_points = &casm_class.entry_points_by_type;
+    Ok(poseidon_hash_many(&[
+        cairo_short_string_to_felt("COMPILED_CLASS_V1")?,
+        hash_entry_points(&entry_points.external)?,
+        hash_entry_points(&entry_points.l1_handler)?,
+        hash_entry_points(&entry_points.constructor)?,
+        bytecode_hash,
+    ]))
+}
+
+/// Hashes the bytecode at the start of `bytecode` covered by the segment structure, returning the
+/// hash along with the number of felts covered. Leaves hash their data directly, while nodes hash
+/// the length and hash of each child, plus one.
+fn hash_bytecode_segment(
+    bytecode: &[FieldElement],
+    segment_lengths: &NestedIntList,
+) -> Result<(FieldElement, usize)> {
+    match segment_lengths {
+        NestedIntList::Leaf(len) => {
+            let len = *len as usize;
+            let data = bytecode.get(..len).ok_or_else(|| {
+                anyhow::anyhow!("invalid CASM class: bytecode segment out of range")
+            })?;
+            Ok((poseidon_hash_many(data), len))
+        }
+        NestedIntList::Node(children) => {
+            let mut elements = vec![];
+            let mut total_len = 0;
+            for child in children.iter() {
+                let (hash, len) = hash_bytecode_segment(&bytecode[total_len..], child)?;
+                elements.push(FieldElement::from(len));
+                elements.push(hash);
+                total_len += len;
             }
-            Self::Hash(hash) => Ok(*hash),
+            Ok((poseidon_hash_many(&elements) + FieldElement::ONE, total_len))
+        }
+    }
+}
+
+fn hash_entry_points(entry_points: &[CompiledClassEntrypoint]) -> Result<FieldElement> {
+    let mut elements = vec![];
+    for entry_point in entry_points.iter() {
+        let builtins = entry_point
+            .builtins
+            .iter()
+            .map(|builtin| cairo_short_string_to_felt(builtin))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        elements.push(entry_point.selector);
+        elements.push(FieldElement::from(entry_point.offset));
+        elements.push(poseidon_hash_many(&builtins));
+    }
+    Ok(poseidon_hash_many(&elements))
+}
+
+/// The Sierra compiler version each known network uses to compute CASM hashes.
+pub fn network_compiler_version(network: Network) -> CompilerVersion {
+    match network {
+        Network::Mainnet => CompilerVersion::V2_1_0,
+        Network::Goerli
+        | Network::Sepolia
+        | Network::GoerliIntegration
+        | Network::SepoliaIntegration => CompilerVersion::V2_4_0,
+    }
+}
+
+/// Checks the CASM hash against the one the network's compiler would produce, so that a mismatch
+/// is reported locally instead of as an opaque `CompiledClassHashMismatch` from the sequencer.
+/// The check is skipped for unknown networks.
+pub async fn check_casm_hash_for_network<N>(
+    network_source: N,
+    sierra_class: &SierraClass,
+    casm_class_hash: FieldElement,
+) -> Result<()>
+where
+    N: NetworkSource,
+{
+    let network = match network_source.get_network().await? {
+        Some(network) => network,
+        None => return Ok(()),
+    };
+
+    let expected_version = network_compiler_version(network);
+    let expected_hash = match BuiltInCompiler::from(expected_version).compile(sierra_class) {
+        Ok(hash) => hash,
+        Err(err) => {
+            Warning::CasmVerification.print(format!(
+                "unable to verify CASM hash with compiler version {} used by {}: {}",
+                expected_version, network, err
+            ));
+            return Ok(());
         }
+    };
+
+    if expected_hash != casm_class_hash {
+        anyhow::bail!(
+            "CASM hash mismatch: {} compiles this class to CASM hash {:#064x} with compiler \
+            version {}, but {:#064x} is used. The declaration would be rejected. Use \
+            `--compiler-version {}` to match the network, or --no-casm-check if you're sure the \
+            network has switched to another compiler version.",
+            network,
+            expected_hash,
+            expected_version,
+            casm_class_hash,
+            expected_version
+        );
     }
+
+    Ok(())
 }
@@ -1,6 +1,9 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use starknet::{core::types::FieldElement, providers::Provider};
+use starknet::{core::types::FieldElement, macros::short_string, providers::Provider};
+
+/// Chain ID used by Katana devnets.
+pub const CHAIN_ID_KATANA: FieldElement = short_string!("KATANA");
 
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -1,33 +1,65 @@
 use std::{
     fmt::Display,
+    io::{BufWriter, Write},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Stdio},
     str::FromStr,
 };
 
 use anyhow::Result;
 use cairo_starknet_2_1_0::{
+    allowed_libfuncs::{
+        validate_compatible_sierra_version as cairo_210_validate_libfuncs,
+        ListSelector as Cairo210ListSelector,
+    },
     casm_contract_class::CasmContractClass as Cairo210CasmClass,
     contract_class::ContractClass as Cairo210Class,
 };
 use cairo_starknet_2_4_0::{
+    allowed_libfuncs::{
+        validate_compatible_sierra_version as cairo_240_validate_libfuncs,
+        ListSelector as Cairo240ListSelector,
+    },
     casm_contract_class::CasmContractClass as Cairo240CasmClass,
     contract_class::ContractClass as Cairo240Class,
 };
 use clap::{builder::PossibleValue, ValueEnum};
-use starknet::core::types::{
-    contract::{CompiledClass, SierraClass},
-    FieldElement,
-};
+use starknet::core::types::{contract::SierraClass, FieldElement};
+
+use crate::{casm::compiled_class_hash, profile::Profiles};
 
 #[derive(Debug)]
 pub struct BuiltInCompiler {
     version: CompilerVersion,
+    allowed_libfuncs: Option<AllowedLibfuncs>,
+    cache: CasmCacheMode,
+}
+
+/// Whether compilation results are cached on disk, keyed by Sierra class hash and compiler
+/// version. The same compiler always produces the same CASM for a class, so entries never expire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CasmCacheMode {
+    Disabled,
+    /// Only the compiled class hash is cached, which is all declarations need.
+    HashOnly,
+    /// The full CASM class is also kept next to the hash.
+    Full,
 }
 
 #[derive(Debug)]
 pub struct CompilerBinary {
     path: PathBuf,
+    allowed_libfuncs: Option<AllowedLibfuncs>,
+}
+
+/// The list of libfuncs a Sierra class is allowed to use. Networks only accept classes using
+/// libfuncs from their allowed list.
+#[derive(Debug, Clone)]
+pub enum AllowedLibfuncs {
+    Audited,
+    Experimental,
+    All,
+    File(PathBuf),
 }
 
 // TODO: separate known compiler versions with linked versions
@@ -38,11 +70,57 @@ pub enum CompilerVersion {
 }
 
 impl BuiltInCompiler {
+    pub fn with_allowed_libfuncs(mut self, allowed_libfuncs: Option<AllowedLibfuncs>) -> Self {
+        self.allowed_libfuncs = allowed_libfuncs;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: CasmCacheMode) -> Self {
+        self.cache = cache;
+        self
+    }
+
     pub fn version(&self) -> CompilerVersion {
         self.version
     }
 
     pub fn compile(&self, class: &SierraClass) -> Result<FieldElement> {
+        self.validate_compatible_sierra_version(class)?;
+
+        // Libfuncs are validated during compilation, which a cached result would skip
+        let cache_key = if self.cache != CasmCacheMode::Disabled && self.allowed_libfuncs.is_none()
+        {
+            let class_hash = class.class_hash()?;
+            let compiler = format!("builtin-{}", self.version);
+            if let Some(casm_class_hash) = read_cached_casm_hash(class_hash, &compiler) {
+                return Ok(casm_class_hash);
+            }
+            Some((class_hash, compiler))
+        } else {
+            None
+        };
+
+        let casm_class_json = self.compile_to_json(class)?;
+
+        // TODO: directly convert type without going through JSON
+        let casm_class_hash = compiled_class_hash(&casm_class_json)?;
+
+        if let Some((class_hash, compiler)) = cache_key {
+            let casm_class_json = match self.cache {
+                CasmCacheMode::Full => Some(casm_class_json.as_str()),
+                _ => None,
+            };
+            if let Err(err) =
+                write_cached_casm(class_hash, &compiler, casm_class_hash, casm_class_json)
+            {
+                log::debug!("unable to write to CASM cache: {}", err);
+            }
+        }
+
+        Ok(casm_class_hash)
+    }
+
+    fn compile_to_json(&self, class: &SierraClass) -> Result<String> {
         // We do this because the Sierra doesn't need ABI anyways. Feeding it with the ABI could
         // actually cause unnecessary deserialization errors due to ABI structure changes between
         // compiler versions.
@@ -56,7 +134,12 @@ impl BuiltInCompiler {
                 // TODO: directly convert type without going through JSON
                 let contract_class: Cairo210Class = serde_json::from_str(&sierra_class_json)?;
 
-                // TODO: implement the `validate_compatible_sierra_version` call
+                if let Some(allowed_libfuncs) = &self.allowed_libfuncs {
+                    cairo_210_validate_libfuncs(
+                        &contract_class,
+                        allowed_libfuncs.to_cairo_210_selector(),
+                    )?;
+                }
 
                 let casm_contract = Cairo210CasmClass::from_contract_class(contract_class, false)?;
 
@@ -66,7 +149,12 @@ impl BuiltInCompiler {
                 // TODO: directly convert type without going through JSON
                 let contract_class: Cairo240Class = serde_json::from_str(&sierra_class_json)?;
 
-                // TODO: implement the `validate_compatible_sierra_version` call
+                if let Some(allowed_libfuncs) = &self.allowed_libfuncs {
+                    cairo_240_validate_libfuncs(
+                        &contract_class,
+                        allowed_libfuncs.to_cairo_240_selector(),
+                    )?;
+                }
 
                 let casm_contract = Cairo240CasmClass::from_contract_class(contract_class, false)?;
 
@@ -74,56 +162,333 @@ impl BuiltInCompiler {
             }
         };
 
-        // TODO: directly convert type without going through JSON
-        let casm_class = serde_json::from_str::<CompiledClass>(&casm_class_json)?;
+        Ok(casm_class_json)
+    }
 
-        let casm_class_hash = casm_class.class_hash()?;
+    /// Checks the Sierra version embedded in the class against the range supported by the
+    /// compiler, as failures from the compiler itself in this case are rather cryptic.
+    fn validate_compatible_sierra_version(&self, class: &SierraClass) -> Result<()> {
+        let class_version = SierraVersion::from_class(class)?;
+        let supported_version = self.version.max_sierra_version();
+
+        if !supported_version.supports(&class_version) {
+            let suggestion = match CompilerVersion::value_variants()
+                .iter()
+                .find(|version| version.max_sierra_version().supports(&class_version))
+            {
+                Some(version) => format!("Use --compiler-version {} instead.", version),
+                None => "No built-in compiler version supports it. Try --compiler-path with a \
+                    newer starknet-sierra-compile binary."
+                    .to_owned(),
+            };
 
-        Ok(casm_class_hash)
+            anyhow::bail!(
+                "Sierra version {} of the class is not supported by compiler version {}, which \
+                supports up to {}. {}",
+                class_version,
+                self.version,
+                supported_version,
+                suggestion
+            );
+        }
+
+        Ok(())
     }
 }
 
 impl CompilerBinary {
+    pub fn with_allowed_libfuncs(mut self, allowed_libfuncs: Option<AllowedLibfuncs>) -> Self {
+        self.allowed_libfuncs = allowed_libfuncs;
+        self
+    }
+
     pub fn path(&self) -> &Path {
         &self.path
     }
 
+    /// Runs the binary with `--version` to make sure it's `starknet-sierra-compile`, returning the
+    /// version it reports.
+    pub fn probe_version(&self) -> Result<SierraVersion> {
+        let output = Command::new(&self.path)
+            .arg("--version")
+            .output()
+            .map_err(|err| {
+                anyhow::anyhow!("unable to run compiler {}: {}", self.path.display(), err)
+            })?;
+        let output = String::from_utf8_lossy(&output.stdout);
+        let output = output.trim();
+
+        // The output looks like `starknet-sierra-compile 2.4.0`
+        let version = match output.split_once(' ') {
+            Some((name, version)) if name == "starknet-sierra-compile" => version,
+            _ => anyhow::bail!(
+                "{} is not a starknet-sierra-compile binary (`--version` printed: {})",
+                self.path.display(),
+                output
+            ),
+        };
+
+        SierraVersion::parse(version).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unable to parse the version of {}: {}",
+                self.path.display(),
+                version
+            )
+        })
+    }
+
     pub fn compile(&self, class: &SierraClass) -> Result<FieldElement> {
+        let compiler_version = self.probe_version()?;
+
+        let class_version = SierraVersion::from_class(class)?;
+        if let Some(min_version) = class_version.min_compiler_version() {
+            if compiler_version < min_version {
+                anyhow::bail!(
+                    "Sierra version {} of the class requires starknet-sierra-compile {} or \
+                    newer, but {} is version {}",
+                    class_version,
+                    min_version,
+                    self.path.display(),
+                    compiler_version
+                );
+            }
+        }
+
         // We do this because the Sierra doesn't need ABI anyways. Feeding it with the ABI could
         // actually cause unnecessary deserialization errors due to ABI structure changes between
         // compiler versions.
         let mut class = class.clone();
         class.abi.clear();
 
-        let mut input_file = tempfile::NamedTempFile::new()?;
-        serde_json::to_writer(&mut input_file, &class)?;
+        // Files are passed by path instead of an open temp file handle, which other processes
+        // can't open on Windows. Having the compiler write to a file instead of stdout also avoids
+        // pipe issues with large classes.
+        let temp_dir = tempfile::tempdir()?;
+        let input_path = temp_dir.path().join("class.sierra.json");
+        let output_path = temp_dir.path().join("class.casm.json");
+        {
+            let mut input_file = BufWriter::new(std::fs::File::create(&input_path)?);
+            serde_json::to_writer(&mut input_file, &class)?;
+            input_file.flush()?;
+        }
 
-        let process_output = Command::new(&self.path)
-            .arg(
-                input_file
-                    .path()
-                    .to_str()
-                    .ok_or_else(|| anyhow::anyhow!("invalid temp file path"))?,
-            )
-            .output()?;
+        let mut command = Command::new(&self.path);
+        command
+            .arg(&input_path)
+            .arg(&output_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null());
+        match &self.allowed_libfuncs {
+            Some(AllowedLibfuncs::File(path)) => {
+                command.arg("--allowed-libfuncs-list-file").arg(path);
+            }
+            Some(allowed_libfuncs) => {
+                command
+                    .arg("--allowed-libfuncs-list-name")
+                    .arg(allowed_libfuncs.to_string());
+            }
+            // The binary validates against its audited list by default, while the built-in
+            // compilers don't validate unless asked to. The `all` list only exists since 2.0.0.
+            None if compiler_version.major >= 2 => {
+                command.arg("--allowed-libfuncs-list-name").arg("all");
+            }
+            None => {}
+        }
+
+        let process_output = command.output().map_err(|err| {
+            anyhow::anyhow!("unable to run compiler {}: {}", self.path.display(), err)
+        })?;
+        let diagnostics = String::from_utf8_lossy(&process_output.stderr);
 
         if !process_output.status.success() {
             anyhow::bail!(
-                "Sierra compiler process failed with exit code: {}",
-                process_output.status
+                "Sierra compiler process failed with exit code: {}\n{}",
+                process_output.status,
+                diagnostics.trim()
             );
         }
 
-        let casm_class_json = String::from_utf8(process_output.stdout)?;
-
-        let casm_class = serde_json::from_str::<CompiledClass>(&casm_class_json)?;
+        let casm_class_json = std::fs::read_to_string(&output_path).map_err(|err| {
+            anyhow::anyhow!(
+                "Sierra compiler did not produce a CASM file: {}\n{}",
+                err,
+                diagnostics.trim()
+            )
+        })?;
 
-        let casm_class_hash = casm_class.class_hash()?;
+        // Newer compilers segment the bytecode, which changes how the hash is computed
+        let casm_class_hash = compiled_class_hash(&casm_class_json)?;
 
         Ok(casm_class_hash)
     }
 }
 
+fn casm_cache_folder(class_hash: FieldElement) -> Result<PathBuf> {
+    let mut path = Profiles::get_cache_folder()?;
+    path.push("casm");
+    path.push(format!("{:#064x}", class_hash));
+    Ok(path)
+}
+
+fn read_cached_casm_hash(class_hash: FieldElement, compiler: &str) -> Option<FieldElement> {
+    let mut path = casm_cache_folder(class_hash).ok()?;
+    path.push(format!("{}.hash", compiler));
+
+    let content = std::fs::read_to_string(path).ok()?;
+    FieldElement::from_hex_be(content.trim()).ok()
+}
+
+fn write_cached_casm(
+    class_hash: FieldElement,
+    compiler: &str,
+    casm_class_hash: FieldElement,
+    casm_class_json: Option<&str>,
+) -> Result<()> {
+    let folder = casm_cache_folder(class_hash)?;
+    std::fs::create_dir_all(&folder)?;
+
+    std::fs::write(
+        folder.join(format!("{}.hash", compiler)),
+        format!("{:#064x}", casm_class_hash),
+    )?;
+
+    if let Some(casm_class_json) = casm_class_json {
+        std::fs::write(folder.join(format!("{}.json", compiler)), casm_class_json)?;
+    }
+
+    Ok(())
+}
+
+/// The Sierra version encoded in the first 3 felts of a Sierra program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SierraVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl SierraVersion {
+    const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn from_class(class: &SierraClass) -> Result<Self> {
+        Self::from_felts(class, 0)
+    }
+
+    /// The version of the Cairo compiler that produced the class, encoded right after the Sierra
+    /// version.
+    pub fn compiler_from_class(class: &SierraClass) -> Result<Self> {
+        Self::from_felts(class, 3)
+    }
+
+    fn from_felts(class: &SierraClass, start: usize) -> Result<Self> {
+        let felt_to_u64 = |index: usize| -> Result<u64> {
+            let felt = class
+                .sierra_program
+                .get(index)
+                .ok_or_else(|| anyhow::anyhow!("Sierra program too short to contain version"))?;
+            u64::try_from(*felt).map_err(|_| anyhow::anyhow!("invalid Sierra version: {}", felt))
+        };
+
+        Ok(Self::new(
+            felt_to_u64(start)?,
+            felt_to_u64(start + 1)?,
+            felt_to_u64(start + 2)?,
+        ))
+    }
+
+    /// Parses a version like `2.4.0`, ignoring pre-release suffixes like `-rc0`.
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim().trim_start_matches('v');
+        let version = version.split(['-', '+']).next()?;
+
+        let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+        let major = parts.next()??;
+        let minor = parts.next().unwrap_or(Some(0))?;
+        let patch = parts.next().unwrap_or(Some(0))?;
+
+        Some(Self::new(major, minor, patch))
+    }
+
+    /// The oldest compiler release able to compile classes of this Sierra version. Versions
+    /// newer than the ones known are not checked.
+    fn min_compiler_version(&self) -> Option<Self> {
+        match (self.major, self.minor) {
+            (1, 0) => Some(Self::new(1, 0, 0)),
+            (1, 1) => Some(Self::new(1, 1, 0)),
+            (1, 2) => Some(Self::new(2, 0, 0)),
+            (1, 3) => Some(Self::new(2, 1, 0)),
+            (1, 4) => Some(Self::new(2, 4, 0)),
+            (1, 5) => Some(Self::new(2, 6, 0)),
+            _ => None,
+        }
+    }
+
+    fn supports(&self, other: &Self) -> bool {
+        self.major == other.major && self.minor >= other.minor
+    }
+}
+
+impl Display for SierraVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl CompilerVersion {
+    pub fn max_sierra_version(&self) -> SierraVersion {
+        match self {
+            Self::V2_1_0 => SierraVersion::new(1, 3, 0),
+            Self::V2_4_0 => SierraVersion::new(1, 4, 0),
+        }
+    }
+}
+
+impl AllowedLibfuncs {
+    fn to_cairo_210_selector(&self) -> Cairo210ListSelector {
+        match self {
+            Self::File(path) => Cairo210ListSelector::ListFile(path.display().to_string()),
+            _ => Cairo210ListSelector::ListName(self.to_string()),
+        }
+    }
+
+    fn to_cairo_240_selector(&self) -> Cairo240ListSelector {
+        match self {
+            Self::File(path) => Cairo240ListSelector::ListFile(path.display().to_string()),
+            _ => Cairo240ListSelector::ListName(self.to_string()),
+        }
+    }
+}
+
+impl FromStr for AllowedLibfuncs {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "audited" => Self::Audited,
+            "experimental" => Self::Experimental,
+            "all" => Self::All,
+            _ => Self::File(PathBuf::from(s)),
+        })
+    }
+}
+
+impl Display for AllowedLibfuncs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Audited => write!(f, "audited"),
+            Self::Experimental => write!(f, "experimental"),
+            Self::All => write!(f, "all"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
 impl Default for CompilerVersion {
     fn default() -> Self {
         Self::V2_1_0
@@ -166,12 +531,19 @@ impl Display for CompilerVersion {
 
 impl From<CompilerVersion> for BuiltInCompiler {
     fn from(value: CompilerVersion) -> Self {
-        Self { version: value }
+        Self {
+            version: value,
+            allowed_libfuncs: None,
+            cache: CasmCacheMode::HashOnly,
+        }
     }
 }
 
 impl From<PathBuf> for CompilerBinary {
     fn from(value: PathBuf) -> Self {
-        Self { path: value }
+        Self {
+            path: value,
+            allowed_libfuncs: None,
+        }
     }
 }
@@ -1,11 +1,22 @@
 use anyhow::Result;
+use futures_util::future::{try_join3, try_join_all};
 use num_bigint::BigUint;
-use starknet::core::{
-    types::FieldElement,
-    utils::{cairo_short_string_to_felt, get_selector_from_name, get_storage_var_address},
+use starknet::{
+    accounts::Call,
+    core::{
+        types::FieldElement,
+        utils::{cairo_short_string_to_felt, get_selector_from_name},
+    },
 };
 
-use crate::{address_book::AddressBookResolver, chain_id::ChainIdSource};
+use crate::{
+    address_book::AddressBookResolver,
+    chain_id::ChainIdSource,
+    utils::{
+        format_address, is_valid_address_checksum, parse_eth_address, storage_path_to_address,
+    },
+    warning::Warning,
+};
 
 pub struct FeltDecoder<S> {
     address_book_resolver: AddressBookResolver<S>,
@@ -31,10 +42,93 @@ impl<S> FeltDecoder<S>
 where
     S: ChainIdSource,
 {
+    /// Parses calls in the multicall syntax, where each call is a contract address and a selector
+    /// followed by its arguments, with calls separated by `/`.
+    pub async fn decode_calls(&self, args: Vec<String>) -> Result<Vec<Call>> {
+        // TODO: show more helpful message
+        let unexpected_end_of_args = || anyhow::anyhow!("unexpected end of arguments");
+
+        // Calls are split up front so that all of them can be resolved at once
+        let mut raw_calls = vec![];
+
+        let mut arg_iter = args.into_iter();
+
+        while let Some(first_arg) = arg_iter.next() {
+            let next_arg = arg_iter.next().ok_or_else(unexpected_end_of_args)?;
+
+            let mut calldata = vec![];
+            for arg in &mut arg_iter {
+                match arg.as_str() {
+                    "/" | "-" | "\\" => break,
+                    _ => calldata.push(arg),
+                }
+            }
+
+            raw_calls.push((first_arg, next_arg, calldata));
+        }
+
+        try_join_all(
+            raw_calls
+                .iter()
+                .map(|(contract_address, selector, calldata)| async move {
+                    let (to, selector, calldata) = self
+                        .decode_call(contract_address, selector, calldata)
+                        .await?;
+                    Ok::<_, anyhow::Error>(Call {
+                        to,
+                        selector,
+                        calldata,
+                    })
+                }),
+        )
+        .await
+    }
+
+    /// Resolves the contract address, selector and calldata of a single call concurrently, so that
+    /// a command with many names to look up doesn't wait on each of them in turn.
+    pub async fn decode_call(
+        &self,
+        contract_address: &str,
+        selector: &str,
+        calldata: &[String],
+    ) -> Result<(FieldElement, FieldElement, Vec<FieldElement>)> {
+        try_join3(
+            self.decode_single_with_addr_fallback(contract_address),
+            self.decode_single_with_selector_fallback(selector),
+            self.decode_all(calldata),
+        )
+        .await
+    }
+
+    /// Decodes each argument concurrently, concatenating the results in order.
+    pub async fn decode_all(&self, raw: &[String]) -> Result<Vec<FieldElement>> {
+        Ok(try_join_all(raw.iter().map(|arg| self.decode(arg)))
+            .await?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
     pub async fn decode_single_with_addr_fallback(&self, raw: &str) -> Result<FieldElement> {
         let decoded = self.decode_inner(raw, FallbackOption::Address).await?;
 
         if decoded.len() == 1 {
+            if !is_valid_address_checksum(raw, decoded[0]) {
+                Warning::AddressChecksum.print(format!(
+                    "address {} fails its checksum, which may indicate a copy-paste error. The \
+                    checksummed form is {}.",
+                    raw,
+                    format_address(decoded[0])
+                ));
+            }
+
+            // Only literal addresses are checked, as names already resolve per network
+            if raw.parse::<FieldElement>().is_ok() {
+                self.address_book_resolver
+                    .check_address_network(decoded[0])
+                    .await;
+            }
+
             Ok(decoded[0])
         } else {
             Err(anyhow::anyhow!(
@@ -79,7 +173,21 @@ where
         raw: &str,
         fallback_option: FallbackOption,
     ) -> Result<Vec<FieldElement>> {
-        if let Some(addr_name) = raw.strip_prefix("addr:") {
+        let mut decoded = vec![];
+        for expanded in expand_arg(raw)?.iter() {
+            decoded.append(&mut self.decode_expanded(expanded, fallback_option).await?);
+        }
+        Ok(decoded)
+    }
+
+    async fn decode_expanded(
+        &self,
+        raw: &str,
+        fallback_option: FallbackOption,
+    ) -> Result<Vec<FieldElement>> {
+        if let Some(addr_name) = raw.strip_prefix('@') {
+            Ok(vec![self.resolve_addr(addr_name).await?])
+        } else if let Some(addr_name) = raw.strip_prefix("addr:") {
             Ok(vec![self.resolve_addr(addr_name).await?])
         } else if let Some(u256_str) = raw.strip_prefix("u256:") {
             let bigint = if let Some(hex_str) = u256_str.strip_prefix("0x") {
@@ -140,28 +248,25 @@ where
             }
         } else if let Some(short_string) = raw.strip_prefix("str:") {
             Ok(vec![cairo_short_string_to_felt(short_string)?])
+        } else if let Some(eth_address) = raw.strip_prefix("eth:") {
+            Ok(vec![parse_eth_address(eth_address)?])
         } else if let Some(selector) = raw.strip_prefix("selector:") {
             Ok(vec![get_selector_from_name(selector)?])
         } else if let Some(storage) = raw.strip_prefix("storage:") {
-            if storage.contains('[') || storage.contains(']') {
-                anyhow::bail!("cannot resolve storage address: maps not supported yet")
-            }
-            Ok(vec![get_storage_var_address(storage, &[])?])
+            Ok(vec![storage_path_to_address(storage)?])
         } else {
             match raw.parse::<FieldElement>() {
                 Ok(value) => Ok(vec![value]),
                 Err(err) => match fallback_option {
                     FallbackOption::Address => match self.resolve_addr(raw).await {
                         Ok(value) => Ok(vec![value]),
+                        // Namespaced names can't be felts, so their resolution errors are the
+                        // more useful ones, e.g. for a name only defined on another network
+                        Err(resolve_err) if raw.contains(':') => Err(resolve_err),
                         Err(_) => Err(err.into()),
                     },
                     FallbackOption::Selector => Ok(vec![get_selector_from_name(raw)?]),
-                    FallbackOption::Storage => {
-                        if raw.contains('[') || raw.contains(']') {
-                            anyhow::bail!("cannot resolve storage address: maps not supported yet")
-                        }
-                        Ok(vec![get_storage_var_address(raw, &[])?])
-                    }
+                    FallbackOption::Storage => Ok(vec![storage_path_to_address(raw)?]),
                     FallbackOption::None => Err(err.into()),
                 },
             }
@@ -175,3 +280,38 @@ where
             .ok_or_else(|| anyhow::anyhow!("address book entry not found for \"{}\"", name))
     }
 }
+
+/// Replaces `$ENV_VAR` (or `${ENV_VAR}`) with the variable's value, and `file:<path>` with the
+/// values listed in the file, so that scripts can be shared without hard-coded values. Expanded
+/// values are decoded like any other argument, but are not expanded again.
+fn expand_arg(raw: &str) -> Result<Vec<String>> {
+    if let Some(path) = raw.strip_prefix("file:") {
+        let path = shellexpand::tilde(path);
+        let content = std::fs::read_to_string(path.as_ref())
+            .map_err(|err| anyhow::anyhow!("unable to read {}: {}", path, err))?;
+
+        // Values are separated by whitespace or new lines, and `#` starts a comment
+        Ok(content
+            .lines()
+            .flat_map(|line| {
+                line.split('#')
+                    .next()
+                    .unwrap_or_default()
+                    .split_whitespace()
+            })
+            .map(|value| value.to_owned())
+            .collect())
+    } else if let Some(name) = raw.strip_prefix('$') {
+        let name = name
+            .strip_prefix('{')
+            .and_then(|name| name.strip_suffix('}'))
+            .unwrap_or(name);
+
+        match std::env::var(name) {
+            Ok(value) => Ok(vec![value.trim().to_owned()]),
+            Err(_) => Err(anyhow::anyhow!("environment variable {} is not set", name)),
+        }
+    } else {
+        Ok(vec![raw.to_owned()])
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::OnceLock;
+
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{BlockId, BlockTag};
+
+use crate::profile::{Profiles, DEFAULT_PROFILE_NAME};
+
+static DEFAULT_BLOCK: OnceLock<DefaultBlock> = OnceLock::new();
+
+/// The block that reads without an explicit block go to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultBlock {
+    /// The block being built, including transactions not yet included in a block
+    #[default]
+    Pending,
+    /// The most recent block, without pending transactions
+    Latest,
+}
+
+#[derive(Debug, Clone, Default, Parser)]
+pub struct DefaultBlockOptions {
+    #[clap(
+        long,
+        global = true,
+        env = "STARKLI_DEFAULT_BLOCK",
+        value_enum,
+        help = "Block to read state from when commands don't take one explicitly, for providers \
+                with unreliable pending state. Defaults to `pending`"
+    )]
+    default_block: Option<DefaultBlock>,
+}
+
+impl DefaultBlockOptions {
+    /// Sets the default block for the rest of the process, from the flag or from `default_block`
+    /// in the `default` profile.
+    pub fn apply(self) {
+        // A broken profiles file is reported by the commands actually using it
+        let from_profile = || {
+            Profiles::load().ok().and_then(|profiles| {
+                profiles
+                    .profiles
+                    .get(DEFAULT_PROFILE_NAME)
+                    .and_then(|profile| profile.default_block)
+            })
+        };
+
+        let _ = DEFAULT_BLOCK.set(self.default_block.or_else(from_profile).unwrap_or_default());
+    }
+}
+
+/// The block ID for reads that don't specify a block.
+pub fn default_block_id() -> BlockId {
+    match DEFAULT_BLOCK.get_or_init(Default::default) {
+        DefaultBlock::Pending => BlockId::Tag(BlockTag::Pending),
+        DefaultBlock::Latest => BlockId::Tag(BlockTag::Latest),
+    }
+}
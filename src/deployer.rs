@@ -0,0 +1,118 @@
+use clap::{Parser, ValueEnum};
+use starknet::{
+    accounts::Call,
+    core::{crypto::pedersen_hash, types::FieldElement, utils::get_contract_address},
+    macros::selector,
+};
+
+use crate::provider::ExtendedProvider;
+
+#[derive(Debug, Clone, Parser)]
+pub struct DeployerArgs {
+    #[clap(
+        long,
+        help = "Address of the deployer contract to use instead of the Universal Deployer Contract \
+                configured for the network"
+    )]
+    deployer_address: Option<FieldElement>,
+    #[clap(
+        long,
+        value_enum,
+        default_value = "udc",
+        help = "Interface of the deployer contract, which determines how the deployed address is \
+                computed"
+    )]
+    deploy_syscall_style: DeploySyscallStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DeploySyscallStyle {
+    /// `deployContract(class_hash, salt, unique, calldata)` as implemented by the UDC. Unique
+    /// deployments mix the sender address into the salt.
+    Udc,
+    /// `deploy_contract(class_hash, salt, calldata)` on a factory calling `deploy_syscall` with
+    /// `deploy_from_zero` set to false, so the factory address is the deployer.
+    Factory,
+    /// Same as `factory`, but with `deploy_from_zero` set to true, so the address does not depend
+    /// on the factory.
+    FactoryFromZero,
+}
+
+/// Builds deployment calls through a deployer contract.
+#[derive(Debug, Clone, Copy)]
+pub struct Deployer {
+    address: FieldElement,
+    style: DeploySyscallStyle,
+}
+
+impl DeployerArgs {
+    pub fn into_deployer(self, provider: &ExtendedProvider) -> Deployer {
+        Deployer {
+            address: self
+                .deployer_address
+                .unwrap_or_else(|| provider.udc_address()),
+            style: self.deploy_syscall_style,
+        }
+    }
+}
+
+impl Deployer {
+    pub fn address(&self) -> FieldElement {
+        self.address
+    }
+
+    /// Returns the call to send from `sender` for the deployment, along with the address the
+    /// contract will be deployed at. `unique` only applies to the `udc` style.
+    pub fn deployment_call(
+        &self,
+        sender: FieldElement,
+        class_hash: FieldElement,
+        salt: FieldElement,
+        unique: bool,
+        ctor_args: &[FieldElement],
+    ) -> (Call, FieldElement) {
+        let (selector, mut calldata) = match self.style {
+            DeploySyscallStyle::Udc => (
+                selector!("deployContract"),
+                vec![
+                    class_hash,
+                    salt,
+                    if unique {
+                        FieldElement::ONE
+                    } else {
+                        FieldElement::ZERO
+                    },
+                ],
+            ),
+            DeploySyscallStyle::Factory | DeploySyscallStyle::FactoryFromZero => {
+                (selector!("deploy_contract"), vec![class_hash, salt])
+            }
+        };
+        calldata.push(FieldElement::from(ctor_args.len()));
+        calldata.extend_from_slice(ctor_args);
+
+        let deployed_address = match self.style {
+            DeploySyscallStyle::Udc if unique => get_contract_address(
+                pedersen_hash(&sender, &salt),
+                class_hash,
+                ctor_args,
+                self.address,
+            ),
+            DeploySyscallStyle::Udc | DeploySyscallStyle::FactoryFromZero => {
+                get_contract_address(salt, class_hash, ctor_args, FieldElement::ZERO)
+            }
+            DeploySyscallStyle::Factory => {
+                get_contract_address(salt, class_hash, ctor_args, self.address)
+            }
+        };
+
+        (
+            Call {
+                to: self.address,
+                selector,
+                calldata,
+            },
+            deployed_address,
+        )
+    }
+}
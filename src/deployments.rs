@@ -0,0 +1,103 @@
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use starknet::core::{types::FieldElement, utils::parse_cairo_short_string};
+
+use crate::profile::Profiles;
+
+/// Contracts deployed from manifests, stored in `deployments.toml` under the config folder so
+/// that deployed addresses can be looked up by label later on.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeploymentRegistry {
+    #[serde(default)]
+    deployments: Vec<DeploymentRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeploymentRecord {
+    pub chain_id: String,
+    pub label: String,
+    pub class_hash: FieldElement,
+    pub address: FieldElement,
+    pub salt: FieldElement,
+    pub transaction_hash: FieldElement,
+    /// Unix timestamp of when the deployment transaction was sent.
+    pub sent_at: u64,
+}
+
+impl DeploymentRegistry {
+    pub fn load() -> Result<Self> {
+        let path = Self::get_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let mut file = std::fs::File::open(&path)?;
+        let mut buffer = String::new();
+        file.read_to_string(&mut buffer)?;
+
+        toml::from_str(&buffer).map_err(|err| {
+            anyhow::anyhow!("invalid deployment registry {}: {}", path.display(), err)
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let serialized = toml::to_string_pretty(self)?;
+
+        let config_folder = Profiles::get_config_folder()?;
+        if !config_folder.exists() {
+            std::fs::create_dir_all(config_folder)?;
+        }
+
+        let mut file = std::fs::File::create(Self::get_path()?)?;
+        file.write_all(serialized.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Adds a record, replacing any previous deployment with the same label on the same network.
+    pub fn record(&mut self, record: DeploymentRecord) {
+        self.deployments.retain(|existing| {
+            existing.chain_id != record.chain_id || existing.label != record.label
+        });
+        self.deployments.push(record);
+    }
+
+    pub fn get_path() -> Result<PathBuf> {
+        let mut path = Profiles::get_config_folder()?;
+        path.push("deployments.toml");
+        Ok(path)
+    }
+}
+
+impl DeploymentRecord {
+    pub fn new(
+        chain_id: FieldElement,
+        label: String,
+        class_hash: FieldElement,
+        address: FieldElement,
+        salt: FieldElement,
+        transaction_hash: FieldElement,
+    ) -> Self {
+        Self {
+            chain_id: parse_cairo_short_string(&chain_id)
+                .unwrap_or_else(|_| format!("{:#x}", chain_id)),
+            label,
+            class_hash,
+            address,
+            salt,
+            transaction_hash,
+            sent_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use serde_json::Value;
+use starknet::core::types::FieldElement;
+
+use crate::{http, provider::ExtendedProvider};
+
+/// Sends a devnet-specific JSON-RPC request, which the `Provider` trait doesn't cover.
+pub async fn devnet_request(
+    provider: &ExtendedProvider,
+    method: &str,
+    params: Value,
+) -> Result<Value> {
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+
+    let response = http::shared_client()
+        .post(provider.rpc_url().clone())
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(&request)?)
+        .send()
+        .await?
+        .text()
+        .await?;
+    let mut response: Value = serde_json::from_str(&response)?;
+
+    if let Some(error) = response.get("error") {
+        match error.get("code").and_then(|code| code.as_i64()) {
+            // Method not found
+            Some(-32601) => anyhow::bail!(
+                "the node does not support `{}`. Make sure you're connected to starknet-devnet",
+                method
+            ),
+            _ => anyhow::bail!("devnet request `{}` failed: {}", method, error),
+        }
+    }
+
+    Ok(response
+        .get_mut("result")
+        .map(|result| result.take())
+        .unwrap_or_default())
+}
+
+/// Makes the devnet accept transactions from `address` without checking signatures. This only
+/// works on a devnet forked from another network.
+pub async fn impersonate_account(provider: &ExtendedProvider, address: FieldElement) -> Result<()> {
+    devnet_request(
+        provider,
+        "devnet_impersonateAccount",
+        serde_json::json!({
+            "account_address": format!("{:#064x}", address),
+        }),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use starknet::{
+    accounts::{AccountError, AccountFactoryError},
+    core::types::{BlockId, BlockTag, FieldElement, StarknetError},
+    providers::{Provider, ProviderError},
+};
+
+use crate::provider::ExtendedProvider;
+
+/// Turns provider errors into actionable messages. Errors without a known explanation are passed
+/// through unchanged.
+pub fn decode_provider_error(err: ProviderError) -> anyhow::Error {
+    match &err {
+        ProviderError::StarknetError(starknet_err) => match explain_starknet_error(starknet_err) {
+            Some(message) => anyhow::anyhow!("{}", message),
+            None => err.into(),
+        },
+        ProviderError::RateLimited => anyhow::anyhow!(
+            "the JSON-RPC endpoint is rate limiting requests. Wait a moment before trying again, \
+            or use a different provider with --rpc."
+        ),
+        _ => err.into(),
+    }
+}
+
+pub fn decode_account_error<S>(err: AccountError<S>) -> anyhow::Error
+where
+    S: std::error::Error + Send + Sync + 'static,
+{
+    match err {
+        AccountError::Provider(err) => decode_provider_error(err),
+        err => err.into(),
+    }
+}
+
+pub fn decode_account_factory_error<S>(err: AccountFactoryError<S>) -> anyhow::Error
+where
+    S: std::error::Error + Send + Sync + 'static,
+{
+    match err {
+        AccountFactoryError::Provider(err) => decode_provider_error(err),
+        err => err.into(),
+    }
+}
+
+/// Same as `decode_account_error`, but also shows the nonce expected by the network when the
+/// transaction is rejected for having an invalid nonce.
+pub async fn decode_send_error<S>(
+    provider: &ExtendedProvider,
+    sender: FieldElement,
+    err: AccountError<S>,
+) -> anyhow::Error
+where
+    S: std::error::Error + Send + Sync + 'static,
+{
+    if let AccountError::Provider(ProviderError::StarknetError(
+        StarknetError::InvalidTransactionNonce,
+    )) = &err
+    {
+        if let Ok(nonce) = provider
+            .get_nonce(BlockId::Tag(BlockTag::Pending), sender)
+            .await
+        {
+            return anyhow::anyhow!(
+                "invalid transaction nonce: the network expects nonce {} for account {:#064x}. \
+                Use --nonce {} or omit --nonce to have it fetched automatically.",
+                nonce,
+                sender,
+                nonce
+            );
+        }
+    }
+
+    decode_account_error(err)
+}
+
+fn explain_starknet_error(err: &StarknetError) -> Option<String> {
+    Some(match err {
+        StarknetError::InsufficientAccountBalance => {
+            "the account balance is too low to cover the max fee of the transaction. Fund the \
+            account, or lower the max fee with --max-fee."
+                .into()
+        }
+        StarknetError::InsufficientMaxFee => {
+            "the max fee is lower than the minimum fee required by the network. Raise it with \
+            --max-fee, or omit --max-fee to use fee estimation."
+                .into()
+        }
+        StarknetError::InvalidTransactionNonce => {
+            "invalid transaction nonce. Use `starkli nonce` to check the current nonce of the \
+            account."
+                .into()
+        }
+        StarknetError::ClassAlreadyDeclared => {
+            "the class has already been declared on this network, so there's no need to declare \
+            it again."
+                .into()
+        }
+        StarknetError::CompiledClassHashMismatch => {
+            "the network rejected the CASM hash of the class. It was most likely compiled with a \
+            Sierra compiler version different from the one the network uses. Use \
+            --compiler-version to pick a different version."
+                .into()
+        }
+        StarknetError::ValidationFailure(reason) => format!(
+            "account validation failed: {}. Check that the signer matches the one set up in the \
+            account contract.",
+            reason
+        ),
+        StarknetError::ContractNotFound => {
+            "contract not found. Check that the address is correct and that the contract has been \
+            deployed on this network."
+                .into()
+        }
+        StarknetError::ClassHashNotFound => {
+            "class hash not found. The class must be declared on this network first.".into()
+        }
+        StarknetError::TransactionHashNotFound => {
+            "transaction not found. It might not have been received by this node yet.".into()
+        }
+        StarknetError::BlockNotFound => "block not found.".into(),
+        StarknetError::NonAccount => "the sender address is not an account contract.".into(),
+        StarknetError::ContractError(data) => format!("contract error: {}", data.revert_error),
+        StarknetError::TransactionExecutionError(data) => {
+            format!("transaction execution failed: {}", data.execution_error)
+        }
+        StarknetError::UnsupportedTxVersion => {
+            "the node does not support this transaction version.".into()
+        }
+        StarknetError::UnsupportedContractClassVersion => {
+            "the network does not support the Sierra version of this class. Use \
+            --compiler-version to pick a different compiler version."
+                .into()
+        }
+        StarknetError::ContractClassSizeIsTooLarge => {
+            "the class exceeds the size limit of the network.".into()
+        }
+        StarknetError::CompilationFailed => {
+            "the network failed to compile the class into CASM.".into()
+        }
+        _ => return None,
+    })
+}
@@ -0,0 +1,408 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use serde_json::Value;
+use starknet::core::{
+    types::{BlockId, BlockTag, FieldElement},
+    utils::get_selector_from_name,
+};
+
+use crate::{
+    abi_cache::CachedAbi,
+    network::{Network, NetworkSource},
+    provider::ExtendedProvider,
+};
+
+/// Upper bound of result pages fetched from explorer APIs for a single query.
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Clone, Parser)]
+pub struct ExplorerArgs {
+    #[clap(
+        long,
+        env = "STARKLI_EXPLORER",
+        help = "Use an explorer API as an additional data source. The API key is read from \
+                VOYAGER_API_KEY or STARKSCAN_API_KEY"
+    )]
+    explorer: Option<Explorer>,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct ExplorerLinkArgs {
+    #[clap(
+        long,
+        env = "STARKLI_EXPLORER",
+        default_value = "voyager",
+        help = "Explorer used for the links printed after sending transactions"
+    )]
+    explorer: Explorer,
+    #[clap(long, help = "Do not print explorer links")]
+    quiet: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Explorer {
+    Voyager,
+    Starkscan,
+}
+
+/// A client for the explorer APIs. Responses are read leniently as these APIs are not versioned
+/// the same way JSON-RPC is, and any failure should only result in falling back to pure RPC.
+pub struct ExplorerClient {
+    explorer: Explorer,
+    base_url: &'static str,
+    api_key: String,
+    client: reqwest::Client,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExplorerLink {
+    Transaction(FieldElement),
+    Contract(FieldElement),
+    Class(FieldElement),
+}
+
+/// Function names resolved from contract ABIs, cached by contract address.
+pub struct FunctionNames<'a> {
+    provider: &'a ExtendedProvider,
+    explorer: Option<&'a ExplorerClient>,
+    cache: HashMap<FieldElement, HashMap<FieldElement, String>>,
+}
+
+impl ExplorerArgs {
+    /// Gets a client for the chosen explorer. Returns `None`, with a warning printed, when the
+    /// explorer cannot be used, in which case callers should degrade to pure RPC.
+    pub async fn into_client<N>(self, network_source: N) -> Result<Option<ExplorerClient>>
+    where
+        N: NetworkSource,
+    {
+        let explorer = match self.explorer {
+            Some(explorer) => explorer,
+            None => return Ok(None),
+        };
+
+        let api_key = match std::env::var(explorer.api_key_env()) {
+            Ok(api_key) if !api_key.is_empty() => api_key,
+            _ => {
+                print_fallback_warning(&format!(
+                    "{} is not set, so {} cannot be used",
+                    explorer.api_key_env(),
+                    explorer
+                ));
+                return Ok(None);
+            }
+        };
+
+        let network = network_source.get_network().await?;
+        let base_url = match network.and_then(|network| explorer.api_base_url(network)) {
+            Some(base_url) => base_url,
+            None => {
+                print_fallback_warning(&format!(
+                    "{} does not support the current network",
+                    explorer
+                ));
+                return Ok(None);
+            }
+        };
+
+        Ok(Some(ExplorerClient {
+            explorer,
+            base_url,
+            api_key,
+            client: reqwest::Client::new(),
+        }))
+    }
+}
+
+impl ExplorerLinkArgs {
+    /// Prints the explorer URL for a transaction, contract, or class. Nothing is printed for
+    /// networks without a known explorer.
+    pub async fn print_link<N>(&self, network_source: N, link: ExplorerLink)
+    where
+        N: NetworkSource,
+    {
+        if self.quiet {
+            return;
+        }
+
+        if let Ok(Some(network)) = network_source.get_network().await {
+            if let Some(url) = self.explorer.web_url(network, link) {
+                eprintln!("View on {}: {}", self.explorer, url.bright_cyan());
+            }
+        }
+    }
+}
+
+impl Explorer {
+    fn web_url(&self, network: Network, link: ExplorerLink) -> Option<String> {
+        let base_url = match (self, network) {
+            (Self::Voyager, Network::Mainnet) => "https://voyager.online",
+            (Self::Voyager, Network::Goerli) => "https://goerli.voyager.online",
+            (Self::Voyager, Network::Sepolia) => "https://sepolia.voyager.online",
+            (Self::Starkscan, Network::Mainnet) => "https://starkscan.co",
+            (Self::Starkscan, Network::Goerli) => "https://testnet.starkscan.co",
+            (Self::Starkscan, Network::Sepolia) => "https://sepolia.starkscan.co",
+            (_, Network::GoerliIntegration) | (_, Network::SepoliaIntegration) => return None,
+        };
+
+        Some(match link {
+            ExplorerLink::Transaction(hash) => format!("{}/tx/{:#064x}", base_url, hash),
+            ExplorerLink::Contract(address) => format!("{}/contract/{:#064x}", base_url, address),
+            ExplorerLink::Class(hash) => format!("{}/class/{:#064x}", base_url, hash),
+        })
+    }
+
+    fn api_key_env(&self) -> &'static str {
+        match self {
+            Self::Voyager => "VOYAGER_API_KEY",
+            Self::Starkscan => "STARKSCAN_API_KEY",
+        }
+    }
+
+    fn api_base_url(&self, network: Network) -> Option<&'static str> {
+        match (self, network) {
+            (Self::Voyager, Network::Mainnet) => Some("https://api.voyager.online/beta"),
+            (Self::Voyager, Network::Goerli) => Some("https://goerli-api.voyager.online/beta"),
+            (Self::Voyager, Network::Sepolia) => Some("https://sepolia-api.voyager.online/beta"),
+            (Self::Starkscan, Network::Mainnet) => Some("https://api.starkscan.co/api/v0"),
+            (Self::Starkscan, Network::Goerli) => Some("https://api-testnet.starkscan.co/api/v0"),
+            (Self::Starkscan, Network::Sepolia) => Some("https://api-sepolia.starkscan.co/api/v0"),
+            (_, Network::GoerliIntegration) | (_, Network::SepoliaIntegration) => None,
+        }
+    }
+}
+
+impl ExplorerClient {
+    pub fn explorer(&self) -> Explorer {
+        self.explorer
+    }
+
+    /// Hashes of transactions sent by an account, newest first.
+    pub async fn account_transactions(&self, address: FieldElement) -> Result<Vec<FieldElement>> {
+        let mut hashes = vec![];
+
+        match self.explorer {
+            Explorer::Voyager => {
+                for page in 1..=MAX_PAGES {
+                    let response = self
+                        .get(&format!(
+                            "{}/txns?to={:#064x}&ps=100&p={}",
+                            self.base_url, address, page
+                        ))
+                        .await?;
+
+                    if let Some(items) = response.get("items").and_then(|items| items.as_array()) {
+                        hashes.extend(items.iter().filter_map(|item| json_hex(item, "hash")));
+                    }
+
+                    let last_page = response
+                        .get("lastPage")
+                        .and_then(|value| value.as_u64())
+                        .unwrap_or_default();
+                    if page as u64 >= last_page {
+                        break;
+                    }
+                }
+            }
+            Explorer::Starkscan => {
+                let mut url = format!(
+                    "{}/transactions?contract_address={:#064x}&order_by=desc&limit=100",
+                    self.base_url, address
+                );
+                for _ in 0..MAX_PAGES {
+                    let response = self.get(&url).await?;
+
+                    if let Some(items) = response.get("data").and_then(|items| items.as_array()) {
+                        hashes.extend(
+                            items
+                                .iter()
+                                .filter_map(|item| json_hex(item, "transaction_hash")),
+                        );
+                    }
+
+                    match response.get("next_url").and_then(|value| value.as_str()) {
+                        Some(next_url) => url = next_url.to_owned(),
+                        None => break,
+                    }
+                }
+            }
+        }
+
+        Ok(hashes)
+    }
+
+    /// The ABI of a contract, if its source code has been verified on the explorer.
+    pub async fn verified_abi(&self, address: FieldElement) -> Result<Option<Value>> {
+        let url = match self.explorer {
+            Explorer::Voyager => format!("{}/contracts/{:#064x}", self.base_url, address),
+            Explorer::Starkscan => format!("{}/contract/{:#064x}", self.base_url, address),
+        };
+
+        let response = self.get(&url).await?;
+
+        Ok(match response.get("abi") {
+            Some(abi @ Value::Array(_)) => Some(abi.to_owned()),
+            // Some responses embed the ABI as a JSON string
+            Some(Value::String(abi)) => serde_json::from_str(abi).ok(),
+            _ => None,
+        })
+    }
+
+    async fn get(&self, url: &str) -> Result<Value> {
+        let response = self
+            .client
+            .get(url)
+            .header("x-api-key", &self.api_key)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "{} API request failed: {}",
+                self.explorer,
+                response.status()
+            );
+        }
+
+        Ok(serde_json::from_str(&response.text().await?)?)
+    }
+}
+
+impl<'a> FunctionNames<'a> {
+    pub fn new(provider: &'a ExtendedProvider, explorer: Option<&'a ExplorerClient>) -> Self {
+        Self {
+            provider,
+            explorer,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Resolves the deployed classes of several contracts ahead of `lookup`, with their class
+    /// hashes fetched in batches instead of one request each. Does nothing when an explorer is
+    /// used, as verified ABIs are looked up per contract anyway.
+    pub async fn prefetch(&mut self, contracts: &[FieldElement]) {
+        if self.explorer.is_some() {
+            return;
+        }
+
+        let mut seen = HashSet::new();
+        let contracts = contracts
+            .iter()
+            .filter(|contract| !self.cache.contains_key(contract) && seen.insert(**contract))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let class_hashes = self
+            .provider
+            .batch_class_hash_at(&contracts, BlockId::Tag(BlockTag::Pending))
+            .await;
+
+        for (contract, class_hash) in contracts.into_iter().zip(class_hashes.into_iter()) {
+            let mut names = HashMap::new();
+            if let Ok(class_hash) = class_hash {
+                if let Ok(abi) = CachedAbi::of_class(self.provider, class_hash).await {
+                    collect_function_names(&abi.abi, &mut names);
+                }
+            }
+            self.cache.insert(contract, names);
+        }
+    }
+
+    /// Reverse lookup of a selector against the ABI of the contract being called. The verified
+    /// ABI from the explorer is preferred when available, with the ABI of the deployed class as a
+    /// fallback.
+    pub async fn lookup(
+        &mut self,
+        contract: FieldElement,
+        selector: FieldElement,
+    ) -> Option<String> {
+        if let Some(names) = self.cache.get(&contract) {
+            return names.get(&selector).cloned();
+        }
+
+        let abi = match self.explorer {
+            Some(explorer) => explorer.verified_abi(contract).await.ok().flatten(),
+            None => None,
+        };
+        let abi = match abi {
+            Some(abi) => Some(abi),
+            None => self.class_abi(contract).await,
+        };
+
+        let mut names = HashMap::new();
+        if let Some(abi) = abi {
+            collect_function_names(&abi, &mut names);
+        }
+        let name = names.get(&selector).cloned();
+        self.cache.insert(contract, names);
+
+        name
+    }
+
+    async fn class_abi(&self, contract: FieldElement) -> Option<Value> {
+        let abi = CachedAbi::of_contract(self.provider, contract)
+            .await
+            .ok()?
+            .abi;
+        if abi.is_null() {
+            None
+        } else {
+            Some(abi)
+        }
+    }
+}
+
+impl std::fmt::Display for Explorer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Voyager => write!(f, "Voyager"),
+            Self::Starkscan => write!(f, "Starkscan"),
+        }
+    }
+}
+
+/// Walks both Sierra and legacy ABIs, including functions nested in Sierra interfaces.
+fn collect_function_names(abi: &Value, names: &mut HashMap<FieldElement, String>) {
+    match abi {
+        Value::Array(items) => {
+            for item in items.iter() {
+                collect_function_names(item, names);
+            }
+        }
+        Value::Object(item) => {
+            let item_type = item.get("type").and_then(|value| value.as_str());
+            let name = item.get("name").and_then(|value| value.as_str());
+
+            match (item_type, name) {
+                (Some("function") | Some("l1_handler"), Some(name)) => {
+                    if let Ok(selector) = get_selector_from_name(name) {
+                        names.insert(selector, name.to_owned());
+                    }
+                }
+                (Some("interface"), _) => {
+                    if let Some(items) = item.get("items") {
+                        collect_function_names(items, names);
+                    }
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn json_hex(value: &Value, key: &str) -> Option<FieldElement> {
+    value
+        .get(key)
+        .and_then(|value| value.as_str())
+        .and_then(|value| FieldElement::from_hex_be(value).ok())
+}
+
+fn print_fallback_warning(reason: &str) {
+    eprintln!(
+        "{}",
+        format!("WARNING: {}. Falling back to JSON-RPC only.", reason).bright_magenta()
+    );
+}
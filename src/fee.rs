@@ -1,9 +1,21 @@
 use anyhow::Result;
 use bigdecimal::BigDecimal;
 use clap::Parser;
-use starknet::{core::types::FieldElement, macros::felt};
+use colored::Colorize;
+use serde_json::Value;
+use starknet::{
+    accounts::{ConnectedAccount, Execution},
+    core::types::{BlockId, BlockTag, FeeEstimate, FieldElement},
+    macros::felt,
+    providers::Provider,
+};
 
-use crate::utils::bigdecimal_to_felt;
+use crate::{
+    amount::{format_ether, AmountFormat, AmountFormatArgs},
+    provider::ExtendedProvider,
+    utils::{bigdecimal_to_felt, print_colored_json},
+    warning::Warning,
+};
 
 #[derive(Debug, Clone, Parser)]
 pub struct FeeArgs {
@@ -16,35 +28,59 @@ pub struct FeeArgs {
         help = "Only estimate transaction fee without sending transaction"
     )]
     estimate_only: bool,
+    #[clap(
+        long,
+        requires = "estimate_only",
+        help = "Print the fee estimation as JSON with all fee components"
+    )]
+    json: bool,
+    #[clap(
+        long,
+        help = "Send the transaction even if the max fee exceeds the safety limits, including the \
+                network's `max_fee_ceiling`"
+    )]
+    force: bool,
+    #[clap(flatten)]
+    amounts: AmountFormatArgs,
 }
 
 #[derive(Debug)]
 pub enum FeeSetting {
     Manual(FieldElement),
-    EstimateOnly,
+    EstimateOnly {
+        json: bool,
+        amount_format: AmountFormat,
+    },
     None,
 }
 
 impl FeeArgs {
+    pub fn is_forced(&self) -> bool {
+        self.force
+    }
+
     pub fn into_setting(self) -> Result<FeeSetting> {
         match (self.max_fee, self.max_fee_raw, self.estimate_only) {
             (Some(max_fee), None, false) => {
                 let max_fee_felt = bigdecimal_to_felt(&max_fee, 18)?;
 
                 // The user is most likely making a mistake for using a max fee higher than 1 ETH
-                // TODO: allow skipping this safety check
-                if max_fee_felt > felt!("1000000000000000000") {
+                if !self.force && max_fee_felt > felt!("1000000000000000000") {
                     anyhow::bail!(
                         "the --max-fee value is too large. \
                         --max-fee expects a value in Ether (18 decimals). \
-                        Use --max-fee-raw instead to use a raw max_fee amount in Wei."
+                        Use --max-fee-raw instead to use a raw max_fee amount in Wei, \
+                        or --force if you really mean it."
                     )
                 }
 
                 Ok(FeeSetting::Manual(max_fee_felt))
             }
             (None, Some(max_fee_raw), false) => Ok(FeeSetting::Manual(max_fee_raw)),
-            (None, None, true) => Ok(FeeSetting::EstimateOnly),
+            (None, None, true) => Ok(FeeSetting::EstimateOnly {
+                json: self.json,
+                amount_format: self.amounts.format_or(AmountFormat::HUMAN),
+            }),
             (None, None, false) => Ok(FeeSetting::None),
             _ => Err(anyhow::anyhow!(
                 "invalid fee option. \
@@ -56,6 +92,116 @@ impl FeeArgs {
 
 impl FeeSetting {
     pub fn is_estimate_only(&self) -> bool {
-        matches!(self, FeeSetting::EstimateOnly)
+        matches!(self, FeeSetting::EstimateOnly { .. })
+    }
+
+    pub fn is_json(&self) -> bool {
+        matches!(self, FeeSetting::EstimateOnly { json: true, .. })
+    }
+
+    /// Prints the result of `--estimate-only`, either as the overall fee in Ether (or Wei with
+    /// `--raw-amounts`) or as the full estimation in JSON when `--json` is used.
+    pub fn print_estimate(&self, estimate: &FeeEstimate) -> Result<()> {
+        let amount_format = match self {
+            FeeSetting::EstimateOnly { amount_format, .. } => *amount_format,
+            _ => AmountFormat::HUMAN,
+        };
+
+        if self.is_json() {
+            print_colored_json(estimate)?;
+        } else if let AmountFormat::Human(style) = amount_format {
+            println!(
+                "{} ETH",
+                style.format_ether(estimate.overall_fee).bright_yellow(),
+            );
+        } else {
+            println!("{}", estimate.overall_fee);
+        }
+
+        Ok(())
     }
 }
+
+/// Prints the estimated fee next to an approximation of the same transaction paid in STRK. Sending
+/// v3 transactions is not supported yet, so the STRK fee is derived from the gas consumed by the
+/// estimated transaction priced at the STRK gas price of the pending block.
+pub async fn print_fee_comparison(
+    provider: &ExtendedProvider,
+    estimate: &FeeEstimate,
+) -> Result<()> {
+    let block = serde_json::to_value(
+        provider
+            .get_block_with_tx_hashes(BlockId::Tag(BlockTag::Pending))
+            .await?,
+    )?;
+
+    // Named `price_in_strk` before JSON-RPC v0.6.0
+    let strk_gas_price = ["price_in_fri", "price_in_strk"]
+        .iter()
+        .find_map(
+            |field| match block.get("l1_gas_price").and_then(|price| price.get(field)) {
+                Some(Value::String(price)) => FieldElement::from_hex_be(price).ok(),
+                _ => None,
+            },
+        )
+        .ok_or_else(|| anyhow::anyhow!("the node does not report a STRK gas price"))?;
+
+    let strk_fee = estimate.gas_consumed * strk_gas_price;
+
+    println!(
+        "v2 (ETH):  {} ETH",
+        format_ether(estimate.overall_fee).bright_yellow()
+    );
+    println!(
+        "v3 (STRK): {} STRK (approximate)",
+        format_ether(strk_fee).bright_yellow()
+    );
+
+    Ok(())
+}
+
+/// Estimates the fee of an execution by simulating it with validation skipped, for accounts whose
+/// signer is not available. The estimate does not include the cost of validation.
+pub async fn estimate_fee_skip_validate<A>(execution: Execution<'_, A>) -> Result<FeeEstimate>
+where
+    A: ConnectedAccount + Sync,
+    A::SignError: Send + Sync + 'static,
+{
+    // An explicit max fee prevents `simulate()` from estimating the fee with validation
+    let simulation = execution
+        .max_fee(FieldElement::ZERO)
+        .simulate(true, true)
+        .await?;
+
+    Ok(simulation.fee_estimation)
+}
+
+/// Aborts when the max fee exceeds the `max_fee_ceiling` configured for the network in the profile,
+/// guarding against fee estimation spikes and unit mistakes.
+pub fn check_max_fee_ceiling(
+    provider: &ExtendedProvider,
+    max_fee: FieldElement,
+    force: bool,
+) -> Result<()> {
+    if let Some(ceiling) = provider.max_fee_ceiling() {
+        if max_fee > ceiling {
+            if force {
+                Warning::FeeCeiling.print(format!(
+                    "max fee {} ETH exceeds the network's max_fee_ceiling of {} ETH. Proceeding \
+                    anyway due to --force.",
+                    format_ether(max_fee),
+                    format_ether(ceiling)
+                ));
+            } else {
+                anyhow::bail!(
+                    "max fee {} ETH exceeds the max_fee_ceiling of {} ETH configured for the \
+                    network. Use --force to send the transaction anyway.",
+                    format_ether(max_fee),
+                    format_ether(ceiling)
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use std::{process::Command, time::Duration};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde_json::json;
+use starknet::{
+    core::types::{ExecutionResult, FieldElement},
+    providers::Provider,
+};
+use url::Url;
+
+use crate::{interrupt::watch_interruptible, utils::wait_for_tx};
+
+#[derive(Debug, Clone, Parser)]
+pub struct WatchHookArgs {
+    #[clap(
+        long,
+        help = "Shell command to run once the transaction is confirmed. Implies --watch"
+    )]
+    on_confirm: Option<String>,
+    #[clap(
+        long,
+        help = "Shell command to run if the transaction is reverted. Implies --watch"
+    )]
+    on_revert: Option<String>,
+    #[clap(
+        long,
+        help = "Webhook URL to POST a JSON summary to once the transaction is confirmed or \
+                reverted. Implies --watch"
+    )]
+    notify_url: Option<Url>,
+}
+
+impl WatchHookArgs {
+    pub fn is_empty(&self) -> bool {
+        self.on_confirm.is_none() && self.on_revert.is_none() && self.notify_url.is_none()
+    }
+
+    /// Same as `watch_tx`, but also runs the hook matching the execution result. Transaction
+    /// details are passed to the hook in the `STARKLI_TX_HASH`, `STARKLI_TX_STATUS`, and
+    /// `STARKLI_REVERT_REASON` environment variables.
+    pub async fn watch_tx<P>(
+        &self,
+        provider: P,
+        transaction_hash: FieldElement,
+        poll_interval: Duration,
+    ) -> Result<()>
+    where
+        P: Provider + Clone,
+    {
+        let execution_result = watch_interruptible(
+            transaction_hash,
+            wait_for_tx(provider.clone(), transaction_hash, poll_interval),
+        )
+        .await?;
+
+        if let Some(url) = &self.notify_url {
+            notify(url, provider, transaction_hash, &execution_result).await;
+        }
+
+        match execution_result {
+            ExecutionResult::Succeeded => {
+                if let Some(command) = &self.on_confirm {
+                    run_hook(command, transaction_hash, "SUCCEEDED", "");
+                }
+
+                Ok(())
+            }
+            ExecutionResult::Reverted { reason } => {
+                if let Some(command) = &self.on_revert {
+                    run_hook(command, transaction_hash, "REVERTED", &reason);
+                }
+
+                Err(anyhow::anyhow!("transaction reverted: {}", reason))
+            }
+        }
+    }
+}
+
+/// Hook failures are reported but never fail the command, as the transaction itself has already
+/// been processed at this point.
+fn run_hook(command: &str, transaction_hash: FieldElement, status: &str, revert_reason: &str) {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.arg("/C").arg(command);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.arg("-c").arg(command);
+        process
+    };
+
+    let result = process
+        .env("STARKLI_TX_HASH", format!("{:#064x}", transaction_hash))
+        .env("STARKLI_TX_STATUS", status)
+        .env("STARKLI_REVERT_REASON", revert_reason)
+        .status();
+
+    match result {
+        Ok(exit_status) if exit_status.success() => {}
+        Ok(exit_status) => eprintln!(
+            "{}",
+            format!("WARNING: hook command failed with {}", exit_status).bright_magenta()
+        ),
+        Err(err) => eprintln!(
+            "{}",
+            format!("WARNING: unable to run hook command: {}", err).bright_magenta()
+        ),
+    }
+}
+
+/// Posts the outcome of a transaction to a webhook. Besides the structured fields, the payload
+/// carries a one-line summary in `text` and `content`, which are the fields displayed by Slack and
+/// Discord webhooks respectively. Like hooks, failures here are only reported.
+async fn notify<P>(
+    url: &Url,
+    provider: P,
+    transaction_hash: FieldElement,
+    execution_result: &ExecutionResult,
+) where
+    P: Provider,
+{
+    let (status, revert_reason) = match execution_result {
+        ExecutionResult::Succeeded => ("SUCCEEDED", None),
+        ExecutionResult::Reverted { reason } => ("REVERTED", Some(reason.as_str())),
+    };
+
+    // The fee format differs across RPC versions, so it's forwarded as returned by the node
+    let actual_fee = match provider.get_transaction_receipt(transaction_hash).await {
+        Ok(receipt) => serde_json::to_value(receipt)
+            .ok()
+            .and_then(|receipt| receipt.get("actual_fee").cloned()),
+        Err(err) => {
+            log::debug!("unable to fetch receipt for notification: {}", err);
+            None
+        }
+    };
+
+    let summary = format!(
+        "Starknet transaction {:#064x}: {}",
+        transaction_hash, status
+    );
+    let payload = json!({
+        "transaction_hash": format!("{:#064x}", transaction_hash),
+        "status": status,
+        "revert_reason": revert_reason,
+        "actual_fee": actual_fee,
+        "text": summary,
+        "content": summary,
+    });
+
+    let result = reqwest::Client::new()
+        .post(url.clone())
+        .header("Content-Type", "application/json")
+        .body(payload.to_string())
+        .send()
+        .await;
+
+    match result {
+        Ok(response) if response.status().is_success() => {}
+        Ok(response) => eprintln!(
+            "{}",
+            format!(
+                "WARNING: webhook notification failed with status {}",
+                response.status()
+            )
+            .bright_magenta()
+        ),
+        Err(err) => eprintln!(
+            "{}",
+            format!("WARNING: unable to send webhook notification: {}", err).bright_magenta()
+        ),
+    }
+}
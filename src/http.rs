@@ -0,0 +1,73 @@
+use std::{sync::OnceLock, time::Duration};
+
+use clap::Parser;
+
+static OPTIONS: OnceLock<HttpOptions> = OnceLock::new();
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// Connection settings for the HTTP client shared by providers, applying to all commands.
+#[derive(Debug, Clone, Default, Parser)]
+pub struct HttpOptions {
+    #[clap(
+        long,
+        global = true,
+        env = "STARKLI_RPC_TIMEOUT",
+        value_name = "SECONDS",
+        help = "Timeout for each request to the RPC endpoint. Requests never time out by default"
+    )]
+    pub rpc_timeout: Option<u64>,
+    #[clap(
+        long,
+        global = true,
+        env = "STARKLI_RPC_POOL_SIZE",
+        value_name = "CONNECTIONS",
+        help = "Maximum number of idle connections kept open per host for reuse"
+    )]
+    pub rpc_pool_size: Option<usize>,
+    #[clap(
+        long,
+        global = true,
+        env = "STARKLI_NO_RPC_BATCH",
+        help = "Send each JSON-RPC request on its own, for endpoints that reject batched requests"
+    )]
+    pub no_rpc_batch: bool,
+}
+
+impl HttpOptions {
+    /// Sets the options for the shared client. Only takes effect before the client is first used.
+    pub fn apply(self) {
+        let _ = OPTIONS.set(self);
+    }
+}
+
+/// Whether independent JSON-RPC requests may be sent together as a single batch.
+pub fn batching_enabled() -> bool {
+    !OPTIONS.get_or_init(Default::default).no_rpc_batch
+}
+
+/// The client shared by all providers within a command, so that commands issuing many requests
+/// reuse pooled connections instead of paying for a new connection and TLS handshake each time.
+pub fn shared_client() -> reqwest::Client {
+    // Clients are reference-counted, so clones share the same connection pool
+    CLIENT
+        .get_or_init(|| {
+            let options = OPTIONS.get_or_init(Default::default);
+
+            let mut builder = reqwest::Client::builder()
+                .user_agent(concat!("starkli/", env!("CARGO_PKG_VERSION")))
+                .tcp_keepalive(Duration::from_secs(60))
+                .pool_idle_timeout(Duration::from_secs(90));
+            if let Some(timeout) = options.rpc_timeout {
+                builder = builder.timeout(Duration::from_secs(timeout));
+            }
+            if let Some(pool_size) = options.rpc_pool_size {
+                builder = builder.pool_max_idle_per_host(pool_size);
+            }
+
+            // Building only fails if the TLS backend can't be initialized
+            builder
+                .build()
+                .expect("unable to initialize the HTTP client")
+        })
+        .clone()
+}
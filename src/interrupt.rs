@@ -0,0 +1,42 @@
+use std::future::Future;
+
+use anyhow::Result;
+use colored::Colorize;
+use starknet::core::types::FieldElement;
+
+use crate::messages::{message, plain_message};
+
+/// Resolves once the user presses Ctrl-C. Failing to listen for the signal is treated as never
+/// being interrupted.
+pub async fn ctrl_c() {
+    if tokio::signal::ctrl_c().await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Runs `future` watching an already sent transaction, unless interrupted with Ctrl-C. Instead of
+/// exiting without a trace, an interruption prints the transaction hash and how to keep watching
+/// it.
+pub async fn watch_interruptible<F, T>(transaction_hash: FieldElement, future: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    tokio::select! {
+        result = future => result,
+        _ = ctrl_c() => {
+            eprintln!();
+            eprintln!(
+                "{}",
+                plain_message(
+                    "tx.interrupted",
+                    &[("hash", &format!("{:#064x}", transaction_hash))]
+                )
+                .bright_magenta()
+            );
+            eprintln!("{}", message("tx.keep_watching", &[]));
+            eprintln!("    starkli transaction wait {:#064x}", transaction_hash);
+
+            anyhow::bail!("interrupted")
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+/// Service name under which keystore passwords are saved in the OS keychain.
+const KEYCHAIN_SERVICE: &str = "starkli-keystore";
+
+/// Keystore passwords saved in the OS keychain (macOS Keychain, freedesktop Secret Service, or
+/// Windows Credential Manager), keyed by the canonical path of the keystore file.
+pub struct Keychain;
+
+impl Keychain {
+    pub fn set_keystore_password(keystore: &Path, password: &str) -> Result<()> {
+        Self::entry(keystore)?
+            .set_password(password)
+            .map_err(|err| anyhow::anyhow!("unable to save password to OS keychain: {}", err))
+    }
+
+    /// Returns whether a password was saved for the keystore.
+    pub fn delete_keystore_password(keystore: &Path) -> Result<bool> {
+        match Self::entry(keystore)?.delete_password() {
+            Ok(_) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(err) => Err(anyhow::anyhow!(
+                "unable to remove password from OS keychain: {}",
+                err
+            )),
+        }
+    }
+
+    /// Looks up the password of a keystore linked with `starkli signer keystore link-keychain`.
+    /// Keystores that aren't linked, or platforms without a usable keychain, yield `None`.
+    pub fn get_keystore_password(keystore: &Path) -> Option<String> {
+        let entry = match Self::entry(keystore) {
+            Ok(entry) => entry,
+            Err(err) => {
+                log::debug!("OS keychain unavailable: {}", err);
+                return None;
+            }
+        };
+
+        match entry.get_password() {
+            Ok(password) => Some(password),
+            Err(keyring::Error::NoEntry) => None,
+            Err(err) => {
+                log::debug!("unable to read from OS keychain: {}", err);
+                None
+            }
+        }
+    }
+
+    fn entry(keystore: &Path) -> Result<keyring::Entry> {
+        let path = std::fs::canonicalize(keystore)?;
+        Ok(keyring::Entry::new(
+            KEYCHAIN_SERVICE,
+            &path.to_string_lossy(),
+        )?)
+    }
+}
@@ -0,0 +1,223 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use coins_ledger::{
+    common::{APDUData, APDUResponseCodes},
+    transports::LedgerAsync,
+    APDUAnswer, APDUCommand, Ledger,
+};
+use sha2::{Digest, Sha256};
+use starknet::core::{crypto::Signature, types::FieldElement};
+
+/// APDU class of the Starknet Ledger app.
+const CLA_STARKNET: u8 = 0x5a;
+const INS_GET_PUBLIC_KEY: u8 = 0x01;
+const INS_SIGN_HASH: u8 = 0x02;
+
+/// Number of levels in an EIP-2645 path.
+const EIP2645_LENGTH: usize = 6;
+const EIP2645_PURPOSE: u32 = 0x80000a55;
+const HARDENED_BIT: u32 = 0x80000000;
+
+pub const DEFAULT_HD_PATH: &str = "m/2645'/starknet'/starkli'/0'/0'/0";
+
+/// An EIP-2645 derivation path, like `m/2645'/starknet'/starkli'/0'/0'/0`. Non-numeric levels
+/// are converted to numbers by taking the lowest 31 bits of their SHA-256 hash.
+#[derive(Debug, Clone)]
+pub struct HdPath(Vec<u32>);
+
+/// Connection to the Starknet app on a Ledger device.
+pub struct LedgerDevice {
+    transport: Ledger,
+}
+
+/// Signer using the key at a path on a Ledger device. The device is connected to on each use, so
+/// that the signer can be created before the device is needed.
+#[derive(Debug)]
+pub struct LedgerSigner {
+    path: HdPath,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct LedgerSignerError(String);
+
+impl LedgerDevice {
+    pub async fn connect() -> Result<Self> {
+        let transport = Ledger::init().await.map_err(|err| {
+            anyhow::anyhow!(
+                "unable to connect to Ledger device: {}. Make sure it's plugged in and unlocked, \
+                with the Starknet app open",
+                err
+            )
+        })?;
+
+        Ok(Self { transport })
+    }
+
+    /// Gets the public key at the path. With `display` set, the device shows the key and waits
+    /// for the user to confirm it on screen.
+    pub async fn get_public_key(&self, path: &HdPath, display: bool) -> Result<FieldElement> {
+        let command = APDUCommand {
+            cla: CLA_STARKNET,
+            ins: INS_GET_PUBLIC_KEY,
+            p1: if display { 0x01 } else { 0x00 },
+            p2: 0x00,
+            data: APDUData::new(&path.to_bytes()),
+            response_len: None,
+        };
+
+        let answer = self.transport.exchange(&command).await?;
+        let data = answer_data(&answer)?;
+
+        // Uncompressed point: 0x04 followed by the x and y coordinates
+        if data.len() != 65 || data[0] != 0x04 {
+            anyhow::bail!("unexpected public key format from Ledger device");
+        }
+
+        Ok(FieldElement::from_byte_slice_be(&data[1..33])?)
+    }
+
+    /// Blind-signs a hash, which the device shows for the user to approve.
+    pub async fn sign_hash(&self, path: &HdPath, hash: &FieldElement) -> Result<Signature> {
+        let path_command = APDUCommand {
+            cla: CLA_STARKNET,
+            ins: INS_SIGN_HASH,
+            p1: 0x00,
+            p2: 0x00,
+            data: APDUData::new(&path.to_bytes()),
+            response_len: None,
+        };
+        answer_data(&self.transport.exchange(&path_command).await?)?;
+
+        // The app expects the hash shifted left by 4 bits, which always fits as hashes are below
+        // 2^252
+        let hash_bytes = hash.to_bytes_be();
+        let mut shifted = [0u8; 32];
+        for (index, byte) in shifted.iter_mut().enumerate() {
+            *byte =
+                (hash_bytes[index] << 4) | hash_bytes.get(index + 1).map_or(0, |next| next >> 4);
+        }
+
+        let hash_command = APDUCommand {
+            cla: CLA_STARKNET,
+            ins: INS_SIGN_HASH,
+            p1: 0x01,
+            p2: 0x00,
+            data: APDUData::new(&shifted),
+            response_len: None,
+        };
+        let answer = self.transport.exchange(&hash_command).await?;
+        let data = answer_data(&answer)?;
+
+        // Length of `r` and `s` combined, followed by `r`, `s`, and `v`
+        if data.len() != 66 || data[0] != 64 {
+            anyhow::bail!("unexpected signature format from Ledger device");
+        }
+
+        Ok(Signature {
+            r: FieldElement::from_byte_slice_be(&data[1..33])?,
+            s: FieldElement::from_byte_slice_be(&data[33..65])?,
+        })
+    }
+}
+
+impl LedgerSigner {
+    pub fn new(path: HdPath) -> Self {
+        Self { path }
+    }
+
+    pub async fn get_public_key(&self) -> Result<FieldElement, LedgerSignerError> {
+        async {
+            LedgerDevice::connect()
+                .await?
+                .get_public_key(&self.path, false)
+                .await
+        }
+        .await
+        .map_err(|err| LedgerSignerError(err.to_string()))
+    }
+
+    pub async fn sign_hash(&self, hash: &FieldElement) -> Result<Signature, LedgerSignerError> {
+        async {
+            let device = LedgerDevice::connect().await?;
+
+            eprintln!(
+                "Confirm signing hash {:#064x} on your Ledger device...",
+                hash
+            );
+            device.sign_hash(&self.path, hash).await
+        }
+        .await
+        .map_err(|err| LedgerSignerError(err.to_string()))
+    }
+}
+
+impl HdPath {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0
+            .iter()
+            .flat_map(|level| level.to_be_bytes())
+            .collect()
+    }
+}
+
+impl FromStr for HdPath {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let levels = s
+            .strip_prefix("m/")
+            .ok_or_else(|| anyhow::anyhow!("HD path must start with `m/`"))?
+            .split('/')
+            .map(|level| {
+                let (level, hardened) = match level.strip_suffix('\'') {
+                    Some(level) => (level, true),
+                    None => (level, false),
+                };
+
+                let index = match level.parse::<u32>() {
+                    Ok(index) if index < HARDENED_BIT => index,
+                    Ok(_) => anyhow::bail!("HD path level out of range: {}", level),
+                    Err(_) => {
+                        if level.is_empty() {
+                            anyhow::bail!("empty HD path level");
+                        }
+
+                        let hash = Sha256::digest(level.as_bytes());
+                        u32::from_be_bytes([hash[28], hash[29], hash[30], hash[31]]) & !HARDENED_BIT
+                    }
+                };
+
+                Ok(if hardened {
+                    index | HARDENED_BIT
+                } else {
+                    index
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if levels.len() != EIP2645_LENGTH || levels[0] != EIP2645_PURPOSE {
+            anyhow::bail!(
+                "only EIP-2645 paths are supported, like `{}`",
+                DEFAULT_HD_PATH
+            );
+        }
+
+        Ok(Self(levels))
+    }
+}
+
+fn answer_data(answer: &APDUAnswer) -> Result<&[u8]> {
+    let code = answer.retcode();
+    match APDUResponseCodes::try_from(code) {
+        Ok(status) if status.is_success() => answer
+            .data()
+            .ok_or_else(|| anyhow::anyhow!("empty response from Ledger device")),
+        Ok(status) => Err(anyhow::anyhow!("Ledger request failed: {:?}", status)),
+        Err(_) => Err(anyhow::anyhow!(
+            "unexpected response code from Ledger device: {:#06x}",
+            code
+        )),
+    }
+}
@@ -1,25 +1,67 @@
 use anyhow::Result;
 use clap::{CommandFactory, Parser, Subcommand};
 use colored::Colorize;
+use starknet::providers::ProviderError;
 
-use crate::{provider::ProviderArgs, subcommands::*};
+use crate::{
+    accessibility::AccessibilityOptions, default_block::DefaultBlockOptions,
+    error::decode_provider_error, http::HttpOptions, messages::MessageOptions,
+    provider::ProviderArgs, resolve_cache::ResolveCache, subcommands::*, warning::WarningOptions,
+};
 
+mod abi;
+mod abi_cache;
+mod abi_registry;
+mod accessibility;
 mod account;
 mod account_factory;
 mod address_book;
+mod agent;
+mod amount;
+mod artifact_store;
 mod casm;
 mod chain_id;
 mod compiler;
 mod decode;
+mod default_block;
+mod deployer;
+mod deployments;
+mod devnet;
+mod error;
+mod explorer;
 mod fee;
+mod hook;
+mod http;
+mod interrupt;
+mod keychain;
+mod ledger;
+mod messages;
+mod metrics;
 mod network;
+mod nonce;
 mod path;
+mod preflight;
 mod profile;
+mod progress;
 mod provider;
+mod resolve_cache;
+mod script_state;
+mod secrets;
+mod select;
+mod send_schedule;
+mod signature_share;
 mod signer;
+mod simulation_compare;
+mod simulation_summary;
 mod subcommands;
+mod template;
+mod token;
+mod transaction_file;
+mod upload;
 mod utils;
 mod verbosity;
+mod wallet_format;
+mod warning;
 
 const VERSION_STRING: &str = concat!(env!("CARGO_PKG_VERSION"), " (", env!("VERGEN_GIT_SHA"), ")");
 const VERSION_STRING_VERBOSE: &str = concat!(
@@ -43,6 +85,23 @@ struct Cli {
         help = "Use verbose output (currently only applied to version)"
     )]
     verbose: bool,
+    #[clap(
+        long,
+        global = true,
+        env = "STARKLI_NO_RESOLVE_CACHE",
+        help = "Do not use chain IDs and names resolved by previous commands"
+    )]
+    no_resolve_cache: bool,
+    #[clap(flatten)]
+    http: HttpOptions,
+    #[clap(flatten)]
+    messages: MessageOptions,
+    #[clap(flatten)]
+    accessibility: AccessibilityOptions,
+    #[clap(flatten)]
+    default_block: DefaultBlockOptions,
+    #[clap(flatten)]
+    warnings: WarningOptions,
 }
 
 #[derive(Debug, Subcommand)]
@@ -52,19 +111,41 @@ enum Subcommands {
     //
     #[clap(about = "Calculate selector from name")]
     Selector(Selector),
+    #[clap(about = "Calculate the event selector (first key) from an event name")]
+    EventSelector(EventSelector),
     #[clap(about = "Calculate class hash from any contract artifacts (Sierra, casm, legacy)")]
     ClassHash(ClassHash),
+    #[clap(about = "Calculate the storage address of a variable, including map entries")]
+    StorageAddress(StorageAddress),
+    #[clap(about = "Sierra class utilities")]
+    Sierra(Sierra),
+    #[clap(about = "CASM class utilities")]
+    Casm(Casm),
+    #[clap(about = "Legacy (Cairo 0) class utilities")]
+    Legacy(Legacy),
     #[clap(about = "Encode string into felt with the Cairo short string representation")]
     ToCairoString(ToCairoString),
     #[clap(about = "Decode string from felt with the Cairo short string representation")]
     ParseCairoString(ParseCairoString),
+    #[clap(about = "Decode a felt array as a type, or annotate each felt")]
+    PrettyFelts(PrettyFelts),
     #[clap(about = "Print the montgomery representation of a field element")]
     Mont(Mont),
+    #[clap(about = "Perform field element or u256 arithmetic")]
+    Math(Math),
+    #[clap(about = "Low-level ECDSA operations over the Stark curve")]
+    Ecdsa(Ecdsa),
+    #[clap(about = "Encode an Ethereum address into felt, validating its length")]
+    EthAddressToFelt(EthAddressToFelt),
+    #[clap(about = "Decode an Ethereum address from felt, validating the 160-bit range")]
+    FeltToEthAddress(FeltToEthAddress),
     //
     // JSON-RPC query client
     //
     #[clap(about = "Call contract functions without sending transactions")]
     Call(Call),
+    #[clap(about = "Estimate the fee of invoking contract functions from an account")]
+    Estimate(Estimate),
     #[clap(alias = "tx", about = "Get Starknet transaction by hash")]
     Transaction(Transaction),
     #[clap(alias = "bn", about = "Get latest block number")]
@@ -90,21 +171,47 @@ enum Subcommands {
     Balance(Balance),
     #[clap(about = "Get nonce for a certain contract")]
     Nonce(Nonce),
+    #[clap(about = "List transactions from an address in the pending block")]
+    Pending(Pending),
+    #[clap(about = "List transactions sent by an account in a range of blocks")]
+    History(History),
+    #[clap(about = "Query events, optionally using saved filter presets")]
+    Events(Events),
     #[clap(about = "Get storage value for a slot at a contract")]
     Storage(Storage),
+    #[clap(
+        about = "Read and print all storage variables of a contract described in a layout file"
+    )]
+    StorageDump(StorageDump),
     #[clap(about = "Get contract class hash deployed at a certain address")]
     ClassHashAt(ClassHashAt),
+    #[clap(about = "Wait until a contract is deployed at a certain address")]
+    WaitForContract(WaitForContract),
     #[clap(about = "Get contract class by hash")]
     ClassByHash(ClassByHash),
     #[clap(about = "Get contract class deployed at a certain address")]
     ClassAt(ClassAt),
+    #[clap(about = "Checks against classes declared on the network")]
+    Class(Class),
+    #[clap(about = "Summarize what's deployed at an address: class, contract type, and balances")]
+    Inspect(Inspect),
+    #[clap(about = "Estimate the L2 fee for an L1-to-L2 message")]
+    EstimateMessageFee(EstimateMessageFee),
     #[clap(about = "Get node syncing status")]
     Syncing(Syncing),
+    #[clap(about = "Browse recent blocks and transactions in an interactive terminal UI")]
+    Tui(Tui),
     //
     // Signer management
     //
     #[clap(about = "Signer management commands")]
     Signer(Signer),
+    #[clap(about = "Encrypted secret store for RPC URLs and keystore passwords")]
+    Secret(Secret),
+    #[clap(about = "Keep unlocked keystores in a background agent for a limited time")]
+    Agent(Agent),
+    #[clap(about = "Ledger hardware wallet commands")]
+    Ledger(Ledger),
     //
     // Account management
     //
@@ -115,15 +222,65 @@ enum Subcommands {
     //
     #[clap(about = "Send an invoke transaction from an account contract")]
     Invoke(Invoke),
+    #[clap(about = "Send transactions from many accounts at once")]
+    Multi(Multi),
+    #[clap(about = "Resume multi-step commands like `deploy --manifest` and `multi invoke`")]
+    Script(Script),
+    #[clap(about = "Simulate a queue of transactions in order, each on top of the ones before it")]
+    SimulateQueue(SimulateQueue),
+    #[clap(about = "Save and reuse parameterized invoke transactions")]
+    Template(Template),
+    #[clap(about = "Ownership, role, pause and timelock operations on admin contracts")]
+    Admin(Admin),
+    #[clap(about = "Generate transaction load against devnets and appchains")]
+    Bench(Bench),
     #[clap(about = "Declare a contract class")]
     Declare(Declare),
     #[clap(about = "Deploy contract via the Universal Deployer Contract")]
     Deploy(Deploy),
+    #[clap(about = "Sign a transaction file exported with `--export`")]
+    SignTx(SignTx),
+    #[clap(about = "Send out a signed transaction file")]
+    BroadcastTx(BroadcastTx),
+    #[clap(
+        about = "Verify the hash and signature of a signed transaction file and show its contents"
+    )]
+    VerifyTx(VerifyTx),
+    #[clap(about = "Collect signatures from multisig owners over a shared directory or URL")]
+    Multisig(Multisig),
+    #[clap(
+        about = "Run a local JSON-RPC service that signs and sends transactions with an account"
+    )]
+    Serve(Serve),
+    #[clap(about = "Run a local service that watches transactions on behalf of many clients")]
+    Watchd(Watchd),
+    #[clap(about = "StarkGate token bridge helpers")]
+    Bridge(Bridge),
+    #[clap(about = "Local devnet helpers for testing against forked network state")]
+    Devnet(Devnet),
+    #[clap(about = "Generate client code from contract ABIs")]
+    Generate(Generate),
     //
     // Misc
     //
+    #[clap(about = "Read and change settings in the profiles file")]
+    Config(Config),
+    #[clap(about = "List networks and set the default one")]
+    Network(Network),
+    #[clap(about = "Sync and browse address books shared by a team")]
+    AddressBook(AddressBook),
+    #[clap(about = "Manage the local cache of class ABIs")]
+    AbiCache(AbiCache),
+    #[clap(about = "Keep contract artifacts in a local store indexed by class hash")]
+    Store(Store),
     #[clap(about = "Generate shell completions script")]
     Completions(Completions),
+    #[clap(about = "Print all commands and options as JSON for use by external tools")]
+    Schema(Schema),
+    #[clap(about = "Check provider, account, signer, and compiler setup for common problems")]
+    Doctor(Doctor),
+    #[clap(about = "Set up a network, keystore and account interactively")]
+    Init(Init),
     //
     // Experimental
     //
@@ -138,12 +295,26 @@ enum Subcommands {
 #[tokio::main]
 async fn main() {
     if let Err(err) = run_command(Cli::parse()).await {
+        // Provider errors propagated as-is are decoded here as a last resort
+        let err = match err.downcast::<ProviderError>() {
+            Ok(err) => decode_provider_error(err),
+            Err(err) => err,
+        };
         eprintln!("{}", format!("Error: {err}").red());
         std::process::exit(1);
     }
 }
 
 async fn run_command(cli: Cli) -> Result<()> {
+    if cli.no_resolve_cache {
+        ResolveCache::disable();
+    }
+    cli.http.apply();
+    cli.messages.apply()?;
+    cli.accessibility.apply();
+    cli.default_block.apply();
+    cli.warnings.apply();
+
     match (cli.version, cli.command) {
         (false, None) => Ok(Cli::command().print_help()?),
         (true, _) => {
@@ -160,11 +331,22 @@ async fn run_command(cli: Cli) -> Result<()> {
         }
         (false, Some(command)) => match command {
             Subcommands::Selector(cmd) => cmd.run(),
+            Subcommands::EventSelector(cmd) => cmd.run(),
             Subcommands::ClassHash(cmd) => cmd.run(),
+            Subcommands::StorageAddress(cmd) => cmd.run(),
+            Subcommands::Sierra(cmd) => cmd.run(),
+            Subcommands::Casm(cmd) => cmd.run(),
+            Subcommands::Legacy(cmd) => cmd.run(),
             Subcommands::ToCairoString(cmd) => cmd.run(),
             Subcommands::ParseCairoString(cmd) => cmd.run(),
+            Subcommands::PrettyFelts(cmd) => cmd.run().await,
             Subcommands::Mont(cmd) => cmd.run(),
+            Subcommands::Math(cmd) => cmd.run(),
+            Subcommands::Ecdsa(cmd) => cmd.run(),
+            Subcommands::EthAddressToFelt(cmd) => cmd.run(),
+            Subcommands::FeltToEthAddress(cmd) => cmd.run(),
             Subcommands::Call(cmd) => cmd.run().await,
+            Subcommands::Estimate(cmd) => cmd.run().await,
             Subcommands::Transaction(cmd) => cmd.run().await,
             Subcommands::BlockNumber(cmd) => cmd.run().await,
             Subcommands::BlockHash(cmd) => cmd.run().await,
@@ -176,17 +358,52 @@ async fn run_command(cli: Cli) -> Result<()> {
             Subcommands::ChainId(cmd) => cmd.run().await,
             Subcommands::Balance(cmd) => cmd.run().await,
             Subcommands::Nonce(cmd) => cmd.run().await,
+            Subcommands::Pending(cmd) => cmd.run().await,
+            Subcommands::History(cmd) => cmd.run().await,
+            Subcommands::Events(cmd) => cmd.run().await,
             Subcommands::Storage(cmd) => cmd.run().await,
+            Subcommands::StorageDump(cmd) => cmd.run().await,
             Subcommands::ClassHashAt(cmd) => cmd.run().await,
+            Subcommands::WaitForContract(cmd) => cmd.run().await,
             Subcommands::ClassByHash(cmd) => cmd.run().await,
             Subcommands::ClassAt(cmd) => cmd.run().await,
+            Subcommands::Class(cmd) => cmd.run().await,
+            Subcommands::Inspect(cmd) => cmd.run().await,
+            Subcommands::EstimateMessageFee(cmd) => cmd.run().await,
             Subcommands::Syncing(cmd) => cmd.run().await,
+            Subcommands::Tui(cmd) => cmd.run().await,
             Subcommands::Signer(cmd) => cmd.run(),
+            Subcommands::Secret(cmd) => cmd.run(),
+            Subcommands::Agent(cmd) => cmd.run(),
+            Subcommands::Ledger(cmd) => cmd.run().await,
             Subcommands::Account(cmd) => cmd.run().await,
             Subcommands::Invoke(cmd) => cmd.run().await,
+            Subcommands::Multi(cmd) => cmd.run().await,
+            Subcommands::Script(cmd) => cmd.run().await,
+            Subcommands::SimulateQueue(cmd) => cmd.run().await,
+            Subcommands::Template(cmd) => cmd.run().await,
+            Subcommands::Admin(cmd) => cmd.run().await,
+            Subcommands::Bench(cmd) => cmd.run().await,
             Subcommands::Declare(cmd) => cmd.run().await,
             Subcommands::Deploy(cmd) => cmd.run().await,
+            Subcommands::SignTx(cmd) => cmd.run().await,
+            Subcommands::BroadcastTx(cmd) => cmd.run().await,
+            Subcommands::VerifyTx(cmd) => cmd.run().await,
+            Subcommands::Multisig(cmd) => cmd.run().await,
+            Subcommands::Serve(cmd) => cmd.run().await,
+            Subcommands::Watchd(cmd) => cmd.run().await,
+            Subcommands::Bridge(cmd) => cmd.run().await,
+            Subcommands::Devnet(cmd) => cmd.run().await,
+            Subcommands::Generate(cmd) => cmd.run().await,
+            Subcommands::Config(cmd) => cmd.run(),
+            Subcommands::Network(cmd) => cmd.run(),
+            Subcommands::AddressBook(cmd) => cmd.run().await,
+            Subcommands::AbiCache(cmd) => cmd.run().await,
+            Subcommands::Store(cmd) => cmd.run(),
             Subcommands::Completions(cmd) => cmd.run(),
+            Subcommands::Schema(cmd) => cmd.run(),
+            Subcommands::Doctor(cmd) => cmd.run().await,
+            Subcommands::Init(cmd) => cmd.run().await,
             Subcommands::Lab(cmd) => cmd.run(),
         },
     }
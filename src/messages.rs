@@ -0,0 +1,171 @@
+use std::{collections::HashMap, fmt::Display, path::PathBuf, sync::OnceLock};
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+
+use crate::path::ExpandedPathbufParser;
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// Built-in English messages. Keys are stable so that catalogs and wrappers parsing the `keys`
+/// mode keep working across releases. Placeholders are written as `{name}`.
+const DEFAULT_MESSAGES: &[(&str, &str)] = &[
+    ("tx.waiting", "Waiting for transaction {hash} to confirm..."),
+    ("tx.not_confirmed", "Transaction not confirmed yet..."),
+    ("tx.confirmed", "Transaction {hash} confirmed"),
+    (
+        "tx.reorged",
+        "Chain reorganization detected: block {block} containing the transaction is no longer \
+        canonical. Waiting for the transaction to be included again...",
+    ),
+    (
+        "tx.dropped",
+        "Transaction dropped from block {block} by a chain reorganization. Waiting for it to be \
+        included again...",
+    ),
+    (
+        "tx.interrupted",
+        "Interrupted. Transaction {hash} was already sent and may still be confirmed.",
+    ),
+    ("tx.keep_watching", "To keep watching it, run:"),
+    ("invoke.sent", "Invoke transaction: {hash}"),
+    ("declare.sent", "Contract declaration transaction: {hash}"),
+    ("deploy.sent", "Contract deployment transaction: {hash}"),
+    ("prompt.send", "Send this transaction? [y/N] "),
+    ("prompt.send_with_fee", "Send with max fee {fee}? [y/N] "),
+    (
+        "prompt.send_noop",
+        "Send a no-op transaction with nonce {nonce} and max fee {fee}? Only confirm if no \
+        transaction with this nonce is still on its way, as it would be replaced. [y/N] ",
+    ),
+    ("prompt.aborted", "aborted"),
+];
+
+#[derive(Debug, Clone, Default, Parser)]
+pub struct MessageOptions {
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        env = "STARKLI_MESSAGES",
+        help = "How progress messages, prompts and warnings are shown: `text` for readable \
+                messages, or `keys` for a message key followed by its values as JSON"
+    )]
+    messages: Option<MessageMode>,
+    #[clap(
+        long,
+        global = true,
+        env = "STARKLI_MESSAGE_CATALOG",
+        value_parser = ExpandedPathbufParser,
+        help = "TOML file with translated messages, keyed by message key. Messages missing from \
+                the file are shown in English"
+    )]
+    message_catalog: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MessageMode {
+    #[default]
+    Text,
+    Keys,
+}
+
+#[derive(Debug, Default)]
+struct Catalog {
+    mode: MessageMode,
+    overrides: HashMap<String, String>,
+}
+
+impl MessageOptions {
+    /// Loads the catalog for the rest of the process.
+    pub fn apply(self) -> Result<()> {
+        let mut overrides = HashMap::new();
+        if let Some(path) = &self.message_catalog {
+            let table: toml::Table =
+                toml::from_str(&std::fs::read_to_string(path)?).map_err(|err| {
+                    anyhow::anyhow!("invalid message catalog {}: {}", path.display(), err)
+                })?;
+            flatten_table("", &table, &mut overrides);
+        }
+
+        let _ = CATALOG.set(Catalog {
+            mode: self.messages.unwrap_or_default(),
+            overrides,
+        });
+
+        Ok(())
+    }
+}
+
+/// Renders a message with its values highlighted. In `keys` mode, the key and the values as a
+/// JSON object are rendered instead, without any color.
+pub fn message(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    render(key, args, true)
+}
+
+/// Same as [message], but without highlighting the values, for messages that are colored as a
+/// whole.
+pub fn plain_message(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    render(key, args, false)
+}
+
+fn render(key: &str, args: &[(&str, &dyn Display)], highlight: bool) -> String {
+    let catalog = CATALOG.get_or_init(Default::default);
+
+    if catalog.mode == MessageMode::Keys {
+        let values = args
+            .iter()
+            .map(|(name, value)| {
+                (
+                    (*name).to_owned(),
+                    serde_json::Value::String(value.to_string()),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+        return format!("{} {}", key, serde_json::Value::Object(values));
+    }
+
+    let template = catalog
+        .overrides
+        .get(key)
+        .map(|template| template.as_str())
+        .or_else(|| {
+            DEFAULT_MESSAGES
+                .iter()
+                .find(|(default_key, _)| *default_key == key)
+                .map(|(_, template)| *template)
+        })
+        .unwrap_or(key);
+
+    let mut text = template.to_owned();
+    for (name, value) in args.iter() {
+        let value = if highlight {
+            value.to_string().bright_yellow().to_string()
+        } else {
+            value.to_string()
+        };
+        text = text.replace(&format!("{{{}}}", name), &value);
+    }
+    text
+}
+
+/// Tables in the catalog file map to dotted keys, so `[tx]` with `waiting = "..."` is the same as
+/// `"tx.waiting" = "..."`.
+fn flatten_table(prefix: &str, table: &toml::Table, messages: &mut HashMap<String, String>) {
+    for (key, value) in table.iter() {
+        let key = if prefix.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+
+        match value {
+            toml::Value::String(template) => {
+                messages.insert(key, template.to_owned());
+            }
+            toml::Value::Table(table) => flatten_table(&key, table, messages),
+            _ => log::warn!("ignoring non-string message catalog entry: {}", key),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    convert::Infallible,
+    fmt::Write,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::Result;
+use hyper::{
+    header::{HeaderValue, CONTENT_TYPE},
+    service::{make_service_fn, service_fn},
+    Body, Response, Server,
+};
+
+#[derive(Default)]
+pub struct Counter(AtomicU64);
+
+/// Total time and number of observations, exposed as a Prometheus summary without quantiles.
+#[derive(Default)]
+pub struct Latency {
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+/// Renders metrics in the Prometheus text exposition format.
+#[derive(Default)]
+pub struct MetricsWriter {
+    buffer: String,
+}
+
+impl Counter {
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Latency {
+    pub fn observe(&self, elapsed: Duration) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+impl MetricsWriter {
+    pub fn counter(&mut self, name: &str, help: &str, counter: &Counter) {
+        // Writing to a `String` never fails
+        let _ = writeln!(self.buffer, "# HELP {} {}", name, help);
+        let _ = writeln!(self.buffer, "# TYPE {} counter", name);
+        let _ = writeln!(
+            self.buffer,
+            "{} {}",
+            name,
+            counter.0.load(Ordering::Relaxed)
+        );
+    }
+
+    pub fn latency(&mut self, name: &str, help: &str, latency: &Latency) {
+        let _ = writeln!(self.buffer, "# HELP {} {}", name, help);
+        let _ = writeln!(self.buffer, "# TYPE {} summary", name);
+        let _ = writeln!(
+            self.buffer,
+            "{}_sum {}",
+            name,
+            latency.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000f64
+        );
+        let _ = writeln!(
+            self.buffer,
+            "{}_count {}",
+            name,
+            latency.count.load(Ordering::Relaxed)
+        );
+    }
+
+    pub fn finish(self) -> String {
+        self.buffer
+    }
+}
+
+/// Starts serving the output of `render` on localhost in the background, for any path, so that it
+/// can be scraped by Prometheus. Fails right away if the port cannot be bound.
+pub fn spawn_metrics_server<F>(port: u16, render: F) -> Result<SocketAddr>
+where
+    F: Fn() -> String + Send + Sync + 'static,
+{
+    let render = Arc::new(render);
+
+    let make_service = make_service_fn(move |_| {
+        let render = render.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |_| {
+                let mut response = Response::new(Body::from(render()));
+                response.headers_mut().insert(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static("text/plain; version=0.0.4"),
+                );
+                async move { Ok::<_, Infallible>(response) }
+            }))
+        }
+    });
+
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let server = Server::try_bind(&address)?.serve(make_service);
+
+    tokio::spawn(async move {
+        if let Err(err) = server.await {
+            log::error!("metrics server error: {}", err);
+        }
+    });
+
+    Ok(address)
+}
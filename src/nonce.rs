@@ -0,0 +1,94 @@
+use std::cmp::Ordering;
+
+use anyhow::Result;
+use colored::Colorize;
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement},
+    providers::Provider,
+};
+
+use crate::{provider::ExtendedProvider, warning::Warning};
+
+/// Where the nonce of a transaction comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonceSource {
+    /// Given with `--nonce`.
+    Flag,
+    /// The account's nonce in the pending block.
+    Chain,
+}
+
+/// The nonce a transaction is going to use, along with the account's nonces on the chain, shown in
+/// previews so that nonce problems surface before the transaction is sent.
+#[derive(Debug, Clone)]
+pub struct NoncePreview {
+    pub nonce: FieldElement,
+    pub source: NonceSource,
+    pub pending: FieldElement,
+    pub latest: FieldElement,
+}
+
+impl NoncePreview {
+    pub async fn fetch(
+        provider: &ExtendedProvider,
+        address: FieldElement,
+        manual_nonce: Option<FieldElement>,
+    ) -> Result<Self> {
+        let (pending, latest) = tokio::try_join!(
+            provider.get_nonce(BlockId::Tag(BlockTag::Pending), address),
+            provider.get_nonce(BlockId::Tag(BlockTag::Latest), address),
+        )?;
+
+        Ok(match manual_nonce {
+            Some(nonce) => Self {
+                nonce,
+                source: NonceSource::Flag,
+                pending,
+                latest,
+            },
+            None => Self {
+                nonce: pending,
+                source: NonceSource::Chain,
+                pending,
+                latest,
+            },
+        })
+    }
+
+    /// Prints the preview to stderr, with a warning when a manually set nonce would not be accepted
+    /// right away.
+    pub fn print(&self) {
+        let source = match self.source {
+            NonceSource::Flag => "from --nonce",
+            NonceSource::Chain => "account's pending nonce",
+        };
+        eprintln!(
+            "Nonce: {} ({}; pending: {}, latest: {})",
+            format!("{}", self.nonce).bright_yellow(),
+            source,
+            self.pending,
+            self.latest
+        );
+
+        if self.pending > self.latest {
+            eprintln!(
+                "{} transaction(s) from this account are in the pending block.",
+                format!("{}", self.pending - self.latest).bright_yellow()
+            );
+        }
+
+        match self.nonce.cmp(&self.pending) {
+            Ordering::Less => Warning::NonceMismatch.print(format!(
+                "nonce {} has already been used by this account. The transaction would be \
+                rejected for an invalid nonce.",
+                self.nonce
+            )),
+            Ordering::Greater => Warning::NonceMismatch.print(format!(
+                "nonce {} is ahead of the account's pending nonce {}. The transaction can't be \
+                included until the nonces in between are used. See `starkli nonce doctor`.",
+                self.nonce, self.pending
+            )),
+            Ordering::Equal => {}
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use clap::Parser;
+use num_bigint::BigUint;
+use starknet::{
+    accounts::Call,
+    core::{
+        types::{
+            contract::AbiEntry, BlockId, BlockTag, FieldElement, LegacyContractAbiEntry,
+            StarknetError,
+        },
+        utils::get_selector_from_name,
+    },
+    macros::{felt, selector},
+    providers::{Provider, ProviderError},
+};
+
+use crate::{
+    abi_cache::CachedAbi,
+    address_book::HARDCODED_ADDRESS_BOOK,
+    amount::{format_ether, DecimalStyle},
+    provider::ExtendedProvider,
+    token::fetch_balance,
+    utils::bigdecimal_to_felt,
+    warning::Warning,
+};
+
+const U128_MAX: FieldElement = felt!("0xffffffffffffffffffffffffffffffff");
+
+#[derive(Debug, Clone, Parser)]
+pub struct PreflightArgs {
+    #[clap(
+        long,
+        help = "Skip the checks for common mistakes before sending the transaction"
+    )]
+    no_preflight: bool,
+    #[clap(
+        long,
+        env = "STARKLI_WARN_FEE_ABOVE",
+        default_value = "0.05",
+        help = "Warn when the max fee in Ether (18 decimals) exceeds this amount"
+    )]
+    warn_fee_above: BigDecimal,
+    #[clap(
+        long,
+        help = "Skip checking that the account balance covers the max fee and the ETH sent by the \
+                calls"
+    )]
+    no_balance_check: bool,
+}
+
+impl PreflightArgs {
+    /// Runs cheap heuristics on the calls to be sent, printing a warning for each suspicious one.
+    /// Checks that fail to complete (e.g. due to network errors) are silently skipped, as they're
+    /// never meant to block a transaction.
+    pub async fn check_calls(&self, provider: &ExtendedProvider, calls: &[Call]) {
+        if self.no_preflight {
+            return;
+        }
+
+        for (ind, call) in calls.iter().enumerate() {
+            for (warning, message) in check_call(provider, call).await.into_iter() {
+                warning.print(format!("call #{}: {}", ind + 1, message));
+            }
+        }
+    }
+
+    pub fn check_max_fee(&self, max_fee: FieldElement) {
+        if self.no_preflight {
+            return;
+        }
+
+        let threshold = match bigdecimal_to_felt(&self.warn_fee_above, 18) {
+            Ok(threshold) => threshold,
+            Err(_) => return,
+        };
+
+        if max_fee > threshold {
+            Warning::HighFee.print(format!(
+                "max fee {} ETH is higher than {} ETH. Use --warn-fee-above to adjust the \
+                threshold.",
+                format_ether(max_fee),
+                self.warn_fee_above
+            ));
+        }
+    }
+
+    /// Fails early when the ETH balance of the sender doesn't cover the max fee plus the ETH sent
+    /// by the calls, which would otherwise only be caught by validation on-chain. Only direct
+    /// `transfer` calls are counted, and the check is skipped if the balance can't be fetched.
+    pub async fn check_balance(
+        &self,
+        provider: &ExtendedProvider,
+        sender: FieldElement,
+        max_fee: FieldElement,
+        calls: &[Call],
+    ) -> Result<()> {
+        if self.no_preflight || self.no_balance_check {
+            return Ok(());
+        }
+
+        let fee_token = provider.eth_token_address();
+        let balance = match fetch_balance(provider, fee_token, sender).await {
+            Ok(balance) => balance,
+            Err(_) => return Ok(()),
+        };
+
+        let transferred = calls
+            .iter()
+            .filter(|call| call.to == fee_token)
+            .filter_map(|call| transferred_amount(call, sender))
+            .sum::<BigUint>();
+        let required = felt_to_biguint(max_fee) + &transferred;
+
+        if balance < required {
+            let format = |amount: &BigUint| DecimalStyle::default().format(amount, 18);
+
+            anyhow::bail!(
+                "insufficient ETH balance: {:#064x} has {} ETH, but needs {} ETH ({} ETH max fee \
+                 + {} ETH transferred), {} ETH short. Use --no-balance-check to send anyway",
+                sender,
+                format(&balance),
+                format(&required),
+                format_ether(max_fee),
+                format(&transferred),
+                format(&(&required - &balance))
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The `u256` amount moved out of `sender` by an ERC20 transfer call, if it's one.
+fn transferred_amount(call: &Call, sender: FieldElement) -> Option<BigUint> {
+    let (low, high) = if call.selector == selector!("transfer") && call.calldata.len() == 3 {
+        (call.calldata[1], call.calldata[2])
+    } else if (call.selector == selector!("transfer_from")
+        || call.selector == selector!("transferFrom"))
+        && call.calldata.len() == 4
+        && call.calldata[0] == sender
+    {
+        (call.calldata[2], call.calldata[3])
+    } else {
+        return None;
+    };
+
+    Some((felt_to_biguint(high) << 128) + felt_to_biguint(low))
+}
+
+async fn check_call(provider: &ExtendedProvider, call: &Call) -> Vec<(Warning, String)> {
+    let mut warnings = vec![];
+
+    if let Some(message) = check_abi_arity(provider, call).await {
+        warnings.push((Warning::CalldataArity, message));
+    }
+
+    // Heuristics for token contracts
+    let recipient = if call.selector == selector!("transfer") && call.calldata.len() == 3 {
+        Some(call.calldata[0])
+    } else if (call.selector == selector!("transfer_from")
+        || call.selector == selector!("transferFrom"))
+        && call.calldata.len() == 4
+    {
+        Some(call.calldata[1])
+    } else {
+        None
+    };
+    if let Some(recipient) = recipient {
+        if is_deployed(provider, recipient).await == Some(false) {
+            warnings.push((
+                Warning::UndeployedRecipient,
+                format!(
+                    "sending tokens to {:#064x}, which is not a deployed contract",
+                    recipient
+                ),
+            ));
+        }
+    }
+
+    if call.selector == selector!("approve")
+        && call.calldata.len() == 3
+        && call.calldata[1] == U128_MAX
+        && call.calldata[2] == U128_MAX
+    {
+        let spender = call.calldata[0];
+        let is_known = HARDCODED_ADDRESS_BOOK
+            .iter()
+            .any(|entry| entry.address == spender);
+
+        if !is_known {
+            warnings.push((
+                Warning::UnlimitedApproval,
+                format!(
+                    "approving an unlimited amount to {:#064x}, which is not a known contract",
+                    spender
+                ),
+            ));
+        }
+    }
+
+    warnings
+}
+
+async fn is_deployed(provider: &ExtendedProvider, address: FieldElement) -> Option<bool> {
+    match provider
+        .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+        .await
+    {
+        Ok(_) => Some(true),
+        Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Some(false),
+        Err(_) => None,
+    }
+}
+
+/// Compares the calldata length against the function inputs in the contract ABI. Only inputs
+/// with statically-known sizes are supported; the check is skipped otherwise.
+async fn check_abi_arity(provider: &ExtendedProvider, call: &Call) -> Option<String> {
+    let class_abi = CachedAbi::of_contract(provider, call.to).await.ok()?;
+
+    let (name, expected_len) = match class_abi.sierra_abi().ok()? {
+        Some(abi) => {
+            let mut structs = HashMap::new();
+            let mut functions = vec![];
+            collect_abi_items(&abi, &mut structs, &mut functions);
+
+            let (name, input_types) = functions
+                .into_iter()
+                .find(|(name, _)| get_selector_from_name(name).ok() == Some(call.selector))?;
+
+            let mut expected_len = 0;
+            for input_type in input_types.iter() {
+                expected_len += static_size(input_type, &structs)?;
+            }
+
+            (name, expected_len)
+        }
+        None => {
+            let abi = serde_json::from_value::<Option<Vec<LegacyContractAbiEntry>>>(class_abi.abi)
+                .ok()
+                .flatten()?;
+            let function = abi.into_iter().find_map(|entry| match entry {
+                LegacyContractAbiEntry::Function(function)
+                    if get_selector_from_name(&function.name).ok() == Some(call.selector) =>
+                {
+                    Some(function)
+                }
+                _ => None,
+            })?;
+
+            if function.inputs.iter().any(|input| input.r#type != "felt") {
+                return None;
+            }
+
+            (function.name, function.inputs.len())
+        }
+    };
+
+    if expected_len != call.calldata.len() {
+        Some(format!(
+            "`{}` expects {} calldata element(s) according to the ABI, but {} provided",
+            name,
+            expected_len,
+            call.calldata.len()
+        ))
+    } else {
+        None
+    }
+}
+
+fn collect_abi_items(
+    entries: &[AbiEntry],
+    structs: &mut HashMap<String, Vec<String>>,
+    functions: &mut Vec<(String, Vec<String>)>,
+) {
+    for entry in entries.iter() {
+        match entry {
+            AbiEntry::Function(function) => functions.push((
+                function.name.clone(),
+                function
+                    .inputs
+                    .iter()
+                    .map(|input| input.r#type.clone())
+                    .collect(),
+            )),
+            AbiEntry::Struct(abi_struct) => {
+                structs.insert(
+                    abi_struct.name.clone(),
+                    abi_struct
+                        .members
+                        .iter()
+                        .map(|member| member.r#type.clone())
+                        .collect(),
+                );
+            }
+            AbiEntry::Interface(interface) => {
+                collect_abi_items(&interface.items, structs, functions)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn static_size(type_name: &str, structs: &HashMap<String, Vec<String>>) -> Option<usize> {
+    match type_name {
+        "core::felt252"
+        | "core::bool"
+        | "core::integer::u8"
+        | "core::integer::u16"
+        | "core::integer::u32"
+        | "core::integer::u64"
+        | "core::integer::u128"
+        | "core::integer::i8"
+        | "core::integer::i16"
+        | "core::integer::i32"
+        | "core::integer::i64"
+        | "core::integer::i128"
+        | "core::starknet::contract_address::ContractAddress"
+        | "core::starknet::class_hash::ClassHash"
+        | "core::starknet::eth_address::EthAddress" => Some(1),
+        "core::integer::u256" => Some(2),
+        _ => {
+            let mut size = 0;
+            for member_type in structs.get(type_name)?.iter() {
+                size += static_size(member_type, structs)?;
+            }
+            Some(size)
+        }
+    }
+}
+
+fn felt_to_biguint(value: FieldElement) -> BigUint {
+    BigUint::from_bytes_be(&value.to_bytes_be())
+}
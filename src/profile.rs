@@ -5,6 +5,7 @@ use std::{
 };
 
 use anyhow::Result;
+use bigdecimal::BigDecimal;
 use etcetera::{choose_base_strategy, BaseStrategy};
 use indexmap::IndexMap;
 use serde::{de::Visitor, Deserialize, Serialize};
@@ -14,6 +15,8 @@ use starknet::core::{
 };
 use url::Url;
 
+use crate::{default_block::DefaultBlock, warning::Warning};
+
 pub(crate) const DEFAULT_PROFILE_NAME: &str = "default";
 
 #[derive(Debug, Default)]
@@ -25,6 +28,20 @@ pub struct Profiles {
 #[serde(deny_unknown_fields)]
 pub struct Profile {
     pub networks: IndexMap<String, Network>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_account: Option<String>,
+    /// Network used when neither `--rpc` nor `--network` is supplied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_network: Option<String>,
+    /// Always use accessible output, as with `--accessible`.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub accessible: bool,
+    /// Block to read from when commands don't take one, as with `--default-block`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_block: Option<DefaultBlock>,
+    /// Warnings that are never shown, as with `--allow`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<Warning>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -40,11 +57,36 @@ pub struct Network {
     #[serde(default, skip_serializing_if = "is_false")]
     pub is_integration: bool,
     pub provider: NetworkProvider,
+    /// Address of the Universal Deployer Contract, for networks not using the canonical one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub udc_address: Option<FieldElement>,
+    /// Address of the ETH fee token, for networks not using the canonical one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eth_token_address: Option<FieldElement>,
+    /// Address of the STRK fee token, for networks not using the canonical one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strk_token_address: Option<FieldElement>,
+    /// Address of a Multicall-style aggregator contract used for batching read calls.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregator_address: Option<FieldElement>,
+    /// Transactions with a max fee above this amount in Ether are aborted unless forced.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_ether_amount",
+        deserialize_with = "deserialize_ether_amount"
+    )]
+    pub max_fee_ceiling: Option<BigDecimal>,
+    /// Account used on this network by commands sending to multiple networks at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
 }
 
 #[derive(Debug)]
 pub enum NetworkProvider {
     Rpc(Url),
+    /// An RPC URL kept in the encrypted secret store by name, for URLs containing API keys.
+    RpcSecret(String),
     Free(FreeProviderVendor),
 }
 
@@ -62,27 +104,32 @@ impl Profiles {
     pub fn load() -> Result<Self> {
         let path = Self::get_profiles_path()?;
 
-        let loaded_profiles = if path.exists() {
+        if path.exists() {
             let mut file = std::fs::File::open(path)?;
             let mut buffer = String::new();
             file.read_to_string(&mut buffer)?;
 
-            toml::from_str(&buffer)?
+            Self::from_toml(&buffer)
         } else {
-            Self::default()
-        };
+            Ok(Self::default())
+        }
+    }
+
+    /// Parses and validates the content of a profiles file.
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let profiles: Self = toml::from_str(content)?;
 
         // Custom profile to be supported in the future
-        if loaded_profiles.profiles.len() > 1
-            || (loaded_profiles.profiles.len() == 1
-                && !loaded_profiles.profiles.contains_key(DEFAULT_PROFILE_NAME))
+        if profiles.profiles.len() > 1
+            || (profiles.profiles.len() == 1
+                && !profiles.profiles.contains_key(DEFAULT_PROFILE_NAME))
         {
             anyhow::bail!(
                 "invalid profiles: only the `default` profile is supported at the moment"
             );
         }
 
-        Ok(loaded_profiles)
+        Ok(profiles)
     }
 
     pub fn save(&self) -> Result<()> {
@@ -101,7 +148,7 @@ impl Profiles {
         Ok(())
     }
 
-    fn get_config_folder() -> Result<PathBuf> {
+    pub fn get_config_folder() -> Result<PathBuf> {
         let strategy = choose_base_strategy()
             .map_err(|_| anyhow::anyhow!("unable to find the config directory"))?;
         let mut path = strategy.config_dir();
@@ -109,7 +156,60 @@ impl Profiles {
         Ok(path)
     }
 
-    fn get_profiles_path() -> Result<PathBuf> {
+    /// The folder for data that can be regenerated at any time, like compilation results.
+    pub fn get_cache_folder() -> Result<PathBuf> {
+        let strategy = choose_base_strategy()
+            .map_err(|_| anyhow::anyhow!("unable to find the cache directory"))?;
+        let mut path = strategy.cache_dir();
+        path.push("starkli");
+        Ok(path)
+    }
+
+    /// The folder where account config files can be stored to be referenced by name.
+    pub fn get_accounts_folder() -> Result<PathBuf> {
+        let mut path = Self::get_config_folder()?;
+        path.push("accounts");
+        Ok(path)
+    }
+
+    /// The folder where keystores can be stored to be referenced by name.
+    pub fn get_keystores_folder() -> Result<PathBuf> {
+        let mut path = Self::get_config_folder()?;
+        path.push("keystores");
+        Ok(path)
+    }
+
+    /// Gets the `default` profile, creating it if it doesn't exist yet.
+    pub fn default_profile_mut(&mut self) -> &mut Profile {
+        self.profiles
+            .entry(DEFAULT_PROFILE_NAME.to_owned())
+            .or_default()
+    }
+
+    /// The `default` profile as a TOML table, for reading and editing settings by key.
+    pub fn default_profile_table(&self) -> Result<toml::Table> {
+        let value = match self.profiles.get(DEFAULT_PROFILE_NAME) {
+            Some(profile) => toml::Value::try_from(profile)?,
+            None => toml::Value::try_from(Profile::default())?,
+        };
+
+        match value {
+            toml::Value::Table(table) => Ok(table),
+            _ => Err(anyhow::anyhow!("unexpected profile format")),
+        }
+    }
+
+    /// Replaces the `default` profile with one parsed from a TOML table, failing on unknown keys
+    /// and invalid values.
+    pub fn set_default_profile_table(&mut self, table: toml::Table) -> Result<()> {
+        let profile = Profile::deserialize(toml::Value::Table(table))
+            .map_err(|err| anyhow::anyhow!("invalid profile: {}", err))?;
+        self.profiles
+            .insert(DEFAULT_PROFILE_NAME.to_owned(), profile);
+        Ok(())
+    }
+
+    pub fn get_profiles_path() -> Result<PathBuf> {
         let mut path = Self::get_config_folder()?;
         path.push("profiles.toml");
         Ok(path)
@@ -154,6 +254,12 @@ impl Serialize for NetworkProvider {
         #[serde(transparent)]
         struct RpcVariant<'a>(&'a str);
 
+        #[derive(Serialize)]
+        struct RpcSecretVariant<'a> {
+            r#type: &'static str,
+            secret: &'a str,
+        }
+
         #[derive(Serialize)]
         struct FreeVariant<'a> {
             r#type: &'static str,
@@ -162,6 +268,11 @@ impl Serialize for NetworkProvider {
 
         match self {
             Self::Rpc(value) => RpcVariant(value.as_ref()).serialize(serializer),
+            Self::RpcSecret(value) => RpcSecretVariant {
+                r#type: "rpc_secret",
+                secret: value,
+            }
+            .serialize(serializer),
             Self::Free(value) => FreeVariant {
                 r#type: "free",
                 vendor: value,
@@ -187,6 +298,7 @@ impl<'de> Deserialize<'de> for NetworkProvider {
         #[serde(deny_unknown_fields, tag = "type", rename_all = "snake_case")]
         enum Tagged {
             Rpc(RpcVariant),
+            RpcSecret(RpcSecretVariant),
             Free(FreeVariant),
         }
 
@@ -197,6 +309,12 @@ impl<'de> Deserialize<'de> for NetworkProvider {
             url: Url,
         }
 
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct RpcSecretVariant {
+            secret: String,
+        }
+
         #[derive(Deserialize)]
         #[serde(deny_unknown_fields)]
         struct FreeVariant {
@@ -207,6 +325,7 @@ impl<'de> Deserialize<'de> for NetworkProvider {
             ShorthandOrTagged::Shorthand(value) => Self::Rpc(value),
             ShorthandOrTagged::Tagged(value) => match value {
                 Tagged::Rpc(value) => Self::Rpc(value.url),
+                Tagged::RpcSecret(value) => Self::RpcSecret(value.secret),
                 Tagged::Free(value) => Self::Free(value.vendor),
             },
         })
@@ -276,6 +395,29 @@ where
     deserializer.deserialize_str(ChainIdVisitor)
 }
 
+fn serialize_ether_amount<S>(value: &Option<BigDecimal>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match value {
+        Some(value) => serializer.serialize_str(&value.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_ether_amount<'de, D>(deserializer: D) -> Result<Option<BigDecimal>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    value.parse::<BigDecimal>().map(Some).map_err(|_| {
+        serde::de::Error::invalid_value(
+            serde::de::Unexpected::Str(&value),
+            &"decimal amount in Ether",
+        )
+    })
+}
+
 fn deserialize_url<'de, D>(deserializer: D) -> Result<Url, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -0,0 +1,169 @@
+use std::{
+    cell::{Cell, RefCell},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use serde_json::Value;
+use starknet::core::types::FieldElement;
+
+#[derive(Debug, Clone, Parser)]
+pub struct ProgressArgs {
+    #[clap(
+        long,
+        value_enum,
+        default_value = "text",
+        help = "Format of progress reporting. `json` emits one JSON event per line to stderr"
+    )]
+    progress: ProgressFormat,
+    #[clap(long, help = "Print wall-clock time spent in each stage when done")]
+    timings: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProgressFormat {
+    Text,
+    Json,
+}
+
+/// Reports the stages of long-running commands. In `json` mode, each stage is emitted as a single
+/// line of JSON on stderr, interleaved with the regular human-readable messages.
+///
+/// Each stage is timed from the end of the previous one. With `--timings`, a summary is printed
+/// when the reporter is dropped, so that commands returning early (e.g. `--estimate-only`) are
+/// covered as well.
+#[derive(Debug)]
+pub struct Progress {
+    format: ProgressFormat,
+    timings: bool,
+    started_at: Instant,
+    last_stage_at: Cell<Instant>,
+    stages: RefCell<Vec<(String, Duration)>>,
+}
+
+impl ProgressArgs {
+    pub fn reporter(&self) -> Progress {
+        let now = Instant::now();
+        Progress {
+            format: self.progress,
+            timings: self.timings,
+            started_at: now,
+            last_stage_at: Cell::new(now),
+            stages: RefCell::new(vec![]),
+        }
+    }
+}
+
+impl Progress {
+    pub fn event(&self, stage: &str, data: Value) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_stage_at.replace(now));
+        self.stages.borrow_mut().push((stage.to_owned(), elapsed));
+
+        if self.format != ProgressFormat::Json {
+            return;
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or_default();
+
+        eprintln!(
+            "{}",
+            serde_json::json!({
+                "stage": stage,
+                "timestamp": timestamp as u64,
+                "elapsed_ms": elapsed.as_millis() as u64,
+                "data": data,
+            })
+        );
+    }
+
+    pub fn class_parsed(&self, class_hash: FieldElement) {
+        self.event(
+            "parse",
+            serde_json::json!({ "class_hash": format!("{:#064x}", class_hash) }),
+        );
+    }
+
+    pub fn transaction_sent(&self, transaction_hash: FieldElement) {
+        self.event(
+            "send",
+            serde_json::json!({ "transaction_hash": format!("{:#064x}", transaction_hash) }),
+        );
+    }
+
+    pub fn transaction_confirmed(&self, transaction_hash: FieldElement) {
+        self.event(
+            "confirm",
+            serde_json::json!({ "transaction_hash": format!("{:#064x}", transaction_hash) }),
+        );
+    }
+
+    pub fn fee_estimated(&self, overall_fee: FieldElement) {
+        self.event(
+            "estimate",
+            serde_json::json!({ "overall_fee": format!("{:#x}", overall_fee) }),
+        );
+    }
+
+    fn print_timings(&self) {
+        let stages = self.stages.borrow();
+        let total = self.started_at.elapsed();
+
+        if self.format == ProgressFormat::Json {
+            eprintln!(
+                "{}",
+                serde_json::json!({
+                    "stage": "timings",
+                    "data": {
+                        "stages": stages
+                            .iter()
+                            .map(|(stage, elapsed)| serde_json::json!({
+                                "stage": stage,
+                                "elapsed_ms": elapsed.as_millis() as u64,
+                            }))
+                            .collect::<Vec<_>>(),
+                        "total_ms": total.as_millis() as u64,
+                    },
+                })
+            );
+            return;
+        }
+
+        let width = stages
+            .iter()
+            .map(|(stage, _)| stage.len())
+            .max()
+            .unwrap_or_default()
+            .max("total".len());
+
+        eprintln!("Timings:");
+        for (stage, elapsed) in stages.iter() {
+            eprintln!(
+                "  {:width$}  {}",
+                stage,
+                format_duration(*elapsed).bright_yellow()
+            );
+        }
+        eprintln!(
+            "  {:width$}  {}",
+            "total",
+            format_duration(total).bright_yellow()
+        );
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if self.timings {
+            self.print_timings();
+        }
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}s", duration.as_secs_f64())
+}
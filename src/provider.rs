@@ -1,27 +1,66 @@
-use std::str::FromStr;
+use std::{
+    future::Future,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+};
 
 use anyhow::Result;
 use async_trait::async_trait;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use colored::Colorize;
+use futures_util::future::join_all;
 use indexmap::map::Entry;
 use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 use starknet::{
     core::types::*,
     macros::short_string,
-    providers::{jsonrpc::HttpTransport, AnyProvider, JsonRpcClient, Provider, ProviderError},
+    providers::{
+        jsonrpc::{HttpTransport, HttpTransportError, JsonRpcClientError},
+        AnyProvider, JsonRpcClient, Provider, ProviderError,
+    },
 };
 use url::Url;
 
 use crate::{
+    chain_id::CHAIN_ID_KATANA,
+    http,
     network::Network,
     profile::{FreeProviderVendor, NetworkProvider, Profile, Profiles, DEFAULT_PROFILE_NAME},
+    resolve_cache::ResolveCache,
+    secrets::SecretStore,
+    token::{ETH_ADDRESS, STRK_ADDRESS},
+    upload::{self, LARGE_REQUEST_SIZE},
+    utils::bigdecimal_to_felt,
+    warning::Warning,
 };
 
 const CHAIN_ID_MAINNET: FieldElement = short_string!("SN_MAIN");
 const CHAIN_ID_GOERLI: FieldElement = short_string!("SN_GOERLI");
 const CHAIN_ID_SEPOLIA: FieldElement = short_string!("SN_SEPOLIA");
 
+/// Maximum number of requests sent in one JSON-RPC batch. Public endpoints commonly cap batch
+/// sizes, so larger sets are split into several batches.
+const MAX_BATCH_SIZE: usize = 50;
+
+/// JSON-RPC error code for methods the node doesn't implement.
+const METHOD_NOT_FOUND_CODE: i64 = -32601;
+
+/// The JSON-RPC spec version (major, minor) that the bundled `starknet-rs` implements.
+const SUPPORTED_SPEC_VERSION: (u64, u64) = (0, 6);
+
+/// The canonical UDC address: 0x041a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf.
+const DEFAULT_UDC_ADDRESS: FieldElement = FieldElement::from_mont([
+    15144800532519055890,
+    15685625669053253235,
+    9333317513348225193,
+    121672436446604875,
+]);
+
 #[derive(Debug, Clone, Parser)]
 pub struct ProviderArgs {
     #[clap(
@@ -32,6 +71,86 @@ pub struct ProviderArgs {
     rpc: Option<Url>,
     #[clap(long = "network", env = "STARKNET_NETWORK", help = "Starknet network")]
     network: Option<String>,
+    #[clap(
+        long,
+        env = "STARKNET_COMPAT",
+        value_enum,
+        default_value = "auto",
+        help = "Adapt to quirks of a specific node implementation"
+    )]
+    compat: CompatMode,
+}
+
+/// Runs a read-only query against multiple networks at once.
+#[derive(Debug, Clone, Parser)]
+pub struct NetworksArgs {
+    #[clap(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["rpc", "network"],
+        help = "Comma-separated list of networks to run the query against concurrently"
+    )]
+    networks: Vec<String>,
+}
+
+/// Node implementations deviating from the JSON-RPC specification in ways Starkli can work around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompatMode {
+    /// Detect the node implementation from the chain ID where possible.
+    Auto,
+    /// Assume a spec-compliant node.
+    None,
+    Katana,
+    Madara,
+}
+
+impl NetworksArgs {
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty()
+    }
+
+    /// Runs `query` against each network concurrently, printing the results labeled with the
+    /// network names in the order the networks are given. Failures are reported per network
+    /// instead of aborting the others.
+    pub async fn fan_out<F, Fut>(&self, query: F) -> Result<()>
+    where
+        F: Fn(Arc<ExtendedProvider>) -> Fut,
+        Fut: Future<Output = Result<String>> + Send + 'static,
+    {
+        let mut handles = vec![];
+        for network in self.networks.iter() {
+            let provider = Arc::new(ProviderArgs::resolve_network(network)?);
+            handles.push((network, tokio::spawn(query(provider))));
+        }
+
+        let width = self
+            .networks
+            .iter()
+            .map(|network| network.len())
+            .max()
+            .unwrap_or_default();
+
+        let mut failed = 0;
+        for (network, handle) in handles.into_iter() {
+            match handle.await? {
+                Ok(result) => println!("{:width$}  {}", network, result),
+                Err(err) => {
+                    failed += 1;
+                    println!(
+                        "{:width$}  {}",
+                        network,
+                        format!("error: {}", err).bright_magenta()
+                    );
+                }
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("query failed on {} network(s)", failed);
+        }
+
+        Ok(())
+    }
 }
 
 /// We need this because integration network has the same chain ID as `goerli`. We would otherwise
@@ -40,43 +159,84 @@ pub struct ProviderArgs {
 /// need this.
 pub struct ExtendedProvider {
     provider: AnyProvider,
+    rpc_url: Url,
     is_integration: bool,
+    contracts: NetworkContracts,
+    max_fee_ceiling: Option<FieldElement>,
+    profile_chain_id: Option<FieldElement>,
+    compat: CompatMode,
+    detected_compat: OnceLock<CompatMode>,
+    batch_rejected: AtomicBool,
+}
+
+/// System contract addresses that can be overridden for custom networks such as appchains.
+#[derive(Debug, Default, Clone)]
+pub struct NetworkContracts {
+    pub udc_address: Option<FieldElement>,
+    pub eth_token_address: Option<FieldElement>,
+    pub strk_token_address: Option<FieldElement>,
+    pub aggregator_address: Option<FieldElement>,
 }
 
 impl ProviderArgs {
+    /// The options as command line arguments, for forwarding to another command.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(rpc) = &self.rpc {
+            args.push("--rpc".to_owned());
+            args.push(rpc.to_string());
+        }
+        if let Some(network) = &self.network {
+            args.push("--network".to_owned());
+            args.push(network.to_owned());
+        }
+        if let Some(compat) = self.compat.to_possible_value() {
+            args.push("--compat".to_owned());
+            args.push(compat.get_name().to_owned());
+        }
+        args
+    }
+
     pub fn into_provider(self) -> Result<ExtendedProvider> {
-        Ok(match (self.rpc, self.network) {
-            (Some(rpc), None) => ExtendedProvider::new(
-                AnyProvider::JsonRpcHttp(JsonRpcClient::new(HttpTransport::new(rpc))),
-                false,
-            ),
+        let compat = self.compat;
+
+        let provider = match (self.rpc, self.network) {
+            (Some(rpc), None) => ExtendedProvider::new(rpc, false),
             (Some(rpc), Some(_)) => {
-                eprintln!(
-                    "{}",
-                    "WARNING: the --rpc option and the STARKNET_RPC environment variable take \
-                    precedence over the --network option and the STARKNET_NETWORK environment \
-                    variable. See https://book.starkli.rs/providers for more details."
-                        .bright_magenta()
+                Warning::RpcPrecedence.print(
+                    "the --rpc option and the STARKNET_RPC environment variable take precedence \
+                    over the --network option and the STARKNET_NETWORK environment variable. See \
+                    https://book.starkli.rs/providers for more details.",
                 );
 
-                ExtendedProvider::new(
-                    AnyProvider::JsonRpcHttp(JsonRpcClient::new(HttpTransport::new(rpc))),
-                    false,
-                )
+                ExtendedProvider::new(rpc, false)
             }
             (None, Some(network)) => Self::resolve_network(&network)?,
             (None, None) => {
-                eprintln!(
-                    "{}",
-                    "WARNING: you're using neither --rpc (STARKNET_RPC) nor --network \
-                    (STARKNET_NETWORK). The `goerli` network is used by default. See \
-                    https://book.starkli.rs/providers for more details."
-                        .bright_magenta()
+                let default_network = Profiles::load()?
+                    .profiles
+                    .get(DEFAULT_PROFILE_NAME)
+                    .and_then(|profile| profile.default_network.clone());
+                if let Some(network) = default_network {
+                    let provider = Self::resolve_network(&network)?.with_compat(compat);
+                    provider.check_spec_version();
+                    return Ok(provider);
+                }
+
+                Warning::ImplicitNetwork.print(
+                    "you're using neither --rpc (STARKNET_RPC) nor --network (STARKNET_NETWORK). \
+                    The `goerli` network is used by default. See \
+                    https://book.starkli.rs/providers for more details.",
                 );
 
                 Self::resolve_network("goerli")?
             }
-        })
+        };
+
+        let provider = provider.with_compat(compat);
+        provider.check_spec_version();
+
+        Ok(provider)
     }
 
     pub fn resolve_network(network: &str) -> Result<ExtendedProvider> {
@@ -95,6 +255,11 @@ impl ProviderArgs {
 
                 entry.insert(Profile {
                     networks: Default::default(),
+                    default_account: None,
+                    default_network: None,
+                    accessible: false,
+                    default_block: None,
+                    allow: vec![],
                 })
             }
         };
@@ -159,6 +324,12 @@ impl ProviderArgs {
                                         provider: NetworkProvider::Free(choose_vendor(
                                             &builtin_network,
                                         )),
+                                        udc_address: None,
+                                        eth_token_address: None,
+                                        strk_token_address: None,
+                                        aggregator_address: None,
+                                        max_fee_ceiling: None,
+                                        account: None,
                                     },
                                     Network::Goerli => crate::profile::Network {
                                         name: Some("Starknet Goerli Testnet".into()),
@@ -167,6 +338,12 @@ impl ProviderArgs {
                                         provider: NetworkProvider::Free(choose_vendor(
                                             &builtin_network,
                                         )),
+                                        udc_address: None,
+                                        eth_token_address: None,
+                                        strk_token_address: None,
+                                        aggregator_address: None,
+                                        max_fee_ceiling: None,
+                                        account: None,
                                     },
                                     Network::Sepolia => crate::profile::Network {
                                         name: Some("Starknet Sepolia Testnet".into()),
@@ -175,6 +352,12 @@ impl ProviderArgs {
                                         provider: NetworkProvider::Free(choose_vendor(
                                             &builtin_network,
                                         )),
+                                        udc_address: None,
+                                        eth_token_address: None,
+                                        strk_token_address: None,
+                                        aggregator_address: None,
+                                        max_fee_ceiling: None,
+                                        account: None,
                                     },
                                     Network::GoerliIntegration | Network::SepoliaIntegration => {
                                         anyhow::bail!(
@@ -208,6 +391,8 @@ impl ProviderArgs {
 
         let rpc_url = match &matched_network.provider {
             NetworkProvider::Rpc(rpc) => rpc.to_owned(),
+            NetworkProvider::RpcSecret(name) => Url::parse(SecretStore::load(name)?.trim())
+                .map_err(|_| anyhow::anyhow!("secret {} is not a valid RPC URL", name))?,
             NetworkProvider::Free(vendor) => {
                 let url = match vendor {
                     FreeProviderVendor::Blast => {
@@ -251,10 +436,21 @@ impl ProviderArgs {
             }
         };
 
-        let provider = ExtendedProvider::new(
-            AnyProvider::JsonRpcHttp(JsonRpcClient::new(HttpTransport::new(rpc_url))),
-            matched_network.is_integration,
-        );
+        let provider = ExtendedProvider::new(rpc_url, matched_network.is_integration)
+            .with_contracts(NetworkContracts {
+                udc_address: matched_network.udc_address,
+                eth_token_address: matched_network.eth_token_address,
+                strk_token_address: matched_network.strk_token_address,
+                aggregator_address: matched_network.aggregator_address,
+            })
+            .with_max_fee_ceiling(
+                matched_network
+                    .max_fee_ceiling
+                    .as_ref()
+                    .map(|ceiling| bigdecimal_to_felt(ceiling, 18))
+                    .transpose()?,
+            )
+            .with_profile_chain_id(matched_network.chain_id);
 
         if made_changes {
             profiles.save()?;
@@ -265,13 +461,383 @@ impl ProviderArgs {
 }
 
 impl ExtendedProvider {
-    pub fn new(provider: AnyProvider, is_integration: bool) -> Self {
+    pub fn new(rpc_url: Url, is_integration: bool) -> Self {
         Self {
-            provider,
+            provider: AnyProvider::JsonRpcHttp(JsonRpcClient::new(HttpTransport::new_with_client(
+                rpc_url.clone(),
+                http::shared_client(),
+            ))),
+            rpc_url,
             is_integration,
+            contracts: Default::default(),
+            max_fee_ceiling: None,
+            profile_chain_id: None,
+            compat: CompatMode::Auto,
+            detected_compat: OnceLock::new(),
+            batch_rejected: AtomicBool::new(false),
+        }
+    }
+
+    pub fn with_compat(mut self, compat: CompatMode) -> Self {
+        self.compat = compat;
+        self
+    }
+
+    pub fn with_max_fee_ceiling(mut self, max_fee_ceiling: Option<FieldElement>) -> Self {
+        self.max_fee_ceiling = max_fee_ceiling;
+        self
+    }
+
+    /// The highest max fee in Wei allowed without `--force`, as configured for the network.
+    pub fn max_fee_ceiling(&self) -> Option<FieldElement> {
+        self.max_fee_ceiling
+    }
+
+    pub fn with_profile_chain_id(mut self, chain_id: FieldElement) -> Self {
+        self.profile_chain_id = Some(chain_id);
+        self
+    }
+
+    /// The chain ID configured for the network in the profile. Only available when the provider
+    /// is resolved from a network instead of a raw RPC URL.
+    pub fn profile_chain_id(&self) -> Option<FieldElement> {
+        self.profile_chain_id
+    }
+
+    /// Resolves `CompatMode::Auto` into the actual node implementation. Only Katana can be
+    /// recognized by its chain ID; any other node is assumed to be spec-compliant.
+    pub async fn compat_mode(&self) -> CompatMode {
+        if self.compat != CompatMode::Auto {
+            return self.compat;
+        }
+
+        if let Some(detected) = self.detected_compat.get() {
+            return *detected;
+        }
+
+        // The chain ID from the profile or the resolution cache saves a request when available
+        let chain_id = match self.profile_chain_id {
+            Some(chain_id) => Ok(chain_id),
+            None => self.chain_id().await,
+        };
+        let detected = match chain_id {
+            Ok(chain_id) if chain_id == CHAIN_ID_KATANA => CompatMode::Katana,
+            _ => CompatMode::None,
+        };
+
+        // It's OK if another task set it first
+        let _ = self.detected_compat.set(detected);
+
+        detected
+    }
+
+    /// Compares the JSON-RPC spec version served by the node against the one this build speaks,
+    /// printing a warning on mismatch. The version is cached per endpoint like chain IDs, so that
+    /// the check usually costs no request. Otherwise it's fetched in the background so as not to
+    /// delay the command.
+    fn check_spec_version(&self) {
+        if !self.is_rpc() {
+            return;
+        }
+
+        if let Some(spec_version) = ResolveCache::spec_version(&self.rpc_url) {
+            warn_on_spec_version_mismatch(&spec_version);
+            return;
+        }
+
+        let handle = match tokio::runtime::Handle::try_current() {
+            Ok(handle) => handle,
+            Err(_) => return,
+        };
+        let rpc_url = self.rpc_url.clone();
+        handle.spawn(async move {
+            let client = JsonRpcClient::new(HttpTransport::new_with_client(
+                rpc_url.clone(),
+                http::shared_client(),
+            ));
+
+            // Nodes too old to report a spec version are not worth a warning on every command
+            if let Ok(spec_version) = client.spec_version().await {
+                ResolveCache::set_spec_version(&rpc_url, &spec_version);
+                warn_on_spec_version_mismatch(&spec_version);
+            }
+        });
+    }
+
+    /// Katana mines a block per transaction and Madara does not maintain a pending block, so
+    /// `pending` is mapped to `latest` for both.
+    async fn adapt_block_id(&self, block_id: &BlockId) -> BlockId {
+        match block_id {
+            BlockId::Tag(BlockTag::Pending) => match self.compat {
+                CompatMode::None => *block_id,
+                _ => match self.compat_mode().await {
+                    CompatMode::Katana | CompatMode::Madara => BlockId::Tag(BlockTag::Latest),
+                    CompatMode::Auto | CompatMode::None => *block_id,
+                },
+            },
+            _ => *block_id,
         }
     }
 
+    pub fn with_contracts(mut self, contracts: NetworkContracts) -> Self {
+        self.contracts = contracts;
+        self
+    }
+
+    pub fn udc_address(&self) -> FieldElement {
+        self.contracts.udc_address.unwrap_or(DEFAULT_UDC_ADDRESS)
+    }
+
+    pub fn eth_token_address(&self) -> FieldElement {
+        self.contracts.eth_token_address.unwrap_or(ETH_ADDRESS)
+    }
+
+    pub fn strk_token_address(&self) -> FieldElement {
+        self.contracts.strk_token_address.unwrap_or(STRK_ADDRESS)
+    }
+
+    /// There's no canonical aggregator deployment, so this is only available when configured.
+    pub fn aggregator_address(&self) -> Option<FieldElement> {
+        self.contracts.aggregator_address
+    }
+
+    /// The JSON-RPC endpoint, for sending requests not covered by the `Provider` trait.
+    pub fn rpc_url(&self) -> &Url {
+        &self.rpc_url
+    }
+
+    /// Calls several contracts, sending the calls as JSON-RPC batches unless batching is disabled
+    /// or rejected by the endpoint. Results are in the same order as the calls.
+    pub async fn batch_call(
+        &self,
+        calls: &[FunctionCall],
+        block_id: BlockId,
+    ) -> Vec<Result<Vec<FieldElement>>> {
+        let block_id = self.adapt_block_id(&block_id).await;
+        let requests = calls
+            .iter()
+            .map(|call| {
+                (
+                    "starknet_call",
+                    serde_json::json!({
+                        "request": {
+                            "contract_address": format!("{:#x}", call.contract_address),
+                            "entry_point_selector": format!("{:#x}", call.entry_point_selector),
+                            "calldata": call
+                                .calldata
+                                .iter()
+                                .map(|item| format!("{:#x}", item))
+                                .collect::<Vec<_>>(),
+                        },
+                        "block_id": block_id_param(&block_id),
+                    }),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        match self.send_batches(&requests).await {
+            Some(responses) => responses
+                .into_iter()
+                .map(|response| {
+                    serde_json::from_value::<Vec<String>>(response?)?
+                        .iter()
+                        .map(|item| Ok(FieldElement::from_hex_be(item)?))
+                        .collect()
+                })
+                .collect(),
+            None => {
+                join_all(
+                    calls
+                        .iter()
+                        .map(|call| async move { Ok(self.call(call.clone(), block_id).await?) }),
+                )
+                .await
+            }
+        }
+    }
+
+    /// Looks up the classes of several contracts, batching the requests like `batch_call`.
+    pub async fn batch_class_hash_at(
+        &self,
+        addresses: &[FieldElement],
+        block_id: BlockId,
+    ) -> Vec<Result<FieldElement>> {
+        let block_id = self.adapt_block_id(&block_id).await;
+        let requests = addresses
+            .iter()
+            .map(|address| {
+                (
+                    "starknet_getClassHashAt",
+                    serde_json::json!({
+                        "block_id": block_id_param(&block_id),
+                        "contract_address": format!("{:#x}", address),
+                    }),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        match self.send_batches(&requests).await {
+            Some(responses) => responses
+                .into_iter()
+                .map(|response| {
+                    Ok(FieldElement::from_hex_be(
+                        &serde_json::from_value::<String>(response?)?,
+                    )?)
+                })
+                .collect(),
+            None => {
+                join_all(addresses.iter().map(|address| async move {
+                    Ok(self.get_class_hash_at(block_id, *address).await?)
+                }))
+                .await
+            }
+        }
+    }
+
+    /// Sends the requests in batches of up to `MAX_BATCH_SIZE`. Returns `None` when the requests
+    /// should be sent individually instead: with `--no-rpc-batch`, when there's nothing to gain
+    /// from batching, or when the endpoint doesn't accept batches.
+    async fn send_batches(&self, requests: &[(&str, Value)]) -> Option<Vec<Result<Value>>> {
+        if !http::batching_enabled()
+            || !self.is_rpc()
+            || requests.len() < 2
+            || self.batch_rejected.load(Ordering::Relaxed)
+        {
+            return None;
+        }
+
+        let mut responses = Vec::with_capacity(requests.len());
+        for chunk in requests.chunks(MAX_BATCH_SIZE) {
+            match self.send_batch(chunk).await {
+                Ok(chunk_responses) => responses.extend(chunk_responses),
+                Err(err) => {
+                    // Requests already answered are simply sent again, as they're all read-only
+                    log::debug!("JSON-RPC batch failed: {}", err);
+                    if !self.batch_rejected.swap(true, Ordering::Relaxed) {
+                        eprintln!(
+                            "{}",
+                            "WARNING: the RPC endpoint does not accept batched requests. Sending \
+                            requests individually instead. Use --no-rpc-batch to skip batching \
+                            for this endpoint."
+                                .bright_magenta()
+                        );
+                    }
+                    return None;
+                }
+            }
+        }
+
+        Some(responses)
+    }
+
+    /// Sends one batch, matching the responses to the requests by ID. An error means the batch as
+    /// a whole failed, while errors of individual requests are returned in their slots.
+    async fn send_batch(&self, requests: &[(&str, Value)]) -> Result<Vec<Result<Value>>> {
+        let body = requests
+            .iter()
+            .enumerate()
+            .map(|(id, (method, params))| {
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": method,
+                    "params": params,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let response = http::shared_client()
+            .post(self.rpc_url.clone())
+            .header("Content-Type", "application/json")
+            .body(serde_json::to_string(&body)?)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("unexpected HTTP status: {}", response.status());
+        }
+
+        // Endpoints without batch support usually reply with a single error object
+        let items = match serde_json::from_str::<Value>(&response.text().await?)? {
+            Value::Array(items) => items,
+            response => anyhow::bail!("unexpected batch response: {}", response),
+        };
+
+        let mut slots = (0..requests.len()).map(|_| None).collect::<Vec<_>>();
+        for mut item in items.into_iter() {
+            let slot = item
+                .get("id")
+                .and_then(|id| id.as_u64())
+                .and_then(|id| slots.get_mut(id as usize))
+                .ok_or_else(|| anyhow::anyhow!("unexpected response ID in batch"))?;
+
+            *slot = Some(match item.get("error") {
+                Some(error) => Err(rpc_error(error)),
+                None => Ok(item
+                    .get_mut("result")
+                    .map(|result| result.take())
+                    .unwrap_or_default()),
+            });
+        }
+
+        slots
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| anyhow::anyhow!("missing responses in batch"))
+    }
+
+    /// Sends requests carrying large classes through the upload path, which shows progress and
+    /// compresses the body. Returns `None` for smaller requests, and whenever the upload path can't
+    /// produce a result, leaving it to the regular client to send the request and report errors.
+    async fn send_if_large<T>(
+        &self,
+        label: &str,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<Option<T>, ProviderError>
+    where
+        T: DeserializeOwned,
+    {
+        let params = match params {
+            Some(params) if self.is_rpc() => params,
+            _ => return Ok(None),
+        };
+        match serde_json::to_vec(&params) {
+            Ok(body) if body.len() >= LARGE_REQUEST_SIZE => {}
+            _ => return Ok(None),
+        }
+
+        let mut response =
+            match upload::send_large_request(&self.rpc_url, label, method, params).await {
+                Ok(response) => response,
+                Err(err) => {
+                    eprintln!(
+                        "{}",
+                        format!(
+                            "WARNING: upload failed ({}). Retrying without progress reporting...",
+                            err
+                        )
+                        .bright_magenta()
+                    );
+                    return Ok(None);
+                }
+            };
+
+        if let Some(error) = response.get("error") {
+            return match starknet_error(error) {
+                Some(err) => Err(ProviderError::StarknetError(err)),
+                None => {
+                    log::debug!("unrecognized error from upload: {}", error);
+                    Ok(None)
+                }
+            };
+        }
+
+        Ok(response
+            .get_mut("result")
+            .map(|result| result.take())
+            .and_then(|result| serde_json::from_value(result).ok()))
+    }
+
     pub fn is_rpc(&self) -> bool {
         matches!(self.provider, AnyProvider::JsonRpcHttp(_))
     }
@@ -295,6 +861,7 @@ impl Provider for ExtendedProvider {
     where
         B: AsRef<BlockId> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::get_block_with_tx_hashes(&self.provider, block_id).await
     }
 
@@ -305,6 +872,7 @@ impl Provider for ExtendedProvider {
     where
         B: AsRef<BlockId> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::get_block_with_txs(&self.provider, block_id).await
     }
 
@@ -315,6 +883,7 @@ impl Provider for ExtendedProvider {
     where
         B: AsRef<BlockId> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::get_state_update(&self.provider, block_id).await
     }
 
@@ -329,6 +898,7 @@ impl Provider for ExtendedProvider {
         K: AsRef<FieldElement> + Send + Sync,
         B: AsRef<BlockId> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::get_storage_at(&self.provider, contract_address, key, block_id)
             .await
     }
@@ -340,7 +910,30 @@ impl Provider for ExtendedProvider {
     where
         H: AsRef<FieldElement> + Send + Sync,
     {
-        <AnyProvider as Provider>::get_transaction_status(&self.provider, transaction_hash).await
+        let transaction_hash = transaction_hash.as_ref();
+        match <AnyProvider as Provider>::get_transaction_status(&self.provider, transaction_hash)
+            .await
+        {
+            // Derived from the receipt instead, which only misses transactions not yet executed
+            Err(err) if self.compat != CompatMode::None && is_method_not_found(&err) => {
+                let receipt = <AnyProvider as Provider>::get_transaction_receipt(
+                    &self.provider,
+                    transaction_hash,
+                )
+                .await?;
+                let execution_status = receipt.execution_result().status();
+
+                Ok(match receipt.finality_status() {
+                    TransactionFinalityStatus::AcceptedOnL2 => {
+                        TransactionStatus::AcceptedOnL2(execution_status)
+                    }
+                    TransactionFinalityStatus::AcceptedOnL1 => {
+                        TransactionStatus::AcceptedOnL1(execution_status)
+                    }
+                })
+            }
+            result => result,
+        }
     }
 
     async fn get_transaction_by_hash<H>(
@@ -361,6 +954,7 @@ impl Provider for ExtendedProvider {
     where
         B: AsRef<BlockId> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::get_transaction_by_block_id_and_index(
             &self.provider,
             block_id,
@@ -388,6 +982,7 @@ impl Provider for ExtendedProvider {
         B: AsRef<BlockId> + Send + Sync,
         H: AsRef<FieldElement> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::get_class(&self.provider, block_id, class_hash).await
     }
 
@@ -400,6 +995,7 @@ impl Provider for ExtendedProvider {
         B: AsRef<BlockId> + Send + Sync,
         A: AsRef<FieldElement> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::get_class_hash_at(&self.provider, block_id, contract_address)
             .await
     }
@@ -413,6 +1009,7 @@ impl Provider for ExtendedProvider {
         B: AsRef<BlockId> + Send + Sync,
         A: AsRef<FieldElement> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::get_class_at(&self.provider, block_id, contract_address).await
     }
 
@@ -420,6 +1017,7 @@ impl Provider for ExtendedProvider {
     where
         B: AsRef<BlockId> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::get_block_transaction_count(&self.provider, block_id).await
     }
 
@@ -428,6 +1026,7 @@ impl Provider for ExtendedProvider {
         R: AsRef<FunctionCall> + Send + Sync,
         B: AsRef<BlockId> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::call(&self.provider, request, block_id).await
     }
 
@@ -442,6 +1041,29 @@ impl Provider for ExtendedProvider {
         S: AsRef<[SimulationFlagForEstimateFee]> + Send + Sync,
         B: AsRef<BlockId> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
+
+        let params = serde_json::to_value(request.as_ref())
+            .ok()
+            .zip(serde_json::to_value(simulation_flags.as_ref()).ok())
+            .map(|(request, simulation_flags)| {
+                serde_json::json!({
+                    "request": request,
+                    "simulation_flags": simulation_flags,
+                    "block_id": block_id_param(&block_id),
+                })
+            });
+        if let Some(estimates) = self
+            .send_if_large(
+                "Uploading transaction for fee estimation",
+                "starknet_estimateFee",
+                params,
+            )
+            .await?
+        {
+            return Ok(estimates);
+        }
+
         <AnyProvider as Provider>::estimate_fee(&self.provider, request, simulation_flags, block_id)
             .await
     }
@@ -455,6 +1077,7 @@ impl Provider for ExtendedProvider {
         M: AsRef<MsgFromL1> + Send + Sync,
         B: AsRef<BlockId> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::estimate_message_fee(&self.provider, message, block_id).await
     }
 
@@ -463,11 +1086,35 @@ impl Provider for ExtendedProvider {
     }
 
     async fn block_hash_and_number(&self) -> Result<BlockHashAndNumber, ProviderError> {
-        <AnyProvider as Provider>::block_hash_and_number(&self.provider).await
+        match <AnyProvider as Provider>::block_hash_and_number(&self.provider).await {
+            // Taken from the latest block instead, which is never pending
+            Err(err) if self.compat != CompatMode::None && is_method_not_found(&err) => {
+                match <AnyProvider as Provider>::get_block_with_tx_hashes(
+                    &self.provider,
+                    BlockId::Tag(BlockTag::Latest),
+                )
+                .await?
+                {
+                    MaybePendingBlockWithTxHashes::Block(block) => Ok(BlockHashAndNumber {
+                        block_hash: block.block_hash,
+                        block_number: block.block_number,
+                    }),
+                    MaybePendingBlockWithTxHashes::PendingBlock(_) => Err(err),
+                }
+            }
+            result => result,
+        }
     }
 
     async fn chain_id(&self) -> Result<FieldElement, ProviderError> {
-        <AnyProvider as Provider>::chain_id(&self.provider).await
+        if let Some(chain_id) = ResolveCache::chain_id(&self.rpc_url) {
+            return Ok(chain_id);
+        }
+
+        let chain_id = <AnyProvider as Provider>::chain_id(&self.provider).await?;
+        ResolveCache::set_chain_id(&self.rpc_url, chain_id);
+
+        Ok(chain_id)
     }
 
     async fn syncing(&self) -> Result<SyncStatusType, ProviderError> {
@@ -498,6 +1145,7 @@ impl Provider for ExtendedProvider {
         B: AsRef<BlockId> + Send + Sync,
         A: AsRef<FieldElement> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::get_nonce(&self.provider, block_id, contract_address).await
     }
 
@@ -518,6 +1166,23 @@ impl Provider for ExtendedProvider {
     where
         D: AsRef<BroadcastedDeclareTransaction> + Send + Sync,
     {
+        let params =
+            serde_json::to_value(declare_transaction.as_ref())
+                .ok()
+                .map(|declare_transaction| {
+                    serde_json::json!({ "declare_transaction": declare_transaction })
+                });
+        if let Some(result) = self
+            .send_if_large(
+                "Uploading declare transaction",
+                "starknet_addDeclareTransaction",
+                params,
+            )
+            .await?
+        {
+            return Ok(result);
+        }
+
         <AnyProvider as Provider>::add_declare_transaction(&self.provider, declare_transaction)
             .await
     }
@@ -557,6 +1222,7 @@ impl Provider for ExtendedProvider {
         T: AsRef<[BroadcastedTransaction]> + Send + Sync,
         S: AsRef<[SimulationFlag]> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::simulate_transactions(
             &self.provider,
             block_id,
@@ -573,14 +1239,102 @@ impl Provider for ExtendedProvider {
     where
         B: AsRef<BlockId> + Send + Sync,
     {
+        let block_id = self.adapt_block_id(block_id.as_ref()).await;
         <AnyProvider as Provider>::trace_block_transactions(&self.provider, block_id).await
     }
 }
 
-fn randome_free_provider(choices: &[FreeProviderVendor]) -> FreeProviderVendor {
+pub fn randome_free_provider(choices: &[FreeProviderVendor]) -> FreeProviderVendor {
     let mut rng = StdRng::from_entropy();
 
     // We never call this function with an empty slice
     let index = rng.gen_range(0..choices.len());
     choices[index]
 }
+
+/// `BlockId` as a raw JSON-RPC parameter, for requests sent outside the `Provider` trait.
+fn block_id_param(block_id: &BlockId) -> Value {
+    match block_id {
+        BlockId::Hash(hash) => serde_json::json!({ "block_hash": format!("{:#x}", hash) }),
+        BlockId::Number(number) => serde_json::json!({ "block_number": number }),
+        BlockId::Tag(BlockTag::Latest) => Value::String("latest".into()),
+        BlockId::Tag(BlockTag::Pending) => Value::String("pending".into()),
+    }
+}
+
+fn rpc_error(error: &Value) -> anyhow::Error {
+    let message = error
+        .get("message")
+        .and_then(|message| message.as_str())
+        .unwrap_or("unknown error");
+    match error.get("data") {
+        Some(data) => anyhow::anyhow!("JSON-RPC error: {} ({})", message, data),
+        None => anyhow::anyhow!("JSON-RPC error: {}", message),
+    }
+}
+
+/// Maps the JSON-RPC errors that declares and fee estimations commonly fail with, for requests
+/// sent through the upload path. Anything else is left to the regular client.
+fn starknet_error(error: &Value) -> Option<StarknetError> {
+    let data = error.get("data");
+    Some(match error.get("code")?.as_i64()? {
+        41 => StarknetError::TransactionExecutionError(serde_json::from_value(data?.clone()).ok()?),
+        51 => StarknetError::ClassAlreadyDeclared,
+        52 => StarknetError::InvalidTransactionNonce,
+        53 => StarknetError::InsufficientMaxFee,
+        54 => StarknetError::InsufficientAccountBalance,
+        55 => StarknetError::ValidationFailure(
+            data.and_then(|data| data.as_str())
+                .unwrap_or_default()
+                .to_owned(),
+        ),
+        56 => StarknetError::CompilationFailed,
+        57 => StarknetError::ContractClassSizeIsTooLarge,
+        58 => StarknetError::NonAccount,
+        60 => StarknetError::CompiledClassHashMismatch,
+        61 => StarknetError::UnsupportedTxVersion,
+        62 => StarknetError::UnsupportedContractClassVersion,
+        _ => return None,
+    })
+}
+
+/// Nodes with `--compat` quirks lack some methods, which is reported with the standard JSON-RPC
+/// error code.
+fn is_method_not_found(err: &ProviderError) -> bool {
+    match err {
+        ProviderError::Other(err) => matches!(
+            err.as_any()
+                .downcast_ref::<JsonRpcClientError<HttpTransportError>>(),
+            Some(JsonRpcClientError::JsonRpcError(err)) if err.code == METHOD_NOT_FOUND_CODE
+        ),
+        _ => false,
+    }
+}
+
+fn warn_on_spec_version_mismatch(spec_version: &str) {
+    let mut parts = spec_version.trim_start_matches('v').split('.');
+    let mut next_part = || {
+        parts
+            .next()
+            .and_then(|part| part.parse::<u64>().ok())
+            .unwrap_or_default()
+    };
+    let version = (next_part(), next_part());
+
+    let hint = match version.cmp(&SUPPORTED_SPEC_VERSION) {
+        std::cmp::Ordering::Less => {
+            "Upgrade the node, or use an endpoint serving JSON-RPC v0.6 (usually ending in \
+            `/rpc/v0_6`) with --rpc."
+        }
+        std::cmp::Ordering::Greater => {
+            "Upgrade Starkli with `starkliup`, or use an endpoint serving JSON-RPC v0.6 \
+            (usually ending in `/rpc/v0_6`) with --rpc."
+        }
+        std::cmp::Ordering::Equal => return,
+    };
+
+    Warning::RpcVersion.print(format!(
+        "the node serves JSON-RPC {} but this version of Starkli is built for v{}.{}. {}",
+        spec_version, SUPPORTED_SPEC_VERSION.0, SUPPORTED_SPEC_VERSION.1, hint
+    ));
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::FieldElement;
+use url::Url;
+
+use crate::profile::Profiles;
+
+/// Entries are trusted for an hour unless `STARKLI_RESOLVE_CACHE_TTL` says otherwise.
+const DEFAULT_TTL_SECONDS: u64 = 3600;
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Chain IDs and spec versions of RPC endpoints, and addresses of names resolved by previous
+/// commands, stored as `resolutions.json` in the cache folder. Scripts that run starkli many times in a row would
+/// otherwise repeat the same lookups for every command.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResolveCache {
+    #[serde(default)]
+    chain_ids: BTreeMap<String, CachedValue<FieldElement>>,
+    #[serde(default)]
+    spec_versions: BTreeMap<String, CachedValue<String>>,
+    /// Keyed by `<chain_id>/<name>`. Names that don't resolve are cached too.
+    #[serde(default)]
+    names: BTreeMap<String, CachedValue<Option<FieldElement>>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedValue<T> {
+    value: T,
+    /// Unix timestamp in seconds.
+    cached_at: u64,
+}
+
+impl ResolveCache {
+    /// Bypasses the cache for the rest of the process, as with `--no-resolve-cache`.
+    pub fn disable() {
+        DISABLED.store(true, Ordering::Relaxed);
+    }
+
+    pub fn get_path() -> Result<PathBuf> {
+        let mut path = Profiles::get_cache_folder()?;
+        path.push("resolutions.json");
+        Ok(path)
+    }
+
+    pub fn chain_id(rpc_url: &Url) -> Option<FieldElement> {
+        if !is_cacheable_url(rpc_url) {
+            return None;
+        }
+        Self::load()?
+            .chain_ids
+            .get(rpc_url.as_str())
+            .and_then(|cached| cached.fresh())
+            .copied()
+    }
+
+    pub fn set_chain_id(rpc_url: &Url, chain_id: FieldElement) {
+        if is_cacheable_url(rpc_url) {
+            Self::update(|cache| {
+                cache
+                    .chain_ids
+                    .insert(rpc_url.to_string(), CachedValue::new(chain_id));
+            });
+        }
+    }
+
+    pub fn spec_version(rpc_url: &Url) -> Option<String> {
+        if !is_cacheable_url(rpc_url) {
+            return None;
+        }
+        Self::load()?
+            .spec_versions
+            .get(rpc_url.as_str())
+            .and_then(|cached| cached.fresh())
+            .cloned()
+    }
+
+    pub fn set_spec_version(rpc_url: &Url, spec_version: &str) {
+        if is_cacheable_url(rpc_url) {
+            Self::update(|cache| {
+                cache.spec_versions.insert(
+                    rpc_url.to_string(),
+                    CachedValue::new(spec_version.to_owned()),
+                );
+            });
+        }
+    }
+
+    /// The cached resolution of a name, where `Some(None)` means the name was known not to exist.
+    pub fn name(chain_id: FieldElement, name: &str) -> Option<Option<FieldElement>> {
+        Self::load()?
+            .names
+            .get(&name_key(chain_id, name))
+            .and_then(|cached| cached.fresh())
+            .copied()
+    }
+
+    pub fn set_name(chain_id: FieldElement, name: &str, address: Option<FieldElement>) {
+        Self::update(|cache| {
+            cache
+                .names
+                .insert(name_key(chain_id, name), CachedValue::new(address));
+        });
+    }
+
+    /// Forgets names under an address book namespace, after it's synced again or removed.
+    pub fn clear_namespace(namespace: &str) {
+        let prefix = format!("{}:", namespace);
+        Self::update(|cache| {
+            cache.names.retain(|key, _| {
+                key.split_once('/')
+                    .map_or(true, |(_, name)| !name.starts_with(&prefix))
+            });
+        });
+    }
+
+    fn load() -> Option<Self> {
+        if DISABLED.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        // A corrupted cache file is treated as empty and overwritten
+        let content = std::fs::read_to_string(Self::get_path().ok()?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Applies a change to the cache file. Failing to write the cache is not worth failing the
+    /// command for, so errors are only logged.
+    fn update<F>(change: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        if DISABLED.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let mut cache = Self::load().unwrap_or_default();
+        change(&mut cache);
+
+        // Stale entries are dropped on every write to keep the file small
+        cache.chain_ids.retain(|_, cached| cached.fresh().is_some());
+        cache
+            .spec_versions
+            .retain(|_, cached| cached.fresh().is_some());
+        cache.names.retain(|_, cached| cached.fresh().is_some());
+
+        if let Err(err) = cache.save() {
+            log::warn!("unable to write resolution cache: {}", err);
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let folder = Profiles::get_cache_folder()?;
+        if !folder.exists() {
+            std::fs::create_dir_all(folder)?;
+        }
+
+        std::fs::write(Self::get_path()?, serde_json::to_string(self)?)?;
+
+        Ok(())
+    }
+}
+
+impl<T> CachedValue<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value,
+            cached_at: now(),
+        }
+    }
+
+    fn fresh(&self) -> Option<&T> {
+        if now().saturating_sub(self.cached_at) < ttl() {
+            Some(&self.value)
+        } else {
+            None
+        }
+    }
+}
+
+fn name_key(chain_id: FieldElement, name: &str) -> String {
+    format!("{:#x}/{}", chain_id, name)
+}
+
+/// Local nodes like devnets are often restarted with a different chain on the same port, so their
+/// chain IDs and spec versions are never cached.
+fn is_cacheable_url(rpc_url: &Url) -> bool {
+    match rpc_url.host() {
+        Some(url::Host::Domain(domain)) => domain != "localhost",
+        Some(url::Host::Ipv4(ip)) => !ip.is_loopback() && !ip.is_unspecified(),
+        Some(url::Host::Ipv6(ip)) => !ip.is_loopback() && !ip.is_unspecified(),
+        None => false,
+    }
+}
+
+fn ttl() -> u64 {
+    std::env::var("STARKLI_RESOLVE_CACHE_TTL")
+        .ok()
+        .and_then(|ttl| ttl.parse().ok())
+        .unwrap_or(DEFAULT_TTL_SECONDS)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,131 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use starknet::core::types::FieldElement;
+
+/// Options that are never saved to state files as they may hold secrets, like API keys in RPC
+/// URLs. They have to be passed again when resuming. All of them take a value.
+const SECRET_ARGS: [&str; 3] = ["--rpc", "--private-key", "--keystore-password"];
+
+/// Options that are dropped from state files as they would be stale when resuming. All of them
+/// take a value.
+const STALE_ARGS: [&str; 3] = ["--state-file", "--resume", "--nonce"];
+
+/// Progress of a multi-step command like `deploy --manifest` or `multi invoke`, saved after every
+/// step so that `starkli script resume` can pick up where it stopped without sending anything
+/// twice.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptState {
+    /// Command line arguments of the original run, without the program name.
+    pub args: Vec<String>,
+    /// Secret options of the original run that were left out of `args`.
+    pub omitted: Vec<String>,
+    /// Working directory of the original run, so that relative paths resolve the same way.
+    pub working_dir: PathBuf,
+    /// SHA-256 hash of the input file, so that a state file is only resumed with the same input.
+    input_hash: String,
+    /// Completed steps by key, such as manifest labels or account addresses.
+    steps: BTreeMap<String, StepResult>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StepResult {
+    pub transaction_hash: FieldElement,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<FieldElement>,
+}
+
+impl ScriptState {
+    /// Starts the state of a new run, which is saved to `path`. Fails if `path` already exists, as
+    /// it would hold the progress of another run.
+    pub fn create(path: &Path, input: &[u8]) -> Result<Self> {
+        if path.exists() {
+            anyhow::bail!(
+                "state file {} already exists. Resume it with `starkli script resume {}`, or \
+                remove it to start over",
+                path.display(),
+                path.display()
+            );
+        }
+
+        let mut args = vec![];
+        let mut omitted = vec![];
+        let mut skip_value = false;
+        for arg in std::env::args().skip(1) {
+            if skip_value {
+                skip_value = false;
+                continue;
+            }
+
+            let name = arg.split_once('=').map_or(arg.as_str(), |(name, _)| name);
+            let secret = SECRET_ARGS.contains(&name);
+            if secret || STALE_ARGS.contains(&name) {
+                if secret && !omitted.iter().any(|omitted| omitted == name) {
+                    omitted.push(name.to_owned());
+                }
+                skip_value = name == arg;
+                continue;
+            }
+
+            args.push(arg);
+        }
+
+        Ok(Self {
+            args,
+            omitted,
+            working_dir: std::env::current_dir()?,
+            input_hash: hash_input(input),
+            steps: BTreeMap::new(),
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        serde_json::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|err| anyhow::anyhow!("invalid state file {}: {}", path.display(), err))
+    }
+
+    /// Reads a state file for resuming a run over `input`.
+    pub fn resume(path: &Path, input: &[u8]) -> Result<Self> {
+        let state = Self::read(path)?;
+        if state.input_hash != hash_input(input) {
+            anyhow::bail!(
+                "state file {} was saved for a different input",
+                path.display()
+            );
+        }
+
+        Ok(state)
+    }
+
+    pub fn step(&self, key: &str) -> Option<&StepResult> {
+        self.steps.get(key)
+    }
+
+    pub fn steps(&self) -> impl Iterator<Item = (&String, &StepResult)> {
+        self.steps.iter()
+    }
+
+    pub fn record(&mut self, key: String, result: StepResult) {
+        self.steps.insert(key, result);
+    }
+
+    pub fn completed_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|err| anyhow::anyhow!("unable to save state to {}: {}", path.display(), err))
+    }
+}
+
+fn hash_input(input: &[u8]) -> String {
+    hex::encode(Sha256::digest(input))
+}
@@ -0,0 +1,150 @@
+use std::{path::PathBuf, sync::Mutex};
+
+use anyhow::Result;
+
+use crate::profile::Profiles;
+
+/// Master password entered in this process, so that it's only prompted for once per invocation.
+static MASTER_PASSWORD: Mutex<Option<String>> = Mutex::new(None);
+
+/// Secrets like RPC URLs with API keys or keystore passwords, each stored as an encrypted
+/// keystore file under the `secrets` folder and unlocked with a master password.
+pub struct SecretStore;
+
+impl SecretStore {
+    pub fn get_folder() -> Result<PathBuf> {
+        let mut path = Profiles::get_config_folder()?;
+        path.push("secrets");
+        Ok(path)
+    }
+
+    pub fn get_path(name: &str) -> Result<PathBuf> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            anyhow::bail!(
+                "invalid secret name \"{}\": only letters, digits, `_` and `-` are allowed",
+                name
+            );
+        }
+
+        let mut path = Self::get_folder()?;
+        path.push(format!("{}.json", name));
+        Ok(path)
+    }
+
+    pub fn exists(name: &str) -> Result<bool> {
+        Ok(Self::get_path(name)?.exists())
+    }
+
+    /// Names of all stored secrets, sorted.
+    pub fn list() -> Result<Vec<String>> {
+        let folder = Self::get_folder()?;
+        if !folder.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut names = std::fs::read_dir(folder)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+
+        Ok(names)
+    }
+
+    /// Encrypts and saves a secret with the master password, replacing any existing value.
+    pub fn store(name: &str, value: &str) -> Result<()> {
+        let master_password = Self::master_password()?;
+
+        let path = Self::get_path(name)?;
+
+        let folder = Self::get_folder()?;
+        if !folder.exists() {
+            std::fs::create_dir_all(&folder)?;
+        }
+
+        eth_keystore::encrypt_key(
+            &folder,
+            &mut rand::thread_rng(),
+            value.as_bytes(),
+            &master_password,
+            path.file_name().and_then(|name| name.to_str()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn remove(name: &str) -> Result<()> {
+        let path = Self::get_path(name)?;
+        if !path.exists() {
+            anyhow::bail!("secret not found: {}", name);
+        }
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// Decrypts a secret, prompting for the master password if it hasn't been entered yet.
+    pub fn load(name: &str) -> Result<String> {
+        let path = Self::get_path(name)?;
+        if !path.exists() {
+            anyhow::bail!(
+                "secret not found: {}. Use `starkli secret set {}` to store it",
+                name,
+                name
+            );
+        }
+
+        let value = decrypt(name, &Self::master_password()?)?;
+
+        String::from_utf8(value).map_err(|_| anyhow::anyhow!("secret {} is not valid UTF-8", name))
+    }
+
+    /// Gets the master password, prompting for it only once per process. A new password is chosen
+    /// when there's no secret yet. Otherwise, it's checked against an existing secret so that all
+    /// secrets share the same password.
+    pub fn master_password() -> Result<String> {
+        let mut master_password = MASTER_PASSWORD
+            .lock()
+            .map_err(|_| anyhow::anyhow!("secret store lock poisoned"))?;
+        if let Some(password) = master_password.as_ref() {
+            return Ok(password.to_owned());
+        }
+
+        let password = match Self::list()?.first() {
+            Some(existing) => {
+                let password = rpassword::prompt_password("Enter secrets master password: ")?;
+                decrypt(existing, &password)?;
+                password
+            }
+            None => {
+                let password = rpassword::prompt_password("Choose a secrets master password: ")?;
+                let confirmation = rpassword::prompt_password("Confirm master password: ")?;
+                if password != confirmation {
+                    anyhow::bail!("passwords do not match");
+                }
+                password
+            }
+        };
+
+        *master_password = Some(password.clone());
+        Ok(password)
+    }
+}
+
+fn decrypt(name: &str, password: &str) -> Result<Vec<u8>> {
+    eth_keystore::decrypt_key(SecretStore::get_path(name)?, password).map_err(|err| match err {
+        eth_keystore::KeystoreError::MacMismatch => {
+            anyhow::anyhow!("unable to decrypt secret {}: wrong master password", name)
+        }
+        err => anyhow::anyhow!("unable to decrypt secret {}: {}", name, err),
+    })
+}
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use clap::Parser;
+use colored_json::{ColorMode, Output};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Parser)]
+pub struct SelectArgs {
+    #[clap(
+        long,
+        value_name = "PATH",
+        help = "Only print the JSON field at this dot-separated path (e.g. `events.0.data`). Can \
+                be repeated to print multiple fields separated by tabs"
+    )]
+    select: Vec<String>,
+}
+
+impl SelectArgs {
+    pub fn is_empty(&self) -> bool {
+        self.select.is_empty()
+    }
+
+    /// Prints the whole value as colored JSON, or only the selected fields if any. Strings are
+    /// printed without quotes so that they can be used in scripts as is.
+    pub fn print(&self, value: &Value) -> Result<()> {
+        if self.select.is_empty() {
+            let value = colored_json::to_colored_json(value, ColorMode::Auto(Output::StdOut))?;
+            println!("{value}");
+            return Ok(());
+        }
+
+        let fields = self
+            .select
+            .iter()
+            .map(|path| {
+                select_path(value, path).map(|field| match field {
+                    Value::String(field) => field.to_owned(),
+                    field => field.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        println!("{}", fields.join("\t"));
+
+        Ok(())
+    }
+}
+
+fn select_path<'a>(value: &'a Value, path: &str) -> Result<&'a Value> {
+    let mut current = value;
+
+    for segment in path.split('.') {
+        let next = match current {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get(index)),
+            _ => None,
+        };
+
+        current = next.ok_or_else(|| {
+            anyhow::anyhow!("field `{}` not found in output at `{}`", path, segment)
+        })?;
+    }
+
+    Ok(current)
+}
@@ -0,0 +1,186 @@
+use std::{fmt::Display, time::Duration};
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, TimeZone, Utc};
+use clap::Parser;
+use colored::Colorize;
+use num_bigint::BigUint;
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement},
+    providers::Provider,
+};
+
+use crate::{
+    amount::DecimalStyle, interrupt::ctrl_c, provider::ExtendedProvider, utils::bigdecimal_to_felt,
+};
+
+/// Longest time to sleep at once while waiting for `--send-at`, so that clock adjustments and
+/// system suspends don't delay sending for long.
+const MAX_SLEEP: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Parser)]
+pub struct SendScheduleArgs {
+    #[clap(
+        long,
+        value_name = "TIME",
+        help = "Sign the transaction right away but only send it at this time, given as a Unix \
+                timestamp or an RFC 3339 date (e.g. 2024-01-01T12:00:00Z)"
+    )]
+    send_at: Option<String>,
+    #[clap(
+        long,
+        value_name = "BLOCK",
+        conflicts_with = "send_at",
+        help = "Sign the transaction right away but only send it once the chain reaches this block \
+                number"
+    )]
+    send_after_block: Option<u64>,
+    #[clap(
+        long,
+        value_name = "GWEI",
+        help = "Sign the transaction right away but only send it once the L1 gas price of the \
+                pending block is at or below this price in Gwei (9 decimals)"
+    )]
+    max_gas_price: Option<BigDecimal>,
+}
+
+/// When a transaction signed ahead of time is to be sent.
+#[derive(Debug, Clone, Copy)]
+pub enum SendCondition {
+    At(DateTime<Utc>),
+    AfterBlock(u64),
+    /// Gas price in Wei.
+    MaxGasPrice(FieldElement),
+}
+
+impl SendScheduleArgs {
+    /// Conditions to wait for in turn before sending, if any. The gas price always comes last, as
+    /// it has to be low at the moment of sending rather than at some point before.
+    pub fn into_conditions(self) -> Result<Vec<SendCondition>> {
+        let mut conditions = vec![];
+
+        match (self.send_at, self.send_after_block) {
+            (Some(time), None) => conditions.push(SendCondition::At(parse_time(&time)?)),
+            (None, Some(block)) => conditions.push(SendCondition::AfterBlock(block)),
+            (None, None) => {}
+            (Some(_), Some(_)) => {
+                anyhow::bail!("--send-at and --send-after-block cannot be used together")
+            }
+        }
+        if let Some(max_gas_price) = self.max_gas_price {
+            conditions.push(SendCondition::MaxGasPrice(bigdecimal_to_felt(
+                &max_gas_price,
+                9,
+            )?));
+        }
+
+        Ok(conditions)
+    }
+}
+
+impl SendCondition {
+    /// Waits until the transaction is due. Ctrl-C aborts the wait without sending anything.
+    pub async fn wait(&self, provider: &ExtendedProvider, poll_interval: Duration) -> Result<()> {
+        tokio::select! {
+            result = self.wait_until_due(provider, poll_interval) => result,
+            _ = ctrl_c() => {
+                eprintln!();
+                anyhow::bail!("interrupted. The signed transaction was not sent")
+            }
+        }
+    }
+
+    async fn wait_until_due(
+        &self,
+        provider: &ExtendedProvider,
+        poll_interval: Duration,
+    ) -> Result<()> {
+        match self {
+            Self::At(time) => loop {
+                let remaining = match (*time - Utc::now()).to_std() {
+                    Ok(remaining) if !remaining.is_zero() => remaining,
+                    // Negative durations fail to convert, meaning the time has passed
+                    _ => return Ok(()),
+                };
+                tokio::time::sleep(remaining.min(MAX_SLEEP)).await;
+            },
+            Self::AfterBlock(target) => loop {
+                match provider.block_number().await {
+                    Ok(block_number) if block_number >= *target => return Ok(()),
+                    Ok(_) => {}
+                    // The transaction is already signed, so a flaky node shouldn't make it miss
+                    // its slot
+                    Err(err) => log::warn!("unable to fetch block number: {}", err),
+                }
+                tokio::time::sleep(poll_interval).await;
+            },
+            Self::MaxGasPrice(max_gas_price) => {
+                let mut last_reported = None;
+                loop {
+                    match pending_gas_price(provider).await {
+                        Ok(gas_price) if gas_price <= *max_gas_price => return Ok(()),
+                        Ok(gas_price) => {
+                            if last_reported != Some(gas_price) {
+                                eprintln!(
+                                    "Gas price is {} gwei, waiting for it to drop to {} gwei...",
+                                    format_gwei(gas_price).bright_yellow(),
+                                    format_gwei(*max_gas_price)
+                                );
+                                last_reported = Some(gas_price);
+                            }
+                        }
+                        Err(err) => log::warn!("unable to fetch gas price: {}", err),
+                    }
+                    tokio::time::sleep(poll_interval).await;
+                }
+            }
+        }
+    }
+}
+
+/// The L1 gas price in Wei from the header of the pending block, which is what new transactions
+/// are charged.
+async fn pending_gas_price(provider: &ExtendedProvider) -> Result<FieldElement> {
+    let block = serde_json::to_value(
+        provider
+            .get_block_with_tx_hashes(BlockId::Tag(BlockTag::Pending))
+            .await?,
+    )?;
+
+    block
+        .get("l1_gas_price")
+        .and_then(|price| price.get("price_in_wei"))
+        .and_then(|price| price.as_str())
+        .and_then(|price| FieldElement::from_hex_be(price).ok())
+        .ok_or_else(|| anyhow::anyhow!("the node does not report the gas price"))
+}
+
+fn format_gwei(amount: FieldElement) -> String {
+    DecimalStyle::default().format(&BigUint::from_bytes_be(&amount.to_bytes_be()), 9)
+}
+
+impl Display for SendCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::At(time) => write!(f, "{}", time.to_rfc3339()),
+            Self::AfterBlock(block) => write!(f, "block {}", block),
+            Self::MaxGasPrice(gas_price) => {
+                write!(f, "gas price at or below {} gwei", format_gwei(*gas_price))
+            }
+        }
+    }
+}
+
+fn parse_time(time: &str) -> Result<DateTime<Utc>> {
+    if let Ok(timestamp) = time.parse::<i64>() {
+        return Utc
+            .timestamp_opt(timestamp, 0)
+            .single()
+            .ok_or_else(|| anyhow::anyhow!("invalid timestamp: {}", time));
+    }
+
+    Ok(DateTime::parse_from_rfc3339(time)
+        .map_err(|err| anyhow::anyhow!("invalid time {}: {}", time, err))?
+        .with_timezone(&Utc))
+}
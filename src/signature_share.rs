@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::FieldElement;
+
+use crate::transaction_file::TransactionFile;
+
+/// A place where multisig owners exchange a transaction proposal and their signatures for it:
+/// either a directory, such as a shared drive or a checked-out git repository, or an HTTP endpoint
+/// that serves and accepts (`GET` and `PUT`) one JSON document per transaction hash.
+///
+/// In a directory, each transaction gets its own folder with `proposal.json` and one file per
+/// signer, so that owners signing at the same time never overwrite each other.
+#[derive(Debug, Clone)]
+pub enum SignatureShare {
+    Http(String),
+    Directory(PathBuf),
+}
+
+/// A signature from one owner, identified by its public key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignerSignature {
+    pub transaction_hash: FieldElement,
+    pub signer: FieldElement,
+    pub r: FieldElement,
+    pub s: FieldElement,
+    pub signed_at: String,
+}
+
+/// The document stored per transaction on HTTP endpoints.
+#[derive(Serialize, Deserialize)]
+struct SharedTransaction {
+    proposal: TransactionFile,
+    #[serde(default)]
+    signatures: Vec<SignerSignature>,
+}
+
+impl SignatureShare {
+    /// Anything other than an `http(s)://` URL is taken as a directory path.
+    pub fn parse(target: &str) -> Self {
+        if target.starts_with("https://") || target.starts_with("http://") {
+            Self::Http(target.trim_end_matches('/').to_owned())
+        } else {
+            Self::Directory(PathBuf::from(target))
+        }
+    }
+
+    /// Shares the proposal, returning where it can be found. Signatures already collected for the
+    /// same transaction are kept.
+    pub async fn publish_proposal(&self, proposal: TransactionFile) -> Result<String> {
+        let transaction_hash = proposal.transaction_hash;
+
+        match self {
+            Self::Http(_) => {
+                let signatures = match self.fetch_shared(transaction_hash).await? {
+                    Some(shared) => shared.signatures,
+                    None => vec![],
+                };
+                self.put_shared(
+                    transaction_hash,
+                    &SharedTransaction {
+                        proposal,
+                        signatures,
+                    },
+                )
+                .await
+            }
+            Self::Directory(_) => {
+                let mut path = self.transaction_folder(transaction_hash)?;
+                std::fs::create_dir_all(&path)?;
+                path.push("proposal.json");
+                proposal.save(&path)?;
+
+                Ok(path.display().to_string())
+            }
+        }
+    }
+
+    pub async fn fetch_proposal(&self, transaction_hash: FieldElement) -> Result<TransactionFile> {
+        let not_found = || {
+            anyhow::anyhow!(
+                "no proposal found for transaction {:#064x}",
+                transaction_hash
+            )
+        };
+
+        match self {
+            Self::Http(_) => Ok(self
+                .fetch_shared(transaction_hash)
+                .await?
+                .ok_or_else(not_found)?
+                .proposal),
+            Self::Directory(_) => {
+                let mut path = self.transaction_folder(transaction_hash)?;
+                path.push("proposal.json");
+                if !path.exists() {
+                    return Err(not_found());
+                }
+                TransactionFile::load(&path)
+            }
+        }
+    }
+
+    /// Adds a signature, replacing any previous signature from the same signer.
+    pub async fn add_signature(&self, signature: SignerSignature) -> Result<String> {
+        let transaction_hash = signature.transaction_hash;
+
+        match self {
+            Self::Http(_) => {
+                // Read-modify-write, so signers of the same proposal should take turns
+                let mut shared = self.fetch_shared(transaction_hash).await?.ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "no proposal found for transaction {:#064x}",
+                        transaction_hash
+                    )
+                })?;
+                shared
+                    .signatures
+                    .retain(|existing| existing.signer != signature.signer);
+                shared.signatures.push(signature);
+
+                self.put_shared(transaction_hash, &shared).await
+            }
+            Self::Directory(_) => {
+                let mut path = self.transaction_folder(transaction_hash)?;
+                std::fs::create_dir_all(&path)?;
+                path.push(format!("{:#064x}.json", signature.signer));
+                std::fs::write(&path, serde_json::to_string_pretty(&signature)?)?;
+
+                Ok(path.display().to_string())
+            }
+        }
+    }
+
+    /// All signatures shared for the transaction. They're not validated in any way.
+    pub async fn fetch_signatures(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<Vec<SignerSignature>> {
+        match self {
+            Self::Http(_) => Ok(self
+                .fetch_shared(transaction_hash)
+                .await?
+                .map(|shared| shared.signatures)
+                .unwrap_or_default()),
+            Self::Directory(_) => {
+                let folder = self.transaction_folder(transaction_hash)?;
+                if !folder.exists() {
+                    return Ok(vec![]);
+                }
+
+                let mut signatures = vec![];
+                for entry in std::fs::read_dir(folder)? {
+                    let path = entry?.path();
+                    if path.extension().map_or(true, |ext| ext != "json")
+                        || path.file_stem().is_some_and(|stem| stem == "proposal")
+                    {
+                        continue;
+                    }
+
+                    // Stray files are skipped instead of failing the whole collection
+                    match std::fs::read_to_string(&path)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|content| Ok(serde_json::from_str(&content)?))
+                    {
+                        Ok(signature) => signatures.push(signature),
+                        Err(err) => log::warn!("skipping {}: {}", path.display(), err),
+                    }
+                }
+
+                Ok(signatures)
+            }
+        }
+    }
+
+    fn transaction_folder(&self, transaction_hash: FieldElement) -> Result<PathBuf> {
+        match self {
+            Self::Directory(dir) => {
+                let mut path = dir.clone();
+                path.push(format!("{:#064x}", transaction_hash));
+                Ok(path)
+            }
+            Self::Http(_) => anyhow::bail!("not a directory share"),
+        }
+    }
+
+    fn url(&self, transaction_hash: FieldElement) -> Result<String> {
+        match self {
+            Self::Http(endpoint) => Ok(format!("{}/{:#064x}.json", endpoint, transaction_hash)),
+            Self::Directory(_) => anyhow::bail!("not an HTTP share"),
+        }
+    }
+
+    async fn fetch_shared(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<Option<SharedTransaction>> {
+        let url = self.url(transaction_hash)?;
+
+        let response = with_token(client()?.get(&url)).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("request to {} failed: {}", url, response.status());
+        }
+
+        Ok(Some(
+            serde_json::from_str(&response.text().await?)
+                .map_err(|err| anyhow::anyhow!("invalid shared transaction at {}: {}", url, err))?,
+        ))
+    }
+
+    async fn put_shared(
+        &self,
+        transaction_hash: FieldElement,
+        shared: &SharedTransaction,
+    ) -> Result<String> {
+        let url = self.url(transaction_hash)?;
+
+        let response = with_token(client()?.put(&url).json(shared)).send().await?;
+        if !response.status().is_success() {
+            anyhow::bail!("request to {} failed: {}", url, response.status());
+        }
+
+        Ok(url)
+    }
+}
+
+/// Private endpoints can require a bearer token, taken from `STARKLI_MULTISIG_SHARE_TOKEN`.
+fn with_token(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match std::env::var("STARKLI_MULTISIG_SHARE_TOKEN") {
+        Ok(token) => request.bearer_auth(token),
+        Err(_) => request,
+    }
+}
+
+fn client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .user_agent(concat!("starkli/", env!("CARGO_PKG_VERSION")))
+        .build()?)
+}
@@ -1,42 +1,86 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use async_trait::async_trait;
 use clap::Parser;
-use colored::Colorize;
 use starknet::{
     core::{crypto::Signature, types::FieldElement},
     signers::{LocalWallet, Signer, SigningKey, VerifyingKey},
 };
 
+use crate::{
+    agent::{AgentSignError, AgentSigner},
+    keychain::Keychain,
+    ledger::{HdPath, LedgerSigner, LedgerSignerError},
+    profile::Profiles,
+    secrets::SecretStore,
+    warning::Warning,
+};
+
 #[derive(Debug)]
 pub enum AnySigner {
     LocalWallet(LocalWallet),
+    /// A keystore unlocked in `starkli agent`, signing through the agent.
+    Agent(AgentSigner),
+    Ledger(LedgerSigner),
+    /// Used for watch-only accounts. Fails whenever a signature is requested.
+    WatchOnly,
 }
 
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
 pub enum AnySignerGetPublicKeyError {
     LocalWallet(<LocalWallet as Signer>::GetPublicKeyError),
+    Ledger(LedgerSignerError),
+    WatchOnly(WatchOnlyError),
 }
 
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
 pub enum AnySignerSignError {
     LocalWallet(<LocalWallet as Signer>::SignError),
+    Agent(AgentSignError),
+    Ledger(LedgerSignerError),
+    WatchOnly(WatchOnlyError),
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "this is a watch-only account without a signer. Use --export with --max-fee to build the \
+    transaction for signing elsewhere, or --sender to simulate or estimate it without a signature"
+)]
+pub struct WatchOnlyError;
+
 #[derive(Debug, Clone, Parser)]
 pub struct SignerArgs {
     #[clap(long, help = keystore_help())]
     keystore: Option<String>,
+    #[clap(
+        long,
+        conflicts_with_all = ["keystore", "private_key"],
+        help = "Name of a keystore in the keystores directory, without the .json extension"
+    )]
+    keystore_name: Option<String>,
     #[clap(
         long,
         help = "Supply keystore password from command line option instead of prompt"
     )]
     keystore_password: Option<String>,
+    #[clap(
+        long,
+        conflicts_with_all = ["keystore_password", "private_key"],
+        help = "Name of the secret in the encrypted secret store holding the keystore password"
+    )]
+    keystore_password_secret: Option<String>,
     #[clap(long, help = private_key_help())]
     private_key: Option<String>,
+    #[clap(
+        long,
+        conflicts_with_all = ["keystore", "keystore_name", "keystore_password", "private_key"],
+        help = "Sign with the key at this EIP-2645 path on a Ledger device, e.g. \
+                m/2645'/starknet'/starkli'/0'/0'/0"
+    )]
+    ledger_path: Option<HdPath>,
 }
 
 #[derive(Debug)]
@@ -53,12 +97,14 @@ pub enum SignerResolutionTask {
 pub enum SignerResolutionTaskContent {
     Keystore(KeystoreTaskContent),
     PrivateKey(PrivateKeyTaskContent),
+    Ledger(HdPath),
 }
 
 #[derive(Debug)]
 pub struct KeystoreTaskContent {
     keystore: String,
     keystore_password: Option<String>,
+    keystore_password_secret: Option<String>,
 }
 
 #[derive(Debug)]
@@ -82,6 +128,14 @@ impl Signer for AnySigner {
             Self::LocalWallet(inner) => Ok(<LocalWallet as Signer>::get_public_key(inner)
                 .await
                 .map_err(Self::GetPublicKeyError::LocalWallet)?),
+            Self::Agent(inner) => Ok(VerifyingKey::from_scalar(inner.public_key())),
+            Self::Ledger(inner) => Ok(VerifyingKey::from_scalar(
+                inner
+                    .get_public_key()
+                    .await
+                    .map_err(Self::GetPublicKeyError::Ledger)?,
+            )),
+            Self::WatchOnly => Err(Self::GetPublicKeyError::WatchOnly(WatchOnlyError)),
         }
     }
 
@@ -90,28 +144,50 @@ impl Signer for AnySigner {
             Self::LocalWallet(inner) => Ok(<LocalWallet as Signer>::sign_hash(inner, hash)
                 .await
                 .map_err(Self::SignError::LocalWallet)?),
+            Self::Agent(inner) => inner.sign(hash).await.map_err(Self::SignError::Agent),
+            Self::Ledger(inner) => inner.sign_hash(hash).await.map_err(Self::SignError::Ledger),
+            Self::WatchOnly => Err(Self::SignError::WatchOnly(WatchOnlyError)),
         }
     }
 }
 
 impl SignerArgs {
+    pub fn is_ledger(&self) -> bool {
+        self.ledger_path.is_some()
+    }
+
     pub fn into_signer(self) -> Result<AnySigner> {
         self.into_task()?.resolve()
     }
 
+    pub fn into_signing_key(self) -> Result<SigningKey> {
+        self.into_task()?.resolve_signing_key()
+    }
+
     /// Parses the options into a resolution task without immediately performing the resolution.
     /// This method allows callers to defer resolution to a later stage while still performing some
     /// initial validations.
     pub fn into_task(self) -> Result<SignerResolutionTask> {
+        // Mixing with other signer options on the command line is already rejected by `clap`,
+        // and signer env vars are ignored in favor of the device.
+        if let Some(path) = self.ledger_path {
+            return Ok(SignerResolutionTask::Strong(
+                SignerResolutionTaskContent::Ledger(path),
+            ));
+        }
+
         // We're not using the `env` derive from `clap` because we need to distinguish between
         // whether the value is supplied from the command line or the environment variable.
         //
         // This distinction is important because we would not yell at the user for having option
         // conflicts from env vars. This allows us to reject conflicts on options provided from the
         // command line while ignoring those from env vars.
-        let keystore = match self.keystore {
-            Some(value) => Some(StringValue::FromCommandLine(value)),
-            None => match std::env::var("STARKNET_KEYSTORE") {
+        let keystore = match (self.keystore, self.keystore_name) {
+            (Some(value), _) => Some(StringValue::FromCommandLine(value)),
+            (None, Some(name)) => Some(StringValue::FromCommandLine(
+                named_keystore_path(&name)?.to_string_lossy().into_owned(),
+            )),
+            (None, None) => match std::env::var("STARKNET_KEYSTORE") {
                 Ok(value) => Some(StringValue::FromEnvVar(value)),
                 Err(_) => None,
             },
@@ -124,7 +200,7 @@ impl SignerArgs {
             },
         };
 
-        let task = match (keystore, self.keystore_password, private_key) {
+        let mut task = match (keystore, self.keystore_password, private_key) {
             // Options:
             //   Keystore: from command line
             //   Private key: from env var or not supplied at all
@@ -138,6 +214,7 @@ impl SignerArgs {
                 KeystoreTaskContent {
                     keystore,
                     keystore_password,
+                    keystore_password_secret: None,
                 },
             )),
             // Options:
@@ -161,6 +238,7 @@ impl SignerArgs {
                     KeystoreTaskContent {
                         keystore,
                         keystore_password,
+                        keystore_password_secret: None,
                     },
                 ))
             }
@@ -191,16 +269,49 @@ impl SignerArgs {
             }
         };
 
+        if let Some(secret) = self.keystore_password_secret {
+            match &mut task {
+                SignerResolutionTask::Strong(SignerResolutionTaskContent::Keystore(content))
+                | SignerResolutionTask::Weak(SignerResolutionTaskContent::Keystore(content)) => {
+                    content.keystore_password_secret = Some(secret);
+                }
+                _ => anyhow::bail!("--keystore-password-secret can only be used with a keystore"),
+            }
+        }
+
         Ok(task)
     }
 }
 
 impl SignerResolutionTask {
     pub fn resolve(self) -> Result<AnySigner> {
+        if let Self::Strong(SignerResolutionTaskContent::Keystore(inner))
+        | Self::Weak(SignerResolutionTaskContent::Keystore(inner)) = &self
+        {
+            if let Some(signer) = inner.agent_signer() {
+                return Ok(AnySigner::Agent(signer));
+            }
+        }
+
+        if let Self::Strong(SignerResolutionTaskContent::Ledger(path)) = self {
+            return Ok(AnySigner::Ledger(LedgerSigner::new(path)));
+        }
+
+        Ok(AnySigner::LocalWallet(LocalWallet::from_signing_key(
+            self.resolve_signing_key()?,
+        )))
+    }
+
+    /// Resolves the raw signing key instead of a signer. This is only possible for signer sources
+    /// where the key is locally available.
+    pub fn resolve_signing_key(self) -> Result<SigningKey> {
         match self {
             Self::Strong(task) | Self::Weak(task) => match task {
                 SignerResolutionTaskContent::Keystore(inner) => inner.resolve(),
                 SignerResolutionTaskContent::PrivateKey(inner) => inner.resolve(),
+                SignerResolutionTaskContent::Ledger(_) => Err(anyhow::anyhow!(
+                    "the private key of a Ledger device cannot be used directly"
+                )),
             },
             Self::None => Err(anyhow::anyhow!(
                 "no valid signer option provided. \
@@ -212,7 +323,19 @@ impl SignerResolutionTask {
 }
 
 impl KeystoreTaskContent {
-    pub fn resolve(self) -> Result<AnySigner> {
+    /// Uses the key held by `starkli agent` for this keystore, unless a password is supplied.
+    fn agent_signer(&self) -> Option<AgentSigner> {
+        if self.keystore_password.is_some() || self.keystore_password_secret.is_some() {
+            return None;
+        }
+
+        let keystore = PathBuf::from(shellexpand::tilde(&self.keystore).into_owned());
+        let keystore = std::fs::canonicalize(keystore).ok()?;
+
+        AgentSigner::find(&keystore.to_string_lossy())
+    }
+
+    pub fn resolve(self) -> Result<SigningKey> {
         if self.keystore.is_empty() {
             anyhow::bail!("empty keystore path");
         }
@@ -220,12 +343,9 @@ impl KeystoreTaskContent {
         let keystore = PathBuf::from(shellexpand::tilde(&self.keystore).into_owned());
 
         if self.keystore_password.is_some() {
-            eprintln!(
-                "{}",
-                "WARNING: setting keystore passwords via --password is generally \
-                        considered insecure, as they will be stored in your shell history or other \
-                        log files."
-                    .bright_magenta()
+            Warning::PasswordOption.print(
+                "setting keystore passwords via --password is generally considered insecure, as \
+                they will be stored in your shell history or other log files.",
             );
         }
 
@@ -235,18 +355,22 @@ impl KeystoreTaskContent {
 
         let password = if let Some(password) = self.keystore_password {
             password
+        } else if let Some(secret) = self.keystore_password_secret {
+            SecretStore::load(&secret)?
+        } else if let Some(password) = Keychain::get_keystore_password(&keystore) {
+            password
         } else {
             rpassword::prompt_password("Enter keystore password: ")?
         };
 
         let key = SigningKey::from_keystore(keystore, &password)?;
 
-        Ok(AnySigner::LocalWallet(LocalWallet::from_signing_key(key)))
+        Ok(key)
     }
 }
 
 impl PrivateKeyTaskContent {
-    pub fn resolve(self) -> Result<AnySigner> {
+    pub fn resolve(self) -> Result<SigningKey> {
         let print_warning = match std::env::var("STARKLI_NO_PLAIN_KEY_WARNING") {
             Ok(value) => value == "false",
             Err(_) => true,
@@ -254,22 +378,31 @@ impl PrivateKeyTaskContent {
 
         // TODO: change to recommend hardware wallets when they become available
         if print_warning {
-            eprintln!(
-                "{}",
-                "WARNING: using private key in plain text is highly insecure, and you should \
-                ONLY do this for development. Consider using an encrypted keystore instead. \
-                (Check out https://book.starkli.rs/signers on how to suppress this warning)"
-                    .bright_magenta()
+            Warning::PlainPrivateKey.print(
+                "using private key in plain text is highly insecure, and you should ONLY do this \
+                for development. Consider using an encrypted keystore instead. (Check out \
+                https://book.starkli.rs/signers on how to suppress this warning)",
             );
         }
 
         let private_key = FieldElement::from_hex_be(&self.key)?;
         let key = SigningKey::from_secret_scalar(private_key);
 
-        Ok(AnySigner::LocalWallet(LocalWallet::from_signing_key(key)))
+        Ok(key)
     }
 }
 
+/// Path of a keystore referenced by name in the keystores directory.
+pub fn named_keystore_path(name: &str) -> Result<PathBuf> {
+    if name.is_empty() || Path::new(name).components().count() != 1 {
+        anyhow::bail!("invalid keystore name: {}", name);
+    }
+
+    let mut path = Profiles::get_keystores_folder()?;
+    path.push(format!("{}.json", name));
+    Ok(path)
+}
+
 fn keystore_help() -> String {
     format!(
         "Path to keystore JSON file [env: STARKNET_KEYSTORE={}]",
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use colored::Colorize;
+use starknet::{
+    core::types::{BlockId, BlockTag, BroadcastedTransaction, SimulationFlag},
+    providers::Provider,
+};
+use url::Url;
+
+use crate::{provider::ExtendedProvider, utils::diff_json};
+
+/// Runs the same simulation against the primary provider and the endpoint given with
+/// `--compare-provider`, and prints where the results differ. Both endpoints must see the exact
+/// same signed request for the comparison to be meaningful, so callers build it once instead of
+/// going through `simulate()`.
+pub async fn compare_simulations(
+    provider: &ExtendedProvider,
+    compare_provider: Url,
+    transaction: BroadcastedTransaction,
+    skip_validate: bool,
+    skip_fee_charge: bool,
+) -> Result<()> {
+    let transactions = [transaction];
+    let mut flags = vec![];
+    if skip_validate {
+        flags.push(SimulationFlag::SkipValidate);
+    }
+    if skip_fee_charge {
+        flags.push(SimulationFlag::SkipFeeCharge);
+    }
+
+    let compare_provider = ExtendedProvider::new(compare_provider, false);
+
+    let (left, right) = tokio::join!(
+        provider.simulate_transactions(BlockId::Tag(BlockTag::Pending), &transactions, &flags),
+        compare_provider.simulate_transactions(
+            BlockId::Tag(BlockTag::Pending),
+            &transactions,
+            &flags
+        )
+    );
+
+    print_simulation_differences(
+        &serde_json::to_value(left?)?,
+        &serde_json::to_value(right?)?,
+    );
+
+    Ok(())
+}
+
+/// Prints the differences between two simulation results, with the primary provider on the left.
+pub fn print_simulation_differences(left: &serde_json::Value, right: &serde_json::Value) {
+    let differences = diff_json(left, right);
+    if differences.is_empty() {
+        eprintln!("Simulation results from both providers are identical.");
+        return;
+    }
+
+    eprintln!(
+        "{} difference(s) found ({} vs {}):",
+        differences.len(),
+        "primary".bright_yellow(),
+        "compare".bright_cyan()
+    );
+    for difference in differences.into_iter() {
+        let show = |value: Option<serde_json::Value>| match value {
+            Some(value) => value.to_string(),
+            None => "<missing>".to_owned(),
+        };
+
+        println!(
+            "{}: {} -> {}",
+            difference.path,
+            show(difference.left).bright_yellow(),
+            show(difference.right).bright_cyan()
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::cmp::Ordering;
+
+use anyhow::Result;
+use colored::Colorize;
+use num_bigint::BigUint;
+use serde_json::Value;
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement},
+    providers::Provider,
+};
+
+use crate::{
+    amount::DecimalStyle,
+    provider::ExtendedProvider,
+    utils::{json_felt, storage_address},
+};
+
+/// Prints the changes a simulated transaction makes to the sending account itself, in plain
+/// language: the nonce bump, the change in ETH and STRK balances, storage written to the account
+/// contract, and class replacements. Values before the transaction are read from the pending
+/// block.
+pub async fn print_account_summary(
+    provider: &ExtendedProvider,
+    account: FieldElement,
+    simulation: &Value,
+) -> Result<()> {
+    let state_diff = match simulation
+        .get("transaction_trace")
+        .and_then(|trace| trace.get("state_diff"))
+    {
+        Some(state_diff) => state_diff,
+        None => {
+            log::debug!("simulation has no state diff, skipping account summary");
+            return Ok(());
+        }
+    };
+
+    eprintln!(
+        "Changes to account {}:",
+        format!("{:#064x}", account).bright_yellow()
+    );
+
+    let new_nonce = list(state_diff, "nonces")
+        .find(|entry| json_felt(entry, "contract_address") == Some(account))
+        .and_then(|entry| json_felt(entry, "nonce"));
+    match new_nonce {
+        Some(new_nonce) => {
+            let old_nonce = provider
+                .get_nonce(BlockId::Tag(BlockTag::Pending), account)
+                .await?;
+            eprintln!("  Nonce:        {} -> {}", old_nonce, new_nonce);
+        }
+        None => eprintln!("  Nonce:        unchanged"),
+    }
+
+    if let Some(fee) = simulation
+        .get("fee_estimation")
+        .and_then(|fee| json_felt(fee, "overall_fee"))
+    {
+        eprintln!(
+            "  Fee:          {} ETH",
+            DecimalStyle::default().format_ether(fee).bright_yellow()
+        );
+    }
+
+    for (symbol, token) in [
+        ("ETH", provider.eth_token_address()),
+        ("STRK", provider.strk_token_address()),
+    ] {
+        let delta = balance_delta(provider, state_diff, token, account).await?;
+        eprintln!(
+            "  {:<13} {}",
+            format!("{} balance:", symbol),
+            match delta {
+                Some((true, amount)) => format!("+{} {}", format_units(&amount), symbol),
+                Some((false, amount)) => format!("-{} {}", format_units(&amount), symbol),
+                None => "unchanged".to_owned(),
+            }
+        );
+    }
+
+    let storage_writes = list(state_diff, "storage_diffs")
+        .filter(|entry| json_felt(entry, "address") == Some(account))
+        .map(|entry| list(entry, "storage_entries").count())
+        .sum::<usize>();
+    if storage_writes == 0 {
+        eprintln!("  Storage:      no slot of the account contract written");
+    } else {
+        eprintln!(
+            "  Storage:      {} slot(s) of the account contract written",
+            storage_writes.to_string().bright_yellow()
+        );
+    }
+
+    if let Some(class_hash) = list(state_diff, "replaced_classes")
+        .find(|entry| json_felt(entry, "contract_address") == Some(account))
+        .and_then(|entry| json_felt(entry, "class_hash"))
+    {
+        eprintln!(
+            "  {}",
+            format!(
+                "Class:        replaced with {:#064x}. The account is being upgraded",
+                class_hash
+            )
+            .bright_magenta()
+        );
+    }
+
+    Ok(())
+}
+
+/// The change in an ERC20 balance of the account, as `(is_increase, amount)`, or `None` if the
+/// balance doesn't change.
+async fn balance_delta(
+    provider: &ExtendedProvider,
+    state_diff: &Value,
+    token: FieldElement,
+    account: FieldElement,
+) -> Result<Option<(bool, BigUint)>> {
+    let low_key = storage_address("ERC20_balances", &[account], 0)?;
+    let high_key = low_key + FieldElement::ONE;
+
+    let written = |key: FieldElement| {
+        list(state_diff, "storage_diffs")
+            .filter(|entry| json_felt(entry, "address") == Some(token))
+            .flat_map(|entry| list(entry, "storage_entries"))
+            .filter(|entry| json_felt(entry, "key") == Some(key))
+            .last()
+            .and_then(|entry| json_felt(entry, "value"))
+    };
+    let new_low = written(low_key);
+    let new_high = written(high_key);
+    if new_low.is_none() && new_high.is_none() {
+        return Ok(None);
+    }
+
+    let old_low = provider
+        .get_storage_at(token, low_key, BlockId::Tag(BlockTag::Pending))
+        .await?;
+    let old_high = provider
+        .get_storage_at(token, high_key, BlockId::Tag(BlockTag::Pending))
+        .await?;
+
+    let old = to_u256(old_low, old_high);
+    let new = to_u256(new_low.unwrap_or(old_low), new_high.unwrap_or(old_high));
+
+    Ok(match new.cmp(&old) {
+        Ordering::Greater => Some((true, new - old)),
+        Ordering::Less => Some((false, old - new)),
+        Ordering::Equal => None,
+    })
+}
+
+fn list<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    value
+        .get(key)
+        .and_then(|value| value.as_array())
+        .into_iter()
+        .flatten()
+}
+
+fn to_u256(low: FieldElement, high: FieldElement) -> BigUint {
+    (BigUint::from_bytes_be(&high.to_bytes_be()) << 128)
+        + BigUint::from_bytes_be(&low.to_bytes_be())
+}
+
+fn format_units(amount: &BigUint) -> String {
+    DecimalStyle::default().format(amount, 18)
+}
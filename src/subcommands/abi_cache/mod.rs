@@ -0,0 +1,31 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod prune;
+use prune::Prune;
+
+mod warm;
+use warm::Warm;
+
+#[derive(Debug, Parser)]
+pub struct AbiCache {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Delete cached ABIs, all of them by default")]
+    Prune(Prune),
+    #[clap(about = "Fetch the ABIs of contracts or classes into the cache ahead of time")]
+    Warm(Warm),
+}
+
+impl AbiCache {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Prune(cmd) => cmd.run(),
+            Subcommands::Warm(cmd) => cmd.run().await,
+        }
+    }
+}
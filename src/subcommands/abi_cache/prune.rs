@@ -0,0 +1,60 @@
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::abi_cache::CachedAbi;
+
+#[derive(Debug, Parser)]
+pub struct Prune {
+    #[clap(
+        long,
+        help = "Only delete ABIs cached more than this number of days ago"
+    )]
+    older_than: Option<u64>,
+    #[clap(long, help = "Only print what would be deleted")]
+    dry_run: bool,
+}
+
+impl Prune {
+    pub fn run(self) -> Result<()> {
+        let cutoff = match self.older_than {
+            Some(days) => Some(
+                SystemTime::now()
+                    .checked_sub(Duration::from_secs(days * 24 * 60 * 60))
+                    .ok_or_else(|| anyhow::anyhow!("--older-than is too large"))?,
+            ),
+            None => None,
+        };
+
+        let mut count = 0usize;
+        let mut size = 0u64;
+        for entry in CachedAbi::list()?.into_iter() {
+            if cutoff.is_some_and(|cutoff| entry.cached_at > cutoff) {
+                continue;
+            }
+
+            if self.dry_run {
+                println!("{:#064x}", entry.class_hash);
+            } else {
+                std::fs::remove_file(&entry.path)?;
+            }
+            count += 1;
+            size += entry.size;
+        }
+
+        eprintln!(
+            "{} {} cached ABI(s) ({} KiB)",
+            if self.dry_run {
+                "Would delete"
+            } else {
+                "Deleted"
+            },
+            count.to_string().bright_yellow(),
+            size / 1024
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement},
+    providers::Provider,
+};
+
+use crate::{
+    abi_cache::CachedAbi, address_book::AddressBookResolver, decode::FeltDecoder,
+    verbosity::VerbosityArgs, ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Warm {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "Treat the targets as class hashes instead of contract addresses"
+    )]
+    class_hash: bool,
+    #[clap(
+        required = true,
+        help = "Contract addresses, or class hashes with --class-hash"
+    )]
+    targets: Vec<String>,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Warm {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let mut failures = 0usize;
+        for target in self.targets.iter() {
+            let class_hash = if self.class_hash {
+                FieldElement::from_hex_be(target)
+                    .map_err(|_| anyhow::anyhow!("invalid class hash: {}", target))?
+            } else {
+                let address = felt_decoder
+                    .decode_single_with_addr_fallback(target)
+                    .await?;
+                match provider
+                    .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+                    .await
+                {
+                    Ok(class_hash) => class_hash,
+                    Err(err) => {
+                        failures += 1;
+                        eprintln!("{} {}: {}", "failed".bright_magenta(), target, err);
+                        continue;
+                    }
+                }
+            };
+
+            match CachedAbi::warm(&provider, class_hash).await {
+                Ok(true) => eprintln!(
+                    "{} {}",
+                    "cached".bright_green(),
+                    format!("{:#064x}", class_hash).bright_yellow()
+                ),
+                Ok(false) => eprintln!(
+                    "{} {}",
+                    "already cached".bright_green(),
+                    format!("{:#064x}", class_hash).bright_yellow()
+                ),
+                Err(err) => {
+                    failures += 1;
+                    eprintln!("{} {}: {}", "failed".bright_magenta(), target, err);
+                }
+            }
+        }
+
+        if failures > 0 {
+            anyhow::bail!("{} target(s) could not be cached", failures);
+        }
+
+        Ok(())
+    }
+}
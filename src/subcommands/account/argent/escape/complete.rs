@@ -0,0 +1,87 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::ProviderArgs;
+
+use super::{format_remaining, format_timestamp, ArgentTarget, EscapeState, EscapeType};
+
+#[derive(Debug, Parser)]
+pub struct Complete {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, short, help = "Send without asking for confirmation")]
+    yes: bool,
+    #[clap(help = "Address of the Argent account")]
+    address: String,
+    #[clap(
+        last = true,
+        help = "Options for `starkli invoke` after `--`, like --account or --keystore"
+    )]
+    invoke_args: Vec<String>,
+}
+
+impl Complete {
+    pub async fn run(self) -> Result<()> {
+        let target = ArgentTarget::load(&self.provider, &self.address).await?;
+
+        let (escape_type, new_signer) = match target.escape_state().await? {
+            EscapeState::None => anyhow::bail!(
+                "no escape in progress. Start one with `starkli account argent escape trigger`"
+            ),
+            EscapeState::Waiting {
+                ready_at,
+                remaining,
+                ..
+            } => anyhow::bail!(
+                "the escape is not ready yet. It can be completed at {} ({} from now)",
+                format_timestamp(ready_at),
+                format_remaining(remaining)
+            ),
+            EscapeState::Expired { .. } => anyhow::bail!(
+                "the escape has expired. Trigger it again with `starkli account argent escape \
+                trigger`"
+            ),
+            EscapeState::Ready {
+                escape_type,
+                new_signer,
+                ..
+            } => (escape_type, new_signer),
+        };
+
+        eprintln!(
+            "Completing {} escape to {}. The transaction must be signed with the {} key alone.",
+            escape_type,
+            format!("{:#064x}", new_signer).bright_yellow(),
+            escape_type.signing_key().bright_yellow()
+        );
+
+        let function = match escape_type {
+            EscapeType::Guardian => "escape_guardian",
+            EscapeType::Owner => "escape_owner",
+        };
+
+        super::send(
+            &self.provider,
+            &target,
+            function,
+            vec![],
+            self.invoke_args,
+            self.yes,
+        )
+        .await?;
+
+        // Account files keep the keys, which no longer match the contract
+        eprintln!(
+            "Once the transaction is confirmed, update the account file with `starkli account \
+            fetch`{}.",
+            if escape_type == EscapeType::Owner {
+                " and sign with the new owner key from then on"
+            } else {
+                ""
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{TimeZone, Utc};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement, FunctionCall, MaybePendingBlockWithTxHashes},
+    macros::selector,
+    providers::Provider,
+};
+
+use crate::{
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    messages::{message, plain_message},
+    provider::ExtendedProvider,
+    subcommands::Invoke,
+    utils::format_address,
+    ProviderArgs,
+};
+
+mod status;
+use status::Status;
+
+mod trigger;
+use trigger::Trigger;
+
+mod complete;
+use complete::Complete;
+
+/// Time an escape stays ready to be completed before it expires, in Argent accounts from v0.3.0.
+const ESCAPE_EXPIRY_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Parser)]
+pub struct Escape {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Show the escape in progress and when it can be completed")]
+    Status(Status),
+    #[clap(about = "Start replacing the guardian or the owner after the security period")]
+    Trigger(Trigger),
+    #[clap(about = "Complete an escape once its security period has passed")]
+    Complete(Complete),
+}
+
+/// The key being replaced by an escape. Each can be escaped with the other key alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EscapeType {
+    Guardian,
+    Owner,
+}
+
+/// An Argent account whose escape is inspected or changed.
+struct ArgentTarget {
+    provider: Arc<ExtendedProvider>,
+    /// The address as entered, forwarded as is to `starkli invoke`.
+    raw_address: String,
+    address: FieldElement,
+}
+
+enum EscapeState {
+    None,
+    Waiting {
+        escape_type: EscapeType,
+        new_signer: FieldElement,
+        ready_at: u64,
+        remaining: u64,
+    },
+    Ready {
+        escape_type: EscapeType,
+        new_signer: FieldElement,
+        expires_at: u64,
+    },
+    Expired {
+        escape_type: EscapeType,
+        new_signer: FieldElement,
+    },
+}
+
+impl Escape {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Status(cmd) => cmd.run().await,
+            Subcommands::Trigger(cmd) => cmd.run().await,
+            Subcommands::Complete(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl ArgentTarget {
+    async fn load(provider: &ProviderArgs, raw_address: &str) -> Result<Self> {
+        let provider = Arc::new(provider.clone().into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = felt_decoder
+            .decode_single_with_addr_fallback(raw_address)
+            .await?;
+
+        Ok(Self {
+            provider,
+            raw_address: raw_address.to_owned(),
+            address,
+        })
+    }
+
+    async fn call(&self, selector: FieldElement) -> Result<Vec<FieldElement>> {
+        Ok(self
+            .provider
+            .call(
+                FunctionCall {
+                    contract_address: self.address,
+                    entry_point_selector: selector,
+                    calldata: vec![],
+                },
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await?)
+    }
+
+    async fn guardian(&self) -> Result<FieldElement> {
+        match self.call(selector!("get_guardian")).await?.as_slice() {
+            [guardian] => Ok(*guardian),
+            _ => anyhow::bail!("unexpected `get_guardian` result"),
+        }
+    }
+
+    /// Reads the escape with the status computed by the account itself, using the pending block
+    /// for the time left, as that's the block a completion would be included in.
+    async fn escape_state(&self) -> Result<EscapeState> {
+        let (ready_at, escape_type, new_signer, status) =
+            match self.call(selector!("get_escape_and_status")).await {
+                Ok(result) => match result.as_slice() {
+                    [ready_at, escape_type, new_signer, status] => (
+                        u64::try_from(*ready_at)?,
+                        *escape_type,
+                        *new_signer,
+                        *status,
+                    ),
+                    _ => anyhow::bail!("unexpected `get_escape_and_status` result"),
+                },
+                Err(err) => anyhow::bail!(
+                    "unable to read the escape of {}: {}. Only Argent accounts from v0.3.0 \
+                    are supported. Older accounts can be upgraded in the Argent X extension",
+                    format_address(self.address),
+                    err
+                ),
+            };
+
+        let escape_type = match u64::try_from(escape_type)? {
+            0 => return Ok(EscapeState::None),
+            1 => EscapeType::Guardian,
+            2 => EscapeType::Owner,
+            value => anyhow::bail!("unknown escape type: {}", value),
+        };
+
+        let now = match self
+            .provider
+            .get_block_with_tx_hashes(BlockId::Tag(BlockTag::Pending))
+            .await?
+        {
+            MaybePendingBlockWithTxHashes::Block(block) => block.timestamp,
+            MaybePendingBlockWithTxHashes::PendingBlock(block) => block.timestamp,
+        };
+
+        // Statuses: 0 = none, 1 = not ready, 2 = ready, 3 = expired
+        Ok(match u64::try_from(status)? {
+            0 => EscapeState::None,
+            1 => EscapeState::Waiting {
+                escape_type,
+                new_signer,
+                ready_at,
+                remaining: ready_at.saturating_sub(now),
+            },
+            2 => EscapeState::Ready {
+                escape_type,
+                new_signer,
+                expires_at: ready_at + ESCAPE_EXPIRY_PERIOD,
+            },
+            3 => EscapeState::Expired {
+                escape_type,
+                new_signer,
+            },
+            value => anyhow::bail!("unknown escape status: {}", value),
+        })
+    }
+}
+
+impl EscapeType {
+    /// The key that signs escape transactions alone, as the escaped key is assumed lost.
+    fn signing_key(&self) -> &'static str {
+        match self {
+            Self::Guardian => "owner",
+            Self::Owner => "guardian",
+        }
+    }
+}
+
+impl std::fmt::Display for EscapeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Guardian => write!(f, "guardian"),
+            Self::Owner => write!(f, "owner"),
+        }
+    }
+}
+
+impl std::fmt::Display for EscapeState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none (no escape in progress)"),
+            Self::Waiting {
+                escape_type,
+                remaining,
+                ..
+            } => write!(
+                f,
+                "{} escape waiting ({} remaining)",
+                escape_type,
+                format_remaining(*remaining)
+            ),
+            Self::Ready { escape_type, .. } => write!(f, "{} escape ready", escape_type),
+            Self::Expired { escape_type, .. } => write!(f, "{} escape expired", escape_type),
+        }
+    }
+}
+
+/// Shows the call, asks for confirmation unless `yes` is set, and sends it from the account to
+/// itself through the regular invoke command so that all its options and checks apply.
+async fn send(
+    provider: &ProviderArgs,
+    target: &ArgentTarget,
+    function: &str,
+    args: Vec<String>,
+    invoke_args: Vec<String>,
+    yes: bool,
+) -> Result<()> {
+    eprintln!(
+        "Account: {}",
+        format_address(target.address).bright_yellow()
+    );
+    eprintln!("Function: {}", function.bright_yellow());
+    for (ind, arg) in args.iter().enumerate() {
+        eprintln!("Argument #{}: {}", ind, arg.bright_yellow());
+    }
+
+    if !yes {
+        eprint!("{}", message("prompt.send", &[]));
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if !matches!(input.trim(), "y" | "Y" | "yes") {
+            anyhow::bail!("{}", plain_message("prompt.aborted", &[]));
+        }
+    }
+
+    let invoke = Invoke::try_parse_from(
+        std::iter::once("starkli invoke".to_owned())
+            .chain(provider.to_args())
+            .chain(invoke_args)
+            .chain([
+                "--".to_owned(),
+                target.raw_address.clone(),
+                function.to_owned(),
+            ])
+            .chain(args),
+    )
+    .unwrap_or_else(|err| err.exit());
+
+    invoke.run().await
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    match Utc.timestamp_opt(timestamp as i64, 0).single() {
+        Some(time) => time.to_rfc3339(),
+        None => timestamp.to_string(),
+    }
+}
+
+fn format_remaining(seconds: u64) -> String {
+    let days = seconds / (24 * 60 * 60);
+    let hours = seconds % (24 * 60 * 60) / (60 * 60);
+    let minutes = seconds % (60 * 60) / 60;
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes.max(1))
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::types::FieldElement;
+
+use crate::{verbosity::VerbosityArgs, ProviderArgs};
+
+use super::{format_timestamp, ArgentTarget, EscapeState};
+
+#[derive(Debug, Parser)]
+pub struct Status {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(help = "Address of the Argent account")]
+    address: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Status {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let target = ArgentTarget::load(&self.provider, &self.address).await?;
+
+        let guardian = target.guardian().await?;
+        eprintln!(
+            "Guardian: {}",
+            if guardian == FieldElement::ZERO {
+                "none".to_owned()
+            } else {
+                format!("{:#064x}", guardian)
+            }
+            .bright_yellow()
+        );
+
+        let state = target.escape_state().await?;
+        match &state {
+            EscapeState::None => {}
+            EscapeState::Waiting {
+                escape_type,
+                new_signer,
+                ready_at,
+                ..
+            } => {
+                eprintln!(
+                    "New {}: {}",
+                    escape_type,
+                    format!("{:#064x}", new_signer).bright_yellow()
+                );
+                eprintln!("Ready at: {}", format_timestamp(*ready_at).bright_yellow());
+            }
+            EscapeState::Ready {
+                escape_type,
+                new_signer,
+                expires_at,
+            } => {
+                eprintln!(
+                    "New {}: {}",
+                    escape_type,
+                    format!("{:#064x}", new_signer).bright_yellow()
+                );
+                eprintln!(
+                    "Expires at: {}",
+                    format_timestamp(*expires_at).bright_yellow()
+                );
+            }
+            EscapeState::Expired {
+                escape_type,
+                new_signer,
+            } => {
+                eprintln!(
+                    "New {}: {}",
+                    escape_type,
+                    format!("{:#064x}", new_signer).bright_yellow()
+                );
+            }
+        }
+        println!("{}", state);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::types::FieldElement;
+
+use crate::{address_book::AddressBookResolver, decode::FeltDecoder, ProviderArgs};
+
+use super::{format_remaining, ArgentTarget, EscapeState, EscapeType};
+
+/// Time from triggering an escape until it can be completed, in Argent accounts from v0.3.0.
+const ESCAPE_SECURITY_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Parser)]
+pub struct Trigger {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        value_name = "NEW_GUARDIAN",
+        conflicts_with = "owner",
+        required_unless_present = "owner",
+        help = "Replace the guardian, signing with the owner key alone. Use 0 to remove the \
+                guardian"
+    )]
+    guardian: Option<String>,
+    #[clap(
+        long,
+        value_name = "NEW_OWNER",
+        help = "Replace the owner (public key), signing with the guardian key alone"
+    )]
+    owner: Option<String>,
+    #[clap(long, short, help = "Send without asking for confirmation")]
+    yes: bool,
+    #[clap(help = "Address of the Argent account")]
+    address: String,
+    #[clap(
+        last = true,
+        help = "Options for `starkli invoke` after `--`, like --account or --keystore"
+    )]
+    invoke_args: Vec<String>,
+}
+
+impl Trigger {
+    pub async fn run(self) -> Result<()> {
+        let target = ArgentTarget::load(&self.provider, &self.address).await?;
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(target.provider.clone()));
+
+        // `clap` makes sure exactly one of them is present
+        let (escape_type, new_signer) = match (&self.guardian, &self.owner) {
+            (Some(guardian), _) => (EscapeType::Guardian, guardian),
+            (_, Some(owner)) => (EscapeType::Owner, owner),
+            _ => unreachable!(),
+        };
+        let new_signer = felt_decoder
+            .decode_single_with_addr_fallback(new_signer)
+            .await?;
+
+        if escape_type == EscapeType::Owner && new_signer == FieldElement::ZERO {
+            anyhow::bail!("the owner cannot be removed");
+        }
+        if target.guardian().await? == FieldElement::ZERO {
+            anyhow::bail!(
+                "the account has no guardian, so there's nothing to escape. The owner can change \
+                keys directly"
+            );
+        }
+
+        let state = target.escape_state().await?;
+        if !matches!(state, EscapeState::None | EscapeState::Expired { .. }) {
+            eprintln!(
+                "{}",
+                format!(
+                    "WARNING: an escape is already in progress ({}). Triggering another one \
+                    replaces it and restarts the security period, if the account allows it.",
+                    state
+                )
+                .bright_magenta()
+            );
+        }
+
+        eprintln!(
+            "The transaction must be signed with the {} key alone. The escape can be completed \
+            with `starkli account argent escape complete` after {}, unless cancelled from the \
+            wallet with both keys.",
+            escape_type.signing_key().bright_yellow(),
+            format_remaining(ESCAPE_SECURITY_PERIOD).bright_yellow()
+        );
+
+        let function = match escape_type {
+            EscapeType::Guardian => "trigger_escape_guardian",
+            EscapeType::Owner => "trigger_escape_owner",
+        };
+
+        super::send(
+            &self.provider,
+            &target,
+            function,
+            vec![format!("{:#064x}", new_signer)],
+            self.invoke_args,
+            self.yes,
+        )
+        .await
+    }
+}
@@ -12,9 +12,11 @@ use starknet::{
 use crate::{
     account::{
         AccountConfig, AccountVariant, ArgentAccountConfig, DeploymentStatus, UndeployedStatus,
+        CURRENT_ACCOUNT_CONFIG_VERSION,
     },
     path::ExpandedPathbufParser,
     signer::SignerArgs,
+    utils::format_address,
 };
 
 /// Official hashes used as of extension version 5.7.0
@@ -51,7 +53,7 @@ impl Init {
         let salt = SigningKey::from_random().secret_scalar();
 
         let account_config = AccountConfig {
-            version: 1,
+            version: CURRENT_ACCOUNT_CONFIG_VERSION,
             variant: AccountVariant::Argent(ArgentAccountConfig {
                 version: 1,
                 implementation: None,
@@ -78,7 +80,7 @@ impl Init {
         eprintln!();
         eprintln!(
             "Once deployed, this account will be available at:\n    {}",
-            format!("{:#064x}", deployed_address).bright_yellow()
+            format_address(deployed_address).bright_yellow()
         );
         eprintln!();
         eprintln!(
@@ -4,6 +4,9 @@ use clap::{Parser, Subcommand};
 mod init;
 use init::Init;
 
+mod escape;
+use escape::Escape;
+
 #[derive(Debug, Parser)]
 pub struct Argent {
     #[clap(subcommand)]
@@ -14,12 +17,15 @@ pub struct Argent {
 enum Subcommands {
     #[clap(about = "Create a new account configuration without actually deploying")]
     Init(Init),
+    #[clap(about = "Recover access by replacing the guardian or the owner through an escape")]
+    Escape(Escape),
 }
 
 impl Argent {
     pub async fn run(self) -> Result<()> {
         match self.command {
             Subcommands::Init(cmd) => cmd.run().await,
+            Subcommands::Escape(cmd) => cmd.run().await,
         }
     }
 }
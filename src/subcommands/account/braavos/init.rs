@@ -13,10 +13,11 @@ use crate::{
     account::{
         AccountConfig, AccountVariant, BraavosAccountConfig, BraavosDeploymentContext,
         BraavosMultisigConfig, BraavosSigner, BraavosStarkSigner, DeploymentContext,
-        DeploymentStatus, UndeployedStatus,
+        DeploymentStatus, UndeployedStatus, CURRENT_ACCOUNT_CONFIG_VERSION,
     },
     path::ExpandedPathbufParser,
     signer::SignerArgs,
+    utils::format_address,
 };
 
 /// Official hashes used as of extension version 3.21.10
@@ -57,7 +58,7 @@ impl Init {
         let salt = SigningKey::from_random().secret_scalar();
 
         let account_config = AccountConfig {
-            version: 1,
+            version: CURRENT_ACCOUNT_CONFIG_VERSION,
             variant: AccountVariant::Braavos(BraavosAccountConfig {
                 version: 1,
                 implementation: BRAAVOS_IMPL_CLASS_HASH,
@@ -88,7 +89,7 @@ impl Init {
         eprintln!();
         eprintln!(
             "Once deployed, this account will be available at:\n    {}",
-            format!("{:#064x}", deployed_address).bright_yellow()
+            format_address(deployed_address).bright_yellow()
         );
         eprintln!();
         eprintln!(
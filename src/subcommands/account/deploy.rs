@@ -3,7 +3,6 @@ use std::{io::Write, path::PathBuf, sync::Arc, time::Duration};
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use colored_json::{ColorMode, Output};
 use starknet::{
     accounts::{AccountFactory, ArgentAccountFactory, OpenZeppelinAccountFactory},
     core::types::{BlockId, BlockTag, FieldElement},
@@ -11,17 +10,26 @@ use starknet::{
     providers::Provider,
     signers::Signer,
 };
+use url::Url;
 
 use crate::{
     account::{
-        AccountConfig, AccountVariant, BraavosMultisigConfig, BraavosSigner, DeployedStatus,
-        DeploymentContext, DeploymentStatus,
+        detect_account_network, AccountConfig, AccountVariant, BraavosMultisigConfig,
+        BraavosSigner, DeployedStatus, DeploymentContext, DeploymentStatus, UndeployedStatus,
     },
     account_factory::{AnyAccountFactory, BraavosAccountFactory},
-    fee::{FeeArgs, FeeSetting},
+    amount::format_ether,
+    error::decode_account_factory_error,
+    explorer::{ExplorerLink, ExplorerLinkArgs},
+    fee::{check_max_fee_ceiling, FeeArgs, FeeSetting},
+    hook::WatchHookArgs,
     path::ExpandedPathbufParser,
-    signer::SignerArgs,
-    utils::watch_tx,
+    progress::ProgressArgs,
+    provider::ExtendedProvider,
+    select::SelectArgs,
+    signer::{AnySigner, SignerArgs},
+    simulation_compare::print_simulation_differences,
+    utils::format_address,
     verbosity::VerbosityArgs,
     ProviderArgs,
 };
@@ -36,6 +44,24 @@ pub struct Deploy {
     fee: FeeArgs,
     #[clap(long, help = "Simulate the transaction only")]
     simulate: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Skip account validation when simulating the transaction"
+    )]
+    skip_validate: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Skip charging the fee when simulating the transaction"
+    )]
+    skip_fee_charge: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Run the same simulation against another JSON-RPC endpoint and show the differences"
+    )]
+    compare_provider: Option<Url>,
     #[clap(long, help = "Provide transaction nonce manually")]
     nonce: Option<FieldElement>,
     #[clap(
@@ -51,6 +77,14 @@ pub struct Deploy {
     )]
     file: PathBuf,
     #[clap(flatten)]
+    explorer: ExplorerLinkArgs,
+    #[clap(flatten)]
+    hooks: WatchHookArgs,
+    #[clap(flatten)]
+    progress: ProgressArgs,
+    #[clap(flatten)]
+    select: SelectArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
@@ -67,11 +101,16 @@ enum MaxFeeType {
 impl Deploy {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
+        let progress = self.progress.reporter();
 
+        let force_fee = self.fee.is_forced();
         let fee_setting = self.fee.into_setting()?;
         if self.simulate && fee_setting.is_estimate_only() {
             anyhow::bail!("--simulate cannot be used with --estimate-only");
         }
+        if !self.select.is_empty() && !self.simulate {
+            anyhow::bail!("--select can only be used with --simulate");
+        }
 
         let provider = Arc::new(self.provider.into_provider()?);
         let signer = Arc::new(self.signer.into_signer()?);
@@ -80,8 +119,7 @@ impl Deploy {
             anyhow::bail!("account config file not found");
         }
 
-        let mut account: AccountConfig =
-            serde_json::from_reader(&mut std::fs::File::open(&self.file)?)?;
+        let mut account = AccountConfig::load(&self.file)?;
 
         let signer_public_key = signer.get_public_key().await?.scalar();
 
@@ -94,100 +132,15 @@ impl Deploy {
 
         let chain_id = provider.chain_id().await?;
 
-        let factory = match &account.variant {
-            AccountVariant::OpenZeppelin(oz_config) => {
-                // Makes sure we're using the right key
-                if signer_public_key != oz_config.public_key {
-                    anyhow::bail!(
-                        "public key mismatch. Expected: {:#064x}; actual: {:#064x}.",
-                        oz_config.public_key,
-                        signer_public_key
-                    );
-                }
-
-                let mut factory = OpenZeppelinAccountFactory::new(
-                    undeployed_status.class_hash,
-                    chain_id,
-                    signer.clone(),
-                    provider.clone(),
-                )
-                .await?;
-                factory.set_block_id(BlockId::Tag(BlockTag::Pending));
-
-                AnyAccountFactory::OpenZeppelin(factory)
-            }
-            AccountVariant::Argent(argent_config) => {
-                // It's probably not worth it to continue to support legacy account deployment.
-                // Users can always deploy with an old Starkli version.
-                if argent_config.implementation.is_some() {
-                    anyhow::bail!(
-                        "deployment of legacy Argent X (Cairo 0) accounts is no longer supported"
-                    );
-                }
-
-                // Makes sure we're using the right key
-                if signer_public_key != argent_config.owner {
-                    anyhow::bail!(
-                        "public key mismatch. Expected: {:#064x}; actual: {:#064x}.",
-                        argent_config.owner,
-                        signer_public_key
-                    );
-                }
-
-                let mut factory = ArgentAccountFactory::new(
-                    undeployed_status.class_hash,
-                    chain_id,
-                    FieldElement::ZERO,
-                    signer.clone(),
-                    provider.clone(),
-                )
-                .await?;
-                factory.set_block_id(BlockId::Tag(BlockTag::Pending));
-
-                AnyAccountFactory::Argent(factory)
-            }
-            AccountVariant::Braavos(braavos_config) => {
-                if !matches!(braavos_config.multisig, BraavosMultisigConfig::Off) {
-                    anyhow::bail!("Braavos accounts cannot be deployed with multisig on");
-                }
-                if braavos_config.signers.len() != 1 {
-                    anyhow::bail!("Braavos accounts can only be deployed with one seed signer");
-                }
-
-                match &undeployed_status.context {
-                    Some(DeploymentContext::Braavos(context)) => {
-                        // Safe to unwrap as we already checked for length
-                        match braavos_config.signers.get(0).unwrap() {
-                            BraavosSigner::Stark(stark_signer) => {
-                                // Makes sure we're using the right key
-                                if signer_public_key != stark_signer.public_key {
-                                    anyhow::bail!(
-                                        "public key mismatch. \
-                                        Expected: {:#064x}; actual: {:#064x}.",
-                                        stark_signer.public_key,
-                                        signer_public_key
-                                    );
-                                }
-
-                                let mut factory = BraavosAccountFactory::new(
-                                    undeployed_status.class_hash,
-                                    context.mock_implementation,
-                                    braavos_config.implementation,
-                                    chain_id,
-                                    signer.clone(),
-                                    provider.clone(),
-                                )
-                                .await?;
-                                factory.set_block_id(BlockId::Tag(BlockTag::Pending));
-
-                                AnyAccountFactory::Braavos(factory)
-                            } // Reject other variants as we add more types
-                        }
-                    }
-                    _ => anyhow::bail!("missing Braavos deployment context"),
-                }
-            }
-        };
+        let factory = build_factory(
+            &account,
+            undeployed_status,
+            signer.clone(),
+            signer_public_key,
+            chain_id,
+            provider.clone(),
+        )
+        .await?;
 
         let account_deployment = factory.deploy(undeployed_status.salt);
 
@@ -200,17 +153,19 @@ impl Deploy {
 
         let max_fee = match fee_setting {
             FeeSetting::Manual(fee) => MaxFeeType::Manual { max_fee: fee },
-            FeeSetting::EstimateOnly | FeeSetting::None => {
-                let estimated_fee = account_deployment.estimate_fee().await?.overall_fee;
+            FeeSetting::EstimateOnly { .. } | FeeSetting::None => {
+                let fee_estimate = account_deployment
+                    .estimate_fee()
+                    .await
+                    .map_err(decode_account_factory_error)?;
+                let estimated_fee = fee_estimate.overall_fee;
+                progress.fee_estimated(estimated_fee);
 
                 // TODO: make buffer configurable
                 let estimated_fee_with_buffer = (estimated_fee * felt!("3")).floor_div(felt!("2"));
 
                 if fee_setting.is_estimate_only() {
-                    println!(
-                        "{} ETH",
-                        format!("{}", estimated_fee.to_big_decimal(18)).bright_yellow(),
-                    );
+                    fee_setting.print_estimate(&fee_estimate)?;
                     return Ok(());
                 }
 
@@ -221,14 +176,16 @@ impl Deploy {
             }
         };
 
+        check_max_fee_ceiling(&provider, max_fee.max_fee(), force_fee)?;
+
         if !self.simulate {
             match max_fee {
                 MaxFeeType::Manual { max_fee } => {
                     eprintln!(
                         "You've manually specified the account deployment fee to be {}. \
                         Therefore, fund at least:\n    {}",
-                        format!("{} ETH", max_fee.to_big_decimal(18)).bright_yellow(),
-                        format!("{} ETH", max_fee.to_big_decimal(18)).bright_yellow(),
+                        format!("{} ETH", format_ether(max_fee)).bright_yellow(),
+                        format!("{} ETH", format_ether(max_fee)).bright_yellow(),
                     );
                 }
                 MaxFeeType::Estimated {
@@ -238,15 +195,15 @@ impl Deploy {
                     eprintln!(
                         "The estimated account deployment fee is {}. \
                         However, to avoid failure, fund at least:\n    {}",
-                        format!("{} ETH", estimate.to_big_decimal(18)).bright_yellow(),
-                        format!("{} ETH", estimate_with_buffer.to_big_decimal(18)).bright_yellow()
+                        format!("{} ETH", format_ether(estimate)).bright_yellow(),
+                        format!("{} ETH", format_ether(estimate_with_buffer)).bright_yellow()
                     );
                 }
             }
 
             eprintln!(
                 "to the following address:\n    {}",
-                format!("{:#064x}", target_deployment_address).bright_yellow()
+                format_address(target_deployment_address).bright_yellow()
             );
 
             // TODO: add flag for skipping this manual confirmation step
@@ -260,22 +217,64 @@ impl Deploy {
         };
         let account_deployment = account_deployment.max_fee(max_fee.max_fee());
 
+        if let Some(compare_provider) = self.compare_provider {
+            // Unlike other transactions, the signed deploy account request isn't accessible, so we
+            // sign the same deployment again with a factory connected to the other endpoint.
+            let nonce = match self.nonce {
+                Some(nonce) => nonce,
+                None => account_deployment.fetch_nonce().await?,
+            };
+            let compare_factory = build_factory(
+                &account,
+                undeployed_status,
+                signer.clone(),
+                signer_public_key,
+                chain_id,
+                Arc::new(ExtendedProvider::new(compare_provider, false)),
+            )
+            .await?;
+
+            let account_deployment = account_deployment.nonce(nonce);
+            let compare_deployment = compare_factory
+                .deploy(undeployed_status.salt)
+                .nonce(nonce)
+                .max_fee(max_fee.max_fee());
+
+            let (left, right) = tokio::join!(
+                account_deployment.simulate(self.skip_validate, self.skip_fee_charge),
+                compare_deployment.simulate(self.skip_validate, self.skip_fee_charge)
+            );
+            print_simulation_differences(
+                &serde_json::to_value(left?)?,
+                &serde_json::to_value(right?)?,
+            );
+
+            return Ok(());
+        }
+
         if self.simulate {
-            let simulation = account_deployment.simulate(false, false).await?;
+            let simulation = account_deployment
+                .simulate(self.skip_validate, self.skip_fee_charge)
+                .await?;
             let simulation_json = serde_json::to_value(simulation)?;
 
-            let simulation_json =
-                colored_json::to_colored_json(&simulation_json, ColorMode::Auto(Output::StdOut))?;
-            println!("{simulation_json}");
-            return Ok(());
+            return self.select.print(&simulation_json);
         }
 
         // TODO: add option to check ETH balance before sending out tx
-        let account_deployment_tx = account_deployment.send().await?.transaction_hash;
+        let account_deployment_tx = account_deployment
+            .send()
+            .await
+            .map_err(decode_account_factory_error)?
+            .transaction_hash;
+        progress.transaction_sent(account_deployment_tx);
         eprintln!(
             "Account deployment transaction: {}",
             format!("{:#064x}", account_deployment_tx).bright_yellow()
         );
+        self.explorer
+            .print_link(&provider, ExplorerLink::Transaction(account_deployment_tx))
+            .await;
 
         // By default we wait for the tx to confirm so that we don't incorrectly mark the account
         // as deployed
@@ -285,16 +284,19 @@ impl Deploy {
             format!("{:#064x}", account_deployment_tx).bright_yellow(),
             "starkli account fetch".bright_yellow(),
         );
-        watch_tx(
-            &provider,
-            account_deployment_tx,
-            Duration::from_millis(self.poll_interval),
-        )
-        .await?;
+        self.hooks
+            .watch_tx(
+                &provider,
+                account_deployment_tx,
+                Duration::from_millis(self.poll_interval),
+            )
+            .await?;
+        progress.transaction_confirmed(account_deployment_tx);
 
         account.deployment = DeploymentStatus::Deployed(DeployedStatus {
             class_hash: undeployed_status.class_hash,
             address: target_deployment_address,
+            network: detect_account_network(&provider).await,
         });
 
         // Never write directly to the original file to avoid data loss
@@ -327,3 +329,110 @@ impl MaxFeeType {
         }
     }
 }
+
+async fn build_factory(
+    account: &AccountConfig,
+    undeployed_status: &UndeployedStatus,
+    signer: Arc<AnySigner>,
+    signer_public_key: FieldElement,
+    chain_id: FieldElement,
+    provider: Arc<ExtendedProvider>,
+) -> Result<AnyAccountFactory<Arc<AnySigner>, Arc<ExtendedProvider>>> {
+    Ok(match &account.variant {
+        AccountVariant::OpenZeppelin(oz_config) => {
+            // Makes sure we're using the right key
+            if signer_public_key != oz_config.public_key {
+                anyhow::bail!(
+                    "public key mismatch. Expected: {:#064x}; actual: {:#064x}.",
+                    oz_config.public_key,
+                    signer_public_key
+                );
+            }
+
+            let mut factory = OpenZeppelinAccountFactory::new(
+                undeployed_status.class_hash,
+                chain_id,
+                signer,
+                provider,
+            )
+            .await?;
+            factory.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+            AnyAccountFactory::OpenZeppelin(factory)
+        }
+        AccountVariant::Argent(argent_config) => {
+            // It's probably not worth it to continue to support legacy account deployment.
+            // Users can always deploy with an old Starkli version.
+            if argent_config.implementation.is_some() {
+                anyhow::bail!(
+                    "deployment of legacy Argent X (Cairo 0) accounts is no longer supported"
+                );
+            }
+
+            // Makes sure we're using the right key
+            if signer_public_key != argent_config.owner {
+                anyhow::bail!(
+                    "public key mismatch. Expected: {:#064x}; actual: {:#064x}.",
+                    argent_config.owner,
+                    signer_public_key
+                );
+            }
+
+            let mut factory = ArgentAccountFactory::new(
+                undeployed_status.class_hash,
+                chain_id,
+                FieldElement::ZERO,
+                signer,
+                provider,
+            )
+            .await?;
+            factory.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+            AnyAccountFactory::Argent(factory)
+        }
+        AccountVariant::WatchOnly(_) => {
+            anyhow::bail!("watch-only accounts cannot be deployed")
+        }
+        AccountVariant::Braavos(braavos_config) => {
+            if !matches!(braavos_config.multisig, BraavosMultisigConfig::Off) {
+                anyhow::bail!("Braavos accounts cannot be deployed with multisig on");
+            }
+            if braavos_config.signers.len() != 1 {
+                anyhow::bail!("Braavos accounts can only be deployed with one seed signer");
+            }
+
+            match &undeployed_status.context {
+                Some(DeploymentContext::Braavos(context)) => {
+                    // Safe to unwrap as we already checked for length
+                    match braavos_config.signers.get(0).unwrap() {
+                        BraavosSigner::Stark(stark_signer) => {
+                            // Makes sure we're using the right key
+                            if signer_public_key != stark_signer.public_key {
+                                anyhow::bail!(
+                                    "public key mismatch. \
+                                    Expected: {:#064x}; actual: {:#064x}.",
+                                    stark_signer.public_key,
+                                    signer_public_key
+                                );
+                            }
+
+                            let mut factory = BraavosAccountFactory::new(
+                                undeployed_status.class_hash,
+                                context.mock_implementation,
+                                braavos_config.implementation,
+                                chain_id,
+                                signer,
+                                provider,
+                            )
+                            .await?;
+                            factory.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+                            AnyAccountFactory::Braavos(factory)
+                        } // Reject other variants as we add more types
+                    }
+                }
+                _ => anyhow::bail!("missing Braavos deployment context"),
+            }
+        }
+    })
+}
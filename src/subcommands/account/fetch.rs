@@ -4,16 +4,17 @@ use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
 use starknet::{
-    core::types::{BlockId, BlockTag, FieldElement, FunctionCall},
+    core::types::{BlockId, BlockTag, ContractClass, FieldElement, FunctionCall},
     macros::selector,
     providers::Provider,
 };
 
 use crate::{
     account::{
-        AccountConfig, AccountVariant, AccountVariantType, ArgentAccountConfig,
-        BraavosMultisigConfig, BraavosSigner, DeployedStatus, DeploymentStatus, OzAccountConfig,
-        KNOWN_ACCOUNT_CLASSES,
+        detect_account_network, AccountConfig, AccountVariant, AccountVariantType,
+        ArgentAccountConfig, BraavosMultisigConfig, BraavosSigner, DeployedStatus,
+        DeploymentStatus, KnownAccountClass, OzAccountConfig, WatchOnlyAccountConfig,
+        CURRENT_ACCOUNT_CONFIG_VERSION, KNOWN_ACCOUNT_CLASSES,
     },
     verbosity::VerbosityArgs,
     ProviderArgs,
@@ -27,6 +28,11 @@ pub struct Fetch {
     force: bool,
     #[clap(long, help = "Path to save the account config file")]
     output: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "Save the account as watch-only without a signer. Works with any account contract"
+    )]
+    watch_only: bool,
     #[clap(help = "Contract address")]
     address: String,
     #[clap(flatten)]
@@ -53,219 +59,53 @@ impl Fetch {
         let provider = self.provider.into_provider()?;
         let address = FieldElement::from_hex_be(&self.address)?;
 
-        let class_hash = provider
-            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
-            .await?;
-
-        let known_class = match KNOWN_ACCOUNT_CLASSES
-            .iter()
-            .find(|class| class.class_hash == class_hash)
-        {
-            Some(class) => class,
-            None => {
-                eprintln!(
-                    "{} is not a known account class hash. \
-                    If you believe this is a bug, submit a PR to:",
-                    format!("{:#064x}", class_hash).bright_yellow()
-                );
-                eprintln!("    https://github.com/xJonathanLEI/starkli");
-                anyhow::bail!("unknown class hash: {:#064x}", class_hash);
-            }
-        };
+        let (class_hash, variant) = if self.watch_only {
+            let class_hash = provider
+                .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+                .await?;
+            let legacy = matches!(
+                provider
+                    .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
+                    .await?,
+                ContractClass::Legacy(_)
+            );
 
-        eprintln!(
-            "Account contract type identified as: {}",
-            format!("{}", known_class.variant).bright_yellow()
-        );
-        eprintln!("Description: {}", known_class.description.bright_yellow());
+            (
+                class_hash,
+                AccountVariant::WatchOnly(WatchOnlyAccountConfig { version: 1, legacy }),
+            )
+        } else {
+            let (class_hash, known_class) = identify_account_class(&provider, address).await?;
 
-        // No need to proceed if the user doesn't even want to save the config
-        let output = match self.output {
-            Some(output) => output,
-            None => return Ok(()),
-        };
+            eprintln!(
+                "Account contract type identified as: {}",
+                format!("{}", known_class.variant).bright_yellow()
+            );
+            eprintln!("Description: {}", known_class.description.bright_yellow());
 
-        let variant = match known_class.variant {
-            AccountVariantType::OpenZeppelinLegacy => {
-                let public_key = provider
-                    .call(
-                        FunctionCall {
-                            contract_address: address,
-                            entry_point_selector: selector!("getPublicKey"),
-                            calldata: vec![],
-                        },
-                        BlockId::Tag(BlockTag::Pending),
-                    )
-                    .await?[0];
-
-                AccountVariant::OpenZeppelin(OzAccountConfig {
-                    version: 1,
-                    public_key,
-                    legacy: true,
-                })
-            }
-            AccountVariantType::ArgentLegacy => {
-                let implementation = provider
-                    .call(
-                        FunctionCall {
-                            contract_address: address,
-                            entry_point_selector: selector!("get_implementation"),
-                            calldata: vec![],
-                        },
-                        BlockId::Tag(BlockTag::Pending),
-                    )
-                    .await?[0];
-                let signer = provider
-                    .call(
-                        FunctionCall {
-                            contract_address: address,
-                            entry_point_selector: selector!("getSigner"),
-                            calldata: vec![],
-                        },
-                        BlockId::Tag(BlockTag::Pending),
-                    )
-                    .await?[0];
-                let guardian = provider
-                    .call(
-                        FunctionCall {
-                            contract_address: address,
-                            entry_point_selector: selector!("getGuardian"),
-                            calldata: vec![],
-                        },
-                        BlockId::Tag(BlockTag::Pending),
-                    )
-                    .await?[0];
-
-                AccountVariant::Argent(ArgentAccountConfig {
-                    version: 1,
-                    implementation: Some(implementation),
-                    owner: signer,
-                    guardian,
-                })
+            // No need to proceed if the user doesn't even want to save the config
+            if self.output.is_none() {
+                return Ok(());
             }
-            AccountVariantType::Braavos => {
-                let implementation = provider
-                    .call(
-                        FunctionCall {
-                            contract_address: address,
-                            entry_point_selector: selector!("get_implementation"),
-                            calldata: vec![],
-                        },
-                        BlockId::Tag(BlockTag::Pending),
-                    )
-                    .await?[0];
-                let signers = provider
-                    .call(
-                        FunctionCall {
-                            contract_address: address,
-                            entry_point_selector: selector!("get_signers"),
-                            calldata: vec![],
-                        },
-                        BlockId::Tag(BlockTag::Pending),
-                    )
-                    .await?;
-                let multisig = provider
-                    .call(
-                        FunctionCall {
-                            contract_address: address,
-                            entry_point_selector: selector!("get_multisig"),
-                            calldata: vec![],
-                        },
-                        BlockId::Tag(BlockTag::Pending),
-                    )
-                    .await?[0];
-
-                let signers = {
-                    let mut buffer = vec![];
-
-                    let num_signers = TryInto::<u64>::try_into(signers[0])? as usize;
-
-                    for ind_signer in 0..num_signers {
-                        let base_offset = ind_signer * 8 + 1;
-
-                        if Into::<FieldElement>::into(ind_signer as u64) != signers[base_offset] {
-                            anyhow::bail!("unable to decode Braavos signers: index mismatch");
-                        }
-
-                        let signer =
-                            BraavosSigner::decode(&signers[(base_offset + 1)..(base_offset + 8)])?;
-
-                        buffer.push(signer);
-                    }
 
-                    buffer
-                };
+            (
+                class_hash,
+                fetch_account_variant(&provider, address, &known_class.variant).await?,
+            )
+        };
 
-                let multisig = if multisig == FieldElement::ZERO {
-                    BraavosMultisigConfig::Off
-                } else {
-                    BraavosMultisigConfig::On {
-                        num_signers: TryInto::<u64>::try_into(multisig)? as usize,
-                    }
-                };
-
-                AccountVariant::Braavos(crate::account::BraavosAccountConfig {
-                    version: 1,
-                    implementation,
-                    multisig,
-                    signers,
-                })
-            }
-            AccountVariantType::Argent => {
-                let owner = provider
-                    .call(
-                        FunctionCall {
-                            contract_address: address,
-                            entry_point_selector: selector!("get_owner"),
-                            calldata: vec![],
-                        },
-                        BlockId::Tag(BlockTag::Pending),
-                    )
-                    .await?[0];
-                let guardian = provider
-                    .call(
-                        FunctionCall {
-                            contract_address: address,
-                            entry_point_selector: selector!("get_guardian"),
-                            calldata: vec![],
-                        },
-                        BlockId::Tag(BlockTag::Pending),
-                    )
-                    .await?[0];
-
-                AccountVariant::Argent(ArgentAccountConfig {
-                    version: 1,
-                    implementation: None,
-                    owner,
-                    guardian,
-                })
-            }
-            AccountVariantType::OpenZeppelin => {
-                let public_key = provider
-                    .call(
-                        FunctionCall {
-                            contract_address: address,
-                            entry_point_selector: selector!("get_public_key"),
-                            calldata: vec![],
-                        },
-                        BlockId::Tag(BlockTag::Pending),
-                    )
-                    .await?[0];
-
-                AccountVariant::OpenZeppelin(OzAccountConfig {
-                    version: 1,
-                    public_key,
-                    legacy: false,
-                })
-            }
+        let output = match self.output {
+            Some(output) => output,
+            None => return Ok(()),
         };
 
         let account = AccountConfig {
-            version: 1,
+            version: CURRENT_ACCOUNT_CONFIG_VERSION,
             variant,
             deployment: DeploymentStatus::Deployed(DeployedStatus {
                 class_hash,
                 address,
+                network: detect_account_network(&provider).await,
             }),
         };
 
@@ -281,3 +121,222 @@ impl Fetch {
         Ok(())
     }
 }
+
+/// Identifies the account contract deployed at `address` by its class hash.
+pub async fn identify_account_class<P>(
+    provider: P,
+    address: FieldElement,
+) -> Result<(FieldElement, &'static KnownAccountClass)>
+where
+    P: Provider,
+{
+    let class_hash = provider
+        .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+        .await?;
+
+    let known_class = match KNOWN_ACCOUNT_CLASSES
+        .iter()
+        .find(|class| class.class_hash == class_hash)
+    {
+        Some(class) => class,
+        None => {
+            eprintln!(
+                "{} is not a known account class hash. \
+                If you believe this is a bug, submit a PR to:",
+                format!("{:#064x}", class_hash).bright_yellow()
+            );
+            eprintln!("    https://github.com/xJonathanLEI/starkli");
+            anyhow::bail!("unknown class hash: {:#064x}", class_hash);
+        }
+    };
+
+    Ok((class_hash, known_class))
+}
+
+/// Reads the variant-specific account config from chain.
+pub async fn fetch_account_variant<P>(
+    provider: P,
+    address: FieldElement,
+    variant_type: &AccountVariantType,
+) -> Result<AccountVariant>
+where
+    P: Provider,
+{
+    Ok(match variant_type {
+        AccountVariantType::OpenZeppelinLegacy => {
+            let public_key = provider
+                .call(
+                    FunctionCall {
+                        contract_address: address,
+                        entry_point_selector: selector!("getPublicKey"),
+                        calldata: vec![],
+                    },
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?[0];
+
+            AccountVariant::OpenZeppelin(OzAccountConfig {
+                version: 1,
+                public_key,
+                legacy: true,
+            })
+        }
+        AccountVariantType::ArgentLegacy => {
+            let implementation = provider
+                .call(
+                    FunctionCall {
+                        contract_address: address,
+                        entry_point_selector: selector!("get_implementation"),
+                        calldata: vec![],
+                    },
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?[0];
+            let signer = provider
+                .call(
+                    FunctionCall {
+                        contract_address: address,
+                        entry_point_selector: selector!("getSigner"),
+                        calldata: vec![],
+                    },
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?[0];
+            let guardian = provider
+                .call(
+                    FunctionCall {
+                        contract_address: address,
+                        entry_point_selector: selector!("getGuardian"),
+                        calldata: vec![],
+                    },
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?[0];
+
+            AccountVariant::Argent(ArgentAccountConfig {
+                version: 1,
+                implementation: Some(implementation),
+                owner: signer,
+                guardian,
+            })
+        }
+        AccountVariantType::Braavos => {
+            let implementation = provider
+                .call(
+                    FunctionCall {
+                        contract_address: address,
+                        entry_point_selector: selector!("get_implementation"),
+                        calldata: vec![],
+                    },
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?[0];
+            let signers = provider
+                .call(
+                    FunctionCall {
+                        contract_address: address,
+                        entry_point_selector: selector!("get_signers"),
+                        calldata: vec![],
+                    },
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?;
+            let multisig = provider
+                .call(
+                    FunctionCall {
+                        contract_address: address,
+                        entry_point_selector: selector!("get_multisig"),
+                        calldata: vec![],
+                    },
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?[0];
+
+            let signers = {
+                let mut buffer = vec![];
+
+                let num_signers = TryInto::<u64>::try_into(signers[0])? as usize;
+
+                for ind_signer in 0..num_signers {
+                    let base_offset = ind_signer * 8 + 1;
+
+                    if Into::<FieldElement>::into(ind_signer as u64) != signers[base_offset] {
+                        anyhow::bail!("unable to decode Braavos signers: index mismatch");
+                    }
+
+                    let signer =
+                        BraavosSigner::decode(&signers[(base_offset + 1)..(base_offset + 8)])?;
+
+                    buffer.push(signer);
+                }
+
+                buffer
+            };
+
+            let multisig = if multisig == FieldElement::ZERO {
+                BraavosMultisigConfig::Off
+            } else {
+                BraavosMultisigConfig::On {
+                    num_signers: TryInto::<u64>::try_into(multisig)? as usize,
+                }
+            };
+
+            AccountVariant::Braavos(crate::account::BraavosAccountConfig {
+                version: 1,
+                implementation,
+                multisig,
+                signers,
+            })
+        }
+        AccountVariantType::Argent => {
+            let owner = provider
+                .call(
+                    FunctionCall {
+                        contract_address: address,
+                        entry_point_selector: selector!("get_owner"),
+                        calldata: vec![],
+                    },
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?[0];
+            let guardian = provider
+                .call(
+                    FunctionCall {
+                        contract_address: address,
+                        entry_point_selector: selector!("get_guardian"),
+                        calldata: vec![],
+                    },
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?[0];
+
+            AccountVariant::Argent(ArgentAccountConfig {
+                version: 1,
+                implementation: None,
+                owner,
+                guardian,
+            })
+        }
+        AccountVariantType::WatchOnly => {
+            anyhow::bail!("watch-only accounts have no config to fetch")
+        }
+        AccountVariantType::OpenZeppelin => {
+            let public_key = provider
+                .call(
+                    FunctionCall {
+                        contract_address: address,
+                        entry_point_selector: selector!("get_public_key"),
+                        calldata: vec![],
+                    },
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?[0];
+
+            AccountVariant::OpenZeppelin(OzAccountConfig {
+                version: 1,
+                public_key,
+                legacy: false,
+            })
+        }
+    })
+}
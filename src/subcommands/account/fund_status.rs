@@ -0,0 +1,115 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use clap::Parser;
+use colored::Colorize;
+use num_bigint::{BigUint, ToBigInt};
+use starknet::core::types::FieldElement;
+
+use crate::{
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    provider::ExtendedProvider,
+    token::fetch_balance,
+    utils::{bigdecimal_to_felt, format_address},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+/// Both ETH and STRK use 18 decimals.
+const FEE_TOKEN_DECIMALS: i64 = 18;
+
+#[derive(Debug, Parser)]
+pub struct FundStatus {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "Minimum balance required, with an `eth` or `strk` suffix (e.g. 0.005eth). \
+                Defaults to ETH without a suffix"
+    )]
+    min: String,
+    #[clap(long, help = "Wait until the balance reaches the minimum")]
+    wait: bool,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        default_value = "5000",
+        help = "Balance poll interval in milliseconds"
+    )]
+    poll_interval: u64,
+    #[clap(help = "Account address")]
+    address: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl FundStatus {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = felt_decoder
+            .decode_single_with_addr_fallback(&self.address)
+            .await?;
+        let (min, symbol, token) = parse_min_balance(&self.min, &provider)?;
+
+        loop {
+            let balance = fetch_balance(&provider, token, address).await?;
+            let balance_dec = BigDecimal::new(
+                // `to_bigint()` from `BigUint` always returns `Some`.
+                balance.to_bigint().unwrap(),
+                FEE_TOKEN_DECIMALS,
+            );
+
+            if balance >= min {
+                eprintln!(
+                    "Account {} is funded with {} {}",
+                    format_address(address).bright_yellow(),
+                    balance_dec.to_string().bright_yellow(),
+                    symbol
+                );
+                return Ok(());
+            }
+
+            if !self.wait {
+                anyhow::bail!(
+                    "insufficient balance: {} {} (minimum {})",
+                    balance_dec,
+                    symbol,
+                    self.min
+                );
+            }
+
+            eprintln!(
+                "Balance {} {} below minimum. Waiting for funds...",
+                balance_dec.to_string().bright_yellow(),
+                symbol
+            );
+            tokio::time::sleep(Duration::from_millis(self.poll_interval)).await;
+        }
+    }
+}
+
+fn parse_min_balance(
+    raw: &str,
+    provider: &ExtendedProvider,
+) -> Result<(BigUint, &'static str, FieldElement)> {
+    let raw = raw.trim().to_lowercase();
+
+    let (amount, symbol, token) = if let Some(amount) = raw.strip_suffix("strk") {
+        (amount, "STRK", provider.strk_token_address())
+    } else if let Some(amount) = raw.strip_suffix("eth") {
+        (amount, "ETH", provider.eth_token_address())
+    } else {
+        (raw.as_str(), "ETH", provider.eth_token_address())
+    };
+
+    let amount: BigDecimal = amount.trim().parse()?;
+    let amount = bigdecimal_to_felt(&amount, FEE_TOKEN_DECIMALS)?;
+
+    Ok((BigUint::from_bytes_be(&amount.to_bytes_be()), symbol, token))
+}
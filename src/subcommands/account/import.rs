@@ -0,0 +1,331 @@
+use std::{io::Write, path::PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{core::types::FieldElement, providers::Provider, signers::SigningKey};
+
+use crate::{
+    account::{
+        detect_account_network, AccountConfig, AccountVariant, AccountVariantType,
+        ArgentAccountConfig, DeployedStatus, DeploymentStatus, OzAccountConfig, UndeployedStatus,
+        CURRENT_ACCOUNT_CONFIG_VERSION, KNOWN_ACCOUNT_CLASSES,
+    },
+    path::ExpandedPathbufParser,
+    provider::ExtendedProvider,
+    verbosity::VerbosityArgs,
+    wallet_format::{accounts_file_network, AccountsFile, AccountsFileEntry, WalletFormat},
+    warning::Warning,
+    ProviderArgs,
+};
+
+use super::fetch::{fetch_account_variant, identify_account_class};
+
+#[derive(Debug, Parser)]
+pub struct Import {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, value_enum, help = "Format of the accounts file to import from")]
+    format: WalletFormat,
+    #[clap(
+        long,
+        help = "Name of the account to import when the accounts file lists more than one for the \
+                network"
+    )]
+    name: Option<String>,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Path to save the encrypted keystore for the imported key"
+    )]
+    keystore_output: PathBuf,
+    #[clap(
+        long,
+        help = "Supply keystore password from command line option instead of prompt"
+    )]
+    keystore_password: Option<String>,
+    #[clap(long, help = "Overwrite the files if they already exist")]
+    force: bool,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the accounts file"
+    )]
+    file: PathBuf,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to save the account config file"
+    )]
+    output: PathBuf,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+struct ImportedKey {
+    address: FieldElement,
+    private_key: FieldElement,
+    /// Accounts files also list accounts that are not deployed yet.
+    undeployed: Option<ImportedUndeployed>,
+}
+
+struct ImportedUndeployed {
+    class_hash: FieldElement,
+    salt: FieldElement,
+    account_type: Option<String>,
+    legacy: bool,
+}
+
+impl Import {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        if self.output.exists() && !self.force {
+            anyhow::bail!("account config file already exists");
+        }
+        if self.keystore_output.exists() && !self.force {
+            anyhow::bail!("keystore file already exists");
+        }
+
+        let provider = self.provider.clone().into_provider()?;
+
+        let imported = self.read_accounts_file(&provider).await?;
+
+        let signing_key = SigningKey::from_secret_scalar(imported.private_key);
+        let public_key = signing_key.verifying_key().scalar();
+
+        let (variant, deployment) = match &imported.undeployed {
+            Some(undeployed) => {
+                let variant = undeployed_variant(undeployed, public_key)?;
+
+                let account = AccountConfig {
+                    version: CURRENT_ACCOUNT_CONFIG_VERSION,
+                    variant,
+                    deployment: DeploymentStatus::Undeployed(UndeployedStatus {
+                        class_hash: undeployed.class_hash,
+                        salt: undeployed.salt,
+                        context: None,
+                    }),
+                };
+
+                // Makes sure the account can be deployed to the address listed in the file
+                let address = account.deploy_account_address()?;
+                if address != imported.address {
+                    anyhow::bail!(
+                        "address mismatch. Expected: {:#064x}; computed: {:#064x}.",
+                        imported.address,
+                        address
+                    );
+                }
+
+                eprintln!(
+                    "Account not deployed yet. Deploy it with: {}",
+                    "starkli account deploy".bright_yellow()
+                );
+
+                (account.variant, account.deployment)
+            }
+            None => {
+                let (class_hash, known_class) =
+                    identify_account_class(&provider, imported.address).await?;
+                eprintln!(
+                    "Account contract type identified as: {}",
+                    format!("{}", known_class.variant).bright_yellow()
+                );
+
+                let variant =
+                    fetch_account_variant(&provider, imported.address, &known_class.variant)
+                        .await?;
+
+                // Makes sure the imported key actually controls the account
+                match variant.owner_public_key() {
+                    Some(expected_public_key) if expected_public_key == public_key => {}
+                    Some(expected_public_key) => anyhow::bail!(
+                        "public key mismatch. Expected: {:#064x}; actual: {:#064x}.",
+                        expected_public_key,
+                        public_key
+                    ),
+                    None => anyhow::bail!(
+                        "only accounts controlled by a single Stark key can be imported"
+                    ),
+                }
+
+                (
+                    variant,
+                    DeploymentStatus::Deployed(DeployedStatus {
+                        class_hash,
+                        address: imported.address,
+                        network: detect_account_network(&provider).await,
+                    }),
+                )
+            }
+        };
+
+        if self.keystore_password.is_some() {
+            Warning::PasswordOption.print(
+                "setting keystore passwords via --keystore-password is generally considered \
+                insecure, as they will be stored in your shell history or other log files.",
+            );
+        }
+        let password = match self.keystore_password {
+            Some(password) => password,
+            None => rpassword::prompt_password("Enter password for the new keystore: ")?,
+        };
+        signing_key.save_as_keystore(&self.keystore_output, &password)?;
+
+        let account = AccountConfig {
+            version: CURRENT_ACCOUNT_CONFIG_VERSION,
+            variant,
+            deployment,
+        };
+
+        let mut file = std::fs::File::create(&self.output)?;
+        serde_json::to_writer_pretty(&mut file, &account)?;
+        file.write_all(b"\n")?;
+
+        eprintln!(
+            "Created new encrypted keystore file: {}",
+            std::fs::canonicalize(&self.keystore_output)?.display()
+        );
+        eprintln!(
+            "Created new account config file: {}",
+            std::fs::canonicalize(&self.output)?.display()
+        );
+
+        Ok(())
+    }
+
+    async fn read_accounts_file(&self, provider: &ExtendedProvider) -> Result<ImportedKey> {
+        if !self.file.exists() {
+            anyhow::bail!("accounts file not found");
+        }
+        let mut file = std::fs::File::open(&self.file)?;
+
+        Ok(match self.format {
+            WalletFormat::Sncast | WalletFormat::CairoLang => {
+                let accounts_file: AccountsFile = serde_json::from_reader(&mut file)?;
+                let network = accounts_file_network(provider.chain_id().await?);
+                let (name, entry) = self.select_account_entry(accounts_file, &network)?;
+                eprintln!(
+                    "Importing account {} of network {}",
+                    name.bright_yellow(),
+                    network.bright_yellow()
+                );
+
+                let undeployed = if entry.deployed {
+                    None
+                } else {
+                    match (entry.class_hash, entry.salt) {
+                        (Some(class_hash), Some(salt)) => Some(ImportedUndeployed {
+                            class_hash,
+                            salt,
+                            account_type: entry.account_type,
+                            legacy: entry.legacy,
+                        }),
+                        _ => anyhow::bail!(
+                            "account {} is not deployed, and the file lacks the class hash and \
+                            salt needed to deploy it. Deploy it with the original tool first",
+                            name
+                        ),
+                    }
+                };
+
+                ImportedKey {
+                    address: entry.address,
+                    private_key: entry.private_key,
+                    undeployed,
+                }
+            }
+        })
+    }
+
+    /// Picks the entry for the provider's network, falling back to the only network in the file
+    /// since custom networks may be named differently.
+    fn select_account_entry(
+        &self,
+        mut accounts_file: AccountsFile,
+        network: &str,
+    ) -> Result<(String, AccountsFileEntry)> {
+        let network_key = match accounts_file
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case(network))
+        {
+            Some(key) => key.to_owned(),
+            None if accounts_file.len() == 1 => accounts_file.keys().next().unwrap().to_owned(),
+            None => anyhow::bail!(
+                "no account for network {} in the accounts file (found: {})",
+                network,
+                accounts_file.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        };
+        // Safe to unwrap as the key was just found
+        let mut accounts = accounts_file.remove(&network_key).unwrap();
+
+        let name = match (&self.name, accounts.len()) {
+            (_, 0) => anyhow::bail!("the accounts file contains no account"),
+            (Some(name), _) => name.to_owned(),
+            (None, 1) => accounts.keys().next().unwrap().to_owned(),
+            // The legacy cairo-lang CLI names its default account `__default__`
+            (None, _) if accounts.contains_key("__default__") => "__default__".to_owned(),
+            (None, _) => anyhow::bail!(
+                "the accounts file contains {} accounts ({}). Use --name to choose one",
+                accounts.len(),
+                accounts.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        };
+
+        match accounts.remove(&name) {
+            Some(entry) => Ok((name, entry)),
+            None => anyhow::bail!("account {} not found in the accounts file", name),
+        }
+    }
+}
+
+/// Builds the account config of an undeployed account. The class hash decides the variant when
+/// it's a known one, and the account type written by `sncast` otherwise.
+fn undeployed_variant(
+    undeployed: &ImportedUndeployed,
+    public_key: FieldElement,
+) -> Result<AccountVariant> {
+    let variant = KNOWN_ACCOUNT_CLASSES
+        .iter()
+        .find(|class| class.class_hash == undeployed.class_hash)
+        .map(|class| &class.variant);
+
+    Ok(
+        match (
+            variant,
+            undeployed.account_type.as_deref(),
+            undeployed.legacy,
+        ) {
+            (Some(AccountVariantType::OpenZeppelin), _, _)
+            | (None, Some("open_zeppelin" | "oz"), false) => {
+                AccountVariant::OpenZeppelin(OzAccountConfig {
+                    version: 1,
+                    public_key,
+                    legacy: false,
+                })
+            }
+            (Some(AccountVariantType::OpenZeppelinLegacy), _, _)
+            | (None, Some("open_zeppelin" | "oz"), true) => {
+                AccountVariant::OpenZeppelin(OzAccountConfig {
+                    version: 1,
+                    public_key,
+                    legacy: true,
+                })
+            }
+            (Some(AccountVariantType::Argent), _, _) | (None, Some("argent"), false) => {
+                AccountVariant::Argent(ArgentAccountConfig {
+                    version: 1,
+                    implementation: None,
+                    owner: public_key,
+                    guardian: FieldElement::ZERO,
+                })
+            }
+            (Some(variant), _, _) => {
+                anyhow::bail!("undeployed {} accounts cannot be imported", variant)
+            }
+            (None, _, _) => {
+                anyhow::bail!("unknown account class hash {:#064x}", undeployed.class_hash)
+            }
+        },
+    )
+}
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{
+    account::{load_default_account, AccountConfig, DeploymentStatus},
+    profile::Profiles,
+    utils::format_address,
+};
+
+#[derive(Debug, Parser)]
+pub struct List {}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        let accounts_folder = Profiles::get_accounts_folder()?;
+        let default_account = load_default_account()?;
+
+        eprintln!(
+            "Accounts directory: {}",
+            format!("{}", accounts_folder.display()).bright_yellow()
+        );
+        match &default_account {
+            Some(default_account) => {
+                eprintln!("Default account: {}", default_account.bright_yellow())
+            }
+            None => eprintln!("Default account: {}", "(not set)".bright_yellow()),
+        }
+
+        if !accounts_folder.exists() {
+            eprintln!("No account found.");
+            return Ok(());
+        }
+
+        let mut entries = std::fs::read_dir(&accounts_folder)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        if entries.is_empty() {
+            eprintln!("No account found.");
+            return Ok(());
+        }
+
+        for path in entries.into_iter() {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            let is_default = default_account
+                .as_ref()
+                .is_some_and(|default_account| default_account == &name);
+            let marker = if is_default { "*" } else { " " };
+
+            let account = match AccountConfig::load(&path) {
+                Ok(account) => account,
+                Err(err) => {
+                    println!(
+                        "{} {:<16} {}",
+                        marker,
+                        name,
+                        format!("(invalid: {err})").red()
+                    );
+                    continue;
+                }
+            };
+
+            let (address, status, network) = match &account.deployment {
+                DeploymentStatus::Undeployed(_) => {
+                    (account.deploy_account_address().ok(), "undeployed", None)
+                }
+                DeploymentStatus::Deployed(deployed) => (
+                    Some(deployed.address),
+                    "deployed",
+                    deployed.network.as_deref(),
+                ),
+            };
+
+            println!(
+                "{} {:<16} {} {:<10} {:<20} {}",
+                marker,
+                name,
+                match address {
+                    Some(address) => format_address(address).bright_yellow(),
+                    None => format!("{:<66}", "-").normal(),
+                },
+                status,
+                network.unwrap_or("-"),
+                account.variant.variant_type()
+            );
+        }
+
+        Ok(())
+    }
+}
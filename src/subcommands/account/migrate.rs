@@ -0,0 +1,77 @@
+use std::{io::Write, path::PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{
+    account::{migrate_account_config, AccountConfig, CURRENT_ACCOUNT_CONFIG_VERSION},
+    path::ExpandedPathbufParser,
+    utils::print_colored_json,
+};
+
+#[derive(Debug, Parser)]
+pub struct Migrate {
+    #[clap(
+        long,
+        help = "Print the migrated account config instead of writing to the file"
+    )]
+    dry_run: bool,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the account config file"
+    )]
+    file: PathBuf,
+}
+
+impl Migrate {
+    pub fn run(self) -> Result<()> {
+        if !self.file.exists() {
+            anyhow::bail!("account config file not found");
+        }
+
+        let raw_config: serde_json::Value =
+            serde_json::from_reader(&mut std::fs::File::open(&self.file)?)?;
+        let (migrated, changed) = migrate_account_config(raw_config)?;
+
+        // Makes sure the result is actually loadable before touching the file
+        serde_json::from_value::<AccountConfig>(migrated.clone())
+            .map_err(|err| anyhow::anyhow!("unable to migrate account config: {}", err))?;
+
+        if self.dry_run {
+            print_colored_json(&migrated)?;
+            return Ok(());
+        }
+
+        if !changed {
+            eprintln!(
+                "Account config is already up to date (version {})",
+                format!("{}", CURRENT_ACCOUNT_CONFIG_VERSION).bright_yellow()
+            );
+            return Ok(());
+        }
+
+        // Never write directly to the original file to avoid data loss
+        let mut temp_file_name = self
+            .file
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("unable to determine file name"))?
+            .to_owned();
+        temp_file_name.push(".tmp");
+        let mut temp_path = self.file.clone();
+        temp_path.set_file_name(temp_file_name);
+
+        let mut temp_file = std::fs::File::create(&temp_path)?;
+        serde_json::to_writer_pretty(&mut temp_file, &migrated)?;
+        temp_file.write_all(b"\n")?;
+        std::fs::rename(temp_path, &self.file)?;
+
+        eprintln!(
+            "Account config migrated to version {}: {}",
+            format!("{}", CURRENT_ACCOUNT_CONFIG_VERSION).bright_yellow(),
+            std::fs::canonicalize(&self.file)?.display()
+        );
+
+        Ok(())
+    }
+}
@@ -1,12 +1,27 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+mod list;
+use list::List;
+
+mod set_default;
+use set_default::SetDefault;
+
+mod migrate;
+use migrate::Migrate;
+
 mod fetch;
 use fetch::Fetch;
 
+mod import;
+use import::Import;
+
 mod deploy;
 use deploy::Deploy;
 
+mod fund_status;
+use fund_status::FundStatus;
+
 mod oz;
 use oz::Oz;
 
@@ -24,10 +39,20 @@ pub struct Account {
 
 #[derive(Debug, Subcommand)]
 enum Subcommands {
+    #[clap(about = "List account config files in the accounts directory")]
+    List(List),
+    #[clap(about = "Set the account to use when --account is not provided")]
+    SetDefault(SetDefault),
+    #[clap(about = "Upgrade an account config file to the latest format version")]
+    Migrate(Migrate),
     #[clap(about = "Fetch account config from an already deployed account contract")]
     Fetch(Fetch),
+    #[clap(about = "Import an account from the accounts file of another CLI")]
+    Import(Import),
     #[clap(about = "Deploy account contract with a DeployAccount transaction")]
-    Deploy(Deploy),
+    Deploy(Box<Deploy>),
+    #[clap(about = "Check or wait for an account to be funded with enough fee tokens")]
+    FundStatus(FundStatus),
     #[clap(about = "Create and manage OpenZeppelin account contracts")]
     Oz(Oz),
     #[clap(about = "Create and manage Argent X account contracts")]
@@ -39,8 +64,13 @@ enum Subcommands {
 impl Account {
     pub async fn run(self) -> Result<()> {
         match self.command {
+            Subcommands::List(cmd) => cmd.run(),
+            Subcommands::SetDefault(cmd) => cmd.run(),
+            Subcommands::Migrate(cmd) => cmd.run(),
             Subcommands::Fetch(cmd) => cmd.run().await,
+            Subcommands::Import(cmd) => cmd.run().await,
             Subcommands::Deploy(cmd) => cmd.run().await,
+            Subcommands::FundStatus(cmd) => cmd.run().await,
             Subcommands::Oz(cmd) => cmd.run().await,
             Subcommands::Argent(cmd) => cmd.run().await,
             Subcommands::Braavos(cmd) => cmd.run().await,
@@ -3,22 +3,12 @@ use std::{io::Write, path::PathBuf};
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use starknet::{
-    core::types::FieldElement,
-    macros::felt,
-    signers::{Signer, SigningKey},
-};
+use starknet::signers::Signer;
 
 use crate::{
-    account::{AccountConfig, AccountVariant, DeploymentStatus, OzAccountConfig, UndeployedStatus},
-    path::ExpandedPathbufParser,
-    signer::SignerArgs,
+    account::AccountConfig, path::ExpandedPathbufParser, signer::SignerArgs, utils::format_address,
 };
 
-/// OpenZeppelin account contract v0.7.0 compiled with cairo v2.2.0
-const OZ_ACCOUNT_CLASS_HASH: FieldElement =
-    felt!("0x04c6d6cf894f8bc96bb9c525e6853e5483177841f7388f74a46cfda6f028c755");
-
 #[derive(Debug, Parser)]
 pub struct Init {
     // TODO: allow manually specifying public key without using a signer
@@ -45,22 +35,7 @@ impl Init {
 
         let signer = self.signer.into_signer()?;
 
-        // Too lazy to write random salt generation
-        let salt = SigningKey::from_random().secret_scalar();
-
-        let account_config = AccountConfig {
-            version: 1,
-            variant: AccountVariant::OpenZeppelin(OzAccountConfig {
-                version: 1,
-                public_key: signer.get_public_key().await?.scalar(),
-                legacy: false,
-            }),
-            deployment: DeploymentStatus::Undeployed(UndeployedStatus {
-                class_hash: OZ_ACCOUNT_CLASS_HASH,
-                salt,
-                context: None,
-            }),
-        };
+        let account_config = AccountConfig::new_oz(signer.get_public_key().await?.scalar());
 
         let deployed_address = account_config.deploy_account_address()?;
 
@@ -75,7 +50,7 @@ impl Init {
         eprintln!();
         eprintln!(
             "Once deployed, this account will be available at:\n    {}",
-            format!("{:#064x}", deployed_address).bright_yellow()
+            format_address(deployed_address).bright_yellow()
         );
         eprintln!();
         eprintln!(
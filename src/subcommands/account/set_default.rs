@@ -0,0 +1,73 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{
+    account::{find_builtin_account, resolve_account_path, AccountConfig},
+    profile::Profiles,
+};
+
+#[derive(Debug, Parser)]
+pub struct SetDefault {
+    #[clap(
+        long,
+        conflicts_with = "account",
+        help = "Remove the default account setting"
+    )]
+    unset: bool,
+    #[clap(
+        required_unless_present = "unset",
+        help = "Path to account config JSON file, name of an account in the accounts \
+            directory, or built-in account ID"
+    )]
+    account: Option<String>,
+}
+
+impl SetDefault {
+    pub fn run(self) -> Result<()> {
+        let mut profiles = Profiles::load()?;
+
+        let default_account = match self.account {
+            Some(account) => {
+                let value = if find_builtin_account(&account).is_some() {
+                    account
+                } else {
+                    let path = resolve_account_path(&account)?;
+                    if !path.exists() {
+                        anyhow::bail!("account config file not found");
+                    }
+
+                    // Makes sure the file is actually a valid account before saving it
+                    AccountConfig::load(&path)
+                        .map_err(|err| anyhow::anyhow!("invalid account config file: {}", err))?;
+
+                    // Accounts in the accounts directory are saved by name so that they still
+                    // resolve should the directory move. Everything else is saved as an absolute
+                    // path as the command could be run from anywhere.
+                    if path.starts_with(Profiles::get_accounts_folder()?)
+                        && path
+                            .file_stem()
+                            .is_some_and(|stem| stem == account.as_str())
+                    {
+                        account
+                    } else {
+                        std::fs::canonicalize(&path)?.display().to_string()
+                    }
+                };
+
+                Some(value)
+            }
+            None => None,
+        };
+
+        match &default_account {
+            Some(account) => eprintln!("Default account set to: {}", account.bright_yellow()),
+            None => eprintln!("Default account unset"),
+        }
+
+        profiles.default_profile_mut().default_account = default_account;
+        profiles.save()?;
+
+        Ok(())
+    }
+}
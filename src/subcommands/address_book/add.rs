@@ -0,0 +1,68 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::address_book::SyncedAddressBook;
+
+#[derive(Debug, Parser)]
+pub struct Add {
+    #[clap(
+        long,
+        help = "Replace the address book if the namespace already exists"
+    )]
+    force: bool,
+    #[clap(
+        long,
+        help = "Apply the names to all networks. The source is then a single table of names and \
+                addresses instead of one per chain ID"
+    )]
+    shared: bool,
+    #[clap(help = "Namespace for the names, referenced as `namespace:name`")]
+    namespace: String,
+    #[clap(
+        help = "HTTP(S) URL or path to a JSON or TOML file mapping chain IDs to names and addresses"
+    )]
+    source: String,
+}
+
+impl Add {
+    pub async fn run(self) -> Result<()> {
+        if SyncedAddressBook::load(&self.namespace)?.is_some() && !self.force {
+            anyhow::bail!(
+                "address book namespace {} already exists. Use `starkli address-book sync` to \
+                refresh it, or --force to replace it",
+                self.namespace
+            );
+        }
+
+        // Local paths are made absolute so that syncing works from any directory
+        let source = if self.source.starts_with("https://") || self.source.starts_with("http://") {
+            self.source
+        } else {
+            std::fs::canonicalize(&self.source)
+                .map_err(|err| anyhow::anyhow!("unable to access {}: {}", self.source, err))?
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        let address_book = SyncedAddressBook::fetch(&source, self.shared).await?;
+        address_book.save(&self.namespace)?;
+
+        if address_book.shared {
+            eprintln!(
+                "Added {} names on all networks as namespace {}",
+                address_book.name_count().to_string().bright_yellow(),
+                self.namespace.bright_yellow()
+            );
+        } else {
+            eprintln!(
+                "Added {} names on {} chains as namespace {}",
+                address_book.name_count().to_string().bright_yellow(),
+                address_book.chains.len().to_string().bright_yellow(),
+                self.namespace.bright_yellow()
+            );
+        }
+
+        Ok(())
+    }
+}
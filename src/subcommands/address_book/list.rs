@@ -0,0 +1,58 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::address_book::SyncedAddressBook;
+
+#[derive(Debug, Parser)]
+pub struct List {
+    #[clap(help = "Print the names in this namespace instead of listing address books")]
+    namespace: Option<String>,
+}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        match &self.namespace {
+            Some(namespace) => {
+                let address_book = SyncedAddressBook::load(namespace)?.ok_or_else(|| {
+                    anyhow::anyhow!("address book namespace not found: {}", namespace)
+                })?;
+
+                eprintln!(
+                    "Synced from {} at {}",
+                    address_book.source.bright_yellow(),
+                    address_book.synced_at
+                );
+
+                for (chain, names) in address_book.chains.iter() {
+                    for (name, address) in names.iter() {
+                        println!("{}\t{}:{}\t{:#064x}", chain, namespace, name, address);
+                    }
+                }
+            }
+            None => {
+                eprintln!(
+                    "Address books directory: {}",
+                    format!("{}", SyncedAddressBook::get_folder()?.display()).bright_yellow()
+                );
+
+                let namespaces = SyncedAddressBook::list()?;
+                if namespaces.is_empty() {
+                    eprintln!("No address book found.");
+                }
+
+                for namespace in namespaces.into_iter() {
+                    if let Some(address_book) = SyncedAddressBook::load(&namespace)? {
+                        if address_book.shared {
+                            println!("{}\t{}\t(shared)", namespace, address_book.source);
+                        } else {
+                            println!("{}\t{}", namespace, address_book.source);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod add;
+use add::Add;
+
+mod sync;
+use sync::Sync;
+
+mod list;
+use list::List;
+
+mod remove;
+use remove::Remove;
+
+#[derive(Debug, Parser)]
+pub struct AddressBook {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Import an address book from a URL or file under a namespace")]
+    Add(Add),
+    #[clap(about = "Refresh synced address books from their sources")]
+    Sync(Sync),
+    #[clap(about = "List synced address books, or the names in one of them")]
+    List(List),
+    #[clap(about = "Delete a synced address book")]
+    Remove(Remove),
+}
+
+impl AddressBook {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Add(cmd) => cmd.run().await,
+            Subcommands::Sync(cmd) => cmd.run().await,
+            Subcommands::List(cmd) => cmd.run(),
+            Subcommands::Remove(cmd) => cmd.run(),
+        }
+    }
+}
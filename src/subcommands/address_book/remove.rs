@@ -0,0 +1,19 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::address_book::SyncedAddressBook;
+
+#[derive(Debug, Parser)]
+pub struct Remove {
+    #[clap(help = "Namespace of the address book")]
+    namespace: String,
+}
+
+impl Remove {
+    pub fn run(self) -> Result<()> {
+        SyncedAddressBook::remove(&self.namespace)?;
+        eprintln!("Address book {} removed", self.namespace);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::address_book::SyncedAddressBook;
+
+#[derive(Debug, Parser)]
+pub struct Sync {
+    #[clap(help = "Namespaces to refresh. All address books are refreshed by default")]
+    namespaces: Vec<String>,
+}
+
+impl Sync {
+    pub async fn run(self) -> Result<()> {
+        let namespaces = if self.namespaces.is_empty() {
+            SyncedAddressBook::list()?
+        } else {
+            self.namespaces
+        };
+
+        if namespaces.is_empty() {
+            eprintln!("No address book to sync. Use `starkli address-book add` to import one.");
+            return Ok(());
+        }
+
+        // A failing source doesn't prevent the others from being refreshed
+        let mut failures = 0usize;
+        for namespace in namespaces.iter() {
+            match sync_namespace(namespace).await {
+                Ok(address_book) => {
+                    eprintln!(
+                        "Synced {}: {} names{}",
+                        namespace.bright_yellow(),
+                        address_book.name_count(),
+                        match &address_book.revision {
+                            Some(revision) => format!(" at revision {}", revision),
+                            None => String::new(),
+                        }
+                    );
+                }
+                Err(err) => {
+                    failures += 1;
+                    eprintln!(
+                        "{}",
+                        format!("WARNING: failed to sync {}: {}", namespace, err).bright_magenta()
+                    );
+                }
+            }
+        }
+
+        if failures > 0 {
+            anyhow::bail!(
+                "{} of {} address books failed to sync",
+                failures,
+                namespaces.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+async fn sync_namespace(namespace: &str) -> Result<SyncedAddressBook> {
+    let existing = SyncedAddressBook::load(namespace)?
+        .ok_or_else(|| anyhow::anyhow!("address book namespace not found"))?;
+
+    let address_book = SyncedAddressBook::fetch(&existing.source, existing.shared).await?;
+    address_book.save(namespace)?;
+
+    Ok(address_book)
+}
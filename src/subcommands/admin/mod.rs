@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use starknet::{
+    core::{
+        types::{BlockId, BlockTag, FieldElement, FunctionCall},
+        utils::get_selector_from_name,
+    },
+    providers::Provider,
+};
+
+use crate::{
+    abi_cache::CachedAbi,
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    messages::{message, plain_message},
+    provider::ExtendedProvider,
+    subcommands::Invoke,
+    utils::format_address,
+    ProviderArgs,
+};
+
+mod transfer_ownership;
+use transfer_ownership::TransferOwnership;
+
+mod role;
+use role::RoleChange;
+
+mod pause;
+use pause::PauseChange;
+
+mod timelock;
+use timelock::Timelock;
+
+#[derive(Debug, Parser)]
+pub struct Admin {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Transfer ownership of an Ownable contract")]
+    TransferOwnership(TransferOwnership),
+    #[clap(about = "Grant a role on an AccessControl contract")]
+    GrantRole(RoleChange),
+    #[clap(about = "Revoke a role on an AccessControl contract")]
+    RevokeRole(RoleChange),
+    #[clap(about = "Pause a Pausable contract")]
+    Pause(PauseChange),
+    #[clap(about = "Unpause a Pausable contract")]
+    Unpause(PauseChange),
+    #[clap(about = "Schedule, execute and inspect operations on a timelock contract")]
+    Timelock(Timelock),
+}
+
+/// A contract whose admin functions are looked up from its ABI.
+struct AdminTarget {
+    provider: Arc<ExtendedProvider>,
+    /// The address as entered, forwarded as is to `starkli invoke`.
+    raw_address: String,
+    address: FieldElement,
+    abi: CachedAbi,
+}
+
+impl Admin {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::TransferOwnership(cmd) => cmd.run().await,
+            Subcommands::GrantRole(cmd) => cmd.run(true).await,
+            Subcommands::RevokeRole(cmd) => cmd.run(false).await,
+            Subcommands::Pause(cmd) => cmd.run(true).await,
+            Subcommands::Unpause(cmd) => cmd.run(false).await,
+            Subcommands::Timelock(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl AdminTarget {
+    async fn load(provider: &ProviderArgs, raw_address: &str) -> Result<Self> {
+        let provider = Arc::new(provider.clone().into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = felt_decoder
+            .decode_single_with_addr_fallback(raw_address)
+            .await?;
+        let abi = CachedAbi::of_contract(&provider, address).await?;
+
+        Ok(Self {
+            provider,
+            raw_address: raw_address.to_owned(),
+            address,
+            abi,
+        })
+    }
+
+    /// The first of the candidate functions found in the ABI. Candidates cover the naming styles
+    /// of the same function across implementations (e.g. `transfer_ownership` in Cairo 1 and
+    /// `transferOwnership` in Cairo 0).
+    fn find_function<'a>(&self, pattern: &str, candidates: &[&'a str]) -> Result<&'a str> {
+        let names = self.abi.function_names()?;
+
+        candidates
+            .iter()
+            .find(|candidate| names.contains(**candidate))
+            .copied()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "contract {} does not look like {}: none of {} found in its ABI",
+                    format_address(self.address),
+                    pattern,
+                    candidates.join(", ")
+                )
+            })
+    }
+
+    /// Calls a view function when the ABI has it, for showing the current state before a change.
+    /// Failures are ignored, as the preview is only informational.
+    async fn try_call(
+        &self,
+        candidates: &[&str],
+        calldata: &[String],
+    ) -> Option<Vec<FieldElement>> {
+        let names = self.abi.function_names().ok()?;
+        let function = candidates
+            .iter()
+            .find(|candidate| names.contains(**candidate))?;
+
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(self.provider.clone()));
+        let calldata = felt_decoder.decode_all(calldata).await.ok()?;
+
+        self.provider
+            .call(
+                FunctionCall {
+                    contract_address: self.address,
+                    entry_point_selector: get_selector_from_name(function).ok()?,
+                    calldata,
+                },
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await
+            .ok()
+    }
+}
+
+/// Shows the call, asks for confirmation unless `yes` is set, and sends it through the regular
+/// invoke command so that all its options and checks apply.
+async fn send(
+    provider: &ProviderArgs,
+    target: &AdminTarget,
+    function: &str,
+    args: Vec<String>,
+    invoke_args: Vec<String>,
+    yes: bool,
+) -> Result<()> {
+    eprintln!(
+        "Contract: {}",
+        format_address(target.address).bright_yellow()
+    );
+    eprintln!("Function: {}", function.bright_yellow());
+    for (ind, arg) in args.iter().enumerate() {
+        eprintln!("Argument #{}: {}", ind, arg.bright_yellow());
+    }
+
+    if !yes {
+        eprint!("{}", message("prompt.send", &[]));
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if !matches!(input.trim(), "y" | "Y" | "yes") {
+            anyhow::bail!("{}", plain_message("prompt.aborted", &[]));
+        }
+    }
+
+    let invoke = Invoke::try_parse_from(
+        std::iter::once("starkli invoke".to_owned())
+            .chain(provider.to_args())
+            .chain(invoke_args)
+            .chain([
+                "--".to_owned(),
+                target.raw_address.clone(),
+                function.to_owned(),
+            ])
+            .chain(args),
+    )
+    .unwrap_or_else(|err| err.exit());
+
+    invoke.run().await
+}
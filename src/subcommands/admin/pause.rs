@@ -0,0 +1,67 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::types::FieldElement;
+
+use crate::ProviderArgs;
+
+use super::AdminTarget;
+
+/// Shared by `pause` and `unpause`, which take the same arguments.
+#[derive(Debug, Parser)]
+pub struct PauseChange {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, short, help = "Send without asking for confirmation")]
+    yes: bool,
+    #[clap(help = "Address of the Pausable contract")]
+    address: String,
+    #[clap(
+        last = true,
+        help = "Options for `starkli invoke` after `--`, like --account or --watch"
+    )]
+    invoke_args: Vec<String>,
+}
+
+impl PauseChange {
+    pub async fn run(self, pause: bool) -> Result<()> {
+        let target = AdminTarget::load(&self.provider, &self.address).await?;
+        let function = if pause {
+            target.find_function("a Pausable contract", &["pause"])?
+        } else {
+            target.find_function("a Pausable contract", &["unpause"])?
+        };
+
+        if let Some([paused]) = target
+            .try_call(&["is_paused", "paused", "isPaused"], &[])
+            .await
+            .as_deref()
+        {
+            let paused = *paused != FieldElement::ZERO;
+            eprintln!(
+                "Currently: {}",
+                if paused { "paused" } else { "not paused" }.bright_yellow()
+            );
+            if paused == pause {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "WARNING: the contract is {} already. The transaction will likely revert.",
+                        if pause { "paused" } else { "not paused" }
+                    )
+                    .bright_magenta()
+                );
+            }
+        }
+
+        super::send(
+            &self.provider,
+            &target,
+            function,
+            vec![],
+            self.invoke_args,
+            self.yes,
+        )
+        .await
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::types::FieldElement;
+
+use crate::{template::ParamType, ProviderArgs};
+
+use super::AdminTarget;
+
+/// Shared by `grant-role` and `revoke-role`, which take the same arguments.
+#[derive(Debug, Parser)]
+pub struct RoleChange {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, short, help = "Send without asking for confirmation")]
+    yes: bool,
+    #[clap(help = "Address of the AccessControl contract")]
+    address: String,
+    #[clap(
+        help = "Role as a felt, or a role name like MINTER_ROLE, which is turned into its selector \
+                as in OpenZeppelin contracts. DEFAULT_ADMIN_ROLE is 0"
+    )]
+    role: String,
+    #[clap(help = "Address of the account to grant the role to or revoke it from")]
+    account: String,
+    #[clap(
+        last = true,
+        help = "Options for `starkli invoke` after `--`, like --account or --watch"
+    )]
+    invoke_args: Vec<String>,
+}
+
+impl RoleChange {
+    pub async fn run(self, grant: bool) -> Result<()> {
+        let target = AdminTarget::load(&self.provider, &self.address).await?;
+        let function = if grant {
+            target.find_function("an AccessControl contract", &["grant_role", "grantRole"])?
+        } else {
+            target.find_function("an AccessControl contract", &["revoke_role", "revokeRole"])?
+        };
+
+        let role = encode_role(&self.role);
+        let account = ParamType::Address.encode(&self.account)?;
+
+        if let Some([has_role]) = target
+            .try_call(&["has_role", "hasRole"], &[role.clone(), account.clone()])
+            .await
+            .as_deref()
+        {
+            let has_role = *has_role != FieldElement::ZERO;
+            if has_role == grant {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "WARNING: the account {} the role already.",
+                        if grant { "has" } else { "does not have" }
+                    )
+                    .bright_magenta()
+                );
+            }
+        }
+
+        super::send(
+            &self.provider,
+            &target,
+            function,
+            vec![role, account],
+            self.invoke_args,
+            self.yes,
+        )
+        .await
+    }
+}
+
+fn encode_role(role: &str) -> String {
+    if role == "DEFAULT_ADMIN_ROLE" {
+        "0".to_owned()
+    } else if role.parse::<FieldElement>().is_ok() || role.contains(':') {
+        role.to_owned()
+    } else {
+        format!("selector:{}", role)
+    }
+}
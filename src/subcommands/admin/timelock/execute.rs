@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::ProviderArgs;
+
+use super::{send, AdminTarget, OperationArgs};
+
+#[derive(Debug, Parser)]
+pub struct Execute {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, short, help = "Send without asking for confirmation")]
+    yes: bool,
+    #[clap(help = "Address of the timelock contract")]
+    timelock: String,
+    #[clap(flatten)]
+    operation: OperationArgs,
+    #[clap(
+        last = true,
+        help = "Options for `starkli invoke` after `--`, like --account or --watch"
+    )]
+    invoke_args: Vec<String>,
+}
+
+impl Execute {
+    pub async fn run(self) -> Result<()> {
+        let target = AdminTarget::load(&self.provider, &self.timelock).await?;
+        target.find_function("a timelock contract", &["execute", "execute_batch"])?;
+
+        let operation = self.operation.decode(Arc::clone(&target.provider)).await?;
+        let id = operation.id();
+
+        eprintln!("Operation ID: {}", format!("{:#064x}", id).bright_yellow());
+
+        // Sending an operation that isn't ready only wastes fees, so it's caught early
+        let state = super::status::fetch_state(&target, id).await?;
+        if !matches!(state, super::status::OperationState::Ready) {
+            anyhow::bail!("operation is not ready to execute: {}", state);
+        }
+
+        let (function, args) = operation.function_args("execute", "execute_batch", &[]);
+        target.find_function("a timelock contract", &[function.as_str()])?;
+
+        send(
+            &self.provider,
+            &target,
+            &function,
+            args,
+            self.invoke_args,
+            self.yes,
+        )
+        .await
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+
+use crate::{verbosity::VerbosityArgs, ProviderArgs};
+
+use super::OperationArgs;
+
+#[derive(Debug, Parser)]
+pub struct Hash {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    operation: OperationArgs,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Hash {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        // The provider is only used if calls refer to names
+        let provider = Arc::new(self.provider.into_provider()?);
+        let operation = self.operation.decode(provider).await?;
+
+        println!("{:#064x}", operation.id());
+
+        Ok(())
+    }
+}
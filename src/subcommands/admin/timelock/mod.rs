@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use starknet::{accounts::Call, core::types::FieldElement};
+use starknet_crypto::poseidon_hash_many;
+
+use crate::{address_book::AddressBookResolver, decode::FeltDecoder, provider::ExtendedProvider};
+
+use super::{send, AdminTarget};
+
+mod hash;
+use hash::Hash;
+
+mod schedule;
+use schedule::Schedule;
+
+mod execute;
+use execute::Execute;
+
+mod status;
+use status::Status;
+
+/// Marks executed operations in `get_timestamp`, as in the OpenZeppelin timelock.
+const DONE_TIMESTAMP: u64 = 1;
+
+#[derive(Debug, Parser)]
+pub struct Timelock {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Compute the ID of a timelock operation")]
+    Hash(Hash),
+    #[clap(about = "Schedule an operation on a timelock contract")]
+    Schedule(Schedule),
+    #[clap(about = "Execute a scheduled operation once its delay has passed")]
+    Execute(Execute),
+    #[clap(about = "Show the state and remaining delay of an operation")]
+    Status(Status),
+}
+
+/// The calls of an operation and what sets it apart from identical calls scheduled before.
+#[derive(Debug, Parser)]
+struct OperationArgs {
+    #[clap(
+        long,
+        default_value = "0",
+        help = "ID of an operation that must be executed before this one, or 0 for none"
+    )]
+    predecessor: FieldElement,
+    #[clap(
+        long,
+        default_value = "0",
+        help = "Salt to tell apart operations with identical calls"
+    )]
+    salt: FieldElement,
+    #[clap(help = "One or more calls in the multicall syntax of `starkli invoke`")]
+    calls: Vec<String>,
+}
+
+struct Operation {
+    calls: Vec<Call>,
+    predecessor: FieldElement,
+    salt: FieldElement,
+}
+
+impl Timelock {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Hash(cmd) => cmd.run().await,
+            Subcommands::Schedule(cmd) => cmd.run().await,
+            Subcommands::Execute(cmd) => cmd.run().await,
+            Subcommands::Status(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl OperationArgs {
+    async fn decode(&self, provider: Arc<ExtendedProvider>) -> Result<Operation> {
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider));
+        let calls = felt_decoder.decode_calls(self.calls.clone()).await?;
+        if calls.is_empty() {
+            anyhow::bail!("an operation needs at least one call");
+        }
+
+        Ok(Operation {
+            calls,
+            predecessor: self.predecessor,
+            salt: self.salt,
+        })
+    }
+}
+
+impl Operation {
+    /// The operation ID, as computed by `hash_operation` and `hash_operation_batch` in the
+    /// OpenZeppelin timelock: a Poseidon hash of the calls, the predecessor and the salt. Like the
+    /// function scheduling it, a single call is hashed without the length prefix of a batch, so it
+    /// doesn't hash the same as a batch of one.
+    fn id(&self) -> FieldElement {
+        let mut elements = match self.calls.as_slice() {
+            [call] => encode_call(call),
+            calls => encode_calls(calls),
+        };
+        elements.push(self.predecessor);
+        elements.push(self.salt);
+        poseidon_hash_many(&elements)
+    }
+
+    /// The name and arguments of the function scheduling or executing the operation, where
+    /// `extra` is appended to the arguments (e.g. the delay). Single calls use the non-batch
+    /// functions, which take the call without a length prefix.
+    fn function_args(
+        &self,
+        single: &str,
+        batch: &str,
+        extra: &[FieldElement],
+    ) -> (String, Vec<String>) {
+        let (function, mut args) = match self.calls.as_slice() {
+            [call] => (single, encode_call(call)),
+            calls => (batch, encode_calls(calls)),
+        };
+        args.push(self.predecessor);
+        args.push(self.salt);
+        args.extend_from_slice(extra);
+
+        (
+            function.to_owned(),
+            args.iter().map(|arg| format!("{:#x}", arg)).collect(),
+        )
+    }
+}
+
+fn encode_call(call: &Call) -> Vec<FieldElement> {
+    let mut encoded = vec![call.to, call.selector, call.calldata.len().into()];
+    encoded.extend_from_slice(&call.calldata);
+    encoded
+}
+
+fn encode_calls(calls: &[Call]) -> Vec<FieldElement> {
+    let mut encoded = vec![calls.len().into()];
+    for call in calls.iter() {
+        encoded.append(&mut encode_call(call));
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+
+    use super::*;
+
+    fn call(to: FieldElement, calldata: Vec<FieldElement>) -> Call {
+        Call {
+            to,
+            selector: felt!("0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e"),
+            calldata,
+        }
+    }
+
+    // Elements laid out as `HashCallImpl` and `HashCallsImpl` in the OpenZeppelin timelock feed
+    // them to Poseidon: `to`, `selector`, calldata length and calldata for each call, with the
+    // number of calls in front only for batches.
+    #[test]
+    fn test_operation_id_single_call() {
+        let operation = Operation {
+            calls: vec![call(felt!("0x1234"), vec![felt!("0x1"), felt!("0x2")])],
+            predecessor: felt!("0x0"),
+            salt: felt!("0x5"),
+        };
+
+        assert_eq!(
+            operation.id(),
+            poseidon_hash_many(&[
+                felt!("0x1234"),
+                felt!("0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e"),
+                felt!("0x2"),
+                felt!("0x1"),
+                felt!("0x2"),
+                felt!("0x0"),
+                felt!("0x5"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_operation_id_batch() {
+        let operation = Operation {
+            calls: vec![
+                call(felt!("0x1234"), vec![felt!("0x1")]),
+                call(felt!("0x5678"), vec![]),
+            ],
+            predecessor: felt!("0x9"),
+            salt: felt!("0x0"),
+        };
+
+        assert_eq!(
+            operation.id(),
+            poseidon_hash_many(&[
+                felt!("0x2"),
+                felt!("0x1234"),
+                felt!("0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e"),
+                felt!("0x1"),
+                felt!("0x1"),
+                felt!("0x5678"),
+                felt!("0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e"),
+                felt!("0x0"),
+                felt!("0x9"),
+                felt!("0x0"),
+            ])
+        );
+    }
+}
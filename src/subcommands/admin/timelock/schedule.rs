@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::ProviderArgs;
+
+use super::{send, AdminTarget, OperationArgs};
+
+#[derive(Debug, Parser)]
+pub struct Schedule {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "Delay in seconds before the operation can be executed. Defaults to the minimum \
+                delay of the timelock"
+    )]
+    delay: Option<u64>,
+    #[clap(long, short, help = "Send without asking for confirmation")]
+    yes: bool,
+    #[clap(help = "Address of the timelock contract")]
+    timelock: String,
+    #[clap(flatten)]
+    operation: OperationArgs,
+    #[clap(
+        last = true,
+        help = "Options for `starkli invoke` after `--`, like --account or --watch"
+    )]
+    invoke_args: Vec<String>,
+}
+
+impl Schedule {
+    pub async fn run(self) -> Result<()> {
+        let target = AdminTarget::load(&self.provider, &self.timelock).await?;
+        target.find_function("a timelock contract", &["schedule", "schedule_batch"])?;
+
+        let operation = self.operation.decode(Arc::clone(&target.provider)).await?;
+
+        let min_delay = match target.try_call(&["get_min_delay"], &[]).await.as_deref() {
+            Some([min_delay]) => Some(u64::try_from(*min_delay)?),
+            _ => None,
+        };
+        let delay = match (self.delay, min_delay) {
+            (Some(delay), Some(min_delay)) if delay < min_delay => anyhow::bail!(
+                "delay of {} seconds is below the minimum delay of the timelock ({} seconds)",
+                delay,
+                min_delay
+            ),
+            (Some(delay), _) => delay,
+            (None, Some(min_delay)) => min_delay,
+            (None, None) => anyhow::bail!(
+                "unable to get the minimum delay of the timelock. Use --delay to set it"
+            ),
+        };
+
+        eprintln!(
+            "Operation ID: {}",
+            format!("{:#064x}", operation.id()).bright_yellow()
+        );
+        eprintln!("Delay: {} seconds", delay.to_string().bright_yellow());
+
+        let (function, args) =
+            operation.function_args("schedule", "schedule_batch", &[delay.into()]);
+        target.find_function("a timelock contract", &[function.as_str()])?;
+
+        send(
+            &self.provider,
+            &target,
+            &function,
+            args,
+            self.invoke_args,
+            self.yes,
+        )
+        .await
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{TimeZone, Utc};
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement, MaybePendingBlockWithTxHashes},
+    providers::Provider,
+};
+
+use crate::{verbosity::VerbosityArgs, ProviderArgs};
+
+use super::{AdminTarget, OperationArgs, DONE_TIMESTAMP};
+
+#[derive(Debug, Parser)]
+pub struct Status {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "ID of the operation. Use this instead of the calls, predecessor and salt"
+    )]
+    id: Option<FieldElement>,
+    #[clap(help = "Address of the timelock contract")]
+    timelock: String,
+    #[clap(flatten)]
+    operation: OperationArgs,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+pub enum OperationState {
+    /// Never scheduled, or cancelled.
+    Unset,
+    Waiting {
+        ready_at: u64,
+        remaining: u64,
+    },
+    Ready,
+    Done,
+}
+
+impl Status {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let target = AdminTarget::load(&self.provider, &self.timelock).await?;
+
+        let id = match self.id {
+            Some(id) => {
+                if !self.operation.calls.is_empty() {
+                    anyhow::bail!("--id cannot be used together with calls");
+                }
+                id
+            }
+            None => self
+                .operation
+                .decode(Arc::clone(&target.provider))
+                .await?
+                .id(),
+        };
+
+        eprintln!("Operation ID: {}", format!("{:#064x}", id).bright_yellow());
+
+        let state = fetch_state(&target, id).await?;
+        if let OperationState::Waiting { ready_at, .. } = state {
+            eprintln!("Ready at: {}", format_timestamp(ready_at).bright_yellow());
+        }
+        println!("{}", state);
+
+        Ok(())
+    }
+}
+
+/// Compares the operation timestamp against the pending block, which is the block the operation
+/// would be executed in.
+pub(super) async fn fetch_state(target: &AdminTarget, id: FieldElement) -> Result<OperationState> {
+    target.find_function("a timelock contract", &["get_timestamp"])?;
+
+    let timestamp = match target
+        .try_call(&["get_timestamp"], &[format!("{:#x}", id)])
+        .await
+        .as_deref()
+    {
+        Some([timestamp]) => u64::try_from(*timestamp)?,
+        _ => anyhow::bail!("unable to get the operation timestamp from the timelock"),
+    };
+
+    let now = match target
+        .provider
+        .get_block_with_tx_hashes(BlockId::Tag(BlockTag::Pending))
+        .await?
+    {
+        MaybePendingBlockWithTxHashes::Block(block) => block.timestamp,
+        MaybePendingBlockWithTxHashes::PendingBlock(block) => block.timestamp,
+    };
+
+    Ok(match timestamp {
+        0 => OperationState::Unset,
+        DONE_TIMESTAMP => OperationState::Done,
+        ready_at if ready_at > now => OperationState::Waiting {
+            ready_at,
+            remaining: ready_at - now,
+        },
+        _ => OperationState::Ready,
+    })
+}
+
+impl std::fmt::Display for OperationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unset => write!(f, "unset (not scheduled, or cancelled)"),
+            Self::Waiting { remaining, .. } => {
+                write!(f, "waiting ({} seconds remaining)", remaining)
+            }
+            Self::Ready => write!(f, "ready"),
+            Self::Done => write!(f, "done"),
+        }
+    }
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    match Utc.timestamp_opt(timestamp as i64, 0).single() {
+        Some(time) => time.to_rfc3339(),
+        None => timestamp.to_string(),
+    }
+}
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{template::ParamType, utils::format_address, ProviderArgs};
+
+use super::AdminTarget;
+
+#[derive(Debug, Parser)]
+pub struct TransferOwnership {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, short, help = "Send without asking for confirmation")]
+    yes: bool,
+    #[clap(help = "Address of the Ownable contract")]
+    address: String,
+    #[clap(help = "Address of the new owner")]
+    new_owner: String,
+    #[clap(
+        last = true,
+        help = "Options for `starkli invoke` after `--`, like --account or --watch"
+    )]
+    invoke_args: Vec<String>,
+}
+
+impl TransferOwnership {
+    pub async fn run(self) -> Result<()> {
+        let target = AdminTarget::load(&self.provider, &self.address).await?;
+        let function = target.find_function(
+            "an Ownable contract",
+            &["transfer_ownership", "transferOwnership"],
+        )?;
+
+        if let Some([owner]) = target.try_call(&["owner"], &[]).await.as_deref() {
+            eprintln!("Current owner: {}", format_address(*owner).bright_yellow());
+        }
+
+        // Names are resolved as addresses instead of being taken as raw calldata
+        let new_owner = ParamType::Address.encode(&self.new_owner)?;
+
+        super::send(
+            &self.provider,
+            &target,
+            function,
+            vec![new_owner],
+            self.invoke_args,
+            self.yes,
+        )
+        .await
+    }
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::signers::SigningKey;
+
+use crate::{
+    agent::{connect, AgentRequest},
+    keychain::Keychain,
+    path::ExpandedPathbufParser,
+    warning::Warning,
+};
+
+#[derive(Debug, Parser)]
+pub struct Add {
+    #[clap(
+        long,
+        help = "Supply password from command line option instead of prompt"
+    )]
+    password: Option<String>,
+    #[clap(
+        long,
+        help = "Number of seconds to keep the key. Defaults to the TTL of the agent"
+    )]
+    ttl: Option<u64>,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the JSON keystore"
+    )]
+    file: PathBuf,
+}
+
+impl Add {
+    pub fn run(self) -> Result<()> {
+        if self.password.is_some() {
+            Warning::PasswordOption.print(
+                "setting passwords via --password is generally considered insecure, as they will \
+                be stored in your shell history or other log files.",
+            );
+        }
+
+        let session = connect()?;
+
+        if !self.file.exists() {
+            anyhow::bail!("keystore file not found");
+        }
+        let keystore = std::fs::canonicalize(&self.file)?;
+
+        let password = match self.password {
+            Some(password) => password,
+            None => match Keychain::get_keystore_password(&keystore) {
+                Some(password) => password,
+                None => rpassword::prompt_password("Enter keystore password: ")?,
+            },
+        };
+
+        let key = SigningKey::from_keystore(&keystore, &password)?;
+
+        session.request(AgentRequest::Add {
+            keystore: keystore.to_string_lossy().into_owned(),
+            private_key: key.secret_scalar(),
+            ttl: self.ttl,
+        })?;
+
+        eprintln!(
+            "Keystore {} unlocked in agent. Public key: {}",
+            keystore.display(),
+            format!("{:#064x}", key.verifying_key().scalar()).bright_yellow()
+        );
+
+        Ok(())
+    }
+}
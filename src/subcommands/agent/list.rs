@@ -0,0 +1,32 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::agent::{connect, AgentRequest, AgentResponse};
+
+#[derive(Debug, Parser)]
+pub struct List {}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        let keys = match connect()?.request(AgentRequest::List)? {
+            AgentResponse::Keys(keys) => keys,
+            _ => anyhow::bail!("unexpected agent response"),
+        };
+
+        if keys.is_empty() {
+            eprintln!("No keystore unlocked.");
+        }
+
+        for key in keys.into_iter() {
+            println!(
+                "{} {} (expires in {}s)",
+                format!("{:#064x}", key.public_key).bright_yellow(),
+                key.keystore,
+                key.expires_in
+            );
+        }
+
+        Ok(())
+    }
+}
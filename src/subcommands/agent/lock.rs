@@ -0,0 +1,16 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::agent::{connect, AgentRequest};
+
+#[derive(Debug, Parser)]
+pub struct Lock {}
+
+impl Lock {
+    pub fn run(self) -> Result<()> {
+        connect()?.request(AgentRequest::Lock)?;
+        eprintln!("All keys removed from agent");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod start;
+use start::Start;
+
+mod add;
+use add::Add;
+
+mod list;
+use list::List;
+
+mod lock;
+use lock::Lock;
+
+mod stop;
+use stop::Stop;
+
+#[derive(Debug, Parser)]
+pub struct Agent {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Run the agent in the foreground")]
+    Start(Start),
+    #[clap(about = "Unlock a keystore and hand its key to the agent")]
+    Add(Add),
+    #[clap(about = "List keystores unlocked in the agent")]
+    List(List),
+    #[clap(about = "Make the agent forget all keys")]
+    Lock(Lock),
+    #[clap(about = "Stop the running agent")]
+    Stop(Stop),
+}
+
+impl Agent {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Start(cmd) => cmd.run(),
+            Subcommands::Add(cmd) => cmd.run(),
+            Subcommands::List(cmd) => cmd.run(),
+            Subcommands::Lock(cmd) => cmd.run(),
+            Subcommands::Stop(cmd) => cmd.run(),
+        }
+    }
+}
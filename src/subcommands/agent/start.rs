@@ -0,0 +1,220 @@
+use std::{
+    collections::HashMap,
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use rand::RngCore;
+use starknet::signers::SigningKey;
+
+use crate::{
+    agent::{AgentKeyInfo, AgentRequest, AgentResponse, AgentSession},
+    interrupt::ctrl_c,
+    verbosity::VerbosityArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Start {
+    #[clap(
+        long,
+        default_value = "900",
+        help = "Number of seconds to keep each key after it's added"
+    )]
+    ttl: u64,
+    #[clap(
+        long,
+        default_value = "0",
+        help = "Port to listen on. The agent is only exposed on localhost, on a random free port \
+                by default"
+    )]
+    port: u16,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+struct AgentKey {
+    key: SigningKey,
+    expires_at: Instant,
+}
+
+type AgentKeys = Arc<Mutex<HashMap<String, AgentKey>>>;
+
+impl Start {
+    pub fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        if Instant::now()
+            .checked_add(Duration::from_secs(self.ttl))
+            .is_none()
+        {
+            anyhow::bail!("--ttl is too large");
+        }
+
+        if let Some(session) = AgentSession::load()? {
+            if session.request(AgentRequest::List).is_ok() {
+                anyhow::bail!("an agent is already running on port {}", session.port);
+            }
+        }
+
+        let listener = TcpListener::bind(("127.0.0.1", self.port))?;
+
+        let mut token = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut token);
+        let session = AgentSession {
+            port: listener.local_addr()?.port(),
+            token: hex::encode(token),
+        };
+        session.save()?;
+
+        // Otherwise Ctrl+C would leave `agent.json` behind, pointing at a port that any local
+        // process could take over. The agent loop blocks, so the signal is handled on its own
+        // thread.
+        std::thread::spawn(|| {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(err) => {
+                    log::warn!("unable to listen for Ctrl+C: {}", err);
+                    return;
+                }
+            };
+            runtime.block_on(ctrl_c());
+
+            if let Err(err) = AgentSession::remove() {
+                log::warn!("unable to remove agent session file: {}", err);
+            }
+            eprintln!();
+            eprintln!("Agent stopped");
+            std::process::exit(130);
+        });
+
+        let keys = AgentKeys::default();
+
+        // Expired keys are dropped even when no request comes in
+        {
+            let keys = keys.clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(Duration::from_secs(1));
+                if let Ok(mut keys) = keys.lock() {
+                    let now = Instant::now();
+                    keys.retain(|_, key| key.expires_at > now);
+                }
+            });
+        }
+
+        eprintln!(
+            "Agent listening on {} with a TTL of {} seconds",
+            format!("127.0.0.1:{}", session.port).bright_yellow(),
+            self.ttl.to_string().bright_yellow()
+        );
+        log::info!("agent listening on port {}", session.port);
+        eprintln!(
+            "Unlock keystores with `starkli agent add`. Stop with Ctrl+C or `starkli agent stop`."
+        );
+
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+            // Keeps a stalled client from blocking everyone else
+            stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+            let (response, stop) = match session.read_request(&stream) {
+                Ok(request) => {
+                    let stop = matches!(request, AgentRequest::Stop);
+                    (self.handle(&keys, request), stop)
+                }
+                Err(err) => (AgentResponse::Error(err.to_string()), false),
+            };
+
+            if let Err(err) = AgentSession::write_response(&stream, &response) {
+                log::debug!("unable to respond to agent client: {}", err);
+            }
+
+            if stop {
+                break;
+            }
+        }
+
+        AgentSession::remove()?;
+        log::info!("agent stopped");
+        eprintln!("Agent stopped");
+
+        Ok(())
+    }
+
+    fn handle(&self, keys: &AgentKeys, request: AgentRequest) -> AgentResponse {
+        let mut keys = match keys.lock() {
+            Ok(keys) => keys,
+            Err(_) => return AgentResponse::Error("agent state poisoned".into()),
+        };
+
+        let now = Instant::now();
+        keys.retain(|_, key| key.expires_at > now);
+
+        match request {
+            AgentRequest::Add {
+                keystore,
+                private_key,
+                ttl,
+            } => {
+                let ttl = ttl.unwrap_or(self.ttl);
+                let expires_at = match now.checked_add(Duration::from_secs(ttl)) {
+                    Some(expires_at) => expires_at,
+                    None => return AgentResponse::Error("TTL too large".into()),
+                };
+                eprintln!("Unlocked {} for {} seconds", keystore, ttl);
+                log::info!("unlocked {} for {} seconds", keystore, ttl);
+
+                keys.insert(
+                    keystore,
+                    AgentKey {
+                        key: SigningKey::from_secret_scalar(private_key),
+                        expires_at,
+                    },
+                );
+                AgentResponse::Ok
+            }
+            AgentRequest::PublicKey { keystore } => match keys.get(&keystore) {
+                Some(key) => AgentResponse::PublicKey(key.key.verifying_key().scalar()),
+                None => AgentResponse::Error(format!("{} is not unlocked", keystore)),
+            },
+            AgentRequest::Sign { keystore, hash } => match keys.get(&keystore) {
+                Some(key) => match key.key.sign(&hash) {
+                    Ok(signature) => {
+                        eprintln!("Signed {:#064x} with {}", hash, keystore);
+                        log::info!("signed {:#064x} with {}", hash, keystore);
+                        AgentResponse::Signature {
+                            r: signature.r,
+                            s: signature.s,
+                        }
+                    }
+                    Err(err) => AgentResponse::Error(err.to_string()),
+                },
+                None => AgentResponse::Error(format!("{} is not unlocked", keystore)),
+            },
+            AgentRequest::List => AgentResponse::Keys(
+                keys.iter()
+                    .map(|(keystore, key)| AgentKeyInfo {
+                        keystore: keystore.to_owned(),
+                        public_key: key.key.verifying_key().scalar(),
+                        expires_in: key.expires_at.saturating_duration_since(now).as_secs(),
+                    })
+                    .collect(),
+            ),
+            AgentRequest::Lock | AgentRequest::Stop => {
+                keys.clear();
+                eprintln!("All keys forgotten");
+                log::info!("all keys forgotten");
+                AgentResponse::Ok
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::agent::{connect, AgentRequest};
+
+#[derive(Debug, Parser)]
+pub struct Stop {}
+
+impl Stop {
+    pub fn run(self) -> Result<()> {
+        connect()?.request(AgentRequest::Stop)?;
+        eprintln!("Agent stopped");
+
+        Ok(())
+    }
+}
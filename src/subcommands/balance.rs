@@ -1,9 +1,8 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow::Result;
-use bigdecimal::BigDecimal;
 use clap::Parser;
-use num_bigint::{BigUint, ToBigInt};
+use num_bigint::BigUint;
 use starknet::{
     core::types::{BlockId, BlockTag, FieldElement, FunctionCall},
     macros::selector,
@@ -11,23 +10,48 @@ use starknet::{
 };
 
 use crate::{
-    address_book::AddressBookResolver, decode::FeltDecoder, verbosity::VerbosityArgs, ProviderArgs,
+    accessibility::Status,
+    address_book::AddressBookResolver,
+    amount::{AmountFormat, AmountFormatArgs},
+    decode::FeltDecoder,
+    provider::{ExtendedProvider, NetworksArgs},
+    token::{fetch_balance, fetch_balances, TokenRegistry},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
 };
 
-/// The default ETH address: 0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7.
-const DEFAULT_ETH_ADDRESS: FieldElement = FieldElement::from_mont([
-    4380532846569209554,
-    17839402928228694863,
-    17240401758547432026,
-    418961398025637529,
-]);
-
 #[derive(Debug, Parser)]
 pub struct Balance {
     #[clap(flatten)]
     provider: ProviderArgs,
-    #[clap(help = "Account address")]
-    account_address: String,
+    #[clap(flatten)]
+    networks: NetworksArgs,
+    #[clap(
+        long,
+        conflicts_with = "networks",
+        help = "Path to a file with one address per line to query balances for in batch"
+    )]
+    file: Option<PathBuf>,
+    #[clap(
+        long,
+        requires = "file",
+        help = "Print batch results as CSV instead of a table"
+    )]
+    csv: bool,
+    #[clap(
+        required_unless_present = "file",
+        conflicts_with = "file",
+        help = "Account address"
+    )]
+    account_address: Option<String>,
+    #[clap(help = "Token contract address. Defaults to ETH")]
+    token: Option<String>,
+    #[clap(
+        long = "token",
+        conflicts_with = "token",
+        help = "Token contract address, which can also be used with --file. Defaults to ETH"
+    )]
+    token_option: Option<String>,
     #[clap(
         long,
         conflicts_with = "hex",
@@ -41,6 +65,8 @@ pub struct Balance {
     )]
     hex: bool,
     #[clap(flatten)]
+    amounts: AmountFormatArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
@@ -48,43 +74,219 @@ impl Balance {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
 
+        let amount_format = match self.amounts.format() {
+            Some(_) if self.hex => {
+                anyhow::bail!("--hex cannot be used with amount format options")
+            }
+            Some(AmountFormat::Human(_)) if self.raw => {
+                anyhow::bail!("--raw cannot be used with human-readable amount options")
+            }
+            Some(amount_format) => amount_format,
+            None if self.raw => AmountFormat::Raw,
+            None => AmountFormat::HUMAN,
+        };
+
+        let token = self.token.as_ref().or(self.token_option.as_ref());
+
+        if !self.networks.is_empty() {
+            // `clap` makes sure this is present when `--file` is not used
+            let account_address = self.account_address.clone().unwrap();
+            let token = token.cloned();
+            let hex = self.hex;
+
+            return self
+                .networks
+                .fan_out(move |provider| {
+                    let account_address = account_address.clone();
+                    let token = token.clone();
+
+                    async move {
+                        let felt_decoder =
+                            FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+                        let account_address = felt_decoder
+                            .decode_single_with_addr_fallback(&account_address)
+                            .await?;
+                        let (token, decimals, symbol) =
+                            resolve_token(&provider, &felt_decoder, token.as_ref()).await?;
+
+                        let balance = fetch_balance(&provider, token, account_address).await?;
+                        Ok(format_balance(
+                            amount_format,
+                            hex,
+                            &balance,
+                            decimals,
+                            symbol.as_deref(),
+                        ))
+                    }
+                })
+                .await;
+        }
+
         let provider = Arc::new(self.provider.into_provider()?);
         let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
 
-        let account_address = felt_decoder
-            .decode_single_with_addr_fallback(&self.account_address)
-            .await?;
-
-        let result = provider
-            .call(
-                FunctionCall {
-                    contract_address: DEFAULT_ETH_ADDRESS,
-                    entry_point_selector: selector!("balanceOf"),
-                    calldata: vec![account_address],
-                },
-                BlockId::Tag(BlockTag::Pending),
-            )
-            .await?;
-
-        if result.len() != 2 {
-            anyhow::bail!("unexpected call result size: {}", result.len());
-        }
+        // The symbol is only shown when the token is chosen explicitly or in the batch table, so
+        // that the default ETH output stays scriptable.
+        let show_symbol = (token.is_some() || self.file.is_some()) && !self.csv;
+
+        let (token, decimals, symbol) = resolve_token(&provider, &felt_decoder, token).await?;
+        let symbol = symbol.filter(|_| show_symbol);
+
+        match &self.file {
+            Some(file) => {
+                // Lines that can't be resolved are reported in place of their balance, so that one
+                // bad line doesn't hide the results of all the others
+                let mut rows = vec![];
+                for line in std::fs::read_to_string(file)?.lines() {
+                    let line = line.trim();
+                    if line.is_empty() || line.starts_with('#') {
+                        continue;
+                    }
+                    let address = felt_decoder.decode_single_with_addr_fallback(line).await;
+                    rows.push((line.to_owned(), address));
+                }
 
-        let low = BigUint::from_bytes_be(&result[0].to_bytes_be());
-        let high = BigUint::from_bytes_be(&result[1].to_bytes_be());
+                let addresses = rows
+                    .iter()
+                    .filter_map(|(_, address)| address.as_ref().ok().copied())
+                    .collect::<Vec<_>>();
+                let mut balances = fetch_balances(&provider, token, &addresses)
+                    .await
+                    .into_iter();
 
-        let raw_balance: BigUint = (high << 128) + low;
+                if self.csv {
+                    println!("address,balance,error");
+                }
+                let mut failed = 0usize;
+                for (line, address) in rows.iter() {
+                    let (address, balance) = match address {
+                        Ok(address) => (
+                            format!("{:#064x}", address),
+                            // There's one balance for each resolved address
+                            balances
+                                .next()
+                                .unwrap_or_else(|| Err(anyhow::anyhow!("missing balance"))),
+                        ),
+                        Err(err) => (line.to_owned(), Err(anyhow::anyhow!("{}", err))),
+                    };
 
-        if self.raw {
-            println!("{}", raw_balance);
-        } else if self.hex {
-            println!("{:#x}", raw_balance);
-        } else {
-            // `to_bigint()` from `BigUint` always returns `Some`.
-            let balance_dec = BigDecimal::new(raw_balance.to_bigint().unwrap(), 18);
-            println!("{}", balance_dec);
+                    match balance {
+                        Ok(balance) => {
+                            let balance = format_balance(
+                                amount_format,
+                                self.hex,
+                                &balance,
+                                decimals,
+                                symbol.as_deref(),
+                            );
+                            if self.csv {
+                                println!("{},{},", address, balance);
+                            } else {
+                                println!("{}  {}", address, balance);
+                            }
+                        }
+                        Err(err) => {
+                            failed += 1;
+                            if self.csv {
+                                println!(
+                                    "{},,\"{}\"",
+                                    address,
+                                    err.to_string().replace('"', "\"\"")
+                                );
+                            } else {
+                                println!(
+                                    "{}  {}",
+                                    address,
+                                    Status::Failure.label(&format!("error: {}", err))
+                                );
+                            }
+                        }
+                    }
+                }
+
+                if failed > 0 {
+                    anyhow::bail!(
+                        "{} of {} balance(s) could not be fetched",
+                        failed,
+                        rows.len()
+                    );
+                }
+            }
+            None => {
+                // `clap` makes sure this is present when `--file` is not used
+                let account_address = felt_decoder
+                    .decode_single_with_addr_fallback(self.account_address.as_ref().unwrap())
+                    .await?;
+
+                let balance = fetch_balance(&provider, token, account_address).await?;
+                println!(
+                    "{}",
+                    format_balance(
+                        amount_format,
+                        self.hex,
+                        &balance,
+                        decimals,
+                        symbol.as_deref()
+                    )
+                );
+            }
         }
 
         Ok(())
     }
 }
+
+/// Resolves the token address (defaulting to ETH), decimals, and symbol if known.
+async fn resolve_token(
+    provider: &Arc<ExtendedProvider>,
+    felt_decoder: &FeltDecoder<Arc<ExtendedProvider>>,
+    token: Option<&String>,
+) -> Result<(FieldElement, u32, Option<String>)> {
+    let token = match token {
+        Some(token) => felt_decoder.decode_single_with_addr_fallback(token).await?,
+        None => provider.eth_token_address(),
+    };
+
+    let registry = TokenRegistry::load_for_provider(provider).await?;
+    let (decimals, symbol) = match registry.get(token) {
+        Some(info) => (info.decimals as u32, Some(info.symbol.clone())),
+        None => (fetch_decimals(provider, token).await?, None),
+    };
+
+    Ok((token, decimals, symbol))
+}
+
+fn format_balance(
+    amount_format: AmountFormat,
+    hex: bool,
+    raw_balance: &BigUint,
+    decimals: u32,
+    symbol: Option<&str>,
+) -> String {
+    if hex {
+        format!("{:#x}", raw_balance)
+    } else {
+        amount_format.format_units(raw_balance, decimals, symbol)
+    }
+}
+
+async fn fetch_decimals(provider: &ExtendedProvider, token: FieldElement) -> Result<u32> {
+    let result = provider
+        .call(
+            FunctionCall {
+                contract_address: token,
+                entry_point_selector: selector!("decimals"),
+                calldata: vec![],
+            },
+            BlockId::Tag(BlockTag::Pending),
+        )
+        .await?;
+
+    match result.first() {
+        Some(decimals) => Ok(u8::try_from(*decimals)
+            .map_err(|_| anyhow::anyhow!("invalid token decimals: {}", decimals))?
+            as u32),
+        None => anyhow::bail!("unexpected call result size: {}", result.len()),
+    }
+}
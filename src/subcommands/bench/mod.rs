@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod send;
+use send::BenchSend;
+
+#[derive(Debug, Parser)]
+pub struct Bench {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Send transfers at a fixed rate and measure throughput and latency")]
+    Send(BenchSend),
+}
+
+impl Bench {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Send(cmd) => cmd.run().await,
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    accounts::{Account, ConnectedAccount, ExecutionEncoding, SingleOwnerAccount},
+    core::{chain_id::MAINNET, types::FieldElement},
+    providers::Provider,
+    signers::{LocalWallet, SigningKey},
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    account::{load_accounts_file, BUILTIN_ACCOUNTS},
+    fee::check_max_fee_ceiling,
+    path::ExpandedPathbufParser,
+    provider::ExtendedProvider,
+    signer::AnySigner,
+    utils::{noop_call, parse_duration},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+/// How long to keep polling for the receipt of a sent transaction.
+const ACCEPTANCE_TIMEOUT: Duration = Duration::from_secs(120);
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Parser)]
+pub struct BenchSend {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Accounts file in the same format as `multi invoke`. Defaults to the built-in \
+                Katana accounts"
+    )]
+    accounts: Option<PathBuf>,
+    #[clap(long, help = "Password shared by all keystores in the accounts file")]
+    keystore_password: Option<String>,
+    #[clap(
+        long,
+        default_value = "10",
+        help = "Target number of transactions per second"
+    )]
+    tps: f64,
+    #[clap(
+        long,
+        value_parser = parse_duration,
+        default_value = "60s",
+        help = "How long to send transactions for, like `90s` or `5m`"
+    )]
+    duration: Duration,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+/// An account from the pool. Transactions from the same account are sent one at a time with
+/// locally tracked nonces, so that they never arrive out of order.
+struct PoolAccount {
+    account: SingleOwnerAccount<Arc<ExtendedProvider>, AnySigner>,
+    nonce: Mutex<FieldElement>,
+}
+
+struct TxResult {
+    /// Time taken for the node to accept the submission.
+    submission: Duration,
+    /// Time from submission until the receipt is available, if it ever was.
+    acceptance: Option<Duration>,
+}
+
+impl BenchSend {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        if self.tps.is_nan() || self.tps <= 0f64 {
+            anyhow::bail!("--tps must be positive");
+        }
+
+        let provider = Arc::new(self.provider.into_provider()?);
+
+        // Benchmarks send large numbers of transactions, which is never intended with real funds
+        let chain_id = provider.chain_id().await?;
+        if chain_id == MAINNET {
+            anyhow::bail!("benchmarks can't be run on mainnet");
+        }
+
+        let accounts = match &self.accounts {
+            Some(path) => {
+                load_accounts_file(provider.clone(), path, self.keystore_password.as_deref())
+                    .await?
+            }
+            None => BUILTIN_ACCOUNTS
+                .iter()
+                .map(|builtin| {
+                    SingleOwnerAccount::new(
+                        provider.clone(),
+                        AnySigner::LocalWallet(LocalWallet::from_signing_key(
+                            SigningKey::from_secret_scalar(builtin.private_key),
+                        )),
+                        builtin.address,
+                        chain_id,
+                        ExecutionEncoding::Legacy,
+                    )
+                })
+                .collect(),
+        };
+
+        let mut pool = vec![];
+        for account in accounts.into_iter() {
+            let nonce = account.get_nonce().await?;
+            pool.push(Arc::new(PoolAccount {
+                account,
+                nonce: Mutex::new(nonce),
+            }));
+        }
+
+        // All transactions are identical self-transfers, so a single estimate with a generous
+        // buffer is used for all of them instead of estimating each one
+        let first_account = &pool[0].account;
+        let max_fee = first_account
+            .execute(vec![noop_call(
+                provider.eth_token_address(),
+                first_account.address(),
+            )])
+            .estimate_fee()
+            .await?
+            .overall_fee
+            * FieldElement::TWO;
+        check_max_fee_ceiling(&provider, max_fee, false)?;
+
+        eprintln!(
+            "Sending {} transactions per second for {} seconds from {} account(s)...",
+            self.tps.to_string().bright_yellow(),
+            self.duration.as_secs().to_string().bright_yellow(),
+            pool.len().to_string().bright_yellow()
+        );
+
+        let start_time = Instant::now();
+        let mut ticker = tokio::time::interval(Duration::from_secs_f64(1f64 / self.tps));
+        let mut handles = vec![];
+
+        while start_time.elapsed() < self.duration {
+            ticker.tick().await;
+
+            let pool_account = pool[handles.len() % pool.len()].clone();
+            let provider = provider.clone();
+            handles.push(tokio::spawn(async move {
+                send_transfer(&provider, &pool_account, max_fee).await
+            }));
+        }
+        let send_duration = start_time.elapsed();
+
+        eprintln!("Waiting for receipts...");
+
+        let mut results = vec![];
+        let mut errors: HashMap<String, usize> = HashMap::new();
+        for handle in handles.into_iter() {
+            match handle.await? {
+                Ok(result) => results.push(result),
+                Err(err) => *errors.entry(err.to_string()).or_default() += 1,
+            }
+        }
+
+        print_report(&results, &errors, send_duration);
+
+        Ok(())
+    }
+}
+
+async fn send_transfer(
+    provider: &ExtendedProvider,
+    pool_account: &PoolAccount,
+    max_fee: FieldElement,
+) -> Result<TxResult> {
+    let account = &pool_account.account;
+
+    let (transaction_hash, submission) = {
+        let mut nonce = pool_account.nonce.lock().await;
+
+        let start_time = Instant::now();
+        let transaction_hash = account
+            .execute(vec![noop_call(
+                provider.eth_token_address(),
+                account.address(),
+            )])
+            .nonce(*nonce)
+            .max_fee(max_fee)
+            .send()
+            .await?
+            .transaction_hash;
+        *nonce += FieldElement::ONE;
+
+        (transaction_hash, start_time.elapsed())
+    };
+
+    let start_time = Instant::now();
+    while start_time.elapsed() < ACCEPTANCE_TIMEOUT {
+        if provider
+            .get_transaction_receipt(transaction_hash)
+            .await
+            .is_ok()
+        {
+            return Ok(TxResult {
+                submission,
+                acceptance: Some(start_time.elapsed()),
+            });
+        }
+
+        tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+    }
+
+    Ok(TxResult {
+        submission,
+        acceptance: None,
+    })
+}
+
+fn print_report(results: &[TxResult], errors: &HashMap<String, usize>, send_duration: Duration) {
+    let failed = errors.values().sum::<usize>();
+    let accepted = results
+        .iter()
+        .filter(|result| result.acceptance.is_some())
+        .count();
+
+    println!(
+        "Sent:     {} ({} failed) in {:.1}s, {:.2} tx/s",
+        results.len().to_string().bright_yellow(),
+        failed,
+        send_duration.as_secs_f64(),
+        results.len() as f64 / send_duration.as_secs_f64()
+    );
+    println!(
+        "Accepted: {} ({} timed out)",
+        accepted.to_string().bright_yellow(),
+        results.len() - accepted
+    );
+
+    print_latency(
+        "Submission latency",
+        results.iter().map(|result| result.submission).collect(),
+    );
+    print_latency(
+        "Acceptance latency",
+        results
+            .iter()
+            .filter_map(|result| result.acceptance)
+            .collect(),
+    );
+
+    if !errors.is_empty() {
+        eprintln!("{}", "Errors:".bright_magenta());
+        for (err, count) in errors.iter() {
+            eprintln!("  {} x {}", count, err);
+        }
+    }
+}
+
+fn print_latency(label: &str, mut latencies: Vec<Duration>) {
+    if latencies.is_empty() {
+        println!("{}: n/a", label);
+        return;
+    }
+
+    latencies.sort();
+    let percentile = |p: usize| latencies[(latencies.len() - 1) * p / 100];
+    let average = latencies.iter().sum::<Duration>() / latencies.len() as u32;
+
+    println!(
+        "{}: avg {}ms, p50 {}ms, p95 {}ms, max {}ms",
+        label,
+        average.as_millis(),
+        percentile(50).as_millis(),
+        percentile(95).as_millis(),
+        percentile(100).as_millis()
+    );
+}
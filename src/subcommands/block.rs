@@ -1,9 +1,19 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 use clap::Parser;
-use colored_json::{ColorMode, Output};
-use starknet::providers::Provider;
+use colored::Colorize;
+use starknet::{core::types::FieldElement, providers::Provider};
 
-use crate::{utils::parse_block_id, verbosity::VerbosityArgs, ProviderArgs};
+use crate::{
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    provider::ExtendedProvider,
+    select::SelectArgs,
+    utils::{decode_execute_calldata, json_felt, parse_block_id},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
 
 #[derive(Debug, Parser)]
 pub struct Block {
@@ -11,33 +21,219 @@ pub struct Block {
     provider: ProviderArgs,
     #[clap(long, help = "Fetch full transactions instead of hashes only")]
     full: bool,
+    #[clap(
+        long,
+        conflicts_with_all = ["full", "select"],
+        help = "Print a summary table of the block's transactions instead of the block"
+    )]
+    txs: bool,
+    #[clap(
+        long = "where",
+        value_name = "KEY=VALUE",
+        requires = "txs",
+        help = "Only include transactions matching the filter. Keys are `sender`, `to`, \
+                `selector`, and `type`. Can be repeated, in which case all filters must match"
+    )]
+    filters: Vec<String>,
     #[clap(
         default_value = "latest",
         help = "Block number, hash, or tag (latest/pending)"
     )]
     block_id: String,
     #[clap(flatten)]
+    select: SelectArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
+enum TxFilter {
+    Sender(FieldElement),
+    To(FieldElement),
+    Selector(FieldElement),
+    Type(String),
+}
+
+/// Transaction fields relevant to filtering, extracted from the JSON representation to uniformly
+/// cover all types and versions.
+struct TxSummary {
+    hash: FieldElement,
+    tx_type: String,
+    sender: Option<FieldElement>,
+    calls: Vec<(FieldElement, FieldElement)>,
+}
+
 impl Block {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
 
-        let provider = self.provider.into_provider()?;
+        let provider = Arc::new(self.provider.into_provider()?);
 
         let block_id = parse_block_id(&self.block_id)?;
 
+        if self.txs {
+            let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+            let mut filters = vec![];
+            for filter in self.filters.iter() {
+                filters.push(TxFilter::parse(&felt_decoder, filter).await?);
+            }
+
+            let block = serde_json::to_value(provider.get_block_with_txs(block_id).await?)?;
+            let transactions = block
+                .get("transactions")
+                .and_then(|txs| txs.as_array())
+                .map(|txs| txs.iter().map(TxSummary::from_json).collect::<Vec<_>>())
+                .unwrap_or_default();
+            let total = transactions.len();
+
+            let mut matched = 0usize;
+            for (index, tx) in transactions.into_iter().enumerate() {
+                if filters.iter().all(|filter| filter.matches(&tx)) {
+                    matched += 1;
+                    tx.print(index);
+                }
+            }
+
+            eprintln!(
+                "{} of {} transaction(s) matched",
+                matched.to_string().bright_yellow(),
+                total
+            );
+
+            return Ok(());
+        }
+
         let block_json = if self.full {
             serde_json::to_value(provider.get_block_with_txs(block_id).await?)?
         } else {
             serde_json::to_value(provider.get_block_with_tx_hashes(block_id).await?)?
         };
 
-        let block_json =
-            colored_json::to_colored_json(&block_json, ColorMode::Auto(Output::StdOut))?;
-        println!("{block_json}");
+        self.select.print(&block_json)
+    }
+}
+
+impl TxFilter {
+    async fn parse(
+        felt_decoder: &FeltDecoder<Arc<ExtendedProvider>>,
+        filter: &str,
+    ) -> Result<Self> {
+        let (key, value) = filter
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("invalid filter `{}`: expected KEY=VALUE", filter))?;
+
+        Ok(match key.trim() {
+            "sender" => Self::Sender(
+                felt_decoder
+                    .decode_single_with_addr_fallback(value.trim())
+                    .await?,
+            ),
+            "to" => Self::To(
+                felt_decoder
+                    .decode_single_with_addr_fallback(value.trim())
+                    .await?,
+            ),
+            "selector" => Self::Selector(
+                felt_decoder
+                    .decode_single_with_selector_fallback(value.trim())
+                    .await?,
+            ),
+            "type" => Self::Type(value.trim().to_uppercase()),
+            key => anyhow::bail!(
+                "unknown filter key `{}`: expected `sender`, `to`, `selector`, or `type`",
+                key
+            ),
+        })
+    }
+
+    fn matches(&self, tx: &TxSummary) -> bool {
+        match self {
+            Self::Sender(sender) => tx.sender == Some(*sender),
+            Self::To(to) => tx.calls.iter().any(|(target, _)| target == to),
+            Self::Selector(selector) => tx.calls.iter().any(|(_, call)| call == selector),
+            Self::Type(tx_type) => &tx.tx_type == tx_type,
+        }
+    }
+}
+
+impl TxSummary {
+    fn from_json(tx: &serde_json::Value) -> Self {
+        let tx_type = tx
+            .get("type")
+            .and_then(|value| value.as_str())
+            .unwrap_or("UNKNOWN")
+            .to_owned();
+
+        let calldata = tx
+            .get("calldata")
+            .and_then(|value| value.as_array())
+            .map(|calldata| {
+                calldata
+                    .iter()
+                    .filter_map(|item| item.as_str())
+                    .filter_map(|item| FieldElement::from_hex_be(item).ok())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        // L1 handlers call the target directly, while invokes go through the account. Legacy (v0)
+        // invokes name the account in `contract_address` instead of `sender_address`.
+        let (sender, calls) = match tx_type.as_str() {
+            "L1_HANDLER" => (
+                None,
+                json_felt(tx, "contract_address")
+                    .zip(json_felt(tx, "entry_point_selector"))
+                    .into_iter()
+                    .collect(),
+            ),
+            "INVOKE" => (
+                json_felt(tx, "sender_address").or_else(|| json_felt(tx, "contract_address")),
+                decode_execute_calldata(&calldata)
+                    .map(|calls| {
+                        calls
+                            .into_iter()
+                            .map(|call| (call.to, call.selector))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            _ => (json_felt(tx, "sender_address"), vec![]),
+        };
+
+        Self {
+            hash: json_felt(tx, "transaction_hash").unwrap_or(FieldElement::ZERO),
+            tx_type,
+            sender,
+            calls,
+        }
+    }
+
+    fn print(&self, index: usize) {
+        let calls = self
+            .calls
+            .iter()
+            .map(|(to, selector)| format!("{}:{}", shorten_felt(to), shorten_felt(selector)))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        println!(
+            "{:>4}  {}  {:<14}  {}  {}",
+            index,
+            format!("{:#064x}", self.hash).bright_yellow(),
+            self.tx_type,
+            self.sender
+                .map(|sender| shorten_felt(&sender))
+                .unwrap_or_else(|| "-".to_owned()),
+            calls
+        );
+    }
+}
 
-        Ok(())
+fn shorten_felt(value: &FieldElement) -> String {
+    let hex = format!("{:x}", value);
+    if hex.len() > 10 {
+        format!("0x{}..{}", &hex[..4], &hex[hex.len() - 4..])
+    } else {
+        format!("0x{}", hex)
     }
 }
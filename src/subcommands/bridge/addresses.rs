@@ -0,0 +1,49 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{
+    network::NetworkSource, utils::eth_address_from_felt, verbosity::VerbosityArgs, ProviderArgs,
+};
+
+use super::KNOWN_BRIDGES;
+
+#[derive(Debug, Parser)]
+pub struct Addresses {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Addresses {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = self.provider.into_provider()?;
+        let network = provider.get_network().await?;
+
+        let bridges = KNOWN_BRIDGES
+            .iter()
+            .filter(|bridge| Some(bridge.network) == network)
+            .collect::<Vec<_>>();
+
+        if bridges.is_empty() {
+            eprintln!("No known StarkGate bridge for this network.");
+            return Ok(());
+        }
+
+        for bridge in bridges.into_iter() {
+            println!("{}:", bridge.token.to_uppercase().bright_yellow());
+            println!(
+                "    L1 bridge: {}",
+                eth_address_from_felt(bridge.l1_bridge)?
+            );
+            println!("    L2 bridge: {:#064x}", bridge.l2_bridge);
+            println!("    L2 token:  {:#064x}", bridge.l2_token);
+            println!("    Decimals:  {}", bridge.decimals);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::{
+        crypto::compute_hash_on_elements,
+        types::{FieldElement, StarknetError},
+    },
+    macros::{selector, short_string},
+    providers::{Provider, ProviderError},
+};
+
+use crate::{
+    address_book::AddressBookResolver, decode::FeltDecoder, network::NetworkSource,
+    utils::print_colored_json, verbosity::VerbosityArgs, ProviderArgs,
+};
+
+use super::{amount_to_u256, find_bridge};
+
+#[derive(Debug, Parser)]
+pub struct DepositStatus {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, default_value = "eth", help = "Token deposited")]
+    token: String,
+    #[clap(long, help = "Nonce of the L1-to-L2 message emitted by the deposit")]
+    message_nonce: FieldElement,
+    #[clap(help = "L2 recipient address")]
+    recipient: String,
+    #[clap(help = "Amount deposited, in token units (e.g. 0.1 for 0.1 ETH)")]
+    amount: BigDecimal,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl DepositStatus {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let bridge = find_bridge(provider.get_network().await?, &self.token)?;
+        let recipient = felt_decoder
+            .decode_single_with_addr_fallback(&self.recipient)
+            .await?;
+        let (amount_low, amount_high) = amount_to_u256(&self.amount, bridge.decimals)?;
+
+        // The L1 handler transaction hash is fully determined by the message, so it can be
+        // computed without looking at L1 at all.
+        let chain_id = provider.chain_id().await?;
+        let tx_hash = compute_hash_on_elements(&[
+            short_string!("l1_handler"),
+            FieldElement::ZERO, // version
+            bridge.l2_bridge,
+            selector!("handle_deposit"),
+            compute_hash_on_elements(&[bridge.l1_bridge, recipient, amount_low, amount_high]),
+            FieldElement::ZERO, // max_fee
+            chain_id,
+            self.message_nonce,
+        ]);
+
+        eprintln!(
+            "L1 handler transaction: {}",
+            format!("{:#064x}", tx_hash).bright_yellow()
+        );
+
+        match provider.get_transaction_receipt(tx_hash).await {
+            Ok(receipt) => {
+                let receipt = serde_json::to_value(receipt)?;
+                print_colored_json(&serde_json::json!({
+                    "finality_status": receipt.get("finality_status"),
+                    "execution_status": receipt.get("execution_status"),
+                    "block_number": receipt.get("block_number"),
+                }))?;
+            }
+            Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {
+                eprintln!(
+                    "The deposit has not been processed on L2 yet. Double check the message \
+                    nonce, recipient, and amount if this persists."
+                );
+            }
+            Err(err) => return Err(err.into()),
+        }
+
+        Ok(())
+    }
+}
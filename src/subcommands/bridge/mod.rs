@@ -0,0 +1,102 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use clap::{Parser, Subcommand};
+use starknet::{core::types::FieldElement, macros::felt};
+
+use crate::{network::Network, token::ETH_ADDRESS, utils::bigdecimal_to_felt};
+
+mod addresses;
+use addresses::Addresses;
+
+mod withdraw;
+use withdraw::Withdraw;
+
+mod deposit_status;
+use deposit_status::DepositStatus;
+
+/// StarkGate bridges known to Starkli. Only ETH is supported for now.
+const KNOWN_BRIDGES: [KnownBridge; 3] = [
+    KnownBridge {
+        network: Network::Mainnet,
+        token: "eth",
+        decimals: 18,
+        l1_bridge: felt!("0xae0ee0a63a2ce6baeeffe56e7714fb4efe48d419"),
+        l2_bridge: felt!("0x073314940630fd6dcda0d772d4c972c4e0a9946bef9dabf4ef84eda8ef542b82"),
+        l2_token: ETH_ADDRESS,
+    },
+    KnownBridge {
+        network: Network::Goerli,
+        token: "eth",
+        decimals: 18,
+        l1_bridge: felt!("0xc3511006c04ef1d78af4c8e0e74ec18a6e64ff9e"),
+        l2_bridge: felt!("0x073314940630fd6dcda0d772d4c972c4e0a9946bef9dabf4ef84eda8ef542b82"),
+        l2_token: ETH_ADDRESS,
+    },
+    KnownBridge {
+        network: Network::Sepolia,
+        token: "eth",
+        decimals: 18,
+        l1_bridge: felt!("0x8453fc6cd1bcfe8d4dfc069c400b433054d47bdc"),
+        l2_bridge: felt!("0x04c5772d1914fe6ce891b64eb35bf3522aeae1315647314aac58b01137607f3f"),
+        l2_token: ETH_ADDRESS,
+    },
+];
+
+struct KnownBridge {
+    network: Network,
+    token: &'static str,
+    decimals: u8,
+    l1_bridge: FieldElement,
+    l2_bridge: FieldElement,
+    l2_token: FieldElement,
+}
+
+#[derive(Debug, Parser)]
+pub struct Bridge {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Show the known StarkGate bridge addresses for the current network")]
+    Addresses(Addresses),
+    #[clap(about = "Initiate a withdrawal from L2 to L1")]
+    Withdraw(Box<Withdraw>),
+    #[clap(about = "Check whether an L1 deposit has been processed on L2")]
+    DepositStatus(DepositStatus),
+}
+
+impl Bridge {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Addresses(cmd) => cmd.run().await,
+            Subcommands::Withdraw(cmd) => cmd.run().await,
+            Subcommands::DepositStatus(cmd) => cmd.run().await,
+        }
+    }
+}
+
+fn find_bridge(network: Option<Network>, token: &str) -> Result<&'static KnownBridge> {
+    let network = network.ok_or_else(|| {
+        anyhow::anyhow!("StarkGate bridges are only known for mainnet, goerli, and sepolia")
+    })?;
+    let token = token.to_lowercase();
+
+    KNOWN_BRIDGES
+        .iter()
+        .find(|bridge| bridge.network == network && bridge.token == token)
+        .ok_or_else(|| anyhow::anyhow!("no known StarkGate bridge for {} on {}", token, network))
+}
+
+/// Converts a human-readable token amount into the `(low, high)` words of a `u256`.
+fn amount_to_u256(amount: &BigDecimal, decimals: u8) -> Result<(FieldElement, FieldElement)> {
+    let amount = bigdecimal_to_felt(amount, decimals)?;
+    let bytes = amount.to_bytes_be();
+
+    // Unwrapping is safe as these are never out of range
+    let high = FieldElement::from_byte_slice_be(&bytes[..16]).unwrap();
+    let low = FieldElement::from_byte_slice_be(&bytes[16..]).unwrap();
+
+    Ok((low, high))
+}
@@ -0,0 +1,132 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    accounts::{Account, Call},
+    macros::{felt, selector},
+};
+
+use crate::{
+    account::AccountArgs,
+    error::decode_account_error,
+    fee::{check_max_fee_ceiling, FeeArgs, FeeSetting},
+    messages::message,
+    network::NetworkSource,
+    utils::{eth_address_from_felt, parse_eth_address, watch_tx},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+use super::{amount_to_u256, find_bridge};
+
+#[derive(Debug, Parser)]
+pub struct Withdraw {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    account: AccountArgs,
+    #[clap(flatten)]
+    fee: FeeArgs,
+    #[clap(long, default_value = "eth", help = "Token to withdraw")]
+    token: String,
+    #[clap(long, short, help = "Wait for the transaction to confirm")]
+    watch: bool,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        default_value = "5000",
+        help = "Transaction result poll interval in milliseconds"
+    )]
+    poll_interval: u64,
+    #[clap(help = "L1 recipient address")]
+    l1_recipient: String,
+    #[clap(help = "Amount to withdraw, in token units (e.g. 0.1 for 0.1 ETH)")]
+    amount: BigDecimal,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Withdraw {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let force_fee = self.fee.is_forced();
+        let fee_setting = self.fee.into_setting()?;
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let bridge = find_bridge(provider.get_network().await?, &self.token)?;
+
+        let l1_recipient = parse_eth_address(&self.l1_recipient)?;
+        let (amount_low, amount_high) = amount_to_u256(&self.amount, bridge.decimals)?;
+
+        let account = self.account.into_account(provider.clone()).await?;
+
+        // The max fee is always set explicitly, so the estimate is buffered below instead of
+        // with a fee estimate multiplier
+        let execution = account.execute(vec![Call {
+            to: bridge.l2_bridge,
+            selector: selector!("initiate_withdraw"),
+            calldata: vec![l1_recipient, amount_low, amount_high],
+        }]);
+
+        let max_fee = match fee_setting {
+            FeeSetting::Manual(fee) => fee,
+            FeeSetting::EstimateOnly { .. } | FeeSetting::None => {
+                let fee_estimate = execution
+                    .estimate_fee()
+                    .await
+                    .map_err(decode_account_error)?;
+                let estimated_fee = fee_estimate.overall_fee;
+
+                if fee_setting.is_estimate_only() {
+                    fee_setting.print_estimate(&fee_estimate)?;
+                    return Ok(());
+                }
+
+                (estimated_fee * felt!("3")).floor_div(felt!("2"))
+            }
+        };
+
+        check_max_fee_ceiling(&provider, max_fee, force_fee)?;
+
+        eprintln!(
+            "Withdrawing {} {} to L1 address {}",
+            format!("{}", self.amount).bright_yellow(),
+            bridge.token.to_uppercase(),
+            eth_address_from_felt(l1_recipient)?.bright_yellow()
+        );
+
+        let withdraw_tx = execution
+            .max_fee(max_fee)
+            .send()
+            .await
+            .map_err(decode_account_error)?
+            .transaction_hash;
+        eprintln!(
+            "Withdrawal transaction: {}",
+            format!("{:#064x}", withdraw_tx).bright_yellow()
+        );
+
+        if self.watch {
+            eprintln!(
+                "{}",
+                message("tx.waiting", &[("hash", &format!("{:#064x}", withdraw_tx))])
+            );
+            watch_tx(
+                &provider,
+                withdraw_tx,
+                Duration::from_millis(self.poll_interval),
+            )
+            .await?;
+        }
+
+        eprintln!(
+            "The funds can be claimed on L1 once the block containing the transaction is proven."
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::providers::Provider;
+
+use crate::{
+    messages::message,
+    path::ExpandedPathbufParser,
+    transaction_file::{ExportedTransaction, TransactionFile},
+    utils::watch_tx,
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct BroadcastTx {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, short, help = "Wait for the transaction to confirm")]
+    watch: bool,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        default_value = "5000",
+        help = "Transaction result poll interval in milliseconds"
+    )]
+    poll_interval: u64,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the signed transaction file"
+    )]
+    file: PathBuf,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl BroadcastTx {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = self.provider.into_provider()?;
+
+        let transaction_file = TransactionFile::load(&self.file)?;
+        let expected_hash = transaction_file.verify_hash()?;
+
+        if transaction_file.signature.is_empty() {
+            anyhow::bail!("transaction is not signed. Sign it with `starkli sign-tx` first");
+        }
+
+        let chain_id = provider.chain_id().await?;
+        if chain_id != transaction_file.chain_id {
+            anyhow::bail!(
+                "chain ID mismatch: transaction is for {:#x} but the provider is on {:#x}",
+                transaction_file.chain_id,
+                chain_id
+            );
+        }
+
+        let signature = transaction_file.signature;
+        let transaction_hash = match transaction_file.transaction {
+            ExportedTransaction::Invoke(invoke) => {
+                provider
+                    .add_invoke_transaction(invoke.into_broadcasted(signature))
+                    .await?
+                    .transaction_hash
+            }
+            ExportedTransaction::Declare(declare) => {
+                provider
+                    .add_declare_transaction(declare.into_broadcasted(signature))
+                    .await?
+                    .transaction_hash
+            }
+            ExportedTransaction::DeclareLegacy(declare) => {
+                provider
+                    .add_declare_transaction(declare.into_broadcasted(signature))
+                    .await?
+                    .transaction_hash
+            }
+        };
+
+        if transaction_hash != expected_hash {
+            eprintln!(
+                "{}",
+                "WARNING: the transaction hash returned by the node differs from the one in the file."
+                    .bright_magenta()
+            );
+        }
+
+        eprintln!(
+            "Transaction sent: {}",
+            format!("{:#064x}", transaction_hash).bright_yellow()
+        );
+
+        if self.watch {
+            eprintln!(
+                "{}",
+                message(
+                    "tx.waiting",
+                    &[("hash", &format!("{:#064x}", transaction_hash))]
+                )
+            );
+            watch_tx(
+                &provider,
+                transaction_hash,
+                Duration::from_millis(self.poll_interval),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -2,60 +2,197 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use clap::Parser;
+use colored::Colorize;
+use futures_util::{stream, StreamExt};
 use starknet::{
-    core::types::{BlockId, BlockTag, FunctionCall},
+    accounts::Call as ContractCall,
+    core::types::{BlockId, FieldElement, FunctionCall},
+    macros::selector,
     providers::Provider,
 };
 
 use crate::{
-    address_book::AddressBookResolver, decode::FeltDecoder, verbosity::VerbosityArgs, ProviderArgs,
+    abi::{function_outputs, AbiDecoder},
+    abi_cache::CachedAbi,
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    default_block::default_block_id,
+    provider::{ExtendedProvider, NetworksArgs},
+    utils::read_stdin_calldata,
+    verbosity::VerbosityArgs,
+    ProviderArgs,
 };
 
+/// Maximum number of calls in flight at the same time when using `--every-block`.
+const SERIES_CONCURRENCY: usize = 16;
+
 #[derive(Debug, Parser)]
 pub struct Call {
     #[clap(flatten)]
     provider: ProviderArgs,
+    #[clap(
+        long,
+        value_name = "FROM..TO",
+        conflicts_with = "networks",
+        help = "Evaluate the call at every block in the inclusive range and print the results as CSV"
+    )]
+    every_block: Option<String>,
+    #[clap(
+        long,
+        requires = "every_block",
+        default_value = "1",
+        help = "Number of blocks between evaluations when using --every-block"
+    )]
+    step: u64,
+    #[clap(
+        long,
+        conflicts_with_all = ["every_block", "networks"],
+        help = "Exit with an error if the result differs from this value. Accepts a single value \
+                in any calldata format (e.g. `u256:100`), or a JSON array of values"
+    )]
+    expect: Option<String>,
+    #[clap(
+        long,
+        conflicts_with_all = ["every_block", "networks"],
+        help = "Decode the result into typed JSON using the function outputs in the contract ABI"
+    )]
+    decode: bool,
+    #[clap(
+        long,
+        conflicts_with_all = ["decode", "every_block", "networks"],
+        help = "Print the result as one felt per line instead of a JSON array"
+    )]
+    lines: bool,
+    #[clap(
+        long,
+        help = "Append calldata read from stdin, either as a JSON array or one value per line"
+    )]
+    stdin_calldata: bool,
+    #[clap(flatten)]
+    networks: NetworksArgs,
+    #[clap(
+        long,
+        help = "Aggregator contract for batch calls, overriding the one configured for the network"
+    )]
+    aggregator: Option<String>,
+    #[clap(
+        long,
+        conflicts_with = "aggregator",
+        help = "Send batch calls as separate RPC calls even if an aggregator contract is available"
+    )]
+    no_aggregator: bool,
     #[clap(help = "Contract address")]
     contract_address: String,
     #[clap(help = "Name of the function being called")]
     selector: String,
-    #[clap(help = "Raw function call arguments")]
+    #[clap(help = "Raw function call arguments. Use `/` to separate multiple calls in a batch")]
     calldata: Vec<String>,
     #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
 impl Call {
-    pub async fn run(self) -> Result<()> {
+    pub async fn run(mut self) -> Result<()> {
         self.verbosity.setup_logging();
 
-        let provider = Arc::new(self.provider.into_provider()?);
-        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+        if self.stdin_calldata {
+            self.calldata.extend(read_stdin_calldata()?);
+        }
+
+        if !self.networks.is_empty() {
+            let contract_address = self.contract_address.clone();
+            let selector = self.selector.clone();
+            let calldata = self.calldata.clone();
 
-        let contract_address = felt_decoder
-            .decode_single_with_addr_fallback(&self.contract_address)
-            .await?;
-        let selector = felt_decoder
-            .decode_single_with_selector_fallback(&self.selector)
-            .await?;
+            return self
+                .networks
+                .fan_out(move |provider| {
+                    let contract_address = contract_address.clone();
+                    let selector = selector.clone();
+                    let calldata = calldata.clone();
 
-        let mut calldata = vec![];
-        for element in self.calldata.iter() {
-            calldata.append(&mut felt_decoder.decode(element).await?);
+                    async move {
+                        // Arguments are resolved per network as address book entries may differ
+                        let call =
+                            build_call(provider.clone(), &contract_address, &selector, &calldata)
+                                .await?;
+                        let result = provider.call(call, default_block_id()).await?;
+
+                        Ok(format!(
+                            "[{}]",
+                            result
+                                .iter()
+                                .map(|element| format!("\"{:#064x}\"", element))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ))
+                    }
+                })
+                .await;
         }
 
-        let result = provider
-            .call(
-                FunctionCall {
-                    contract_address,
-                    entry_point_selector: selector,
-                    calldata,
-                },
-                BlockId::Tag(BlockTag::Pending),
-            )
-            .await?;
-
-        if result.is_empty() {
+        if self
+            .calldata
+            .iter()
+            .any(|arg| matches!(arg.as_str(), "/" | "-" | "\\"))
+        {
+            if self.every_block.is_some()
+                || self.expect.is_some()
+                || self.decode
+                || self.lines
+                || !self.networks.is_empty()
+            {
+                anyhow::bail!(
+                    "batch calls cannot be used with --every-block, --expect, --decode, --lines \
+                     or --networks"
+                );
+            }
+
+            return self.run_batch().await;
+        }
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let call = build_call(
+            provider.clone(),
+            &self.contract_address,
+            &self.selector,
+            &self.calldata,
+        )
+        .await?;
+
+        if let Some(range) = &self.every_block {
+            return Self::print_series(provider, call, parse_block_range(range)?, self.step).await;
+        }
+
+        // Decoded before calling so that a malformed expectation fails without touching the network
+        let expected = match &self.expect {
+            Some(expect) => Some(decode_expectation(provider.clone(), expect).await?),
+            None => None,
+        };
+
+        let contract_address = call.contract_address;
+        let selector = call.entry_point_selector;
+        let result = provider.call(call, default_block_id()).await?;
+
+        if self.decode {
+            let abi = CachedAbi::of_contract(&provider, contract_address)
+                .await?
+                .sierra_abi()?
+                .ok_or_else(|| anyhow::anyhow!("decoding is only supported for Sierra classes"))?;
+            let outputs = function_outputs(&abi, selector)
+                .ok_or_else(|| anyhow::anyhow!("function not found in the contract ABI"))?;
+
+            println!(
+                "{}",
+                serde_json::to_string_pretty(
+                    &AbiDecoder::new(&abi).decode_outputs(&outputs, &result)?
+                )?
+            );
+        } else if self.lines {
+            for element in result.iter() {
+                println!("{:#064x}", element);
+            }
+        } else if result.is_empty() {
             println!("[]");
         } else {
             println!("[");
@@ -75,6 +212,277 @@ impl Call {
             println!("]");
         }
 
+        if let Some(expected) = expected {
+            if result != expected {
+                anyhow::bail!(
+                    "unexpected call result: expected [{}], got [{}]",
+                    format_felts(&expected),
+                    format_felts(&result)
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run_batch(self) -> Result<()> {
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let mut args = vec![self.contract_address, self.selector];
+        args.extend(self.calldata);
+        let calls = felt_decoder.decode_calls(args).await?;
+
+        let aggregator = match &self.aggregator {
+            Some(aggregator) => Some(
+                felt_decoder
+                    .decode_single_with_addr_fallback(aggregator)
+                    .await?,
+            ),
+            None if self.no_aggregator => None,
+            None => provider.aggregator_address(),
+        };
+
+        let results = match aggregator {
+            Some(aggregator) => aggregate_calls(&provider, aggregator, &calls).await?,
+            None => call_individually(provider, calls).await?,
+        };
+
+        let results = results
+            .iter()
+            .map(|result| {
+                result
+                    .iter()
+                    .map(|element| format!("{:#064x}", element))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&results)?);
+
+        Ok(())
+    }
+
+    async fn print_series(
+        provider: Arc<ExtendedProvider>,
+        call: FunctionCall,
+        (from, to): (u64, u64),
+        step: u64,
+    ) -> Result<()> {
+        if step == 0 {
+            anyhow::bail!("--step must be greater than 0");
+        }
+
+        let blocks = (from..=to).step_by(step as usize).collect::<Vec<_>>();
+        let mut header_printed = false;
+
+        // Rows are printed as soon as each call completes so that long ranges show progress, and
+        // a slow block doesn't hold back the ones after it. Each row carries its block number, so
+        // the completion order doesn't matter.
+        let mut results = stream::iter(blocks.iter().map(|block| {
+            let provider = provider.clone();
+            let call = call.clone();
+            let block = *block;
+            async move { (block, provider.call(call, BlockId::Number(block)).await) }
+        }))
+        .buffer_unordered(SERIES_CONCURRENCY);
+
+        let mut failed = 0usize;
+        while let Some((block, result)) = results.next().await {
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    failed += 1;
+                    eprintln!(
+                        "{}",
+                        format!("Call failed at block {}: {}", block, err).red()
+                    );
+                    continue;
+                }
+            };
+
+            // The number of columns is only known after the first result
+            if !header_printed {
+                let columns = (0..result.len())
+                    .map(|ind| format!("value_{}", ind))
+                    .collect::<Vec<_>>();
+                println!("block,{}", columns.join(","));
+                header_printed = true;
+            }
+
+            let values = result
+                .iter()
+                .map(|value| format!("{:#x}", value))
+                .collect::<Vec<_>>();
+            println!("{},{}", block, values.join(","));
+        }
+
+        if failed > 0 {
+            anyhow::bail!("the call failed at {} of {} block(s)", failed, blocks.len());
+        }
+
         Ok(())
     }
 }
+
+async fn build_call(
+    provider: Arc<ExtendedProvider>,
+    contract_address: &str,
+    selector: &str,
+    calldata: &[String],
+) -> Result<FunctionCall> {
+    let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider));
+
+    let (contract_address, selector, calldata) = felt_decoder
+        .decode_call(contract_address, selector, calldata)
+        .await?;
+
+    Ok(FunctionCall {
+        contract_address,
+        entry_point_selector: selector,
+        calldata,
+    })
+}
+
+/// Executes all calls in a single `aggregate` call, so that every result is taken from the same
+/// block. The aggregator follows the Multicall convention: it takes an array of
+/// `(to, selector, calldata)` structs and returns the block number followed by an array of
+/// results, each one prefixed with its length.
+async fn aggregate_calls(
+    provider: &ExtendedProvider,
+    aggregator: FieldElement,
+    calls: &[ContractCall],
+) -> Result<Vec<Vec<FieldElement>>> {
+    let mut calldata = vec![calls.len().into()];
+    for call in calls.iter() {
+        calldata.push(call.to);
+        calldata.push(call.selector);
+        calldata.push(call.calldata.len().into());
+        calldata.extend_from_slice(&call.calldata);
+    }
+
+    let output = provider
+        .call(
+            FunctionCall {
+                contract_address: aggregator,
+                entry_point_selector: selector!("aggregate"),
+                calldata,
+            },
+            default_block_id(),
+        )
+        .await?;
+
+    let malformed = || anyhow::anyhow!("unexpected output from aggregator contract");
+
+    let mut output = output.into_iter();
+    let block_number = output.next().ok_or_else(malformed)?;
+    let result_count = felt_to_usize(output.next().ok_or_else(malformed)?)?;
+    if result_count != calls.len() {
+        return Err(malformed());
+    }
+
+    let mut results = vec![];
+    for _ in 0..result_count {
+        let len = felt_to_usize(output.next().ok_or_else(malformed)?)?;
+        let result = output.by_ref().take(len).collect::<Vec<_>>();
+        if result.len() != len {
+            return Err(malformed());
+        }
+        results.push(result);
+    }
+
+    eprintln!(
+        "Aggregated {} calls at block {}",
+        calls.len(),
+        format!("{}", block_number).bright_yellow()
+    );
+
+    Ok(results)
+}
+
+/// Fallback for networks without an aggregator. Calls are sent as JSON-RPC batches where possible
+/// and pinned to the latest block so that results are still consistent with each other.
+async fn call_individually(
+    provider: Arc<ExtendedProvider>,
+    calls: Vec<ContractCall>,
+) -> Result<Vec<Vec<FieldElement>>> {
+    let block_number = provider.block_number().await?;
+    let call_count = calls.len();
+
+    let calls = calls
+        .into_iter()
+        .map(|call| FunctionCall {
+            contract_address: call.to,
+            entry_point_selector: call.selector,
+            calldata: call.calldata,
+        })
+        .collect::<Vec<_>>();
+
+    let results = provider
+        .batch_call(&calls, BlockId::Number(block_number))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+    eprintln!(
+        "Sent {} calls individually at block {}",
+        call_count,
+        block_number.to_string().bright_yellow()
+    );
+
+    Ok(results)
+}
+
+fn felt_to_usize(value: FieldElement) -> Result<usize> {
+    Ok(
+        u64::try_from(value).map_err(|_| anyhow::anyhow!("value out of range: {:#x}", value))?
+            as usize,
+    )
+}
+
+async fn decode_expectation(
+    provider: Arc<ExtendedProvider>,
+    expect: &str,
+) -> Result<Vec<FieldElement>> {
+    let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider));
+
+    let values = if expect.trim_start().starts_with('[') {
+        // Numbers are accepted too so that plain JSON like `[1, 2]` works
+        serde_json::from_str::<Vec<serde_json::Value>>(expect)
+            .map_err(|err| anyhow::anyhow!("invalid --expect JSON: {}", err))?
+            .into_iter()
+            .map(|value| match value {
+                serde_json::Value::String(value) => Ok(value),
+                serde_json::Value::Number(value) => Ok(value.to_string()),
+                _ => Err(anyhow::anyhow!(
+                    "--expect JSON values must be strings or numbers"
+                )),
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        vec![expect.to_owned()]
+    };
+
+    felt_decoder.decode_all(&values).await
+}
+
+fn format_felts(felts: &[FieldElement]) -> String {
+    felts
+        .iter()
+        .map(|felt| format!("{:#x}", felt))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn parse_block_range(range: &str) -> Result<(u64, u64)> {
+    let (from, to) = range
+        .split_once("..")
+        .ok_or_else(|| anyhow::anyhow!("invalid block range: {}", range))?;
+    let from = from.trim().parse::<u64>()?;
+    let to = to.trim().parse::<u64>()?;
+
+    if from > to {
+        anyhow::bail!("invalid block range: {} is after {}", from, to);
+    }
+
+    Ok((from, to))
+}
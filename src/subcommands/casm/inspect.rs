@@ -0,0 +1,275 @@
+use std::{borrow::Cow, collections::HashSet, path::PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::types::{
+    contract::{CompiledClass, CompiledClassEntrypoint},
+    FieldElement,
+};
+
+use crate::path::ExpandedPathbufParser;
+
+const OFFSET_BIAS: i32 = 1 << 15;
+
+#[derive(Debug, Parser)]
+pub struct Inspect {
+    #[clap(
+        long,
+        help = "Disassemble instructions around this bytecode offset (PC relative to the class)"
+    )]
+    pc: Option<usize>,
+    #[clap(
+        long,
+        default_value = "10",
+        requires = "pc",
+        help = "Number of instructions to show before and after --pc"
+    )]
+    context: usize,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the CASM artifact"
+    )]
+    file: PathBuf,
+}
+
+/// A single decoded Cairo instruction, along with its immediate value if any.
+struct Instruction {
+    pc: usize,
+    size: usize,
+    text: String,
+}
+
+impl Inspect {
+    pub fn run(self) -> Result<()> {
+        let class: CompiledClass = serde_json::from_reader(std::fs::File::open(&self.file)?)?;
+
+        println!(
+            "Compiler version: {}",
+            class.compiler_version.bright_yellow()
+        );
+        println!(
+            "Bytecode length:  {} felts",
+            class.bytecode.len().to_string().bright_yellow()
+        );
+        println!(
+            "Hints:            {} (at {} distinct offsets)",
+            class
+                .hints
+                .iter()
+                .map(|hint| hint.code.len())
+                .sum::<usize>()
+                .to_string()
+                .bright_yellow(),
+            class.hints.len().to_string().bright_yellow()
+        );
+        if let Some(pythonic_hints) = &class.pythonic_hints {
+            println!(
+                "Pythonic hints:   {}",
+                pythonic_hints.len().to_string().bright_yellow()
+            );
+        }
+
+        print_entry_points("Constructor", &class.entry_points_by_type.constructor);
+        print_entry_points("External", &class.entry_points_by_type.external);
+        print_entry_points("L1 handler", &class.entry_points_by_type.l1_handler);
+
+        if let Some(pc) = self.pc {
+            if pc >= class.bytecode.len() {
+                anyhow::bail!(
+                    "offset {} out of range: bytecode length is {}",
+                    pc,
+                    class.bytecode.len()
+                );
+            }
+
+            // Instructions have variable lengths, so decoding always needs to start from the
+            // beginning to find the correct instruction boundaries.
+            let instructions = disassemble(&class.bytecode);
+            let target_index = instructions
+                .iter()
+                .position(|instruction| pc < instruction.pc + instruction.size)
+                .unwrap_or(instructions.len() - 1);
+            if instructions[target_index].pc != pc {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "WARNING: offset {} is inside the instruction at {}",
+                        pc, instructions[target_index].pc
+                    )
+                    .bright_magenta()
+                );
+            }
+
+            let hint_offsets = class
+                .hints
+                .iter()
+                .map(|hint| hint.id as usize)
+                .collect::<HashSet<_>>();
+
+            println!();
+            println!("Disassembly:");
+            let start = target_index.saturating_sub(self.context);
+            let end = (target_index + self.context + 1).min(instructions.len());
+            for (index, instruction) in instructions[start..end].iter().enumerate() {
+                let line = format!(
+                    "{} {:>6}{}  {}",
+                    if start + index == target_index {
+                        ">"
+                    } else {
+                        " "
+                    },
+                    instruction.pc,
+                    if hint_offsets.contains(&instruction.pc) {
+                        "*"
+                    } else {
+                        " "
+                    },
+                    instruction.text
+                );
+
+                if start + index == target_index {
+                    println!("{}", line.bright_yellow());
+                } else {
+                    println!("{}", line);
+                }
+            }
+            println!();
+            println!("(offsets marked with * have hints attached)");
+        }
+
+        Ok(())
+    }
+}
+
+fn print_entry_points(kind: &str, entry_points: &[CompiledClassEntrypoint]) {
+    if entry_points.is_empty() {
+        return;
+    }
+
+    let mut entry_points = entry_points.iter().collect::<Vec<_>>();
+    entry_points.sort_by_key(|entry_point| entry_point.offset);
+
+    println!();
+    println!("{} entrypoints:", kind);
+    for entry_point in entry_points.into_iter() {
+        println!(
+            "  {:>6}  {:#064x}  {}",
+            entry_point.offset.to_string().bright_yellow(),
+            entry_point.selector,
+            entry_point.builtins.join(", ")
+        );
+    }
+}
+
+fn disassemble(bytecode: &[FieldElement]) -> Vec<Instruction> {
+    let mut instructions = vec![];
+
+    let mut pc = 0;
+    while pc < bytecode.len() {
+        let (size, text) = match decode_instruction(&bytecode[pc], bytecode.get(pc + 1)) {
+            Some(decoded) => decoded,
+            None => (1, format!("<data {:#x}>", bytecode[pc])),
+        };
+
+        instructions.push(Instruction { pc, size, text });
+        pc += size;
+    }
+
+    instructions
+}
+
+/// Decodes a Cairo instruction word into its assembly representation, returning the instruction
+/// size and text. Returns `None` if the word is not a valid instruction.
+fn decode_instruction(
+    word: &FieldElement,
+    immediate: Option<&FieldElement>,
+) -> Option<(usize, String)> {
+    let bytes = word.to_bytes_be();
+    if bytes[..24].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    let encoded = u64::from_be_bytes(bytes[24..].try_into().unwrap());
+
+    let off_dst = (encoded & 0xffff) as i32 - OFFSET_BIAS;
+    let off_op0 = ((encoded >> 16) & 0xffff) as i32 - OFFSET_BIAS;
+    let off_op1 = ((encoded >> 32) & 0xffff) as i32 - OFFSET_BIAS;
+    let flags = encoded >> 48;
+
+    let dst_reg = if flags & 1 == 0 { "ap" } else { "fp" };
+    let op0_reg = if (flags >> 1) & 1 == 0 { "ap" } else { "fp" };
+    let op1_src = (flags >> 2) & 0b111;
+    let res_logic = (flags >> 5) & 0b11;
+    let pc_update = (flags >> 7) & 0b111;
+    let ap_update = (flags >> 10) & 0b11;
+    let opcode = (flags >> 12) & 0b111;
+
+    let dst = memory(dst_reg, off_dst);
+    let op0 = memory(op0_reg, off_op0);
+    let (size, op1) = match op1_src {
+        0 => (1, format!("[{}]", offset(&op0, off_op1))),
+        1 => (2, format_immediate(immediate?)),
+        2 => (1, memory("fp", off_op1)),
+        4 => (1, memory("ap", off_op1)),
+        _ => return None,
+    };
+    let res: Cow<str> = match res_logic {
+        0 => Cow::Borrowed(&op1),
+        1 => Cow::Owned(format!("{} + {}", op0, op1)),
+        2 => Cow::Owned(format!("{} * {}", op0, op1)),
+        _ => return None,
+    };
+
+    let mut text = match (opcode, pc_update) {
+        (0, 0) => String::new(),
+        (0, 1) => format!("jmp abs {}", res),
+        (0, 2) => format!("jmp rel {}", res),
+        (0, 4) => format!("jmp rel {} if {} != 0", op1, dst),
+        (1, 1) => format!("call abs {}", res),
+        (1, 2) => format!("call rel {}", res),
+        (2, 1) => String::from("ret"),
+        (4, 0) => format!("{} = {}", dst, res),
+        _ => return None,
+    };
+
+    let ap_text = match ap_update {
+        0 => None,
+        1 => Some(format!("ap += {}", res)),
+        2 => Some(String::from("ap++")),
+        _ => return None,
+    };
+    if let Some(ap_text) = ap_text {
+        if text.is_empty() {
+            text = ap_text;
+        } else {
+            text = format!("{}, {}", text, ap_text);
+        }
+    }
+    if text.is_empty() {
+        text = String::from("nop");
+    }
+
+    Some((size, text))
+}
+
+fn memory(register: &str, off: i32) -> String {
+    format!("[{}]", offset(register, off))
+}
+
+fn offset(base: &str, off: i32) -> String {
+    match off.cmp(&0) {
+        std::cmp::Ordering::Less => format!("{} - {}", base, -off),
+        std::cmp::Ordering::Equal => format!("{} + 0", base),
+        std::cmp::Ordering::Greater => format!("{} + {}", base, off),
+    }
+}
+
+fn format_immediate(value: &FieldElement) -> String {
+    // Negative numbers are encoded as `PRIME - n`, which reads much better as `-n`
+    let negated = FieldElement::ZERO - *value;
+    if negated < *value {
+        format!("-{}", negated)
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod inspect;
+use inspect::Inspect;
+
+#[derive(Debug, Parser)]
+pub struct Casm {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(
+        about = "Show bytecode size, hints, entrypoints, and optionally disassembly of a CASM class"
+    )]
+    Inspect(Inspect),
+}
+
+impl Casm {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Inspect(cmd) => cmd.run(),
+        }
+    }
+}
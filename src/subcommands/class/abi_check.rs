@@ -0,0 +1,281 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use regex::Regex;
+use serde_json::Value;
+use starknet::core::types::{contract::AbiEntry, FieldElement};
+
+use crate::{
+    abi_cache::CachedAbi, accessibility::Status, address_book::AddressBookResolver,
+    decode::FeltDecoder, path::ExpandedPathbufParser, verbosity::VerbosityArgs, ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct AbiCheck {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "Treat the target as a class hash instead of a contract address"
+    )]
+    class_hash: bool,
+    #[clap(help = "Address of the deployed contract, or class hash with --class-hash")]
+    target: String,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the expected interface, as a Sierra ABI or class JSON file, or a text \
+                file with one `fn name(arg: Type, ...) -> Type` declaration per line"
+    )]
+    interface: PathBuf,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+/// Input and output types of a function, with module paths removed so that short names from
+/// interface files match the fully qualified names in ABIs.
+#[derive(PartialEq, Eq)]
+struct Signature {
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+}
+
+impl AbiCheck {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let expected = load_interface(&self.interface)?;
+        if expected.is_empty() {
+            anyhow::bail!("no function found in {}", self.interface.display());
+        }
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let abi = if self.class_hash {
+            let class_hash = FieldElement::from_hex_be(&self.target)?;
+            CachedAbi::of_class(&provider, class_hash).await?
+        } else {
+            let address = felt_decoder
+                .decode_single_with_addr_fallback(&self.target)
+                .await?;
+            CachedAbi::of_contract(&provider, address).await?
+        };
+        let abi = abi
+            .sierra_abi()?
+            .ok_or_else(|| anyhow::anyhow!("ABI checks are only supported for Sierra classes"))?;
+
+        let mut actual = BTreeMap::new();
+        collect_functions(&abi, &mut actual);
+
+        let mut problems = 0usize;
+        for (name, expected_signature) in expected.iter() {
+            match actual.get(name) {
+                Some(actual_signature) if actual_signature == expected_signature => {
+                    println!("{} {}", Status::Success.label("ok"), name);
+                }
+                Some(actual_signature) => {
+                    problems += 1;
+                    println!("{} {}", Status::Failure.label("mismatch"), name);
+                    println!("    expected: {}", expected_signature.display(name));
+                    println!("    actual:   {}", actual_signature.display(name));
+                }
+                None => {
+                    problems += 1;
+                    println!("{} {}", Status::Failure.label("missing"), name);
+                }
+            }
+        }
+
+        eprintln!(
+            "{} of {} function(s) match",
+            (expected.len() - problems).to_string().bright_yellow(),
+            expected.len()
+        );
+
+        if problems > 0 {
+            anyhow::bail!("class does not implement the expected interface");
+        }
+
+        Ok(())
+    }
+}
+
+impl Signature {
+    fn new<'a, I, O>(inputs: I, outputs: O) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+        O: IntoIterator<Item = &'a str>,
+    {
+        Self {
+            inputs: inputs.into_iter().map(normalize_type).collect(),
+            outputs: outputs.into_iter().map(normalize_type).collect(),
+        }
+    }
+
+    fn display(&self, name: &str) -> String {
+        let mut signature = format!("fn {}({})", name, self.inputs.join(", "));
+        if !self.outputs.is_empty() {
+            signature.push_str(" -> ");
+            signature.push_str(&self.outputs.join(", "));
+        }
+        signature
+    }
+}
+
+fn collect_functions(entries: &[AbiEntry], functions: &mut BTreeMap<String, Signature>) {
+    for entry in entries.iter() {
+        match entry {
+            AbiEntry::Function(function) => {
+                functions.insert(
+                    function.name.clone(),
+                    Signature::new(
+                        function.inputs.iter().map(|input| input.r#type.as_str()),
+                        function.outputs.iter().map(|output| output.r#type.as_str()),
+                    ),
+                );
+            }
+            AbiEntry::Interface(interface) => collect_functions(&interface.items, functions),
+            _ => {}
+        }
+    }
+}
+
+fn load_interface(path: &Path) -> Result<BTreeMap<String, Signature>> {
+    let content = std::fs::read_to_string(path)?;
+
+    let mut functions = BTreeMap::new();
+
+    match serde_json::from_str::<Value>(&content) {
+        Ok(file) => {
+            let abi: Vec<AbiEntry> = match file {
+                // Sierra class artifacts, where the ABI is either embedded as a string or inline
+                Value::Object(mut class) => match class.remove("abi") {
+                    Some(Value::String(abi)) => serde_json::from_str(&abi)?,
+                    Some(abi) => serde_json::from_value(abi)?,
+                    None => anyhow::bail!("file {} does not contain an ABI", path.display()),
+                },
+                abi => serde_json::from_value(abi)?,
+            };
+            collect_functions(&abi, &mut functions);
+        }
+        Err(_) => {
+            for (ind_line, line) in content.lines().enumerate() {
+                let line = line.split("//").next().unwrap_or_default().trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+
+                let (name, signature) = parse_declaration(line).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "invalid declaration on line {} of {}: {}",
+                        ind_line + 1,
+                        path.display(),
+                        line
+                    )
+                })?;
+                functions.insert(name, signature);
+            }
+        }
+    }
+
+    Ok(functions)
+}
+
+/// Parses a declaration like `fn transfer(recipient: ContractAddress, amount: u256) -> bool;`.
+/// Argument names are optional, and `self` is skipped so that Cairo traits can be pasted as is.
+fn parse_declaration(line: &str) -> Option<(String, Signature)> {
+    let line = line.strip_prefix("fn ")?.trim().trim_end_matches(';');
+
+    let (name, rest) = line.split_once('(')?;
+    let close = matching_paren(rest)?;
+    let (args, ret) = (&rest[..close], rest[close + 1..].trim());
+
+    let inputs = split_top_level(args)
+        .into_iter()
+        .filter(|arg| arg_name(arg) != "self")
+        .map(arg_type)
+        .collect::<Vec<_>>();
+
+    let outputs = match ret.strip_prefix("->") {
+        Some(ret) => vec![ret.trim()],
+        None if ret.is_empty() => vec![],
+        None => return None,
+    };
+
+    Some((name.trim().to_owned(), Signature::new(inputs, outputs)))
+}
+
+fn arg_name(arg: &str) -> &str {
+    let arg = arg.strip_prefix("ref ").unwrap_or(arg);
+    arg.split(':').next().unwrap_or_default().trim()
+}
+
+/// Takes the type out of an argument like `amount: u256`. The separator is a single colon, as
+/// double colons are part of paths.
+fn arg_type(arg: &str) -> &str {
+    let bytes = arg.as_bytes();
+    for (index, byte) in bytes.iter().enumerate() {
+        let is_separator = *byte == b':'
+            && bytes.get(index + 1) != Some(&b':')
+            && (index == 0 || bytes[index - 1] != b':');
+        if is_separator {
+            return arg[index + 1..].trim();
+        }
+    }
+    arg
+}
+
+/// Finds the position of the parenthesis closing an already opened one.
+fn matching_paren(text: &str) -> Option<usize> {
+    let mut depth = 1usize;
+    for (index, character) in text.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits on commas that are not nested in generics or tuples.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0i32;
+    let mut start = 0usize;
+
+    for (index, character) in text.char_indices() {
+        match character {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(text[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+
+    parts.into_iter().filter(|part| !part.is_empty()).collect()
+}
+
+/// Turns `core::array::Span::<core::felt252>` into `Span<felt252>`.
+fn normalize_type(ty: &str) -> String {
+    // Safe to unwrap as the pattern is valid
+    let path_regex = Regex::new(r"(?:[A-Za-z_][A-Za-z0-9_]*::)+").unwrap();
+
+    let ty = ty.replace(char::is_whitespace, "").replace("::<", "<");
+    path_regex.replace_all(&ty, "").into_owned()
+}
@@ -0,0 +1,202 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde_json::Value;
+use starknet::core::{
+    types::FieldElement,
+    utils::{get_selector_from_name, starknet_keccak},
+};
+
+use crate::{
+    abi_cache::CachedAbi, address_book::AddressBookResolver, decode::FeltDecoder,
+    verbosity::VerbosityArgs, ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Inspect {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "Treat the target as a class hash instead of a contract address"
+    )]
+    class_hash: bool,
+    #[clap(help = "Address of the deployed contract, or class hash with --class-hash")]
+    target: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Inspect {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let abi = if self.class_hash {
+            let class_hash = FieldElement::from_hex_be(&self.target)?;
+            CachedAbi::of_class(&provider, class_hash).await?
+        } else {
+            let address = felt_decoder
+                .decode_single_with_addr_fallback(&self.target)
+                .await?;
+            CachedAbi::of_contract(&provider, address).await?
+        };
+
+        println!(
+            "Class hash: {}",
+            format!("{:#064x}", abi.class_hash).bright_yellow()
+        );
+        println!(
+            "Cairo:      {}",
+            if abi.sierra { "Cairo 1" } else { "Cairo 0" }.bright_yellow()
+        );
+
+        let entries = abi.abi.as_array().cloned().unwrap_or_default();
+
+        let mut functions = vec![];
+        collect_functions(&entries, &mut functions);
+        println!();
+        println!(
+            "Functions ({}):",
+            functions.len().to_string().bright_yellow()
+        );
+        for (name, kind) in functions.iter() {
+            println!(
+                "  {}  {}  ({})",
+                format!("{:#064x}", get_selector_from_name(name)?).bright_yellow(),
+                name,
+                kind
+            );
+        }
+
+        let events = entries
+            .iter()
+            .filter(|entry| entry_type(entry) == Some("event"))
+            .collect::<Vec<_>>();
+        println!();
+        println!("Events:");
+        if events.is_empty() {
+            println!("  (none)");
+        }
+
+        if abi.sierra && events.iter().any(|event| event.get("kind").is_some()) {
+            // Events of contracts are emitted through a root enum, usually named `Event`, whose
+            // variant names become the keys. Components add a key per nesting level unless flat.
+            let nested_types = events
+                .iter()
+                .flat_map(|event| variants(event))
+                .filter_map(|variant| variant.get("type").and_then(|ty| ty.as_str()))
+                .collect::<HashSet<_>>();
+            for event in events.iter() {
+                let name = entry_name(event).unwrap_or_default();
+                if event.get("kind").and_then(|kind| kind.as_str()) == Some("enum")
+                    && !nested_types.contains(name)
+                {
+                    print_event_variants(&events, event, 1);
+                }
+            }
+        } else {
+            // Legacy events and events in early Sierra ABIs are keyed by their own name
+            for event in events.iter() {
+                let name = short_name(entry_name(event).unwrap_or_default());
+                print_event_line(name, 1);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn print_event_variants(events: &[&Value], event: &Value, depth: usize) {
+    for variant in variants(event) {
+        let name = entry_name(variant).unwrap_or_default();
+        let inner = variant
+            .get("type")
+            .and_then(|ty| ty.as_str())
+            .and_then(|ty| events.iter().find(|event| entry_name(event) == Some(ty)));
+        let inner_enum =
+            inner.filter(|inner| inner.get("kind").and_then(|kind| kind.as_str()) == Some("enum"));
+
+        match variant.get("kind").and_then(|kind| kind.as_str()) {
+            // Flat variants don't add a key of their own
+            Some("flat") => {
+                if let Some(inner) = inner_enum {
+                    print_event_variants(events, inner, depth);
+                }
+            }
+            _ => {
+                print_event_line(name, depth);
+                if let Some(inner) = inner_enum {
+                    print_event_variants(events, inner, depth + 1);
+                }
+            }
+        }
+    }
+}
+
+/// Each level of indentation is the next key of the event.
+fn print_event_line(name: &str, depth: usize) {
+    println!(
+        "{}{}  {}",
+        "  ".repeat(depth),
+        format!("{:#064x}", starknet_keccak(name.as_bytes())).bright_yellow(),
+        name
+    );
+}
+
+/// Functions with their kind, including those nested in interfaces.
+fn collect_functions(entries: &[Value], functions: &mut Vec<(String, String)>) {
+    for entry in entries.iter() {
+        match entry_type(entry) {
+            Some("function") => {
+                let kind = match entry
+                    .get("state_mutability")
+                    .or_else(|| entry.get("stateMutability"))
+                    .and_then(|value| value.as_str())
+                {
+                    Some("view") => "view",
+                    _ => "external",
+                };
+                if let Some(name) = entry_name(entry) {
+                    functions.push((name.to_owned(), kind.to_owned()));
+                }
+            }
+            Some("l1_handler") => {
+                if let Some(name) = entry_name(entry) {
+                    functions.push((name.to_owned(), "l1_handler".to_owned()));
+                }
+            }
+            Some("interface") => {
+                if let Some(items) = entry.get("items").and_then(|items| items.as_array()) {
+                    collect_functions(items, functions);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn variants(event: &Value) -> impl Iterator<Item = &Value> {
+    event
+        .get("variants")
+        .and_then(|variants| variants.as_array())
+        .into_iter()
+        .flatten()
+}
+
+fn entry_type(entry: &Value) -> Option<&str> {
+    entry.get("type").and_then(|ty| ty.as_str())
+}
+
+fn entry_name(entry: &Value) -> Option<&str> {
+    entry.get("name").and_then(|name| name.as_str())
+}
+
+/// Strips the module path from a fully qualified name.
+fn short_name(name: &str) -> &str {
+    name.rsplit("::").next().unwrap_or(name)
+}
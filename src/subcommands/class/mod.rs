@@ -0,0 +1,31 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod abi_check;
+use abi_check::AbiCheck;
+
+mod inspect;
+use inspect::Inspect;
+
+#[derive(Debug, Parser)]
+pub struct Class {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Check that an on-chain class implements the functions of a local interface")]
+    AbiCheck(AbiCheck),
+    #[clap(about = "List the functions and events of an on-chain class with their selectors")]
+    Inspect(Inspect),
+}
+
+impl Class {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::AbiCheck(cmd) => cmd.run().await,
+            Subcommands::Inspect(cmd) => cmd.run().await,
+        }
+    }
+}
@@ -4,7 +4,7 @@ use anyhow::Result;
 use clap::Parser;
 use starknet::core::types::contract::{legacy::LegacyContractClass, CompiledClass, SierraClass};
 
-use crate::path::ExpandedPathbufParser;
+use crate::{casm::compiled_class_hash, path::ExpandedPathbufParser};
 
 #[derive(Debug, Parser)]
 pub struct ClassHash {
@@ -24,10 +24,10 @@ impl ClassHash {
             serde_json::from_reader::<_, SierraClass>(std::fs::File::open(&self.file)?)
         {
             class.class_hash()?
-        } else if let Ok(class) =
-            serde_json::from_reader::<_, CompiledClass>(std::fs::File::open(&self.file)?)
+        } else if serde_json::from_reader::<_, CompiledClass>(std::fs::File::open(&self.file)?)
+            .is_ok()
         {
-            class.class_hash()?
+            compiled_class_hash(&std::fs::read_to_string(&self.file)?)?
         } else if let Ok(class) =
             serde_json::from_reader::<_, LegacyContractClass>(std::fs::File::open(self.file)?)
         {
@@ -5,12 +5,14 @@ use starknet::{
     providers::Provider,
 };
 
-use crate::{verbosity::VerbosityArgs, ProviderArgs};
+use crate::{provider::NetworksArgs, verbosity::VerbosityArgs, ProviderArgs};
 
 #[derive(Debug, Parser)]
 pub struct ClassHashAt {
     #[clap(flatten)]
     provider: ProviderArgs,
+    #[clap(flatten)]
+    networks: NetworksArgs,
     #[clap(help = "Contract address")]
     address: String,
     #[clap(flatten)]
@@ -21,9 +23,22 @@ impl ClassHashAt {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
 
-        let provider = self.provider.into_provider()?;
         let address = FieldElement::from_hex_be(&self.address)?;
 
+        if !self.networks.is_empty() {
+            return self
+                .networks
+                .fan_out(|provider| async move {
+                    let class_hash = provider
+                        .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+                        .await?;
+                    Ok(format!("{:#064x}", class_hash))
+                })
+                .await;
+        }
+
+        let provider = self.provider.into_provider()?;
+
         // TODO: allow custom block
         let class_hash = provider
             .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
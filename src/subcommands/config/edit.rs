@@ -0,0 +1,91 @@
+use std::{io::Write, path::Path, process::Command};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::profile::Profiles;
+
+#[derive(Debug, Parser)]
+pub struct Edit {}
+
+impl Edit {
+    /// A copy of the profiles file is edited, so that the actual file is only replaced with a
+    /// valid one. Comments and formatting are kept as written.
+    pub fn run(self) -> Result<()> {
+        let path = Profiles::get_profiles_path()?;
+        let original = if path.exists() {
+            std::fs::read_to_string(&path)?
+        } else {
+            String::new()
+        };
+
+        let mut file = tempfile::Builder::new()
+            .prefix("profiles-")
+            .suffix(".toml")
+            .tempfile()?;
+        file.write_all(original.as_bytes())?;
+        file.flush()?;
+
+        let edited = loop {
+            open_editor(file.path())?;
+
+            let edited = std::fs::read_to_string(file.path())?;
+            match Profiles::from_toml(&edited) {
+                Ok(_) => break edited,
+                Err(err) => {
+                    eprintln!("{}", format!("Invalid profiles: {}", err).bright_magenta());
+                    eprint!("Edit again? [Y/n] ");
+
+                    let mut answer = String::new();
+                    std::io::stdin().read_line(&mut answer)?;
+                    if answer.trim().eq_ignore_ascii_case("n") {
+                        anyhow::bail!("profiles left unchanged");
+                    }
+                }
+            }
+        };
+
+        if edited == original {
+            eprintln!("No changes made");
+            return Ok(());
+        }
+
+        if let Some(folder) = path.parent() {
+            std::fs::create_dir_all(folder)?;
+        }
+        std::fs::write(&path, edited)?;
+        eprintln!("Profiles saved to {}", path.display());
+
+        Ok(())
+    }
+}
+
+/// Uses `VISUAL` or `EDITOR`, which may include arguments like `code --wait`.
+fn open_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| {
+            if cfg!(windows) {
+                "notepad".to_owned()
+            } else {
+                "vi".to_owned()
+            }
+        });
+
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("empty editor command"))?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|err| anyhow::anyhow!("unable to run editor {}: {}", program, err))?;
+    if !status.success() {
+        anyhow::bail!("editor exited with {}", status);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::profile::Profiles;
+
+use super::{format_value, get_value, parse_key};
+
+#[derive(Debug, Parser)]
+pub struct Get {
+    #[clap(help = "Dotted key of the setting, e.g. `networks.sepolia.provider`")]
+    key: String,
+}
+
+impl Get {
+    pub fn run(self) -> Result<()> {
+        let table = Profiles::load()?.default_profile_table()?;
+
+        let value = get_value(&table, &parse_key(&self.key)?)
+            .ok_or_else(|| anyhow::anyhow!("key not set: {}", self.key))?;
+        match value {
+            toml::Value::Table(table) => print!("{}", toml::to_string_pretty(table)?),
+            value => println!("{}", format_value(value)),
+        }
+
+        Ok(())
+    }
+}
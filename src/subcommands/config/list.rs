@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::profile::Profiles;
+
+use super::format_value;
+
+#[derive(Debug, Parser)]
+pub struct List {}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        let table = Profiles::load()?.default_profile_table()?;
+
+        let mut settings = vec![];
+        flatten("", &table, &mut settings);
+        if settings.is_empty() {
+            eprintln!("No settings found in the default profile");
+        }
+
+        for (key, value) in settings.iter() {
+            println!("{} = {}", key, value.bright_yellow());
+        }
+
+        Ok(())
+    }
+}
+
+fn flatten(prefix: &str, table: &toml::Table, settings: &mut Vec<(String, String)>) {
+    for (key, value) in table.iter() {
+        let key = if prefix.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+
+        match value {
+            toml::Value::Table(table) => flatten(&key, table, settings),
+            value => settings.push((key, format_value(value))),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod get;
+use get::Get;
+
+mod set;
+use set::Set;
+
+mod list;
+use list::List;
+
+mod edit;
+use edit::Edit;
+
+#[derive(Debug, Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Print a setting of the default profile")]
+    Get(Get),
+    #[clap(about = "Change or remove a setting of the default profile")]
+    Set(Set),
+    #[clap(about = "List all settings of the default profile")]
+    List(List),
+    #[clap(about = "Open the profiles file in an editor, validating changes before saving")]
+    Edit(Edit),
+}
+
+impl Config {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Get(cmd) => cmd.run(),
+            Subcommands::Set(cmd) => cmd.run(),
+            Subcommands::List(cmd) => cmd.run(),
+            Subcommands::Edit(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// Splits a dotted key like `networks.sepolia.chain_id` into its parts.
+fn parse_key(key: &str) -> Result<Vec<&str>> {
+    let parts = key.split('.').collect::<Vec<_>>();
+    if parts.iter().any(|part| part.is_empty()) {
+        anyhow::bail!("invalid key: {}", key);
+    }
+    Ok(parts)
+}
+
+fn get_value<'a>(table: &'a toml::Table, key: &[&str]) -> Option<&'a toml::Value> {
+    let (last, parents) = key.split_last()?;
+
+    let mut table = table;
+    for part in parents.iter() {
+        table = table.get(*part)?.as_table()?;
+    }
+    table.get(*last)
+}
+
+/// Sets a value, creating missing parent tables along the way.
+fn set_value(table: &mut toml::Table, key: &[&str], value: toml::Value) -> Result<()> {
+    let (last, parents) = key
+        .split_last()
+        .ok_or_else(|| anyhow::anyhow!("empty key"))?;
+
+    let mut table = table;
+    for part in parents.iter() {
+        table = table
+            .entry(part.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("{} is not a table", part))?;
+    }
+    table.insert(last.to_string(), value);
+
+    Ok(())
+}
+
+fn remove_value(table: &mut toml::Table, key: &[&str]) -> Result<()> {
+    let (last, parents) = key
+        .split_last()
+        .ok_or_else(|| anyhow::anyhow!("empty key"))?;
+
+    let mut table = table;
+    for part in parents.iter() {
+        table = match table.get_mut(*part).and_then(|value| value.as_table_mut()) {
+            Some(table) => table,
+            None => anyhow::bail!("key not set: {}", key.join(".")),
+        };
+    }
+    if table.remove(*last).is_none() {
+        anyhow::bail!("key not set: {}", key.join("."));
+    }
+
+    Ok(())
+}
+
+/// Strings are printed without quotes so that values can be used in scripts as is.
+fn format_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(value) => value.to_owned(),
+        value => value.to_string(),
+    }
+}
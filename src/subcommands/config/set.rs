@@ -0,0 +1,66 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::profile::Profiles;
+
+use super::{format_value, parse_key, remove_value, set_value};
+
+#[derive(Debug, Parser)]
+pub struct Set {
+    #[clap(long, conflicts_with = "value", help = "Remove the setting instead")]
+    unset: bool,
+    #[clap(help = "Dotted key of the setting, e.g. `networks.sepolia.provider`")]
+    key: String,
+    #[clap(
+        required_unless_present = "unset",
+        help = "New value. `true` and `false` are booleans, and values starting with `{` or `[` \
+                are parsed as TOML inline tables or arrays. Anything else is a string"
+    )]
+    value: Option<String>,
+}
+
+impl Set {
+    pub fn run(self) -> Result<()> {
+        let mut profiles = Profiles::load()?;
+        let mut table = profiles.default_profile_table()?;
+        let key = parse_key(&self.key)?;
+
+        let value = match &self.value {
+            Some(value) => {
+                let value = parse_value(value)?;
+                set_value(&mut table, &key, value.clone())?;
+                Some(value)
+            }
+            None => {
+                remove_value(&mut table, &key)?;
+                None
+            }
+        };
+
+        // Deserializing the whole profile rejects unknown keys and bad values before saving
+        profiles.set_default_profile_table(table)?;
+        profiles.save()?;
+
+        match value {
+            Some(value) => eprintln!("{} set to {}", self.key, format_value(&value)),
+            None => eprintln!("{} removed", self.key),
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_value(value: &str) -> Result<toml::Value> {
+    Ok(match value {
+        "true" => toml::Value::Boolean(true),
+        "false" => toml::Value::Boolean(false),
+        _ if value.starts_with('{') || value.starts_with('[') => {
+            let mut parsed: toml::Table = toml::from_str(&format!("value = {}", value))
+                .map_err(|err| anyhow::anyhow!("invalid TOML value: {}", err))?;
+            parsed
+                .remove("value")
+                .ok_or_else(|| anyhow::anyhow!("invalid TOML value"))?
+        }
+        _ => toml::Value::String(value.to_owned()),
+    })
+}
@@ -1,25 +1,50 @@
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use colored_json::{ColorMode, Output};
 use starknet::{
-    accounts::Account,
+    accounts::{Account, ConnectedAccount},
     core::types::{
         contract::{legacy::LegacyContractClass, CompiledClass, SierraClass},
-        BlockId, BlockTag, FieldElement, StarknetError,
+        BroadcastedDeclareTransaction, BroadcastedTransaction, FieldElement, FlattenedSierraClass,
+        StarknetError,
     },
     macros::felt,
     providers::{Provider, ProviderError},
 };
+use url::Url;
 
 use crate::{
-    account::AccountArgs,
-    casm::{CasmArgs, CasmHashSource},
-    fee::{FeeArgs, FeeSetting},
+    abi_registry::{AbiRegistry, PublishedAbi},
+    account::{impersonated_account, load_network_account, AccountArgs},
+    address_book::AddressBookResolver,
+    artifact_store::ArtifactStore,
+    casm::{check_casm_hash_for_network, network_compiler_version, CasmArgs, CasmHashSource},
+    decode::FeltDecoder,
+    default_block::default_block_id,
+    devnet::impersonate_account,
+    error::decode_account_error,
+    explorer::{ExplorerLink, ExplorerLinkArgs},
+    fee::{check_max_fee_ceiling, print_fee_comparison, FeeArgs, FeeSetting},
+    hook::WatchHookArgs,
+    messages::message,
+    network::NetworkSource,
+    nonce::NoncePreview,
     path::ExpandedPathbufParser,
-    utils::watch_tx,
+    progress::ProgressArgs,
+    select::SelectArgs,
+    simulation_compare::compare_simulations,
+    simulation_summary::print_account_summary,
+    transaction_file::{
+        ExportedDeclare, ExportedDeclareLegacy, ExportedTransaction, TransactionFile,
+    },
+    utils::{print_receipt, send_with_retry},
     verbosity::VerbosityArgs,
     ProviderArgs,
 };
@@ -30,16 +55,80 @@ pub struct Declare {
     provider: ProviderArgs,
     #[clap(flatten)]
     account: AccountArgs,
+    #[clap(
+        long,
+        conflicts_with = "account",
+        help = "Send the transaction as this address on a forked devnet, without its private key"
+    )]
+    impersonate: Option<String>,
     #[clap(flatten)]
     casm: CasmArgs,
+    #[clap(
+        long,
+        help = "Skip checking the CASM hash against the compiler version used by the network"
+    )]
+    no_casm_check: bool,
     #[clap(flatten)]
     fee: FeeArgs,
+    #[clap(
+        long,
+        requires = "estimate_only",
+        conflicts_with = "json",
+        help = "Compare the fee of declaring as a v2 (ETH) and a v3 (STRK) transaction. The v3 \
+                fee is approximated from the v2 gas usage and the current STRK gas price"
+    )]
+    both_tokens: bool,
     #[clap(long, help = "Simulate the transaction only")]
     simulate: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Skip account validation when simulating the transaction"
+    )]
+    skip_validate: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Skip charging the fee when simulating the transaction"
+    )]
+    skip_fee_charge: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Run the same simulation against another JSON-RPC endpoint and show the differences"
+    )]
+    compare_provider: Option<Url>,
     #[clap(long, help = "Provide transaction nonce manually")]
     nonce: Option<FieldElement>,
+    #[clap(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["rpc", "network", "impersonate", "nonce", "simulate", "export", "both_tokens"],
+        help = "Comma-separated list of networks to declare the class on, one after another. Each \
+                network uses the account set for it in the profile, or --account otherwise"
+    )]
+    networks: Vec<String>,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        conflicts_with_all = ["simulate", "watch"],
+        help = "Write the unsigned transaction to a file for signing with `starkli sign-tx` instead of sending it"
+    )]
+    export: Option<PathBuf>,
+    #[clap(
+        long,
+        requires = "export",
+        help = "Also show the exported transaction as a QR code on the terminal"
+    )]
+    qr: bool,
     #[clap(long, short, help = "Wait for the transaction to confirm")]
     watch: bool,
+    #[clap(
+        long,
+        requires = "watch",
+        help = "Print the transaction receipt once the transaction is confirmed"
+    )]
+    print_receipt: bool,
     #[clap(
         long,
         env = "STARKNET_POLL_INTERVAL",
@@ -47,11 +136,42 @@ pub struct Declare {
         help = "Transaction result poll interval in milliseconds"
     )]
     poll_interval: u64,
+    #[clap(
+        long,
+        env = "STARKLI_ABI_REGISTRY",
+        conflicts_with = "networks",
+        help = "Publish the class ABI with declaration metadata once declared, either with a PUT \
+                to `<URL>/<CLASS_HASH>` for HTTP(S) URLs, or as `<CLASS_HASH>.json` in a directory"
+    )]
+    publish_abi: Option<String>,
+    #[clap(
+        long,
+        help = "Cross-check the class hash with a second local computation before sending, and \
+                with the hash the node computes for the declaration"
+    )]
+    verify_class_hash: bool,
+    #[clap(
+        long,
+        value_name = "CLASS_HASH",
+        conflicts_with = "file",
+        help = "Declare the artifact added with `starkli store add` under this class hash, \
+                instead of a file"
+    )]
+    from_store: Option<String>,
     #[clap(
         value_parser = ExpandedPathbufParser,
+        required_unless_present = "from_store",
         help = "Path to contract artifact file"
     )]
-    file: PathBuf,
+    file: Option<PathBuf>,
+    #[clap(flatten)]
+    explorer: ExplorerLinkArgs,
+    #[clap(flatten)]
+    hooks: WatchHookArgs,
+    #[clap(flatten)]
+    progress: ProgressArgs,
+    #[clap(flatten)]
+    select: SelectArgs,
     #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
@@ -59,34 +179,52 @@ pub struct Declare {
 impl Declare {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
+        let progress = self.progress.reporter();
 
-        let fee_setting = self.fee.into_setting()?;
+        let force_fee = self.fee.is_forced();
+        let fee_setting = self.fee.clone().into_setting()?;
         if self.simulate && fee_setting.is_estimate_only() {
             anyhow::bail!("--simulate cannot be used with --estimate-only");
         }
+        if !self.select.is_empty() && !self.simulate {
+            anyhow::bail!("--select can only be used with --simulate");
+        }
+
+        let file = self.artifact_file()?;
+
+        if !self.networks.is_empty() {
+            return self.run_networks(&file, fee_setting, force_fee).await;
+        }
 
         let provider = Arc::new(self.provider.into_provider()?);
 
-        let account = self.account.into_account(provider.clone()).await?;
+        let account = match self.impersonate {
+            Some(address) => {
+                let address = FeltDecoder::new(AddressBookResolver::new(provider.clone()))
+                    .decode_single_with_addr_fallback(&address)
+                    .await?;
+                impersonate_account(&provider, address).await?;
+                impersonated_account(provider.clone(), address).await?
+            }
+            None => self.account.into_account(provider.clone()).await?,
+        };
 
-        // Workaround for issue:
-        //   https://github.com/eqlabs/pathfinder/issues/1208
-        let (fee_multiplier_num, fee_multiplier_denom): (FieldElement, FieldElement) =
-            if provider.is_rpc() {
-                (felt!("5"), felt!("2"))
-            } else {
-                (felt!("3"), felt!("2"))
-            };
+        if self.simulate || fee_setting.is_estimate_only() || self.export.is_some() {
+            NoncePreview::fetch(&provider, account.address(), self.nonce)
+                .await?
+                .print();
+        }
 
         // Working around a deserialization bug in `starknet-rs`:
         //   https://github.com/xJonathanLEI/starknet-rs/issues/392
 
         #[allow(clippy::redundant_pattern_matching)]
-        let (class_hash, declaration_tx_hash) = if let Ok(class) =
-            serde_json::from_reader::<_, SierraClass>(std::fs::File::open(&self.file)?)
+        let (class_hash, declaration_tx_hash, compiled_class_hash, abi) = if let Ok(class) =
+            serde_json::from_reader::<_, SierraClass>(std::fs::File::open(&file)?)
         {
             // Declaring Cairo 1 class
             let class_hash = class.class_hash()?;
+            progress.class_parsed(class_hash);
 
             // TODO: add option to skip checking
             if Self::check_already_declared(&provider, class_hash).await? {
@@ -131,6 +269,25 @@ impl Declare {
 
             let casm_class_hash = casm_source.get_casm_hash(&class)?;
 
+            // No need to check when we already compiled with the network's own version
+            let compiled_by_network_version = match &casm_source {
+                CasmHashSource::BuiltInCompiler(compiler) => provider
+                    .get_network()
+                    .await?
+                    .map(|network| network_compiler_version(network) == compiler.version())
+                    .unwrap_or(true),
+                _ => false,
+            };
+            if !self.no_casm_check && !compiled_by_network_version {
+                check_casm_hash_for_network(&provider, &class, casm_class_hash).await?;
+            }
+            progress.event(
+                "compile",
+                serde_json::json!({
+                    "compiled_class_hash": format!("{:#064x}", casm_class_hash),
+                }),
+            );
+
             if !fee_setting.is_estimate_only() {
                 eprintln!(
                     "CASM class hash: {}",
@@ -139,55 +296,126 @@ impl Declare {
             }
 
             // TODO: make buffer configurable
-            let declaration = account.declare(Arc::new(class.flatten()?), casm_class_hash);
+            let flattened_class = Arc::new(class.flatten()?);
+            if self.verify_class_hash {
+                verify_sierra_class_hash(&flattened_class, class_hash)?;
+            }
+            let declaration = account.declare(flattened_class.clone(), casm_class_hash);
 
             let max_fee = match fee_setting {
                 FeeSetting::Manual(fee) => fee,
-                FeeSetting::EstimateOnly | FeeSetting::None => {
-                    let estimated_fee = declaration.estimate_fee().await?.overall_fee;
-
+                FeeSetting::EstimateOnly { .. } | FeeSetting::None => {
+                    let fee_estimate = declaration
+                        .estimate_fee()
+                        .await
+                        .map_err(decode_account_error)?;
+                    let estimated_fee = fee_estimate.overall_fee;
+                    progress.fee_estimated(estimated_fee);
+
+                    if self.both_tokens {
+                        print_fee_comparison(&provider, &fee_estimate).await?;
+                        return Ok(());
+                    }
                     if fee_setting.is_estimate_only() {
-                        println!(
-                            "{} ETH",
-                            format!("{}", estimated_fee.to_big_decimal(18)).bright_yellow(),
-                        );
+                        fee_setting.print_estimate(&fee_estimate)?;
                         return Ok(());
                     }
 
                     // TODO: make buffer configurable
-                    (estimated_fee * fee_multiplier_num).floor_div(fee_multiplier_denom)
+                    (estimated_fee * felt!("3")).floor_div(felt!("2"))
                 }
             };
 
+            check_max_fee_ceiling(&provider, max_fee, force_fee)?;
+
             let declaration = match self.nonce {
                 Some(nonce) => declaration.nonce(nonce),
                 None => declaration,
             };
             let declaration = declaration.max_fee(max_fee);
 
+            if let Some(export) = &self.export {
+                let nonce = match self.nonce {
+                    Some(nonce) => nonce,
+                    None => account.get_nonce().await?,
+                };
+
+                TransactionFile::new(
+                    account.chain_id(),
+                    ExportedTransaction::Declare(ExportedDeclare {
+                        sender_address: account.address(),
+                        max_fee,
+                        nonce,
+                        class_hash,
+                        compiled_class_hash: casm_class_hash,
+                        contract_class: (*flattened_class).clone(),
+                    }),
+                )
+                .export(export, self.qr)?;
+                return Ok(());
+            }
+
+            if let Some(compare_provider) = self.compare_provider {
+                let nonce = match self.nonce {
+                    Some(nonce) => nonce,
+                    None => account.get_nonce().await?,
+                };
+                let request = declaration
+                    .nonce(nonce)
+                    .prepared()?
+                    .get_declare_request(true)
+                    .await?;
+
+                return compare_simulations(
+                    &provider,
+                    compare_provider,
+                    BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V2(request)),
+                    self.skip_validate,
+                    self.skip_fee_charge,
+                )
+                .await;
+            }
+
             if self.simulate {
-                let simulation = declaration.simulate(false, false).await?;
+                let simulation = declaration
+                    .simulate(self.skip_validate, self.skip_fee_charge)
+                    .await?;
                 let simulation_json = serde_json::to_value(simulation)?;
+                print_account_summary(&provider, account.address(), &simulation_json).await?;
 
-                let simulation_json = colored_json::to_colored_json(
-                    &simulation_json,
-                    ColorMode::Auto(Output::StdOut),
-                )?;
-                println!("{simulation_json}");
-                return Ok(());
+                return self.select.print(&simulation_json);
+            }
+
+            let result =
+                send_with_retry(&provider, account.address(), self.nonce.is_some(), || {
+                    declaration.send()
+                })
+                .await?;
+            if self.verify_class_hash {
+                check_node_class_hash(class_hash, result.class_hash)?;
             }
 
-            (class_hash, declaration.send().await?.transaction_hash)
+            (
+                class_hash,
+                result.transaction_hash,
+                Some(casm_class_hash),
+                serde_json::from_str(&flattened_class.abi)?,
+            )
         } else if let Ok(_) =
-            serde_json::from_reader::<_, CompiledClass>(std::fs::File::open(&self.file)?)
+            serde_json::from_reader::<_, CompiledClass>(std::fs::File::open(&file)?)
         {
             // TODO: add more helpful instructions to fix this
             anyhow::bail!("unexpected CASM class");
         } else if let Ok(class) =
-            serde_json::from_reader::<_, LegacyContractClass>(std::fs::File::open(self.file)?)
+            serde_json::from_reader::<_, LegacyContractClass>(std::fs::File::open(&file)?)
         {
             // Declaring Cairo 0 class
+            if self.both_tokens {
+                anyhow::bail!("--both-tokens is not available as Cairo 0 classes cannot be declared with v3 transactions");
+            }
+
             let class_hash = class.class_hash()?;
+            progress.class_parsed(class_hash);
 
             // TODO: add option to skip checking
             if Self::check_already_declared(&provider, class_hash).await? {
@@ -202,65 +430,172 @@ impl Declare {
             }
 
             // TODO: make buffer configurable
-            let declaration = account.declare_legacy(Arc::new(class));
+            let class = Arc::new(class);
+            let declaration = account.declare_legacy(class.clone());
 
             let max_fee = match fee_setting {
                 FeeSetting::Manual(fee) => fee,
-                FeeSetting::EstimateOnly | FeeSetting::None => {
-                    let estimated_fee = declaration.estimate_fee().await?.overall_fee;
+                FeeSetting::EstimateOnly { .. } | FeeSetting::None => {
+                    let fee_estimate = declaration
+                        .estimate_fee()
+                        .await
+                        .map_err(decode_account_error)?;
+                    let estimated_fee = fee_estimate.overall_fee;
+                    progress.fee_estimated(estimated_fee);
 
                     if fee_setting.is_estimate_only() {
-                        println!(
-                            "{} ETH",
-                            format!("{}", estimated_fee.to_big_decimal(18)).bright_yellow(),
-                        );
+                        fee_setting.print_estimate(&fee_estimate)?;
                         return Ok(());
                     }
 
                     // TODO: make buffer configurable
-                    (estimated_fee * fee_multiplier_num).floor_div(fee_multiplier_denom)
+                    (estimated_fee * felt!("3")).floor_div(felt!("2"))
                 }
             };
 
+            check_max_fee_ceiling(&provider, max_fee, force_fee)?;
+
             let declaration = match self.nonce {
                 Some(nonce) => declaration.nonce(nonce),
                 None => declaration,
             };
             let declaration = declaration.max_fee(max_fee);
 
+            if let Some(export) = &self.export {
+                let nonce = match self.nonce {
+                    Some(nonce) => nonce,
+                    None => account.get_nonce().await?,
+                };
+
+                TransactionFile::new(
+                    account.chain_id(),
+                    ExportedTransaction::DeclareLegacy(ExportedDeclareLegacy {
+                        sender_address: account.address(),
+                        max_fee,
+                        nonce,
+                        class_hash,
+                        contract_class: class.compress()?,
+                    }),
+                )
+                .export(export, self.qr)?;
+                return Ok(());
+            }
+
+            if let Some(compare_provider) = self.compare_provider {
+                let nonce = match self.nonce {
+                    Some(nonce) => nonce,
+                    None => account.get_nonce().await?,
+                };
+                let request = declaration
+                    .nonce(nonce)
+                    .prepared()?
+                    .get_declare_request(true)
+                    .await?;
+
+                return compare_simulations(
+                    &provider,
+                    compare_provider,
+                    BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V1(request)),
+                    self.skip_validate,
+                    self.skip_fee_charge,
+                )
+                .await;
+            }
+
             if self.simulate {
-                let simulation = declaration.simulate(false, false).await?;
+                let simulation = declaration
+                    .simulate(self.skip_validate, self.skip_fee_charge)
+                    .await?;
                 let simulation_json = serde_json::to_value(simulation)?;
+                print_account_summary(&provider, account.address(), &simulation_json).await?;
 
-                let simulation_json = colored_json::to_colored_json(
-                    &simulation_json,
-                    ColorMode::Auto(Output::StdOut),
-                )?;
-                println!("{simulation_json}");
-                return Ok(());
+                return self.select.print(&simulation_json);
+            }
+
+            let result =
+                send_with_retry(&provider, account.address(), self.nonce.is_some(), || {
+                    declaration.send()
+                })
+                .await?;
+            if self.verify_class_hash {
+                check_node_class_hash(class_hash, result.class_hash)?;
             }
 
-            (class_hash, declaration.send().await?.transaction_hash)
+            (
+                class_hash,
+                result.transaction_hash,
+                None,
+                serde_json::to_value(&class.abi)?,
+            )
         } else {
             anyhow::bail!("failed to parse contract artifact");
         };
 
+        progress.transaction_sent(declaration_tx_hash);
         eprintln!(
-            "Contract declaration transaction: {}",
-            format!("{:#064x}", declaration_tx_hash).bright_yellow()
+            "{}",
+            message(
+                "declare.sent",
+                &[("hash", &format!("{:#064x}", declaration_tx_hash))]
+            )
         );
+        self.explorer
+            .print_link(&provider, ExplorerLink::Transaction(declaration_tx_hash))
+            .await;
 
-        if self.watch {
+        if self.watch || !self.hooks.is_empty() {
             eprintln!(
-                "Waiting for transaction {} to confirm...",
-                format!("{:#064x}", declaration_tx_hash).bright_yellow(),
+                "{}",
+                message(
+                    "tx.waiting",
+                    &[("hash", &format!("{:#064x}", declaration_tx_hash))]
+                )
             );
-            watch_tx(
-                &provider,
-                declaration_tx_hash,
-                Duration::from_millis(self.poll_interval),
-            )
-            .await?;
+            self.hooks
+                .watch_tx(
+                    &provider,
+                    declaration_tx_hash,
+                    Duration::from_millis(self.poll_interval),
+                )
+                .await?;
+            progress.transaction_confirmed(declaration_tx_hash);
+
+            if self.print_receipt {
+                print_receipt(&provider, declaration_tx_hash).await?;
+            }
+        }
+
+        // The declaration already went through, so failing to publish is only a warning
+        if let Some(registry) = &self.publish_abi {
+            let published = PublishedAbi {
+                class_hash,
+                compiled_class_hash,
+                chain_id: account.chain_id(),
+                transaction_hash: declaration_tx_hash,
+                sender_address: account.address(),
+                // Stored artifacts are named after their class hash, which is no contract name
+                contract_name: match &self.from_store {
+                    Some(_) => None,
+                    None => file
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned()),
+                },
+                declared_at: chrono::Utc::now().to_rfc3339(),
+                starkli_version: env!("CARGO_PKG_VERSION").to_owned(),
+                abi,
+            };
+
+            match AbiRegistry::parse(registry).publish(&published).await {
+                Ok(location) => {
+                    eprintln!("Class ABI published to {}", location.bright_yellow());
+                }
+                Err(err) => {
+                    eprintln!(
+                        "{}",
+                        format!("WARNING: unable to publish class ABI: {}", err).bright_magenta()
+                    );
+                }
+            }
         }
 
         eprintln!("Class hash declared:");
@@ -268,17 +603,215 @@ impl Declare {
         // Only the class hash goes to stdout so this can be easily scripted
         println!("{}", format!("{:#064x}", class_hash).bright_yellow());
 
+        self.explorer
+            .print_link(&provider, ExplorerLink::Class(class_hash))
+            .await;
+
         Ok(())
     }
 
+    /// Declares a Sierra class on each network in turn. The class is only compiled once per
+    /// compiler version, so networks sharing a version reuse the CASM hash. Failures are reported
+    /// per network instead of aborting the remaining ones.
+    async fn run_networks(
+        self,
+        file: &Path,
+        fee_setting: FeeSetting,
+        force_fee: bool,
+    ) -> Result<()> {
+        if fee_setting.is_json() {
+            anyhow::bail!("--json cannot be used with --networks");
+        }
+
+        let class = serde_json::from_reader::<_, SierraClass>(std::fs::File::open(file)?).map_err(
+            |_| anyhow::anyhow!("--networks is only supported for Cairo 1 (Sierra) classes"),
+        )?;
+        let class_hash = class.class_hash()?;
+        let flattened_class = Arc::new(class.clone().flatten()?);
+        if self.verify_class_hash {
+            verify_sierra_class_hash(&flattened_class, class_hash)?;
+        }
+        eprintln!(
+            "Declaring Cairo 1 class {} on {} network(s)",
+            format!("{:#064x}", class_hash).bright_yellow(),
+            self.networks.len()
+        );
+
+        let mut casm_hashes = HashMap::new();
+        let mut results = vec![];
+        for network in self.networks.iter() {
+            eprintln!("Network {}:", network.bright_yellow());
+
+            let result = self
+                .declare_on_network(
+                    network,
+                    &class,
+                    flattened_class.clone(),
+                    &fee_setting,
+                    force_fee,
+                    &mut casm_hashes,
+                )
+                .await;
+            if let Err(err) = &result {
+                eprintln!(
+                    "{}",
+                    format!("Error on {}: {}", network, err).bright_magenta()
+                );
+            }
+
+            results.push((network, result));
+        }
+
+        let width = self
+            .networks
+            .iter()
+            .map(|network| network.len())
+            .max()
+            .unwrap_or_default();
+
+        eprintln!();
+        let mut failed = 0;
+        for (network, result) in results.into_iter() {
+            match result {
+                Ok(outcome) => println!("{:width$}  {}", network, outcome),
+                Err(err) => {
+                    failed += 1;
+                    println!(
+                        "{:width$}  {}",
+                        network,
+                        format!("error: {}", err).bright_magenta()
+                    );
+                }
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("declaration failed on {} network(s)", failed);
+        }
+
+        Ok(())
+    }
+
+    async fn declare_on_network(
+        &self,
+        network: &str,
+        class: &SierraClass,
+        flattened_class: Arc<FlattenedSierraClass>,
+        fee_setting: &FeeSetting,
+        force_fee: bool,
+        casm_hashes: &mut HashMap<String, FieldElement>,
+    ) -> Result<String> {
+        let class_hash = flattened_class.class_hash();
+        let provider = Arc::new(ProviderArgs::resolve_network(network)?);
+
+        match provider.get_class(default_block_id(), class_hash).await {
+            Ok(_) => return Ok("already declared".to_owned()),
+            Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let account = match load_network_account(network)? {
+            Some(account) => self.account.with_account(account),
+            None => self.account.clone(),
+        }
+        .into_account(provider.clone())
+        .await?;
+
+        let casm_source = self.casm.clone().into_casm_hash_source(&provider).await?;
+
+        // Sources other than the built-in compiler give the same hash regardless of the network
+        let compiler_key = match &casm_source {
+            CasmHashSource::BuiltInCompiler(compiler) => compiler.version().to_string(),
+            _ => String::new(),
+        };
+        let casm_class_hash = match casm_hashes.get(&compiler_key) {
+            Some(casm_class_hash) => *casm_class_hash,
+            None => {
+                let casm_class_hash = casm_source.get_casm_hash(class)?;
+                casm_hashes.insert(compiler_key, casm_class_hash);
+                casm_class_hash
+            }
+        };
+        if !self.no_casm_check {
+            check_casm_hash_for_network(&provider, class, casm_class_hash).await?;
+        }
+        eprintln!(
+            "CASM class hash: {}",
+            format!("{:#064x}", casm_class_hash).bright_yellow()
+        );
+
+        let declaration = account.declare(flattened_class, casm_class_hash);
+
+        let max_fee = match fee_setting {
+            FeeSetting::Manual(fee) => *fee,
+            FeeSetting::EstimateOnly { .. } | FeeSetting::None => {
+                let fee_estimate = declaration
+                    .estimate_fee()
+                    .await
+                    .map_err(decode_account_error)?;
+
+                if let FeeSetting::EstimateOnly { amount_format, .. } = fee_setting {
+                    return Ok(format!(
+                        "estimated fee {}",
+                        amount_format.format(fee_estimate.overall_fee, "ETH")
+                    ));
+                }
+
+                (fee_estimate.overall_fee * felt!("3")).floor_div(felt!("2"))
+            }
+        };
+
+        check_max_fee_ceiling(&provider, max_fee, force_fee)?;
+
+        let declaration = declaration.max_fee(max_fee);
+        let result =
+            send_with_retry(&provider, account.address(), false, || declaration.send()).await?;
+        if self.verify_class_hash {
+            check_node_class_hash(class_hash, result.class_hash)?;
+        }
+        let declaration_tx_hash = result.transaction_hash;
+        eprintln!(
+            "{}",
+            message(
+                "declare.sent",
+                &[("hash", &format!("{:#064x}", declaration_tx_hash))]
+            )
+        );
+
+        if self.watch || !self.hooks.is_empty() {
+            eprintln!(
+                "{}",
+                message(
+                    "tx.waiting",
+                    &[("hash", &format!("{:#064x}", declaration_tx_hash))]
+                )
+            );
+            self.hooks
+                .watch_tx(
+                    &provider,
+                    declaration_tx_hash,
+                    Duration::from_millis(self.poll_interval),
+                )
+                .await?;
+        }
+
+        Ok(format!("declared in {:#064x}", declaration_tx_hash))
+    }
+
+    /// The artifact to declare, either given as a path or looked up in the artifact store.
+    fn artifact_file(&self) -> Result<PathBuf> {
+        match &self.from_store {
+            Some(class_hash) => ArtifactStore::open()?.get(FieldElement::from_hex_be(class_hash)?),
+            // `clap` makes sure this is present when `--from-store` is not used
+            None => Ok(self.file.clone().unwrap()),
+        }
+    }
+
     async fn check_already_declared<P>(provider: P, class_hash: FieldElement) -> Result<bool>
     where
         P: Provider,
     {
-        match provider
-            .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
-            .await
-        {
+        match provider.get_class(default_block_id(), class_hash).await {
             Ok(_) => {
                 eprintln!("Not declaring class as it's already declared. Class hash:");
                 println!("{}", format!("{:#064x}", class_hash).bright_yellow());
@@ -290,3 +823,56 @@ impl Declare {
         }
     }
 }
+
+/// Hashes the class again from its flattened form, both as built and after the JSON round trip it
+/// goes through on its way to the node, to catch hashing bugs before anything is sent.
+fn verify_sierra_class_hash(
+    flattened_class: &FlattenedSierraClass,
+    class_hash: FieldElement,
+) -> Result<()> {
+    let flattened_hash = flattened_class.class_hash();
+    let round_tripped_hash =
+        serde_json::from_value::<FlattenedSierraClass>(serde_json::to_value(flattened_class)?)?
+            .class_hash();
+
+    for (source, hash) in [
+        ("the flattened class", flattened_hash),
+        ("the serialized class", round_tripped_hash),
+    ] {
+        if hash != class_hash {
+            anyhow::bail!(
+                "class hash mismatch: {:#064x} was computed from the artifact but {:#064x} from {}",
+                class_hash,
+                hash,
+                source
+            );
+        }
+    }
+
+    eprintln!(
+        "Class hash verified locally: {}",
+        format!("{:#064x}", class_hash).bright_yellow()
+    );
+
+    Ok(())
+}
+
+/// Compares the class hash computed locally with the one the node computed when accepting the
+/// declaration.
+fn check_node_class_hash(local: FieldElement, node: FieldElement) -> Result<()> {
+    if local != node {
+        anyhow::bail!(
+            "class hash mismatch: computed {:#064x} locally but the node computed {:#064x}. The \
+            transaction has already been sent",
+            local,
+            node
+        );
+    }
+
+    eprintln!(
+        "Class hash confirmed by the node: {}",
+        format!("{:#064x}", node).bright_yellow()
+    );
+
+    Ok(())
+}
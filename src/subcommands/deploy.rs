@@ -1,31 +1,57 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use colored_json::{ColorMode, Output};
+use serde::Deserialize;
+use serde_json::Value;
 use starknet::{
-    contract::ContractFactory, core::types::FieldElement, macros::felt, signers::SigningKey,
+    accounts::{Account, Call, ConnectedAccount},
+    core::{
+        types::{
+            BlockId, BlockTag, BroadcastedTransaction, FeeEstimate, FieldElement,
+            SimulationFlagForEstimateFee, StarknetError,
+        },
+        utils::starknet_keccak,
+    },
+    macros::felt,
+    providers::{Provider, ProviderError},
+    signers::SigningKey,
 };
+use url::Url;
 
 use crate::{
+    abi::{constructor_inputs, AbiEncoder},
+    abi_cache::CachedAbi,
     account::AccountArgs,
     address_book::AddressBookResolver,
     decode::FeltDecoder,
-    fee::{FeeArgs, FeeSetting},
-    utils::watch_tx,
+    deployer::DeployerArgs,
+    deployments::{DeploymentRecord, DeploymentRegistry},
+    error::{decode_account_error, decode_provider_error},
+    explorer::{ExplorerLink, ExplorerLinkArgs},
+    fee::{check_max_fee_ceiling, FeeArgs, FeeSetting},
+    hook::WatchHookArgs,
+    messages::message,
+    nonce::NoncePreview,
+    path::ExpandedPathbufParser,
+    progress::ProgressArgs,
+    provider::ExtendedProvider,
+    script_state::{ScriptState, StepResult},
+    select::SelectArgs,
+    simulation_compare::compare_simulations,
+    simulation_summary::print_account_summary,
+    utils::{format_address, print_receipt, send_with_retry},
     verbosity::VerbosityArgs,
     ProviderArgs,
 };
 
-/// The default UDC address: 0x041a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf.
-const DEFAULT_UDC_ADDRESS: FieldElement = FieldElement::from_mont([
-    15144800532519055890,
-    15685625669053253235,
-    9333317513348225193,
-    121672436446604875,
-]);
-
 #[derive(Debug, Parser)]
 pub struct Deploy {
     #[clap(flatten)]
@@ -35,15 +61,47 @@ pub struct Deploy {
     #[clap(long, help = "Do not derive contract address from deployer address")]
     not_unique: bool,
     #[clap(flatten)]
+    deployer: DeployerArgs,
+    #[clap(flatten)]
     fee: FeeArgs,
     #[clap(long, help = "Simulate the transaction only")]
     simulate: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Skip account validation when simulating the transaction"
+    )]
+    skip_validate: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Skip charging the fee when simulating the transaction"
+    )]
+    skip_fee_charge: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Run the same simulation against another JSON-RPC endpoint and show the differences"
+    )]
+    compare_provider: Option<Url>,
     #[clap(long, help = "Use the given salt to compute contract deploy address")]
     salt: Option<String>,
+    #[clap(
+        long,
+        conflicts_with = "salt",
+        help = "Derive the salt from a human-readable label by hashing it with Starknet Keccak"
+    )]
+    salt_from_string: Option<String>,
     #[clap(long, help = "Provide transaction nonce manually")]
     nonce: Option<FieldElement>,
     #[clap(long, short, help = "Wait for the transaction to confirm")]
     watch: bool,
+    #[clap(
+        long,
+        requires = "watch",
+        help = "Print the transaction receipt once the transaction is confirmed"
+    )]
+    print_receipt: bool,
     #[clap(
         long,
         env = "STARKNET_POLL_INTERVAL",
@@ -51,56 +109,173 @@ pub struct Deploy {
         help = "Transaction result poll interval in milliseconds"
     )]
     poll_interval: u64,
-    #[clap(help = "Class hash")]
-    class_hash: String,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        conflicts_with_all = [
+            "class_hash",
+            "ctor_file",
+            "ctor_args",
+            "salt",
+            "salt_from_string",
+            "simulate"
+        ],
+        help = "Path to a TOML manifest listing contracts to deploy in one go"
+    )]
+    manifest: Option<PathBuf>,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        requires = "manifest",
+        help = "Save manifest deployment progress to a new file after every transaction, for \
+                resuming with `starkli script resume`"
+    )]
+    state_file: Option<PathBuf>,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        requires = "manifest",
+        conflicts_with = "state_file",
+        help = "Resume a manifest deployment from a saved state file, skipping contracts that \
+                were already sent. Progress is saved back to the same file"
+    )]
+    resume: Option<PathBuf>,
+    #[clap(required_unless_present = "manifest", help = "Class hash")]
+    class_hash: Option<String>,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        conflicts_with = "ctor_args",
+        help = "Path to a JSON file with typed constructor arguments matching the class ABI"
+    )]
+    ctor_file: Option<PathBuf>,
     #[clap(help = "Raw constructor arguments")]
     ctor_args: Vec<String>,
     #[clap(flatten)]
+    explorer: ExplorerLinkArgs,
+    #[clap(flatten)]
+    hooks: WatchHookArgs,
+    #[clap(flatten)]
+    progress: ProgressArgs,
+    #[clap(flatten)]
+    select: SelectArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
+/// Manifest for `--manifest`, listing contracts to deploy by label.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DeployManifest {
+    #[serde(default)]
+    contracts: Vec<ManifestContract>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestContract {
+    label: String,
+    class_hash: FieldElement,
+    /// Raw constructor arguments, same as on the command line.
+    #[serde(default)]
+    ctor_args: Vec<String>,
+    /// Typed constructor arguments matching the class ABI, same as with `--ctor-file`.
+    ctor: Option<toml::Value>,
+    /// Defaults to a salt derived from the label.
+    salt: Option<FieldElement>,
+    #[serde(default)]
+    not_unique: bool,
+}
+
+struct PlannedDeployment {
+    label: String,
+    class_hash: FieldElement,
+    salt: FieldElement,
+    call: Call,
+    address: FieldElement,
+    max_fee: FieldElement,
+}
+
 impl Deploy {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
 
+        if let Some(manifest) = self.manifest.clone() {
+            return self.run_manifest(&manifest).await;
+        }
+
+        let progress = self.progress.reporter();
+
+        let force_fee = self.fee.is_forced();
         let fee_setting = self.fee.into_setting()?;
         if self.simulate && fee_setting.is_estimate_only() {
             anyhow::bail!("--simulate cannot be used with --estimate-only");
         }
+        if !self.select.is_empty() && !self.simulate {
+            anyhow::bail!("--select can only be used with --simulate");
+        }
 
         let provider = Arc::new(self.provider.into_provider()?);
         let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
 
-        let class_hash = FieldElement::from_hex_be(&self.class_hash)?;
-        let mut ctor_args = vec![];
-        for element in self.ctor_args.iter() {
-            ctor_args.append(&mut felt_decoder.decode(element).await?);
-        }
+        let class_hash = FieldElement::from_hex_be(
+            self.class_hash
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("missing class hash"))?,
+        )?;
+        let ctor_args = match &self.ctor_file {
+            Some(ctor_file) => {
+                let ctor_file: Value =
+                    serde_json::from_reader(&mut std::fs::File::open(ctor_file)?)?;
+                encode_typed_ctor_args(&provider, &felt_decoder, class_hash, &ctor_file).await?
+            }
+            None if self.ctor_args.is_empty() && std::io::stdin().is_terminal() => {
+                prompt_ctor_args(&provider, &felt_decoder, class_hash).await?
+            }
+            None => {
+                let mut ctor_args = vec![];
+                for element in self.ctor_args.iter() {
+                    ctor_args.append(&mut felt_decoder.decode(element).await?);
+                }
+                ctor_args
+            }
+        };
 
         let salt = if let Some(s) = self.salt {
             FieldElement::from_hex_be(&s)?
+        } else if let Some(label) = &self.salt_from_string {
+            salt_from_label(label)
         } else {
             SigningKey::from_random().secret_scalar()
         };
 
         let account = self.account.into_account(provider.clone()).await?;
+        let sender = account.address();
 
-        // TODO: allow custom UDC
-        let factory = ContractFactory::new_with_udc(class_hash, account, DEFAULT_UDC_ADDRESS);
+        if self.simulate || fee_setting.is_estimate_only() {
+            NoncePreview::fetch(&provider, sender, self.nonce)
+                .await?
+                .print();
+        }
+
+        let deployer = self.deployer.into_deployer(&provider);
+        let (deployment_call, deployed_address) =
+            deployer.deployment_call(sender, class_hash, salt, !self.not_unique, &ctor_args);
 
-        let contract_deployment = factory.deploy(ctor_args, salt, !self.not_unique);
-        let deployed_address = contract_deployment.deployed_address();
+        let contract_deployment = account.execute(vec![deployment_call]);
 
         let max_fee = match fee_setting {
             FeeSetting::Manual(fee) => fee,
-            FeeSetting::EstimateOnly | FeeSetting::None => {
-                let estimated_fee = contract_deployment.estimate_fee().await?.overall_fee;
+            FeeSetting::EstimateOnly { .. } | FeeSetting::None => {
+                let fee_estimate = contract_deployment
+                    .estimate_fee()
+                    .await
+                    .map_err(decode_account_error)?;
+                let estimated_fee = fee_estimate.overall_fee;
+                progress.fee_estimated(estimated_fee);
 
                 if fee_setting.is_estimate_only() {
-                    eprintln!(
-                        "{} ETH",
-                        format!("{}", estimated_fee.to_big_decimal(18)).bright_yellow(),
-                    );
+                    fee_setting.print_estimate(&fee_estimate)?;
                     return Ok(());
                 }
 
@@ -109,14 +284,17 @@ impl Deploy {
             }
         };
 
+        check_max_fee_ceiling(&provider, max_fee, force_fee)?;
+
         eprintln!(
-            "Deploying class {} with salt {}...",
+            "Deploying class {} with salt {} via deployer {}...",
             format!("{:#064x}", class_hash).bright_yellow(),
-            format!("{:#064x}", salt).bright_yellow()
+            format!("{:#064x}", salt).bright_yellow(),
+            format_address(deployer.address()).bright_yellow()
         );
         eprintln!(
             "The contract will be deployed at address {}",
-            format!("{:#064x}", deployed_address).bright_yellow()
+            format_address(deployed_address).bright_yellow()
         );
 
         let contract_deployment = match self.nonce {
@@ -125,33 +303,74 @@ impl Deploy {
         };
         let contract_deployment = contract_deployment.max_fee(max_fee);
 
+        if let Some(compare_provider) = self.compare_provider {
+            let nonce = match self.nonce {
+                Some(nonce) => nonce,
+                None => account.get_nonce().await?,
+            };
+            let request = contract_deployment
+                .nonce(nonce)
+                .prepared()?
+                .get_invoke_request(true)
+                .await?;
+
+            return compare_simulations(
+                &provider,
+                compare_provider,
+                BroadcastedTransaction::Invoke(request),
+                self.skip_validate,
+                self.skip_fee_charge,
+            )
+            .await;
+        }
+
         if self.simulate {
-            let simulation = contract_deployment.simulate(false, false).await?;
+            let simulation = contract_deployment
+                .simulate(self.skip_validate, self.skip_fee_charge)
+                .await?;
             let simulation_json = serde_json::to_value(simulation)?;
+            print_account_summary(&provider, sender, &simulation_json).await?;
 
-            let simulation_json =
-                colored_json::to_colored_json(&simulation_json, ColorMode::Auto(Output::StdOut))?;
-            println!("{simulation_json}");
-            return Ok(());
+            return self.select.print(&simulation_json);
         }
 
-        let deployment_tx = contract_deployment.send().await?.transaction_hash;
+        let deployment_tx = send_with_retry(&provider, sender, self.nonce.is_some(), || {
+            contract_deployment.send()
+        })
+        .await?
+        .transaction_hash;
+        progress.transaction_sent(deployment_tx);
         eprintln!(
-            "Contract deployment transaction: {}",
-            format!("{:#064x}", deployment_tx).bright_yellow()
+            "{}",
+            message(
+                "deploy.sent",
+                &[("hash", &format!("{:#064x}", deployment_tx))]
+            )
         );
+        self.explorer
+            .print_link(&provider, ExplorerLink::Transaction(deployment_tx))
+            .await;
 
-        if self.watch {
+        if self.watch || !self.hooks.is_empty() {
             eprintln!(
-                "Waiting for transaction {} to confirm...",
-                format!("{:#064x}", deployment_tx).bright_yellow(),
+                "{}",
+                message(
+                    "tx.waiting",
+                    &[("hash", &format!("{:#064x}", deployment_tx))]
+                )
             );
-            watch_tx(
-                &provider,
-                deployment_tx,
-                Duration::from_millis(self.poll_interval),
-            )
-            .await?;
+            self.hooks
+                .watch_tx(
+                    &provider,
+                    deployment_tx,
+                    Duration::from_millis(self.poll_interval),
+                )
+                .await?;
+            progress.transaction_confirmed(deployment_tx);
+
+            if self.print_receipt {
+                print_receipt(&provider, deployment_tx).await?;
+            }
         }
 
         eprintln!("Contract deployed:");
@@ -159,6 +378,391 @@ impl Deploy {
         // Only the contract goes to stdout so this can be easily scripted
         println!("{}", format!("{:#064x}", deployed_address).bright_yellow());
 
+        self.explorer
+            .print_link(&provider, ExplorerLink::Contract(deployed_address))
+            .await;
+
         Ok(())
     }
+
+    /// Deploys all contracts listed in the manifest. Fees are estimated for all deployments before
+    /// anything is sent, in a single batched request, and transactions are then sent back to back
+    /// with consecutive nonces without waiting for confirmations in between.
+    async fn run_manifest(self, manifest_path: &Path) -> Result<()> {
+        let progress = self.progress.reporter();
+
+        let force_fee = self.fee.is_forced();
+        let fee_setting = self.fee.into_setting()?;
+
+        let manifest_content = std::fs::read_to_string(manifest_path)?;
+        let manifest: DeployManifest = toml::from_str(&manifest_content).map_err(|err| {
+            anyhow::anyhow!("invalid manifest {}: {}", manifest_path.display(), err)
+        })?;
+        if manifest.contracts.is_empty() {
+            anyhow::bail!("manifest {} has no contracts", manifest_path.display());
+        }
+        let mut labels = HashSet::new();
+        for contract in manifest.contracts.iter() {
+            if !labels.insert(contract.label.as_str()) {
+                anyhow::bail!("duplicate label in manifest: {}", contract.label);
+            }
+            if contract.ctor.is_some() && !contract.ctor_args.is_empty() {
+                anyhow::bail!(
+                    "{}: only one of `ctor` and `ctor_args` can be set",
+                    contract.label
+                );
+            }
+        }
+
+        // Progress is only saved when asked for, along with where it's saved to
+        let mut saved_state = match (&self.resume, &self.state_file) {
+            (Some(resume), _) => {
+                let state = ScriptState::resume(resume, manifest_content.as_bytes())?;
+
+                eprintln!(
+                    "Resuming with {} transaction(s) already sent",
+                    state.completed_count().to_string().bright_yellow()
+                );
+                Some((state, resume))
+            }
+            (None, Some(state_file)) => Some((
+                ScriptState::create(state_file, manifest_content.as_bytes())?,
+                state_file,
+            )),
+            (None, None) => None,
+        };
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+        let chain_id = provider.chain_id().await?;
+
+        let account = self.account.into_account(provider.clone()).await?;
+        let sender = account.address();
+        let deployer = self.deployer.into_deployer(&provider);
+
+        let mut planned = vec![];
+        let mut sent = vec![];
+        for contract in manifest.contracts.into_iter() {
+            // Sent in a previous run, but possibly not even pending yet
+            if let Some(step) = saved_state
+                .as_ref()
+                .and_then(|(state, _)| state.step(&contract.label))
+            {
+                let address = step.address.unwrap_or_default();
+                eprintln!(
+                    "Skipping {}: already sent in transaction {}",
+                    contract.label,
+                    format!("{:#064x}", step.transaction_hash).bright_yellow()
+                );
+                sent.push((contract.label, address, step.transaction_hash));
+                continue;
+            }
+
+            let ctor_args = match &contract.ctor {
+                Some(ctor) => encode_typed_ctor_args(
+                    &provider,
+                    &felt_decoder,
+                    contract.class_hash,
+                    &serde_json::to_value(ctor)?,
+                )
+                .await
+                .map_err(|err| anyhow::anyhow!("{}: {}", contract.label, err))?,
+                None => {
+                    let mut ctor_args = vec![];
+                    for element in contract.ctor_args.iter() {
+                        ctor_args.append(&mut felt_decoder.decode(element).await?);
+                    }
+                    ctor_args
+                }
+            };
+
+            // Salts default to the label so that the manifest alone determines the addresses
+            let salt = contract
+                .salt
+                .unwrap_or_else(|| salt_from_label(&contract.label));
+            let unique = !(self.not_unique || contract.not_unique);
+
+            let (call, address) =
+                deployer.deployment_call(sender, contract.class_hash, salt, unique, &ctor_args);
+
+            // Makes re-running a partially applied manifest safe
+            match provider
+                .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+                .await
+            {
+                Ok(_) => {
+                    eprintln!(
+                        "Skipping {}: already deployed at {}",
+                        contract.label,
+                        format_address(address).bright_yellow()
+                    );
+                    continue;
+                }
+                Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {}
+                Err(err) => return Err(err.into()),
+            }
+
+            planned.push(PlannedDeployment {
+                label: contract.label,
+                class_hash: contract.class_hash,
+                salt,
+                call,
+                address,
+                max_fee: FieldElement::ZERO,
+            });
+        }
+
+        if planned.is_empty() && sent.is_empty() {
+            return Ok(());
+        }
+
+        let first_nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => account.get_nonce().await?,
+        };
+
+        match &fee_setting {
+            FeeSetting::Manual(fee) => {
+                for deployment in planned.iter_mut() {
+                    deployment.max_fee = *fee;
+                }
+            }
+            FeeSetting::EstimateOnly { .. } | FeeSetting::None => {
+                let fee_estimates =
+                    estimate_planned_fees(&provider, &account, &planned, first_nonce).await?;
+
+                for (deployment, fee_estimate) in planned.iter_mut().zip(fee_estimates.iter()) {
+                    progress.fee_estimated(fee_estimate.overall_fee);
+
+                    if fee_setting.is_estimate_only() {
+                        eprintln!("{}:", deployment.label);
+                        fee_setting.print_estimate(fee_estimate)?;
+                        continue;
+                    }
+
+                    // TODO: make buffer configurable
+                    deployment.max_fee =
+                        (fee_estimate.overall_fee * felt!("3")).floor_div(felt!("2"));
+                }
+            }
+        }
+
+        if fee_setting.is_estimate_only() {
+            return Ok(());
+        }
+        for deployment in planned.iter() {
+            check_max_fee_ceiling(&provider, deployment.max_fee, force_fee)?;
+        }
+
+        if !planned.is_empty() {
+            eprintln!(
+                "Deploying {} contract(s) via deployer {}...",
+                planned.len(),
+                format_address(deployer.address()).bright_yellow()
+            );
+        }
+
+        let mut nonce = first_nonce;
+        let mut registry = DeploymentRegistry::load()?;
+        if let Some((state, state_file)) = &saved_state {
+            state.save(state_file)?;
+        }
+
+        for deployment in planned.into_iter() {
+            let execution = account
+                .execute(vec![deployment.call])
+                .nonce(nonce)
+                .max_fee(deployment.max_fee);
+
+            // Nonces are managed here, so nonce errors must not be retried with a fresh nonce
+            let transaction_hash =
+                match send_with_retry(&provider, sender, true, || execution.send()).await {
+                    Ok(result) => result.transaction_hash,
+                    Err(err) => {
+                        if let Some((_, state_file)) = &saved_state {
+                            eprintln!("Progress saved. To retry the remaining contracts, run:");
+                            eprintln!("    starkli script resume {}", state_file.display());
+                        }
+
+                        anyhow::bail!("{}: {}", deployment.label, err);
+                    }
+                };
+            progress.transaction_sent(transaction_hash);
+            eprintln!(
+                "Deploying {} at {} in transaction {}",
+                deployment.label,
+                format_address(deployment.address).bright_yellow(),
+                format!("{:#064x}", transaction_hash).bright_yellow()
+            );
+
+            // Saved after every transaction so that nothing is lost if a later one fails
+            registry.record(DeploymentRecord::new(
+                chain_id,
+                deployment.label.clone(),
+                deployment.class_hash,
+                deployment.address,
+                deployment.salt,
+                transaction_hash,
+            ));
+            registry.save()?;
+            if let Some((state, state_file)) = &mut saved_state {
+                state.record(
+                    deployment.label.clone(),
+                    StepResult {
+                        transaction_hash,
+                        address: Some(deployment.address),
+                    },
+                );
+                state.save(state_file)?;
+            }
+
+            sent.push((deployment.label, deployment.address, transaction_hash));
+            nonce += FieldElement::ONE;
+        }
+
+        eprintln!(
+            "Deployments recorded in {}",
+            DeploymentRegistry::get_path()?.display()
+        );
+
+        if self.watch || !self.hooks.is_empty() {
+            for (label, _, transaction_hash) in sent.iter() {
+                eprintln!(
+                    "Waiting for {} in transaction {} to confirm...",
+                    label,
+                    format!("{:#064x}", transaction_hash).bright_yellow(),
+                );
+                self.hooks
+                    .watch_tx(
+                        &provider,
+                        *transaction_hash,
+                        Duration::from_millis(self.poll_interval),
+                    )
+                    .await?;
+                progress.transaction_confirmed(*transaction_hash);
+
+                if self.print_receipt {
+                    print_receipt(&provider, *transaction_hash).await?;
+                }
+            }
+        }
+
+        // Only labels and addresses go to stdout so this can be easily scripted
+        for (label, address, _) in sent.iter() {
+            println!("{} {:#064x}", label, address);
+        }
+
+        Ok(())
+    }
+}
+
+/// Estimates the fees of all planned deployments in a single `starknet_estimateFee` request instead
+/// of one round trip each. The node applies the transactions in order, so they're estimated with
+/// the consecutive nonces they'll be sent with.
+async fn estimate_planned_fees<A>(
+    provider: &ExtendedProvider,
+    account: &A,
+    planned: &[PlannedDeployment],
+    first_nonce: FieldElement,
+) -> Result<Vec<FeeEstimate>>
+where
+    A: ConnectedAccount + Sync,
+    A::SignError: Send + Sync + 'static,
+{
+    if planned.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut requests = vec![];
+    for (ind, deployment) in planned.iter().enumerate() {
+        let request = account
+            .execute(vec![deployment.call.clone()])
+            .nonce(first_nonce + FieldElement::from(ind))
+            .max_fee(FieldElement::ZERO)
+            .prepared()?
+            .get_invoke_request(true)
+            .await
+            .map_err(|err| anyhow::anyhow!("{}: {}", deployment.label, err))?;
+        requests.push(BroadcastedTransaction::Invoke(request));
+    }
+
+    match provider
+        .estimate_fee(
+            &requests,
+            &[] as &[SimulationFlagForEstimateFee],
+            BlockId::Tag(BlockTag::Pending),
+        )
+        .await
+    {
+        Ok(fee_estimates) if fee_estimates.len() == planned.len() => Ok(fee_estimates),
+        Ok(fee_estimates) => Err(anyhow::anyhow!(
+            "expected {} fee estimates but got {}",
+            planned.len(),
+            fee_estimates.len()
+        )),
+        // Points to the deployment that failed instead of the position in the batch
+        Err(ProviderError::StarknetError(StarknetError::TransactionExecutionError(data))) => {
+            let label = planned
+                .get(data.transaction_index as usize)
+                .map(|deployment| deployment.label.as_str())
+                .unwrap_or("unknown");
+            Err(anyhow::anyhow!(
+                "{}: transaction execution error: {}",
+                label,
+                data.execution_error
+            ))
+        }
+        Err(err) => Err(decode_provider_error(err)),
+    }
+}
+
+/// Encodes typed constructor arguments against the ABI of a Sierra class.
+async fn encode_typed_ctor_args(
+    provider: &ExtendedProvider,
+    felt_decoder: &FeltDecoder<Arc<ExtendedProvider>>,
+    class_hash: FieldElement,
+    ctor: &Value,
+) -> Result<Vec<FieldElement>> {
+    let abi = CachedAbi::of_class(provider, class_hash)
+        .await?
+        .sierra_abi()?
+        .ok_or_else(|| {
+            anyhow::anyhow!("typed constructor arguments are only supported for Sierra classes")
+        })?;
+
+    AbiEncoder::new(&abi, felt_decoder)
+        .encode_inputs(&constructor_inputs(&abi), ctor)
+        .await
+        .map_err(|err| anyhow::anyhow!("invalid constructor arguments: {}", err))
+}
+
+/// Prompts for the constructor arguments by name and type when the class is a Sierra class with a
+/// constructor taking arguments.
+async fn prompt_ctor_args(
+    provider: &ExtendedProvider,
+    felt_decoder: &FeltDecoder<Arc<ExtendedProvider>>,
+    class_hash: FieldElement,
+) -> Result<Vec<FieldElement>> {
+    let abi = match CachedAbi::of_class(provider, class_hash)
+        .await?
+        .sierra_abi()?
+    {
+        Some(abi) => abi,
+        None => return Ok(vec![]),
+    };
+
+    let inputs = constructor_inputs(&abi);
+    if inputs.is_empty() {
+        return Ok(vec![]);
+    }
+
+    eprintln!("Enter constructor arguments:");
+    AbiEncoder::new(&abi, felt_decoder)
+        .prompt_inputs(&inputs)
+        .await
+}
+
+/// Labels are hashed the same way as selectors, so the same label always yields the same salt.
+pub fn salt_from_label(label: &str) -> FieldElement {
+    starknet_keccak(label.as_bytes())
 }
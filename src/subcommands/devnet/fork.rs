@@ -0,0 +1,292 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde::Deserialize;
+use starknet::{
+    core::types::{BlockId, BlockTag, ContractClass, FieldElement},
+    providers::Provider,
+};
+use tokio::process::Command;
+use url::Url;
+
+use crate::{
+    account::{
+        AccountConfig, AccountVariant, DeployedStatus, DeploymentStatus, OzAccountConfig,
+        CURRENT_ACCOUNT_CONFIG_VERSION,
+    },
+    devnet::devnet_request,
+    profile::{Network, NetworkProvider, Profiles},
+    provider::ExtendedProvider,
+    utils::format_address,
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+/// How long to wait for a spawned devnet to start accepting requests.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Parser)]
+pub struct Fork {
+    #[clap(long, help = "Network to fork from")]
+    network: String,
+    #[clap(long, help = "Block number to fork from. Defaults to the latest block")]
+    block: Option<u64>,
+    #[clap(
+        long,
+        help = "Connect to an already running forked devnet instead of starting one"
+    )]
+    connect: Option<Url>,
+    #[clap(
+        long,
+        default_value = "starknet-devnet",
+        conflicts_with = "connect",
+        help = "Path to the starknet-devnet binary"
+    )]
+    devnet_path: PathBuf,
+    #[clap(
+        long,
+        default_value = "5050",
+        conflicts_with = "connect",
+        help = "Port for the devnet to listen on"
+    )]
+    port: u16,
+    #[clap(
+        long,
+        default_value = "devnet",
+        help = "Name to save the devnet network and account under"
+    )]
+    name: String,
+    #[clap(
+        long,
+        default_value = "0",
+        help = "Index of the predeployed devnet account to import"
+    )]
+    account_index: usize,
+    #[clap(
+        long,
+        help = "Overwrite the account file and profile network if they already exist"
+    )]
+    force: bool,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+#[derive(Deserialize)]
+struct PredeployedAccount {
+    address: FieldElement,
+    private_key: FieldElement,
+    public_key: FieldElement,
+}
+
+impl Fork {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let accounts_folder = Profiles::get_accounts_folder()?;
+        let account_path = accounts_folder.join(format!("{}.json", self.name));
+        if !self.force {
+            if account_path.exists() {
+                anyhow::bail!(
+                    "account file {} already exists. Use --force to overwrite it",
+                    account_path.display()
+                );
+            }
+            if Profiles::load()?
+                .default_profile_mut()
+                .networks
+                .contains_key(&self.name)
+            {
+                anyhow::bail!(
+                    "network {} already exists in the profile. Use --force to overwrite it",
+                    self.name
+                );
+            }
+        }
+
+        let (devnet_url, child) = match self.connect {
+            Some(url) => (url, None),
+            None => {
+                let fork_provider = ProviderArgs::resolve_network(&self.network)?;
+
+                let mut command = Command::new(&self.devnet_path);
+                command
+                    // Makes sure devnet doesn't outlive Starkli if anything below fails
+                    .kill_on_drop(true)
+                    .arg("--fork-network")
+                    .arg(fork_provider.rpc_url().as_str())
+                    .arg("--port")
+                    .arg(self.port.to_string())
+                    .arg("--seed")
+                    .arg("0");
+                if let Some(block) = self.block {
+                    command.arg("--fork-block").arg(block.to_string());
+                }
+
+                eprintln!(
+                    "Starting devnet forked from {}{}...",
+                    self.network.bright_yellow(),
+                    match self.block {
+                        Some(block) => format!(" at block {}", block.to_string().bright_yellow()),
+                        None => String::new(),
+                    }
+                );
+
+                let child = command.spawn().map_err(|err| {
+                    anyhow::anyhow!(
+                        "unable to start {}: {}. Is starknet-devnet installed?",
+                        self.devnet_path.display(),
+                        err
+                    )
+                })?;
+
+                // Unwrapping is safe as the URL is always valid
+                let url = Url::parse(&format!("http://127.0.0.1:{}/rpc", self.port)).unwrap();
+
+                (url, Some(child))
+            }
+        };
+
+        let provider = ExtendedProvider::new(devnet_url.clone(), false);
+
+        let chain_id = {
+            let start = std::time::Instant::now();
+            loop {
+                match provider.chain_id().await {
+                    Ok(chain_id) => break chain_id,
+                    Err(err) => {
+                        if child.is_none() || start.elapsed() > STARTUP_TIMEOUT {
+                            anyhow::bail!("devnet not reachable at {}: {}", devnet_url, err);
+                        }
+                        tokio::time::sleep(Duration::from_millis(500)).await;
+                    }
+                }
+            }
+        };
+
+        let accounts: Vec<PredeployedAccount> = serde_json::from_value(
+            devnet_request(
+                &provider,
+                "devnet_getPredeployedAccounts",
+                serde_json::json!({}),
+            )
+            .await?,
+        )?;
+        let funded_account = accounts.get(self.account_index).ok_or_else(|| {
+            anyhow::anyhow!("devnet only has {} predeployed accounts", accounts.len())
+        })?;
+
+        // Predeployed devnet accounts are OpenZeppelin accounts, either in Cairo 0 or Cairo 1
+        // depending on devnet options.
+        let class_hash = provider
+            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), funded_account.address)
+            .await?;
+        let legacy = matches!(
+            provider
+                .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
+                .await?,
+            ContractClass::Legacy(_)
+        );
+
+        let account = AccountConfig {
+            version: CURRENT_ACCOUNT_CONFIG_VERSION,
+            variant: AccountVariant::OpenZeppelin(OzAccountConfig {
+                version: 1,
+                public_key: funded_account.public_key,
+                legacy,
+            }),
+            deployment: DeploymentStatus::Deployed(DeployedStatus {
+                class_hash,
+                address: funded_account.address,
+                network: Some(self.name.clone()),
+            }),
+        };
+
+        if !accounts_folder.exists() {
+            std::fs::create_dir_all(&accounts_folder)?;
+        }
+
+        let mut file = std::fs::File::create(&account_path)?;
+        serde_json::to_writer_pretty(&mut file, &account)?;
+        file.write_all(b"\n")?;
+
+        let mut profiles = Profiles::load()?;
+        let profile = profiles.default_profile_mut();
+        profile.networks.insert(
+            self.name.clone(),
+            Network {
+                name: Some(format!("Devnet forked from {}", self.network)),
+                chain_id,
+                is_integration: false,
+                provider: NetworkProvider::Rpc(devnet_url.clone()),
+                udc_address: None,
+                eth_token_address: None,
+                strk_token_address: None,
+                aggregator_address: None,
+                max_fee_ceiling: None,
+                account: None,
+            },
+        );
+        profiles.save()?;
+
+        // Profile defaults are left untouched, so that other commands keep using the usual network
+        // unless asked otherwise
+        eprintln!(
+            "Saved network {} ({}) and account {} ({}). Use them with:",
+            self.name.bright_yellow(),
+            devnet_url,
+            self.name.bright_yellow(),
+            format_address(funded_account.address).bright_yellow()
+        );
+        eprintln!(
+            "    {}",
+            format!("--network {} --account {}", self.name, self.name).bright_yellow()
+        );
+        eprintln!("Supply its private key to send transactions:");
+        println!(
+            "export STARKNET_PRIVATE_KEY={:#064x}",
+            funded_account.private_key
+        );
+
+        // The network and account only work while the spawned devnet is running
+        if let Some(mut child) = child {
+            eprintln!("Devnet is running. Press Ctrl-C to stop it.");
+
+            let result = tokio::select! {
+                status = child.wait() => status.map(|_| ()),
+                _ = tokio::signal::ctrl_c() => {
+                    // Devnet might have already exited upon the same signal
+                    let _ = child.kill().await;
+                    Ok(())
+                }
+            };
+
+            remove_saved_devnet(&self.name, &account_path)?;
+            eprintln!(
+                "Devnet stopped. Removed network and account {}.",
+                self.name.bright_yellow()
+            );
+
+            result?;
+        }
+
+        Ok(())
+    }
+}
+
+fn remove_saved_devnet(name: &str, account_path: &Path) -> Result<()> {
+    let mut profiles = Profiles::load()?;
+    profiles.default_profile_mut().networks.remove(name);
+    profiles.save()?;
+
+    if account_path.exists() {
+        std::fs::remove_file(account_path)?;
+    }
+
+    Ok(())
+}
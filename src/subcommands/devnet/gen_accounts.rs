@@ -0,0 +1,249 @@
+use std::{io::Write, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+use starknet::{
+    accounts::{AccountFactory, OpenZeppelinAccountFactory},
+    core::{
+        serde::unsigned_field_element::UfeHex,
+        types::{BlockId, BlockTag, ContractClass, FieldElement},
+        utils::get_contract_address,
+    },
+    providers::Provider,
+    signers::{LocalWallet, SigningKey},
+};
+
+use crate::{
+    account::{
+        AccountConfig, AccountVariant, DeployedStatus, DeploymentStatus, OzAccountConfig,
+        CURRENT_ACCOUNT_CONFIG_VERSION,
+    },
+    devnet::devnet_request,
+    error::decode_account_factory_error,
+    path::ExpandedPathbufParser,
+    utils::{format_address, watch_tx},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct GenAccounts {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, default_value = "10", help = "Number of accounts to generate")]
+    count: u64,
+    #[clap(
+        long,
+        default_value = "0",
+        help = "Seed for deriving the keys. The same seed always yields the same accounts"
+    )]
+    seed: u64,
+    #[clap(
+        long,
+        default_value = "1000000000000000000000",
+        help = "Amount of ETH in Wei to mint to each account"
+    )]
+    amount: u128,
+    #[clap(
+        long,
+        short,
+        default_value = "./devnet-accounts",
+        value_parser = ExpandedPathbufParser,
+        help = "Folder to save the account files and manifest to"
+    )]
+    output: PathBuf,
+    #[clap(
+        long,
+        default_value = "5000",
+        help = "Transaction result poll interval in milliseconds"
+    )]
+    poll_interval: u64,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+#[serde_as]
+#[derive(Serialize)]
+struct Manifest {
+    seed: u64,
+    #[serde_as(as = "UfeHex")]
+    class_hash: FieldElement,
+    accounts: Vec<ManifestAccount>,
+}
+
+#[serde_as]
+#[derive(Serialize)]
+struct ManifestAccount {
+    index: u64,
+    #[serde_as(as = "UfeHex")]
+    address: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    public_key: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    private_key: FieldElement,
+    account_file: String,
+}
+
+#[derive(Deserialize)]
+struct PredeployedAccount {
+    address: FieldElement,
+}
+
+impl GenAccounts {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let chain_id = provider.chain_id().await?;
+
+        // Generated accounts use the same class as the predeployed ones, which is always declared
+        let predeployed: Vec<PredeployedAccount> = serde_json::from_value(
+            devnet_request(
+                &provider,
+                "devnet_getPredeployedAccounts",
+                serde_json::json!({}),
+            )
+            .await?,
+        )?;
+        let predeployed = predeployed
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("devnet has no predeployed account"))?;
+        let class_hash = provider
+            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), predeployed.address)
+            .await?;
+        let legacy = matches!(
+            provider
+                .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
+                .await?,
+            ContractClass::Legacy(_)
+        );
+
+        if !self.output.exists() {
+            std::fs::create_dir_all(&self.output)?;
+        }
+
+        let mut manifest = Manifest {
+            seed: self.seed,
+            class_hash,
+            accounts: vec![],
+        };
+
+        for index in 0..self.count {
+            let private_key = derive_private_key(self.seed, index);
+            let signing_key = SigningKey::from_secret_scalar(private_key);
+            let public_key = signing_key.verifying_key().scalar();
+
+            // Using the public key as salt keeps addresses stable without storing extra data
+            let address =
+                get_contract_address(public_key, class_hash, &[public_key], FieldElement::ZERO);
+
+            let deployed = provider
+                .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+                .await
+                .is_ok();
+            if deployed {
+                eprintln!(
+                    "Account #{} already deployed at {}",
+                    index,
+                    format_address(address).bright_yellow()
+                );
+            } else {
+                devnet_request(
+                    &provider,
+                    "devnet_mint",
+                    serde_json::json!({
+                        "address": format!("{:#064x}", address),
+                        "amount": self.amount,
+                        "unit": "WEI",
+                    }),
+                )
+                .await?;
+
+                let mut factory = OpenZeppelinAccountFactory::new(
+                    class_hash,
+                    chain_id,
+                    LocalWallet::from_signing_key(signing_key),
+                    provider.clone(),
+                )
+                .await?;
+                factory.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+                let deployment_tx = factory
+                    .deploy(public_key)
+                    .send()
+                    .await
+                    .map_err(decode_account_factory_error)?
+                    .transaction_hash;
+                watch_tx(
+                    &provider,
+                    deployment_tx,
+                    Duration::from_millis(self.poll_interval),
+                )
+                .await?;
+
+                eprintln!(
+                    "Account #{} deployed at {}",
+                    index,
+                    format_address(address).bright_yellow()
+                );
+            }
+
+            let account = AccountConfig {
+                version: CURRENT_ACCOUNT_CONFIG_VERSION,
+                variant: AccountVariant::OpenZeppelin(OzAccountConfig {
+                    version: 1,
+                    public_key,
+                    legacy,
+                }),
+                deployment: DeploymentStatus::Deployed(DeployedStatus {
+                    class_hash,
+                    address,
+                    network: None,
+                }),
+            };
+
+            let account_file = format!("account-{}.json", index);
+            let mut file = std::fs::File::create(self.output.join(&account_file))?;
+            serde_json::to_writer_pretty(&mut file, &account)?;
+            file.write_all(b"\n")?;
+
+            manifest.accounts.push(ManifestAccount {
+                index,
+                address,
+                public_key,
+                private_key,
+                account_file,
+            });
+        }
+
+        let manifest_path = self.output.join("manifest.json");
+        let mut file = std::fs::File::create(&manifest_path)?;
+        serde_json::to_writer_pretty(&mut file, &manifest)?;
+        file.write_all(b"\n")?;
+
+        eprintln!(
+            "Manifest with {} account(s) written to {}",
+            self.count,
+            manifest_path.display().to_string().bright_yellow()
+        );
+
+        Ok(())
+    }
+}
+
+/// Derives a private key from the seed and account index. Only the first 31 bytes of the hash are
+/// used so that the key is always below the curve order.
+fn derive_private_key(seed: u64, index: u64) -> FieldElement {
+    let mut hasher = Sha256::new();
+    hasher.update(b"starkli-devnet-account");
+    hasher.update(seed.to_be_bytes());
+    hasher.update(index.to_be_bytes());
+    let hash = hasher.finalize();
+
+    // Safe to unwrap as 31 bytes always fit in a field element
+    FieldElement::from_byte_slice_be(&hash[..31]).unwrap()
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod fork;
+use fork::Fork;
+
+mod gen_accounts;
+use gen_accounts::GenAccounts;
+
+#[derive(Debug, Parser)]
+pub struct Devnet {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(
+        about = "Start or connect to a devnet forked from another network and use it by default"
+    )]
+    Fork(Fork),
+    #[clap(about = "Generate and deploy a reproducible set of funded accounts on a devnet")]
+    GenAccounts(GenAccounts),
+}
+
+impl Devnet {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Fork(cmd) => cmd.run().await,
+            Subcommands::GenAccounts(cmd) => cmd.run().await,
+        }
+    }
+}
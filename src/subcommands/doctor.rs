@@ -0,0 +1,373 @@
+use std::{sync::Arc, time::Instant};
+
+use anyhow::Result;
+use bigdecimal::{BigDecimal, Zero};
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use num_bigint::ToBigInt;
+use starknet::{
+    core::{
+        types::{BlockId, BlockTag, FieldElement, FunctionCall, StarknetError},
+        utils::{get_selector_from_name, parse_cairo_short_string},
+    },
+    providers::{Provider, ProviderError},
+};
+
+use crate::{
+    accessibility::Status,
+    account::{
+        find_builtin_account, load_default_account, resolve_account_path, AccountConfig,
+        DeploymentStatus,
+    },
+    casm::network_compiler_version,
+    compiler::CompilerVersion,
+    network::NetworkSource,
+    provider::ExtendedProvider,
+    signer::{SignerArgs, SignerResolutionTask},
+    token::fetch_balance,
+    utils::format_address,
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Doctor {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        env = "STARKNET_ACCOUNT",
+        help = "Path to account config JSON file, or name of an account in the accounts directory. \
+            Falls back to the default account if not set"
+    )]
+    account: Option<String>,
+    #[clap(flatten)]
+    signer: SignerArgs,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+#[derive(Default)]
+struct Report {
+    warnings: usize,
+    failures: usize,
+}
+
+impl Doctor {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let mut report = Report::default();
+
+        eprintln!("Provider:");
+        let provider = match self.provider.clone().into_provider() {
+            Ok(provider) => Arc::new(provider),
+            Err(err) => {
+                report.fail(&format!("unable to resolve provider: {}", err));
+                return report.finish();
+            }
+        };
+        let chain_id = match check_provider(&provider, &mut report).await {
+            Some(chain_id) => chain_id,
+            None => return report.finish(),
+        };
+
+        eprintln!("Account:");
+        let account_address = self.check_account(&provider, &mut report).await;
+
+        eprintln!("Signer:");
+        let public_key = self.check_signer(&mut report);
+        if let (Some(public_key), Some(account_address)) = (public_key, account_address) {
+            check_account_public_key(&provider, account_address, public_key, &mut report).await;
+        }
+
+        eprintln!("Compiler:");
+        check_compiler(&provider, chain_id, &mut report).await;
+
+        report.finish()
+    }
+
+    /// Returns the account address, if there's one to check the signer against.
+    async fn check_account(
+        &self,
+        provider: &Arc<ExtendedProvider>,
+        report: &mut Report,
+    ) -> Option<FieldElement> {
+        let account_arg = match &self.account {
+            Some(account) => account.to_owned(),
+            None => match load_default_account() {
+                Ok(Some(account)) => account,
+                Ok(None) => {
+                    report.warn("no account specified with --account and no default account set");
+                    return None;
+                }
+                Err(err) => {
+                    report.fail(&format!("unable to load default account: {}", err));
+                    return None;
+                }
+            },
+        };
+
+        let address = if let Some(builtin_account) = find_builtin_account(&account_arg) {
+            report.ok(&format!("using built-in account `{}`", builtin_account.id));
+            builtin_account.address
+        } else {
+            let path = match resolve_account_path(&account_arg) {
+                Ok(path) if path.exists() => path,
+                Ok(_) => {
+                    report.fail(&format!("account config file not found: {}", account_arg));
+                    return None;
+                }
+                Err(err) => {
+                    report.fail(&format!("invalid account: {}", err));
+                    return None;
+                }
+            };
+            let account = match AccountConfig::load(&path) {
+                Ok(account) => account,
+                Err(err) => {
+                    report.fail(&format!("unable to parse account config file: {}", err));
+                    return None;
+                }
+            };
+            report.ok(&format!("loaded account config from {}", path.display()));
+
+            match &account.deployment {
+                DeploymentStatus::Deployed(status) => status.address,
+                DeploymentStatus::Undeployed(_) => {
+                    match account.deploy_account_address() {
+                        Ok(address) => report.fail(&format!(
+                            "account {:#064x} is not marked as deployed. Deploy it with `starkli \
+                            account deploy`, or run `starkli account fetch` if it's already \
+                            deployed",
+                            address
+                        )),
+                        Err(err) => report.fail(&format!("account is not deployed: {}", err)),
+                    }
+                    return None;
+                }
+            }
+        };
+
+        match provider
+            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+            .await
+        {
+            Ok(class_hash) => report.ok(&format!(
+                "account {} is deployed with class {:#064x}",
+                format_address(address).bright_yellow(),
+                class_hash
+            )),
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
+                report.fail(&format!(
+                    "account {:#064x} is not deployed on this network",
+                    address
+                ));
+                return None;
+            }
+            Err(err) => {
+                report.fail(&format!("unable to check account deployment: {}", err));
+                return None;
+            }
+        }
+
+        for (symbol, token) in [
+            ("ETH", provider.eth_token_address()),
+            ("STRK", provider.strk_token_address()),
+        ] {
+            match fetch_balance(provider, token, address).await {
+                Ok(balance) => {
+                    // `to_bigint()` from `BigUint` always returns `Some`.
+                    let balance = BigDecimal::new(balance.to_bigint().unwrap(), 18);
+                    if symbol == "ETH" && balance.is_zero() {
+                        report.warn("account has no ETH to pay for transaction fees");
+                    } else {
+                        report.ok(&format!("balance: {} {}", balance, symbol));
+                    }
+                }
+                Err(err) => report.warn(&format!("unable to fetch {} balance: {}", symbol, err)),
+            }
+        }
+
+        Some(address)
+    }
+
+    /// Returns the public key of the signer if it could be resolved.
+    fn check_signer(&self, report: &mut Report) -> Option<FieldElement> {
+        let task = match self.signer.clone().into_task() {
+            Ok(task) => task,
+            Err(err) => {
+                report.fail(&format!("invalid signer options: {}", err));
+                return None;
+            }
+        };
+
+        if matches!(task, SignerResolutionTask::None) {
+            report.warn("no signer configured. Use --keystore (STARKNET_KEYSTORE) to check one");
+            return None;
+        }
+
+        // This prompts for the keystore password, which is exactly what's being checked
+        match task.resolve_signing_key() {
+            Ok(key) => {
+                let public_key = key.verifying_key().scalar();
+                report.ok(&format!(
+                    "signer decrypted with public key {:#064x}",
+                    public_key
+                ));
+                Some(public_key)
+            }
+            Err(err) => {
+                report.fail(&format!("unable to load signer: {}", err));
+                None
+            }
+        }
+    }
+}
+
+impl Report {
+    fn ok(&mut self, message: &str) {
+        print_line(&Status::Success.label("ok"), message);
+    }
+
+    fn warn(&mut self, message: &str) {
+        self.warnings += 1;
+        print_line(&Status::Warning.label("warn"), message);
+    }
+
+    fn fail(&mut self, message: &str) {
+        self.failures += 1;
+        print_line(&Status::Failure.label("fail"), message);
+    }
+
+    fn finish(self) -> Result<()> {
+        eprintln!();
+        if self.failures > 0 {
+            anyhow::bail!(
+                "{} check(s) failed and {} warning(s) found",
+                self.failures,
+                self.warnings
+            );
+        }
+
+        eprintln!(
+            "All checks passed with {} warning(s).",
+            format!("{}", self.warnings).bright_yellow()
+        );
+        Ok(())
+    }
+}
+
+/// Returns the chain ID reported by the provider, or `None` if it's unreachable.
+async fn check_provider(provider: &ExtendedProvider, report: &mut Report) -> Option<FieldElement> {
+    let started_at = Instant::now();
+    let chain_id = match provider.chain_id().await {
+        Ok(chain_id) => chain_id,
+        Err(err) => {
+            report.fail(&format!(
+                "unable to connect to {}: {}",
+                provider.rpc_url(),
+                err
+            ));
+            return None;
+        }
+    };
+    let latency = started_at.elapsed();
+
+    let spec_version = provider
+        .spec_version()
+        .await
+        .unwrap_or_else(|_| "unknown".into());
+    report.ok(&format!(
+        "connected to {} (JSON-RPC {}, {} ms)",
+        provider.rpc_url().to_string().bright_yellow(),
+        spec_version,
+        latency.as_millis()
+    ));
+
+    let chain_id_display =
+        parse_cairo_short_string(&chain_id).unwrap_or_else(|_| format!("{:#x}", chain_id));
+    match provider.profile_chain_id() {
+        Some(profile_chain_id) if profile_chain_id != chain_id => report.fail(&format!(
+            "chain ID {} does not match {} configured in the profile",
+            chain_id_display,
+            parse_cairo_short_string(&profile_chain_id)
+                .unwrap_or_else(|_| format!("{:#x}", profile_chain_id))
+        )),
+        Some(_) => report.ok(&format!(
+            "chain ID {} matches the profile",
+            chain_id_display
+        )),
+        None => report.ok(&format!("chain ID {}", chain_id_display)),
+    }
+
+    match provider.block_number().await {
+        Ok(block_number) => report.ok(&format!("latest block: {}", block_number)),
+        Err(err) => report.warn(&format!("unable to fetch latest block: {}", err)),
+    }
+
+    Some(chain_id)
+}
+
+async fn check_account_public_key(
+    provider: &ExtendedProvider,
+    account_address: FieldElement,
+    public_key: FieldElement,
+    report: &mut Report,
+) {
+    // Most account implementations expose one of these
+    for selector in ["get_public_key", "getPublicKey", "getSigner", "get_owner"] {
+        let result = provider
+            .call(
+                FunctionCall {
+                    contract_address: account_address,
+                    // Safe to unwrap as the names above are valid
+                    entry_point_selector: get_selector_from_name(selector).unwrap(),
+                    calldata: vec![],
+                },
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await;
+
+        if let Ok(result) = result {
+            if result.first() == Some(&public_key) {
+                report.ok("signer matches the account public key");
+            } else {
+                report.fail(
+                    "signer does not match the account public key, so transactions will fail \
+                    validation",
+                );
+            }
+            return;
+        }
+    }
+
+    report.warn("unable to read the public key from the account contract");
+}
+
+async fn check_compiler(provider: &ExtendedProvider, chain_id: FieldElement, report: &mut Report) {
+    let versions = CompilerVersion::value_variants()
+        .iter()
+        .map(|version| format!("{} (Sierra {})", version, version.max_sierra_version()))
+        .collect::<Vec<_>>();
+    report.ok(&format!(
+        "bundled Sierra compilers: {}",
+        versions.join(", ")
+    ));
+
+    match provider.get_network().await {
+        Ok(Some(network)) => report.ok(&format!(
+            "compiler version {} is used for {} by default",
+            network_compiler_version(network),
+            network
+        )),
+        _ => report.warn(&format!(
+            "unknown network with chain ID {:#x}. Use --compiler-version when declaring",
+            chain_id
+        )),
+    }
+}
+
+fn print_line(status: &str, message: &str) {
+    eprintln!("  [{}] {}", status, message);
+}
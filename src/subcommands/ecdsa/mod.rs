@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod sign;
+use sign::Sign;
+
+mod verify;
+use verify::Verify;
+
+mod recover;
+use recover::Recover;
+
+#[derive(Debug, Parser)]
+pub struct Ecdsa {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Sign a raw message hash, printing r, s, and v")]
+    Sign(Sign),
+    #[clap(about = "Verify a signature against a public key")]
+    Verify(Verify),
+    #[clap(about = "Recover the public key from a signature with its v value")]
+    Recover(Recover),
+}
+
+impl Ecdsa {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Sign(cmd) => cmd.run(),
+            Subcommands::Verify(cmd) => cmd.run(),
+            Subcommands::Recover(cmd) => cmd.run(),
+        }
+    }
+}
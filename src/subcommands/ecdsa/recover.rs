@@ -0,0 +1,32 @@
+use anyhow::Result;
+use clap::Parser;
+use starknet_crypto::recover;
+
+use crate::utils::parse_felt_value;
+
+#[derive(Debug, Parser)]
+pub struct Recover {
+    #[clap(help = "Message hash that was signed")]
+    hash: String,
+    #[clap(help = "The r value of the signature")]
+    r: String,
+    #[clap(help = "The s value of the signature")]
+    s: String,
+    #[clap(help = "The v value (0 or 1) of the signature, as printed by `ecdsa sign`")]
+    v: String,
+}
+
+impl Recover {
+    pub fn run(self) -> Result<()> {
+        let public_key = recover(
+            &parse_felt_value(&self.hash)?,
+            &parse_felt_value(&self.r)?,
+            &parse_felt_value(&self.s)?,
+            &parse_felt_value(&self.v)?,
+        )?;
+
+        println!("{:#064x}", public_key);
+
+        Ok(())
+    }
+}
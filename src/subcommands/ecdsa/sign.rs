@@ -0,0 +1,29 @@
+use anyhow::Result;
+use clap::Parser;
+use starknet::core::crypto::ecdsa_sign;
+
+use crate::{signer::SignerArgs, utils::parse_felt_value};
+
+#[derive(Debug, Parser)]
+pub struct Sign {
+    #[clap(flatten)]
+    signer: SignerArgs,
+    #[clap(help = "Message hash to sign, in decimal or hexadecimal representation")]
+    hash: String,
+}
+
+impl Sign {
+    pub fn run(self) -> Result<()> {
+        let hash = parse_felt_value(&self.hash)?;
+        let key = self.signer.into_signing_key()?;
+
+        // The nonce is derived with RFC 6979, so signing the same hash twice gives the same output
+        let signature = ecdsa_sign(&key.secret_scalar(), &hash)?;
+
+        println!("{:#064x}", signature.r);
+        println!("{:#064x}", signature.s);
+        println!("{:#064x}", signature.v);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::crypto::{ecdsa_verify, Signature};
+
+use crate::utils::parse_felt_value;
+
+#[derive(Debug, Parser)]
+pub struct Verify {
+    #[clap(long, help = "Public key of the signer")]
+    public_key: String,
+    #[clap(help = "Message hash that was signed")]
+    hash: String,
+    #[clap(help = "The r value of the signature")]
+    r: String,
+    #[clap(help = "The s value of the signature")]
+    s: String,
+}
+
+impl Verify {
+    pub fn run(self) -> Result<()> {
+        let public_key = parse_felt_value(&self.public_key)?;
+        let hash = parse_felt_value(&self.hash)?;
+        let signature = Signature {
+            r: parse_felt_value(&self.r)?,
+            s: parse_felt_value(&self.s)?,
+        };
+
+        if ecdsa_verify(&public_key, &hash, &signature)? {
+            println!("{}", "Valid signature".bright_green());
+            Ok(())
+        } else {
+            anyhow::bail!("invalid signature")
+        }
+    }
+}
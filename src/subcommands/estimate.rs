@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use starknet::{accounts::Account, core::types::FieldElement};
+
+use crate::{
+    account::{impersonated_account, AccountArgs},
+    address_book::AddressBookResolver,
+    amount::{AmountFormat, AmountFormatArgs},
+    decode::FeltDecoder,
+    error::decode_account_error,
+    fee::{estimate_fee_skip_validate, FeeSetting},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Estimate {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    account: AccountArgs,
+    #[clap(
+        long,
+        conflicts_with = "account",
+        help = "Estimate as this address without its private key. Account validation is skipped, \
+                so its cost is not included"
+    )]
+    sender: Option<String>,
+    #[clap(long, help = "Estimate with this nonce instead of the current one")]
+    nonce: Option<FieldElement>,
+    #[clap(
+        long,
+        help = "Print the fee estimation as JSON with all fee components"
+    )]
+    json: bool,
+    #[clap(flatten)]
+    amounts: AmountFormatArgs,
+    #[clap(help = "One or more contract calls. See documentation for more details")]
+    calls: Vec<String>,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Estimate {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let calls = felt_decoder.decode_calls(self.calls).await?;
+        if calls.is_empty() {
+            anyhow::bail!("empty execution");
+        }
+
+        let signerless = self.sender.is_some();
+        let account = match self.sender {
+            Some(address) => {
+                let address = felt_decoder
+                    .decode_single_with_addr_fallback(&address)
+                    .await?;
+                impersonated_account(provider.clone(), address).await?
+            }
+            None => self.account.into_account(provider.clone()).await?,
+        };
+
+        let execution = account.execute(calls);
+        let execution = match self.nonce {
+            Some(nonce) => execution.nonce(nonce),
+            None => execution,
+        };
+
+        let fee_estimate = if signerless {
+            estimate_fee_skip_validate(execution).await?
+        } else {
+            execution
+                .estimate_fee()
+                .await
+                .map_err(decode_account_error)?
+        };
+
+        FeeSetting::EstimateOnly {
+            json: self.json,
+            amount_format: self.amounts.format_or(AmountFormat::HUMAN),
+        }
+        .print_estimate(&fee_estimate)?;
+
+        Ok(())
+    }
+}
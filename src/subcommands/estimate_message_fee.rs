@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::types::{BlockId, BlockTag, EthAddress, MsgFromL1},
+    providers::Provider,
+};
+
+use crate::{
+    address_book::AddressBookResolver,
+    amount::{AmountFormat, AmountFormatArgs},
+    decode::FeltDecoder,
+    utils::print_colored_json,
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct EstimateMessageFee {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, help = "L1 sender address")]
+    from_l1: String,
+    #[clap(long, help = "L2 contract address handling the message")]
+    to: String,
+    #[clap(long, conflicts_with = "json", help = "Display the fee in Wei")]
+    raw: bool,
+    #[clap(
+        long,
+        conflicts_with = "raw",
+        help = "Display the full fee estimate in JSON"
+    )]
+    json: bool,
+    #[clap(flatten)]
+    amounts: AmountFormatArgs,
+    #[clap(help = "Name of the L1 handler function")]
+    selector: String,
+    #[clap(help = "Raw message payload")]
+    payload: Vec<String>,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl EstimateMessageFee {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let from_address = EthAddress::from_hex(&self.from_l1)
+            .map_err(|err| anyhow::anyhow!("invalid L1 address: {}", err))?;
+        let to_address = felt_decoder
+            .decode_single_with_addr_fallback(&self.to)
+            .await?;
+        let entry_point_selector = felt_decoder
+            .decode_single_with_selector_fallback(&self.selector)
+            .await?;
+
+        let mut payload = vec![];
+        for element in self.payload.iter() {
+            payload.append(&mut felt_decoder.decode(element).await?);
+        }
+
+        let estimate = provider
+            .estimate_message_fee(
+                MsgFromL1 {
+                    from_address,
+                    to_address,
+                    entry_point_selector,
+                    payload,
+                },
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await?;
+
+        let amount_format = if self.raw {
+            AmountFormat::Raw
+        } else {
+            self.amounts.format_or(AmountFormat::HUMAN)
+        };
+
+        if self.json {
+            print_colored_json(&estimate)?;
+        } else if let AmountFormat::Human(style) = amount_format {
+            println!(
+                "{} ETH",
+                style.format_ether(estimate.overall_fee).bright_yellow()
+            );
+        } else {
+            println!("{}", estimate.overall_fee);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::utils::parse_eth_address;
+
+#[derive(Debug, Parser)]
+pub struct EthAddressToFelt {
+    #[clap(long, help = "Display the encoded value in decimal representation")]
+    dec: bool,
+    #[clap(help = "Ethereum address to be encoded in felt")]
+    address: String,
+}
+
+impl EthAddressToFelt {
+    pub fn run(self) -> Result<()> {
+        let felt_value = parse_eth_address(&self.address)?;
+        if self.dec {
+            println!("{felt_value}");
+        } else {
+            println!("{felt_value:#064x}");
+        }
+
+        Ok(())
+    }
+}
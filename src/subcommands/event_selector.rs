@@ -0,0 +1,32 @@
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use starknet::core::utils::starknet_keccak;
+
+#[derive(Debug, Parser)]
+pub struct EventSelector {
+    #[clap(help = "Event name. Module paths are ignored")]
+    name: String,
+}
+
+impl EventSelector {
+    pub fn run(self) -> Result<()> {
+        let trimmed_name = self.name.trim();
+
+        if trimmed_name.contains('(') || trimmed_name.contains(')') {
+            return Err(anyhow!(
+                "parentheses and the content within should not be supplied"
+            ));
+        }
+
+        // The first key of an event is derived from its name without the module path
+        let name = trimmed_name.rsplit("::").next().unwrap_or(trimmed_name);
+        if name.is_empty() {
+            return Err(anyhow!("empty event name"));
+        }
+
+        let selector = starknet_keccak(name.as_bytes());
+        println!("{selector:#064x}");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use std::{collections::BTreeMap, io::Read, path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use colored_json::{ColorMode, Output};
+use serde::{Deserialize, Serialize};
+use starknet::{core::types::EventFilter, providers::Provider};
+
+use crate::{
+    address_book::AddressBookResolver, decode::FeltDecoder, profile::Profiles,
+    provider::ExtendedProvider, utils::parse_block_id, verbosity::VerbosityArgs, ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Events {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, help = "Only include events emitted by this contract")]
+    address: Option<String>,
+    #[clap(
+        long,
+        help = "Filter on event keys, once per key position. Each value is a comma-separated \
+                list of accepted values, or `_` to accept any value. Event names are converted \
+                to selectors"
+    )]
+    keys: Vec<String>,
+    #[clap(long, help = "First block to query (number, hash, or tag)")]
+    from_block: Option<String>,
+    #[clap(long, help = "Last block to query (number, hash, or tag)")]
+    to_block: Option<String>,
+    #[clap(
+        long,
+        default_value = "100",
+        help = "Number of events fetched per request"
+    )]
+    chunk_size: u64,
+    #[clap(
+        long,
+        help = "Load the filter from a saved preset. Options given on the command line take \
+                precedence"
+    )]
+    preset: Option<String>,
+    #[clap(
+        long,
+        help = "Save the filter as a named preset before running the query"
+    )]
+    save: Option<String>,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+/// Saved event queries, stored in `event_presets.toml` under the config folder.
+#[derive(Default, Serialize, Deserialize)]
+struct EventPresets {
+    #[serde(default)]
+    presets: BTreeMap<String, EventQuery>,
+}
+
+/// Filter options as entered by the user, so that names are resolved again on each use.
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct EventQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    keys: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    from_block: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    to_block: Option<String>,
+}
+
+impl Events {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let mut presets = EventPresets::load()?;
+
+        let base_query = match &self.preset {
+            Some(name) => presets
+                .presets
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("event preset not found: {}", name))?,
+            None => EventQuery::default(),
+        };
+        let query = EventQuery {
+            address: self.address.or(base_query.address),
+            keys: if self.keys.is_empty() {
+                base_query.keys
+            } else {
+                self.keys
+            },
+            from_block: self.from_block.or(base_query.from_block),
+            to_block: self.to_block.or(base_query.to_block),
+        };
+
+        if let Some(name) = self.save {
+            presets.presets.insert(name.clone(), query.clone());
+            presets.save()?;
+
+            eprintln!("Event preset {} saved", name.bright_yellow());
+        }
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let filter = query.into_filter(&provider).await?;
+
+        let mut events = vec![];
+        let mut continuation_token = None;
+        loop {
+            let page = provider
+                .get_events(filter.clone(), continuation_token, self.chunk_size)
+                .await?;
+            events.extend(page.events);
+
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        let events_json = serde_json::to_value(events)?;
+        let events_json =
+            colored_json::to_colored_json(&events_json, ColorMode::Auto(Output::StdOut))?;
+        println!("{events_json}");
+
+        Ok(())
+    }
+}
+
+impl EventPresets {
+    fn get_path() -> Result<PathBuf> {
+        let mut path = Profiles::get_config_folder()?;
+        path.push("event_presets.toml");
+        Ok(path)
+    }
+
+    fn load() -> Result<Self> {
+        let path = Self::get_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let mut buffer = String::new();
+        std::fs::File::open(&path)?.read_to_string(&mut buffer)?;
+
+        toml::from_str(&buffer)
+            .map_err(|err| anyhow::anyhow!("invalid event presets {}: {}", path.display(), err))
+    }
+
+    fn save(&self) -> Result<()> {
+        let config_folder = Profiles::get_config_folder()?;
+        if !config_folder.exists() {
+            std::fs::create_dir_all(config_folder)?;
+        }
+
+        std::fs::write(Self::get_path()?, toml::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+}
+
+impl EventQuery {
+    async fn into_filter(self, provider: &Arc<ExtendedProvider>) -> Result<EventFilter> {
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = match self.address {
+            Some(address) => Some(
+                felt_decoder
+                    .decode_single_with_addr_fallback(&address)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        let mut keys = vec![];
+        for key in self.keys.iter() {
+            // An empty list of accepted values matches any key
+            let mut accepted = vec![];
+            if key != "_" {
+                for value in key.split(',') {
+                    accepted.push(
+                        felt_decoder
+                            .decode_single_with_selector_fallback(value.trim())
+                            .await?,
+                    );
+                }
+            }
+            keys.push(accepted);
+        }
+
+        Ok(EventFilter {
+            from_block: self.from_block.as_deref().map(parse_block_id).transpose()?,
+            to_block: self.to_block.as_deref().map(parse_block_id).transpose()?,
+            address,
+            keys: if keys.is_empty() { None } else { Some(keys) },
+        })
+    }
+}
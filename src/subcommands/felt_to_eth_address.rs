@@ -0,0 +1,19 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::utils::{eth_address_from_felt, parse_felt_value};
+
+#[derive(Debug, Parser)]
+pub struct FeltToEthAddress {
+    #[clap(help = "Felt value to be decoded as an Ethereum address")]
+    felt: String,
+}
+
+impl FeltToEthAddress {
+    pub fn run(self) -> Result<()> {
+        let felt_value = parse_felt_value(&self.felt)?;
+        println!("{}", eth_address_from_felt(felt_value)?);
+
+        Ok(())
+    }
+}
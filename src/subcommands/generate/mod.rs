@@ -0,0 +1,306 @@
+use std::{collections::HashSet, io::Write, path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use serde_json::Value;
+use starknet::core::types::contract::{
+    AbiEntry, AbiEnum, AbiFunction, AbiNamedMember, AbiStruct, StateMutability,
+};
+
+use crate::{
+    abi::{constructor_inputs, generic_argument, tuple_members},
+    abi_cache::CachedAbi,
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    ProviderArgs,
+};
+
+mod rust_bindings;
+use rust_bindings::RustBindings;
+
+mod ts;
+use ts::TsBindings;
+
+#[derive(Debug, Parser)]
+pub struct Generate {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Generate typed Rust bindings using starknet-rs types from a contract ABI")]
+    RustBindings(RustBindings),
+    #[clap(about = "Generate a TypeScript module with starknet.js call descriptors and types")]
+    Ts(TsBindings),
+}
+
+/// Where to read the ABI from, shared by all generators.
+#[derive(Debug, Parser)]
+pub struct AbiSourceArgs {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "Name of the generated contract type. Derived from the file name if not set"
+    )]
+    name: Option<String>,
+    #[clap(
+        long,
+        help = "Path to write the generated code to. Prints to stdout if not set"
+    )]
+    out: Option<PathBuf>,
+    #[clap(help = "Path to an ABI or Sierra class file, or the address of a deployed contract")]
+    abi: String,
+}
+
+/// Cairo types as seen by code generators, resolved from the type names used in ABIs.
+pub enum CairoType {
+    /// `felt252` and types represented as a single felt, like `ContractAddress`.
+    Felt,
+    Bool,
+    Unsigned(u32),
+    Signed(u32),
+    U256,
+    ByteArray,
+    Unit,
+    Array(Box<CairoType>),
+    Tuple(Vec<CairoType>),
+    Option(Box<CairoType>),
+    /// A struct or enum defined in the ABI, by its generated identifier.
+    Named(String),
+}
+
+/// The parts of an ABI relevant to code generation, with interfaces flattened.
+pub struct AbiModel {
+    pub constructor: Vec<AbiNamedMember>,
+    pub functions: Vec<AbiFunction>,
+    pub structs: Vec<AbiStruct>,
+    pub enums: Vec<AbiEnum>,
+    defined_types: HashSet<String>,
+}
+
+impl Generate {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::RustBindings(cmd) => cmd.run().await,
+            Subcommands::Ts(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl AbiSourceArgs {
+    /// Loads the ABI along with the name of the contract type to generate.
+    pub async fn load(&self) -> Result<(Vec<AbiEntry>, String)> {
+        let path = PathBuf::from(shellexpand::tilde(&self.abi).into_owned());
+
+        let (abi, default_name) = if path.exists() {
+            let file: Value = serde_json::from_reader(std::fs::File::open(&path)?)?;
+            let abi = match file {
+                // Sierra class artifacts, where the ABI is either embedded as a string or inline
+                Value::Object(mut class) => match class.remove("abi") {
+                    Some(Value::String(abi)) => serde_json::from_str(&abi)?,
+                    Some(abi) => serde_json::from_value(abi)?,
+                    None => anyhow::bail!("file {} does not contain an ABI", path.display()),
+                },
+                abi => serde_json::from_value(abi)?,
+            };
+
+            (abi, name_from_path(&path))
+        } else {
+            let provider = Arc::new(self.provider.clone().into_provider()?);
+            let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+            let address = felt_decoder
+                .decode_single_with_addr_fallback(&self.abi)
+                .await
+                .map_err(|_| anyhow::anyhow!("{} is neither a file nor an address", self.abi))?;
+
+            let abi = CachedAbi::of_contract(&provider, address)
+                .await?
+                .sierra_abi()?
+                .ok_or_else(|| {
+                    anyhow::anyhow!("code generation is only supported for Sierra classes")
+                })?;
+
+            (abi, None)
+        };
+
+        let name = match &self.name {
+            Some(name) => name.to_owned(),
+            None => default_name.unwrap_or_else(|| "Contract".to_owned()),
+        };
+
+        Ok((abi, name))
+    }
+
+    pub fn write_output(&self, code: &str) -> Result<()> {
+        match &self.out {
+            Some(out) => {
+                let mut file = std::fs::File::create(out)?;
+                file.write_all(code.as_bytes())?;
+                eprintln!("Generated code written to {}", out.display());
+            }
+            None => print!("{}", code),
+        }
+
+        Ok(())
+    }
+}
+
+impl AbiModel {
+    pub fn new(abi: &[AbiEntry]) -> Self {
+        let mut model = Self {
+            constructor: constructor_inputs(abi),
+            functions: vec![],
+            structs: vec![],
+            enums: vec![],
+            defined_types: HashSet::new(),
+        };
+        model.collect(abi);
+
+        // Core types like `u256` and `Option` are also listed, but map to built-in types instead
+        let structs = std::mem::take(&mut model.structs)
+            .into_iter()
+            .filter(|item| !model.is_builtin(&item.name))
+            .collect();
+        let enums = std::mem::take(&mut model.enums)
+            .into_iter()
+            .filter(|item| !model.is_builtin(&item.name))
+            .collect();
+        model.structs = structs;
+        model.enums = enums;
+
+        model
+    }
+
+    pub fn resolve(&self, type_name: &str) -> Result<CairoType> {
+        Ok(match type_name {
+            "core::felt252"
+            | "core::starknet::contract_address::ContractAddress"
+            | "core::starknet::class_hash::ClassHash"
+            | "core::starknet::eth_address::EthAddress"
+            | "core::starknet::storage_access::StorageAddress" => CairoType::Felt,
+            "core::bool" => CairoType::Bool,
+            "core::integer::u8" => CairoType::Unsigned(8),
+            "core::integer::u16" => CairoType::Unsigned(16),
+            "core::integer::u32" => CairoType::Unsigned(32),
+            "core::integer::u64" => CairoType::Unsigned(64),
+            "core::integer::u128" => CairoType::Unsigned(128),
+            "core::integer::i8" => CairoType::Signed(8),
+            "core::integer::i16" => CairoType::Signed(16),
+            "core::integer::i32" => CairoType::Signed(32),
+            "core::integer::i64" => CairoType::Signed(64),
+            "core::integer::i128" => CairoType::Signed(128),
+            "core::integer::u256" => CairoType::U256,
+            "core::byte_array::ByteArray" => CairoType::ByteArray,
+            "()" => CairoType::Unit,
+            _ => {
+                if let Some(item_type) = generic_argument(type_name, "core::array::Array::<")
+                    .or_else(|| generic_argument(type_name, "core::array::Span::<"))
+                {
+                    CairoType::Array(Box::new(self.resolve(item_type)?))
+                } else if let Some(item_type) =
+                    generic_argument(type_name, "core::option::Option::<")
+                {
+                    CairoType::Option(Box::new(self.resolve(item_type)?))
+                } else if let Some(members) = tuple_members(type_name) {
+                    CairoType::Tuple(
+                        members
+                            .into_iter()
+                            .map(|member| self.resolve(member))
+                            .collect::<Result<_>>()?,
+                    )
+                } else if self.defined_types.contains(type_name) {
+                    CairoType::Named(type_ident(type_name))
+                } else {
+                    anyhow::bail!("unsupported type: {}", type_name)
+                }
+            }
+        })
+    }
+
+    fn is_builtin(&self, type_name: &str) -> bool {
+        !matches!(self.resolve(type_name), Ok(CairoType::Named(_)) | Err(_))
+    }
+
+    fn collect(&mut self, entries: &[AbiEntry]) {
+        for entry in entries.iter() {
+            match entry {
+                AbiEntry::Function(function) => self.functions.push(function.clone()),
+                AbiEntry::Struct(abi_struct) => {
+                    if self.defined_types.insert(abi_struct.name.clone()) {
+                        self.structs.push(abi_struct.clone());
+                    }
+                }
+                AbiEntry::Enum(abi_enum) => {
+                    if self.defined_types.insert(abi_enum.name.clone()) {
+                        self.enums.push(abi_enum.clone());
+                    }
+                }
+                AbiEntry::Interface(interface) => self.collect(&interface.items),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Whether a function only reads state, in which case it's called instead of invoked.
+pub fn is_view(function: &AbiFunction) -> bool {
+    matches!(function.state_mutability, StateMutability::View)
+}
+
+/// Turns a Cairo type path into an identifier, e.g. `my_contract::Pair::<core::felt252>` into
+/// `PairFelt252`.
+pub fn type_ident(type_name: &str) -> String {
+    let (path, generics) = match type_name.split_once("::<") {
+        Some((path, generics)) => (path, generics.strip_suffix('>')),
+        None => (type_name, None),
+    };
+
+    let mut ident = to_pascal_case(path.rsplit("::").next().unwrap_or(path));
+    if let Some(generics) = generics {
+        let generics = format!("({})", generics);
+        for member in tuple_members(&generics).unwrap_or_default().into_iter() {
+            ident.push_str(&type_ident(member));
+        }
+    }
+
+    ident.retain(|c| c.is_ascii_alphanumeric() || c == '_');
+    ident
+}
+
+pub fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+pub fn to_camel_case(name: &str) -> String {
+    let pascal = to_pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => pascal,
+    }
+}
+
+/// Scarb names artifacts like `package_Contract.contract_class.json`, so the part after the last
+/// underscore is the contract name.
+fn name_from_path(path: &std::path::Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    let stem = file_name.split('.').next()?;
+    let name = stem.rsplit('_').next()?;
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(to_pascal_case(name))
+    }
+}
@@ -0,0 +1,605 @@
+use std::{collections::HashSet, fmt::Write};
+
+use anyhow::Result;
+use clap::Parser;
+use starknet::core::types::contract::{AbiFunction, AbiNamedMember};
+
+use crate::verbosity::VerbosityArgs;
+
+use super::{is_view, AbiModel, AbiSourceArgs, CairoType};
+
+/// Support code included in every generated file, so that bindings only depend on `starknet`.
+const PRELUDE: &str = r#"#[allow(unused_imports)]
+use starknet::{
+    accounts::Call,
+    core::types::{BlockId, BlockTag, FieldElement, FunctionCall},
+    macros::selector,
+    providers::{Provider, ProviderError},
+};
+
+#[derive(Debug)]
+pub enum BindingError {
+    Provider(ProviderError),
+    Decode(String),
+}
+
+impl std::fmt::Display for BindingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Provider(err) => write!(f, "{}", err),
+            Self::Decode(err) => write!(f, "unable to decode result: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for BindingError {}
+
+/// Conversion between Rust values and their Cairo serialization.
+pub trait CairoSerde: Sized {
+    fn serialize(&self, out: &mut Vec<FieldElement>);
+
+    fn deserialize(data: &[FieldElement], offset: &mut usize) -> Result<Self, BindingError>;
+}
+
+fn next_felt(data: &[FieldElement], offset: &mut usize) -> Result<FieldElement, BindingError> {
+    let felt = data
+        .get(*offset)
+        .copied()
+        .ok_or_else(|| BindingError::Decode("unexpected end of data".into()))?;
+    *offset += 1;
+    Ok(felt)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U256 {
+    pub low: u128,
+    pub high: u128,
+}
+
+impl CairoSerde for FieldElement {
+    fn serialize(&self, out: &mut Vec<FieldElement>) {
+        out.push(*self);
+    }
+
+    fn deserialize(data: &[FieldElement], offset: &mut usize) -> Result<Self, BindingError> {
+        next_felt(data, offset)
+    }
+}
+
+impl CairoSerde for bool {
+    fn serialize(&self, out: &mut Vec<FieldElement>) {
+        out.push(if *self {
+            FieldElement::ONE
+        } else {
+            FieldElement::ZERO
+        });
+    }
+
+    fn deserialize(data: &[FieldElement], offset: &mut usize) -> Result<Self, BindingError> {
+        Ok(next_felt(data, offset)? != FieldElement::ZERO)
+    }
+}
+
+macro_rules! impl_unsigned {
+    ($($ty:ty),+) => {
+        $(
+            impl CairoSerde for $ty {
+                fn serialize(&self, out: &mut Vec<FieldElement>) {
+                    out.push(FieldElement::from(*self));
+                }
+
+                fn deserialize(
+                    data: &[FieldElement],
+                    offset: &mut usize,
+                ) -> Result<Self, BindingError> {
+                    let felt = next_felt(data, offset)?;
+                    <$ty>::try_from(felt)
+                        .map_err(|_| BindingError::Decode(format!("{:#x} is out of range", felt)))
+                }
+            }
+        )+
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_signed {
+    ($($ty:ty),+) => {
+        $(
+            impl CairoSerde for $ty {
+                fn serialize(&self, out: &mut Vec<FieldElement>) {
+                    let magnitude = FieldElement::from(self.unsigned_abs());
+                    out.push(if *self < 0 {
+                        FieldElement::ZERO - magnitude
+                    } else {
+                        magnitude
+                    });
+                }
+
+                fn deserialize(
+                    data: &[FieldElement],
+                    offset: &mut usize,
+                ) -> Result<Self, BindingError> {
+                    let felt = next_felt(data, offset)?;
+                    let out_of_range =
+                        || BindingError::Decode(format!("{:#x} is out of range", felt));
+
+                    match u128::try_from(felt) {
+                        Ok(value) => <$ty>::try_from(value).map_err(|_| out_of_range()),
+                        Err(_) => {
+                            // Negative values are represented as `P - |value|`
+                            let magnitude = u128::try_from(FieldElement::ZERO - felt)
+                                .map_err(|_| out_of_range())?;
+                            i128::try_from(magnitude)
+                                .ok()
+                                .and_then(|value| <$ty>::try_from(-value).ok())
+                                .ok_or_else(out_of_range)
+                        }
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_signed!(i8, i16, i32, i64, i128);
+
+impl CairoSerde for U256 {
+    fn serialize(&self, out: &mut Vec<FieldElement>) {
+        self.low.serialize(out);
+        self.high.serialize(out);
+    }
+
+    fn deserialize(data: &[FieldElement], offset: &mut usize) -> Result<Self, BindingError> {
+        Ok(Self {
+            low: u128::deserialize(data, offset)?,
+            high: u128::deserialize(data, offset)?,
+        })
+    }
+}
+
+/// Cairo `ByteArray`.
+impl CairoSerde for String {
+    fn serialize(&self, out: &mut Vec<FieldElement>) {
+        let bytes = self.as_bytes();
+        let words = bytes.chunks_exact(31);
+        let pending = words.remainder();
+
+        out.push(FieldElement::from(bytes.len() / 31));
+        for word in words {
+            // 31 bytes always fit in a felt
+            out.push(FieldElement::from_byte_slice_be(word).unwrap());
+        }
+        out.push(FieldElement::from_byte_slice_be(pending).unwrap());
+        out.push(FieldElement::from(pending.len()));
+    }
+
+    fn deserialize(data: &[FieldElement], offset: &mut usize) -> Result<Self, BindingError> {
+        let word_count = u64::deserialize(data, offset)?;
+
+        let mut bytes = vec![];
+        for _ in 0..word_count {
+            bytes.extend_from_slice(&next_felt(data, offset)?.to_bytes_be()[1..]);
+        }
+
+        let pending = next_felt(data, offset)?;
+        let pending_len = u64::deserialize(data, offset)? as usize;
+        if pending_len > 31 {
+            return Err(BindingError::Decode("invalid ByteArray".into()));
+        }
+        bytes.extend_from_slice(&pending.to_bytes_be()[(32 - pending_len)..]);
+
+        String::from_utf8(bytes).map_err(|err| BindingError::Decode(err.to_string()))
+    }
+}
+
+impl CairoSerde for () {
+    fn serialize(&self, _out: &mut Vec<FieldElement>) {}
+
+    fn deserialize(_data: &[FieldElement], _offset: &mut usize) -> Result<Self, BindingError> {
+        Ok(())
+    }
+}
+
+impl<T: CairoSerde> CairoSerde for Vec<T> {
+    fn serialize(&self, out: &mut Vec<FieldElement>) {
+        out.push(FieldElement::from(self.len()));
+        for item in self.iter() {
+            item.serialize(out);
+        }
+    }
+
+    fn deserialize(data: &[FieldElement], offset: &mut usize) -> Result<Self, BindingError> {
+        let len = u64::deserialize(data, offset)?;
+        (0..len).map(|_| T::deserialize(data, offset)).collect()
+    }
+}
+
+impl<T: CairoSerde> CairoSerde for Option<T> {
+    fn serialize(&self, out: &mut Vec<FieldElement>) {
+        match self {
+            Some(value) => {
+                out.push(FieldElement::ZERO);
+                value.serialize(out);
+            }
+            None => out.push(FieldElement::ONE),
+        }
+    }
+
+    fn deserialize(data: &[FieldElement], offset: &mut usize) -> Result<Self, BindingError> {
+        match u64::deserialize(data, offset)? {
+            0 => Ok(Some(T::deserialize(data, offset)?)),
+            1 => Ok(None),
+            index => Err(BindingError::Decode(format!("invalid Option variant: {}", index))),
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($index:tt $name:ident),+) => {
+        impl<$($name: CairoSerde),+> CairoSerde for ($($name,)+) {
+            fn serialize(&self, out: &mut Vec<FieldElement>) {
+                $(self.$index.serialize(out);)+
+            }
+
+            fn deserialize(data: &[FieldElement], offset: &mut usize) -> Result<Self, BindingError> {
+                Ok(($($name::deserialize(data, offset)?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!(0 A);
+impl_tuple!(0 A, 1 B);
+impl_tuple!(0 A, 1 B, 2 C);
+impl_tuple!(0 A, 1 B, 2 C, 3 D);
+impl_tuple!(0 A, 1 B, 2 C, 3 D, 4 E);
+impl_tuple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F);
+"#;
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+#[derive(Debug, Parser)]
+pub struct RustBindings {
+    #[clap(flatten)]
+    source: AbiSourceArgs,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl RustBindings {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let (abi, name) = self.source.load().await?;
+        let model = AbiModel::new(&abi);
+
+        let code = generate(&model, &name)?;
+        self.source.write_output(&code)
+    }
+}
+
+fn generate(model: &AbiModel, name: &str) -> Result<String> {
+    let mut code = String::new();
+
+    writeln!(
+        code,
+        "// Generated by `starkli generate rust-bindings`. Do not edit manually.\n"
+    )?;
+    code.push_str(PRELUDE);
+
+    for abi_struct in model.structs.iter() {
+        let ident = super::type_ident(&abi_struct.name);
+
+        writeln!(code, "\n#[derive(Debug, Clone, PartialEq, Eq)]")?;
+        writeln!(code, "pub struct {} {{", ident)?;
+        for member in abi_struct.members.iter() {
+            writeln!(
+                code,
+                "    pub {}: {},",
+                rust_ident(&member.name),
+                rust_type(&model.resolve(&member.r#type)?)
+            )?;
+        }
+        writeln!(code, "}}\n")?;
+
+        writeln!(code, "impl CairoSerde for {} {{", ident)?;
+        writeln!(
+            code,
+            "    fn serialize(&self, out: &mut Vec<FieldElement>) {{"
+        )?;
+        for member in abi_struct.members.iter() {
+            writeln!(
+                code,
+                "        self.{}.serialize(out);",
+                rust_ident(&member.name)
+            )?;
+        }
+        writeln!(code, "    }}\n")?;
+        writeln!(
+            code,
+            "    fn deserialize(data: &[FieldElement], offset: &mut usize) -> Result<Self, BindingError> {{"
+        )?;
+        writeln!(code, "        Ok(Self {{")?;
+        for member in abi_struct.members.iter() {
+            writeln!(
+                code,
+                "            {}: CairoSerde::deserialize(data, offset)?,",
+                rust_ident(&member.name)
+            )?;
+        }
+        writeln!(code, "        }})")?;
+        writeln!(code, "    }}")?;
+        writeln!(code, "}}")?;
+    }
+
+    for abi_enum in model.enums.iter() {
+        let ident = super::type_ident(&abi_enum.name);
+
+        writeln!(code, "\n#[derive(Debug, Clone, PartialEq, Eq)]")?;
+        writeln!(code, "pub enum {} {{", ident)?;
+        for variant in abi_enum.variants.iter() {
+            match model.resolve(&variant.r#type)? {
+                CairoType::Unit => writeln!(code, "    {},", rust_ident(&variant.name))?,
+                data => writeln!(
+                    code,
+                    "    {}({}),",
+                    rust_ident(&variant.name),
+                    rust_type(&data)
+                )?,
+            }
+        }
+        writeln!(code, "}}\n")?;
+
+        writeln!(code, "impl CairoSerde for {} {{", ident)?;
+        writeln!(
+            code,
+            "    fn serialize(&self, out: &mut Vec<FieldElement>) {{"
+        )?;
+        writeln!(code, "        match self {{")?;
+        for (index, variant) in abi_enum.variants.iter().enumerate() {
+            let variant_ident = rust_ident(&variant.name);
+            match model.resolve(&variant.r#type)? {
+                CairoType::Unit => writeln!(
+                    code,
+                    "            Self::{} => out.push(FieldElement::from({}u64)),",
+                    variant_ident, index
+                )?,
+                _ => {
+                    writeln!(code, "            Self::{}(value) => {{", variant_ident)?;
+                    writeln!(
+                        code,
+                        "                out.push(FieldElement::from({}u64));",
+                        index
+                    )?;
+                    writeln!(code, "                value.serialize(out);")?;
+                    writeln!(code, "            }}")?;
+                }
+            }
+        }
+        writeln!(code, "        }}")?;
+        writeln!(code, "    }}\n")?;
+        writeln!(
+            code,
+            "    fn deserialize(data: &[FieldElement], offset: &mut usize) -> Result<Self, BindingError> {{"
+        )?;
+        writeln!(code, "        match u64::deserialize(data, offset)? {{")?;
+        for (index, variant) in abi_enum.variants.iter().enumerate() {
+            let variant_ident = rust_ident(&variant.name);
+            match model.resolve(&variant.r#type)? {
+                CairoType::Unit => writeln!(
+                    code,
+                    "            {} => Ok(Self::{}),",
+                    index, variant_ident
+                )?,
+                _ => writeln!(
+                    code,
+                    "            {} => Ok(Self::{}(CairoSerde::deserialize(data, offset)?)),",
+                    index, variant_ident
+                )?,
+            }
+        }
+        writeln!(
+            code,
+            "            index => Err(BindingError::Decode(format!(\"invalid {} variant: {{}}\", index))),",
+            ident
+        )?;
+        writeln!(code, "        }}")?;
+        writeln!(code, "    }}")?;
+        writeln!(code, "}}")?;
+    }
+
+    writeln!(code, "\npub struct {}<P> {{", name)?;
+    writeln!(code, "    pub address: FieldElement,")?;
+    writeln!(code, "    pub provider: P,")?;
+    writeln!(code, "}}\n")?;
+    writeln!(code, "impl<P> {}<P>", name)?;
+    writeln!(code, "where")?;
+    writeln!(code, "    P: Provider + Sync,")?;
+    writeln!(code, "{{")?;
+    writeln!(
+        code,
+        "    pub fn new(address: FieldElement, provider: P) -> Self {{"
+    )?;
+    writeln!(code, "        Self {{ address, provider }}")?;
+    writeln!(code, "    }}")?;
+
+    writeln!(
+        code,
+        "\n    /// Serializes the constructor arguments for deploying the contract."
+    )?;
+    writeln!(
+        code,
+        "    pub fn constructor_calldata({}) -> Vec<FieldElement> {{",
+        params(model, &model.constructor)?
+    )?;
+    write_serialize_inputs(&mut code, &model.constructor)?;
+    writeln!(code, "        __calldata")?;
+    writeln!(code, "    }}")?;
+
+    // Interfaces can list the same function more than once
+    let mut generated = HashSet::new();
+    for function in model.functions.iter() {
+        if !generated.insert(function.name.as_str()) {
+            continue;
+        }
+
+        if is_view(function) {
+            write_view_function(&mut code, model, function)?;
+        } else {
+            write_external_function(&mut code, model, function)?;
+        }
+    }
+
+    writeln!(code, "}}")?;
+
+    Ok(code)
+}
+
+fn write_view_function(code: &mut String, model: &AbiModel, function: &AbiFunction) -> Result<()> {
+    let output_type = match function.outputs.as_slice() {
+        [] => CairoType::Unit,
+        [output] => model.resolve(&output.r#type)?,
+        outputs => CairoType::Tuple(
+            outputs
+                .iter()
+                .map(|output| model.resolve(&output.r#type))
+                .collect::<Result<_>>()?,
+        ),
+    };
+
+    writeln!(code, "\n    /// Calls `{}`.", function.name)?;
+    writeln!(
+        code,
+        "    pub async fn {}(&self{}) -> Result<{}, BindingError> {{",
+        rust_ident(&function.name),
+        method_params(model, &function.inputs)?,
+        rust_type(&output_type)
+    )?;
+    write_serialize_inputs(code, &function.inputs)?;
+    writeln!(code, "        let __result = self")?;
+    writeln!(code, "            .provider")?;
+    writeln!(code, "            .call(")?;
+    writeln!(code, "                FunctionCall {{")?;
+    writeln!(code, "                    contract_address: self.address,")?;
+    writeln!(
+        code,
+        "                    entry_point_selector: selector!(\"{}\"),",
+        function.name
+    )?;
+    writeln!(code, "                    calldata: __calldata,")?;
+    writeln!(code, "                }},")?;
+    writeln!(code, "                BlockId::Tag(BlockTag::Pending),")?;
+    writeln!(code, "            )")?;
+    writeln!(code, "            .await")?;
+    writeln!(code, "            .map_err(BindingError::Provider)?;")?;
+    writeln!(code, "        CairoSerde::deserialize(&__result, &mut 0)")?;
+    writeln!(code, "    }}")?;
+
+    Ok(())
+}
+
+fn write_external_function(
+    code: &mut String,
+    model: &AbiModel,
+    function: &AbiFunction,
+) -> Result<()> {
+    writeln!(
+        code,
+        "\n    /// Builds a call to `{}`, to be executed through an account.",
+        function.name
+    )?;
+    writeln!(
+        code,
+        "    pub fn {}(&self{}) -> Call {{",
+        rust_ident(&function.name),
+        method_params(model, &function.inputs)?
+    )?;
+    write_serialize_inputs(code, &function.inputs)?;
+    writeln!(code, "        Call {{")?;
+    writeln!(code, "            to: self.address,")?;
+    writeln!(
+        code,
+        "            selector: selector!(\"{}\"),",
+        function.name
+    )?;
+    writeln!(code, "            calldata: __calldata,")?;
+    writeln!(code, "        }}")?;
+    writeln!(code, "    }}")?;
+
+    Ok(())
+}
+
+fn write_serialize_inputs(code: &mut String, inputs: &[AbiNamedMember]) -> Result<()> {
+    if inputs.is_empty() {
+        writeln!(code, "        let __calldata = vec![];")?;
+    } else {
+        writeln!(code, "        let mut __calldata = vec![];")?;
+        for input in inputs.iter() {
+            writeln!(
+                code,
+                "        {}.serialize(&mut __calldata);",
+                rust_ident(&input.name)
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn params(model: &AbiModel, inputs: &[AbiNamedMember]) -> Result<String> {
+    Ok(inputs
+        .iter()
+        .map(|input| {
+            Ok(format!(
+                "{}: &{}",
+                rust_ident(&input.name),
+                rust_type(&model.resolve(&input.r#type)?)
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .join(", "))
+}
+
+fn method_params(model: &AbiModel, inputs: &[AbiNamedMember]) -> Result<String> {
+    let params = params(model, inputs)?;
+    Ok(if params.is_empty() {
+        params
+    } else {
+        format!(", {}", params)
+    })
+}
+
+fn rust_type(cairo_type: &CairoType) -> String {
+    match cairo_type {
+        CairoType::Felt => "FieldElement".into(),
+        CairoType::Bool => "bool".into(),
+        CairoType::Unsigned(bits) => format!("u{}", bits),
+        CairoType::Signed(bits) => format!("i{}", bits),
+        CairoType::U256 => "U256".into(),
+        CairoType::ByteArray => "String".into(),
+        CairoType::Unit => "()".into(),
+        CairoType::Array(item) => format!("Vec<{}>", rust_type(item)),
+        CairoType::Tuple(members) => format!(
+            "({},)",
+            members.iter().map(rust_type).collect::<Vec<_>>().join(", ")
+        ),
+        CairoType::Option(item) => format!("Option<{}>", rust_type(item)),
+        CairoType::Named(ident) => ident.to_owned(),
+    }
+}
+
+fn rust_ident(name: &str) -> String {
+    match name {
+        // These cannot be raw identifiers
+        "self" | "Self" | "super" | "crate" => format!("{}_", name),
+        _ if RUST_KEYWORDS.contains(&name) => format!("r#{}", name),
+        _ => name.to_owned(),
+    }
+}
@@ -0,0 +1,321 @@
+use std::{collections::HashSet, fmt::Write};
+
+use anyhow::Result;
+use clap::Parser;
+use starknet::core::types::contract::{AbiEntry, AbiFunction, AbiNamedMember};
+
+use crate::verbosity::VerbosityArgs;
+
+use super::{is_view, to_camel_case, type_ident, AbiModel, AbiSourceArgs, CairoType};
+
+/// Reserved words that cannot be used as parameter names.
+const TS_KEYWORDS: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+#[derive(Debug, Parser)]
+pub struct TsBindings {
+    #[clap(flatten)]
+    source: AbiSourceArgs,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl TsBindings {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let (abi, name) = self.source.load().await?;
+        let model = AbiModel::new(&abi);
+
+        let code = generate(&abi, &model, &name)?;
+        self.source.write_output(&code)
+    }
+}
+
+fn generate(abi: &[AbiEntry], model: &AbiModel, name: &str) -> Result<String> {
+    let mut code = String::new();
+
+    writeln!(
+        code,
+        "// Generated by `starkli generate ts`. Do not edit manually.\n"
+    )?;
+    writeln!(
+        code,
+        "import {{ Abi, BigNumberish, Call, CallData, CairoCustomEnum, CairoOption, Uint256 }} from \"starknet\";\n"
+    )?;
+
+    // The ABI itself is kept so that starknet.js handles (de)serialization of nested types
+    writeln!(
+        code,
+        "export const ABI = {} as const;\n",
+        serde_json::to_string_pretty(abi)?
+    )?;
+    writeln!(
+        code,
+        "const callData = new CallData(ABI as unknown as Abi);"
+    )?;
+
+    for abi_struct in model.structs.iter() {
+        writeln!(
+            code,
+            "\nexport interface {} {{",
+            type_ident(&abi_struct.name)
+        )?;
+        for member in abi_struct.members.iter() {
+            writeln!(
+                code,
+                "  {}: {};",
+                member.name,
+                ts_type(&model.resolve(&member.r#type)?)
+            )?;
+        }
+        writeln!(code, "}}")?;
+    }
+
+    for abi_enum in model.enums.iter() {
+        let ident = type_ident(&abi_enum.name);
+
+        // starknet.js expects every variant to be listed, with only the active one defined
+        let variant_values = |active: &str, value: &str| {
+            abi_enum
+                .variants
+                .iter()
+                .map(|variant| {
+                    if variant.name == active {
+                        format!("{}: {}", variant.name, value)
+                    } else {
+                        format!("{}: undefined", variant.name)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        writeln!(code, "\nexport type {} = CairoCustomEnum;\n", ident)?;
+        writeln!(code, "export const {} = {{", ident)?;
+        for variant in abi_enum.variants.iter() {
+            match model.resolve(&variant.r#type)? {
+                CairoType::Unit => writeln!(
+                    code,
+                    "  {}: (): CairoCustomEnum => new CairoCustomEnum({{ {} }}),",
+                    variant.name,
+                    variant_values(&variant.name, "{}")
+                )?,
+                data => writeln!(
+                    code,
+                    "  {}: (value: {}): CairoCustomEnum => new CairoCustomEnum({{ {} }}),",
+                    variant.name,
+                    ts_type(&data),
+                    variant_values(&variant.name, "value")
+                )?,
+            }
+        }
+        writeln!(code, "}};")?;
+    }
+
+    writeln!(code, "\nexport const {} = {{", name)?;
+    writeln!(code, "  abi: ABI,\n")?;
+    writeln!(
+        code,
+        "  /** Serializes the constructor arguments for deploying the contract. */"
+    )?;
+    writeln!(
+        code,
+        "  constructorCalldata({}): string[] {{",
+        params(model, &model.constructor)?
+    )?;
+    writeln!(
+        code,
+        "    return callData.compile(\"constructor\", {});",
+        args_object(&model.constructor)
+    )?;
+    writeln!(code, "  }},")?;
+
+    // Interfaces can list the same function more than once
+    let mut generated = HashSet::new();
+    for function in model.functions.iter() {
+        if !generated.insert(function.name.as_str()) {
+            continue;
+        }
+
+        write_function(&mut code, model, function)?;
+    }
+
+    writeln!(code, "}};")?;
+
+    Ok(code)
+}
+
+fn write_function(code: &mut String, model: &AbiModel, function: &AbiFunction) -> Result<()> {
+    let params = params(model, &function.inputs)?;
+
+    if is_view(function) {
+        writeln!(
+            code,
+            "\n  /** Call descriptor for `{}`, to be used with `provider.callContract`. */",
+            function.name
+        )?;
+    } else {
+        writeln!(
+            code,
+            "\n  /** Call descriptor for `{}`, to be executed through an account. */",
+            function.name
+        )?;
+    }
+    writeln!(
+        code,
+        "  {}(contractAddress: string{}): Call {{",
+        to_camel_case(&function.name),
+        if params.is_empty() {
+            params
+        } else {
+            format!(", {}", params)
+        }
+    )?;
+    writeln!(code, "    return {{")?;
+    writeln!(code, "      contractAddress,")?;
+    writeln!(code, "      entrypoint: \"{}\",", function.name)?;
+    writeln!(
+        code,
+        "      calldata: callData.compile(\"{}\", {}),",
+        function.name,
+        args_object(&function.inputs)
+    )?;
+    writeln!(code, "    }};")?;
+    writeln!(code, "  }},")?;
+
+    if is_view(function) && !function.outputs.is_empty() {
+        let output_type = match function.outputs.as_slice() {
+            [output] => model.resolve(&output.r#type)?,
+            outputs => CairoType::Tuple(
+                outputs
+                    .iter()
+                    .map(|output| model.resolve(&output.r#type))
+                    .collect::<Result<_>>()?,
+            ),
+        };
+
+        writeln!(
+            code,
+            "\n  /** Decodes the result of calling `{}`. */",
+            function.name
+        )?;
+        writeln!(
+            code,
+            "  {}(result: string[]): {} {{",
+            to_camel_case(&format!("parse_{}", function.name)),
+            ts_type(&output_type)
+        )?;
+        writeln!(
+            code,
+            "    return callData.parse(\"{}\", result) as unknown as {};",
+            function.name,
+            ts_type(&output_type)
+        )?;
+        writeln!(code, "  }},")?;
+    }
+
+    Ok(())
+}
+
+fn params(model: &AbiModel, inputs: &[AbiNamedMember]) -> Result<String> {
+    Ok(inputs
+        .iter()
+        .map(|input| {
+            Ok(format!(
+                "{}: {}",
+                ts_ident(&input.name),
+                ts_type(&model.resolve(&input.r#type)?)
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .join(", "))
+}
+
+/// Arguments keyed by their ABI names, which starknet.js matches against the ABI.
+fn args_object(inputs: &[AbiNamedMember]) -> String {
+    if inputs.is_empty() {
+        "{}".into()
+    } else {
+        format!(
+            "{{ {} }}",
+            inputs
+                .iter()
+                .map(|input| format!("{}: {}", input.name, ts_ident(&input.name)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+fn ts_type(cairo_type: &CairoType) -> String {
+    match cairo_type {
+        CairoType::Felt | CairoType::Unsigned(_) | CairoType::Signed(_) => "BigNumberish".into(),
+        CairoType::Bool => "boolean".into(),
+        CairoType::U256 => "Uint256 | BigNumberish".into(),
+        CairoType::ByteArray => "string".into(),
+        CairoType::Unit => "undefined".into(),
+        CairoType::Array(item) => format!("Array<{}>", ts_type(item)),
+        CairoType::Tuple(members) => format!(
+            "[{}]",
+            members.iter().map(ts_type).collect::<Vec<_>>().join(", ")
+        ),
+        CairoType::Option(item) => format!("CairoOption<{}>", ts_type(item)),
+        CairoType::Named(ident) => ident.to_owned(),
+    }
+}
+
+fn ts_ident(name: &str) -> String {
+    let ident = to_camel_case(name);
+    if TS_KEYWORDS.contains(&ident.as_str()) {
+        format!("{}_", ident)
+    } else {
+        ident
+    }
+}
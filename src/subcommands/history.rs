@@ -0,0 +1,328 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use futures_util::{stream, StreamExt};
+use serde_json::Value;
+use starknet::{
+    core::types::{BlockId, ExecutionResult, FieldElement},
+    providers::Provider,
+};
+
+use crate::{
+    accessibility::Status,
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    explorer::{ExplorerArgs, FunctionNames},
+    provider::ExtendedProvider,
+    utils::{decode_execute_calldata, format_address, json_felt},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+/// Maximum number of blocks or transactions being fetched at the same time.
+const SCAN_CONCURRENCY: usize = 16;
+
+#[derive(Debug, Parser)]
+pub struct History {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    explorer: ExplorerArgs,
+    #[clap(
+        long,
+        help = "First block to scan. Required unless an explorer is used, in which case it's only \
+                used for filtering"
+    )]
+    from_block: Option<u64>,
+    #[clap(long, help = "Last block to scan. Defaults to the latest block")]
+    to_block: Option<u64>,
+    #[clap(help = "Sender address")]
+    address: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+struct HistoryEntry {
+    block_number: Option<u64>,
+    hash: FieldElement,
+    tx_type: String,
+    nonce: Option<FieldElement>,
+    calldata: Vec<FieldElement>,
+    reverted: bool,
+}
+
+impl History {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = felt_decoder
+            .decode_single_with_addr_fallback(&self.address)
+            .await?;
+
+        let explorer = self.explorer.into_client(provider.clone()).await?;
+        let mut function_names = FunctionNames::new(&provider, explorer.as_ref());
+        let mut found = 0usize;
+        let mut failed = 0usize;
+
+        match &explorer {
+            Some(explorer) => {
+                eprintln!(
+                    "Fetching transactions sent by {} from {}...",
+                    format_address(address).bright_yellow(),
+                    explorer.explorer()
+                );
+
+                let hashes = explorer.account_transactions(address).await?;
+
+                // Entries are printed as soon as each one is fetched so that a slow transaction
+                // doesn't hold back the others
+                let mut entries = stream::iter(hashes.iter().map(|hash| {
+                    let provider = provider.clone();
+                    let hash = *hash;
+                    async move { (hash, fetch_entry(&provider, hash, address).await) }
+                }))
+                .buffer_unordered(SCAN_CONCURRENCY);
+
+                while let Some((hash, entry)) = entries.next().await {
+                    let entry = match entry {
+                        Ok(Some(entry)) => entry,
+                        Ok(None) => continue,
+                        Err(err) => {
+                            failed += 1;
+                            eprintln!(
+                                "{}",
+                                format!("Failed to fetch transaction {:#064x}: {}", hash, err)
+                                    .red()
+                            );
+                            continue;
+                        }
+                    };
+
+                    let in_range = match entry.block_number {
+                        Some(block_number) => {
+                            !matches!(self.from_block, Some(from) if block_number < from)
+                                && !matches!(self.to_block, Some(to) if block_number > to)
+                        }
+                        None => self.to_block.is_none(),
+                    };
+                    if in_range {
+                        print_entry(&entry, &mut function_names).await;
+                        found += 1;
+                    }
+                }
+            }
+            None => {
+                let from_block = self.from_block.ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "--from-block is required when transactions are found by scanning blocks"
+                    )
+                })?;
+                let to_block = match self.to_block {
+                    Some(to_block) => to_block,
+                    None => provider.block_number().await?,
+                };
+                if from_block > to_block {
+                    anyhow::bail!("invalid block range: {} is after {}", from_block, to_block);
+                }
+
+                eprintln!(
+                    "Scanning blocks {} to {} for transactions sent by {}...",
+                    format!("{}", from_block).bright_yellow(),
+                    format!("{}", to_block).bright_yellow(),
+                    format_address(address).bright_yellow()
+                );
+
+                let blocks = (from_block..=to_block).collect::<Vec<_>>();
+
+                // Entries are printed as soon as each block is scanned so that long ranges show
+                // progress, and a slow block doesn't hold back the ones after it
+                let mut scanned = stream::iter(blocks.iter().map(|block| {
+                    let provider = provider.clone();
+                    let block = *block;
+                    async move { (block, scan_block(&provider, block, address).await) }
+                }))
+                .buffer_unordered(SCAN_CONCURRENCY);
+
+                while let Some((block, entries)) = scanned.next().await {
+                    let entries = match entries {
+                        Ok(entries) => entries,
+                        Err(err) => {
+                            failed += 1;
+                            eprintln!(
+                                "{}",
+                                format!("Failed to scan block {}: {}", block, err).red()
+                            );
+                            continue;
+                        }
+                    };
+
+                    function_names.prefetch(&called_contracts(&entries)).await;
+                    for entry in entries.iter() {
+                        print_entry(entry, &mut function_names).await;
+                        found += 1;
+                    }
+                }
+            }
+        }
+
+        eprintln!(
+            "Found {} transaction(s). Note that account deployment transactions are not included.",
+            format!("{}", found).bright_yellow()
+        );
+
+        if failed > 0 {
+            anyhow::bail!("{} item(s) could not be fetched", failed);
+        }
+
+        Ok(())
+    }
+}
+
+async fn scan_block(
+    provider: &ExtendedProvider,
+    block_number: u64,
+    address: FieldElement,
+) -> Result<Vec<HistoryEntry>> {
+    // Transactions are handled as JSON to uniformly cover all types and versions
+    let block = serde_json::to_value(
+        provider
+            .get_block_with_txs(BlockId::Number(block_number))
+            .await?,
+    )?;
+
+    let mut entries = vec![];
+    if let Some(transactions) = block.get("transactions").and_then(|txs| txs.as_array()) {
+        for tx in transactions.iter() {
+            if json_felt(tx, "sender_address") != Some(address) {
+                continue;
+            }
+
+            let hash = json_felt(tx, "transaction_hash")
+                .ok_or_else(|| anyhow::anyhow!("missing transaction hash"))?;
+
+            let reverted = matches!(
+                provider
+                    .get_transaction_receipt(hash)
+                    .await?
+                    .execution_result(),
+                ExecutionResult::Reverted { .. }
+            );
+
+            entries.push(parse_entry(tx, hash, Some(block_number), reverted));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Fetches a transaction listed by the explorer, or `None` if it's not sent by `address`, as
+/// explorers also list transactions merely involving the account.
+async fn fetch_entry(
+    provider: &ExtendedProvider,
+    hash: FieldElement,
+    address: FieldElement,
+) -> Result<Option<HistoryEntry>> {
+    let tx = serde_json::to_value(provider.get_transaction_by_hash(hash).await?)?;
+    if json_felt(&tx, "sender_address") != Some(address) {
+        return Ok(None);
+    }
+
+    let receipt = provider.get_transaction_receipt(hash).await?;
+
+    let reverted = matches!(receipt.execution_result(), ExecutionResult::Reverted { .. });
+    let block_number = serde_json::to_value(receipt)?
+        .get("block_number")
+        .and_then(|value| value.as_u64());
+
+    Ok(Some(parse_entry(&tx, hash, block_number, reverted)))
+}
+
+fn parse_entry(
+    tx: &Value,
+    hash: FieldElement,
+    block_number: Option<u64>,
+    reverted: bool,
+) -> HistoryEntry {
+    HistoryEntry {
+        block_number,
+        hash,
+        tx_type: tx
+            .get("type")
+            .and_then(|value| value.as_str())
+            .unwrap_or("UNKNOWN")
+            .to_owned(),
+        nonce: json_felt(tx, "nonce"),
+        calldata: tx
+            .get("calldata")
+            .and_then(|value| value.as_array())
+            .map(|calldata| {
+                calldata
+                    .iter()
+                    .filter_map(|item| item.as_str())
+                    .filter_map(|item| FieldElement::from_hex_be(item).ok())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        reverted,
+    }
+}
+
+/// Contracts whose function names are shown for the entries.
+fn called_contracts(entries: &[HistoryEntry]) -> Vec<FieldElement> {
+    entries
+        .iter()
+        .filter_map(|entry| decode_execute_calldata(&entry.calldata))
+        .filter_map(|calls| calls.first().map(|call| call.to))
+        .collect()
+}
+
+async fn print_entry(entry: &HistoryEntry, function_names: &mut FunctionNames<'_>) {
+    let status = if entry.reverted {
+        Status::Failure.label("REVERTED")
+    } else {
+        Status::Success.label("SUCCEEDED")
+    };
+
+    println!(
+        "Block {} nonce {}: {} {} ({})",
+        match entry.block_number {
+            Some(block_number) => format!("{}", block_number),
+            None => "pending".to_owned(),
+        },
+        match entry.nonce {
+            Some(nonce) => format!("{}", nonce),
+            None => "-".to_owned(),
+        },
+        format!("{:#064x}", entry.hash).bright_yellow(),
+        status,
+        entry.tx_type
+    );
+
+    if let Some(calls) = decode_execute_calldata(&entry.calldata) {
+        if let Some(first_call) = calls.first() {
+            let function = match function_names
+                .lookup(first_call.to, first_call.selector)
+                .await
+            {
+                Some(name) => name,
+                None => format!("{:#064x}", first_call.selector),
+            };
+
+            println!(
+                "    call {:#064x} {}{}",
+                first_call.to,
+                function,
+                if calls.len() > 1 {
+                    format!(" (+{} more)", calls.len() - 1)
+                } else {
+                    String::new()
+                }
+            );
+        }
+    }
+}
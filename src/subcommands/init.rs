@@ -0,0 +1,239 @@
+use std::{io::Write, str::FromStr};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::{types::FieldElement, utils::parse_cairo_short_string},
+    macros::short_string,
+    providers::Provider,
+    signers::SigningKey,
+};
+use url::Url;
+
+use crate::{
+    account::AccountConfig,
+    network::Network,
+    profile::{FreeProviderVendor, NetworkProvider, Profiles, DEFAULT_PROFILE_NAME},
+    provider::{randome_free_provider, ExtendedProvider},
+    signer::named_keystore_path,
+    utils::format_address,
+};
+
+#[derive(Debug, Parser)]
+pub struct Init {
+    #[clap(
+        long,
+        help = "Replace the keystore and account file if they already exist under the chosen name"
+    )]
+    force: bool,
+}
+
+impl Init {
+    pub async fn run(self) -> Result<()> {
+        let mut profiles = Profiles::load()?;
+
+        if let Some(profile) = profiles.profiles.get(DEFAULT_PROFILE_NAME) {
+            if profile.default_network.is_some() || profile.default_account.is_some() {
+                eprintln!(
+                    "Starkli is already set up with network {} and account {}.",
+                    profile
+                        .default_network
+                        .as_deref()
+                        .unwrap_or("(none)")
+                        .bright_yellow(),
+                    profile
+                        .default_account
+                        .as_deref()
+                        .unwrap_or("(none)")
+                        .bright_yellow()
+                );
+                if !matches!(
+                    prompt("Set up a new network and account anyway? [y/N]: ")?.as_str(),
+                    "y" | "Y" | "yes"
+                ) {
+                    return Ok(());
+                }
+            }
+        }
+
+        eprintln!("This will set up a network, a keystore and an account for Starkli.");
+        eprintln!();
+
+        // Network
+        let network = loop {
+            let answer = prompt("Network to use (mainnet, sepolia) [sepolia]: ")?;
+            let answer = if answer.is_empty() {
+                "sepolia"
+            } else {
+                &answer
+            };
+            match Network::from_str(answer) {
+                Ok(network @ (Network::Mainnet | Network::Sepolia)) => break network,
+                _ => eprintln!("Please enter either mainnet or sepolia."),
+            }
+        };
+        let (network_name, chain_id) = match network {
+            Network::Mainnet => ("Starknet Mainnet", short_string!("SN_MAIN")),
+            _ => ("Starknet Sepolia Testnet", short_string!("SN_SEPOLIA")),
+        };
+
+        // RPC endpoint
+        eprintln!();
+        eprintln!(
+            "Starkli needs a JSON-RPC endpoint. Enter the URL of your own node or of an RPC \
+            provider, or leave it empty to use a free public endpoint, which is rate limited."
+        );
+        let provider = loop {
+            let answer = prompt("RPC endpoint URL: ")?;
+            if answer.is_empty() {
+                break NetworkProvider::Free(randome_free_provider(&[
+                    FreeProviderVendor::Blast,
+                    FreeProviderVendor::Nethermind,
+                ]));
+            }
+
+            let url = match Url::parse(&answer) {
+                Ok(url) => url,
+                Err(err) => {
+                    eprintln!("Invalid URL: {}", err);
+                    continue;
+                }
+            };
+
+            eprintln!("Checking the endpoint...");
+            match ExtendedProvider::new(url.clone(), false).chain_id().await {
+                Ok(endpoint_chain_id) if endpoint_chain_id == chain_id => {
+                    break NetworkProvider::Rpc(url)
+                }
+                Ok(endpoint_chain_id) => eprintln!(
+                    "The endpoint is on chain {}, not {}. Please enter an endpoint for {}.",
+                    format_chain_id(endpoint_chain_id).bright_yellow(),
+                    format_chain_id(chain_id).bright_yellow(),
+                    network
+                ),
+                Err(err) => eprintln!("Unable to reach the endpoint: {}", err),
+            }
+        };
+
+        // Keystore and account file, sharing one name
+        eprintln!();
+        let accounts_folder = Profiles::get_accounts_folder()?;
+        let (name, keystore_path, account_path) = loop {
+            let answer = prompt(&format!(
+                "Name for the new signer and account [{}]: ",
+                network
+            ))?;
+            let name = if answer.is_empty() {
+                network.to_string()
+            } else {
+                answer
+            };
+
+            let keystore_path = match named_keystore_path(&name) {
+                Ok(path) => path,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    continue;
+                }
+            };
+            let account_path = accounts_folder.join(format!("{}.json", name));
+
+            if !self.force && (keystore_path.exists() || account_path.exists()) {
+                eprintln!(
+                    "A keystore or account named {} already exists. Choose another name, or run \
+                    again with --force to replace it.",
+                    name
+                );
+                continue;
+            }
+
+            break (name, keystore_path, account_path);
+        };
+
+        let password = loop {
+            let password = rpassword::prompt_password("Enter a password for the keystore: ")?;
+            if password.is_empty() {
+                eprintln!("The password cannot be empty.");
+                continue;
+            }
+            if rpassword::prompt_password("Confirm the password: ")? != password {
+                eprintln!("Passwords do not match.");
+                continue;
+            }
+            break password;
+        };
+
+        let key = SigningKey::from_random();
+        if let Some(parent) = keystore_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        key.save_as_keystore(&keystore_path, &password)?;
+
+        let account_config = AccountConfig::new_oz(key.verifying_key().scalar());
+        let deployed_address = account_config.deploy_account_address()?;
+
+        std::fs::create_dir_all(&accounts_folder)?;
+        let mut file = std::fs::File::create(&account_path)?;
+        serde_json::to_writer_pretty(&mut file, &account_config)?;
+        file.write_all(b"\n")?;
+
+        // Profile
+        let profile = profiles.default_profile_mut();
+        profile.networks.insert(
+            network.to_string(),
+            crate::profile::Network {
+                name: Some(network_name.into()),
+                chain_id,
+                is_integration: false,
+                provider,
+                udc_address: None,
+                eth_token_address: None,
+                strk_token_address: None,
+                aggregator_address: None,
+                max_fee_ceiling: None,
+                account: None,
+            },
+        );
+        profile.default_network = Some(network.to_string());
+        profile.default_account = Some(name.clone());
+        profiles.save()?;
+
+        eprintln!();
+        eprintln!("Created keystore: {}", keystore_path.display());
+        eprintln!("Created account config file: {}", account_path.display());
+        eprintln!(
+            "Default network set to {} and default account set to {}",
+            network.to_string().bright_yellow(),
+            name.bright_yellow()
+        );
+        eprintln!();
+        eprintln!(
+            "Once deployed, the account will be available at:\n    {}",
+            format_address(deployed_address).bright_yellow()
+        );
+        eprintln!();
+        eprintln!(
+            "Send some ETH to this address to pay for the deployment, then deploy the account \
+            by running:\n    {}",
+            format!("starkli account deploy {} --keystore-name {}", name, name).bright_yellow()
+        );
+
+        Ok(())
+    }
+}
+
+fn prompt(question: &str) -> Result<String> {
+    eprint!("{}", question);
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer)? == 0 {
+        anyhow::bail!("setup aborted");
+    }
+    Ok(answer.trim().to_owned())
+}
+
+fn format_chain_id(chain_id: FieldElement) -> String {
+    parse_cairo_short_string(&chain_id).unwrap_or_else(|_| format!("{:#x}", chain_id))
+}
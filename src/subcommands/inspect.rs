@@ -0,0 +1,164 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use clap::Parser;
+use colored::Colorize;
+use num_bigint::ToBigInt;
+use starknet::{
+    core::types::{BlockId, BlockTag, ContractClass, FieldElement, StarknetError},
+    macros::selector,
+    providers::{Provider, ProviderError},
+};
+
+use crate::{
+    address_book::AddressBookResolver, decode::FeltDecoder, provider::ExtendedProvider,
+    token::fetch_balance, utils::format_address, verbosity::VerbosityArgs, ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Inspect {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(help = "Contract address")]
+    address: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Inspect {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = felt_decoder
+            .decode_single_with_addr_fallback(&self.address)
+            .await?;
+
+        println!("Address:    {}", format_address(address).bright_yellow());
+
+        // TODO: allow custom block
+        let class_hash = match provider
+            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+            .await
+        {
+            Ok(class_hash) => class_hash,
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
+                println!("Deployed:   {}", "no".bright_magenta());
+                print_balances(&provider, address).await;
+                return Ok(());
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        println!("Deployed:   {}", "yes".bright_yellow());
+        println!(
+            "Class hash: {}",
+            format!("{:#064x}", class_hash).bright_yellow()
+        );
+
+        let class = provider
+            .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
+            .await?;
+        let (cairo_version, external_selectors) = match &class {
+            ContractClass::Sierra(class) => (
+                "Cairo 1",
+                class
+                    .entry_points_by_type
+                    .external
+                    .iter()
+                    .map(|entry| entry.selector)
+                    .collect::<HashSet<_>>(),
+            ),
+            ContractClass::Legacy(class) => (
+                "Cairo 0",
+                class
+                    .entry_points_by_type
+                    .external
+                    .iter()
+                    .map(|entry| entry.selector)
+                    .collect::<HashSet<_>>(),
+            ),
+        };
+        println!("Class type: {}", cairo_version.bright_yellow());
+
+        let traits = guess_traits(&external_selectors);
+        println!(
+            "Looks like: {}",
+            if traits.is_empty() {
+                "unknown".to_owned()
+            } else {
+                traits.join(", ")
+            }
+            .bright_yellow()
+        );
+
+        print_balances(&provider, address).await;
+
+        Ok(())
+    }
+}
+
+/// Guesses what the contract is from the external entrypoints it exposes. Both snake_case and
+/// camelCase names are checked as the ecosystem uses both.
+fn guess_traits(selectors: &HashSet<FieldElement>) -> Vec<&'static str> {
+    let has_any = |candidates: &[FieldElement]| {
+        candidates
+            .iter()
+            .any(|selector| selectors.contains(selector))
+    };
+
+    let mut traits = vec![];
+
+    if has_any(&[selector!("__execute__")]) && has_any(&[selector!("__validate__")]) {
+        traits.push("account");
+    }
+
+    if has_any(&[
+        selector!("get_implementation"),
+        selector!("getImplementation"),
+        selector!("get_implementation_hash"),
+        selector!("implementation"),
+    ]) || selectors.contains(&selector!("__default__"))
+    {
+        traits.push("proxy");
+    } else if has_any(&[selector!("upgrade")]) {
+        traits.push("upgradeable");
+    }
+
+    if has_any(&[selector!("balance_of"), selector!("balanceOf")])
+        && has_any(&[selector!("total_supply"), selector!("totalSupply")])
+        && has_any(&[selector!("transfer")])
+        && has_any(&[selector!("decimals")])
+    {
+        traits.push("ERC-20");
+    } else if has_any(&[selector!("owner_of"), selector!("ownerOf")])
+        && has_any(&[selector!("balance_of"), selector!("balanceOf")])
+    {
+        traits.push("ERC-721");
+    }
+
+    traits
+}
+
+async fn print_balances(provider: &ExtendedProvider, address: FieldElement) {
+    for (symbol, token) in [
+        ("ETH", provider.eth_token_address()),
+        ("STRK", provider.strk_token_address()),
+    ] {
+        // The token might not be deployed on all networks, so failures are not fatal here
+        let balance = match fetch_balance(provider, token, address).await {
+            Ok(balance) => format!(
+                "{} {}",
+                // `to_bigint()` from `BigUint` always returns `Some`.
+                BigDecimal::new(balance.to_bigint().unwrap(), 18),
+                symbol
+            )
+            .bright_yellow(),
+            Err(_) => "n/a".bright_magenta(),
+        };
+        println!("{:11} {}", format!("{}:", symbol), balance);
+    }
+}
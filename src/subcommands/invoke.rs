@@ -1,21 +1,52 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
 use colored_json::{ColorMode, Output};
+use num_bigint::BigUint;
 use starknet::{
-    accounts::{Account, Call},
-    core::types::FieldElement,
-    macros::felt,
+    accounts::{Account, Call, ConnectedAccount, ExecutionEncoder},
+    core::types::{
+        BroadcastedTransaction, ExecuteInvocation, FieldElement, SimulatedTransaction,
+        TransactionTrace,
+    },
+    macros::{felt, selector},
+    providers::Provider,
 };
+use url::Url;
 
 use crate::{
-    account::AccountArgs,
+    abi::{function_inputs, AbiEncoder},
+    abi_cache::CachedAbi,
+    account::{impersonated_account, AccountArgs},
     address_book::AddressBookResolver,
+    amount::format_ether,
     decode::FeltDecoder,
-    fee::{FeeArgs, FeeSetting},
-    utils::watch_tx,
+    devnet::impersonate_account,
+    error::decode_account_error,
+    explorer::{ExplorerLink, ExplorerLinkArgs, FunctionNames},
+    fee::{check_max_fee_ceiling, estimate_fee_skip_validate, FeeArgs, FeeSetting},
+    hook::WatchHookArgs,
+    messages::message,
+    nonce::NoncePreview,
+    path::ExpandedPathbufParser,
+    preflight::PreflightArgs,
+    progress::ProgressArgs,
+    provider::ExtendedProvider,
+    select::SelectArgs,
+    send_schedule::SendScheduleArgs,
+    simulation_compare::compare_simulations,
+    simulation_summary::print_account_summary,
+    token::TokenRegistry,
+    transaction_file::{ExportedCall, ExportedInvoke, ExportedTransaction, TransactionFile},
+    utils::{diff_json, json_felt, print_receipt, read_stdin_calldata, send_with_retry},
     verbosity::VerbosityArgs,
     ProviderArgs,
 };
@@ -26,14 +57,93 @@ pub struct Invoke {
     provider: ProviderArgs,
     #[clap(flatten)]
     account: AccountArgs,
+    #[clap(
+        long,
+        conflicts_with = "account",
+        help = "Send the transaction as this address on a forked devnet, without its private key"
+    )]
+    impersonate: Option<String>,
+    #[clap(
+        long,
+        conflicts_with_all = ["account", "impersonate"],
+        help = "Simulate or estimate the transaction as this address without its private key. \
+                Account validation is skipped"
+    )]
+    sender: Option<String>,
     #[clap(flatten)]
     fee: FeeArgs,
     #[clap(long, help = "Simulate the transaction only")]
     simulate: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Skip account validation when simulating the transaction"
+    )]
+    skip_validate: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Skip charging the fee when simulating the transaction"
+    )]
+    skip_fee_charge: bool,
+    #[clap(
+        long,
+        requires = "simulate",
+        help = "Run the same simulation against another JSON-RPC endpoint and show the differences"
+    )]
+    compare_provider: Option<Url>,
+    #[clap(
+        long,
+        requires = "simulate",
+        conflicts_with_all = ["compare_provider", "select"],
+        value_parser = ExpandedPathbufParser,
+        help = "Compare the simulation with the one saved in this file, highlighting changes in \
+                fee, events and state diff. The simulation is saved to the file if it doesn't exist"
+    )]
+    baseline: Option<PathBuf>,
+    #[clap(
+        long,
+        requires = "baseline",
+        help = "Replace the baseline with the new simulation after comparing"
+    )]
+    update_baseline: bool,
+    #[clap(
+        long,
+        conflicts_with_all = ["simulate", "export"],
+        help = "Simulate the transaction first, and only send it if the simulation succeeds with a \
+                fee within --simulation-fee-tolerance of the max fee"
+    )]
+    require_simulation_success: bool,
+    #[clap(
+        long,
+        requires = "require_simulation_success",
+        default_value = "100",
+        help = "Highest simulated fee accepted, as a percentage of the max fee"
+    )]
+    simulation_fee_tolerance: u64,
     #[clap(long, help = "Provide transaction nonce manually")]
     nonce: Option<FieldElement>,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        conflicts_with_all = ["simulate", "watch"],
+        help = "Write the unsigned transaction to a file for signing with `starkli sign-tx` instead of sending it"
+    )]
+    export: Option<PathBuf>,
+    #[clap(
+        long,
+        requires = "export",
+        help = "Also show the exported transaction as a QR code on the terminal"
+    )]
+    qr: bool,
     #[clap(long, short, help = "Wait for the transaction to confirm")]
     watch: bool,
+    #[clap(
+        long,
+        requires = "watch",
+        help = "Print the transaction receipt once the transaction is confirmed"
+    )]
+    print_receipt: bool,
     #[clap(
         long,
         env = "STARKNET_POLL_INTERVAL",
@@ -41,80 +151,133 @@ pub struct Invoke {
         help = "Transaction result poll interval in milliseconds"
     )]
     poll_interval: u64,
+    #[clap(
+        long,
+        help = "Append calldata read from stdin to the last call, either as a JSON array or one \
+                value per line"
+    )]
+    stdin_calldata: bool,
     #[clap(help = "One or more contract calls. See documentation for more details")]
     calls: Vec<String>,
     #[clap(flatten)]
+    preflight: PreflightArgs,
+    #[clap(flatten)]
+    schedule: SendScheduleArgs,
+    #[clap(flatten)]
+    explorer: ExplorerLinkArgs,
+    #[clap(flatten)]
+    hooks: WatchHookArgs,
+    #[clap(flatten)]
+    progress: ProgressArgs,
+    #[clap(flatten)]
+    select: SelectArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
 impl Invoke {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
+        let progress = self.progress.reporter();
 
+        let force_fee = self.fee.is_forced();
         let fee_setting = self.fee.into_setting()?;
         if self.simulate && fee_setting.is_estimate_only() {
             anyhow::bail!("--simulate cannot be used with --estimate-only");
         }
+        if !self.select.is_empty() && !self.simulate {
+            anyhow::bail!("--select can only be used with --simulate");
+        }
+        if self.sender.is_some() && !self.simulate && !fee_setting.is_estimate_only() {
+            anyhow::bail!("--sender can only be used with --simulate or --estimate-only");
+        }
+        // Signatures from the throwaway signer used with `--sender` would never pass validation
+        let signerless = self.sender.is_some();
+        let skip_validate = self.skip_validate || signerless;
+        let send_conditions = self.schedule.into_conditions()?;
+        if !send_conditions.is_empty() && (self.simulate || self.export.is_some()) {
+            anyhow::bail!(
+                "--send-at, --send-after-block and --max-gas-price cannot be used with --simulate \
+                or --export"
+            );
+        }
 
         let provider = Arc::new(self.provider.into_provider()?);
         let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
 
-        // Parses and resolves the calls
-        let calls = {
-            // TODO: show more helpful message
-            let unexpected_end_of_args = || anyhow::anyhow!("unexpected end of arguments");
-
-            let mut buffer = vec![];
+        let mut raw_calls = self.calls;
+        if self.stdin_calldata {
+            raw_calls.extend(read_stdin_calldata()?);
+        }
+        let mut calls = felt_decoder.decode_calls(raw_calls).await?;
 
-            let mut arg_iter = self.calls.into_iter();
+        // A single function given without arguments
+        if let [call] = calls.as_mut_slice() {
+            if call.calldata.is_empty() && std::io::stdin().is_terminal() {
+                call.calldata = prompt_calldata(&provider, &felt_decoder, call).await?;
+            }
+        }
 
-            while let Some(first_arg) = arg_iter.next() {
-                let contract_address = felt_decoder
-                    .decode_single_with_addr_fallback(&first_arg)
-                    .await?;
+        if calls.is_empty() {
+            anyhow::bail!("empty execution");
+        }
 
-                let next_arg = arg_iter.next().ok_or_else(unexpected_end_of_args)?;
-                let selector = felt_decoder
-                    .decode_single_with_selector_fallback(&next_arg)
-                    .await?;
+        if !fee_setting.is_estimate_only() {
+            self.preflight.check_calls(&provider, &calls).await;
+        }
 
-                let mut calldata = vec![];
-                for arg in &mut arg_iter {
-                    let mut arg = match arg.as_str() {
-                        "/" | "-" | "\\" => break,
-                        _ => felt_decoder.decode(&arg).await?,
-                    };
-                    calldata.append(&mut arg);
-                }
+        // The device would otherwise be asked to sign just for fee estimation
+        let uses_ledger =
+            self.impersonate.is_none() && self.sender.is_none() && self.account.uses_ledger();
 
-                buffer.push(Call {
-                    to: contract_address,
-                    selector,
-                    calldata,
-                });
+        let account = match (self.impersonate, self.sender) {
+            (Some(address), _) => {
+                let address = felt_decoder
+                    .decode_single_with_addr_fallback(&address)
+                    .await?;
+                impersonate_account(&provider, address).await?;
+                impersonated_account(provider.clone(), address).await?
             }
-
-            buffer
+            (None, Some(address)) => {
+                let address = felt_decoder
+                    .decode_single_with_addr_fallback(&address)
+                    .await?;
+                impersonated_account(provider.clone(), address).await?
+            }
+            (None, None) => self.account.into_account(provider.clone()).await?,
         };
 
-        if calls.is_empty() {
-            anyhow::bail!("empty execution");
+        if self.simulate || fee_setting.is_estimate_only() || self.export.is_some() {
+            NoncePreview::fetch(&provider, account.address(), self.nonce)
+                .await?
+                .print();
         }
 
-        let account = self.account.into_account(provider.clone()).await?;
-
-        let execution = account.execute(calls).fee_estimate_multiplier(1.5f64);
+        let execution = account
+            .execute(calls.clone())
+            .fee_estimate_multiplier(1.5f64);
 
         let max_fee = match fee_setting {
             FeeSetting::Manual(fee) => fee,
-            FeeSetting::EstimateOnly | FeeSetting::None => {
-                let estimated_fee = execution.estimate_fee().await?.overall_fee;
+            FeeSetting::EstimateOnly { .. } | FeeSetting::None => {
+                let fee_estimate = if signerless || uses_ledger {
+                    let execution = account.execute(calls.clone());
+                    let execution = match self.nonce {
+                        Some(nonce) => execution.nonce(nonce),
+                        None => execution,
+                    };
+                    estimate_fee_skip_validate(execution).await?
+                } else {
+                    execution
+                        .estimate_fee()
+                        .await
+                        .map_err(decode_account_error)?
+                };
+                let estimated_fee = fee_estimate.overall_fee;
+                progress.fee_estimated(estimated_fee);
 
                 if fee_setting.is_estimate_only() {
-                    println!(
-                        "{} ETH",
-                        format!("{}", estimated_fee.to_big_decimal(18)).bright_yellow(),
-                    );
+                    fee_setting.print_estimate(&fee_estimate)?;
                     return Ok(());
                 }
 
@@ -123,41 +286,493 @@ impl Invoke {
             }
         };
 
+        check_max_fee_ceiling(&provider, max_fee, force_fee)?;
+
         let execution = match self.nonce {
             Some(nonce) => execution.nonce(nonce),
             None => execution,
         };
+        self.preflight.check_max_fee(max_fee);
         let execution = execution.max_fee(max_fee);
 
+        if let Some(export) = self.export {
+            let nonce = match self.nonce {
+                Some(nonce) => nonce,
+                None => account.get_nonce().await?,
+            };
+
+            TransactionFile::new(
+                account.chain_id(),
+                ExportedTransaction::Invoke(ExportedInvoke {
+                    sender_address: account.address(),
+                    max_fee,
+                    nonce,
+                    calldata: account.encode_calls(&calls),
+                    calls: calls.iter().map(ExportedCall::from_call).collect(),
+                }),
+            )
+            .export(&export, self.qr)?;
+            return Ok(());
+        }
+
+        if let Some(compare_provider) = self.compare_provider {
+            let nonce = match self.nonce {
+                Some(nonce) => nonce,
+                None => account.get_nonce().await?,
+            };
+            let request = execution
+                .nonce(nonce)
+                .prepared()?
+                .get_invoke_request(true)
+                .await?;
+
+            return compare_simulations(
+                &provider,
+                compare_provider,
+                BroadcastedTransaction::Invoke(request),
+                skip_validate,
+                self.skip_fee_charge,
+            )
+            .await;
+        }
+
         if self.simulate {
-            let simulation = execution.simulate(false, false).await?;
+            let simulation = execution
+                .simulate(skip_validate, self.skip_fee_charge)
+                .await?;
+
+            if let Some(revert_reason) = revert_reason(&simulation) {
+                let nonce = match self.nonce {
+                    Some(nonce) => nonce,
+                    None => account.get_nonce().await?,
+                };
+                let (index, revert_reason) = find_reverted_call(
+                    &account,
+                    &calls,
+                    nonce,
+                    max_fee,
+                    skip_validate,
+                    revert_reason,
+                )
+                .await?;
+
+                let call = &calls[index];
+                let function_name = FunctionNames::new(&provider, None)
+                    .lookup(call.to, call.selector)
+                    .await;
+
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Execution reverted at call #{} of {} ({} call(s) before it succeeded):",
+                        index + 1,
+                        calls.len(),
+                        index
+                    )
+                    .bright_magenta()
+                );
+                eprintln!(
+                    "  Target:   {}",
+                    format!("{:#064x}", call.to).bright_yellow()
+                );
+                match function_name {
+                    Some(name) => eprintln!(
+                        "  Selector: {} ({:#064x})",
+                        name.bright_yellow(),
+                        call.selector
+                    ),
+                    None => eprintln!(
+                        "  Selector: {}",
+                        format!("{:#064x}", call.selector).bright_yellow()
+                    ),
+                }
+                eprintln!("  Reason:   {}", revert_reason.trim());
+            }
+
             let simulation_json = serde_json::to_value(simulation)?;
+            print_account_summary(&provider, account.address(), &simulation_json).await?;
 
-            let simulation_json =
-                colored_json::to_colored_json(&simulation_json, ColorMode::Auto(Output::StdOut))?;
-            println!("{simulation_json}");
-            return Ok(());
+            if let Some(baseline) = &self.baseline {
+                return compare_with_baseline(baseline, &simulation_json, self.update_baseline);
+            }
+
+            return self.select.print(&simulation_json);
         }
 
-        let invoke_tx = execution.send().await?.transaction_hash;
+        self.preflight
+            .check_balance(&provider, account.address(), max_fee, &calls)
+            .await?;
+
+        if self.require_simulation_success {
+            eprintln!("Simulating the transaction before sending...");
+
+            // Validation is skipped for Ledger accounts to avoid asking the device to sign twice
+            let simulation = execution
+                .simulate(uses_ledger, false)
+                .await
+                .map_err(|err| {
+                    anyhow::anyhow!(
+                        "simulation failed, not sending the transaction: {}",
+                        decode_account_error(err)
+                    )
+                })?;
+            if let Some(revert_reason) = revert_reason(&simulation) {
+                anyhow::bail!(
+                    "simulation reverted, not sending the transaction: {}",
+                    revert_reason.trim()
+                );
+            }
+
+            let simulated_fee = simulation.fee_estimation.overall_fee;
+            let fee_limit = (max_fee * FieldElement::from(self.simulation_fee_tolerance))
+                .floor_div(felt!("100"));
+            if simulated_fee > fee_limit {
+                anyhow::bail!(
+                    "simulated fee of {} ETH is above {}% of the max fee ({} ETH), not sending the \
+                     transaction",
+                    format_ether(simulated_fee),
+                    self.simulation_fee_tolerance,
+                    format_ether(max_fee)
+                );
+            }
+
+            eprintln!(
+                "Simulation succeeded with a fee of {} ETH",
+                format_ether(simulated_fee).bright_yellow()
+            );
+        }
+
+        if uses_ledger {
+            print_call_digest(&provider, &calls).await?;
+        }
+
+        let invoke_tx = if send_conditions.is_empty() {
+            send_with_retry(&provider, account.address(), self.nonce.is_some(), || {
+                execution.send()
+            })
+            .await?
+            .transaction_hash
+        } else {
+            // The nonce is fixed at signing, so any other transaction sent from the account in the
+            // meantime makes this one invalid
+            let nonce = match self.nonce {
+                Some(nonce) => nonce,
+                None => account.get_nonce().await?,
+            };
+            let request = execution
+                .nonce(nonce)
+                .prepared()?
+                .get_invoke_request(false)
+                .await?;
+
+            eprintln!(
+                "Transaction signed with nonce {}. Holding it until {}...",
+                format!("{}", nonce).bright_yellow(),
+                send_conditions
+                    .iter()
+                    .map(|condition| condition.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", then ")
+                    .bright_yellow()
+            );
+            eprintln!(
+                "Keep this process running, and don't send other transactions from the account \
+                until then."
+            );
+            for condition in send_conditions.iter() {
+                condition
+                    .wait(&provider, Duration::from_millis(self.poll_interval))
+                    .await?;
+            }
+
+            provider
+                .add_invoke_transaction(&request)
+                .await
+                .map_err(|err| anyhow::anyhow!("failed to send the held transaction: {}", err))?
+                .transaction_hash
+        };
+        progress.transaction_sent(invoke_tx);
         eprintln!(
-            "Invoke transaction: {}",
-            format!("{:#064x}", invoke_tx).bright_yellow()
+            "{}",
+            message("invoke.sent", &[("hash", &format!("{:#064x}", invoke_tx))])
         );
+        self.explorer
+            .print_link(&provider, ExplorerLink::Transaction(invoke_tx))
+            .await;
 
-        if self.watch {
+        if self.watch || !self.hooks.is_empty() {
             eprintln!(
-                "Waiting for transaction {} to confirm...",
-                format!("{:#064x}", invoke_tx).bright_yellow(),
+                "{}",
+                message("tx.waiting", &[("hash", &format!("{:#064x}", invoke_tx))])
             );
-            watch_tx(
-                &provider,
-                invoke_tx,
-                Duration::from_millis(self.poll_interval),
-            )
-            .await?;
+            self.hooks
+                .watch_tx(
+                    &provider,
+                    invoke_tx,
+                    Duration::from_millis(self.poll_interval),
+                )
+                .await?;
+            progress.transaction_confirmed(invoke_tx);
+
+            if self.print_receipt {
+                print_receipt(&provider, invoke_tx).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Summarizes the calls for review before signing on a Ledger device, which only shows the
+/// transaction hash. Token transfers and approvals are totalled per token.
+async fn print_call_digest(provider: &ExtendedProvider, calls: &[Call]) -> Result<()> {
+    let registry = TokenRegistry::load_for_provider(provider).await?;
+    let mut function_names = FunctionNames::new(provider, None);
+
+    // Targets in the order they're first called
+    let mut targets: Vec<(FieldElement, Vec<String>)> = vec![];
+    let mut transfers: HashMap<FieldElement, BigUint> = HashMap::new();
+    let mut approvals: HashMap<FieldElement, BigUint> = HashMap::new();
+
+    for call in calls.iter() {
+        let function_name = match function_names.lookup(call.to, call.selector).await {
+            Some(name) => name,
+            None => format!("{:#064x}", call.selector),
+        };
+        match targets.iter_mut().find(|(target, _)| *target == call.to) {
+            Some((_, functions)) => functions.push(function_name),
+            None => targets.push((call.to, vec![function_name])),
+        }
+
+        // `(recipient, amount.low, amount.high)` for both functions
+        if let [_, low, high] = call.calldata.as_slice() {
+            let amount = (BigUint::from_bytes_be(&high.to_bytes_be()) << 128)
+                + BigUint::from_bytes_be(&low.to_bytes_be());
+
+            let totals = if call.selector == selector!("transfer") {
+                &mut transfers
+            } else if call.selector == selector!("approve") {
+                &mut approvals
+            } else {
+                continue;
+            };
+            *totals.entry(call.to).or_default() += amount;
+        }
+    }
+
+    eprintln!(
+        "Reviewing {} call(s) to {} contract(s):",
+        calls.len().to_string().bright_yellow(),
+        targets.len().to_string().bright_yellow()
+    );
+    for (target, functions) in targets.iter() {
+        eprintln!(
+            "  {} ({} call(s)): {}",
+            format!("{:#064x}", target).bright_yellow(),
+            functions.len(),
+            functions.join(", ")
+        );
+    }
+
+    for (label, totals) in [
+        ("Total transferred", &transfers),
+        ("Total approved", &approvals),
+    ] {
+        for (token, amount) in totals.iter() {
+            let amount = match registry.format_amount(*token, amount) {
+                Some(amount) => amount,
+                None => format!("{} (raw) of {:#064x}", amount, token),
+            };
+            eprintln!("{}: {}", label, amount.bright_yellow());
         }
+    }
+
+    Ok(())
+}
+
+/// Prompts for the function arguments by name and type when the contract has a Sierra ABI with the
+/// function taking arguments.
+async fn prompt_calldata(
+    provider: &ExtendedProvider,
+    felt_decoder: &FeltDecoder<Arc<ExtendedProvider>>,
+    call: &Call,
+) -> Result<Vec<FieldElement>> {
+    let abi = match CachedAbi::of_contract(provider, call.to)
+        .await?
+        .sierra_abi()?
+    {
+        Some(abi) => abi,
+        None => return Ok(vec![]),
+    };
+
+    let inputs = match function_inputs(&abi, call.selector) {
+        Some(inputs) if !inputs.is_empty() => inputs,
+        _ => return Ok(vec![]),
+    };
 
+    eprintln!("Enter function arguments:");
+    AbiEncoder::new(&abi, felt_decoder)
+        .prompt_inputs(&inputs)
+        .await
+}
+
+/// Compares a simulation with the baseline saved in `path`, or saves it as the baseline if there's
+/// none yet. Differences are grouped so that fee, event and state changes stand out from the rest
+/// of the trace.
+fn compare_with_baseline(
+    path: &Path,
+    simulation: &serde_json::Value,
+    update_baseline: bool,
+) -> Result<()> {
+    let save = || -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(simulation)?)?;
+        eprintln!(
+            "Simulation saved as baseline to {}",
+            path.display().to_string().bright_yellow()
+        );
         Ok(())
+    };
+
+    if !path.exists() {
+        save()?;
+
+        let simulation =
+            colored_json::to_colored_json(simulation, ColorMode::Auto(Output::StdOut))?;
+        println!("{simulation}");
+        return Ok(());
     }
+
+    let baseline: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)
+        .map_err(|err| anyhow::anyhow!("invalid baseline file {}: {}", path.display(), err))?;
+
+    let old_fee = baseline
+        .get("fee_estimation")
+        .and_then(|fee| json_felt(fee, "overall_fee"));
+    let new_fee = simulation
+        .get("fee_estimation")
+        .and_then(|fee| json_felt(fee, "overall_fee"));
+    if let (Some(old_fee), Some(new_fee)) = (old_fee, new_fee) {
+        let change = if old_fee == new_fee {
+            "unchanged".to_owned()
+        } else if old_fee == FieldElement::ZERO {
+            "changed".to_owned()
+        } else {
+            let old_fee = BigUint::from_bytes_be(&old_fee.to_bytes_be());
+            let new_fee = BigUint::from_bytes_be(&new_fee.to_bytes_be());
+            let basis_points: f64 = (new_fee * 10000u32 / old_fee)
+                .to_string()
+                .parse()
+                .unwrap_or_default();
+            format!("{:+.2}%", basis_points / 100.0 - 100.0)
+        };
+
+        println!(
+            "Fee: {} ETH -> {} ETH ({})",
+            format_ether(old_fee),
+            format_ether(new_fee).bright_yellow(),
+            change
+        );
+    }
+
+    let differences = diff_json(&baseline, simulation);
+
+    // Fee changes are already summarized above. Anything that's not an event or state change goes
+    // under execution, like call results and revert reasons.
+    let categories = ["Events", "State diff", "Execution"];
+    let mut grouped = [vec![], vec![], vec![]];
+    for difference in differences
+        .iter()
+        .filter(|difference| !difference.path.starts_with("fee_estimation"))
+    {
+        let index = if difference.path.contains("state_diff") {
+            1
+        } else if difference.path.contains("events") {
+            0
+        } else {
+            2
+        };
+        grouped[index].push(difference);
+    }
+
+    if grouped.iter().all(|differences| differences.is_empty()) {
+        println!("No change in events, state diff or execution.");
+    }
+    for (name, differences) in categories.iter().zip(grouped.iter()) {
+        if differences.is_empty() {
+            continue;
+        }
+
+        println!();
+        println!("{} ({} change(s)):", name, differences.len());
+        for difference in differences.iter() {
+            let show = |value: &Option<serde_json::Value>| match value {
+                Some(value) => value.to_string(),
+                None => "<missing>".to_owned(),
+            };
+
+            println!(
+                "  {}: {} -> {}",
+                difference.path,
+                show(&difference.left),
+                show(&difference.right).bright_yellow()
+            );
+        }
+    }
+
+    if update_baseline {
+        save()?;
+    }
+
+    Ok(())
+}
+
+fn revert_reason(simulation: &SimulatedTransaction) -> Option<String> {
+    match &simulation.transaction_trace {
+        TransactionTrace::Invoke(trace) => match &trace.execute_invocation {
+            ExecuteInvocation::Reverted(reverted) => Some(reverted.revert_reason.clone()),
+            ExecuteInvocation::Success(_) => None,
+        },
+        _ => None,
+    }
+}
+
+/// The node only reports a single revert reason for the whole multicall. To find out which call
+/// actually failed, prefixes of the call list are simulated in a binary search for the shortest one
+/// that reverts. Returns the index of the failing call along with its revert reason.
+async fn find_reverted_call<A>(
+    account: &A,
+    calls: &[Call],
+    nonce: FieldElement,
+    max_fee: FieldElement,
+    skip_validate: bool,
+    full_reason: String,
+) -> Result<(usize, String)>
+where
+    A: ConnectedAccount + Sync,
+    A::SignError: Send + Sync + 'static,
+{
+    // Invariant: the first `low - 1` calls succeed, while the first `high` calls revert
+    let mut low = 1;
+    let mut high = calls.len();
+    let mut reason = full_reason;
+
+    while low < high {
+        let mid = (low + high) / 2;
+        let simulation = account
+            .execute(calls[..mid].to_vec())
+            .nonce(nonce)
+            .max_fee(max_fee)
+            .simulate(skip_validate, true)
+            .await?;
+
+        match revert_reason(&simulation) {
+            Some(mid_reason) => {
+                high = mid;
+                reason = mid_reason;
+            }
+            None => low = mid + 1,
+        }
+    }
+
+    Ok((high - 1, reason))
 }
@@ -17,6 +17,8 @@ use starknet::core::{
     utils::{normalize_address, UdcUniqueSettings, UdcUniqueness},
 };
 
+use crate::utils::format_address;
+
 /// The default UDC address: 0x041a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf.
 const DEFAULT_UDC_ADDRESS: FieldElement = FieldElement::from_mont([
     15144800532519055890,
@@ -191,7 +193,7 @@ impl MineUdcSalt {
         );
         println!(
             "Address: {}",
-            format!("{:#064x}", result.deployed_address).bright_yellow()
+            format_address(result.deployed_address).bright_yellow()
         );
 
         Ok(())
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod verify_address;
+use verify_address::VerifyAddress;
+
+#[derive(Debug, Parser)]
+pub struct Ledger {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Show the public key at a path on the device screen for comparison")]
+    VerifyAddress(VerifyAddress),
+}
+
+impl Ledger {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::VerifyAddress(cmd) => cmd.run().await,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{
+    account::{resolve_account_path, AccountConfig, DeploymentStatus},
+    ledger::{HdPath, LedgerDevice, DEFAULT_HD_PATH},
+    utils::format_address,
+};
+
+#[derive(Debug, Parser)]
+pub struct VerifyAddress {
+    #[clap(
+        long,
+        default_value = DEFAULT_HD_PATH,
+        help = "EIP-2645 derivation path of the key"
+    )]
+    hd_path: HdPath,
+    #[clap(
+        long,
+        help = "Account config file to check against the device key. The account address is \
+                printed for comparison with where funds are sent"
+    )]
+    account: Option<String>,
+}
+
+impl VerifyAddress {
+    pub async fn run(self) -> Result<()> {
+        let device = LedgerDevice::connect().await?;
+
+        // Computed by the device itself, so that it can be compared with what the host shows
+        eprintln!("Confirm the public key on your Ledger device...");
+        let public_key = device.get_public_key(&self.hd_path, true).await?;
+
+        println!(
+            "Public key: {}",
+            format!("{:#064x}", public_key).bright_yellow()
+        );
+
+        if let Some(account) = self.account {
+            let account = AccountConfig::load(resolve_account_path(&account)?)?;
+
+            let address = match &account.deployment {
+                DeploymentStatus::Deployed(status) => status.address,
+                DeploymentStatus::Undeployed(_) => account.deploy_account_address()?,
+            };
+            println!(
+                "Account address: {}",
+                format_address(address).bright_yellow()
+            );
+
+            match account.variant.owner_public_key() {
+                Some(owner) if owner == public_key => {
+                    eprintln!("The account is controlled by this device key.")
+                }
+                Some(owner) => anyhow::bail!(
+                    "account owner {:#064x} does not match the device key. Do not send funds to \
+                    this account",
+                    owner
+                ),
+                None => anyhow::bail!("unable to determine the owner key of the account"),
+            }
+        }
+
+        Ok(())
+    }
+}
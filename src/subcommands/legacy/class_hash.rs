@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+
+use crate::path::ExpandedPathbufParser;
+
+use super::load_legacy_class;
+
+#[derive(Debug, Parser)]
+pub struct ClassHash {
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to legacy class file in gateway or RPC format"
+    )]
+    file: PathBuf,
+}
+
+impl ClassHash {
+    pub fn run(self) -> Result<()> {
+        let class = load_legacy_class(&self.file)?;
+        let class_hash = class.class_hash()?;
+
+        println!("{class_hash:#064x}");
+
+        Ok(())
+    }
+}
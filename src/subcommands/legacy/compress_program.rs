@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::Parser;
+use starknet::core::types::contract::legacy::LegacyProgram;
+
+use crate::path::ExpandedPathbufParser;
+
+use super::{gzip_program, load_legacy_class};
+
+#[derive(Debug, Parser)]
+pub struct CompressProgram {
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to legacy class file, or a file containing only the program JSON"
+    )]
+    file: PathBuf,
+}
+
+impl CompressProgram {
+    pub fn run(self) -> Result<()> {
+        let program = match load_legacy_class(&self.file) {
+            Ok(class) => class.program,
+            Err(_) => serde_json::from_reader::<_, LegacyProgram>(std::fs::File::open(&self.file)?)
+                .map_err(|err| {
+                    anyhow::anyhow!("failed to parse legacy class or program: {}", err)
+                })?,
+        };
+
+        println!("{}", STANDARD.encode(gzip_program(&program)?));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use std::{io::Read, path::PathBuf};
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::Parser;
+use flate2::read::GzDecoder;
+
+use crate::{path::ExpandedPathbufParser, utils::print_colored_json};
+
+#[derive(Debug, Parser)]
+pub struct DecompressProgram {
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to a file containing the base64-encoded compressed program"
+    )]
+    file: PathBuf,
+}
+
+impl DecompressProgram {
+    pub fn run(self) -> Result<()> {
+        let content = std::fs::read_to_string(self.file)?;
+
+        // Also accepts the value copied directly from a JSON document with quotes
+        let encoded = content.trim().trim_matches('"');
+        let compressed = STANDARD
+            .decode(encoded)
+            .map_err(|err| anyhow::anyhow!("invalid base64 content: {}", err))?;
+
+        let mut gzip_decoder = GzDecoder::new(compressed.as_slice());
+        let mut program_json = String::new();
+        gzip_decoder.read_to_string(&mut program_json)?;
+
+        let program = serde_json::from_str::<serde_json::Value>(&program_json)?;
+        print_colored_json(&program)?;
+
+        Ok(())
+    }
+}
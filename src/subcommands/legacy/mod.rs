@@ -0,0 +1,79 @@
+use std::path::Path;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use flate2::{write::GzEncoder, Compression};
+use starknet::core::types::{
+    contract::legacy::{LegacyContractClass, LegacyProgram},
+    CompressedLegacyContractClass,
+};
+
+use crate::utils::parse_compressed_legacy_class;
+
+mod class_hash;
+use class_hash::ClassHash;
+
+mod compress_program;
+use compress_program::CompressProgram;
+
+mod decompress_program;
+use decompress_program::DecompressProgram;
+
+mod to_rpc;
+use to_rpc::ToRpc;
+
+mod to_gateway;
+use to_gateway::ToGateway;
+
+#[derive(Debug, Parser)]
+pub struct Legacy {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Calculate the class hash of a legacy class in either gateway or RPC format")]
+    ClassHash(ClassHash),
+    #[clap(about = "Compress the program of a legacy class into the base64-encoded gzip format")]
+    CompressProgram(CompressProgram),
+    #[clap(about = "Decompress a base64-encoded gzip legacy program into JSON")]
+    DecompressProgram(DecompressProgram),
+    #[clap(about = "Convert a legacy class from the gateway format to the RPC format")]
+    ToRpc(ToRpc),
+    #[clap(about = "Convert a legacy class from the RPC format to the gateway format")]
+    ToGateway(ToGateway),
+}
+
+impl Legacy {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::ClassHash(cmd) => cmd.run(),
+            Subcommands::CompressProgram(cmd) => cmd.run(),
+            Subcommands::DecompressProgram(cmd) => cmd.run(),
+            Subcommands::ToRpc(cmd) => cmd.run(),
+            Subcommands::ToGateway(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// Loads a legacy class from a file in either the gateway (uncompressed) or the RPC (compressed)
+/// format.
+fn load_legacy_class(path: &Path) -> Result<LegacyContractClass> {
+    if let Ok(class) = serde_json::from_reader::<_, LegacyContractClass>(std::fs::File::open(path)?)
+    {
+        Ok(class)
+    } else if let Ok(class) =
+        serde_json::from_reader::<_, CompressedLegacyContractClass>(std::fs::File::open(path)?)
+    {
+        parse_compressed_legacy_class(class)
+    } else {
+        anyhow::bail!("failed to parse legacy class in either gateway or RPC format")
+    }
+}
+
+fn gzip_program(program: &LegacyProgram) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    serde_json::to_writer(&mut encoder, program)?;
+    Ok(encoder.finish()?)
+}
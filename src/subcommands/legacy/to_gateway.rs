@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::types::CompressedLegacyContractClass;
+
+use crate::{
+    path::ExpandedPathbufParser,
+    utils::{parse_compressed_legacy_class, print_colored_json},
+};
+
+#[derive(Debug, Parser)]
+pub struct ToGateway {
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to legacy class file in RPC format"
+    )]
+    file: PathBuf,
+}
+
+impl ToGateway {
+    pub fn run(self) -> Result<()> {
+        let class: CompressedLegacyContractClass =
+            serde_json::from_reader(std::fs::File::open(self.file)?)
+                .map_err(|err| anyhow::anyhow!("failed to parse RPC legacy class: {}", err))?;
+
+        let class = parse_compressed_legacy_class(class)?;
+
+        // Entrypoint offset encoding is not preserved in the RPC format
+        eprintln!(
+            "{}",
+            "NOTE: entrypoint offset encoding is guessed from the compiler version. This does not \
+            affect the class hash."
+                .bright_magenta()
+        );
+
+        print_colored_json(&class)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use starknet::core::types::contract::legacy::LegacyContractClass;
+
+use crate::{path::ExpandedPathbufParser, utils::print_colored_json};
+
+#[derive(Debug, Parser)]
+pub struct ToRpc {
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to legacy class file in gateway format"
+    )]
+    file: PathBuf,
+}
+
+impl ToRpc {
+    pub fn run(self) -> Result<()> {
+        let class: LegacyContractClass =
+            serde_json::from_reader(std::fs::File::open(self.file)?)
+                .map_err(|err| anyhow::anyhow!("failed to parse gateway legacy class: {}", err))?;
+
+        print_colored_json(&class.compress()?)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use num_bigint::BigUint;
+use starknet::core::types::FieldElement;
+
+use crate::utils::parse_felt_value;
+
+#[derive(Debug, Parser)]
+pub struct Math {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Add two values")]
+    Add(Operands),
+    #[clap(about = "Subtract the second value from the first one")]
+    Sub(Operands),
+    #[clap(about = "Multiply two values")]
+    Mul(Operands),
+    #[clap(about = "Divide the first value by the second one, printing quotient and remainder")]
+    DivRem(Operands),
+}
+
+#[derive(Debug, Parser)]
+struct Operands {
+    #[clap(
+        long,
+        help = "Operate on u256 integers instead of field elements. Overflows and underflows are \
+                reported as errors, like Cairo's u256 panics"
+    )]
+    u256: bool,
+    #[clap(long, help = "Print results in hexadecimal format")]
+    hex: bool,
+    #[clap(help = "First operand, in decimal or hexadecimal representation")]
+    a: String,
+    #[clap(help = "Second operand, in decimal or hexadecimal representation")]
+    b: String,
+}
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Add,
+    Sub,
+    Mul,
+    DivRem,
+}
+
+impl Math {
+    pub fn run(self) -> Result<()> {
+        let (operation, operands) = match self.command {
+            Subcommands::Add(operands) => (Operation::Add, operands),
+            Subcommands::Sub(operands) => (Operation::Sub, operands),
+            Subcommands::Mul(operands) => (Operation::Mul, operands),
+            Subcommands::DivRem(operands) => (Operation::DivRem, operands),
+        };
+
+        let results = if operands.u256 {
+            u256_op(
+                operation,
+                parse_u256(&operands.a)?,
+                parse_u256(&operands.b)?,
+            )?
+        } else {
+            felt_op(
+                operation,
+                parse_felt_value(&operands.a)?,
+                parse_felt_value(&operands.b)?,
+            )?
+        };
+
+        for result in results.iter() {
+            if operands.hex {
+                println!("{:#x}", result);
+            } else {
+                println!("{}", result);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Field arithmetic is modular: results wrap around the field prime P = 2^251 + 17 * 2^192 + 1,
+/// which is also how `felt252` behaves in Cairo. Division is integer division on the canonical
+/// representations in [0, P), not multiplication by the inverse.
+fn felt_op(operation: Operation, a: FieldElement, b: FieldElement) -> Result<Vec<BigUint>> {
+    let prime = felt_to_biguint(FieldElement::MAX) + 1u32;
+    let a = felt_to_biguint(a);
+    let b = felt_to_biguint(b);
+
+    let (result, wrapped) = match operation {
+        Operation::Add => {
+            let sum = &a + &b;
+            (&sum % &prime, sum >= prime)
+        }
+        Operation::Sub => ((&a + &prime - &b) % &prime, a < b),
+        Operation::Mul => {
+            let product = &a * &b;
+            (&product % &prime, product >= prime)
+        }
+        Operation::DivRem => return div_rem(a, b),
+    };
+
+    if wrapped {
+        eprintln!(
+            "{}",
+            "NOTE: the result wrapped around the field prime (arithmetic is modulo P)"
+                .bright_magenta()
+        );
+    }
+
+    Ok(vec![result])
+}
+
+fn u256_op(operation: Operation, a: BigUint, b: BigUint) -> Result<Vec<BigUint>> {
+    let result = match operation {
+        Operation::Add => &a + &b,
+        Operation::Sub => {
+            if a < b {
+                anyhow::bail!("u256 subtraction underflow");
+            }
+            &a - &b
+        }
+        Operation::Mul => &a * &b,
+        Operation::DivRem => return div_rem(a, b),
+    };
+
+    if result.bits() > 256 {
+        anyhow::bail!("u256 overflow: result does not fit in 256 bits");
+    }
+
+    Ok(vec![result])
+}
+
+fn div_rem(a: BigUint, b: BigUint) -> Result<Vec<BigUint>> {
+    if b == BigUint::default() {
+        anyhow::bail!("division by zero");
+    }
+
+    Ok(vec![&a / &b, &a % &b])
+}
+
+fn parse_u256(value: &str) -> Result<BigUint> {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+        None => BigUint::parse_bytes(value.as_bytes(), 10),
+    }
+    .ok_or_else(|| anyhow::anyhow!("invalid number: {}", value))?;
+
+    if parsed.bits() > 256 {
+        anyhow::bail!("value out of u256 range: {}", value);
+    }
+
+    Ok(parsed)
+}
+
+fn felt_to_biguint(value: FieldElement) -> BigUint {
+    BigUint::from_bytes_be(&value.to_bytes_be())
+}
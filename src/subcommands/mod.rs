@@ -1,9 +1,21 @@
 mod selector;
 pub use selector::Selector;
 
+mod event_selector;
+pub use event_selector::EventSelector;
+
 mod completions;
 pub use completions::Completions;
 
+mod schema;
+pub use schema::Schema;
+
+mod doctor;
+pub use doctor::Doctor;
+
+mod init;
+pub use init::Init;
+
 mod class_hash;
 pub use class_hash::ClassHash;
 
@@ -34,9 +46,18 @@ pub use to_cairo_string::ToCairoString;
 mod parse_cairo_string;
 pub use parse_cairo_string::ParseCairoString;
 
+mod pretty_felts;
+pub use pretty_felts::PrettyFelts;
+
 mod mont;
 pub use mont::Mont;
 
+mod math;
+pub use math::Math;
+
+mod ecdsa;
+pub use ecdsa::Ecdsa;
+
 mod class_by_hash;
 pub use class_by_hash::ClassByHash;
 
@@ -46,6 +67,9 @@ pub use syncing::Syncing;
 mod class_at;
 pub use class_at::ClassAt;
 
+mod class;
+pub use class::Class;
+
 mod class_hash_at;
 pub use class_hash_at::ClassHashAt;
 
@@ -55,6 +79,15 @@ pub use balance::Balance;
 mod nonce;
 pub use nonce::Nonce;
 
+mod pending;
+pub use pending::Pending;
+
+mod history;
+pub use history::History;
+
+mod events;
+pub use events::Events;
+
 mod storage;
 pub use storage::Storage;
 
@@ -64,6 +97,15 @@ pub use state_update::StateUpdate;
 mod signer;
 pub use signer::Signer;
 
+mod secret;
+pub use secret::Secret;
+
+mod agent;
+pub use agent::Agent;
+
+mod ledger;
+pub use ledger::Ledger;
+
 mod account;
 pub use account::Account;
 
@@ -76,11 +118,107 @@ pub use declare::Declare;
 mod call;
 pub use call::Call;
 
+mod estimate;
+pub use estimate::Estimate;
+
 mod invoke;
 pub use invoke::Invoke;
 
+mod multi;
+pub use multi::Multi;
+
+mod script;
+pub use script::Script;
+
+mod bench;
+pub use bench::Bench;
+
 mod lab;
 pub use lab::Lab;
 
 mod transaction_trace;
 pub use transaction_trace::TransactionTrace;
+
+mod tui;
+pub use tui::Tui;
+
+mod estimate_message_fee;
+pub use estimate_message_fee::EstimateMessageFee;
+
+mod bridge;
+pub use bridge::Bridge;
+
+mod devnet;
+pub use devnet::Devnet;
+
+mod generate;
+pub use generate::Generate;
+
+mod sign_tx;
+pub use sign_tx::SignTx;
+
+mod broadcast_tx;
+pub use broadcast_tx::BroadcastTx;
+
+mod serve;
+pub use serve::Serve;
+
+mod eth_address_to_felt;
+pub use eth_address_to_felt::EthAddressToFelt;
+
+mod felt_to_eth_address;
+pub use felt_to_eth_address::FeltToEthAddress;
+
+mod storage_address;
+pub use storage_address::StorageAddress;
+
+mod storage_dump;
+pub use storage_dump::StorageDump;
+
+mod wait_for_contract;
+pub use wait_for_contract::WaitForContract;
+
+mod inspect;
+pub use inspect::Inspect;
+
+mod sierra;
+pub use sierra::Sierra;
+
+mod casm;
+pub use casm::Casm;
+
+mod legacy;
+pub use legacy::Legacy;
+
+mod address_book;
+pub use address_book::AddressBook;
+
+mod template;
+pub use template::Template;
+
+mod verify_tx;
+pub use verify_tx::VerifyTx;
+
+mod watchd;
+pub use watchd::Watchd;
+
+mod abi_cache;
+pub use abi_cache::AbiCache;
+
+mod store;
+pub use store::Store;
+
+mod config;
+pub use config::Config;
+
+mod network;
+pub use network::Network;
+
+mod multisig;
+pub use multisig::Multisig;
+
+mod admin;
+pub use admin::Admin;
+
+mod simulate_queue;
+pub use simulate_queue::SimulateQueue;
@@ -0,0 +1,377 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde::Deserialize;
+use starknet::{
+    accounts::{Account, Call, SingleOwnerAccount},
+    core::types::{ExecutionResult, FieldElement},
+    macros::felt,
+};
+
+use crate::{
+    accessibility::Status,
+    account::load_accounts_file,
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    error::decode_account_error,
+    fee::check_max_fee_ceiling,
+    interrupt::ctrl_c,
+    path::ExpandedPathbufParser,
+    provider::ExtendedProvider,
+    script_state::{ScriptState, StepResult},
+    signer::AnySigner,
+    utils::{send_with_retry, wait_for_tx},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Invoke {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Path to a file listing one account per line, as an account file path or name \
+                followed by its private key or keystore path"
+    )]
+    accounts: PathBuf,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Path to a TOML file with the calls to send from every account"
+    )]
+    file: PathBuf,
+    #[clap(
+        long,
+        help = "Password shared by all keystores in the accounts file. Prompted for each keystore \
+                otherwise"
+    )]
+    keystore_password: Option<String>,
+    #[clap(
+        long,
+        default_value = "4",
+        help = "Maximum number of accounts sending at the same time"
+    )]
+    concurrency: usize,
+    #[clap(long, short, help = "Wait for the transactions to confirm")]
+    watch: bool,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        default_value = "5000",
+        help = "Transaction result poll interval in milliseconds"
+    )]
+    poll_interval: u64,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        default_value = "multi-invoke-state.json",
+        help = "Where to save progress when interrupted or when some accounts fail"
+    )]
+    state_file: PathBuf,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        conflicts_with = "state_file",
+        help = "Resume from a saved state file, skipping accounts that already sent their \
+                transaction. Progress is saved back to the same file"
+    )]
+    resume: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "Send the transactions even if their max fee exceeds the network's \
+                `max_fee_ceiling`"
+    )]
+    force: bool,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CallsFile {
+    calls: Vec<CallEntry>,
+}
+
+/// A call with raw arguments, same as on the command line.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CallEntry {
+    to: String,
+    function: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+enum Outcome {
+    AlreadySent(FieldElement),
+    Sent(FieldElement),
+    Confirmed(FieldElement),
+    Reverted(FieldElement, String),
+    Failed(String),
+}
+
+impl Invoke {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        if self.concurrency == 0 {
+            anyhow::bail!("--concurrency must be at least 1");
+        }
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let calls_file_content = std::fs::read_to_string(&self.file)?;
+        let calls_file: CallsFile = toml::from_str(&calls_file_content).map_err(|err| {
+            anyhow::anyhow!("invalid calls file {}: {}", self.file.display(), err)
+        })?;
+        if calls_file.calls.is_empty() {
+            anyhow::bail!("empty execution");
+        }
+
+        let mut raw_calls = vec![];
+        for call in calls_file.calls.into_iter() {
+            if !raw_calls.is_empty() {
+                raw_calls.push("/".to_owned());
+            }
+            raw_calls.push(call.to);
+            raw_calls.push(call.function);
+            raw_calls.extend(call.args);
+        }
+        let calls = felt_decoder.decode_calls(raw_calls).await?;
+
+        let state_file = self.resume.as_ref().unwrap_or(&self.state_file);
+        let state = match &self.resume {
+            Some(resume) => {
+                let state = ScriptState::resume(resume, calls_file_content.as_bytes())?;
+
+                eprintln!(
+                    "Resuming with {} transaction(s) already sent",
+                    state.completed_count().to_string().bright_yellow()
+                );
+                state
+            }
+            None => ScriptState::create(state_file, calls_file_content.as_bytes())?,
+        };
+        let state = Arc::new(Mutex::new(state));
+
+        // All signers are resolved before sending anything, so that password prompts don't
+        // interleave with progress output and mistakes in the file are caught early
+        let accounts = load_accounts_file(
+            provider.clone(),
+            &self.accounts,
+            self.keystore_password.as_deref(),
+        )
+        .await?;
+
+        eprintln!(
+            "Sending {} call(s) from {} account(s)...",
+            calls.len(),
+            accounts.len().to_string().bright_yellow()
+        );
+
+        let poll_interval = Duration::from_millis(self.poll_interval);
+        let mut results = vec![];
+
+        let sending = async {
+            let mut accounts = accounts.into_iter().peekable();
+            while accounts.peek().is_some() {
+                let handles = accounts
+                    .by_ref()
+                    .take(self.concurrency)
+                    .map(|account| {
+                        let provider = provider.clone();
+                        let calls = calls.clone();
+                        let state = state.clone();
+                        let watch = self.watch;
+                        let force = self.force;
+                        let address = account.address();
+
+                        let handle = tokio::spawn(async move {
+                            send_from_account(
+                                &provider,
+                                &account,
+                                calls,
+                                &state,
+                                watch,
+                                poll_interval,
+                                force,
+                            )
+                            .await
+                        });
+                        (address, handle)
+                    })
+                    .collect::<Vec<_>>();
+
+                for (address, handle) in handles.into_iter() {
+                    let outcome = match handle.await {
+                        Ok(Ok(outcome)) => outcome,
+                        Ok(Err(err)) => Outcome::Failed(err.to_string()),
+                        Err(err) => Outcome::Failed(err.to_string()),
+                    };
+                    print_outcome(address, &outcome);
+                    results.push(outcome);
+                }
+            }
+        };
+
+        tokio::select! {
+            _ = sending => {}
+            _ = ctrl_c() => {
+                let state = state.lock().unwrap();
+                state.save(state_file)?;
+
+                eprintln!();
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Interrupted with {} transaction(s) sent:",
+                        state.completed_count()
+                    )
+                    .bright_magenta()
+                );
+                for (address, step) in state.steps() {
+                    eprintln!(
+                        "{}: {}",
+                        address,
+                        format!("{:#064x}", step.transaction_hash).bright_yellow()
+                    );
+                }
+                eprintln!("To resume, run:");
+                eprintln!("    starkli script resume {}", state_file.display());
+
+                anyhow::bail!("interrupted");
+            }
+        }
+
+        let failed = results
+            .iter()
+            .filter(|outcome| matches!(outcome, Outcome::Failed(_) | Outcome::Reverted(..)))
+            .count();
+
+        eprintln!(
+            "{} succeeded, {} failed",
+            (results.len() - failed).to_string().bright_yellow(),
+            failed.to_string().bright_yellow()
+        );
+
+        if failed > 0 {
+            state.lock().unwrap().save(state_file)?;
+            eprintln!(
+                "Progress saved. To retry the failed accounts, run:\n    \
+                 starkli script resume {}",
+                state_file.display()
+            );
+
+            anyhow::bail!("{} of {} account(s) failed", failed, results.len());
+        }
+
+        Ok(())
+    }
+}
+
+/// Each account has its own nonce, so accounts can send concurrently while nonce conflicts from
+/// other activity on the same account are retried by `send_with_retry`. Accounts that already sent
+/// their transaction in a resumed run only have it watched.
+async fn send_from_account(
+    provider: &ExtendedProvider,
+    account: &SingleOwnerAccount<Arc<ExtendedProvider>, AnySigner>,
+    calls: Vec<Call>,
+    state: &Mutex<ScriptState>,
+    watch: bool,
+    poll_interval: Duration,
+    force: bool,
+) -> Result<Outcome> {
+    let address = format!("{:#064x}", account.address());
+
+    let already_sent = state
+        .lock()
+        .unwrap()
+        .step(&address)
+        .map(|step| step.transaction_hash);
+    let transaction_hash = match already_sent {
+        Some(transaction_hash) => {
+            if !watch {
+                return Ok(Outcome::AlreadySent(transaction_hash));
+            }
+            transaction_hash
+        }
+        None => {
+            let execution = account.execute(calls);
+
+            let fee_estimate = execution
+                .estimate_fee()
+                .await
+                .map_err(decode_account_error)?;
+            // TODO: make buffer configurable
+            let max_fee = (fee_estimate.overall_fee * felt!("3")).floor_div(felt!("2"));
+            check_max_fee_ceiling(provider, max_fee, force)?;
+
+            let execution = execution.max_fee(max_fee);
+            let transaction_hash =
+                send_with_retry(provider, account.address(), false, || execution.send())
+                    .await?
+                    .transaction_hash;
+
+            state.lock().unwrap().record(
+                address,
+                StepResult {
+                    transaction_hash,
+                    address: None,
+                },
+            );
+            transaction_hash
+        }
+    };
+
+    if !watch {
+        return Ok(Outcome::Sent(transaction_hash));
+    }
+
+    Ok(
+        match wait_for_tx(provider, transaction_hash, poll_interval).await? {
+            ExecutionResult::Succeeded => Outcome::Confirmed(transaction_hash),
+            ExecutionResult::Reverted { reason } => Outcome::Reverted(transaction_hash, reason),
+        },
+    )
+}
+
+fn print_outcome(address: FieldElement, outcome: &Outcome) {
+    let address = format!("{:#064x}", address);
+
+    match outcome {
+        Outcome::AlreadySent(hash) => eprintln!(
+            "{}: already sent {}",
+            address,
+            format!("{:#064x}", hash).bright_yellow()
+        ),
+        Outcome::Sent(hash) => eprintln!(
+            "{}: sent {}",
+            address,
+            format!("{:#064x}", hash).bright_yellow()
+        ),
+        Outcome::Confirmed(hash) => eprintln!(
+            "{}: confirmed {}",
+            address,
+            format!("{:#064x}", hash).bright_yellow()
+        ),
+        Outcome::Reverted(hash, reason) => eprintln!(
+            "{}: {} {} ({})",
+            address,
+            Status::Failure.label("reverted"),
+            format!("{:#064x}", hash).bright_yellow(),
+            reason.trim()
+        ),
+        Outcome::Failed(err) => {
+            eprintln!("{}: {} {}", address, Status::Failure.label("failed"), err)
+        }
+    }
+}
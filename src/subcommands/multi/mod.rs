@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod invoke;
+use invoke::Invoke;
+
+#[derive(Debug, Parser)]
+pub struct Multi {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Send the same calls from many accounts")]
+    Invoke(Invoke),
+}
+
+impl Multi {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Invoke(cmd) => cmd.run().await,
+        }
+    }
+}
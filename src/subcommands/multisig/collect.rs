@@ -0,0 +1,163 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::{
+        crypto::{ecdsa_verify, Signature},
+        types::FieldElement,
+    },
+    providers::Provider,
+};
+
+use crate::{
+    accessibility::Status, path::ExpandedPathbufParser, signature_share::SignatureShare,
+    transaction_file::ExportedTransaction, verbosity::VerbosityArgs, ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Collect {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        visible_alias = "share-dir",
+        env = "STARKLI_MULTISIG_SHARE",
+        help = "Directory or http(s):// URL where proposals and signatures are exchanged"
+    )]
+    share: String,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Path to write the signed transaction to. Defaults to <HASH>.json in the current \
+                directory"
+    )]
+    output: Option<PathBuf>,
+    #[clap(help = "Hash of the proposed transaction")]
+    hash: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Collect {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let transaction_hash = FieldElement::from_hex_be(&self.hash)?;
+        let share = SignatureShare::parse(&self.share);
+
+        let mut proposal = share.fetch_proposal(transaction_hash).await?;
+        let computed_hash = proposal.verify_hash()?;
+        if computed_hash != transaction_hash {
+            anyhow::bail!(
+                "proposal is for transaction {:#064x}, not {:#064x}",
+                computed_hash,
+                transaction_hash
+            );
+        }
+
+        let provider = self.provider.into_provider()?;
+
+        let chain_id = provider.chain_id().await?;
+        if chain_id != proposal.chain_id {
+            anyhow::bail!(
+                "chain ID mismatch: transaction is for {:#x} but the provider is on {:#x}",
+                proposal.chain_id,
+                chain_id
+            );
+        }
+
+        let sender = match &proposal.transaction {
+            ExportedTransaction::Invoke(invoke) => invoke.sender_address,
+            ExportedTransaction::Declare(declare) => declare.sender_address,
+            ExportedTransaction::DeclareLegacy(declare) => declare.sender_address,
+        };
+        let (signers, threshold) = super::fetch_signer_set(&provider, sender).await?;
+
+        eprintln!(
+            "Account {} requires {} of {} signers",
+            format!("{:#064x}", sender).bright_yellow(),
+            threshold.to_string().bright_yellow(),
+            signers.len().to_string().bright_yellow()
+        );
+
+        // Every shared signature is checked, as anyone with access to the share can add files
+        let mut valid = vec![];
+        for signature in share.fetch_signatures(transaction_hash).await?.into_iter() {
+            let problem = if signature.transaction_hash != transaction_hash {
+                Some("signs a different transaction")
+            } else if !signers.contains(&signature.signer) {
+                Some("not an owner of the account")
+            } else if !ecdsa_verify(
+                &signature.signer,
+                &transaction_hash,
+                &Signature {
+                    r: signature.r,
+                    s: signature.s,
+                },
+            )
+            .unwrap_or(false)
+            {
+                Some("invalid signature")
+            } else if valid
+                .iter()
+                .any(|(signer, _, _)| *signer == signature.signer)
+            {
+                Some("duplicate signer")
+            } else {
+                None
+            };
+
+            match problem {
+                Some(problem) => eprintln!(
+                    "{} {}: {}",
+                    Status::Warning.label("Skipped"),
+                    format!("{:#064x}", signature.signer).bright_yellow(),
+                    problem
+                ),
+                None => {
+                    eprintln!(
+                        "{} {}",
+                        Status::Success.label("Valid"),
+                        format!("{:#064x}", signature.signer).bright_yellow()
+                    );
+                    valid.push((signature.signer, signature.r, signature.s));
+                }
+            }
+        }
+
+        if (valid.len() as u64) < threshold {
+            anyhow::bail!(
+                "only {} of the {} required signatures collected",
+                valid.len(),
+                threshold
+            );
+        }
+
+        // Argent multisig expects exactly `threshold` signatures as `(signer, r, s)` triples,
+        // ordered by signer
+        valid.sort_by_key(|(signer, _, _)| *signer);
+        valid.truncate(threshold as usize);
+        proposal.signature = valid
+            .into_iter()
+            .flat_map(|(signer, r, s)| [signer, r, s])
+            .collect();
+
+        let output = self
+            .output
+            .unwrap_or_else(|| PathBuf::from(format!("{:#064x}.json", transaction_hash)));
+        proposal.save(&output)?;
+
+        eprintln!(
+            "Signed transaction written to {}",
+            output.display().to_string().bright_yellow()
+        );
+        eprintln!(
+            "Use `{}` to send it out.",
+            "starkli broadcast-tx".bright_yellow()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement, FunctionCall},
+    macros::selector,
+    providers::Provider,
+};
+
+use crate::provider::ExtendedProvider;
+
+mod propose;
+use propose::Propose;
+
+mod sign;
+use sign::Sign;
+
+mod collect;
+use collect::Collect;
+
+#[derive(Debug, Parser)]
+pub struct Multisig {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Share a transaction file exported with `--export` for the owners to sign")]
+    Propose(Propose),
+    #[clap(about = "Sign a shared proposal and share the signature")]
+    Sign(Sign),
+    #[clap(about = "Validate the shared signatures and combine them into a signed transaction")]
+    Collect(Collect),
+}
+
+impl Multisig {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Propose(cmd) => cmd.run().await,
+            Subcommands::Sign(cmd) => cmd.run().await,
+            Subcommands::Collect(cmd) => cmd.run().await,
+        }
+    }
+}
+
+/// The owners and the number of signatures required, as exposed by Argent multisig accounts.
+async fn fetch_signer_set(
+    provider: &ExtendedProvider,
+    account: FieldElement,
+) -> Result<(Vec<FieldElement>, u64)> {
+    let call = |entry_point_selector| {
+        provider.call(
+            FunctionCall {
+                contract_address: account,
+                entry_point_selector,
+                calldata: vec![],
+            },
+            BlockId::Tag(BlockTag::Pending),
+        )
+    };
+
+    let signers = call(selector!("get_signers")).await.map_err(|err| {
+        anyhow::anyhow!(
+            "unable to get the signers of account {:#064x}. Is it a multisig account? {}",
+            account,
+            err
+        )
+    })?;
+    // Arrays are returned with their length first
+    let signers = signers.get(1..).unwrap_or_default().to_vec();
+
+    let threshold = call(selector!("get_threshold")).await?;
+    let threshold = match threshold.as_slice() {
+        [threshold] => (*threshold).try_into().map_err(|_| {
+            anyhow::anyhow!("invalid threshold returned by account: {:#x}", threshold)
+        })?,
+        _ => anyhow::bail!("unexpected get_threshold result from account"),
+    };
+
+    Ok((signers, threshold))
+}
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{
+    path::ExpandedPathbufParser, signature_share::SignatureShare,
+    transaction_file::TransactionFile, verbosity::VerbosityArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Propose {
+    #[clap(
+        long,
+        visible_alias = "share-dir",
+        env = "STARKLI_MULTISIG_SHARE",
+        help = "Directory or http(s):// URL where proposals and signatures are exchanged"
+    )]
+    share: String,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the transaction file exported with `--export`"
+    )]
+    file: PathBuf,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Propose {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let mut proposal = TransactionFile::load(&self.file)?;
+        let transaction_hash = proposal.verify_hash()?;
+
+        proposal.print_summary();
+
+        if !proposal.signature.is_empty() {
+            eprintln!(
+                "{}",
+                "WARNING: the transaction file is already signed. The signature is not shared."
+                    .bright_magenta()
+            );
+            proposal.signature.clear();
+        }
+
+        let location = SignatureShare::parse(&self.share)
+            .publish_proposal(proposal)
+            .await?;
+
+        eprintln!(
+            "Proposal {} shared at {}",
+            format!("{:#064x}", transaction_hash).bright_yellow(),
+            location.bright_yellow()
+        );
+        eprintln!(
+            "Owners can sign it with `{}`, and anyone can then combine the signatures with `{}`.",
+            format!(
+                "starkli multisig sign --share {} {:#064x}",
+                self.share, transaction_hash
+            )
+            .bright_yellow(),
+            format!(
+                "starkli multisig collect --share {} {:#064x}",
+                self.share, transaction_hash
+            )
+            .bright_yellow()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{core::types::FieldElement, signers::Signer};
+
+use crate::{
+    signature_share::{SignatureShare, SignerSignature},
+    signer::SignerArgs,
+    verbosity::VerbosityArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Sign {
+    #[clap(flatten)]
+    signer: SignerArgs,
+    #[clap(
+        long,
+        visible_alias = "share-dir",
+        env = "STARKLI_MULTISIG_SHARE",
+        help = "Directory or http(s):// URL where proposals and signatures are exchanged"
+    )]
+    share: String,
+    #[clap(help = "Hash of the proposed transaction")]
+    hash: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Sign {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let transaction_hash = FieldElement::from_hex_be(&self.hash)?;
+        let share = SignatureShare::parse(&self.share);
+
+        // The proposal comes from a shared location, so it's checked just like any other file
+        let proposal = share.fetch_proposal(transaction_hash).await?;
+        let computed_hash = proposal.verify_hash()?;
+        if computed_hash != transaction_hash {
+            anyhow::bail!(
+                "proposal is for transaction {:#064x}, not {:#064x}",
+                computed_hash,
+                transaction_hash
+            );
+        }
+
+        proposal.print_summary();
+
+        let signer = self.signer.into_signer()?;
+        let public_key = signer.get_public_key().await?.scalar();
+        let signature = signer.sign_hash(&transaction_hash).await?;
+
+        let location = share
+            .add_signature(SignerSignature {
+                transaction_hash,
+                signer: public_key,
+                r: signature.r,
+                s: signature.s,
+                signed_at: chrono::Utc::now().to_rfc3339(),
+            })
+            .await?;
+
+        eprintln!(
+            "Signature of {} shared at {}",
+            format!("{:#064x}", public_key).bright_yellow(),
+            location.bright_yellow()
+        );
+
+        Ok(())
+    }
+}
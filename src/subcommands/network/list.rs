@@ -0,0 +1,78 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::utils::parse_cairo_short_string;
+
+use crate::profile::{NetworkProvider, Profiles, DEFAULT_PROFILE_NAME};
+
+use super::warn_env_overrides;
+
+/// Networks that are configured automatically with a free RPC vendor on first use.
+const FREE_NETWORKS: [(&str, &str); 3] = [
+    ("mainnet", "SN_MAIN"),
+    ("goerli", "SN_GOERLI"),
+    ("sepolia", "SN_SEPOLIA"),
+];
+
+#[derive(Debug, Parser)]
+pub struct List {}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        let profiles = Profiles::load()?;
+        let profile = profiles.profiles.get(DEFAULT_PROFILE_NAME);
+        let default_network = profile.and_then(|profile| profile.default_network.as_deref());
+
+        let mut rows = vec![];
+        if let Some(profile) = profile {
+            for (id, network) in profile.networks.iter() {
+                let chain_id = parse_cairo_short_string(&network.chain_id)
+                    .unwrap_or_else(|_| format!("{:#x}", network.chain_id));
+                let provider = match &network.provider {
+                    NetworkProvider::Rpc(url) => url.to_string(),
+                    NetworkProvider::RpcSecret(name) => format!("secret:{}", name),
+                    NetworkProvider::Free(vendor) => format!("free ({})", vendor),
+                };
+                rows.push((id.to_owned(), chain_id, provider));
+            }
+        }
+        for (id, chain_id) in FREE_NETWORKS.iter() {
+            if !rows.iter().any(|(configured, _, _)| configured == id) {
+                rows.push((
+                    id.to_string(),
+                    chain_id.to_string(),
+                    "free (chosen on first use)".to_owned(),
+                ));
+            }
+        }
+
+        let id_width = rows.iter().map(|(id, _, _)| id.len()).max().unwrap_or(0);
+        let chain_id_width = rows
+            .iter()
+            .map(|(_, chain_id, _)| chain_id.len())
+            .max()
+            .unwrap_or(0);
+
+        for (id, chain_id, provider) in rows.iter() {
+            let padded_id = format!("{:width$}", id, width = id_width);
+            if default_network == Some(id.as_str()) {
+                print!("* {}", padded_id.bright_yellow());
+            } else {
+                print!("  {}", padded_id);
+            }
+            println!(
+                "  {:width$}  {}",
+                chain_id,
+                provider,
+                width = chain_id_width
+            );
+        }
+
+        if default_network.is_none() {
+            eprintln!("No default network set. Use `starkli network use <NETWORK>` to set one.");
+        }
+        warn_env_overrides();
+
+        Ok(())
+    }
+}
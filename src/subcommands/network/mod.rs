@@ -0,0 +1,53 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+mod list;
+use list::List;
+
+mod use_network;
+use use_network::UseNetwork;
+
+#[derive(Debug, Parser)]
+pub struct Network {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "List configured and built-in networks, marking the default one")]
+    List(List),
+    #[clap(
+        name = "use",
+        about = "Set the network used when neither --rpc nor --network is supplied"
+    )]
+    Use(UseNetwork),
+}
+
+impl Network {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::List(cmd) => cmd.run(),
+            Subcommands::Use(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// Warns when environment variables override the default network, as they're easily forgotten in
+/// a shell session.
+fn warn_env_overrides() {
+    for var in ["STARKNET_RPC", "STARKNET_NETWORK"] {
+        if let Ok(value) = std::env::var(var) {
+            eprintln!(
+                "{}",
+                format!(
+                    "WARNING: {} is set to {} in the environment and takes precedence over the \
+                     default network.",
+                    var, value
+                )
+                .bright_magenta()
+            );
+        }
+    }
+}
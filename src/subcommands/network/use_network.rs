@@ -0,0 +1,72 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{
+    network::Network,
+    profile::{Profiles, DEFAULT_PROFILE_NAME},
+};
+
+use super::warn_env_overrides;
+
+#[derive(Debug, Parser)]
+pub struct UseNetwork {
+    #[clap(
+        long,
+        conflicts_with = "network",
+        help = "Remove the default network setting"
+    )]
+    unset: bool,
+    #[clap(
+        long,
+        help = "Only print a shell command setting STARKNET_NETWORK for the current session, to \
+                be used with `eval`, instead of changing the profile"
+    )]
+    session: bool,
+    #[clap(
+        required_unless_present = "unset",
+        help = "Network defined in the profile, or a network eligible for free RPC vendors"
+    )]
+    network: Option<String>,
+}
+
+impl UseNetwork {
+    pub fn run(self) -> Result<()> {
+        let mut profiles = Profiles::load()?;
+
+        if let Some(network) = &self.network {
+            let is_configured = profiles
+                .profiles
+                .get(DEFAULT_PROFILE_NAME)
+                .is_some_and(|profile| profile.networks.contains_key(network));
+            if !is_configured && Network::from_str(network).is_err() {
+                anyhow::bail!(
+                    "network not found: {}. Use `starkli network list` to see available networks",
+                    network
+                );
+            }
+        }
+
+        if self.session {
+            match &self.network {
+                Some(network) => println!("export STARKNET_NETWORK={}", network),
+                None => println!("unset STARKNET_NETWORK"),
+            }
+            return Ok(());
+        }
+
+        let profile = profiles.default_profile_mut();
+        profile.default_network = self.network.clone();
+        profiles.save()?;
+
+        match &self.network {
+            Some(network) => eprintln!("Default network set to {}", network.bright_yellow()),
+            None => eprintln!("Default network removed"),
+        }
+        warn_env_overrides();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    accounts::Account,
+    core::types::{BlockId, BlockTag, FieldElement},
+    macros::felt,
+    providers::Provider,
+};
+
+use crate::{
+    account::AccountArgs,
+    address_book::AddressBookResolver,
+    amount::format_ether,
+    decode::FeltDecoder,
+    fee::{check_max_fee_ceiling, FeeArgs, FeeSetting},
+    messages::{message, plain_message},
+    utils::{noop_call, print_colored_json},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Doctor {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    account: AccountArgs,
+    #[clap(flatten)]
+    fee: FeeArgs,
+    #[clap(
+        long,
+        help = "Highest nonce used in a transaction you've submitted. Nonces below it that are not \
+            yet taken are reported as gaps"
+    )]
+    up_to: Option<u64>,
+    #[clap(
+        long,
+        requires = "up_to",
+        help = "Fill detected gaps with no-op transactions, confirming each one"
+    )]
+    fix: bool,
+    #[clap(help = "Account address")]
+    address: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Doctor {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let force_fee = self.fee.is_forced();
+        let fee_setting = self.fee.into_setting()?;
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = felt_decoder
+            .decode_single_with_addr_fallback(&self.address)
+            .await?;
+
+        let latest_nonce: u64 = provider
+            .get_nonce(BlockId::Tag(BlockTag::Latest), address)
+            .await?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("nonce out of range"))?;
+        let pending_nonce: u64 = provider
+            .get_nonce(BlockId::Tag(BlockTag::Pending), address)
+            .await?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("nonce out of range"))?;
+
+        eprintln!(
+            "Latest nonce: {}; pending nonce: {}",
+            format!("{}", latest_nonce).bright_yellow(),
+            format!("{}", pending_nonce).bright_yellow()
+        );
+        if pending_nonce > latest_nonce {
+            eprintln!(
+                "{} transaction(s) from this account are in the pending block.",
+                format!("{}", pending_nonce - latest_nonce).bright_yellow()
+            );
+        }
+
+        let up_to = match self.up_to {
+            Some(up_to) => up_to,
+            None => {
+                eprintln!(
+                    "Transactions not yet included in the pending block are invisible to JSON-RPC. \
+                    Use --up-to with the highest nonce you've submitted to check for gaps."
+                );
+                return Ok(());
+            }
+        };
+
+        if up_to < pending_nonce {
+            eprintln!(
+                "No gap found: nonce {} has already been taken.",
+                format!("{}", up_to).bright_yellow()
+            );
+            return Ok(());
+        }
+
+        let gaps = (pending_nonce..up_to).collect::<Vec<_>>();
+        if gaps.is_empty() {
+            eprintln!(
+                "No gap found: nonce {} is the next nonce to be used.",
+                format!("{}", up_to).bright_yellow()
+            );
+            return Ok(());
+        }
+
+        eprintln!(
+            "Transactions with nonce {} cannot be included until these nonces are taken:",
+            format!("{}", up_to).bright_yellow()
+        );
+        for nonce in gaps.iter() {
+            eprintln!("    {}", format!("{}", nonce).bright_yellow());
+        }
+
+        if !self.fix {
+            eprintln!("Run again with --fix to fill the gaps with no-op transactions.");
+            return Ok(());
+        }
+
+        let account = self.account.into_account(provider.clone()).await?;
+        if account.address() != address {
+            anyhow::bail!(
+                "account address mismatch. Expected: {:#064x}; actual: {:#064x}.",
+                address,
+                account.address()
+            );
+        }
+
+        // Only the first gap can be estimated as the others are not valid nonces yet. The same fee
+        // is used for all of them.
+        let max_fee = match fee_setting {
+            FeeSetting::Manual(fee) => fee,
+            FeeSetting::EstimateOnly { .. } | FeeSetting::None => {
+                let fee_estimate = account
+                    .execute(vec![noop_call(provider.eth_token_address(), address)])
+                    .nonce(gaps[0].into())
+                    .estimate_fee()
+                    .await?;
+                let estimated_fee = fee_estimate.overall_fee;
+
+                if fee_setting.is_estimate_only() {
+                    if fee_setting.is_json() {
+                        print_colored_json(&fee_estimate)?;
+                    } else {
+                        println!(
+                            "{} ETH per transaction",
+                            format_ether(estimated_fee).bright_yellow(),
+                        );
+                    }
+                    return Ok(());
+                }
+
+                (estimated_fee * felt!("3")).floor_div(felt!("2"))
+            }
+        };
+
+        check_max_fee_ceiling(&provider, max_fee, force_fee)?;
+
+        // A gap may only look like one because its transaction hasn't reached the pending block
+        // yet, and a filler would then replace it. As JSON-RPC can't tell, each filler is
+        // confirmed by the user, and nonces taken in the meantime are skipped.
+        for nonce in gaps.into_iter() {
+            let pending_nonce = provider
+                .get_nonce(BlockId::Tag(BlockTag::Pending), address)
+                .await?;
+            if pending_nonce > FieldElement::from(nonce) {
+                eprintln!(
+                    "Nonce {} has been taken in the meantime. Skipping.",
+                    format!("{}", nonce).bright_yellow()
+                );
+                continue;
+            }
+
+            eprint!(
+                "{}",
+                message(
+                    "prompt.send_noop",
+                    &[
+                        ("nonce", &nonce),
+                        ("fee", &format!("{} ETH", format_ether(max_fee)))
+                    ]
+                )
+            );
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input)?;
+            if !matches!(input.trim(), "y" | "Y" | "yes") {
+                anyhow::bail!("{}", plain_message("prompt.aborted", &[]));
+            }
+
+            let tx_hash = account
+                .execute(vec![noop_call(provider.eth_token_address(), address)])
+                .nonce(FieldElement::from(nonce))
+                .max_fee(max_fee)
+                .send()
+                .await?
+                .transaction_hash;
+
+            eprintln!(
+                "Nonce {} filled with transaction: {}",
+                format!("{}", nonce).bright_yellow(),
+                format!("{:#064x}", tx_hash).bright_yellow()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use starknet::{core::types::FieldElement, providers::Provider};
+
+use crate::{default_block::default_block_id, verbosity::VerbosityArgs, ProviderArgs};
+
+mod doctor;
+use doctor::Doctor;
+
+#[derive(Debug, Parser)]
+#[clap(args_conflicts_with_subcommands = true)]
+pub struct Nonce {
+    #[clap(subcommand)]
+    command: Option<Subcommands>,
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(required = true, help = "Contract address")]
+    address: Option<String>,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Detect nonce gaps for an account and optionally fill them")]
+    Doctor(Doctor),
+}
+
+impl Nonce {
+    pub async fn run(self) -> Result<()> {
+        if let Some(command) = self.command {
+            return match command {
+                Subcommands::Doctor(cmd) => cmd.run().await,
+            };
+        }
+
+        self.verbosity.setup_logging();
+
+        let provider = self.provider.into_provider()?;
+        let address = FieldElement::from_hex_be(
+            &self
+                .address
+                .ok_or_else(|| anyhow::anyhow!("missing contract address"))?,
+        )?;
+
+        let nonce = provider.get_nonce(default_block_id(), address).await?;
+
+        println!("{}", nonce);
+
+        Ok(())
+    }
+}
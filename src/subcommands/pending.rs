@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement},
+    providers::Provider,
+};
+
+use crate::{
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    utils::{decode_execute_calldata, json_felt},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Pending {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, help = "Do not decode calldata into individual calls")]
+    raw: bool,
+    #[clap(help = "Sender address")]
+    address: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+struct PendingTransaction {
+    hash: FieldElement,
+    tx_type: String,
+    nonce: Option<FieldElement>,
+    calldata: Vec<FieldElement>,
+}
+
+impl Pending {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = felt_decoder
+            .decode_single_with_addr_fallback(&self.address)
+            .await?;
+
+        let latest_nonce = provider
+            .get_nonce(BlockId::Tag(BlockTag::Latest), address)
+            .await?;
+        let pending_nonce = provider
+            .get_nonce(BlockId::Tag(BlockTag::Pending), address)
+            .await?;
+
+        eprintln!(
+            "Latest nonce: {}; pending nonce: {}",
+            format!("{}", latest_nonce).bright_yellow(),
+            format!("{}", pending_nonce).bright_yellow()
+        );
+
+        // Transactions are handled as JSON to uniformly cover all types and versions
+        let block = serde_json::to_value(
+            provider
+                .get_block_with_txs(BlockId::Tag(BlockTag::Pending))
+                .await?,
+        )?;
+
+        let mut pending_txs = vec![];
+        if let Some(transactions) = block.get("transactions").and_then(|txs| txs.as_array()) {
+            for tx in transactions.iter() {
+                let sender = tx
+                    .get("sender_address")
+                    .and_then(|value| value.as_str())
+                    .and_then(|value| FieldElement::from_hex_be(value).ok());
+                if sender != Some(address) {
+                    continue;
+                }
+
+                pending_txs.push(PendingTransaction {
+                    hash: json_felt(tx, "transaction_hash")
+                        .ok_or_else(|| anyhow::anyhow!("missing transaction hash"))?,
+                    tx_type: tx
+                        .get("type")
+                        .and_then(|value| value.as_str())
+                        .unwrap_or("UNKNOWN")
+                        .to_owned(),
+                    nonce: json_felt(tx, "nonce"),
+                    calldata: tx
+                        .get("calldata")
+                        .and_then(|value| value.as_array())
+                        .map(|calldata| {
+                            calldata
+                                .iter()
+                                .filter_map(|item| item.as_str())
+                                .filter_map(|item| FieldElement::from_hex_be(item).ok())
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                });
+            }
+        }
+
+        if pending_txs.is_empty() {
+            eprintln!("No pending transaction found from this address.");
+            return Ok(());
+        }
+
+        eprintln!(
+            "Found {} pending transaction(s):",
+            format!("{}", pending_txs.len()).bright_yellow()
+        );
+
+        for tx in pending_txs.iter() {
+            println!(
+                "Nonce {}: {} ({})",
+                match tx.nonce {
+                    Some(nonce) => format!("{}", nonce),
+                    None => "-".to_owned(),
+                },
+                format!("{:#064x}", tx.hash).bright_yellow(),
+                tx.tx_type
+            );
+
+            if tx.calldata.is_empty() {
+                continue;
+            }
+
+            match decode_execute_calldata(&tx.calldata) {
+                Some(calls) if !self.raw => {
+                    for call in calls.iter() {
+                        println!("    call {:#064x} {:#064x}", call.to, call.selector);
+                        for arg in call.calldata.iter() {
+                            println!("        {:#x}", arg);
+                        }
+                    }
+                }
+                _ => {
+                    println!("    calldata:");
+                    for item in tx.calldata.iter() {
+                        println!("        {:#x}", item);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use std::{
+    io::{IsTerminal, Read},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde_json::Value;
+use starknet::core::{
+    types::{contract::AbiEntry, FieldElement},
+    utils::parse_cairo_short_string,
+};
+
+use crate::{
+    abi::AbiDecoder, abi_cache::CachedAbi, address_book::AddressBookResolver, decode::FeltDecoder,
+    verbosity::VerbosityArgs, ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct PrettyFelts {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "Path to a Sierra ABI or class file, or address of a deployed contract, providing \
+                the structs and enums used in --as"
+    )]
+    abi: Option<String>,
+    #[clap(
+        long = "as",
+        value_name = "TYPE",
+        help = "Type to decode the felts as, like `u256`, `ByteArray` or `Array<MyStruct>`. \
+                Without it, each felt is annotated on its own"
+    )]
+    as_type: Option<String>,
+    #[clap(
+        long,
+        requires = "as_type",
+        help = "Decode the type repeatedly until all felts are used, for arrays copied without \
+                their length"
+    )]
+    repeat: bool,
+    #[clap(
+        help = "Felts in hex or decimal. Brackets, quotes and commas are ignored, so arrays can be \
+                pasted as-is. Read from stdin if not set"
+    )]
+    felts: Vec<String>,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl PrettyFelts {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let felts = if self.felts.is_empty() {
+            let mut stdin = std::io::stdin();
+            if stdin.is_terminal() {
+                anyhow::bail!("no felts supplied. Pass them as arguments or pipe them to stdin");
+            }
+            let mut input = String::new();
+            stdin.read_to_string(&mut input)?;
+            parse_felts(&input)?
+        } else {
+            parse_felts(&self.felts.join(" "))?
+        };
+
+        let type_name = match &self.as_type {
+            Some(type_name) => type_name,
+            None => {
+                print_annotated(&felts);
+                return Ok(());
+            }
+        };
+
+        let abi = match &self.abi {
+            Some(abi) => load_abi(abi, self.provider).await?,
+            None => vec![],
+        };
+        let decoder = AbiDecoder::new(&abi);
+        let type_name = decoder.resolve_type_name(type_name)?;
+
+        let mut offset = 0;
+        let value = if self.repeat {
+            let mut values = vec![];
+            while offset < felts.len() {
+                values.push(decoder.decode(&type_name, &felts, &mut offset)?);
+            }
+            Value::Array(values)
+        } else {
+            decoder.decode(&type_name, &felts, &mut offset)?
+        };
+
+        println!("{}", serde_json::to_string_pretty(&value)?);
+
+        if offset < felts.len() {
+            eprintln!(
+                "{}",
+                format!(
+                    "WARNING: {} felt(s) left after decoding {}. Use --repeat to decode the rest \
+                    as the same type.",
+                    felts.len() - offset,
+                    type_name
+                )
+                .bright_magenta()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints each felt with its index, in hex and decimal, and as a short string when it reads as
+/// one.
+fn print_annotated(felts: &[FieldElement]) {
+    let index_width = felts.len().saturating_sub(1).to_string().len();
+
+    for (ind, felt) in felts.iter().enumerate() {
+        let short_string = parse_cairo_short_string(felt)
+            .ok()
+            .filter(|text| {
+                !text.is_empty() && text.chars().all(|c| c.is_ascii_graphic() || c == ' ')
+            })
+            .map(|text| format!("  {:?}", text))
+            .unwrap_or_default();
+
+        println!(
+            "[{:>width$}]  {}  {}{}",
+            ind,
+            format!("{:#064x}", felt).bright_yellow(),
+            felt,
+            short_string,
+            width = index_width
+        );
+    }
+}
+
+fn parse_felts(input: &str) -> Result<Vec<FieldElement>> {
+    input
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | '[' | ']' | '"' | '\''))
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            if item.starts_with("0x") {
+                FieldElement::from_hex_be(item)
+            } else {
+                FieldElement::from_dec_str(item)
+            }
+            .map_err(|_| anyhow::anyhow!("invalid felt: {}", item))
+        })
+        .collect()
+}
+
+/// ABIs are loaded from a file when the path exists, and from the contract at the address
+/// otherwise.
+async fn load_abi(abi: &str, provider: ProviderArgs) -> Result<Vec<AbiEntry>> {
+    let path = PathBuf::from(shellexpand::tilde(abi).into_owned());
+
+    if path.exists() {
+        let file: Value = serde_json::from_reader(std::fs::File::open(&path)?)?;
+        Ok(match file {
+            // Sierra class artifacts, where the ABI is either embedded as a string or inline
+            Value::Object(mut class) => match class.remove("abi") {
+                Some(Value::String(abi)) => serde_json::from_str(&abi)?,
+                Some(abi) => serde_json::from_value(abi)?,
+                None => anyhow::bail!("file {} does not contain an ABI", path.display()),
+            },
+            abi => serde_json::from_value(abi)?,
+        })
+    } else {
+        let provider = Arc::new(provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+        let address = felt_decoder.decode_single_with_addr_fallback(abi).await?;
+
+        CachedAbi::of_contract(&provider, address)
+            .await?
+            .sierra_abi()?
+            .ok_or_else(|| anyhow::anyhow!("decoding is only supported for Sierra classes"))
+    }
+}
@@ -0,0 +1,167 @@
+use anyhow::Result;
+use clap::{ArgAction, CommandFactory, Parser};
+use serde::Serialize;
+
+use crate::{Cli, VERSION_STRING};
+
+#[derive(Debug, Parser)]
+pub struct Schema {
+    #[clap(long, help = "Include hidden commands and options")]
+    include_hidden: bool,
+}
+
+/// The whole command tree as derived from the clap definitions.
+#[derive(Serialize)]
+struct CliSchema {
+    name: String,
+    version: &'static str,
+    #[serde(flatten)]
+    root: CommandSchema,
+}
+
+#[derive(Serialize)]
+struct CommandSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    about: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    args: Vec<ArgSchema>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    subcommands: Vec<SubcommandSchema>,
+}
+
+#[derive(Serialize)]
+struct SubcommandSchema {
+    name: String,
+    #[serde(flatten)]
+    command: CommandSchema,
+}
+
+#[derive(Serialize)]
+struct ArgSchema {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    long: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    short: Option<char>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<String>,
+    kind: ArgKind,
+    positional: bool,
+    required: bool,
+    global: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    value_names: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    possible_values: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    default_values: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    conflicts_with: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ArgKind {
+    /// A switch that takes no value.
+    Flag,
+    /// A switch that can be repeated, like `-vvv`.
+    Count,
+    /// Takes a single value.
+    Value,
+    /// Takes one or more values, or can be repeated.
+    Values,
+}
+
+impl Schema {
+    pub fn run(self) -> Result<()> {
+        let mut command = Cli::command();
+        command.build();
+
+        let schema = CliSchema {
+            name: command.get_name().to_owned(),
+            version: VERSION_STRING,
+            root: self.command_schema(&command),
+        };
+
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+
+        Ok(())
+    }
+
+    fn command_schema(&self, command: &clap::Command) -> CommandSchema {
+        CommandSchema {
+            about: command
+                .get_long_about()
+                .or_else(|| command.get_about())
+                .map(|about| about.to_string()),
+            aliases: command
+                .get_visible_aliases()
+                .map(|alias| alias.to_owned())
+                .collect(),
+            args: command
+                .get_arguments()
+                .filter(|arg| self.include_hidden || !arg.is_hide_set())
+                .map(|arg| ArgSchema {
+                    id: arg.get_id().to_string(),
+                    long: arg.get_long().map(|long| long.to_owned()),
+                    short: arg.get_short(),
+                    help: arg
+                        .get_long_help()
+                        .or_else(|| arg.get_help())
+                        .map(|help| help.to_string()),
+                    kind: match arg.get_action() {
+                        ArgAction::SetTrue | ArgAction::SetFalse => ArgKind::Flag,
+                        ArgAction::Count => ArgKind::Count,
+                        ArgAction::Append => ArgKind::Values,
+                        ArgAction::Set
+                            if arg
+                                .get_num_args()
+                                .map(|range| range.max_values() > 1)
+                                .unwrap_or(false) =>
+                        {
+                            ArgKind::Values
+                        }
+                        ArgAction::Set => ArgKind::Value,
+                        // Help and version switches
+                        _ => ArgKind::Flag,
+                    },
+                    positional: arg.is_positional(),
+                    required: arg.is_required_set(),
+                    global: arg.is_global_set(),
+                    value_names: arg
+                        .get_value_names()
+                        .map(|names| names.iter().map(|name| name.to_string()).collect())
+                        .unwrap_or_default(),
+                    possible_values: arg
+                        .get_possible_values()
+                        .into_iter()
+                        .filter(|value| self.include_hidden || !value.is_hide_set())
+                        .map(|value| value.get_name().to_owned())
+                        .collect(),
+                    default_values: arg
+                        .get_default_values()
+                        .iter()
+                        .map(|value| value.to_string_lossy().into_owned())
+                        .collect(),
+                    env: arg.get_env().map(|env| env.to_string_lossy().into_owned()),
+                    conflicts_with: command
+                        .get_arg_conflicts_with(arg)
+                        .into_iter()
+                        .map(|other| other.get_id().to_string())
+                        .collect(),
+                })
+                .collect(),
+            subcommands: command
+                .get_subcommands()
+                .filter(|subcommand| self.include_hidden || !subcommand.is_hide_set())
+                .map(|subcommand| SubcommandSchema {
+                    name: subcommand.get_name().to_owned(),
+                    command: self.command_schema(subcommand),
+                })
+                .collect(),
+        }
+    }
+}
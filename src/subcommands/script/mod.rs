@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod resume;
+use resume::Resume;
+
+#[derive(Debug, Parser)]
+pub struct Script {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Resume an interrupted or partially failed run from its state file")]
+    Resume(Resume),
+}
+
+impl Script {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Resume(cmd) => cmd.run().await,
+        }
+    }
+}
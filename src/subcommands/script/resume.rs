@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+
+use crate::{path::ExpandedPathbufParser, script_state::ScriptState, Cli, Subcommands};
+
+#[derive(Debug, Parser)]
+pub struct Resume {
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the state file saved by the interrupted run"
+    )]
+    file: PathBuf,
+    #[clap(
+        last = true,
+        help = "Extra arguments to append to the saved ones, such as secrets that are never saved"
+    )]
+    args: Vec<String>,
+}
+
+impl Resume {
+    pub async fn run(self) -> Result<()> {
+        let state = ScriptState::read(&self.file)?;
+        let file = self.file.canonicalize()?;
+
+        let missing = state
+            .omitted
+            .iter()
+            .filter(|name| {
+                !self
+                    .args
+                    .iter()
+                    .any(|arg| arg == *name || arg.starts_with(&format!("{}=", name)))
+            })
+            .collect::<Vec<_>>();
+        // Resuming against another network than the original run's must never happen silently
+        if missing.iter().any(|name| *name == "--rpc") && std::env::var("STARKNET_RPC").is_err() {
+            anyhow::bail!(
+                "the original run used --rpc, which is not saved as it may contain an API key. \
+                Pass it again with `starkli script resume {} -- --rpc <URL>`",
+                self.file.display()
+            );
+        }
+        if !missing.is_empty() {
+            eprintln!(
+                "Not saved in the state file: {}. Pass them again after `--` if needed.",
+                missing
+                    .iter()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        // Relative paths in the saved arguments are relative to the original working directory
+        std::env::set_current_dir(&state.working_dir).map_err(|err| {
+            anyhow::anyhow!(
+                "unable to enter working directory {}: {}",
+                state.working_dir.display(),
+                err
+            )
+        })?;
+
+        let mut args = vec!["starkli".to_owned()];
+        args.extend(state.args);
+
+        // Checked before adding `--resume`, which other commands would reject with a confusing error
+        if !matches!(
+            Cli::try_parse_from(&args).map(|cli| cli.command),
+            Ok(Some(Subcommands::Deploy(_) | Subcommands::Multi(_)))
+        ) {
+            anyhow::bail!(
+                "state file {} is not from a resumable command",
+                file.display()
+            );
+        }
+
+        args.push("--resume".to_owned());
+        args.push(file.to_string_lossy().into_owned());
+        args.extend(self.args);
+
+        match Cli::try_parse_from(args)?.command {
+            Some(Subcommands::Deploy(cmd)) => cmd.run().await,
+            Some(Subcommands::Multi(cmd)) => cmd.run().await,
+            _ => unreachable!(),
+        }
+    }
+}
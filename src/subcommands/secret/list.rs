@@ -0,0 +1,28 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::secrets::SecretStore;
+
+#[derive(Debug, Parser)]
+pub struct List {}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        eprintln!(
+            "Secrets directory: {}",
+            format!("{}", SecretStore::get_folder()?.display()).bright_yellow()
+        );
+
+        let names = SecretStore::list()?;
+        if names.is_empty() {
+            eprintln!("No secret found.");
+        }
+
+        for name in names.into_iter() {
+            println!("{}", name);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod set;
+use set::Set;
+
+mod list;
+use list::List;
+
+mod remove;
+use remove::Remove;
+
+#[derive(Debug, Parser)]
+pub struct Secret {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Encrypt and store a secret under a name")]
+    Set(Set),
+    #[clap(about = "List the names of stored secrets")]
+    List(List),
+    #[clap(about = "Delete a stored secret")]
+    Remove(Remove),
+}
+
+impl Secret {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Set(cmd) => cmd.run(),
+            Subcommands::List(cmd) => cmd.run(),
+            Subcommands::Remove(cmd) => cmd.run(),
+        }
+    }
+}
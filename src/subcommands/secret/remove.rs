@@ -0,0 +1,19 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::secrets::SecretStore;
+
+#[derive(Debug, Parser)]
+pub struct Remove {
+    #[clap(help = "Name of the secret")]
+    name: String,
+}
+
+impl Remove {
+    pub fn run(self) -> Result<()> {
+        SecretStore::remove(&self.name)?;
+        eprintln!("Secret {} removed", self.name);
+
+        Ok(())
+    }
+}
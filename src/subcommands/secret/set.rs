@@ -0,0 +1,44 @@
+use std::io::Read;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::secrets::SecretStore;
+
+#[derive(Debug, Parser)]
+pub struct Set {
+    #[clap(long, help = "Overwrite the secret if it already exists")]
+    force: bool,
+    #[clap(long, help = "Take the secret value from stdin instead of prompt")]
+    value_stdin: bool,
+    #[clap(help = "Name of the secret")]
+    name: String,
+}
+
+impl Set {
+    pub fn run(self) -> Result<()> {
+        if SecretStore::exists(&self.name)? && !self.force {
+            anyhow::bail!("secret already exists. Use --force to overwrite");
+        }
+
+        let value = if self.value_stdin {
+            let mut buffer = String::new();
+            std::io::stdin().read_to_string(&mut buffer)?;
+
+            buffer.trim_end_matches(['\r', '\n']).to_owned()
+        } else {
+            rpassword::prompt_password("Enter secret value: ")?
+        };
+
+        SecretStore::store(&self.name, &value)?;
+
+        eprintln!(
+            "Secret {} saved to {}",
+            self.name.bright_yellow(),
+            format!("{}", SecretStore::get_path(&self.name)?.display()).bright_yellow()
+        );
+
+        Ok(())
+    }
+}
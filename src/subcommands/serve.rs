@@ -0,0 +1,638 @@
+use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Instant};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use hyper::{
+    header::{
+        HeaderValue, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_ORIGIN, AUTHORIZATION,
+        CONTENT_TYPE, HOST, ORIGIN,
+    },
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use starknet::{
+    accounts::{Account, Call, SingleOwnerAccount},
+    core::{types::FieldElement, utils::get_selector_from_name},
+    macros::felt,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    abi::{function_inputs, AbiDecoder},
+    abi_cache::CachedAbi,
+    account::AccountArgs,
+    amount::format_ether,
+    explorer::FunctionNames,
+    fee::check_max_fee_ceiling,
+    messages::message,
+    metrics::{spawn_metrics_server, Counter, Latency, MetricsWriter},
+    provider::ExtendedProvider,
+    signer::AnySigner,
+    token::TokenRegistry,
+    utils::format_address,
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+/// Error code for requests rejected by the user, same as in EIP-1193.
+const USER_REJECTED_CODE: i64 = 4001;
+const INTERNAL_ERROR_CODE: i64 = -32000;
+const INVALID_PARAMS_CODE: i64 = -32602;
+
+#[derive(Debug, Parser)]
+pub struct Serve {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    account: AccountArgs,
+    #[clap(
+        long,
+        default_value = "5050",
+        help = "Port to listen on. The service is only exposed on localhost"
+    )]
+    port: u16,
+    #[clap(
+        long,
+        help = "Approve all transaction requests without prompting. Only use this with trusted \
+                local clients"
+    )]
+    auto_approve: bool,
+    #[clap(
+        long,
+        help = "Allow browser dapps served from this origin to make requests (CORS)"
+    )]
+    allow_origin: Option<String>,
+    #[clap(
+        long,
+        help = "Expose Prometheus metrics on this port. Also only exposed on localhost"
+    )]
+    metrics_port: Option<u16>,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+struct ServeContext {
+    provider: Arc<ExtendedProvider>,
+    account: SingleOwnerAccount<Arc<ExtendedProvider>, AnySigner>,
+    auto_approve: bool,
+    allow_origin: Option<HeaderValue>,
+    /// `Host` header values accepted, which guards against DNS rebinding.
+    allowed_hosts: Vec<String>,
+    /// Bearer token generated for this session, which clients must send in `Authorization`.
+    token: String,
+    http_client: reqwest::Client,
+    /// Serializes approval prompts and sending, so that requests don't interleave on the terminal
+    /// or race for the same nonce.
+    send_lock: Mutex<()>,
+    metrics: Arc<ProxyMetrics>,
+}
+
+#[derive(Default)]
+struct ProxyMetrics {
+    requests: Counter,
+    errors: Counter,
+    upstream_latency: Latency,
+    transactions_sent: Counter,
+    transactions_rejected: Counter,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct TransactionParams {
+    calls: Vec<CallParams>,
+    #[serde(default)]
+    max_fee: Option<FieldElement>,
+}
+
+#[derive(Deserialize)]
+struct CallParams {
+    to: FieldElement,
+    /// Either a function name or a hex-encoded selector.
+    selector: String,
+    #[serde(default)]
+    calldata: Vec<FieldElement>,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl Serve {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let account = self.account.into_account(provider.clone()).await?;
+        let account_address = account.address();
+
+        let allow_origin = match self.allow_origin {
+            Some(origin) => Some(HeaderValue::from_str(&origin)?),
+            None => None,
+        };
+
+        let metrics = Arc::<ProxyMetrics>::default();
+        if let Some(metrics_port) = self.metrics_port {
+            let metrics = metrics.clone();
+            let metrics_address = spawn_metrics_server(metrics_port, move || metrics.render())?;
+
+            eprintln!(
+                "Serving metrics on {}",
+                format!("http://{}/metrics", metrics_address).bright_yellow()
+            );
+        }
+
+        let address = SocketAddr::from(([127, 0, 0, 1], self.port));
+        let server = Server::try_bind(&address)?;
+
+        let mut token = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut token);
+        let token = hex::encode(token);
+
+        let context = Arc::new(ServeContext {
+            provider,
+            account,
+            auto_approve: self.auto_approve,
+            allow_origin,
+            allowed_hosts: vec![
+                format!("127.0.0.1:{}", self.port),
+                format!("localhost:{}", self.port),
+            ],
+            token: token.clone(),
+            http_client: reqwest::Client::new(),
+            send_lock: Mutex::new(()),
+            metrics,
+        });
+
+        let make_service = make_service_fn(move |_| {
+            let context = context.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let context = context.clone();
+                    async move { Ok::<_, Infallible>(context.handle(request).await) }
+                }))
+            }
+        });
+
+        let server = server.serve(make_service);
+        log::info!("serving account {:#064x} on {}", account_address, address);
+
+        eprintln!(
+            "Serving account {} on {}",
+            format_address(account_address).bright_yellow(),
+            format!("http://{}", address).bright_yellow()
+        );
+        eprintln!(
+            "Methods `starkli_getAccount`, `starkli_estimateFee`, and `starkli_sendTransaction` \
+            are handled locally. Other requests are forwarded to the JSON-RPC endpoint."
+        );
+        eprintln!(
+            "Requests must be sent with the header `Authorization: Bearer {}`",
+            token.bright_yellow()
+        );
+        if self.auto_approve {
+            eprintln!(
+                "{}",
+                "WARNING: all transaction requests are approved automatically.".bright_magenta()
+            );
+        }
+
+        server.await?;
+
+        Ok(())
+    }
+}
+
+impl ServeContext {
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let mut response = match self.check_request(&request) {
+            Err(response) => response,
+            Ok(()) => match *request.method() {
+                // CORS preflight
+                Method::OPTIONS => Response::new(Body::empty()),
+                Method::POST => match hyper::body::to_bytes(request.into_body()).await {
+                    Ok(body) => self.handle_body(&body).await,
+                    Err(err) => plain_response(StatusCode::BAD_REQUEST, err.to_string()),
+                },
+                _ => plain_response(
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    "only POST requests are supported".into(),
+                ),
+            },
+        };
+
+        if let Some(origin) = &self.allow_origin {
+            let headers = response.headers_mut();
+            headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_HEADERS,
+                HeaderValue::from_static("authorization, content-type"),
+            );
+        }
+
+        response
+    }
+
+    /// Rejects requests from other websites, through DNS rebinding, or without the session token.
+    /// CORS preflight requests can't carry credentials, so only their origin and host are checked.
+    fn check_request(&self, request: &Request<Body>) -> Result<(), Response<Body>> {
+        let headers = request.headers();
+
+        if let Some(origin) = headers.get(ORIGIN) {
+            if self.allow_origin.as_ref() != Some(origin) {
+                return Err(plain_response(
+                    StatusCode::FORBIDDEN,
+                    "origin not allowed".into(),
+                ));
+            }
+        }
+
+        let host_allowed = match headers.get(HOST).and_then(|host| host.to_str().ok()) {
+            Some(host) => self
+                .allowed_hosts
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(host)),
+            None => false,
+        };
+        if !host_allowed {
+            return Err(plain_response(
+                StatusCode::FORBIDDEN,
+                "host not allowed".into(),
+            ));
+        }
+
+        if request.method() != Method::POST {
+            return Ok(());
+        }
+
+        let is_json = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().eq_ignore_ascii_case("application/json"))
+            .unwrap_or(false);
+        if !is_json {
+            return Err(plain_response(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "content type must be application/json".into(),
+            ));
+        }
+
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if token.map(|token| token.trim()) != Some(self.token.as_str()) {
+            return Err(plain_response(
+                StatusCode::UNAUTHORIZED,
+                "missing or invalid bearer token".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn handle_body(&self, body: &[u8]) -> Response<Body> {
+        self.metrics.requests.inc();
+
+        let request = match serde_json::from_slice::<JsonRpcRequest>(body) {
+            Ok(request) => request,
+            Err(err) => {
+                return plain_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("invalid JSON-RPC request: {}", err),
+                )
+            }
+        };
+
+        let result = match request.method.as_str() {
+            "starkli_getAccount" => Ok(json!({
+                "address": format!("{:#064x}", self.account.address()),
+                "chain_id": format!("{:#x}", self.account.chain_id()),
+            })),
+            "starkli_estimateFee" => self.estimate_fee(request.params).await,
+            "starkli_sendTransaction" => self.send_transaction(request.params).await,
+            _ => return self.forward(body).await,
+        };
+
+        let response = match result {
+            Ok(result) => json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "result": result,
+            }),
+            Err(err) => {
+                self.metrics.errors.inc();
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": request.id,
+                    "error": {
+                        "code": err.code,
+                        "message": err.message,
+                    },
+                })
+            }
+        };
+
+        let mut response = Response::new(Body::from(response.to_string()));
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+    }
+
+    async fn estimate_fee(&self, params: Value) -> Result<Value, RpcError> {
+        let params = parse_params(params)?;
+        let calls = parse_calls(params.calls)?;
+
+        let estimate = self
+            .account
+            .execute(calls)
+            .estimate_fee()
+            .await
+            .map_err(RpcError::internal)?;
+
+        serde_json::to_value(estimate).map_err(RpcError::internal)
+    }
+
+    async fn send_transaction(&self, params: Value) -> Result<Value, RpcError> {
+        let params = parse_params(params)?;
+        let calls = parse_calls(params.calls)?;
+
+        let _guard = self.send_lock.lock().await;
+
+        let execution = self.account.execute(calls.clone());
+        let max_fee = match params.max_fee {
+            Some(max_fee) => max_fee,
+            None => {
+                let estimated_fee = execution
+                    .estimate_fee()
+                    .await
+                    .map_err(RpcError::internal)?
+                    .overall_fee;
+
+                (estimated_fee * felt!("3")).floor_div(felt!("2"))
+            }
+        };
+        check_max_fee_ceiling(&self.provider, max_fee, false).map_err(RpcError::internal)?;
+
+        if !self.auto_approve {
+            let description = describe_calls(&self.provider, &calls).await;
+            let approved =
+                tokio::task::spawn_blocking(move || prompt_approval(&description, max_fee))
+                    .await
+                    .map_err(RpcError::internal)?
+                    .map_err(RpcError::internal)?;
+            if !approved {
+                self.metrics.transactions_rejected.inc();
+                return Err(RpcError {
+                    code: USER_REJECTED_CODE,
+                    message: "transaction rejected by user".into(),
+                });
+            }
+        }
+
+        let transaction_hash = execution
+            .max_fee(max_fee)
+            .send()
+            .await
+            .map_err(RpcError::internal)?
+            .transaction_hash;
+        self.metrics.transactions_sent.inc();
+        log::info!("sent invoke transaction {:#064x}", transaction_hash);
+
+        eprintln!(
+            "{}",
+            message(
+                "invoke.sent",
+                &[("hash", &format!("{:#064x}", transaction_hash))]
+            )
+        );
+
+        Ok(json!({
+            "transaction_hash": format!("{:#064x}", transaction_hash),
+        }))
+    }
+
+    /// Forwards the request untouched to the upstream JSON-RPC endpoint.
+    async fn forward(&self, body: &[u8]) -> Response<Body> {
+        let start_time = Instant::now();
+        let upstream = self
+            .http_client
+            .post(self.provider.rpc_url().clone())
+            .header("Content-Type", "application/json")
+            .body(body.to_vec())
+            .send()
+            .await;
+
+        self.metrics.upstream_latency.observe(start_time.elapsed());
+
+        let upstream = match upstream {
+            Ok(upstream) => upstream,
+            Err(err) => {
+                self.metrics.errors.inc();
+                return plain_response(StatusCode::BAD_GATEWAY, err.to_string());
+            }
+        };
+        let status = upstream.status().as_u16();
+
+        match upstream.bytes().await {
+            Ok(bytes) => {
+                let mut response = Response::new(Body::from(bytes));
+                *response.status_mut() =
+                    StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY);
+                response
+                    .headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                response
+            }
+            Err(err) => plain_response(StatusCode::BAD_GATEWAY, err.to_string()),
+        }
+    }
+}
+
+impl ProxyMetrics {
+    fn render(&self) -> String {
+        let mut writer = MetricsWriter::default();
+        writer.counter(
+            "starkli_serve_requests_total",
+            "JSON-RPC requests received",
+            &self.requests,
+        );
+        writer.counter(
+            "starkli_serve_errors_total",
+            "Requests that failed, either locally or upstream",
+            &self.errors,
+        );
+        writer.latency(
+            "starkli_serve_upstream_latency_seconds",
+            "Latency of requests forwarded to the JSON-RPC endpoint",
+            &self.upstream_latency,
+        );
+        writer.counter(
+            "starkli_serve_transactions_sent_total",
+            "Transactions signed and sent",
+            &self.transactions_sent,
+        );
+        writer.counter(
+            "starkli_serve_transactions_rejected_total",
+            "Transaction requests rejected at the approval prompt",
+            &self.transactions_rejected,
+        );
+        writer.finish()
+    }
+}
+
+impl RpcError {
+    fn internal<E>(err: E) -> Self
+    where
+        E: std::fmt::Display,
+    {
+        Self {
+            code: INTERNAL_ERROR_CODE,
+            message: err.to_string(),
+        }
+    }
+}
+
+/// Accepts both named params (`{"calls": [...]}`) and positional params (`[{"calls": [...]}]`).
+fn parse_params(params: Value) -> Result<TransactionParams, RpcError> {
+    let params = match params {
+        Value::Array(mut items) if items.len() == 1 => items.remove(0),
+        params => params,
+    };
+
+    serde_json::from_value(params).map_err(|err| RpcError {
+        code: INVALID_PARAMS_CODE,
+        message: format!("invalid params: {}", err),
+    })
+}
+
+fn parse_calls(calls: Vec<CallParams>) -> Result<Vec<Call>, RpcError> {
+    if calls.is_empty() {
+        return Err(RpcError {
+            code: INVALID_PARAMS_CODE,
+            message: "empty execution".into(),
+        });
+    }
+
+    calls
+        .into_iter()
+        .map(|call| {
+            let selector = match FieldElement::from_hex_be(&call.selector) {
+                Ok(selector) if call.selector.starts_with("0x") => selector,
+                _ => get_selector_from_name(&call.selector).map_err(|err| RpcError {
+                    code: INVALID_PARAMS_CODE,
+                    message: format!("invalid selector `{}`: {}", call.selector, err),
+                })?,
+            };
+
+            Ok(Call {
+                to: call.to,
+                selector,
+                calldata: call.calldata,
+            })
+        })
+        .collect()
+}
+
+/// Describes calls for approval. Functions and their arguments are decoded with the ABI of the
+/// called contract when available, and known tokens are named, so that users don't have to approve
+/// raw calldata.
+async fn describe_calls(provider: &ExtendedProvider, calls: &[Call]) -> Vec<String> {
+    let registry = TokenRegistry::load_for_provider(provider).await.ok();
+    let mut function_names = FunctionNames::new(provider, None);
+
+    let mut lines = vec![];
+    for call in calls.iter() {
+        let target = match registry.as_ref().and_then(|registry| registry.get(call.to)) {
+            Some(token) => format!("{} ({})", format_address(call.to), token.symbol),
+            None => format_address(call.to),
+        };
+        let function = match function_names.lookup(call.to, call.selector).await {
+            Some(name) => name,
+            None => format!("{:#064x}", call.selector),
+        };
+        lines.push(format!("call {} {}", target, function));
+
+        match decode_call_args(provider, call).await {
+            Some(args) => {
+                for (name, value) in args.into_iter() {
+                    lines.push(format!("    {}: {}", name, value));
+                }
+            }
+            None => {
+                for arg in call.calldata.iter() {
+                    lines.push(format!("    {:#x}", arg));
+                }
+            }
+        }
+    }
+
+    lines
+}
+
+/// Decodes call arguments by name, or `None` if the calldata doesn't match the function inputs in
+/// the ABI of the called contract.
+async fn decode_call_args(
+    provider: &ExtendedProvider,
+    call: &Call,
+) -> Option<Vec<(String, Value)>> {
+    let abi = CachedAbi::of_contract(provider, call.to)
+        .await
+        .ok()?
+        .sierra_abi()
+        .ok()??;
+    let inputs = function_inputs(&abi, call.selector)?;
+    let decoder = AbiDecoder::new(&abi);
+
+    let mut offset = 0;
+    let mut args = vec![];
+    for input in inputs.into_iter() {
+        let value = decoder
+            .decode(&input.r#type, &call.calldata, &mut offset)
+            .ok()?;
+        args.push((input.name, value));
+    }
+
+    if offset != call.calldata.len() {
+        return None;
+    }
+    Some(args)
+}
+
+fn prompt_approval(description: &[String], max_fee: FieldElement) -> Result<bool> {
+    eprintln!("Transaction request received:");
+    for line in description.iter() {
+        eprintln!("    {}", line);
+    }
+    eprint!(
+        "{}",
+        message(
+            "prompt.send_with_fee",
+            &[("fee", &format!("{} ETH", format_ether(max_fee)))]
+        )
+    );
+
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim(), "y" | "Y" | "yes"))
+}
+
+fn plain_response(status: StatusCode, message: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(message));
+    *response.status_mut() = status;
+    response
+}
@@ -0,0 +1,168 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use anyhow::Result;
+use cairo_sierra_2_4_0::program::Statement;
+use cairo_starknet_2_4_0::contract_class::ContractClass as Cairo240Class;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::types::{contract::SierraClass, SierraEntryPoint};
+
+use crate::{compiler::SierraVersion, path::ExpandedPathbufParser};
+
+#[derive(Debug, Parser)]
+pub struct Inspect {
+    #[clap(
+        long,
+        default_value = "20",
+        help = "Number of most used libfuncs to show. Use 0 to show all"
+    )]
+    top: usize,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the Sierra contract artifact"
+    )]
+    file: PathBuf,
+}
+
+impl Inspect {
+    pub fn run(self) -> Result<()> {
+        let class: SierraClass = serde_json::from_reader(std::fs::File::open(&self.file)?)?;
+
+        println!(
+            "Sierra version:   {}",
+            SierraVersion::from_class(&class)?
+                .to_string()
+                .bright_yellow()
+        );
+        println!(
+            "Compiler version: {}",
+            SierraVersion::compiler_from_class(&class)?
+                .to_string()
+                .bright_yellow()
+        );
+        println!(
+            "Program length:   {} felts",
+            class.sierra_program.len().to_string().bright_yellow()
+        );
+
+        // Only the Sierra program is needed. Dropping the ABI avoids deserialization errors from
+        // ABI format changes between compiler versions.
+        let mut stripped_class = class.clone();
+        stripped_class.abi.clear();
+        let contract_class: Cairo240Class =
+            serde_json::from_str(&serde_json::to_string(&stripped_class)?)?;
+        let program = contract_class
+            .extract_sierra_program()
+            .map_err(|err| anyhow::anyhow!("unable to decode Sierra program: {}", err))?;
+
+        println!(
+            "Statements:       {}",
+            program.statements.len().to_string().bright_yellow()
+        );
+        println!(
+            "Functions:        {}",
+            program.funcs.len().to_string().bright_yellow()
+        );
+        println!(
+            "Types:            {}",
+            program.type_declarations.len().to_string().bright_yellow()
+        );
+
+        // Libfunc usage histogram by generic libfunc
+        let generic_ids = program
+            .libfunc_declarations
+            .iter()
+            .map(|declaration| {
+                (
+                    declaration.id.id,
+                    declaration.long_id.generic_id.0.to_string(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let mut usages: HashMap<&str, usize> = HashMap::new();
+        for statement in program.statements.iter() {
+            if let Statement::Invocation(invocation) = statement {
+                if let Some(generic_id) = generic_ids.get(&invocation.libfunc_id.id) {
+                    *usages.entry(generic_id).or_default() += 1;
+                }
+            }
+        }
+        let mut usages = usages.into_iter().collect::<Vec<_>>();
+        usages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        println!();
+        println!(
+            "Libfunc usage ({} distinct):",
+            usages.len().to_string().bright_yellow()
+        );
+        let shown = if self.top == 0 {
+            usages.len()
+        } else {
+            self.top.min(usages.len())
+        };
+        for (libfunc, count) in usages.iter().take(shown) {
+            println!("  {:>7}  {}", count, libfunc);
+        }
+        if shown < usages.len() {
+            println!("  ... and {} more", usages.len() - shown);
+        }
+
+        // Function sizes are derived from the distance between consecutive entry points
+        let mut entry_points = program
+            .funcs
+            .iter()
+            .map(|func| func.entry_point.0)
+            .collect::<Vec<_>>();
+        entry_points.sort_unstable();
+        let function_size = |entry_point: usize| {
+            let next = entry_points
+                .iter()
+                .find(|other| **other > entry_point)
+                .copied()
+                .unwrap_or(program.statements.len());
+            next - entry_point
+        };
+
+        let function_names = class
+            .sierra_program_debug_info
+            .user_func_names
+            .iter()
+            .map(|(id, name)| (*id, name.as_str()))
+            .collect::<HashMap<_, _>>();
+
+        let print_entry_points = |kind: &str, entry_points: &[SierraEntryPoint]| -> Result<()> {
+            if entry_points.is_empty() {
+                return Ok(());
+            }
+
+            println!();
+            println!("{} entrypoints:", kind);
+            for entry_point in entry_points.iter() {
+                let func = program
+                    .funcs
+                    .get(entry_point.function_idx as usize)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("invalid function index: {}", entry_point.function_idx)
+                    })?;
+
+                println!(
+                    "  {}  {:>7} statements  {}",
+                    format!("{:#064x}", entry_point.selector).bright_yellow(),
+                    function_size(func.entry_point.0),
+                    function_names
+                        .get(&func.id.id)
+                        .copied()
+                        .unwrap_or("<unknown>")
+                );
+            }
+
+            Ok(())
+        };
+
+        print_entry_points("Constructor", &class.entry_points_by_type.constructor)?;
+        print_entry_points("External", &class.entry_points_by_type.external)?;
+        print_entry_points("L1 handler", &class.entry_points_by_type.l1_handler)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod inspect;
+use inspect::Inspect;
+
+#[derive(Debug, Parser)]
+pub struct Sierra {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Show version, size, and libfunc usage statistics of a Sierra class")]
+    Inspect(Inspect),
+}
+
+impl Sierra {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Inspect(cmd) => cmd.run(),
+        }
+    }
+}
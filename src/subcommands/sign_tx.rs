@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::signers::Signer;
+
+use crate::{
+    path::ExpandedPathbufParser, signer::SignerArgs, transaction_file::TransactionFile,
+    verbosity::VerbosityArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct SignTx {
+    #[clap(flatten)]
+    signer: SignerArgs,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Path to write the signed transaction to. Defaults to updating the file in place"
+    )]
+    output: Option<PathBuf>,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the transaction file exported with `--export`"
+    )]
+    file: PathBuf,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl SignTx {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let mut transaction_file = TransactionFile::load(&self.file)?;
+        let transaction_hash = transaction_file.verify_hash()?;
+
+        transaction_file.print_summary();
+
+        if !transaction_file.signature.is_empty() {
+            eprintln!(
+                "{}",
+                "WARNING: the transaction is already signed. The existing signature will be replaced."
+                    .bright_magenta()
+            );
+        }
+
+        let signer = self.signer.into_signer()?;
+        let signature = signer.sign_hash(&transaction_hash).await?;
+        transaction_file.signature = vec![signature.r, signature.s];
+
+        let output = self.output.unwrap_or(self.file);
+        transaction_file.save(&output)?;
+
+        eprintln!(
+            "Signed transaction written to {}",
+            output.display().to_string().bright_yellow()
+        );
+
+        Ok(())
+    }
+}
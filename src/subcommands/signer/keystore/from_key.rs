@@ -2,10 +2,9 @@ use std::{io::Read, path::PathBuf};
 
 use anyhow::Result;
 use clap::Parser;
-use colored::Colorize;
 use starknet::{core::types::FieldElement, signers::SigningKey};
 
-use crate::path::ExpandedPathbufParser;
+use crate::{path::ExpandedPathbufParser, signer::named_keystore_path, warning::Warning};
 
 #[derive(Debug, Parser)]
 pub struct FromKey {
@@ -18,25 +17,42 @@ pub struct FromKey {
         help = "Supply password from command line option instead of prompt"
     )]
     password: Option<String>,
+    #[clap(
+        long,
+        conflicts_with = "file",
+        help = "Save the keystore in the keystores directory under this name instead of a path"
+    )]
+    name: Option<String>,
     #[clap(
         value_parser = ExpandedPathbufParser,
+        required_unless_present = "name",
         help = "Path to save the JSON keystore"
     )]
-    file: PathBuf,
+    file: Option<PathBuf>,
 }
 
 impl FromKey {
     pub fn run(self) -> Result<()> {
         if self.password.is_some() {
-            eprintln!(
-                "{}",
-                "WARNING: setting passwords via --password is generally considered insecure, \
-                as they will be stored in your shell history or other log files."
-                    .bright_magenta()
+            Warning::PasswordOption.print(
+                "setting passwords via --password is generally considered insecure, as they will \
+                be stored in your shell history or other log files.",
             );
         }
 
-        if self.file.exists() && !self.force {
+        let file = match (self.file, self.name) {
+            (Some(file), _) => file,
+            (None, Some(name)) => {
+                let path = named_keystore_path(&name)?;
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                path
+            }
+            (None, None) => anyhow::bail!("either a path or --name must be provided"),
+        };
+
+        if file.exists() && !self.force {
             anyhow::bail!("keystore file already exists");
         }
 
@@ -57,11 +73,11 @@ impl FromKey {
         };
 
         let key = SigningKey::from_secret_scalar(private_key);
-        key.save_as_keystore(&self.file, &password)?;
+        key.save_as_keystore(&file, &password)?;
 
         println!(
             "Created new encrypted keystore file: {}",
-            std::fs::canonicalize(self.file)?.display()
+            std::fs::canonicalize(file)?.display()
         );
         println!("Public key: {:#064x}", key.verifying_key().scalar());
 
@@ -2,10 +2,9 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use clap::Parser;
-use colored::Colorize;
 use starknet::signers::SigningKey;
 
-use crate::path::ExpandedPathbufParser;
+use crate::{path::ExpandedPathbufParser, warning::Warning};
 
 #[derive(Debug, Parser)]
 pub struct Inspect {
@@ -26,11 +25,9 @@ pub struct Inspect {
 impl Inspect {
     pub fn run(self) -> Result<()> {
         if self.password.is_some() {
-            eprintln!(
-                "{}",
-                "WARNING: setting passwords via --password is generally considered insecure, \
-                as they will be stored in your shell history or other log files."
-                    .bright_magenta()
+            Warning::PasswordOption.print(
+                "setting passwords via --password is generally considered insecure, as they will \
+                be stored in your shell history or other log files.",
             );
         }
 
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::signers::SigningKey;
+
+use crate::{keychain::Keychain, path::ExpandedPathbufParser};
+
+#[derive(Debug, Parser)]
+pub struct LinkKeychain {
+    #[clap(long, help = "Remove the saved password from the OS keychain instead")]
+    unlink: bool,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the JSON keystore"
+    )]
+    file: PathBuf,
+}
+
+impl LinkKeychain {
+    pub fn run(self) -> Result<()> {
+        if !self.file.exists() {
+            anyhow::bail!("keystore file not found");
+        }
+
+        if self.unlink {
+            if Keychain::delete_keystore_password(&self.file)? {
+                eprintln!("Keystore password removed from the OS keychain");
+            } else {
+                eprintln!("Keystore is not linked to the OS keychain");
+            }
+
+            return Ok(());
+        }
+
+        let password = rpassword::prompt_password("Enter keystore password: ")?;
+
+        // Makes sure a wrong password doesn't get saved
+        SigningKey::from_keystore(&self.file, &password)?;
+
+        Keychain::set_keystore_password(&self.file, &password)?;
+
+        eprintln!(
+            "Keystore password saved to the OS keychain. {} will no longer prompt for the password.",
+            format!("{}", self.file.display()).bright_yellow()
+        );
+
+        Ok(())
+    }
+}
@@ -13,6 +13,9 @@ use inspect_private::InspectPrivate;
 mod from_key;
 use from_key::FromKey;
 
+mod link_keychain;
+use link_keychain::LinkKeychain;
+
 #[derive(Debug, Parser)]
 pub struct Keystore {
     #[clap(subcommand)]
@@ -29,6 +32,10 @@ enum Subcommands {
     Inspect(Inspect),
     #[clap(about = "Check the private key of an existing keystore file")]
     InspectPrivate(InspectPrivate),
+    #[clap(
+        about = "Save the password of a keystore to the OS keychain so it's no longer prompted"
+    )]
+    LinkKeychain(LinkKeychain),
 }
 
 impl Keystore {
@@ -38,6 +45,7 @@ impl Keystore {
             Subcommands::FromKey(cmd) => cmd.run(),
             Subcommands::Inspect(cmd) => cmd.run(),
             Subcommands::InspectPrivate(cmd) => cmd.run(),
+            Subcommands::LinkKeychain(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,221 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde::Deserialize;
+use starknet::{
+    accounts::{Account, ConnectedAccount},
+    core::types::{
+        BlockId, BlockTag, BroadcastedTransaction, ExecuteInvocation, FieldElement, SimulationFlag,
+        StarknetError, TransactionTrace,
+    },
+    providers::{Provider, ProviderError},
+};
+
+use crate::{
+    accessibility::Status,
+    account::{impersonated_account, AccountArgs},
+    address_book::AddressBookResolver,
+    amount::format_ether,
+    decode::FeltDecoder,
+    path::ExpandedPathbufParser,
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct SimulateQueue {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    account: AccountArgs,
+    #[clap(
+        long,
+        help = "Simulate as this account without its signer. Signature validation is skipped"
+    )]
+    sender: Option<String>,
+    #[clap(long, help = "Skip signature validation")]
+    skip_validate: bool,
+    #[clap(
+        long,
+        help = "Nonce of the first transaction. Defaults to the account's current nonce"
+    )]
+    nonce: Option<FieldElement>,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the queue file listing the transactions in order"
+    )]
+    file: PathBuf,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+/// Transactions to be sent one after another from the same account, as TOML:
+///
+/// ```toml
+/// [[transactions]]
+/// description = "Approve the router"
+/// calls = ["eth", "approve", "0x1234", "u256:100"]
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QueueFile {
+    #[serde(default)]
+    transactions: Vec<QueuedTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QueuedTransaction {
+    #[serde(default)]
+    description: Option<String>,
+    /// Calls in the multicall syntax of `starkli invoke`, one argument per item.
+    calls: Vec<String>,
+}
+
+impl SimulateQueue {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let queue: QueueFile =
+            toml::from_str(&std::fs::read_to_string(&self.file)?).map_err(|err| {
+                anyhow::anyhow!("invalid queue file {}: {}", self.file.display(), err)
+            })?;
+        if queue.transactions.is_empty() {
+            anyhow::bail!("no transaction in {}", self.file.display());
+        }
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let mut queued_calls = vec![];
+        for (ind, transaction) in queue.transactions.iter().enumerate() {
+            let calls = felt_decoder
+                .decode_calls(transaction.calls.clone())
+                .await
+                .map_err(|err| {
+                    anyhow::anyhow!("invalid calls in transaction #{}: {}", ind + 1, err)
+                })?;
+            if calls.is_empty() {
+                anyhow::bail!("transaction #{} has no call", ind + 1);
+            }
+            queued_calls.push(calls);
+        }
+
+        // Signing with a Ledger would prompt on the device for every transaction
+        let signerless = self.sender.is_some() || self.account.uses_ledger();
+        let account = match self.sender {
+            Some(address) => {
+                let address = felt_decoder
+                    .decode_single_with_addr_fallback(&address)
+                    .await?;
+                impersonated_account(provider.clone(), address).await?
+            }
+            None => self.account.into_account(provider.clone()).await?,
+        };
+
+        let first_nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => account.get_nonce().await?,
+        };
+
+        // Fees can't be estimated for later transactions without applying earlier ones, so fee
+        // charging is skipped instead and the simulated fee of each transaction is reported.
+        let mut transactions = vec![];
+        for (ind, calls) in queued_calls.into_iter().enumerate() {
+            let request = account
+                .execute(calls)
+                .nonce(first_nonce + FieldElement::from(ind))
+                .max_fee(FieldElement::ZERO)
+                .prepared()?
+                .get_invoke_request(true)
+                .await?;
+            transactions.push(BroadcastedTransaction::Invoke(request));
+        }
+
+        let mut flags = vec![SimulationFlag::SkipFeeCharge];
+        if self.skip_validate || signerless {
+            flags.push(SimulationFlag::SkipValidate);
+        }
+
+        eprintln!(
+            "Simulating {} transactions from {} in sequence...",
+            transactions.len().to_string().bright_yellow(),
+            format!("{:#064x}", account.address()).bright_yellow()
+        );
+
+        // Each transaction is simulated on top of the state left by the ones before it
+        let simulations = match provider
+            .simulate_transactions(BlockId::Tag(BlockTag::Pending), &transactions, &flags)
+            .await
+        {
+            Ok(simulations) => simulations,
+            Err(ProviderError::StarknetError(StarknetError::TransactionExecutionError(data))) => {
+                let ind = data.transaction_index as usize;
+                print_failure(ind, &queue.transactions, &data.execution_error);
+                anyhow::bail!("transaction #{} of the queue would fail", ind + 1);
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        for (ind, simulation) in simulations.iter().enumerate() {
+            let revert_reason = match &simulation.transaction_trace {
+                TransactionTrace::Invoke(trace) => match &trace.execute_invocation {
+                    ExecuteInvocation::Reverted(reverted) => Some(&reverted.revert_reason),
+                    ExecuteInvocation::Success(_) => None,
+                },
+                _ => None,
+            };
+
+            match revert_reason {
+                Some(revert_reason) => {
+                    print_failure(ind, &queue.transactions, revert_reason);
+                    anyhow::bail!("transaction #{} of the queue would revert", ind + 1);
+                }
+                None => eprintln!(
+                    "{} #{}{} (fee: {} ETH)",
+                    Status::Success.label("Succeeded"),
+                    ind + 1,
+                    describe(&queue.transactions[ind]),
+                    format_ether(simulation.fee_estimation.overall_fee).bright_yellow()
+                ),
+            }
+        }
+
+        println!(
+            "{}",
+            format!(
+                "All {} transactions succeed when sent in order",
+                simulations.len()
+            )
+            .bright_green()
+        );
+
+        Ok(())
+    }
+}
+
+fn print_failure(ind: usize, transactions: &[QueuedTransaction], reason: &str) {
+    eprintln!(
+        "{} #{}{}",
+        Status::Failure.label("Failed"),
+        ind + 1,
+        transactions.get(ind).map(describe).unwrap_or_default()
+    );
+    if ind > 0 {
+        eprintln!(
+            "  The {} transaction(s) before it succeed. Check the order of the queue and the \
+            effects of earlier transactions.",
+            ind
+        );
+    }
+    eprintln!("  Reason: {}", reason);
+}
+
+fn describe(transaction: &QueuedTransaction) -> String {
+    match &transaction.description {
+        Some(description) => format!(": {}", description),
+        None => String::new(),
+    }
+}
@@ -2,13 +2,11 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use clap::Parser;
-use starknet::{
-    core::types::{BlockId, BlockTag},
-    providers::Provider,
-};
+use starknet::providers::Provider;
 
 use crate::{
-    address_book::AddressBookResolver, decode::FeltDecoder, verbosity::VerbosityArgs, ProviderArgs,
+    address_book::AddressBookResolver, decode::FeltDecoder, default_block::default_block_id,
+    verbosity::VerbosityArgs, ProviderArgs,
 };
 
 #[derive(Debug, Parser)]
@@ -37,9 +35,8 @@ impl Storage {
             .decode_single_with_storage_fallback(&self.key)
             .await?;
 
-        // TODO: allow custom block
         let value = provider
-            .get_storage_at(address, key, BlockId::Tag(BlockTag::Pending))
+            .get_storage_at(address, key, default_block_id())
             .await?;
 
         println!("{:#064x}", value);
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::utils::{
+    parse_felt_value, parse_storage_path, storage_path_address, StoragePathSegment,
+};
+
+#[derive(Debug, Parser)]
+pub struct StorageAddress {
+    #[clap(
+        long,
+        default_value = "0",
+        help = "Offset of the struct member within the storage value"
+    )]
+    offset: u64,
+    #[clap(long, help = "Display the address in decimal representation")]
+    dec: bool,
+    #[clap(
+        help = "Name of the storage variable, optionally followed by storage node members and map \
+                keys, like `pools[0x1].fee`"
+    )]
+    var_name: String,
+    #[clap(
+        help = "Map keys, in the order they're accessed. Use 2 keys for a u256 key (low, high)"
+    )]
+    keys: Vec<String>,
+}
+
+impl StorageAddress {
+    pub fn run(self) -> Result<()> {
+        let (var_name, mut segments) = parse_storage_path(self.var_name.trim())?;
+        for key in self.keys.iter() {
+            segments.push(StoragePathSegment::Key(parse_felt_value(key)?));
+        }
+
+        let address = storage_path_address(&var_name, &segments, self.offset)?;
+        if self.dec {
+            println!("{address}");
+        } else {
+            println!("{address:#064x}");
+        }
+
+        Ok(())
+    }
+}
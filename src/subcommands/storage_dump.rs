@@ -0,0 +1,212 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde::Deserialize;
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement},
+    providers::Provider,
+};
+
+use crate::{
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    path::ExpandedPathbufParser,
+    utils::{parse_storage_path, storage_path_address, StoragePathSegment},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+/// Maximum number of `Vec` elements read for each variable, to keep accidental huge reads in check.
+const MAX_VEC_ELEMENTS: u64 = 100;
+
+#[derive(Debug, Parser)]
+pub struct StorageDump {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Path to a TOML file describing the storage variables of the contract"
+    )]
+    layout: PathBuf,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Path to a file with one map key per line, used for all map variables. \
+                Separate the felts of multi-felt keys with spaces"
+    )]
+    keys: Option<PathBuf>,
+    #[clap(help = "Contract address")]
+    address: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StorageLayout {
+    #[serde(default, rename = "variable")]
+    variables: Vec<StorageVariable>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StorageVariable {
+    /// Variable name, optionally followed by a path into storage nodes like `config.owner`.
+    name: String,
+    /// Whether the variable is a map. Map entries are only read for the keys in the key file.
+    #[serde(default)]
+    map: bool,
+    /// Whether the variable is a storage `Vec`, whose length is stored at the base address.
+    #[serde(default)]
+    vec: bool,
+    /// Number of consecutive slots the value takes (e.g. 2 for `u256`).
+    #[serde(default = "default_variable_size")]
+    size: u64,
+}
+
+impl StorageDump {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = felt_decoder
+            .decode_single_with_addr_fallback(&self.address)
+            .await?;
+
+        let layout: StorageLayout = toml::from_str(&std::fs::read_to_string(&self.layout)?)
+            .map_err(|err| {
+                anyhow::anyhow!("invalid storage layout {}: {}", self.layout.display(), err)
+            })?;
+
+        let mut map_keys = vec![];
+        if let Some(keys) = &self.keys {
+            for line in std::fs::read_to_string(keys)?.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+
+                let mut key = vec![];
+                for element in line.split_whitespace() {
+                    key.append(&mut felt_decoder.decode(element).await?);
+                }
+                map_keys.push(key);
+            }
+        }
+
+        for variable in layout.variables.iter() {
+            let (var_name, segments) = parse_storage_path(&variable.name)?;
+
+            if variable.map && variable.vec {
+                anyhow::bail!("variable {} cannot be both a map and a vec", variable.name);
+            }
+
+            if variable.vec {
+                let length = provider
+                    .get_storage_at(
+                        address,
+                        storage_path_address(&var_name, &segments, 0)?,
+                        BlockId::Tag(BlockTag::Pending),
+                    )
+                    .await?;
+                let length = u64::try_from(length)
+                    .map_err(|_| anyhow::anyhow!("invalid length for vec {}", variable.name))?;
+                println!("{}.len() = {}", variable.name, length);
+
+                if length > MAX_VEC_ELEMENTS {
+                    eprintln!(
+                        "{}",
+                        format!(
+                            "WARNING: only reading the first {} elements of {}.",
+                            MAX_VEC_ELEMENTS, variable.name
+                        )
+                        .bright_magenta()
+                    );
+                }
+
+                for index in 0..length.min(MAX_VEC_ELEMENTS) {
+                    let mut element_path = segments.clone();
+                    element_path.push(StoragePathSegment::Key(index.into()));
+
+                    let values =
+                        read_variable(&provider, address, variable, &var_name, &element_path)
+                            .await?;
+                    println!("{}[{}] = {}", variable.name, index, format_values(&values));
+                }
+            } else if variable.map {
+                if map_keys.is_empty() {
+                    eprintln!(
+                        "{}",
+                        format!(
+                            "WARNING: skipping map variable {} as no keys are provided.",
+                            variable.name
+                        )
+                        .bright_magenta()
+                    );
+                    continue;
+                }
+
+                for key in map_keys.iter() {
+                    let mut entry_path = segments.clone();
+                    entry_path.extend(key.iter().map(|element| StoragePathSegment::Key(*element)));
+
+                    let values =
+                        read_variable(&provider, address, variable, &var_name, &entry_path).await?;
+                    let key = key
+                        .iter()
+                        .map(|element| format!("{:#x}", element))
+                        .collect::<Vec<_>>()
+                        .join("][");
+                    println!("{}[{}] = {}", variable.name, key, format_values(&values));
+                }
+            } else {
+                let values =
+                    read_variable(&provider, address, variable, &var_name, &segments).await?;
+                println!("{} = {}", variable.name, format_values(&values));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn read_variable<P>(
+    provider: P,
+    address: FieldElement,
+    variable: &StorageVariable,
+    var_name: &str,
+    segments: &[StoragePathSegment],
+) -> Result<Vec<FieldElement>>
+where
+    P: Provider,
+{
+    let mut values = vec![];
+    for offset in 0..variable.size {
+        let key = storage_path_address(var_name, segments, offset)?;
+
+        // TODO: allow custom block
+        values.push(
+            provider
+                .get_storage_at(address, key, BlockId::Tag(BlockTag::Pending))
+                .await?,
+        );
+    }
+    Ok(values)
+}
+
+fn format_values(values: &[FieldElement]) -> String {
+    values
+        .iter()
+        .map(|value| format!("{:#064x}", value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn default_variable_size() -> u64 {
+    1
+}
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{artifact_store::ArtifactStore, path::ExpandedPathbufParser};
+
+#[derive(Debug, Parser)]
+pub struct Add {
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        required = true,
+        help = "Paths to Sierra or Cairo 0 contract artifact files"
+    )]
+    files: Vec<PathBuf>,
+}
+
+impl Add {
+    pub fn run(self) -> Result<()> {
+        let store = ArtifactStore::open()?;
+
+        for file in self.files.iter() {
+            let (class_hash, kind, added) = store
+                .add(file)
+                .map_err(|err| anyhow::anyhow!("unable to add {}: {}", file.display(), err))?;
+
+            eprintln!(
+                "{} {} ({})",
+                if added { "Added" } else { "Already stored" },
+                file.display(),
+                kind
+            );
+
+            // Only the class hash goes to stdout so this can be easily scripted
+            println!("{}", format!("{:#064x}", class_hash).bright_yellow());
+        }
+
+        Ok(())
+    }
+}
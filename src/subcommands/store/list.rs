@@ -0,0 +1,31 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::Parser;
+use colored::Colorize;
+
+use crate::artifact_store::ArtifactStore;
+
+#[derive(Debug, Parser)]
+pub struct List {}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        let artifacts = ArtifactStore::open()?.list()?;
+        if artifacts.is_empty() {
+            eprintln!("No artifacts stored. Add some with `starkli store add`.");
+            return Ok(());
+        }
+
+        for artifact in artifacts.iter() {
+            println!(
+                "{}  {}  {:>6} KiB  {}",
+                format!("{:#064x}", artifact.class_hash).bright_yellow(),
+                artifact.kind,
+                artifact.size / 1024,
+                DateTime::<Local>::from(artifact.added_at).format("%Y-%m-%d %H:%M")
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod add;
+use add::Add;
+
+mod list;
+use list::List;
+
+mod path;
+use path::Path;
+
+mod remove;
+use remove::Remove;
+
+#[derive(Debug, Parser)]
+pub struct Store {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Copy contract artifacts into the store, indexed by class hash")]
+    Add(Add),
+    #[clap(about = "List stored artifacts")]
+    List(List),
+    #[clap(about = "Print the path of a stored artifact, for use with other tools")]
+    Path(Path),
+    #[clap(about = "Remove an artifact from the store")]
+    Remove(Remove),
+}
+
+impl Store {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Add(cmd) => cmd.run(),
+            Subcommands::List(cmd) => cmd.run(),
+            Subcommands::Path(cmd) => cmd.run(),
+            Subcommands::Remove(cmd) => cmd.run(),
+        }
+    }
+}
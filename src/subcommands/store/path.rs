@@ -0,0 +1,22 @@
+use anyhow::Result;
+use clap::Parser;
+use starknet::core::types::FieldElement;
+
+use crate::artifact_store::ArtifactStore;
+
+#[derive(Debug, Parser)]
+pub struct Path {
+    #[clap(help = "Class hash of the stored artifact")]
+    class_hash: String,
+}
+
+impl Path {
+    pub fn run(self) -> Result<()> {
+        let class_hash = FieldElement::from_hex_be(&self.class_hash)?;
+        let path = ArtifactStore::open()?.get(class_hash)?;
+
+        println!("{}", path.display());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::types::FieldElement;
+
+use crate::artifact_store::ArtifactStore;
+
+#[derive(Debug, Parser)]
+pub struct Remove {
+    #[clap(required = true, help = "Class hashes of the artifacts to remove")]
+    class_hashes: Vec<String>,
+}
+
+impl Remove {
+    pub fn run(self) -> Result<()> {
+        let store = ArtifactStore::open()?;
+
+        for class_hash in self.class_hashes.iter() {
+            let class_hash = FieldElement::from_hex_be(class_hash)?;
+            if store.remove(class_hash)? {
+                eprintln!(
+                    "Removed {}",
+                    format!("{:#064x}", class_hash).bright_yellow()
+                );
+            } else {
+                eprintln!(
+                    "{}",
+                    format!("WARNING: class {:#064x} is not in the store", class_hash)
+                        .bright_magenta()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::template::InvokeTemplate;
+
+#[derive(Debug, Parser)]
+pub struct List {}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        eprintln!(
+            "Templates directory: {}",
+            format!("{}", InvokeTemplate::get_folder()?.display()).bright_yellow()
+        );
+
+        let names = InvokeTemplate::list()?;
+        if names.is_empty() {
+            eprintln!("No template found.");
+        }
+
+        for name in names.into_iter() {
+            let template = InvokeTemplate::load(&name)?;
+            let params = template
+                .params
+                .iter()
+                .map(|param| param.signature())
+                .collect::<Vec<_>>();
+
+            match &template.description {
+                Some(description) => println!("{}({})\t{}", name, params.join(", "), description),
+                None => println!("{}({})", name, params.join(", ")),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod save;
+use save::Save;
+
+mod use_template;
+use use_template::UseTemplate;
+
+mod list;
+use list::List;
+
+mod remove;
+use remove::Remove;
+
+#[derive(Debug, Parser)]
+pub struct Template {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Save an invoke with `{param}` placeholders as a named template")]
+    Save(Save),
+    #[clap(
+        name = "use",
+        about = "Fill in a template's parameters and send the invoke"
+    )]
+    Use(UseTemplate),
+    #[clap(about = "List saved templates and their parameters")]
+    List(List),
+    #[clap(about = "Delete a saved template")]
+    Remove(Remove),
+}
+
+impl Template {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Save(cmd) => cmd.run(),
+            Subcommands::Use(cmd) => cmd.run().await,
+            Subcommands::List(cmd) => cmd.run(),
+            Subcommands::Remove(cmd) => cmd.run(),
+        }
+    }
+}
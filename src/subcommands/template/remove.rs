@@ -0,0 +1,19 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::template::InvokeTemplate;
+
+#[derive(Debug, Parser)]
+pub struct Remove {
+    #[clap(help = "Name of the template")]
+    name: String,
+}
+
+impl Remove {
+    pub fn run(self) -> Result<()> {
+        InvokeTemplate::remove(&self.name)?;
+        eprintln!("Template {} removed", self.name);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::template::{InvokeTemplate, TemplateParam};
+
+#[derive(Debug, Parser)]
+pub struct Save {
+    #[clap(long, help = "Replace the template if it already exists")]
+    force: bool,
+    #[clap(
+        long,
+        help = "Short description shown when listing and using the template"
+    )]
+    description: Option<String>,
+    #[clap(
+        long = "param",
+        value_name = "NAME[:TYPE]",
+        help = "Declare a parameter, referenced as `{NAME}` in place of an argument. TYPE is one of \
+                felt (default), u256, address, bool and string. Can be repeated"
+    )]
+    params: Vec<String>,
+    #[clap(help = "Name of the template")]
+    name: String,
+    #[clap(help = "Contract address")]
+    to: String,
+    #[clap(help = "Name of the function being called")]
+    function: String,
+    #[clap(help = "Raw function call arguments, with `{NAME}` for parameters")]
+    args: Vec<String>,
+}
+
+impl Save {
+    pub fn run(self) -> Result<()> {
+        if InvokeTemplate::exists(&self.name)? && !self.force {
+            anyhow::bail!(
+                "template {} already exists. Use --force to replace it",
+                self.name
+            );
+        }
+
+        let params = self
+            .params
+            .iter()
+            .map(|param| param.parse::<TemplateParam>())
+            .collect::<Result<Vec<_>>>()?;
+
+        let template =
+            InvokeTemplate::new(self.description, self.to, self.function, self.args, params)?;
+        template.save(&self.name)?;
+
+        eprintln!(
+            "Template {} saved to {}",
+            self.name.bright_yellow(),
+            format!("{}", InvokeTemplate::get_path(&self.name)?.display()).bright_yellow()
+        );
+
+        Ok(())
+    }
+}
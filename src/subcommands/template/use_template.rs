@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{subcommands::Invoke, template::InvokeTemplate};
+
+#[derive(Debug, Parser)]
+pub struct UseTemplate {
+    #[clap(
+        long,
+        help = "Print the resulting `starkli invoke` command instead of running it"
+    )]
+    print: bool,
+    #[clap(help = "Name of the template")]
+    name: String,
+    #[clap(help = "Parameter values in the NAME=VALUE format")]
+    values: Vec<String>,
+    #[clap(
+        last = true,
+        help = "Options for `starkli invoke` after `--`, like --account or --watch"
+    )]
+    invoke_args: Vec<String>,
+}
+
+impl UseTemplate {
+    pub async fn run(self) -> Result<()> {
+        let template = InvokeTemplate::load(&self.name)?;
+
+        let mut values = BTreeMap::new();
+        for value in self.values.iter() {
+            let (name, value) = value.split_once('=').ok_or_else(|| {
+                anyhow::anyhow!("invalid parameter value (NAME=VALUE): {}", value)
+            })?;
+            if values.insert(name.to_owned(), value.to_owned()).is_some() {
+                anyhow::bail!("parameter {} is set more than once", name);
+            }
+        }
+
+        let calls = template.instantiate(&values)?;
+
+        if self.print {
+            let command = std::iter::once("starkli invoke".to_owned())
+                .chain(self.invoke_args.iter().map(|arg| shell_quote(arg)))
+                .chain(calls.iter().map(|arg| shell_quote(arg)))
+                .collect::<Vec<_>>();
+            println!("{}", command.join(" "));
+            return Ok(());
+        }
+
+        if let Some(description) = &template.description {
+            eprintln!(
+                "Using template {}: {}",
+                self.name.bright_yellow(),
+                description
+            );
+        }
+
+        // Sent through the regular invoke command so that all its options and checks apply
+        let invoke = Invoke::try_parse_from(
+            std::iter::once("starkli invoke".to_owned())
+                .chain(self.invoke_args)
+                .chain(std::iter::once("--".to_owned()))
+                .chain(calls),
+        )
+        .unwrap_or_else(|err| err.exit());
+
+        invoke.run().await
+    }
+}
+
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.:/@=,".contains(c))
+    {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
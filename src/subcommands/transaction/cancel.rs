@@ -0,0 +1,113 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{accounts::Account, core::types::FieldElement, macros::felt};
+
+use crate::{
+    account::AccountArgs,
+    amount::format_ether,
+    error::decode_account_error,
+    fee::{check_max_fee_ceiling, FeeArgs, FeeSetting},
+    messages::message,
+    utils::{noop_call, watch_tx},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Cancel {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    account: AccountArgs,
+    #[clap(flatten)]
+    fee: FeeArgs,
+    #[clap(long, help = "Nonce of the pending transaction to be replaced")]
+    nonce: FieldElement,
+    #[clap(long, short, help = "Wait for the transaction to confirm")]
+    watch: bool,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        default_value = "5000",
+        help = "Transaction result poll interval in milliseconds"
+    )]
+    poll_interval: u64,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Cancel {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let force_fee = self.fee.is_forced();
+        let fee_setting = self.fee.into_setting()?;
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let account = self.account.into_account(provider.clone()).await?;
+
+        let execution = account
+            .execute(vec![noop_call(
+                provider.eth_token_address(),
+                account.address(),
+            )])
+            .nonce(self.nonce);
+
+        let max_fee = match fee_setting {
+            FeeSetting::Manual(fee) => fee,
+            FeeSetting::EstimateOnly { .. } | FeeSetting::None => {
+                let fee_estimate = execution
+                    .estimate_fee()
+                    .await
+                    .map_err(decode_account_error)?;
+                let estimated_fee = fee_estimate.overall_fee;
+
+                if fee_setting.is_estimate_only() {
+                    fee_setting.print_estimate(&fee_estimate)?;
+                    return Ok(());
+                }
+
+                // The replacement needs to outbid the transaction being cancelled, so a larger
+                // buffer than usual is used.
+                estimated_fee * felt!("2")
+            }
+        };
+
+        check_max_fee_ceiling(&provider, max_fee, force_fee)?;
+
+        eprintln!(
+            "Replacing transaction with nonce {} using a no-op transaction with max fee {}",
+            format!("{}", self.nonce).bright_yellow(),
+            format!("{} ETH", format_ether(max_fee)).bright_yellow()
+        );
+
+        let cancel_tx = execution
+            .max_fee(max_fee)
+            .send()
+            .await
+            .map_err(decode_account_error)?
+            .transaction_hash;
+        eprintln!(
+            "Cancellation transaction: {}",
+            format!("{:#064x}", cancel_tx).bright_yellow()
+        );
+
+        if self.watch {
+            eprintln!(
+                "{}",
+                message("tx.waiting", &[("hash", &format!("{:#064x}", cancel_tx))])
+            );
+            watch_tx(
+                &provider,
+                cancel_tx,
+                Duration::from_millis(self.poll_interval),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
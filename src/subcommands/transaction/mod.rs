@@ -0,0 +1,63 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use starknet::{core::types::FieldElement, providers::Provider};
+
+use crate::{select::SelectArgs, verbosity::VerbosityArgs, ProviderArgs};
+
+mod cancel;
+use cancel::Cancel;
+
+mod wait;
+use wait::Wait;
+
+#[derive(Debug, Parser)]
+#[clap(args_conflicts_with_subcommands = true)]
+pub struct Transaction {
+    #[clap(subcommand)]
+    command: Option<Subcommands>,
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(required = true, help = "Transaction hash")]
+    hash: Option<String>,
+    #[clap(flatten)]
+    select: SelectArgs,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(
+        about = "Invalidate a pending transaction by sending a replacement with the same nonce"
+    )]
+    Cancel(Cancel),
+    #[clap(
+        about = "Wait for a transaction to confirm, optionally running hooks or notifying a webhook"
+    )]
+    Wait(Wait),
+}
+
+impl Transaction {
+    pub async fn run(self) -> Result<()> {
+        if let Some(command) = self.command {
+            return match command {
+                Subcommands::Cancel(cmd) => cmd.run().await,
+                Subcommands::Wait(cmd) => cmd.run().await,
+            };
+        }
+
+        self.verbosity.setup_logging();
+
+        let provider = self.provider.into_provider()?;
+        let transaction_hash = FieldElement::from_hex_be(
+            &self
+                .hash
+                .ok_or_else(|| anyhow::anyhow!("missing transaction hash"))?,
+        )?;
+
+        let transaction = provider.get_transaction_by_hash(transaction_hash).await?;
+
+        let transaction_json = serde_json::to_value(transaction)?;
+        self.select.print(&transaction_json)
+    }
+}
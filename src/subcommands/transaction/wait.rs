@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::Parser;
+use starknet::core::types::FieldElement;
+
+use crate::{hook::WatchHookArgs, messages::message, verbosity::VerbosityArgs, ProviderArgs};
+
+#[derive(Debug, Parser)]
+pub struct Wait {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        default_value = "5000",
+        help = "Transaction result poll interval in milliseconds"
+    )]
+    poll_interval: u64,
+    #[clap(flatten)]
+    hooks: WatchHookArgs,
+    #[clap(help = "Transaction hash")]
+    hash: FieldElement,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Wait {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = self.provider.into_provider()?;
+
+        eprintln!(
+            "{}",
+            message("tx.waiting", &[("hash", &format!("{:#064x}", self.hash))])
+        );
+        self.hooks
+            .watch_tx(
+                &provider,
+                self.hash,
+                Duration::from_millis(self.poll_interval),
+            )
+            .await
+    }
+}
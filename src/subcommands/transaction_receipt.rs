@@ -1,34 +1,270 @@
+use std::sync::Arc;
+
 use anyhow::Result;
-use clap::Parser;
-use colored_json::{ColorMode, Output};
-use starknet::{core::types::FieldElement, providers::Provider};
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use starknet::{core::types::FieldElement, macros::selector, providers::Provider};
 
-use crate::{verbosity::VerbosityArgs, ProviderArgs};
+use crate::{
+    address_book::AddressBookResolver,
+    amount::AmountFormatArgs,
+    decode::FeltDecoder,
+    select::SelectArgs,
+    utils::{collect_inner_events, print_receipt_json, ROOT_INVOCATION_FIELDS},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
 
 #[derive(Debug, Parser)]
 pub struct TransactionReceipt {
     #[clap(flatten)]
     provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "Exit with an error if the transaction execution status is not the one expected"
+    )]
+    expect_status: Option<ExpectedStatus>,
+    #[clap(
+        long,
+        help = "Exit with an error if the transaction did not emit this event. Accepts an event \
+                name or selector, and can be repeated"
+    )]
+    expect_event: Vec<String>,
+    #[clap(
+        long,
+        help = "Add an `inner_events` list with the events emitted by all nested calls, taken from \
+                the transaction trace and attributed to the emitting contract"
+    )]
+    include_inner_events: bool,
+    #[clap(
+        long,
+        conflicts_with = "select",
+        help = "Only print the addresses of contracts deployed by the transaction, one per line, \
+                from UDC `ContractDeployed` events and constructor calls in the trace"
+    )]
+    deployed_addresses: bool,
     #[clap(help = "Transaction hash")]
     hash: String,
     #[clap(flatten)]
+    select: SelectArgs,
+    #[clap(flatten)]
+    amounts: AmountFormatArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExpectedStatus {
+    Succeeded,
+    Reverted,
+}
+
 impl TransactionReceipt {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
 
-        let provider = self.provider.into_provider()?;
+        let provider = Arc::new(self.provider.into_provider()?);
         let transaction_hash = FieldElement::from_hex_be(&self.hash)?;
 
-        let receipt = provider.get_transaction_receipt(transaction_hash).await?;
+        // Resolved upfront so that typos are reported even if the receipt is not found
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+        let mut expected_events = vec![];
+        for event in self.expect_event.iter() {
+            expected_events.push((
+                event,
+                felt_decoder
+                    .decode_single_with_selector_fallback(event)
+                    .await?,
+            ));
+        }
+
+        let mut receipt =
+            serde_json::to_value(provider.get_transaction_receipt(transaction_hash).await?)?;
+
+        if self.include_inner_events {
+            let trace = serde_json::to_value(provider.trace_transaction(transaction_hash).await?)?;
+            if let serde_json::Value::Object(receipt) = &mut receipt {
+                receipt.insert(
+                    "inner_events".to_owned(),
+                    serde_json::Value::Array(collect_inner_events(&trace)),
+                );
+            }
+        }
+
+        if let Some(amount_format) = self.amounts.format() {
+            if let Some(fee) = receipt.get_mut("actual_fee") {
+                amount_format.apply_to_json_fee(fee);
+            }
+        }
 
-        let receipt_json = serde_json::to_value(receipt)?;
-        let receipt_json =
-            colored_json::to_colored_json(&receipt_json, ColorMode::Auto(Output::StdOut))?;
-        println!("{receipt_json}");
+        if self.deployed_addresses {
+            // Deployments through `deploy_syscall` don't emit events, but always run a constructor
+            let trace = match provider.trace_transaction(transaction_hash).await {
+                Ok(trace) => Some(serde_json::to_value(trace)?),
+                Err(err) => {
+                    eprintln!(
+                        "{}",
+                        format!(
+                            "WARNING: unable to fetch the transaction trace, only showing \
+                            deployments found in the receipt: {}",
+                            err
+                        )
+                        .bright_magenta()
+                    );
+                    None
+                }
+            };
+
+            let addresses = deployed_addresses(&receipt, trace.as_ref());
+            if addresses.is_empty() {
+                eprintln!("No contract was deployed by this transaction.");
+            }
+            for address in addresses.into_iter() {
+                println!("{:#064x}", address);
+            }
+        } else if self.select.is_empty() {
+            print_receipt_json(&provider, &receipt).await?;
+        } else {
+            // The transfer summary is skipped as the output is meant for scripts
+            self.select.print(&receipt)?;
+        }
+
+        let mut failures = vec![];
+
+        if let Some(expected_status) = self.expect_status {
+            let status = receipt
+                .get("execution_status")
+                .and_then(|value| value.as_str())
+                .unwrap_or_default();
+            let expected = match expected_status {
+                ExpectedStatus::Succeeded => "SUCCEEDED",
+                ExpectedStatus::Reverted => "REVERTED",
+            };
+
+            if status != expected {
+                failures.push(format!(
+                    "expected execution status {}, got {}",
+                    expected, status
+                ));
+            }
+        }
+
+        // Event selectors are always the first key. Inner events, when included, also count
+        let emitted_selectors = ["events", "inner_events"]
+            .iter()
+            .filter_map(|field| receipt.get(*field).and_then(|value| value.as_array()))
+            .flatten()
+            .filter_map(|event| {
+                event
+                    .get("keys")
+                    .and_then(|keys| keys.as_array())
+                    .and_then(|keys| keys.first())
+                    .and_then(|key| key.as_str())
+                    .and_then(|key| FieldElement::from_hex_be(key).ok())
+            })
+            .collect::<Vec<_>>();
+        for (name, selector) in expected_events.into_iter() {
+            if !emitted_selectors.contains(&selector) {
+                failures.push(format!("expected event {} was not emitted", name));
+            }
+        }
+
+        if !failures.is_empty() {
+            anyhow::bail!("receipt assertion failed: {}", failures.join("; "));
+        }
 
         Ok(())
     }
 }
+
+/// Addresses of contracts deployed by a transaction, in the order they're found: the deployed
+/// account of `DEPLOY_ACCOUNT` transactions, contracts whose constructor ran in the trace, then the
+/// addresses in `ContractDeployed` events emitted by the UDC or compatible deployers.
+fn deployed_addresses(
+    receipt: &serde_json::Value,
+    trace: Option<&serde_json::Value>,
+) -> Vec<FieldElement> {
+    fn collect_constructors(invocation: &serde_json::Value, addresses: &mut Vec<FieldElement>) {
+        if invocation
+            .get("entry_point_type")
+            .and_then(|value| value.as_str())
+            == Some("CONSTRUCTOR")
+        {
+            if let Some(address) = invocation
+                .get("contract_address")
+                .and_then(|value| value.as_str())
+                .and_then(|value| FieldElement::from_hex_be(value).ok())
+            {
+                addresses.push(address);
+            }
+        }
+
+        let calls = invocation
+            .get("calls")
+            .and_then(|value| value.as_array())
+            .into_iter()
+            .flatten();
+        for call in calls {
+            collect_constructors(call, addresses);
+        }
+    }
+
+    let parse_felt = |value: &serde_json::Value| {
+        value
+            .as_str()
+            .and_then(|value| FieldElement::from_hex_be(value).ok())
+    };
+
+    let mut addresses = vec![];
+
+    if let Some(address) = receipt.get("contract_address").and_then(parse_felt) {
+        addresses.push(address);
+    }
+
+    if let Some(trace) = trace {
+        // Validation can't deploy contracts
+        for field in ROOT_INVOCATION_FIELDS
+            .iter()
+            .filter(|field| **field != "validate_invocation")
+        {
+            if let Some(invocation) = trace.get(*field) {
+                collect_constructors(invocation, &mut addresses);
+            }
+        }
+    }
+
+    // `ContractDeployed(address, deployer, unique, class_hash, calldata, salt)`
+    let events = receipt
+        .get("events")
+        .and_then(|value| value.as_array())
+        .into_iter()
+        .flatten();
+    for event in events {
+        let is_deployment = event
+            .get("keys")
+            .and_then(|keys| keys.as_array())
+            .and_then(|keys| keys.first())
+            .and_then(parse_felt)
+            == Some(selector!("ContractDeployed"));
+        if !is_deployment {
+            continue;
+        }
+
+        if let Some(address) = event
+            .get("data")
+            .and_then(|data| data.as_array())
+            .and_then(|data| data.first())
+            .and_then(parse_felt)
+        {
+            addresses.push(address);
+        }
+    }
+
+    let mut unique = vec![];
+    for address in addresses.into_iter() {
+        if !unique.contains(&address) {
+            unique.push(address);
+        }
+    }
+    unique
+}
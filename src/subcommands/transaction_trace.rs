@@ -1,14 +1,32 @@
 use anyhow::Result;
 use clap::Parser;
+use colored::Colorize;
 use colored_json::{ColorMode, Output};
 use starknet::{core::types::FieldElement, providers::Provider};
 
-use crate::{verbosity::VerbosityArgs, ProviderArgs};
+use crate::{
+    token::{collect_transfers, print_transfer_summary, TokenRegistry},
+    utils::{collect_inner_events, ROOT_INVOCATION_FIELDS},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
 
 #[derive(Debug, Parser)]
 pub struct TransactionTrace {
     #[clap(flatten)]
     provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "Print execution resources attributed to each call frame instead of the raw trace"
+    )]
+    profile: bool,
+    #[clap(
+        long,
+        conflicts_with = "profile",
+        help = "Add an `inner_events` list with the events emitted by all nested calls, attributed \
+                to the emitting contract"
+    )]
+    include_inner_events: bool,
     #[clap(help = "Transaction hash")]
     hash: String,
     #[clap(flatten)]
@@ -24,11 +42,172 @@ impl TransactionTrace {
 
         let trace = provider.trace_transaction(transaction_hash).await?;
 
-        let trace_json = serde_json::to_value(trace)?;
+        let mut trace_json = serde_json::to_value(trace)?;
+
+        if self.profile {
+            print_profile(&trace_json);
+            return Ok(());
+        }
+
+        let transfers = collect_transfers(&trace_json);
+
+        if self.include_inner_events {
+            let inner_events = collect_inner_events(&trace_json);
+            if let serde_json::Value::Object(trace) = &mut trace_json {
+                trace.insert(
+                    "inner_events".to_owned(),
+                    serde_json::Value::Array(inner_events),
+                );
+            }
+        }
+
         let trace_json =
             colored_json::to_colored_json(&trace_json, ColorMode::Auto(Output::StdOut))?;
         println!("{trace_json}");
 
+        if !transfers.is_empty() {
+            let registry = TokenRegistry::load_for_provider(&provider).await?;
+            print_transfer_summary(&registry, &transfers);
+        }
+
         Ok(())
     }
 }
+
+/// Width of the bar showing each frame's share of total steps.
+const BAR_WIDTH: usize = 20;
+
+struct Frame {
+    contract_address: String,
+    selector: String,
+    /// Resources consumed by the frame itself, excluding inner calls.
+    self_steps: u64,
+    self_builtins: u64,
+    /// Resources reported for the invocation, which already include inner calls.
+    total_steps: u64,
+    total_builtins: u64,
+    children: Vec<Frame>,
+}
+
+impl Frame {
+    fn from_json(invocation: &serde_json::Value) -> Option<Self> {
+        let object = invocation.as_object()?;
+
+        // Reverted executions have no invocation to profile
+        let contract_address = object.get("contract_address")?.as_str()?.to_owned();
+        let selector = object
+            .get("entry_point_selector")
+            .and_then(|selector| selector.as_str())
+            .unwrap_or_default()
+            .to_owned();
+
+        let (total_steps, total_builtins) = match object.get("execution_resources") {
+            Some(serde_json::Value::Object(resources)) => {
+                let steps = resources
+                    .get("steps")
+                    .and_then(|steps| steps.as_u64())
+                    .unwrap_or_default();
+                let builtins = resources
+                    .iter()
+                    .filter(|(key, _)| key.ends_with("_builtin_applications"))
+                    .filter_map(|(_, value)| value.as_u64())
+                    .sum();
+                (steps, builtins)
+            }
+            _ => (0, 0),
+        };
+
+        let children = match object.get("calls") {
+            Some(serde_json::Value::Array(calls)) => {
+                calls.iter().filter_map(Self::from_json).collect::<Vec<_>>()
+            }
+            _ => vec![],
+        };
+
+        Some(Self {
+            self_steps: total_steps
+                .saturating_sub(children.iter().map(|child| child.total_steps).sum::<u64>()),
+            self_builtins: total_builtins.saturating_sub(
+                children
+                    .iter()
+                    .map(|child| child.total_builtins)
+                    .sum::<u64>(),
+            ),
+            contract_address,
+            selector,
+            total_steps,
+            total_builtins,
+            children,
+        })
+    }
+
+    fn print(&self, depth: usize, grand_total_steps: u64) {
+        let share = if grand_total_steps == 0 {
+            0f64
+        } else {
+            self.total_steps as f64 / grand_total_steps as f64
+        };
+        let filled = ((share * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+        let bar = format!("{}{}", "#".repeat(filled), ".".repeat(BAR_WIDTH - filled));
+
+        println!(
+            "{} {:>6.2}% {:>9} {:>9} {:>7} {:>7}  {}{} {}",
+            bar.bright_magenta(),
+            share * 100f64,
+            self.total_steps,
+            self.self_steps,
+            self.total_builtins,
+            self.self_builtins,
+            "  ".repeat(depth),
+            shorten_hex(&self.contract_address).bright_yellow(),
+            shorten_hex(&self.selector)
+        );
+
+        for child in self.children.iter() {
+            child.print(depth + 1, grand_total_steps);
+        }
+    }
+}
+
+fn print_profile(trace: &serde_json::Value) {
+    let roots = ROOT_INVOCATION_FIELDS
+        .iter()
+        .filter_map(|field| {
+            trace
+                .get(field)
+                .and_then(Frame::from_json)
+                .map(|frame| (*field, frame))
+        })
+        .collect::<Vec<_>>();
+
+    if roots.is_empty() {
+        eprintln!("No invocation with execution resources found in trace.");
+        return;
+    }
+
+    let grand_total_steps = roots.iter().map(|(_, frame)| frame.total_steps).sum();
+
+    println!(
+        "{:width$} {:>7} {:>9} {:>9} {:>7} {:>7}  frame",
+        "",
+        "share",
+        "steps",
+        "self",
+        "builtin",
+        "self",
+        width = BAR_WIDTH
+    );
+    for (field, frame) in roots.iter() {
+        println!("{}:", field.trim_end_matches("_invocation"));
+        frame.print(0, grand_total_steps);
+    }
+}
+
+fn shorten_hex(value: &str) -> String {
+    match value.strip_prefix("0x") {
+        Some(hex) if hex.len() > 10 => {
+            format!("0x{}..{}", &hex[..4], &hex[hex.len() - 4..])
+        }
+        _ => value.to_owned(),
+    }
+}
@@ -0,0 +1,475 @@
+use std::{
+    io::Stdout,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use chrono::{TimeZone, Utc};
+use clap::Parser;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block as TuiBlock, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame, Terminal,
+};
+use serde_json::Value;
+use starknet::{
+    core::types::{BlockId, FieldElement, MaybePendingBlockWithTxHashes},
+    providers::Provider,
+};
+
+use crate::{provider::ExtendedProvider, utils::decode_execute_calldata, ProviderArgs};
+
+#[derive(Debug, Parser)]
+pub struct Tui {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        default_value = "20",
+        help = "Number of recent blocks to keep on screen"
+    )]
+    blocks: u64,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        default_value = "5000",
+        help = "New block poll interval in milliseconds"
+    )]
+    poll_interval: u64,
+}
+
+struct BlockSummary {
+    number: u64,
+    hash: FieldElement,
+    timestamp: u64,
+    transactions: Vec<FieldElement>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Blocks,
+    Transactions,
+    Detail,
+}
+
+struct App {
+    provider: ExtendedProvider,
+    max_blocks: u64,
+    blocks: Vec<BlockSummary>,
+    block_state: ListState,
+    tx_state: ListState,
+    focus: Focus,
+    detail: Vec<String>,
+    detail_scroll: u16,
+    status: String,
+}
+
+type CrosstermTerminal = Terminal<CrosstermBackend<Stdout>>;
+
+impl Tui {
+    pub async fn run(self) -> Result<()> {
+        // Logging is intentionally not set up here as anything written to the terminal would mess
+        // up the screen.
+        let provider = self.provider.into_provider()?;
+
+        let mut app = App {
+            provider,
+            max_blocks: self.blocks.max(1),
+            blocks: vec![],
+            block_state: ListState::default(),
+            tx_state: ListState::default(),
+            focus: Focus::Blocks,
+            detail: vec![],
+            detail_scroll: 0,
+            status: String::new(),
+        };
+
+        // Loads the initial blocks before taking over the terminal so that errors are printed
+        // normally.
+        app.refresh_blocks().await?;
+
+        enable_raw_mode()?;
+        let mut stdout = std::io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+        let result = app
+            .event_loop(&mut terminal, Duration::from_millis(self.poll_interval))
+            .await;
+
+        // Always restore the terminal, even when the loop failed
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+
+        result
+    }
+}
+
+impl App {
+    async fn event_loop(
+        &mut self,
+        terminal: &mut CrosstermTerminal,
+        poll_interval: Duration,
+    ) -> Result<()> {
+        let mut last_poll = Instant::now();
+
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Tab | KeyCode::Right => self.focus_next(),
+                        KeyCode::BackTab | KeyCode::Left => self.focus_prev(),
+                        KeyCode::Down | KeyCode::Char('j') => self.move_selection(1).await,
+                        KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1).await,
+                        KeyCode::Char('r') => {
+                            if let Err(err) = self.refresh_blocks().await {
+                                self.status = format!("Error: {err}");
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            if last_poll.elapsed() >= poll_interval {
+                last_poll = Instant::now();
+                if let Err(err) = self.refresh_blocks().await {
+                    self.status = format!("Error: {err}");
+                }
+            }
+        }
+    }
+
+    /// Fetches blocks newer than the ones already loaded, keeping at most `max_blocks` entries.
+    async fn refresh_blocks(&mut self) -> Result<()> {
+        let latest = self.provider.block_number().await?;
+        let oldest = latest.saturating_sub(self.max_blocks - 1);
+        let start = match self.blocks.first() {
+            Some(newest_loaded) => (newest_loaded.number + 1).max(oldest),
+            None => oldest,
+        };
+
+        let mut new_blocks = vec![];
+        for number in (start..=latest).rev() {
+            match self
+                .provider
+                .get_block_with_tx_hashes(BlockId::Number(number))
+                .await?
+            {
+                MaybePendingBlockWithTxHashes::Block(block) => new_blocks.push(BlockSummary {
+                    number: block.block_number,
+                    hash: block.block_hash,
+                    timestamp: block.timestamp,
+                    transactions: block.transactions,
+                }),
+                MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+                    anyhow::bail!("unexpected pending block for block number {}", number)
+                }
+            }
+        }
+
+        let selected_number = self
+            .block_state
+            .selected()
+            .and_then(|index| self.blocks.get(index))
+            .map(|block| block.number);
+
+        new_blocks.append(&mut self.blocks);
+        new_blocks.truncate(self.max_blocks as usize);
+        self.blocks = new_blocks;
+
+        // Keeps the same block selected as new blocks get prepended
+        let selected_index = match selected_number {
+            Some(number) => self.blocks.iter().position(|block| block.number == number),
+            None => None,
+        };
+        self.block_state
+            .select(Some(selected_index.unwrap_or_default()));
+
+        self.status = format!("Latest block: {latest}");
+
+        Ok(())
+    }
+
+    async fn move_selection(&mut self, delta: isize) {
+        match self.focus {
+            Focus::Blocks => {
+                if move_list_state(&mut self.block_state, self.blocks.len(), delta) {
+                    self.tx_state.select(None);
+                    self.detail.clear();
+                    self.detail_scroll = 0;
+                }
+            }
+            Focus::Transactions => {
+                let tx_count = self
+                    .selected_block()
+                    .map(|block| block.transactions.len())
+                    .unwrap_or_default();
+                if move_list_state(&mut self.tx_state, tx_count, delta) {
+                    self.load_detail().await;
+                }
+            }
+            Focus::Detail => {
+                self.detail_scroll = if delta < 0 {
+                    self.detail_scroll.saturating_sub(1)
+                } else {
+                    self.detail_scroll.saturating_add(1)
+                };
+            }
+        }
+    }
+
+    async fn load_detail(&mut self) {
+        let tx_hash = match self.selected_transaction() {
+            Some(tx_hash) => tx_hash,
+            None => return,
+        };
+
+        self.detail_scroll = 0;
+        self.detail = match self.describe_transaction(tx_hash).await {
+            Ok(lines) => lines,
+            Err(err) => vec![format!("Error: {err}")],
+        };
+    }
+
+    async fn describe_transaction(&self, tx_hash: FieldElement) -> Result<Vec<String>> {
+        let transaction =
+            serde_json::to_value(self.provider.get_transaction_by_hash(tx_hash).await?)?;
+        let receipt = serde_json::to_value(self.provider.get_transaction_receipt(tx_hash).await?)?;
+
+        let mut lines = vec![
+            format!("Hash:    {:#064x}", tx_hash),
+            format!("Type:    {}", json_str(&transaction, "type")),
+            format!("Version: {}", json_str(&transaction, "version")),
+        ];
+        if let Some(sender) = transaction.get("sender_address") {
+            lines.push(format!("Sender:  {}", sender.as_str().unwrap_or_default()));
+        }
+        lines.push(format!(
+            "Status:  {} / {}",
+            json_str(&receipt, "finality_status"),
+            json_str(&receipt, "execution_status")
+        ));
+        if let Some(reason) = receipt
+            .get("revert_reason")
+            .and_then(|value| value.as_str())
+        {
+            lines.push(format!("Revert:  {reason}"));
+        }
+        if let Some(fee) = receipt.get("actual_fee") {
+            lines.push(format!("Fee:     {fee}"));
+        }
+
+        if let Some(calldata) = transaction
+            .get("calldata")
+            .and_then(|value| value.as_array())
+        {
+            let calldata = calldata
+                .iter()
+                .filter_map(|value| value.as_str())
+                .filter_map(|value| FieldElement::from_hex_be(value).ok())
+                .collect::<Vec<_>>();
+
+            lines.push(String::new());
+            match decode_execute_calldata(&calldata) {
+                Some(calls) => {
+                    lines.push(format!("Calls ({}):", calls.len()));
+                    for (ind_call, call) in calls.iter().enumerate() {
+                        lines.push(format!("  #{ind_call} to {:#064x}", call.to));
+                        lines.push(format!("     selector {:#064x}", call.selector));
+                        for arg in call.calldata.iter() {
+                            lines.push(format!("       {:#x}", arg));
+                        }
+                    }
+                }
+                None => {
+                    lines.push(format!("Calldata ({}):", calldata.len()));
+                    for item in calldata.iter() {
+                        lines.push(format!("  {:#x}", item));
+                    }
+                }
+            }
+        }
+
+        if let Some(events) = receipt.get("events").and_then(|value| value.as_array()) {
+            lines.push(String::new());
+            lines.push(format!("Events ({}):", events.len()));
+            for (ind_event, event) in events.iter().enumerate() {
+                lines.push(format!(
+                    "  #{ind_event} from {}",
+                    json_str(event, "from_address")
+                ));
+                lines.push(format!("     keys {}", json_felt_list(event, "keys")));
+                lines.push(format!("     data {}", json_felt_list(event, "data")));
+            }
+        }
+
+        Ok(lines)
+    }
+
+    fn selected_block(&self) -> Option<&BlockSummary> {
+        self.block_state
+            .selected()
+            .and_then(|index| self.blocks.get(index))
+    }
+
+    fn selected_transaction(&self) -> Option<FieldElement> {
+        let block = self.selected_block()?;
+        self.tx_state
+            .selected()
+            .and_then(|index| block.transactions.get(index))
+            .copied()
+    }
+
+    fn focus_next(&mut self) {
+        self.focus = match self.focus {
+            Focus::Blocks => Focus::Transactions,
+            Focus::Transactions => Focus::Detail,
+            Focus::Detail => Focus::Blocks,
+        };
+    }
+
+    fn focus_prev(&mut self) {
+        self.focus = match self.focus {
+            Focus::Blocks => Focus::Detail,
+            Focus::Transactions => Focus::Blocks,
+            Focus::Detail => Focus::Transactions,
+        };
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(frame.size());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(36),
+                Constraint::Length(70),
+                Constraint::Min(20),
+            ])
+            .split(rows[0]);
+
+        let block_items = self
+            .blocks
+            .iter()
+            .map(|block| {
+                ListItem::new(format!(
+                    "#{:<9} {:>4} txs {}",
+                    block.number,
+                    block.transactions.len(),
+                    format_timestamp(block.timestamp)
+                ))
+            })
+            .collect::<Vec<_>>();
+        let block_list = List::new(block_items)
+            .block(self.pane("Blocks", Focus::Blocks))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(block_list, columns[0], &mut self.block_state);
+
+        let (tx_title, tx_items) = match self.selected_block() {
+            Some(block) => (
+                format!("Transactions in block {} ({:#x})", block.number, block.hash),
+                block
+                    .transactions
+                    .iter()
+                    .map(|tx_hash| ListItem::new(format!("{:#064x}", tx_hash)))
+                    .collect::<Vec<_>>(),
+            ),
+            None => ("Transactions".to_owned(), vec![]),
+        };
+        let tx_list = List::new(tx_items)
+            .block(self.pane(&tx_title, Focus::Transactions))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(tx_list, columns[1], &mut self.tx_state);
+
+        let detail = Paragraph::new(
+            self.detail
+                .iter()
+                .map(|line| Line::from(line.as_str()))
+                .collect::<Vec<_>>(),
+        )
+        .block(self.pane("Detail", Focus::Detail))
+        .wrap(Wrap { trim: false })
+        .scroll((self.detail_scroll, 0));
+        frame.render_widget(detail, columns[2]);
+
+        let status = Paragraph::new(format!(
+            "{} | [Tab] switch pane  [↑/↓] navigate  [r] refresh  [q] quit",
+            self.status
+        ))
+        .style(Style::default().fg(Color::Yellow));
+        frame.render_widget(status, rows[1]);
+    }
+
+    fn pane<'a>(&self, title: &'a str, pane: Focus) -> TuiBlock<'a> {
+        let block = TuiBlock::default().borders(Borders::ALL).title(title);
+        if self.focus == pane {
+            block.border_style(Style::default().fg(Color::Yellow))
+        } else {
+            block
+        }
+    }
+}
+
+/// Moves the selection of a list by `delta` while staying in bounds. Returns whether the selection
+/// has changed.
+fn move_list_state(state: &mut ListState, len: usize, delta: isize) -> bool {
+    if len == 0 {
+        return false;
+    }
+
+    let new_index = match state.selected() {
+        Some(index) => (index as isize + delta).clamp(0, len as isize - 1) as usize,
+        None => 0,
+    };
+
+    let changed = state.selected() != Some(new_index);
+    state.select(Some(new_index));
+    changed
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    match Utc.timestamp_opt(timestamp as i64, 0).single() {
+        Some(time) => time.format("%H:%M:%S").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+fn json_str<'a>(value: &'a Value, key: &str) -> &'a str {
+    value
+        .get(key)
+        .and_then(|value| value.as_str())
+        .unwrap_or("-")
+}
+
+fn json_felt_list(value: &Value, key: &str) -> String {
+    match value.get(key).and_then(|value| value.as_array()) {
+        Some(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .filter_map(|item| item.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        None => "[]".to_owned(),
+    }
+}
@@ -0,0 +1,179 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::{
+        crypto::{ecdsa_verify, Signature},
+        types::{BlockId, BlockTag, FieldElement, FunctionCall},
+    },
+    macros::{selector, short_string},
+    providers::Provider,
+};
+
+use crate::{
+    path::ExpandedPathbufParser,
+    provider::ExtendedProvider,
+    transaction_file::{ExportedTransaction, TransactionFile},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+/// Getters for the signer public key of common single-signer account contracts: OpenZeppelin
+/// (both naming styles), Argent, and legacy Argent.
+const PUBLIC_KEY_SELECTORS: [FieldElement; 4] = [
+    selector!("get_public_key"),
+    selector!("getPublicKey"),
+    selector!("get_owner"),
+    selector!("getSigner"),
+];
+
+#[derive(Debug, Parser)]
+pub struct VerifyTx {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the signed transaction file"
+    )]
+    file: PathBuf,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl VerifyTx {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let transaction_file = TransactionFile::load(&self.file)?;
+
+        // The hash is recomputed from the fields, so the summary reflects what's actually signed
+        let transaction_hash = transaction_file.verify_hash()?;
+        transaction_file.print_summary();
+        eprintln!(
+            "{}",
+            "Transaction hash matches the transaction fields".bright_green()
+        );
+
+        if transaction_file.signature.is_empty() {
+            anyhow::bail!("transaction is not signed. Sign it with `starkli sign-tx` first");
+        }
+
+        let provider = self.provider.into_provider()?;
+
+        let chain_id = provider.chain_id().await?;
+        if chain_id != transaction_file.chain_id {
+            anyhow::bail!(
+                "chain ID mismatch: transaction is for {:#x} but the provider is on {:#x}",
+                transaction_file.chain_id,
+                chain_id
+            );
+        }
+
+        let sender = match &transaction_file.transaction {
+            ExportedTransaction::Invoke(invoke) => invoke.sender_address,
+            ExportedTransaction::Declare(declare) => declare.sender_address,
+            ExportedTransaction::DeclareLegacy(declare) => declare.sender_address,
+        };
+        let signature = &transaction_file.signature;
+
+        let valid = match (
+            fetch_public_key(&provider, sender).await,
+            signature.as_slice(),
+        ) {
+            (Some(public_key), [r, s]) => {
+                eprintln!(
+                    "Sender public key: {}",
+                    format!("{:#064x}", public_key).bright_yellow()
+                );
+                ecdsa_verify(&public_key, &transaction_hash, &Signature { r: *r, s: *s })?
+            }
+            // Accounts with other signature schemes, like multisigs or guardians, are asked to
+            // validate the signature themselves
+            _ => {
+                eprintln!("Checking the signature with the sender account contract...");
+                is_valid_signature(&provider, sender, transaction_hash, signature).await?
+            }
+        };
+
+        if valid {
+            println!("{}", "Valid signature".bright_green());
+            Ok(())
+        } else {
+            anyhow::bail!("invalid signature: not signed by the sender account's key")
+        }
+    }
+}
+
+/// Returns `None` if none of the known getters exist on the account.
+async fn fetch_public_key(
+    provider: &ExtendedProvider,
+    account: FieldElement,
+) -> Option<FieldElement> {
+    for selector in PUBLIC_KEY_SELECTORS.into_iter() {
+        if let Ok(result) = provider
+            .call(
+                FunctionCall {
+                    contract_address: account,
+                    entry_point_selector: selector,
+                    calldata: vec![],
+                },
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await
+        {
+            if let [public_key] = result.as_slice() {
+                if *public_key != FieldElement::ZERO {
+                    return Some(*public_key);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Calls the SRC-6 `is_valid_signature` entrypoint, falling back to the legacy camel case name.
+/// Valid signatures return `VALID` in SRC-6, or `1` in older accounts.
+async fn is_valid_signature(
+    provider: &ExtendedProvider,
+    account: FieldElement,
+    hash: FieldElement,
+    signature: &[FieldElement],
+) -> Result<bool> {
+    let mut calldata = vec![hash, signature.len().into()];
+    calldata.extend_from_slice(signature);
+
+    let mut last_error = None;
+    for selector in [
+        selector!("is_valid_signature"),
+        selector!("isValidSignature"),
+    ] {
+        match provider
+            .call(
+                FunctionCall {
+                    contract_address: account,
+                    entry_point_selector: selector,
+                    calldata: calldata.clone(),
+                },
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await
+        {
+            Ok(result) => {
+                return Ok(matches!(
+                    result.first(),
+                    Some(value) if *value == short_string!("VALID") || *value == FieldElement::ONE
+                ))
+            }
+            Err(err) => last_error = Some(err),
+        }
+    }
+
+    // Safe to unwrap as the loop above always runs
+    Err(anyhow::anyhow!(
+        "unable to verify the signature with the sender account: {}",
+        last_error.unwrap()
+    ))
+}
@@ -0,0 +1,94 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::types::{BlockId, BlockTag, StarknetError},
+    providers::{Provider, ProviderError},
+};
+
+use crate::{
+    address_book::AddressBookResolver, decode::FeltDecoder, utils::format_address,
+    verbosity::VerbosityArgs, ProviderArgs,
+};
+
+/// Upper bound of the poll interval as it backs off.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Parser)]
+pub struct WaitForContract {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        help = "Give up after this many seconds. Waits indefinitely if not specified"
+    )]
+    timeout: Option<u64>,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        default_value = "5000",
+        help = "Initial poll interval in milliseconds, doubled after each attempt up to 60 seconds"
+    )]
+    poll_interval: u64,
+    #[clap(help = "Contract address")]
+    address: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl WaitForContract {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = felt_decoder
+            .decode_single_with_addr_fallback(&self.address)
+            .await?;
+
+        let started_at = Instant::now();
+        let deadline = self.timeout.map(Duration::from_secs);
+        let mut poll_interval = Duration::from_millis(self.poll_interval);
+
+        loop {
+            match provider
+                .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+                .await
+            {
+                Ok(class_hash) => {
+                    eprintln!(
+                        "Contract {} deployed with class hash:",
+                        format_address(address).bright_yellow()
+                    );
+                    println!("{:#064x}", class_hash);
+                    return Ok(());
+                }
+                Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
+                    eprintln!("Contract not deployed yet...");
+                }
+                Err(err) => return Err(err.into()),
+            }
+
+            if let Some(deadline) = deadline {
+                let elapsed = started_at.elapsed();
+                if elapsed >= deadline {
+                    anyhow::bail!(
+                        "timed out after {} seconds waiting for contract {:#064x}",
+                        deadline.as_secs(),
+                        address
+                    );
+                }
+                poll_interval = poll_interval.min(deadline - elapsed);
+            }
+
+            tokio::time::sleep(poll_interval).await;
+            poll_interval = (poll_interval * 2).min(MAX_POLL_INTERVAL);
+        }
+    }
+}
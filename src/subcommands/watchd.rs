@@ -0,0 +1,661 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use hyper::{
+    header::{HeaderValue, CONTENT_TYPE},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use starknet::{
+    core::types::{ExecutionResult, FieldElement, StarknetError},
+    providers::{Provider, ProviderError},
+};
+use tokio::sync::{Mutex, Notify};
+
+use crate::{
+    profile::Profiles, provider::ExtendedProvider, verbosity::VerbosityArgs, ProviderArgs,
+};
+
+const INTERNAL_ERROR_CODE: i64 = -32000;
+const METHOD_NOT_FOUND_CODE: i64 = -32601;
+const INVALID_PARAMS_CODE: i64 = -32602;
+
+/// Maximum number of receipt requests in flight at the same time in each polling round.
+const POLL_CONCURRENCY: usize = 16;
+
+#[derive(Debug, Parser)]
+pub struct Watchd {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(
+        long,
+        default_value = "5051",
+        help = "Port to listen on. The service is only exposed on localhost"
+    )]
+    port: u16,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        default_value = "5000",
+        help = "Transaction result poll interval in milliseconds"
+    )]
+    poll_interval: u64,
+    #[clap(
+        long,
+        default_value = "3600",
+        help = "Number of seconds to keep the results of finished watches"
+    )]
+    retention: u64,
+    #[clap(
+        long,
+        help = "Path to the file where watches are persisted across restarts. Defaults to \
+                watchd-<PORT>.json in the cache folder"
+    )]
+    state_file: Option<PathBuf>,
+    #[clap(
+        long,
+        conflicts_with = "state_file",
+        help = "Keep watches in memory only, losing them when the daemon stops"
+    )]
+    no_persist: bool,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+/// Watches shared by all clients. A single polling loop updates them, and wakes up clients waiting
+/// for results after each round.
+struct WatchdContext {
+    provider: Arc<ExtendedProvider>,
+    chain_id: FieldElement,
+    watches: Mutex<HashMap<FieldElement, Watch>>,
+    last_block: Mutex<Option<u64>>,
+    state_file: Option<PathBuf>,
+    updated: Notify,
+}
+
+struct Watch {
+    status: WatchStatus,
+    /// When the watch was added, or when it finished for finished watches.
+    since: Instant,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "SCREAMING_SNAKE_CASE")]
+enum WatchStatus {
+    Pending,
+    Succeeded {
+        block_number: Option<u64>,
+    },
+    Reverted {
+        block_number: Option<u64>,
+        #[serde(rename = "revert_reason")]
+        reason: String,
+    },
+}
+
+/// Watches and the last block seen, written to the state file after every change so that a
+/// restarted daemon resumes where it left off.
+#[derive(Serialize, Deserialize)]
+struct WatchdState {
+    /// Guards against resuming watches on a different network.
+    chain_id: FieldElement,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_block: Option<u64>,
+    watches: Vec<PersistedWatch>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedWatch {
+    transaction_hash: FieldElement,
+    #[serde(flatten)]
+    status: WatchStatus,
+    /// `Watch::since` as a Unix timestamp in seconds, as instants don't survive restarts.
+    since: u64,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct WatchParams {
+    transaction_hash: FieldElement,
+    /// Only used when waiting. Defaults to waiting indefinitely.
+    #[serde(default)]
+    timeout: Option<u64>,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl Watchd {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        if self.poll_interval == 0 {
+            anyhow::bail!("--poll-interval must be greater than 0");
+        }
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let chain_id = provider.chain_id().await?;
+
+        let state_file = if self.no_persist {
+            None
+        } else {
+            match self.state_file {
+                Some(state_file) => Some(state_file),
+                None => {
+                    let mut path = Profiles::get_cache_folder()?;
+                    path.push(format!("watchd-{}.json", self.port));
+                    Some(path)
+                }
+            }
+        };
+
+        let (watches, last_block) = match &state_file {
+            Some(state_file) if state_file.exists() => {
+                let state = load_state(state_file, chain_id)?;
+
+                let pending = state
+                    .watches
+                    .iter()
+                    .filter(|watch| matches!(watch.status, WatchStatus::Pending))
+                    .count();
+                eprintln!(
+                    "Resumed {} watches ({} pending) from {}{}",
+                    state.watches.len().to_string().bright_yellow(),
+                    pending.to_string().bright_yellow(),
+                    state_file.display(),
+                    match state.last_block {
+                        Some(last_block) => format!(", last seen block {}", last_block),
+                        None => String::new(),
+                    }
+                );
+
+                let watches = state
+                    .watches
+                    .into_iter()
+                    .map(|watch| {
+                        (
+                            watch.transaction_hash,
+                            Watch {
+                                status: watch.status,
+                                since: instant_from_unix(watch.since),
+                            },
+                        )
+                    })
+                    .collect();
+                (watches, state.last_block)
+            }
+            _ => (HashMap::new(), None),
+        };
+
+        let context = Arc::new(WatchdContext {
+            provider,
+            chain_id,
+            watches: Mutex::new(watches),
+            last_block: Mutex::new(last_block),
+            state_file,
+            updated: Notify::new(),
+        });
+
+        tokio::spawn(context.clone().poll_loop(
+            Duration::from_millis(self.poll_interval),
+            Duration::from_secs(self.retention),
+        ));
+
+        let make_service = make_service_fn(move |_| {
+            let context = context.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let context = context.clone();
+                    async move { Ok::<_, Infallible>(context.handle(request).await) }
+                }))
+            }
+        });
+
+        let address = SocketAddr::from(([127, 0, 0, 1], self.port));
+        let server = Server::try_bind(&address)?.serve(make_service);
+        log::info!("watch daemon listening on {}", address);
+
+        eprintln!(
+            "Watching transactions on {}",
+            format!("http://{}", address).bright_yellow()
+        );
+        eprintln!(
+            "Methods: `starkli_watch`, `starkli_waitForTransaction`, `starkli_getWatch`, and \
+            `starkli_listWatches`."
+        );
+
+        server.await?;
+
+        Ok(())
+    }
+}
+
+impl WatchdContext {
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        match *request.method() {
+            Method::POST => match hyper::body::to_bytes(request.into_body()).await {
+                Ok(body) => self.handle_body(&body).await,
+                Err(err) => plain_response(StatusCode::BAD_REQUEST, err.to_string()),
+            },
+            _ => plain_response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "only POST requests are supported".into(),
+            ),
+        }
+    }
+
+    async fn handle_body(&self, body: &[u8]) -> Response<Body> {
+        let request = match serde_json::from_slice::<JsonRpcRequest>(body) {
+            Ok(request) => request,
+            Err(err) => {
+                return plain_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("invalid JSON-RPC request: {}", err),
+                )
+            }
+        };
+
+        let result = match request.method.as_str() {
+            "starkli_watch" => self.watch(request.params).await,
+            "starkli_waitForTransaction" => self.wait(request.params).await,
+            "starkli_getWatch" => self.get_watch(request.params).await,
+            "starkli_listWatches" => Ok(self.list_watches().await),
+            _ => Err(RpcError {
+                code: METHOD_NOT_FOUND_CODE,
+                message: format!("method not found: {}", request.method),
+            }),
+        };
+
+        let response = match result {
+            Ok(result) => json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "result": result,
+            }),
+            Err(err) => json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "error": {
+                    "code": err.code,
+                    "message": err.message,
+                },
+            }),
+        };
+
+        let mut response = Response::new(Body::from(response.to_string()));
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+    }
+
+    /// Adds a watch without waiting. Watching the same transaction again is a no-op.
+    async fn watch(&self, params: Value) -> Result<Value, RpcError> {
+        let params = parse_params(params)?;
+        let status = self.add_watch(params.transaction_hash).await;
+        Ok(status_json(params.transaction_hash, &status))
+    }
+
+    /// Adds a watch if needed, and responds once the transaction is included or the timeout is
+    /// reached, whichever comes first.
+    async fn wait(&self, params: Value) -> Result<Value, RpcError> {
+        let params = parse_params(params)?;
+        let transaction_hash = params.transaction_hash;
+        let deadline = params
+            .timeout
+            .map(|timeout| Instant::now() + Duration::from_secs(timeout));
+
+        let mut status = self.add_watch(transaction_hash).await;
+        loop {
+            if !matches!(status, WatchStatus::Pending) {
+                break;
+            }
+
+            // Registered before checking again so that an update in between isn't missed
+            let updated = self.updated.notified();
+            status = match self.watches.lock().await.get(&transaction_hash) {
+                Some(watch) => watch.status.clone(),
+                None => {
+                    return Err(RpcError::internal(
+                        "watch dropped before the transaction finished",
+                    ))
+                }
+            };
+            if !matches!(status, WatchStatus::Pending) {
+                break;
+            }
+
+            match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero()
+                        || tokio::time::timeout(remaining, updated).await.is_err()
+                    {
+                        break;
+                    }
+                }
+                None => updated.await,
+            }
+        }
+
+        Ok(status_json(transaction_hash, &status))
+    }
+
+    async fn get_watch(&self, params: Value) -> Result<Value, RpcError> {
+        let params = parse_params(params)?;
+        match self.watches.lock().await.get(&params.transaction_hash) {
+            Some(watch) => Ok(status_json(params.transaction_hash, &watch.status)),
+            None => Err(RpcError {
+                code: INVALID_PARAMS_CODE,
+                message: format!(
+                    "transaction {:#064x} is not being watched",
+                    params.transaction_hash
+                ),
+            }),
+        }
+    }
+
+    async fn list_watches(&self) -> Value {
+        Value::Array(
+            self.watches
+                .lock()
+                .await
+                .iter()
+                .map(|(transaction_hash, watch)| status_json(*transaction_hash, &watch.status))
+                .collect(),
+        )
+    }
+
+    async fn add_watch(&self, transaction_hash: FieldElement) -> WatchStatus {
+        let mut watches = self.watches.lock().await;
+        if let Some(watch) = watches.get(&transaction_hash) {
+            return watch.status.clone();
+        }
+
+        log::info!("watching transaction {:#064x}", transaction_hash);
+        watches.insert(
+            transaction_hash,
+            Watch {
+                status: WatchStatus::Pending,
+                since: Instant::now(),
+            },
+        );
+        self.save_state(&watches).await;
+
+        WatchStatus::Pending
+    }
+
+    /// Writes the state file, if persistence is enabled. Failures are only logged, as watches
+    /// still work in memory.
+    async fn save_state(&self, watches: &HashMap<FieldElement, Watch>) {
+        let state_file = match &self.state_file {
+            Some(state_file) => state_file,
+            None => return,
+        };
+
+        let state = WatchdState {
+            chain_id: self.chain_id,
+            last_block: *self.last_block.lock().await,
+            watches: watches
+                .iter()
+                .map(|(transaction_hash, watch)| PersistedWatch {
+                    transaction_hash: *transaction_hash,
+                    status: watch.status.clone(),
+                    since: instant_to_unix(watch.since),
+                })
+                .collect(),
+        };
+
+        if let Err(err) = write_state(state_file, &state) {
+            log::warn!(
+                "failed to write state file {}: {}",
+                state_file.display(),
+                err
+            );
+        }
+    }
+
+    /// Checks all pending transactions in each round, sharing the provider connection among them,
+    /// and drops finished watches once their retention period is over.
+    async fn poll_loop(self: Arc<Self>, poll_interval: Duration, retention: Duration) {
+        loop {
+            tokio::time::sleep(poll_interval).await;
+
+            let mut changed = false;
+
+            if let Ok(block_number) = self.provider.block_number().await {
+                let mut last_block = self.last_block.lock().await;
+                if *last_block != Some(block_number) {
+                    *last_block = Some(block_number);
+                    changed = true;
+                }
+            }
+
+            let pending = {
+                let mut watches = self.watches.lock().await;
+                let count = watches.len();
+                watches.retain(|_, watch| {
+                    matches!(watch.status, WatchStatus::Pending)
+                        || watch.since.elapsed() < retention
+                });
+                changed |= watches.len() != count;
+                watches
+                    .iter()
+                    .filter(|(_, watch)| matches!(watch.status, WatchStatus::Pending))
+                    .map(|(transaction_hash, _)| *transaction_hash)
+                    .collect::<Vec<_>>()
+            };
+
+            for chunk in pending.chunks(POLL_CONCURRENCY) {
+                let handles = chunk
+                    .iter()
+                    .map(|transaction_hash| {
+                        let provider = self.provider.clone();
+                        let transaction_hash = *transaction_hash;
+                        tokio::spawn(async move { fetch_status(&provider, transaction_hash).await })
+                    })
+                    .collect::<Vec<_>>();
+
+                for (transaction_hash, handle) in chunk.iter().zip(handles.into_iter()) {
+                    let status = match handle.await {
+                        Ok(Ok(status)) => status,
+                        Ok(Err(err)) => {
+                            log::warn!(
+                                "failed to fetch receipt of {:#064x}: {}",
+                                transaction_hash,
+                                err
+                            );
+                            continue;
+                        }
+                        Err(err) => {
+                            log::warn!("receipt task failed: {}", err);
+                            continue;
+                        }
+                    };
+
+                    if matches!(status, WatchStatus::Pending) {
+                        continue;
+                    }
+
+                    eprintln!(
+                        "Transaction {} {}",
+                        format!("{:#064x}", transaction_hash).bright_yellow(),
+                        match &status {
+                            WatchStatus::Reverted { .. } => "reverted",
+                            _ => "confirmed",
+                        }
+                    );
+
+                    if let Some(watch) = self.watches.lock().await.get_mut(transaction_hash) {
+                        watch.status = status;
+                        watch.since = Instant::now();
+                        changed = true;
+                    }
+                }
+            }
+
+            if changed {
+                let watches = self.watches.lock().await;
+                self.save_state(&watches).await;
+            }
+
+            self.updated.notify_waiters();
+        }
+    }
+}
+
+impl RpcError {
+    fn internal<E>(err: E) -> Self
+    where
+        E: std::fmt::Display,
+    {
+        Self {
+            code: INTERNAL_ERROR_CODE,
+            message: err.to_string(),
+        }
+    }
+}
+
+async fn fetch_status(
+    provider: &ExtendedProvider,
+    transaction_hash: FieldElement,
+) -> Result<WatchStatus> {
+    match provider.get_transaction_receipt(transaction_hash).await {
+        Ok(receipt) => {
+            let block_number = serde_json::to_value(&receipt)?
+                .get("block_number")
+                .and_then(|value| value.as_u64());
+
+            Ok(match receipt.execution_result() {
+                ExecutionResult::Succeeded => WatchStatus::Succeeded { block_number },
+                ExecutionResult::Reverted { reason } => WatchStatus::Reverted {
+                    block_number,
+                    reason: reason.to_owned(),
+                },
+            })
+        }
+        Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {
+            Ok(WatchStatus::Pending)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn load_state(path: &Path, chain_id: FieldElement) -> Result<WatchdState> {
+    let state: WatchdState = serde_json::from_str(&std::fs::read_to_string(path)?)
+        .map_err(|err| anyhow::anyhow!("invalid state file {}: {}", path.display(), err))?;
+
+    if state.chain_id != chain_id {
+        anyhow::bail!(
+            "state file {} was written for chain {:#x}, but the provider is on chain {:#x}. \
+            Use --state-file to keep watches of different networks apart",
+            path.display(),
+            state.chain_id,
+            chain_id
+        );
+    }
+
+    Ok(state)
+}
+
+/// Writes to a temporary file first, so that a crash in the middle of writing never leaves a
+/// truncated state file behind.
+fn write_state(path: &Path, state: &WatchdState) -> Result<()> {
+    if let Some(folder) = path.parent() {
+        if !folder.as_os_str().is_empty() && !folder.exists() {
+            std::fs::create_dir_all(folder)?;
+        }
+    }
+
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    std::fs::write(&temp_path, serde_json::to_string_pretty(state)?)?;
+    std::fs::rename(&temp_path, path)?;
+
+    Ok(())
+}
+
+fn instant_to_unix(instant: Instant) -> u64 {
+    SystemTime::now()
+        .checked_sub(instant.elapsed())
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Timestamps from before the machine booted can't be represented as instants, in which case the
+/// retention period of finished watches starts over.
+fn instant_from_unix(timestamp: u64) -> Instant {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    Instant::now()
+        .checked_sub(Duration::from_secs(now.saturating_sub(timestamp)))
+        .unwrap_or_else(Instant::now)
+}
+
+fn status_json(transaction_hash: FieldElement, status: &WatchStatus) -> Value {
+    let transaction_hash = format!("{:#064x}", transaction_hash);
+    match status {
+        WatchStatus::Pending => json!({
+            "transaction_hash": transaction_hash,
+            "status": "PENDING",
+        }),
+        WatchStatus::Succeeded { block_number } => json!({
+            "transaction_hash": transaction_hash,
+            "status": "SUCCEEDED",
+            "block_number": block_number,
+        }),
+        WatchStatus::Reverted {
+            block_number,
+            reason,
+        } => json!({
+            "transaction_hash": transaction_hash,
+            "status": "REVERTED",
+            "block_number": block_number,
+            "revert_reason": reason,
+        }),
+    }
+}
+
+/// Accepts both named params (`{"transaction_hash": ...}`) and positional params (`[...]`).
+fn parse_params(params: Value) -> Result<WatchParams, RpcError> {
+    let params = match params {
+        Value::Array(mut items) if items.len() == 1 => items.remove(0),
+        params => params,
+    };
+
+    serde_json::from_value(params).map_err(|err| RpcError {
+        code: INVALID_PARAMS_CODE,
+        message: format!("invalid params: {}", err),
+    })
+}
+
+fn plain_response(status: StatusCode, message: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(message));
+    *response.status_mut() = status;
+    response
+}
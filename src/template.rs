@@ -0,0 +1,332 @@
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::FieldElement;
+
+use crate::profile::Profiles;
+
+/// A parameterized invoke, so that recurring operational transactions like parameter updates are
+/// always sent to the same target with arguments of the expected types. Each template is stored as
+/// a TOML file under the `templates` folder.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InvokeTemplate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub to: String,
+    pub function: String,
+    /// Raw arguments, same as on the command line, where `{name}` stands for a parameter.
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub params: Vec<TemplateParam>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateParam {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: ParamType,
+}
+
+/// How parameter values are checked and turned into arguments.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamType {
+    #[default]
+    Felt,
+    U256,
+    Address,
+    Bool,
+    String,
+}
+
+impl InvokeTemplate {
+    pub fn new(
+        description: Option<String>,
+        to: String,
+        function: String,
+        args: Vec<String>,
+        params: Vec<TemplateParam>,
+    ) -> Result<Self> {
+        let template = Self {
+            description,
+            to,
+            function,
+            args,
+            params,
+        };
+        template.validate()?;
+        Ok(template)
+    }
+
+    pub fn get_folder() -> Result<PathBuf> {
+        let mut path = Profiles::get_config_folder()?;
+        path.push("templates");
+        Ok(path)
+    }
+
+    pub fn get_path(name: &str) -> Result<PathBuf> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            anyhow::bail!(
+                "invalid template name \"{}\": only letters, digits, `_` and `-` are allowed",
+                name
+            );
+        }
+
+        let mut path = Self::get_folder()?;
+        path.push(format!("{}.toml", name));
+        Ok(path)
+    }
+
+    /// Names of all saved templates, sorted.
+    pub fn list() -> Result<Vec<String>> {
+        let folder = Self::get_folder()?;
+        if !folder.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut names = std::fs::read_dir(folder)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .filter_map(|path| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+
+        Ok(names)
+    }
+
+    pub fn exists(name: &str) -> Result<bool> {
+        Ok(Self::get_path(name)?.exists())
+    }
+
+    pub fn load(name: &str) -> Result<Self> {
+        let path = Self::get_path(name)?;
+        if !path.exists() {
+            anyhow::bail!(
+                "template not found: {}. Use `starkli template save {}` to create it",
+                name,
+                name
+            );
+        }
+
+        // Templates may be edited by hand, so they're checked again when loaded
+        let template: Self = toml::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|err| anyhow::anyhow!("invalid template {}: {}", path.display(), err))?;
+        template.validate()?;
+
+        Ok(template)
+    }
+
+    pub fn save(&self, name: &str) -> Result<()> {
+        let path = Self::get_path(name)?;
+
+        let folder = Self::get_folder()?;
+        if !folder.exists() {
+            std::fs::create_dir_all(folder)?;
+        }
+
+        std::fs::write(path, toml::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn remove(name: &str) -> Result<()> {
+        let path = Self::get_path(name)?;
+        if !path.exists() {
+            anyhow::bail!("template not found: {}", name);
+        }
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// Fills in the parameters, returning the target, function and arguments in the multicall
+    /// syntax accepted by `starkli invoke`.
+    pub fn instantiate(&self, values: &BTreeMap<String, String>) -> Result<Vec<String>> {
+        for name in values.keys() {
+            if !self.params.iter().any(|param| &param.name == name) {
+                anyhow::bail!("unknown template parameter: {}", name);
+            }
+        }
+
+        let missing = self
+            .params
+            .iter()
+            .filter(|param| !values.contains_key(&param.name))
+            .map(|param| param.name.as_str())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            anyhow::bail!("missing template parameters: {}", missing.join(", "));
+        }
+
+        let mut instantiated = vec![];
+        for arg in [&self.to, &self.function]
+            .into_iter()
+            .chain(self.args.iter())
+        {
+            instantiated.push(match placeholder(arg) {
+                Some(name) => {
+                    // Both lookups are safe as parameters were checked above
+                    let param = self.params.iter().find(|param| param.name == name).unwrap();
+                    let value = &values[name];
+                    param.kind.encode(value).map_err(|err| {
+                        anyhow::anyhow!("invalid value for parameter {}: {}", name, err)
+                    })?
+                }
+                None => arg.to_owned(),
+            });
+        }
+
+        Ok(instantiated)
+    }
+
+    /// Checks that parameters are declared once and that placeholders match declarations.
+    fn validate(&self) -> Result<()> {
+        for (ind_param, param) in self.params.iter().enumerate() {
+            if !is_valid_param_name(&param.name) {
+                anyhow::bail!("invalid parameter name: {}", param.name);
+            }
+            if self.params[..ind_param]
+                .iter()
+                .any(|previous| previous.name == param.name)
+            {
+                anyhow::bail!("duplicate parameter: {}", param.name);
+            }
+        }
+
+        let placeholders = [&self.to, &self.function]
+            .into_iter()
+            .chain(self.args.iter())
+            .filter_map(|arg| placeholder(arg))
+            .collect::<Vec<_>>();
+
+        for name in placeholders.iter() {
+            if !self.params.iter().any(|param| param.name == *name) {
+                anyhow::bail!(
+                    "undeclared parameter {{{}}}. Declare it with `--param {}:<TYPE>`",
+                    name,
+                    name
+                );
+            }
+        }
+        for param in self.params.iter() {
+            if !placeholders.contains(&param.name.as_str()) {
+                anyhow::bail!("parameter {} is not used in any argument", param.name);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TemplateParam {
+    pub fn signature(&self) -> String {
+        format!("{}: {}", self.name, self.kind)
+    }
+}
+
+impl FromStr for TemplateParam {
+    type Err = anyhow::Error;
+
+    /// Parses a declaration like `fee:u256`, where the type defaults to `felt`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, kind) = match s.split_once(':') {
+            Some((name, kind)) => (name, kind.parse()?),
+            None => (s, ParamType::Felt),
+        };
+
+        Ok(Self {
+            name: name.to_owned(),
+            kind,
+        })
+    }
+}
+
+impl ParamType {
+    /// Checks the value and turns it into an argument that resolves to the right felts.
+    pub fn encode(&self, value: &str) -> Result<String> {
+        Ok(match self {
+            Self::Felt => value.to_owned(),
+            Self::U256 => {
+                if value.starts_with("u256:") {
+                    value.to_owned()
+                } else {
+                    format!("u256:{}", value)
+                }
+            }
+            // Calldata isn't resolved as addresses by default, so names are made explicit
+            Self::Address => {
+                if value.parse::<FieldElement>().is_ok()
+                    || value.starts_with("addr:")
+                    || value.starts_with('@')
+                    || value.starts_with('$')
+                {
+                    value.to_owned()
+                } else {
+                    format!("@{}", value)
+                }
+            }
+            Self::Bool => match value {
+                "true" | "1" => "1".to_owned(),
+                "false" | "0" => "0".to_owned(),
+                _ => anyhow::bail!("expected true or false"),
+            },
+            Self::String => format!("str:{}", value),
+        })
+    }
+}
+
+impl FromStr for ParamType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "felt" => Self::Felt,
+            "u256" => Self::U256,
+            "address" => Self::Address,
+            "bool" => Self::Bool,
+            "string" => Self::String,
+            _ => anyhow::bail!(
+                "unknown parameter type: {}. Expected felt, u256, address, bool or string",
+                s
+            ),
+        })
+    }
+}
+
+impl std::fmt::Display for ParamType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Felt => write!(f, "felt"),
+            Self::U256 => write!(f, "u256"),
+            Self::Address => write!(f, "address"),
+            Self::Bool => write!(f, "bool"),
+            Self::String => write!(f, "string"),
+        }
+    }
+}
+
+/// The parameter name if the argument is exactly `{name}`.
+fn placeholder(arg: &str) -> Option<&str> {
+    arg.strip_prefix('{')
+        .and_then(|arg| arg.strip_suffix('}'))
+        .filter(|name| is_valid_param_name(name))
+}
+
+fn is_valid_param_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
@@ -0,0 +1,394 @@
+use std::{collections::HashMap, io::Read, path::PathBuf};
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use num_bigint::{BigUint, ToBigInt};
+use serde::Deserialize;
+use starknet::{
+    core::{
+        chain_id,
+        types::{BlockId, BlockTag, FieldElement, FunctionCall},
+        utils::cairo_short_string_to_felt,
+    },
+    macros::{felt, selector, short_string},
+    providers::Provider,
+};
+
+use crate::{chain_id::CHAIN_ID_KATANA, profile::Profiles, provider::ExtendedProvider};
+
+/// The canonical ETH address, the same on all networks.
+pub const ETH_ADDRESS: FieldElement =
+    felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7");
+/// The canonical STRK address, the same on all networks.
+pub const STRK_ADDRESS: FieldElement =
+    felt!("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d");
+
+const BUILTIN_TOKENS: [BuiltinToken; 14] = [
+    BuiltinToken::new(chain_id::MAINNET, ETH_ADDRESS, "ETH", 18),
+    BuiltinToken::new(chain_id::MAINNET, STRK_ADDRESS, "STRK", 18),
+    BuiltinToken::new(
+        chain_id::MAINNET,
+        felt!("0x053c91253bc9682c04929ca02ed00b3e423f6710d2ee7e0d5ebb06f3ecf368a8"),
+        "USDC",
+        6,
+    ),
+    BuiltinToken::new(
+        chain_id::MAINNET,
+        felt!("0x068f5c6a61780768455de69077e07e89787839bf8166decfbf92b645209c0fb8"),
+        "USDT",
+        6,
+    ),
+    BuiltinToken::new(
+        chain_id::MAINNET,
+        felt!("0x00da114221cb83fa859dbdb4c44beeaa0bb37c7537ad5ae66fe5e0efd20e6eb3"),
+        "DAI",
+        18,
+    ),
+    BuiltinToken::new(
+        chain_id::MAINNET,
+        felt!("0x03fe2b97c1fd336e750087d68b9b867997fd64a2661ff3ca5a7c771641e8e7ac"),
+        "WBTC",
+        8,
+    ),
+    BuiltinToken::new(chain_id::TESTNET, ETH_ADDRESS, "ETH", 18),
+    BuiltinToken::new(chain_id::TESTNET, STRK_ADDRESS, "STRK", 18),
+    BuiltinToken::new(short_string!("SN_SEPOLIA"), ETH_ADDRESS, "ETH", 18),
+    BuiltinToken::new(short_string!("SN_SEPOLIA"), STRK_ADDRESS, "STRK", 18),
+    BuiltinToken::new(
+        short_string!("SN_INTEGRATION_SEPOLIA"),
+        ETH_ADDRESS,
+        "ETH",
+        18,
+    ),
+    BuiltinToken::new(
+        short_string!("SN_INTEGRATION_SEPOLIA"),
+        STRK_ADDRESS,
+        "STRK",
+        18,
+    ),
+    BuiltinToken::new(CHAIN_ID_KATANA, ETH_ADDRESS, "ETH", 18),
+    BuiltinToken::new(CHAIN_ID_KATANA, STRK_ADDRESS, "STRK", 18),
+];
+
+struct BuiltinToken {
+    chain_id: FieldElement,
+    address: FieldElement,
+    symbol: &'static str,
+    decimals: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenInfo {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// Token metadata for a single network, made from the built-in list plus any entries the user
+/// adds in `tokens.toml` under the config folder. User entries override built-in ones.
+#[derive(Debug, Default)]
+pub struct TokenRegistry {
+    tokens: HashMap<FieldElement, TokenInfo>,
+}
+
+/// A token transfer found in a list of events.
+#[derive(Debug)]
+pub struct TokenTransfer {
+    pub token: FieldElement,
+    pub from: FieldElement,
+    pub to: FieldElement,
+    pub amount: BigUint,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UserTokenList {
+    #[serde(default)]
+    tokens: Vec<UserToken>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UserToken {
+    chain_id: String,
+    address: FieldElement,
+    symbol: String,
+    decimals: u8,
+}
+
+impl BuiltinToken {
+    const fn new(
+        chain_id: FieldElement,
+        address: FieldElement,
+        symbol: &'static str,
+        decimals: u8,
+    ) -> Self {
+        Self {
+            chain_id,
+            address,
+            symbol,
+            decimals,
+        }
+    }
+}
+
+impl TokenRegistry {
+    pub fn load(chain_id: FieldElement) -> Result<Self> {
+        let mut tokens = HashMap::new();
+
+        for token in BUILTIN_TOKENS.iter() {
+            if token.chain_id == chain_id {
+                tokens.insert(
+                    token.address,
+                    TokenInfo {
+                        symbol: token.symbol.to_owned(),
+                        decimals: token.decimals,
+                    },
+                );
+            }
+        }
+
+        let path = Self::get_user_tokens_path()?;
+        if path.exists() {
+            let mut file = std::fs::File::open(&path)?;
+            let mut buffer = String::new();
+            file.read_to_string(&mut buffer)?;
+
+            let user_tokens: UserTokenList = toml::from_str(&buffer)
+                .map_err(|err| anyhow::anyhow!("invalid token list {}: {}", path.display(), err))?;
+
+            for token in user_tokens.tokens.into_iter() {
+                if parse_chain_id(&token.chain_id)? == chain_id {
+                    tokens.insert(
+                        token.address,
+                        TokenInfo {
+                            symbol: token.symbol,
+                            decimals: token.decimals,
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(Self { tokens })
+    }
+
+    /// Loads the registry for the provider's network, also covering the fee tokens in case they
+    /// are overridden for a custom network.
+    pub async fn load_for_provider(provider: &ExtendedProvider) -> Result<Self> {
+        let mut registry = Self::load(provider.chain_id().await?)?;
+
+        for (address, symbol) in [
+            (provider.eth_token_address(), "ETH"),
+            (provider.strk_token_address(), "STRK"),
+        ] {
+            registry.tokens.entry(address).or_insert_with(|| TokenInfo {
+                symbol: symbol.to_owned(),
+                decimals: 18,
+            });
+        }
+
+        Ok(registry)
+    }
+
+    pub fn get(&self, address: FieldElement) -> Option<&TokenInfo> {
+        self.tokens.get(&address)
+    }
+
+    /// Renders a raw amount of `token` as e.g. `12.5 USDC`, or `None` if the token is unknown.
+    pub fn format_amount(&self, token: FieldElement, amount: &BigUint) -> Option<String> {
+        self.get(token).map(|info| info.format_amount(amount))
+    }
+
+    pub fn get_user_tokens_path() -> Result<PathBuf> {
+        let mut path = Profiles::get_config_folder()?;
+        path.push("tokens.toml");
+        Ok(path)
+    }
+}
+
+impl TokenInfo {
+    pub fn format_amount(&self, amount: &BigUint) -> String {
+        // `to_bigint()` from `BigUint` always returns `Some`.
+        let amount = BigDecimal::new(amount.to_bigint().unwrap(), self.decimals as i64);
+        format!("{} {}", amount.normalized(), self.symbol)
+    }
+}
+
+/// Parses an ERC20 `Transfer` event, supporting both the legacy layout with everything in `data`
+/// and the Cairo 1 layout with `from` and `to` as keys.
+pub fn parse_transfer_event(
+    from_address: FieldElement,
+    keys: &[FieldElement],
+    data: &[FieldElement],
+) -> Option<TokenTransfer> {
+    if keys.first() != Some(&selector!("Transfer")) {
+        return None;
+    }
+
+    let (from, to, low, high) = match (keys.len(), data.len()) {
+        (1, 4) => (data[0], data[1], data[2], data[3]),
+        (3, 2) => (keys[1], keys[2], data[0], data[1]),
+        _ => return None,
+    };
+
+    let low = BigUint::from_bytes_be(&low.to_bytes_be());
+    let high = BigUint::from_bytes_be(&high.to_bytes_be());
+
+    Some(TokenTransfer {
+        token: from_address,
+        from,
+        to,
+        amount: (high << 128) + low,
+    })
+}
+
+fn parse_chain_id(chain_id: &str) -> Result<FieldElement> {
+    Ok(if chain_id.starts_with("0x") {
+        FieldElement::from_hex_be(chain_id)?
+    } else {
+        cairo_short_string_to_felt(chain_id)?
+    })
+}
+
+/// Collects token transfers from any JSON document containing events, such as receipts and
+/// traces. Events without a `from_address` are attributed to the enclosing `contract_address`.
+/// Fetches the `balanceOf` of `account_address` for an ERC20 token returning `u256`.
+pub async fn fetch_balance(
+    provider: &ExtendedProvider,
+    token: FieldElement,
+    account_address: FieldElement,
+) -> Result<BigUint> {
+    let result = provider
+        .call(
+            balance_call(token, account_address),
+            BlockId::Tag(BlockTag::Pending),
+        )
+        .await?;
+
+    parse_balance(&result)
+}
+
+/// Fetches the balances of many accounts, batching the queries where the endpoint allows. Each
+/// balance is fetched independently, so a failure only affects its own account.
+pub async fn fetch_balances(
+    provider: &ExtendedProvider,
+    token: FieldElement,
+    account_addresses: &[FieldElement],
+) -> Vec<Result<BigUint>> {
+    let calls = account_addresses
+        .iter()
+        .map(|account_address| balance_call(token, *account_address))
+        .collect::<Vec<_>>();
+
+    provider
+        .batch_call(&calls, BlockId::Tag(BlockTag::Pending))
+        .await
+        .into_iter()
+        .map(|result| parse_balance(&result?))
+        .collect()
+}
+
+fn balance_call(token: FieldElement, account_address: FieldElement) -> FunctionCall {
+    FunctionCall {
+        contract_address: token,
+        entry_point_selector: selector!("balanceOf"),
+        calldata: vec![account_address],
+    }
+}
+
+/// Balances are `u256` values split into low and high felts.
+fn parse_balance(result: &[FieldElement]) -> Result<BigUint> {
+    if result.len() != 2 {
+        anyhow::bail!("unexpected call result size: {}", result.len());
+    }
+
+    let low = BigUint::from_bytes_be(&result[0].to_bytes_be());
+    let high = BigUint::from_bytes_be(&result[1].to_bytes_be());
+
+    Ok((high << 128) + low)
+}
+
+pub fn collect_transfers(value: &serde_json::Value) -> Vec<TokenTransfer> {
+    let mut transfers = vec![];
+    collect_transfers_inner(value, None, &mut transfers);
+    transfers
+}
+
+fn collect_transfers_inner(
+    value: &serde_json::Value,
+    contract_address: Option<FieldElement>,
+    transfers: &mut Vec<TokenTransfer>,
+) {
+    match value {
+        serde_json::Value::Object(object) => {
+            let contract_address = object
+                .get("contract_address")
+                .and_then(json_to_felt)
+                .or(contract_address);
+
+            if let Some(serde_json::Value::Array(events)) = object.get("events") {
+                for event in events.iter() {
+                    let from_address = event
+                        .get("from_address")
+                        .and_then(json_to_felt)
+                        .or(contract_address);
+                    let keys = event.get("keys").and_then(json_to_felts);
+                    let data = event.get("data").and_then(json_to_felts);
+
+                    if let (Some(from_address), Some(keys), Some(data)) = (from_address, keys, data)
+                    {
+                        if let Some(transfer) = parse_transfer_event(from_address, &keys, &data) {
+                            transfers.push(transfer);
+                        }
+                    }
+                }
+            }
+
+            for (key, child) in object.iter() {
+                if key != "events" {
+                    collect_transfers_inner(child, contract_address, transfers);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items.iter() {
+                collect_transfers_inner(item, contract_address, transfers);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn json_to_felt(value: &serde_json::Value) -> Option<FieldElement> {
+    value
+        .as_str()
+        .and_then(|value| FieldElement::from_hex_be(value).ok())
+}
+
+fn json_to_felts(value: &serde_json::Value) -> Option<Vec<FieldElement>> {
+    value.as_array()?.iter().map(json_to_felt).collect()
+}
+
+/// Prints transfers of known tokens to stderr as a human-readable summary.
+pub fn print_transfer_summary(registry: &TokenRegistry, transfers: &[TokenTransfer]) {
+    let known = transfers
+        .iter()
+        .filter_map(|transfer| {
+            registry
+                .format_amount(transfer.token, &transfer.amount)
+                .map(|amount| (transfer, amount))
+        })
+        .collect::<Vec<_>>();
+
+    if known.is_empty() {
+        return;
+    }
+
+    eprintln!("Token transfers:");
+    for (transfer, amount) in known.into_iter() {
+        eprintln!(
+            "    {}: {:#064x} -> {:#064x}",
+            amount, transfer.from, transfer.to
+        );
+    }
+}
@@ -0,0 +1,375 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::Result;
+use colored::Colorize;
+use qrcode::{render::unicode::Dense1x2, QrCode};
+use serde::{Deserialize, Serialize};
+use starknet::{
+    accounts::Call,
+    core::{
+        crypto::compute_hash_on_elements,
+        types::{
+            BroadcastedDeclareTransaction, BroadcastedDeclareTransactionV1,
+            BroadcastedDeclareTransactionV2, BroadcastedInvokeTransaction,
+            BroadcastedInvokeTransactionV1, CompressedLegacyContractClass, FieldElement,
+            FlattenedSierraClass,
+        },
+    },
+    macros::short_string,
+};
+
+/// Version of the exported transaction document format.
+const FORMAT_VERSION: u64 = 1;
+
+const PREFIX_INVOKE: FieldElement = short_string!("invoke");
+const PREFIX_DECLARE: FieldElement = short_string!("declare");
+
+/// A portable document describing a transaction that's ready to be signed and broadcast by
+/// different parties, possibly on different machines.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionFile {
+    pub version: u64,
+    pub chain_id: FieldElement,
+    pub transaction_hash: FieldElement,
+    pub transaction: ExportedTransaction,
+    #[serde(default)]
+    pub signature: Vec<FieldElement>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExportedTransaction {
+    Invoke(ExportedInvoke),
+    Declare(ExportedDeclare),
+    DeclareLegacy(ExportedDeclareLegacy),
+}
+
+/// Invoke transaction (version 1).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedInvoke {
+    pub sender_address: FieldElement,
+    pub max_fee: FieldElement,
+    pub nonce: FieldElement,
+    pub calldata: Vec<FieldElement>,
+    /// The calls encoded in `calldata`, for human review. Checked against `calldata` on load.
+    pub calls: Vec<ExportedCall>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedCall {
+    pub to: FieldElement,
+    pub selector: FieldElement,
+    pub calldata: Vec<FieldElement>,
+}
+
+/// Cairo 1 declare transaction (version 2).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedDeclare {
+    pub sender_address: FieldElement,
+    pub max_fee: FieldElement,
+    pub nonce: FieldElement,
+    pub class_hash: FieldElement,
+    pub compiled_class_hash: FieldElement,
+    pub contract_class: FlattenedSierraClass,
+}
+
+/// Cairo 0 declare transaction (version 1).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedDeclareLegacy {
+    pub sender_address: FieldElement,
+    pub max_fee: FieldElement,
+    pub nonce: FieldElement,
+    pub class_hash: FieldElement,
+    pub contract_class: CompressedLegacyContractClass,
+}
+
+impl TransactionFile {
+    pub fn new(chain_id: FieldElement, transaction: ExportedTransaction) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            chain_id,
+            transaction_hash: transaction.compute_hash(chain_id),
+            transaction,
+            signature: vec![],
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let file: Self = serde_json::from_reader(std::fs::File::open(path)?)?;
+        if file.version != FORMAT_VERSION {
+            anyhow::bail!("unsupported transaction file version: {}", file.version);
+        }
+        Ok(file)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(&mut file, self)?;
+        Ok(())
+    }
+
+    /// Writes the unsigned transaction to `path` for review and signing elsewhere, optionally also
+    /// showing it as a QR code.
+    pub fn export(&self, path: &Path, qr: bool) -> Result<()> {
+        self.save(path)?;
+
+        if qr {
+            self.print_qr()?;
+        }
+
+        eprintln!(
+            "Unsigned transaction {} written to {}",
+            format!("{:#064x}", self.transaction_hash).bright_yellow(),
+            path.display()
+        );
+        eprintln!(
+            "Use `{}` to sign it, and `{}` to send it out once signed.",
+            "starkli sign-tx".bright_yellow(),
+            "starkli broadcast-tx".bright_yellow()
+        );
+
+        Ok(())
+    }
+
+    /// Renders the transaction as a QR code on the terminal. The whole document is encoded when it
+    /// fits, which is usually the case for invokes with a few calls. Otherwise (e.g. declarations),
+    /// only the transaction hash is encoded as a commitment to be checked against the file.
+    pub fn print_qr(&self) -> Result<()> {
+        let payload = serde_json::to_string(self)?;
+
+        let code = match QrCode::new(payload.as_bytes()) {
+            Ok(code) => {
+                eprintln!("QR code for the full transaction:");
+                code
+            }
+            Err(_) => {
+                eprintln!(
+                    "Transaction too large for a QR code. Showing the transaction hash instead:"
+                );
+                QrCode::new(format!("{:#064x}", self.transaction_hash).as_bytes())?
+            }
+        };
+
+        // Inverted colors scan better on dark terminal backgrounds
+        let rendered = code
+            .render::<Dense1x2>()
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .quiet_zone(true)
+            .build();
+        eprintln!("{}", rendered);
+
+        Ok(())
+    }
+
+    /// Recomputes the transaction hash from the transaction fields, making sure it matches the one
+    /// recorded in the file. Signers should never trust the recorded hash.
+    pub fn verify_hash(&self) -> Result<FieldElement> {
+        if let ExportedTransaction::Invoke(invoke) = &self.transaction {
+            // The call summary is what reviewers look at, so it must not be able to deviate from
+            // what's actually being signed.
+            if invoke.calldata != encode_calls_new(&invoke.calls)
+                && invoke.calldata != encode_calls_legacy(&invoke.calls)
+            {
+                anyhow::bail!("call summary does not match the transaction calldata");
+            }
+        }
+
+        if let ExportedTransaction::Declare(declare) = &self.transaction {
+            let class_hash = declare.contract_class.class_hash();
+            if class_hash != declare.class_hash {
+                anyhow::bail!(
+                    "class hash mismatch: file says {:#064x} but the class hashes to {:#064x}",
+                    declare.class_hash,
+                    class_hash
+                );
+            }
+        }
+
+        let computed_hash = self.transaction.compute_hash(self.chain_id);
+        if computed_hash != self.transaction_hash {
+            anyhow::bail!(
+                "transaction hash mismatch: file says {:#064x} but the fields hash to {:#064x}",
+                self.transaction_hash,
+                computed_hash
+            );
+        }
+
+        Ok(computed_hash)
+    }
+
+    /// Prints a human-readable summary of the transaction to stderr.
+    pub fn print_summary(&self) {
+        let print_field = |name: &str, value: FieldElement| {
+            eprintln!("{}: {}", name, format!("{:#064x}", value).bright_yellow());
+        };
+
+        match &self.transaction {
+            ExportedTransaction::Invoke(invoke) => {
+                eprintln!("Transaction type: {}", "INVOKE (v1)".bright_yellow());
+                print_field("Sender", invoke.sender_address);
+                print_field("Nonce", invoke.nonce);
+                eprintln!(
+                    "Max fee: {}",
+                    format!("{:#x}", invoke.max_fee).bright_yellow()
+                );
+                for (ind, call) in invoke.calls.iter().enumerate() {
+                    eprintln!("Call #{}:", ind);
+                    eprintln!("  To: {}", format!("{:#064x}", call.to).bright_yellow());
+                    eprintln!(
+                        "  Selector: {}",
+                        format!("{:#064x}", call.selector).bright_yellow()
+                    );
+                    for (ind_data, data) in call.calldata.iter().enumerate() {
+                        eprintln!("  Calldata #{}: {:#x}", ind_data, data);
+                    }
+                }
+            }
+            ExportedTransaction::Declare(declare) => {
+                eprintln!("Transaction type: {}", "DECLARE (v2)".bright_yellow());
+                print_field("Sender", declare.sender_address);
+                print_field("Nonce", declare.nonce);
+                eprintln!(
+                    "Max fee: {}",
+                    format!("{:#x}", declare.max_fee).bright_yellow()
+                );
+                print_field("Class hash", declare.class_hash);
+                print_field("CASM class hash", declare.compiled_class_hash);
+            }
+            ExportedTransaction::DeclareLegacy(declare) => {
+                eprintln!("Transaction type: {}", "DECLARE (v1)".bright_yellow());
+                print_field("Sender", declare.sender_address);
+                print_field("Nonce", declare.nonce);
+                eprintln!(
+                    "Max fee: {}",
+                    format!("{:#x}", declare.max_fee).bright_yellow()
+                );
+                print_field("Class hash", declare.class_hash);
+            }
+        }
+
+        print_field("Chain ID", self.chain_id);
+        print_field("Transaction hash", self.transaction_hash);
+    }
+}
+
+impl ExportedTransaction {
+    pub fn compute_hash(&self, chain_id: FieldElement) -> FieldElement {
+        match self {
+            Self::Invoke(invoke) => compute_hash_on_elements(&[
+                PREFIX_INVOKE,
+                FieldElement::ONE,
+                invoke.sender_address,
+                FieldElement::ZERO,
+                compute_hash_on_elements(&invoke.calldata),
+                invoke.max_fee,
+                chain_id,
+                invoke.nonce,
+            ]),
+            Self::Declare(declare) => compute_hash_on_elements(&[
+                PREFIX_DECLARE,
+                FieldElement::TWO,
+                declare.sender_address,
+                FieldElement::ZERO,
+                compute_hash_on_elements(&[declare.class_hash]),
+                declare.max_fee,
+                chain_id,
+                declare.nonce,
+                declare.compiled_class_hash,
+            ]),
+            // The compressed legacy class cannot be hashed locally, so the recorded class hash is
+            // used as-is. Broadcasting a mismatching class will be rejected by the network anyway.
+            Self::DeclareLegacy(declare) => compute_hash_on_elements(&[
+                PREFIX_DECLARE,
+                FieldElement::ONE,
+                declare.sender_address,
+                FieldElement::ZERO,
+                compute_hash_on_elements(&[declare.class_hash]),
+                declare.max_fee,
+                chain_id,
+                declare.nonce,
+            ]),
+        }
+    }
+}
+
+impl ExportedCall {
+    pub fn from_call(call: &Call) -> Self {
+        Self {
+            to: call.to,
+            selector: call.selector,
+            calldata: call.calldata.clone(),
+        }
+    }
+}
+
+impl ExportedInvoke {
+    pub fn into_broadcasted(self, signature: Vec<FieldElement>) -> BroadcastedInvokeTransaction {
+        BroadcastedInvokeTransaction::V1(BroadcastedInvokeTransactionV1 {
+            sender_address: self.sender_address,
+            calldata: self.calldata,
+            max_fee: self.max_fee,
+            signature,
+            nonce: self.nonce,
+            is_query: false,
+        })
+    }
+}
+
+impl ExportedDeclare {
+    pub fn into_broadcasted(self, signature: Vec<FieldElement>) -> BroadcastedDeclareTransaction {
+        BroadcastedDeclareTransaction::V2(BroadcastedDeclareTransactionV2 {
+            sender_address: self.sender_address,
+            compiled_class_hash: self.compiled_class_hash,
+            max_fee: self.max_fee,
+            signature,
+            nonce: self.nonce,
+            contract_class: Arc::new(self.contract_class),
+            is_query: false,
+        })
+    }
+}
+
+impl ExportedDeclareLegacy {
+    pub fn into_broadcasted(self, signature: Vec<FieldElement>) -> BroadcastedDeclareTransaction {
+        BroadcastedDeclareTransaction::V1(BroadcastedDeclareTransactionV1 {
+            sender_address: self.sender_address,
+            max_fee: self.max_fee,
+            signature,
+            nonce: self.nonce,
+            contract_class: Arc::new(self.contract_class),
+            is_query: false,
+        })
+    }
+}
+
+fn encode_calls_new(calls: &[ExportedCall]) -> Vec<FieldElement> {
+    let mut calldata = vec![calls.len().into()];
+
+    for call in calls.iter() {
+        calldata.push(call.to);
+        calldata.push(call.selector);
+        calldata.push(call.calldata.len().into());
+        calldata.extend_from_slice(&call.calldata);
+    }
+
+    calldata
+}
+
+fn encode_calls_legacy(calls: &[ExportedCall]) -> Vec<FieldElement> {
+    let mut concated_calldata: Vec<FieldElement> = vec![];
+    let mut calldata = vec![calls.len().into()];
+
+    for call in calls.iter() {
+        calldata.push(call.to);
+        calldata.push(call.selector);
+        calldata.push(concated_calldata.len().into());
+        calldata.push(call.calldata.len().into());
+        concated_calldata.extend_from_slice(&call.calldata);
+    }
+
+    calldata.push(concated_calldata.len().into());
+    calldata.extend_from_slice(&concated_calldata);
+
+    calldata
+}
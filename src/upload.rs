@@ -0,0 +1,145 @@
+use std::{
+    io::{IsTerminal, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::Result;
+use colored::Colorize;
+use flate2::{write::GzEncoder, Compression};
+use futures_util::stream;
+use serde_json::Value;
+use url::Url;
+
+use crate::http;
+
+/// Requests at least this large are uploaded with progress feedback. Only multi-megabyte classes
+/// get there, for which the upload alone can take a while on slow links.
+pub const LARGE_REQUEST_SIZE: usize = 1024 * 1024;
+
+/// Size of the pieces the body is streamed in, which is also how often progress is updated.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// JSON-RPC error code for a body the server can't parse, which is how most nodes respond to a
+/// compressed body they don't understand.
+const PARSE_ERROR: i64 = -32700;
+
+/// Set once the endpoint rejected a compressed request, so later uploads skip compression.
+static COMPRESSION_REJECTED: AtomicBool = AtomicBool::new(false);
+
+/// Sends a large JSON-RPC request, printing upload progress to stderr. The body is gzip-compressed
+/// unless the endpoint turns out not to support it, in which case it's sent again uncompressed.
+/// Returns the JSON-RPC response object as is.
+pub async fn send_large_request(
+    rpc_url: &Url,
+    label: &str,
+    method: &str,
+    params: Value,
+) -> Result<Value> {
+    let body = serde_json::to_vec(&serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    }))?;
+
+    if !COMPRESSION_REJECTED.load(Ordering::Relaxed) {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&body)?;
+        let compressed = encoder.finish()?;
+
+        eprintln!(
+            "{} ({} MiB, {} MiB compressed)...",
+            label,
+            format_mib(body.len()).bright_yellow(),
+            format_mib(compressed.len()).bright_yellow()
+        );
+        if let Some(response) = upload(rpc_url, compressed, true).await? {
+            return Ok(response);
+        }
+
+        COMPRESSION_REJECTED.store(true, Ordering::Relaxed);
+        eprintln!("The endpoint does not accept compressed requests. Uploading uncompressed...");
+    } else {
+        eprintln!(
+            "{} ({} MiB)...",
+            label,
+            format_mib(body.len()).bright_yellow()
+        );
+    }
+
+    upload(rpc_url, body, false)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("unexpected response to uncompressed request"))
+}
+
+/// Streams the body to the endpoint. Returns `None` if the endpoint can't handle a compressed
+/// body.
+async fn upload(rpc_url: &Url, body: Vec<u8>, compressed: bool) -> Result<Option<Value>> {
+    let total = body.len();
+    let show_progress = std::io::stderr().is_terminal();
+
+    let chunks = body
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| chunk.to_vec())
+        .collect::<Vec<_>>();
+    let mut sent = 0;
+    let body_stream = stream::iter(chunks.into_iter().map(move |chunk| {
+        // Chunks are pulled as the connection accepts more data, so this tracks what's been
+        // handed to the socket
+        sent += chunk.len();
+        if show_progress {
+            eprint!(
+                "\r  {} / {} MiB ({}%)",
+                format_mib(sent),
+                format_mib(total),
+                sent * 100 / total.max(1)
+            );
+        }
+        Ok::<_, std::io::Error>(chunk)
+    }));
+
+    let mut request = http::shared_client()
+        .post(rpc_url.clone())
+        .header("Content-Type", "application/json")
+        .header("Content-Length", total);
+    if compressed {
+        request = request.header("Content-Encoding", "gzip");
+    }
+
+    let response = request
+        .body(reqwest::Body::wrap_stream(body_stream))
+        .send()
+        .await;
+    if show_progress {
+        eprintln!();
+    }
+    let response = response?;
+
+    let status = response.status();
+    let response = serde_json::from_str::<Value>(&response.text().await?)
+        .ok()
+        .filter(|response| response.is_object());
+
+    let rejected = match &response {
+        Some(response) => {
+            response
+                .get("error")
+                .and_then(|error| error.get("code"))
+                .and_then(|code| code.as_i64())
+                == Some(PARSE_ERROR)
+        }
+        None => true,
+    };
+    if compressed && rejected {
+        return Ok(None);
+    }
+
+    match response {
+        Some(response) => Ok(Some(response)),
+        None => anyhow::bail!("unexpected response from the RPC endpoint: {}", status),
+    }
+}
+
+fn format_mib(bytes: usize) -> String {
+    format!("{:.1}", bytes as f64 / (1024.0 * 1024.0))
+}
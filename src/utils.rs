@@ -1,4 +1,8 @@
-use std::{io::Read, time::Duration};
+use std::{
+    future::Future,
+    io::{IsTerminal, Read},
+    time::Duration,
+};
 
 use anyhow::Result;
 use bigdecimal::{BigDecimal, Zero};
@@ -7,21 +11,40 @@ use colored_json::{ColorMode, ColoredFormatter, Output};
 use flate2::read::GzDecoder;
 use num_integer::Integer;
 use regex::Regex;
+use reqwest::StatusCode;
 use serde::Serialize;
 use serde_json::ser::PrettyFormatter;
+use sha3::{Digest, Keccak256};
 use starknet::{
-    core::types::{
-        contract::{
-            legacy::{
-                LegacyContractClass, LegacyEntrypointOffset, LegacyProgram, RawLegacyEntryPoint,
-                RawLegacyEntryPoints,
+    accounts::{AccountError, Call},
+    core::{
+        crypto::pedersen_hash,
+        types::{
+            contract::{
+                legacy::{
+                    LegacyContractClass, LegacyEntrypointOffset, LegacyProgram,
+                    RawLegacyEntryPoint, RawLegacyEntryPoints,
+                },
+                AbiEntry, SierraClass, SierraClassDebugInfo,
             },
-            AbiEntry, SierraClass, SierraClassDebugInfo,
+            BlockId, BlockTag, CompressedLegacyContractClass, ExecutionResult, FieldElement,
+            FlattenedSierraClass, LegacyContractEntryPoint, StarknetError,
         },
-        BlockId, BlockTag, CompressedLegacyContractClass, ExecutionResult, FieldElement,
-        FlattenedSierraClass, LegacyContractEntryPoint, StarknetError,
+        utils::{normalize_address, starknet_keccak},
+    },
+    macros::selector,
+    providers::{
+        jsonrpc::{HttpTransportError, JsonRpcClientError},
+        Provider, ProviderError,
     },
-    providers::{Provider, ProviderError},
+};
+
+use crate::{
+    error::decode_send_error,
+    interrupt::watch_interruptible,
+    messages::{message, plain_message},
+    provider::ExtendedProvider,
+    token::{collect_transfers, print_transfer_summary, TokenRegistry},
 };
 
 pub async fn watch_tx<P>(
@@ -32,23 +55,89 @@ pub async fn watch_tx<P>(
 where
     P: Provider,
 {
+    match watch_interruptible(
+        transaction_hash,
+        wait_for_tx(provider, transaction_hash, poll_interval),
+    )
+    .await?
+    {
+        ExecutionResult::Succeeded => Ok(()),
+        ExecutionResult::Reverted { reason } => {
+            Err(anyhow::anyhow!("transaction reverted: {}", reason))
+        }
+    }
+}
+
+/// Polls until the transaction is included, returning its execution result. Unlike `watch_tx`, a
+/// reverted transaction is not treated as an error.
+///
+/// Receipts in a block that's no longer part of the canonical chain are reported as a reorg and
+/// watching continues, so that a transaction dropped by a reorg is never reported as confirmed.
+pub async fn wait_for_tx<P>(
+    provider: P,
+    transaction_hash: FieldElement,
+    poll_interval: Duration,
+) -> Result<ExecutionResult>
+where
+    P: Provider,
+{
+    // Block where the transaction was last seen before being reorged out
+    let mut reorged_block: Option<u64> = None;
+
     loop {
         match provider.get_transaction_receipt(transaction_hash).await {
-            Ok(receipt) => match receipt.execution_result() {
-                ExecutionResult::Succeeded => {
-                    eprintln!(
-                        "Transaction {} confirmed",
-                        format!("{:#064x}", transaction_hash).bright_yellow()
-                    );
+            Ok(receipt) => {
+                let receipt_json = serde_json::to_value(&receipt)?;
+                let block_hash = json_felt(&receipt_json, "block_hash");
+                let block_number = receipt_json
+                    .get("block_number")
+                    .and_then(|value| value.as_u64());
+
+                // Pending receipts have no block to check against
+                if let (Some(block_hash), Some(block_number)) = (block_hash, block_number) {
+                    let canonical_hash = provider
+                        .get_block_with_tx_hashes(BlockId::Number(block_number))
+                        .await
+                        .ok()
+                        .and_then(|block| serde_json::to_value(block).ok())
+                        .and_then(|block| json_felt(&block, "block_hash"));
+
+                    if canonical_hash.is_some_and(|canonical_hash| canonical_hash != block_hash) {
+                        if reorged_block != Some(block_number) {
+                            eprintln!(
+                                "{}",
+                                plain_message("tx.reorged", &[("block", &block_number)])
+                                    .bright_magenta()
+                            );
+                            reorged_block = Some(block_number);
+                        }
 
-                    return Ok(());
+                        tokio::time::sleep(poll_interval).await;
+                        continue;
+                    }
                 }
-                ExecutionResult::Reverted { reason } => {
-                    return Err(anyhow::anyhow!("transaction reverted: {}", reason));
+
+                let execution_result = receipt.execution_result().to_owned();
+                if let ExecutionResult::Succeeded = execution_result {
+                    eprintln!(
+                        "{}",
+                        message(
+                            "tx.confirmed",
+                            &[("hash", &format!("{:#064x}", transaction_hash))]
+                        )
+                    );
                 }
-            },
+
+                return Ok(execution_result);
+            }
             Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {
-                eprintln!("Transaction not confirmed yet...");
+                match reorged_block.take() {
+                    Some(block_number) => eprintln!(
+                        "{}",
+                        plain_message("tx.dropped", &[("block", &block_number)]).bright_magenta()
+                    ),
+                    None => eprintln!("{}", message("tx.not_confirmed", &[])),
+                }
             }
             Err(err) => return Err(err.into()),
         }
@@ -57,6 +146,160 @@ where
     }
 }
 
+/// Maximum number of attempts for sending a transaction when transient errors are encountered.
+const MAX_SEND_ATTEMPTS: u64 = 3;
+
+/// Fetches and prints the receipt of a transaction, followed by a summary of token transfers.
+/// The receipt JSON is returned for further inspection.
+pub async fn print_receipt(
+    provider: &ExtendedProvider,
+    transaction_hash: FieldElement,
+) -> Result<serde_json::Value> {
+    let receipt = provider.get_transaction_receipt(transaction_hash).await?;
+
+    let receipt_json = serde_json::to_value(receipt)?;
+    print_receipt_json(provider, &receipt_json).await?;
+
+    Ok(receipt_json)
+}
+
+/// Prints an already fetched receipt, followed by a summary of the token transfers it contains.
+pub async fn print_receipt_json(
+    provider: &ExtendedProvider,
+    receipt_json: &serde_json::Value,
+) -> Result<()> {
+    let transfers = collect_transfers(receipt_json);
+
+    let colored_receipt =
+        colored_json::to_colored_json(receipt_json, ColorMode::Auto(Output::StdOut))?;
+    println!("{colored_receipt}");
+
+    if !transfers.is_empty() {
+        let registry = TokenRegistry::load_for_provider(provider).await?;
+        print_transfer_summary(&registry, &transfers);
+    }
+
+    Ok(())
+}
+
+/// Sends a transaction with `send`, retrying on errors proven to have happened before the
+/// transaction reached the node. Nonce errors are only retried when the nonce is fetched
+/// automatically, as a fresh one is obtained on each attempt. Other provider errors, like
+/// timeouts, may happen after the node accepted the transaction, so it's only sent again if the
+/// account's nonce shows that it wasn't. Errors that are not retried are decoded into actionable
+/// messages.
+pub async fn send_with_retry<F, Fut, T, S>(
+    provider: &ExtendedProvider,
+    sender: FieldElement,
+    manual_nonce: bool,
+    mut send: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, AccountError<S>>>,
+    S: std::error::Error + Send + Sync + 'static,
+{
+    // A manually set nonce is reused by every attempt, so a transaction that was accepted can't be
+    // sent twice. Otherwise, a fresh nonce would be used, and the nonce before sending tells
+    // whether the transaction was accepted.
+    let nonce_before = if manual_nonce {
+        None
+    } else {
+        provider
+            .get_nonce(BlockId::Tag(BlockTag::Pending), sender)
+            .await
+            .ok()
+    };
+
+    let mut attempt = 1;
+
+    loop {
+        let err = match send().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+
+        let reason = match &err {
+            AccountError::Provider(err) if is_unsent(err) => Some("request not received"),
+            AccountError::Provider(ProviderError::Other(_)) if manual_nonce => {
+                Some("provider error")
+            }
+            AccountError::Provider(ProviderError::Other(_)) => match nonce_before {
+                Some(nonce_before) => {
+                    let nonce_after = provider
+                        .get_nonce(BlockId::Tag(BlockTag::Pending), sender)
+                        .await
+                        .ok();
+                    if nonce_after == Some(nonce_before) {
+                        Some("provider error")
+                    } else {
+                        anyhow::bail!(
+                            "failed to send transaction: {}. It may have been submitted anyway, \
+                            as the account's nonce is no longer {}. Check your account's recent \
+                            transactions before trying again.",
+                            err,
+                            nonce_before
+                        );
+                    }
+                }
+                None => None,
+            },
+            AccountError::Provider(ProviderError::StarknetError(
+                StarknetError::InvalidTransactionNonce,
+            )) if !manual_nonce => Some("nonce changed before the transaction was sent"),
+            AccountError::Provider(ProviderError::StarknetError(StarknetError::DuplicateTx))
+                if attempt > 1 =>
+            {
+                anyhow::bail!(
+                    "an identical transaction has already been submitted, most likely by a \
+                    previous attempt that appeared to fail. Check your account's recent \
+                    transactions before trying again."
+                );
+            }
+            _ => None,
+        };
+
+        match reason {
+            Some(reason) if attempt < MAX_SEND_ATTEMPTS => {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "WARNING: failed to send transaction ({}): {}. Retrying ({}/{})...",
+                        reason,
+                        err,
+                        attempt + 1,
+                        MAX_SEND_ATTEMPTS
+                    )
+                    .bright_magenta()
+                );
+
+                tokio::time::sleep(Duration::from_secs(attempt)).await;
+                attempt += 1;
+            }
+            _ => return Err(decode_send_error(provider, sender, err).await),
+        }
+    }
+}
+
+/// Whether a provider error proves that the request never reached the node, like connection
+/// failures and rate limiting, so that sending it again can't submit a transaction twice.
+fn is_unsent(err: &ProviderError) -> bool {
+    match err {
+        ProviderError::RateLimited => true,
+        ProviderError::Other(err) => matches!(
+            err.as_any()
+                .downcast_ref::<JsonRpcClientError<HttpTransportError>>(),
+            Some(JsonRpcClientError::TransportError(HttpTransportError::Reqwest(err)))
+                if err.is_connect()
+                    || matches!(
+                        err.status(),
+                        Some(StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE)
+                    )
+        ),
+        _ => false,
+    }
+}
+
 pub fn parse_block_id(id: &str) -> Result<BlockId> {
     let regex_block_number = Regex::new("^[0-9]{1,}$").unwrap();
 
@@ -71,6 +314,22 @@ pub fn parse_block_id(id: &str) -> Result<BlockId> {
     }
 }
 
+/// Parses a duration like `90`, `90s`, `5m`, or `1h`, where a bare number is in seconds.
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let (number, multiplier) = match value.char_indices().last() {
+        Some((index, 's')) => (&value[..index], 1),
+        Some((index, 'm')) => (&value[..index], 60),
+        Some((index, 'h')) => (&value[..index], 3600),
+        _ => (value, 1),
+    };
+
+    let number = number
+        .parse::<u64>()
+        .map_err(|_| anyhow::anyhow!("invalid duration: {}", value))?;
+
+    Ok(Duration::from_secs(number * multiplier))
+}
+
 pub fn parse_felt_value(felt: &str) -> Result<FieldElement> {
     let regex_dec_number = Regex::new("^[0-9]{1,}$").unwrap();
 
@@ -81,6 +340,149 @@ pub fn parse_felt_value(felt: &str) -> Result<FieldElement> {
     }
 }
 
+/// Reads calldata piped to stdin, either as a JSON array (as printed by `starkli call`) or as one
+/// value per line. Values are returned as is so that they go through the usual argument resolution.
+pub fn read_stdin_calldata() -> Result<Vec<String>> {
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        anyhow::bail!("--stdin-calldata expects calldata to be piped to stdin");
+    }
+
+    let mut input = String::new();
+    stdin.read_to_string(&mut input)?;
+    let input = input.trim();
+
+    if input.starts_with('[') {
+        let values: Vec<serde_json::Value> = serde_json::from_str(input)
+            .map_err(|err| anyhow::anyhow!("invalid JSON calldata from stdin: {}", err))?;
+        values
+            .into_iter()
+            .map(|value| match value {
+                serde_json::Value::String(value) => Ok(value),
+                serde_json::Value::Number(value) => Ok(value.to_string()),
+                value => Err(anyhow::anyhow!(
+                    "invalid calldata from stdin: expected a string or number, got {}",
+                    value
+                )),
+            })
+            .collect()
+    } else {
+        Ok(input
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_owned())
+            .collect())
+    }
+}
+
+/// Parses an Ethereum address into a field element, making sure it fits in 160 bits.
+pub fn parse_eth_address(address: &str) -> Result<FieldElement> {
+    let hex_str = address
+        .strip_prefix("0x")
+        .ok_or_else(|| anyhow::anyhow!("invalid Ethereum address: missing 0x prefix"))?;
+    if hex_str.is_empty() || hex_str.len() > 40 {
+        anyhow::bail!("invalid Ethereum address: {}", address);
+    }
+
+    // At most 40 hex digits always fit in 160 bits
+    Ok(FieldElement::from_hex_be(address)?)
+}
+
+/// Formats a field element as an Ethereum address, failing if it does not fit in 160 bits.
+pub fn eth_address_from_felt(felt: FieldElement) -> Result<String> {
+    if felt.to_bytes_be()[..12].iter().any(|byte| *byte != 0) {
+        anyhow::bail!("value out of range for Ethereum address: {:#x}", felt);
+    }
+    Ok(format!("0x{:040x}", felt))
+}
+
+/// A step from a storage variable towards one of its entries.
+#[derive(Clone)]
+pub enum StoragePathSegment {
+    /// A felt of a `Map` key or a `Vec` index.
+    Key(FieldElement),
+    /// A member of a storage node (`#[starknet::storage_node]`), which unlike plain struct members
+    /// is hashed into the path instead of being stored at an offset.
+    Member(String),
+}
+
+/// Computes the storage address of a variable following the Cairo storage layout: `sn_keccak` of
+/// the variable name, chained with Pedersen hashes of each map key felt, and finally the member
+/// offset for struct values.
+pub fn storage_address(var_name: &str, keys: &[FieldElement], offset: u64) -> Result<FieldElement> {
+    let segments = keys
+        .iter()
+        .map(|key| StoragePathSegment::Key(*key))
+        .collect::<Vec<_>>();
+    storage_path_address(var_name, &segments, offset)
+}
+
+/// Same as `storage_address`, but also covering storage nodes, whose members are hashed into the
+/// path with the `sn_keccak` of their names.
+pub fn storage_path_address(
+    var_name: &str,
+    segments: &[StoragePathSegment],
+    offset: u64,
+) -> Result<FieldElement> {
+    let mut address = storage_name_hash(var_name)?;
+    for segment in segments.iter() {
+        let element = match segment {
+            StoragePathSegment::Key(key) => *key,
+            StoragePathSegment::Member(member) => storage_name_hash(member)?,
+        };
+        address = pedersen_hash(&address, &element);
+    }
+
+    Ok(normalize_address(address) + FieldElement::from(offset))
+}
+
+fn storage_name_hash(name: &str) -> Result<FieldElement> {
+    if !name.is_ascii() || name.is_empty() {
+        anyhow::bail!("invalid storage name: {}", name);
+    }
+    Ok(starknet_keccak(name.as_bytes()))
+}
+
+/// Parses storage paths in the form of `name`, `name[key]`, `name[key_1][key_2]`, or with storage
+/// node members like `name.member[key]`, with keys given as raw felts.
+pub fn parse_storage_path(path: &str) -> Result<(String, Vec<StoragePathSegment>)> {
+    let (var_name, mut rest) = match path.find(['[', '.']) {
+        Some(ind) => (&path[..ind], &path[ind..]),
+        None => (path, ""),
+    };
+    if var_name.contains(']') {
+        anyhow::bail!("invalid storage path: {}", path);
+    }
+
+    let mut segments = vec![];
+    while !rest.is_empty() {
+        if let Some(member_path) = rest.strip_prefix('.') {
+            let (member, remaining) = match member_path.find(['[', '.']) {
+                Some(ind) => (&member_path[..ind], &member_path[ind..]),
+                None => (member_path, ""),
+            };
+            segments.push(StoragePathSegment::Member(member.to_owned()));
+            rest = remaining;
+        } else {
+            let (key, remaining) = rest
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once(']'))
+                .ok_or_else(|| anyhow::anyhow!("invalid storage path: {}", path))?;
+            segments.push(StoragePathSegment::Key(parse_felt_value(key.trim())?));
+            rest = remaining;
+        }
+    }
+
+    Ok((var_name.to_owned(), segments))
+}
+
+/// Computes the storage address of a path accepted by `parse_storage_path`.
+pub fn storage_path_to_address(path: &str) -> Result<FieldElement> {
+    let (var_name, segments) = parse_storage_path(path)?;
+    storage_path_address(&var_name, &segments, 0)
+}
+
 #[allow(clippy::comparison_chain)]
 pub fn bigdecimal_to_felt<D>(dec: &BigDecimal, decimals: D) -> Result<FieldElement>
 where
@@ -113,6 +515,197 @@ where
     Ok(FieldElement::from_byte_slice_be(&biguint.to_bytes_be())?)
 }
 
+/// A call without side effects for occupying a nonce: a zero-amount ETH transfer from the account to
+/// itself. This is the cheapest call that works for all account variants.
+pub fn noop_call(eth_address: FieldElement, account_address: FieldElement) -> Call {
+    Call {
+        to: eth_address,
+        selector: selector!("transfer"),
+        calldata: vec![account_address, FieldElement::ZERO, FieldElement::ZERO],
+    }
+}
+
+/// Formats an address in its checksummed form: padded to 64 hex digits, with letters uppercased
+/// where the matching nibble of the Keccak-256 hash of the address is 8 or above. This is the
+/// same encoding as `getChecksumAddress` in starknet.js.
+pub fn format_address(address: FieldElement) -> String {
+    let bytes = address.to_bytes_be();
+    let first_non_zero = bytes.iter().position(|byte| *byte != 0).unwrap_or(31);
+    let hash = Keccak256::digest(&bytes[first_non_zero..]);
+
+    let checksummed = format!("{:064x}", address)
+        .chars()
+        .enumerate()
+        .map(|(index, digit)| {
+            let nibble = if index % 2 == 0 {
+                hash[index / 2] >> 4
+            } else {
+                hash[index / 2] & 0x0f
+            };
+            if nibble >= 8 {
+                digit.to_ascii_uppercase()
+            } else {
+                digit
+            }
+        })
+        .collect::<String>();
+
+    format!("0x{}", checksummed)
+}
+
+/// Checks user input against the checksum encoding. Only mixed-case input carries a checksum, so
+/// all-lowercase and all-uppercase addresses always pass.
+pub fn is_valid_address_checksum(raw: &str, address: FieldElement) -> bool {
+    let digits = match raw.strip_prefix("0x").or_else(|| raw.strip_prefix("0X")) {
+        Some(digits) => digits,
+        None => return true,
+    };
+    let mixed_case = digits.chars().any(|digit| digit.is_ascii_lowercase())
+        && digits.chars().any(|digit| digit.is_ascii_uppercase());
+    if !mixed_case || digits.len() > 64 {
+        return true;
+    }
+
+    // Leading zeros may be omitted, in which case the digits line up with the end of the
+    // checksummed form
+    let checksummed = format_address(address);
+    checksummed.ends_with(digits)
+}
+
+/// Reads a hex-encoded felt field from a JSON object, such as a transaction.
+pub fn json_felt(value: &serde_json::Value, key: &str) -> Option<FieldElement> {
+    value
+        .get(key)
+        .and_then(|value| value.as_str())
+        .and_then(|value| FieldElement::from_hex_be(value).ok())
+}
+
+/// Trace fields holding top-level invocations, in execution order.
+pub const ROOT_INVOCATION_FIELDS: [&str; 5] = [
+    "validate_invocation",
+    "constructor_invocation",
+    "execute_invocation",
+    "function_invocation",
+    "fee_transfer_invocation",
+];
+
+/// Extracts events emitted at any depth of a transaction trace, attributing each one to the
+/// contract whose invocation emitted it. For library calls, that's the calling contract, while
+/// `class_hash` identifies the library class. Events are sorted in emission order.
+pub fn collect_inner_events(trace: &serde_json::Value) -> Vec<serde_json::Value> {
+    fn collect(
+        invocation: &serde_json::Value,
+        depth: usize,
+        events: &mut Vec<(u64, serde_json::Value)>,
+    ) {
+        let emitted = invocation
+            .get("events")
+            .and_then(|value| value.as_array())
+            .into_iter()
+            .flatten();
+        for event in emitted {
+            let order = event
+                .get("order")
+                .and_then(|value| value.as_u64())
+                .unwrap_or_default();
+            events.push((
+                order,
+                serde_json::json!({
+                    "order": order,
+                    "from_address": invocation.get("contract_address"),
+                    "class_hash": invocation.get("class_hash"),
+                    "call_type": invocation.get("call_type"),
+                    "depth": depth,
+                    "keys": event.get("keys"),
+                    "data": event.get("data"),
+                }),
+            ));
+        }
+
+        let calls = invocation
+            .get("calls")
+            .and_then(|value| value.as_array())
+            .into_iter()
+            .flatten();
+        for call in calls {
+            collect(call, depth + 1, events);
+        }
+    }
+
+    let mut events = vec![];
+    for field in ROOT_INVOCATION_FIELDS {
+        if let Some(invocation) = trace.get(field) {
+            collect(invocation, 0, &mut events);
+        }
+    }
+
+    // Stable so that events without an order keep their position in the trace
+    events.sort_by_key(|(order, _)| *order);
+    events.into_iter().map(|(_, event)| event).collect()
+}
+
+/// Best-effort decoding of account `__execute__` calldata into individual calls. Both the new
+/// (Cairo 1) and the legacy (Cairo 0) encodings are attempted. Returns `None` if the calldata fits
+/// neither of them.
+pub fn decode_execute_calldata(calldata: &[FieldElement]) -> Option<Vec<Call>> {
+    decode_execute_calldata_new(calldata).or_else(|| decode_execute_calldata_legacy(calldata))
+}
+
+fn decode_execute_calldata_new(calldata: &[FieldElement]) -> Option<Vec<Call>> {
+    let num_calls: u64 = (*calldata.first()?).try_into().ok()?;
+
+    let mut calls = vec![];
+    let mut offset = 1usize;
+    for _ in 0..num_calls {
+        let to = *calldata.get(offset)?;
+        let selector = *calldata.get(offset + 1)?;
+        let args_len: u64 = (*calldata.get(offset + 2)?).try_into().ok()?;
+        let args_start = offset + 3;
+        let args_end = args_start.checked_add(args_len as usize)?;
+
+        calls.push(Call {
+            to,
+            selector,
+            calldata: calldata.get(args_start..args_end)?.to_vec(),
+        });
+        offset = args_end;
+    }
+
+    if offset == calldata.len() {
+        Some(calls)
+    } else {
+        None
+    }
+}
+
+fn decode_execute_calldata_legacy(calldata: &[FieldElement]) -> Option<Vec<Call>> {
+    let num_calls: u64 = (*calldata.first()?).try_into().ok()?;
+    let call_array_end = 1usize.checked_add((num_calls as usize).checked_mul(4)?)?;
+
+    let data_len: u64 = (*calldata.get(call_array_end)?).try_into().ok()?;
+    let data_start = call_array_end + 1;
+    let data = calldata.get(data_start..data_start.checked_add(data_len as usize)?)?;
+    if data_start + data.len() != calldata.len() {
+        return None;
+    }
+
+    let mut calls = vec![];
+    for ind_call in 0..(num_calls as usize) {
+        let base = 1 + ind_call * 4;
+        let offset: u64 = calldata[base + 2].try_into().ok()?;
+        let len: u64 = calldata[base + 3].try_into().ok()?;
+        let args_end = (offset as usize).checked_add(len as usize)?;
+
+        calls.push(Call {
+            to: calldata[base],
+            selector: calldata[base + 1],
+            calldata: data.get((offset as usize)..args_end)?.to_vec(),
+        });
+    }
+
+    Some(calls)
+}
+
 /// Prints colored JSON for any serializable value. This is better then directly calling
 /// `colored_json::to_colored_json` as that method only takes `serde_json::Value`. Unfortunately,
 /// converting certain values to `serde_json::Value` would result in data loss.
@@ -142,6 +735,69 @@ where
     Ok(())
 }
 
+/// A single difference found by [diff_json], with `None` meaning the value is missing on that side.
+pub struct JsonDifference {
+    pub path: String,
+    pub left: Option<serde_json::Value>,
+    pub right: Option<serde_json::Value>,
+}
+
+/// Recursively compares two JSON values, returning the leaf-level differences with their paths.
+pub fn diff_json(left: &serde_json::Value, right: &serde_json::Value) -> Vec<JsonDifference> {
+    let mut differences = vec![];
+    diff_json_inner("", Some(left), Some(right), &mut differences);
+    differences
+}
+
+fn diff_json_inner(
+    path: &str,
+    left: Option<&serde_json::Value>,
+    right: Option<&serde_json::Value>,
+    differences: &mut Vec<JsonDifference>,
+) {
+    use serde_json::Value;
+
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+
+    match (left, right) {
+        (Some(Value::Object(left)), Some(Value::Object(right))) => {
+            for (key, left_value) in left.iter() {
+                diff_json_inner(&join(key), Some(left_value), right.get(key), differences);
+            }
+            for (key, right_value) in right.iter() {
+                if !left.contains_key(key) {
+                    diff_json_inner(&join(key), None, Some(right_value), differences);
+                }
+            }
+        }
+        (Some(Value::Array(left)), Some(Value::Array(right))) => {
+            for ind in 0..left.len().max(right.len()) {
+                diff_json_inner(
+                    &format!("{}[{}]", path, ind),
+                    left.get(ind),
+                    right.get(ind),
+                    differences,
+                );
+            }
+        }
+        (left, right) => {
+            if left != right {
+                differences.push(JsonDifference {
+                    path: path.to_owned(),
+                    left: left.cloned(),
+                    right: right.cloned(),
+                });
+            }
+        }
+    }
+}
+
 /// Attempts to recover a flattened Sierra class by parsing its ABI string. This works only if the
 /// declared ABI string is a valid JSON representation of Seirra ABI.
 pub fn parse_flattened_sierra_class(class: FlattenedSierraClass) -> Result<SierraClass> {
@@ -1,21 +1,87 @@
-use clap::Parser;
-use env_logger::Builder;
+use std::{fs::OpenOptions, io::Write, path::PathBuf};
+
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use env_logger::{Builder, Target};
 use log::LevelFilter;
 
+use crate::path::ExpandedPathbufParser;
+
 #[derive(Debug, Clone, Parser)]
 pub struct VerbosityArgs {
     #[clap(long, help = "Log raw request/response traffic of providers")]
     log_traffic: bool,
+    #[clap(
+        long,
+        value_enum,
+        default_value = "text",
+        help = "Format of log records. `json` prints one object per line"
+    )]
+    log_format: LogFormat,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Append log records to this file instead of printing them to stderr"
+    )]
+    log_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    Text,
+    Json,
 }
 
 impl VerbosityArgs {
     pub fn setup_logging(&self) {
         let mut builder = Builder::new();
 
+        // Starkli's own records are only worth emitting when they're collected somewhere
+        if self.log_file.is_some() || self.log_format == LogFormat::Json {
+            builder.filter_module("starkli", LevelFilter::Info);
+        }
+
         if self.log_traffic {
             builder.filter_module("starknet_providers", LevelFilter::Trace);
         }
 
+        // Allows finer control over levels, using the same syntax as `RUST_LOG`
+        builder.parse_env("STARKLI_LOG");
+
+        if self.log_format == LogFormat::Json {
+            builder.format(|buf, record| {
+                writeln!(
+                    buf,
+                    "{}",
+                    serde_json::json!({
+                        "timestamp": chrono::Utc::now().to_rfc3339(),
+                        "level": record.level().as_str(),
+                        "target": record.target(),
+                        "message": record.args().to_string(),
+                    })
+                )
+            });
+        }
+
+        if let Some(log_file) = &self.log_file {
+            match OpenOptions::new().create(true).append(true).open(log_file) {
+                Ok(file) => {
+                    builder.target(Target::Pipe(Box::new(file)));
+                }
+                Err(err) => {
+                    eprintln!(
+                        "{}",
+                        format!(
+                            "WARNING: unable to open log file {}: {}. Logging to stderr instead.",
+                            log_file.display(),
+                            err
+                        )
+                        .bright_magenta()
+                    );
+                }
+            }
+        }
+
         builder.init();
     }
 }
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde_with::serde_as;
+use starknet::core::{
+    serde::unsigned_field_element::UfeHex, types::FieldElement, utils::parse_cairo_short_string,
+};
+
+/// Accounts files supported by `starkli account import`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum WalletFormat {
+    /// Accounts file of starknet-foundry's `sncast`.
+    Sncast,
+    /// `starknet_open_zeppelin_accounts.json` of the legacy cairo-lang CLI.
+    CairoLang,
+}
+
+/// Accounts files of `sncast` and of the legacy cairo-lang CLI share the same layout: accounts are
+/// keyed by network name, and then by account name. Keys are stored in plain text.
+pub type AccountsFile = BTreeMap<String, BTreeMap<String, AccountsFileEntry>>;
+
+/// Fields other than the key and address are only written by `sncast`, which also lists accounts
+/// created but not deployed yet.
+#[serde_as]
+#[derive(Deserialize)]
+pub struct AccountsFileEntry {
+    #[serde_as(as = "UfeHex")]
+    pub private_key: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub address: FieldElement,
+    #[serde(default)]
+    pub deployed: bool,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default)]
+    pub class_hash: Option<FieldElement>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default)]
+    pub salt: Option<FieldElement>,
+    #[serde(default, rename = "type")]
+    pub account_type: Option<String>,
+    #[serde(default)]
+    pub legacy: bool,
+}
+
+/// The network name under which both tools store accounts for a chain.
+pub fn accounts_file_network(chain_id: FieldElement) -> String {
+    match parse_cairo_short_string(&chain_id) {
+        Ok(chain_id) => match chain_id.as_str() {
+            "SN_MAIN" => "alpha-mainnet".to_owned(),
+            "SN_GOERLI" => "alpha-goerli".to_owned(),
+            "SN_GOERLI2" => "alpha-goerli2".to_owned(),
+            "SN_SEPOLIA" => "alpha-sepolia".to_owned(),
+            _ => chain_id.to_lowercase(),
+        },
+        Err(_) => format!("{:#x}", chain_id),
+    }
+}
@@ -0,0 +1,112 @@
+use std::{fmt::Display, sync::OnceLock};
+
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::profile::{Profiles, DEFAULT_PROFILE_NAME};
+
+static ALLOWED: OnceLock<Vec<Warning>> = OnceLock::new();
+
+/// Warnings that can be silenced with `--allow`, each with a stable code. Codes are printed along
+/// with the warnings so that automation can allow the ones known to be acceptable in its setup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Warning {
+    /// The node serves a JSON-RPC version other than the one Starkli is built for
+    RpcVersion,
+    /// Both an RPC URL and a network are set, and the RPC URL is used
+    RpcPrecedence,
+    /// Neither an RPC URL nor a network is set, and a default network is used
+    ImplicitNetwork,
+    /// The CASM hash can't be verified with the compiler version used by the network
+    CasmVerification,
+    /// The max fee is higher than the --warn-fee-above threshold
+    HighFee,
+    /// The max fee exceeds the network's max_fee_ceiling, and is sent anyway with --force
+    FeeCeiling,
+    /// The calldata length doesn't match the function inputs in the ABI
+    CalldataArity,
+    /// Tokens are sent to an address without a deployed contract
+    UndeployedRecipient,
+    /// An unlimited amount is approved to a contract not in the address book
+    UnlimitedApproval,
+    /// The nonce is already used, or ahead of the pending nonce
+    NonceMismatch,
+    /// A password is supplied on the command line
+    PasswordOption,
+    /// A private key is used in plain text
+    PlainPrivateKey,
+    /// A mixed-case address fails its checksum
+    AddressChecksum,
+    /// An address is only listed in address books for other networks
+    WrongNetwork,
+}
+
+#[derive(Debug, Clone, Default, Parser)]
+pub struct WarningOptions {
+    #[clap(
+        long = "allow",
+        global = true,
+        env = "STARKLI_ALLOW",
+        value_enum,
+        value_delimiter = ',',
+        value_name = "CODE",
+        help = "Silence warnings with this code. Can be repeated, or given as a comma-separated \
+                list"
+    )]
+    allow: Vec<Warning>,
+}
+
+impl WarningOptions {
+    /// Sets the allowed warnings for the rest of the process, from the option and from `allow` in
+    /// the `default` profile.
+    pub fn apply(self) {
+        // A broken profiles file is reported by the commands actually using it
+        let from_profile = Profiles::load()
+            .ok()
+            .and_then(|profiles| {
+                profiles
+                    .profiles
+                    .get(DEFAULT_PROFILE_NAME)
+                    .map(|profile| profile.allow.clone())
+            })
+            .unwrap_or_default();
+
+        let mut allowed = self.allow;
+        allowed.extend(from_profile);
+
+        let _ = ALLOWED.set(allowed);
+    }
+}
+
+impl Warning {
+    pub fn is_allowed(self) -> bool {
+        ALLOWED.get().is_some_and(|allowed| allowed.contains(&self))
+    }
+
+    /// Prints the warning to stderr with its code, unless it's allowed.
+    pub fn print<T>(self, message: T)
+    where
+        T: Display,
+    {
+        if self.is_allowed() {
+            return;
+        }
+
+        eprintln!(
+            "{}",
+            format!("WARNING [{}]: {}", self, message).bright_magenta()
+        );
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // All variants have a possible value as none are skipped
+        match self.to_possible_value() {
+            Some(value) => write!(f, "{}", value.get_name()),
+            None => write!(f, "{:?}", self),
+        }
+    }
+}